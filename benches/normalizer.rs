@@ -0,0 +1,89 @@
+//! Normalization of days with hundreds of imported events.
+//!
+//! quarble is a binary crate, the modules the normalizer needs are compiled into the benchmark.
+//! Most of their code is unused here
+
+#![allow(dead_code, unused_imports)]
+#![allow(clippy::or_fun_call)]
+
+use std::num::NonZeroU32;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+use crate::conf::BreaksConfig;
+use crate::data::{
+    Action, ActiveDay, ActiveDayBuilder, Day, DayEnd, DayStart, JiraIssue, Location, Normalizer,
+    Work, WorkStart,
+};
+use crate::parsing::time::Time;
+
+#[path = "../src/conf/mod.rs"]
+mod conf;
+#[path = "../src/data/mod.rs"]
+mod data;
+#[path = "../src/parsing/mod.rs"]
+mod parsing;
+#[path = "../src/util.rs"]
+mod util;
+
+/// A day with `count` actions one minute apart from 08:00, alternating between issue
+/// starts and short meetings, like after importing a calendar and idle times
+fn dense_day(count: u32) -> ActiveDay {
+    let at = |minute: u32| Time::new(8 * 60 + minute);
+    let mut actions = vec![Action::DayStart(DayStart {
+        location: Location::Office,
+        ts: at(0),
+    })];
+    for i in 0..count {
+        actions.push(if i % 2 == 0 {
+            Action::WorkStart(WorkStart {
+                ts: at(i),
+                task: JiraIssue::create(&format!("DEV-{}", i % 40)).unwrap(),
+                description: "import".to_string(),
+            })
+        } else {
+            Action::Work(Work {
+                start: at(i),
+                end: at(i + 1),
+                task: JiraIssue::create(&format!("MEET-{}", i)).unwrap(),
+                description: "meeting".to_string(),
+                links: Vec::new(),
+            })
+        });
+    }
+    actions.push(Action::DayEnd(DayEnd { ts: at(count + 1) }));
+    ActiveDayBuilder {
+        day: Day::ymd(2022, 1, 6),
+        main_location: Location::Office,
+        active_issue: None,
+        actions,
+    }
+    .build()
+}
+
+fn normalize_dense_day(c: &mut Criterion) {
+    let n = Normalizer {
+        resolution: NonZeroU32::new(1).unwrap(),
+        breaks_config: BreaksConfig {
+            min_breaks_minutes: 45,
+            min_work_time_minutes: 6 * 60,
+            default_break: (Time::new(12 * 60), Time::new(12 * 60 + 45)),
+            weekday_overrides: vec![],
+        },
+        combine_bookings: true,
+        add_break: true,
+        home_zone: None,
+        holidays: Vec::new(),
+    };
+    let mut group = c.benchmark_group("normalize");
+    for count in [100, 500] {
+        let day = dense_day(count);
+        group.bench_with_input(BenchmarkId::from_parameter(count), &day, |b, day| {
+            b.iter(|| n.create_normalized(day).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, normalize_dense_day);
+criterion_main!(benches);
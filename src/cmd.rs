@@ -1,4 +1,16 @@
-use crate::data::ActiveDay;
+use std::fmt::{Display, Formatter};
+use std::io::Write;
+
+use anyhow::bail;
+
+use crate::conf::{Settings, SettingsRef};
+use crate::data::{Action, ActiveDay, RecentIssues, WorkStart};
+use crate::db::DB;
+use crate::parsing::issue_parser::parse_issue_capture;
+use crate::ui;
+use crate::ui::single_instance;
+use crate::ui::ViewId;
+use crate::util::TimelineProvider;
 
 pub fn print_active_day(day: Option<ActiveDay>) -> ! {
     if day.is_none() {
@@ -20,3 +32,208 @@ pub fn print_active_day(day: Option<ActiveDay>) -> ! {
 
     std::process::exit(0);
 }
+
+/// Where the action of a command line call ended up
+#[derive(Debug)]
+enum Stored {
+    Written(Action),
+    /// Stored by the instance that already uses the database
+    Forwarded(Action),
+}
+
+impl Display for Stored {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Stored::Written(action) => write!(f, "Stored {}", action),
+            Stored::Forwarded(action) => write!(f, "Forwarded {} to the running instance", action),
+        }
+    }
+}
+
+/// Reads a single line from the terminal and stores it, without starting the UI
+pub fn prompt_action(settings: SettingsRef, db: &DB, view_id: ViewId) -> ! {
+    match read_and_store(settings, db, view_id) {
+        Ok(stored) => {
+            println!("{}", stored);
+            std::process::exit(0)
+        }
+        Err(e) => {
+            eprintln!("{:?}", e);
+            std::process::exit(1)
+        }
+    }
+}
+
+/// Starts work on the issue found in `input`, so a bookmarklet or browser extension can
+/// start tracking the issue that is open in the browser
+pub fn capture_issue(settings: SettingsRef, db: &DB, input: &str) -> ! {
+    match start_captured(settings, db, input) {
+        Ok(stored) => {
+            println!("{}", stored);
+            std::process::exit(0)
+        }
+        Err(e) => {
+            eprintln!("{:?}", e);
+            std::process::exit(1)
+        }
+    }
+}
+
+fn start_captured(settings: SettingsRef, db: &DB, input: &str) -> anyhow::Result<Stored> {
+    let issue = match parse_issue_capture(input) {
+        Some(issue) => issue,
+        None => bail!("No issue found in: {}", input),
+    };
+    let day = settings.load().active_date;
+    let active_day = db.get_day(day)?;
+    if active_day.is_closed() {
+        bail!("{} is closed", day);
+    }
+
+    let action = Action::WorkStart(WorkStart {
+        ts: settings.load().timeline.time_now(),
+        description: issue.description.clone().unwrap_or_default(),
+        task: issue.clone(),
+    });
+    let stored = store(&settings.load(), db, active_day, action)?;
+    if let Stored::Written(_) = stored {
+        let mut recent = RecentIssues::new(db.load_recent().unwrap_or_default(), settings);
+        recent.issue_used_with_comment(&issue, issue.description.as_deref());
+        if let Err(e) = db.store_recent(&recent.export_data()) {
+            eprintln!("Storing recent issues failed: {:?}", e);
+        }
+    }
+    Ok(stored)
+}
+
+fn read_and_store(settings: SettingsRef, db: &DB, view_id: ViewId) -> anyhow::Result<Stored> {
+    let day = settings.load().active_date;
+    let active_day = db.get_day(day)?;
+    if active_day.is_closed() {
+        bail!("{} is closed", day);
+    }
+    let label = match view_id {
+        ViewId::FastDayStart => "Start day [h|o|location] [+|-]hours or minute: ",
+        _ => "Day end [+|-]hours or minute: ",
+    };
+
+    let action = loop {
+        print!("{}", label);
+        std::io::stdout().flush()?;
+        let mut line = String::new();
+        if std::io::stdin().read_line(&mut line)? == 0 {
+            bail!("No input");
+        }
+        match ui::parse_fast_input(view_id, settings.clone(), &active_day, line.trim()) {
+            Some(action) => break action,
+            None => eprintln!("Invalid input: {}", line.trim()),
+        }
+    };
+
+    store(&settings.load(), db, active_day, action)
+}
+
+/// Hands the action to the running instance, its next save would drop an action
+/// written here. Stores the day directly only if no instance answers
+fn store(
+    settings: &Settings,
+    db: &DB,
+    mut active_day: ActiveDay,
+    action: Action,
+) -> anyhow::Result<Stored> {
+    if single_instance::forward_store(&settings.db_dir, active_day.get_day(), &action) {
+        return Ok(Stored::Forwarded(action));
+    }
+    if let Action::DayStart(_) = action {
+        active_day.record_zone(settings.timeline.utc_offset());
+    }
+    active_day.add_closing_action(action.clone(), settings.auto_close_work);
+    db.store_day(&active_day)?;
+    db.sync_git(db.take_commits());
+    Ok(Stored::Written(action))
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use tempfile::TempDir;
+
+    use crate::cmd::{start_captured, Stored};
+    use crate::conf::{into_settings_ref, Settings, SettingsRef};
+    use crate::data::test_support::{issue_end, issue_start};
+    use crate::data::Action;
+    use crate::db::DB;
+    use crate::util::StaticTimeline;
+
+    fn setup(db_dir: &TempDir) -> (SettingsRef, DB) {
+        let settings = Settings {
+            timeline: Arc::new(StaticTimeline::parse("2022-03-07 10:00")),
+            db_dir: db_dir.path().to_path_buf(),
+            auto_close_work: true,
+            ..Settings::default()
+        };
+        let settings = into_settings_ref(Settings {
+            active_date: settings.today(),
+            ..settings
+        });
+        let db = DB::init(db_dir.path()).unwrap();
+        let mut active_day = db.get_day(settings.load().active_date).unwrap();
+        active_day.add_action(issue_start("9", "QUA-1", "", "dev"));
+        db.store_day(&active_day).unwrap();
+        (settings, db)
+    }
+
+    #[test]
+    fn captured_start_ends_the_running_issue() {
+        let db_dir = TempDir::new().unwrap();
+        let (settings, db) = setup(&db_dir);
+        let day = settings.load().active_date;
+
+        let stored = start_captured(
+            settings,
+            &db,
+            "https://jira.example.com/browse/QUA-2 Export",
+        )
+        .unwrap();
+
+        assert!(matches!(stored, Stored::Written(_)));
+        let stored = db.load_day(day).unwrap().unwrap();
+        assert_eq!(stored.actions().len(), 3);
+        assert!(stored.actions().contains(&issue_end("10", "QUA-1")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn capture_is_stored_by_the_running_instance() {
+        use iced_futures::futures::executor::block_on;
+        use iced_futures::futures::{stream, StreamExt};
+        use iced_native::subscription::Recipe;
+
+        use crate::ui::single_instance::InstanceListener;
+        use crate::ui::Message;
+
+        let db_dir = TempDir::new().unwrap();
+        let (settings, db) = setup(&db_dir);
+        let day = settings.load().active_date;
+        let listener = InstanceListener::bind(db_dir.path()).unwrap();
+        let mut messages = Box::new(listener.clone()).stream(stream::empty().boxed());
+
+        let stored = start_captured(
+            settings,
+            &db,
+            "https://jira.example.com/browse/QUA-2 Export",
+        )
+        .unwrap();
+
+        assert!(matches!(stored, Stored::Forwarded(_)));
+        assert_eq!(db.load_day(day).unwrap().unwrap().actions().len(), 1);
+        match block_on(messages.next()) {
+            Some(Message::StoreForwarded(d, Action::WorkStart(w))) => {
+                assert_eq!(d, day);
+                assert_eq!(w.task.ident, "QUA-2");
+            }
+            m => panic!("unexpected {:?}", m),
+        }
+    }
+}
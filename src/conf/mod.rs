@@ -1,5 +1,10 @@
 pub use settings::{
-    into_settings_ref, update_settings, BreaksConfig, Settings, SettingsRef, SettingsSer,
+    into_settings_ref, migrate_settings, read_settings_file, update_settings, write_settings,
+    AbsenceCategory, BreakLimit, BreaksConfig, DailyBudget, EmailConfig, FieldAdvance, HooksConfig,
+    JiraConfig, OverlapHandling, RecurringBooking, Settings, SettingsFile, SettingsRef,
+    SettingsSer, TraceOutput, WebhookConfig, WeekdayBreaks,
 };
+pub use shared_config::{MergeMode, SharedConfig};
 
 mod settings;
+mod shared_config;
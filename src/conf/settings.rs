@@ -1,11 +1,17 @@
-use std::collections::BTreeMap;
-use std::path::PathBuf;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::fs::OpenOptions;
+use std::io::{BufReader, Write};
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use std::sync::Arc;
 
+use anyhow::Context;
 use arc_swap::ArcSwap;
+use chrono::Weekday;
 
-use crate::data::{Day, JiraIssue};
+use crate::data::{Absence, Day, JiraIssue, RecentRanking, UtcOffset, Work};
+use crate::parsing::locale::{DateFormat, TimeFormat};
 use crate::parsing::time::Time;
 use crate::parsing::JiraIssueParser;
 use crate::util::{update_arcswap, DefaultTimeline, Timeline, TimelineProvider};
@@ -20,14 +26,90 @@ pub struct Settings {
     pub settings_location: Option<PathBuf>,
     pub db_dir: PathBuf,
     pub resolution: chrono::Duration,
-    pub write_settings: bool,
+    /// Rewrites a missing or outdated settings file at startup, submitted changes are always written
+    pub migrate_settings: bool,
     pub active_date: Day,
     pub timeline: Timeline,
     pub issue_parser: JiraIssueParser,
     pub breaks: BreaksConfig,
     pub debug: bool,
+    /// Graphics backend of the renderer, selected automatically if not set
+    pub renderer: Option<String>,
     pub close_on_safe: bool,
     pub max_recent_issues: usize,
+    /// Keep seconds of booked times, only rounded during normalization
+    pub seconds_precision: bool,
+    /// Time zone defining day boundaries, system time zone if not set
+    pub home_zone: Option<UtcOffset>,
+    pub recent_ranking: RecentRanking,
+    /// Custom locations in addition to office and home office
+    pub locations: Vec<String>,
+    /// Valid project prefixes of issue ids, all projects are valid if empty
+    pub projects: Vec<String>,
+    /// Issue id prefixes of meetings, like `MEET` or `I-15`
+    pub meeting_prefixes: Vec<String>,
+    pub absence_categories: Vec<AbsenceCategory>,
+    /// Offered by the start of day wizard
+    pub recurring_bookings: Vec<RecurringBooking>,
+    /// Days without work, skipped like weekends when looking for unbooked days
+    pub holidays: Vec<Day>,
+    /// Work time per day, more time accrues compensation time
+    pub daily_target_minutes: u32,
+    /// Work time per week, shown as progress next to the tabs
+    pub weekly_target_minutes: u32,
+    /// Maximum work time per day, storing more work asks for confirmation
+    pub max_daily_minutes: Option<u32>,
+    /// Maximum work time per week up to the stored day
+    pub max_weekly_minutes: Option<u32>,
+    pub break_limit: BreakLimit,
+    /// Soft limit of the booked and running work of today
+    pub daily_budget: DailyBudget,
+    /// Reminds about a missing day start of today after this time
+    pub day_start_reminder: Option<Time>,
+    pub overlap_handling: OverlapHandling,
+    /// Stores the end of the running work with a start of another issue or the day end
+    pub auto_close_work: bool,
+    pub field_advance: FieldAdvance,
+    /// Format of shown and exported times, stored times are always 24-hour times
+    pub time_format: TimeFormat,
+    /// Format of shown and exported dates, stored dates are always ISO dates
+    pub date_format: DateFormat,
+    /// Black and white colors with strong borders
+    pub high_contrast: bool,
+    /// Show a plain text summary of the view for screen readers
+    pub text_summary: bool,
+    /// Ask before deleting an entry
+    pub confirm_deletes: bool,
+    /// Projects whose recent issues are hidden under their heading
+    pub collapsed_projects: BTreeSet<String>,
+    /// `#rrggbb` colors of issues, other issues get a color derived from their id
+    pub issue_colors: BTreeMap<String, String>,
+    /// Planned share of the work time per project in percent
+    pub project_targets: BTreeMap<String, u32>,
+    /// Cents per hour of issue id prefixes, used for invoices
+    pub hourly_rates: BTreeMap<String, u32>,
+    /// Path of export files with placeholders, exports are not written to files if empty
+    pub export_file_pattern: String,
+    /// Writes the export file when the day end is stored
+    pub auto_export: bool,
+    /// Adds the location, standby and absences to the default export, see
+    /// [crate::data::TimeCockpitExporter]
+    pub extended_export: bool,
+    /// Commits every stored day to the git repository containing `db_dir`
+    pub git_commits: bool,
+    pub email: Option<EmailConfig>,
+    pub webhook: Option<WebhookConfig>,
+    pub jira: Option<JiraConfig>,
+    pub hooks: HooksConfig,
+    /// Replaces the default export format, see [crate::data::TemplateExporter]
+    pub export_template: Option<String>,
+    /// Chord of `quarble hotkey`, like `ctrl+alt+Q`
+    pub global_hotkey: Option<String>,
+    pub trace: TraceOutput,
+    /// Name of the active profile, `None` for the default settings
+    pub profile: Option<String>,
+    /// Stored settings of all other profiles, the default settings have no name
+    pub other_profiles: BTreeMap<Option<String>, SettingsSer>,
 }
 
 impl Settings {
@@ -39,8 +121,48 @@ impl Settings {
                 issue_parser: JiraIssueParser::new(s.issue_shortcuts),
                 breaks: s.breaks,
                 max_recent_issues: s.max_recent_issues as usize,
+                seconds_precision: s.seconds_precision,
+                home_zone: s.home_zone,
+                recent_ranking: s.recent_ranking,
+                locations: s.locations,
+                projects: s.projects,
+                meeting_prefixes: s.meeting_prefixes,
+                absence_categories: s.absence_categories,
+                recurring_bookings: s.recurring_bookings,
+                holidays: s.holidays,
+                daily_target_minutes: s.daily_target_minutes,
+                weekly_target_minutes: s.weekly_target_minutes,
+                max_daily_minutes: s.max_daily_minutes,
+                max_weekly_minutes: s.max_weekly_minutes,
+                break_limit: s.break_limit,
+                daily_budget: s.daily_budget,
+                day_start_reminder: s.day_start_reminder,
+                overlap_handling: s.overlap_handling,
+                auto_close_work: s.auto_close_work,
+                field_advance: s.field_advance,
+                time_format: s.time_format,
+                date_format: s.date_format,
+                high_contrast: s.high_contrast,
+                text_summary: s.text_summary,
+                confirm_deletes: s.confirm_deletes,
+                collapsed_projects: s.collapsed_projects,
+                issue_colors: s.issue_colors,
+                project_targets: s.project_targets,
+                hourly_rates: s.hourly_rates,
+                export_file_pattern: s.export_file_pattern,
+                auto_export: s.auto_export,
+                extended_export: s.extended_export,
+                git_commits: s.git_commits,
+                email: s.email,
+                webhook: s.webhook,
+                jira: s.jira,
+                hooks: s.hooks,
+                export_template: s.export_template,
+                global_hotkey: s.global_hotkey,
+                trace: s.trace,
                 ..Self::default()
             }
+            .with_home_zone_date()
         } else {
             Self::default()
         }
@@ -51,14 +173,129 @@ impl Settings {
             settings_location: self.settings_location.clone(),
             db_dir: ser.db_dir,
             resolution: chrono::Duration::minutes(ser.resolution_minutes as i64),
-            write_settings: self.write_settings,
+            migrate_settings: self.migrate_settings,
             active_date: self.active_date,
             timeline: self.timeline.clone(),
             issue_parser: JiraIssueParser::new(ser.issue_shortcuts),
             breaks: ser.breaks,
             debug: self.debug,
+            renderer: self.renderer.clone(),
             close_on_safe: self.close_on_safe,
             max_recent_issues: ser.max_recent_issues as usize,
+            seconds_precision: ser.seconds_precision,
+            home_zone: ser.home_zone,
+            recent_ranking: ser.recent_ranking,
+            locations: ser.locations,
+            projects: ser.projects,
+            meeting_prefixes: ser.meeting_prefixes,
+            absence_categories: ser.absence_categories,
+            recurring_bookings: ser.recurring_bookings,
+            holidays: ser.holidays,
+            daily_target_minutes: ser.daily_target_minutes,
+            weekly_target_minutes: ser.weekly_target_minutes,
+            max_daily_minutes: ser.max_daily_minutes,
+            max_weekly_minutes: ser.max_weekly_minutes,
+            break_limit: ser.break_limit,
+            daily_budget: ser.daily_budget,
+            day_start_reminder: ser.day_start_reminder,
+            overlap_handling: ser.overlap_handling,
+            auto_close_work: ser.auto_close_work,
+            field_advance: ser.field_advance,
+            time_format: ser.time_format,
+            date_format: ser.date_format,
+            high_contrast: ser.high_contrast,
+            text_summary: ser.text_summary,
+            confirm_deletes: ser.confirm_deletes,
+            collapsed_projects: ser.collapsed_projects,
+            issue_colors: ser.issue_colors,
+            project_targets: ser.project_targets,
+            hourly_rates: ser.hourly_rates,
+            export_file_pattern: ser.export_file_pattern,
+            auto_export: ser.auto_export,
+            extended_export: ser.extended_export,
+            git_commits: ser.git_commits,
+            email: ser.email,
+            webhook: ser.webhook,
+            jira: ser.jira,
+            hooks: ser.hooks,
+            export_template: ser.export_template,
+            global_hotkey: ser.global_hotkey,
+            trace: ser.trace,
+            profile: self.profile.clone(),
+            other_profiles: self.other_profiles.clone(),
+        }
+    }
+
+    /// The settings of the active profile in `file`, `None` if they are the current settings.
+    ///
+    /// The database stays open until the next start, its directory is kept
+    pub fn reload(&self, file: SettingsFile) -> Result<Option<Settings>, String> {
+        let (selected, others) = file.select(self.profile.as_deref());
+        if selected.resolution_minutes < 1 || selected.resolution_minutes > 60 {
+            return Err(format!(
+                "Invalid resolution_minutes: {}",
+                selected.resolution_minutes
+            ));
+        }
+        let selected = SettingsSer {
+            db_dir: self.db_dir.clone(),
+            ..selected
+        };
+        if selected == SettingsSer::from_settings(self) && others == self.other_profiles {
+            return Ok(None);
+        }
+        Ok(Some(Self {
+            other_profiles: others,
+            ..self.apply_ser(selected)
+        }))
+    }
+
+    /// Settings with the active profile replaced by `profile`.
+    ///
+    /// The current settings are kept as stored settings of the previous profile.
+    /// Unknown profiles are created as copy of the current settings.
+    pub fn switch_profile(&self, profile: Option<String>) -> Self {
+        let mut other_profiles = self.other_profiles.clone();
+        other_profiles.insert(self.profile.clone(), SettingsSer::from_settings(self));
+        let target = other_profiles
+            .remove(&profile)
+            .unwrap_or_else(|| SettingsSer::from_settings(self));
+
+        Self {
+            profile,
+            other_profiles,
+            ..self.apply_ser(target)
+        }
+    }
+
+    /// Names of all profiles, `None` for the default settings first
+    pub fn profile_names(&self) -> Vec<Option<String>> {
+        let mut names: Vec<_> = self.other_profiles.keys().cloned().collect();
+        names.push(self.profile.clone());
+        names.sort();
+        names
+    }
+
+    /// Whether the project prefix of the issue id is one of the configured projects
+    pub fn is_valid_project(&self, ident: &str) -> bool {
+        let (project, _) = ident.split_once('-').unwrap_or((ident, ""));
+        let matches = |p: &String| p.eq_ignore_ascii_case(project);
+        self.projects.is_empty() || self.projects.iter().any(matches)
+    }
+
+    /// The current day, respecting the configured home time zone
+    pub fn today(&self) -> Day {
+        if let Some(zone) = self.home_zone {
+            self.timeline.today_in(zone)
+        } else {
+            self.timeline.today()
+        }
+    }
+
+    fn with_home_zone_date(self) -> Self {
+        Self {
+            active_date: self.today(),
+            ..self
         }
     }
 
@@ -87,14 +324,56 @@ impl Default for Settings {
             settings_location: None,
             db_dir: Default::default(),
             resolution: chrono::Duration::minutes(15),
-            write_settings: false,
+            migrate_settings: false,
             active_date: timeline.today(),
             timeline,
             issue_parser: JiraIssueParser::default(),
             breaks: Default::default(),
             debug: false,
+            renderer: None,
             close_on_safe: true,
             max_recent_issues: 10,
+            seconds_precision: false,
+            home_zone: None,
+            recent_ranking: RecentRanking::default(),
+            locations: Vec::new(),
+            projects: Vec::new(),
+            meeting_prefixes: Vec::new(),
+            absence_categories: default_absence_categories(),
+            recurring_bookings: Vec::new(),
+            holidays: Vec::new(),
+            daily_target_minutes: default_daily_target_minutes(),
+            weekly_target_minutes: default_weekly_target_minutes(),
+            max_daily_minutes: None,
+            max_weekly_minutes: None,
+            break_limit: BreakLimit::default(),
+            daily_budget: DailyBudget::default(),
+            day_start_reminder: None,
+            overlap_handling: OverlapHandling::default(),
+            auto_close_work: false,
+            field_advance: FieldAdvance::default(),
+            time_format: TimeFormat::default(),
+            date_format: DateFormat::default(),
+            high_contrast: false,
+            text_summary: false,
+            confirm_deletes: true,
+            collapsed_projects: BTreeSet::new(),
+            issue_colors: BTreeMap::new(),
+            project_targets: BTreeMap::new(),
+            hourly_rates: BTreeMap::new(),
+            export_file_pattern: String::new(),
+            auto_export: false,
+            extended_export: false,
+            git_commits: false,
+            email: None,
+            webhook: None,
+            jira: None,
+            hooks: HooksConfig::default(),
+            export_template: None,
+            global_hotkey: None,
+            trace: TraceOutput::default(),
+            profile: None,
+            other_profiles: BTreeMap::new(),
         }
     }
 }
@@ -110,12 +389,109 @@ pub struct SettingsSer {
     pub breaks: BreaksConfig,
     #[serde(default = "default_max_recent_issues")]
     pub max_recent_issues: u32,
+    #[serde(default)]
+    pub seconds_precision: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub home_zone: Option<UtcOffset>,
+    #[serde(default)]
+    pub recent_ranking: RecentRanking,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub locations: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub projects: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub meeting_prefixes: Vec<String>,
+    #[serde(default = "default_absence_categories")]
+    pub absence_categories: Vec<AbsenceCategory>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub recurring_bookings: Vec<RecurringBooking>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub holidays: Vec<Day>,
+    #[serde(default = "default_daily_target_minutes")]
+    pub daily_target_minutes: u32,
+    #[serde(default = "default_weekly_target_minutes")]
+    pub weekly_target_minutes: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_daily_minutes: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_weekly_minutes: Option<u32>,
+    #[serde(default)]
+    pub break_limit: BreakLimit,
+    #[serde(default)]
+    pub daily_budget: DailyBudget,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub day_start_reminder: Option<Time>,
+    #[serde(default)]
+    pub overlap_handling: OverlapHandling,
+    #[serde(default)]
+    pub auto_close_work: bool,
+    #[serde(default)]
+    pub field_advance: FieldAdvance,
+    #[serde(default)]
+    pub time_format: TimeFormat,
+    #[serde(default)]
+    pub date_format: DateFormat,
+    #[serde(default)]
+    pub high_contrast: bool,
+    #[serde(default)]
+    pub text_summary: bool,
+    #[serde(default = "default_confirm_deletes")]
+    pub confirm_deletes: bool,
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub collapsed_projects: BTreeSet<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub issue_colors: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub project_targets: BTreeMap<String, u32>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub hourly_rates: BTreeMap<String, u32>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub export_file_pattern: String,
+    #[serde(default)]
+    pub auto_export: bool,
+    #[serde(default)]
+    pub extended_export: bool,
+    #[serde(default)]
+    pub git_commits: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<EmailConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub webhook: Option<WebhookConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jira: Option<JiraConfig>,
+    #[serde(default, skip_serializing_if = "HooksConfig::is_empty")]
+    pub hooks: HooksConfig,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub export_template: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub global_hotkey: Option<String>,
+    #[serde(default)]
+    pub trace: TraceOutput,
 }
 
 fn default_max_recent_issues() -> u32 {
     10
 }
 
+fn default_daily_target_minutes() -> u32 {
+    8 * 60
+}
+
+fn default_weekly_target_minutes() -> u32 {
+    5 * default_daily_target_minutes()
+}
+
+fn default_confirm_deletes() -> bool {
+    true
+}
+
+fn default_absence_categories() -> Vec<AbsenceCategory> {
+    vec![AbsenceCategory {
+        name: Absence::DOCTOR.to_string(),
+        code: "DOCTOR".to_string(),
+    }]
+}
+
 impl SettingsSer {
     pub fn from_settings(settings: &Settings) -> SettingsSer {
         SettingsSer {
@@ -124,15 +500,476 @@ impl SettingsSer {
             issue_shortcuts: settings.issue_parser.shortcuts().clone(),
             breaks: settings.breaks.clone(),
             max_recent_issues: settings.max_recent_issues as u32,
+            seconds_precision: settings.seconds_precision,
+            home_zone: settings.home_zone,
+            recent_ranking: settings.recent_ranking,
+            locations: settings.locations.clone(),
+            projects: settings.projects.clone(),
+            meeting_prefixes: settings.meeting_prefixes.clone(),
+            absence_categories: settings.absence_categories.clone(),
+            recurring_bookings: settings.recurring_bookings.clone(),
+            holidays: settings.holidays.clone(),
+            daily_target_minutes: settings.daily_target_minutes,
+            weekly_target_minutes: settings.weekly_target_minutes,
+            max_daily_minutes: settings.max_daily_minutes,
+            max_weekly_minutes: settings.max_weekly_minutes,
+            break_limit: settings.break_limit,
+            daily_budget: settings.daily_budget,
+            day_start_reminder: settings.day_start_reminder,
+            overlap_handling: settings.overlap_handling,
+            auto_close_work: settings.auto_close_work,
+            field_advance: settings.field_advance.clone(),
+            time_format: settings.time_format,
+            date_format: settings.date_format,
+            high_contrast: settings.high_contrast,
+            text_summary: settings.text_summary,
+            confirm_deletes: settings.confirm_deletes,
+            collapsed_projects: settings.collapsed_projects.clone(),
+            issue_colors: settings.issue_colors.clone(),
+            project_targets: settings.project_targets.clone(),
+            hourly_rates: settings.hourly_rates.clone(),
+            export_file_pattern: settings.export_file_pattern.clone(),
+            auto_export: settings.auto_export,
+            extended_export: settings.extended_export,
+            git_commits: settings.git_commits,
+            email: settings.email.clone(),
+            webhook: settings.webhook.clone(),
+            jira: settings.jira.clone(),
+            hooks: settings.hooks.clone(),
+            export_template: settings.export_template.clone(),
+            global_hotkey: settings.global_hotkey.clone(),
+            trace: settings.trace.clone(),
         }
     }
 }
 
+/// Content of the settings file: the default settings and named profiles
+#[derive(Debug, Eq, PartialEq, Clone, serde::Deserialize, serde::Serialize)]
+pub struct SettingsFile {
+    #[serde(flatten)]
+    pub default: SettingsSer,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, SettingsSer>,
+}
+
+impl SettingsFile {
+    pub fn from_settings(settings: &Settings) -> SettingsFile {
+        let mut all = settings.other_profiles.clone();
+        all.insert(
+            settings.profile.clone(),
+            SettingsSer::from_settings(settings),
+        );
+        let default = all
+            .remove(&None)
+            .unwrap_or_else(|| SettingsSer::from_settings(settings));
+
+        SettingsFile {
+            default,
+            profiles: all
+                .into_iter()
+                .filter_map(|(name, s)| name.map(|name| (name, s)))
+                .collect(),
+        }
+    }
+
+    /// Splits into the settings of the selected profile and all others.
+    ///
+    /// Unknown profiles are created from the default settings
+    pub fn select(
+        self,
+        profile: Option<&str>,
+    ) -> (SettingsSer, BTreeMap<Option<String>, SettingsSer>) {
+        let mut all: BTreeMap<_, _> = self
+            .profiles
+            .into_iter()
+            .map(|(name, s)| (Some(name), s))
+            .collect();
+        all.insert(None, self.default);
+
+        let selected = all
+            .remove(&profile.map(str::to_string))
+            .unwrap_or_else(|| all[&None].clone());
+        (selected, all)
+    }
+}
+
+pub fn read_settings_file(location: &Path) -> anyhow::Result<SettingsFile> {
+    let file = std::fs::File::open(location).context("Failed to open settings file")?;
+    serde_json::from_reader(BufReader::new(file)).context("Failed to read settings")
+}
+
+/// Writes the settings file.
+///
+/// The file is replaced at once, an interrupted write keeps the previous settings.
+/// Settings without a location, like in tests, are not written
+pub fn write_settings(settings: &Settings) -> anyhow::Result<()> {
+    let location = match &settings.settings_location {
+        Some(location) => location,
+        None => return Ok(()),
+    };
+    let buffer = serialize_settings(settings)?;
+    log::info!("Writing settings to {}", location.display());
+    write_atomic(location, &buffer)
+}
+
+/// Writes the settings file at startup if it is missing or lacks current settings, if enabled.
+///
+/// Returns whether the file was written
+pub fn migrate_settings(settings: &Settings) -> anyhow::Result<bool> {
+    let location = match &settings.settings_location {
+        Some(location) if settings.migrate_settings => location,
+        _ => return Ok(false),
+    };
+    let buffer = serialize_settings(settings)?;
+    if std::fs::read(location).ok().as_ref() == Some(&buffer) {
+        return Ok(false);
+    }
+    log::info!("Migrating settings in {}", location.display());
+    write_atomic(location, &buffer)?;
+    Ok(true)
+}
+
+fn serialize_settings(settings: &Settings) -> anyhow::Result<Vec<u8>> {
+    let to_write = SettingsFile::from_settings(settings);
+    serde_json::to_vec_pretty(&to_write).context("Failed to serialize settings")
+}
+
+/// Writes a temporary file next to `location` and moves it over `location`
+fn write_atomic(location: &Path, buffer: &[u8]) -> anyhow::Result<()> {
+    if let Some(dir) = location.parent() {
+        if !dir.is_dir() {
+            std::fs::create_dir_all(dir).with_context(|| {
+                format!("Failed to create settings directory: {}", dir.display())
+            })?;
+        }
+    }
+
+    let temp = location.with_extension("json.tmp");
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(&temp)
+        .with_context(|| format!("Cannot open {} for writing", temp.display()))?;
+    file.write_all(buffer)
+        .and_then(|_| file.sync_all())
+        .context("Failed to write settings")?;
+    std::fs::rename(&temp, location)
+        .with_context(|| format!("Cannot replace {}", location.display()))
+}
+
 #[derive(Clone, Debug, Default, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
 pub struct BreaksConfig {
     pub min_breaks_minutes: u32,
     pub min_work_time_minutes: u32,
     pub default_break: (Time, Time),
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub weekday_overrides: Vec<WeekdayBreaks>,
+}
+
+/// Break rules replacing the defaults on a single weekday
+#[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct WeekdayBreaks {
+    pub weekday: Weekday,
+    pub min_breaks_minutes: u32,
+    pub min_work_time_minutes: u32,
+    pub default_break: (Time, Time),
+}
+
+/// SMTP account reports are mailed with, only configured in the settings file
+#[derive(Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct EmailConfig {
+    pub server: String,
+    #[serde(default = "default_smtp_port")]
+    pub port: u16,
+    pub user: String,
+    pub password: String,
+    pub from: String,
+    pub recipients: Vec<String>,
+    /// The weekly report is sent when the end of this day is stored
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weekly_on: Option<Weekday>,
+}
+
+impl fmt::Debug for EmailConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EmailConfig")
+            .field("server", &self.server)
+            .field("port", &self.port)
+            .field("user", &self.user)
+            .field("password", &REDACTED)
+            .field("from", &self.from)
+            .field("recipients", &self.recipients)
+            .field("weekly_on", &self.weekly_on)
+            .finish()
+    }
+}
+
+/// Stands in for passwords and tokens when the settings are logged
+const REDACTED: &str = "<redacted>";
+
+fn redacted(secret: &Option<String>) -> Option<&'static str> {
+    secret.as_ref().map(|_| REDACTED)
+}
+
+fn default_smtp_port() -> u16 {
+    587
+}
+
+/// URL the normalized days are posted to as JSON, only configured in the settings file
+#[derive(Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct WebhookConfig {
+    pub url: String,
+    /// Sent as bearer token in the authorization header
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+    /// Failed deliveries are retried this often, waiting twice as long each time
+    #[serde(default = "default_webhook_retries")]
+    pub retries: u32,
+    /// Posts the day when its end is stored
+    #[serde(default)]
+    pub on_day_end: bool,
+}
+
+impl fmt::Debug for WebhookConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WebhookConfig")
+            .field("url", &self.url)
+            .field("token", &redacted(&self.token))
+            .field("retries", &self.retries)
+            .field("on_day_end", &self.on_day_end)
+            .finish()
+    }
+}
+
+fn default_webhook_retries() -> u32 {
+    3
+}
+
+/// Jira the summaries of recent issues without description are fetched from,
+/// only configured in the settings file
+#[derive(Clone, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct JiraConfig {
+    /// Base URL like `https://example.atlassian.net`
+    pub url: String,
+    /// With a user the token is sent as password, otherwise as bearer token
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+}
+
+impl fmt::Debug for JiraConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JiraConfig")
+            .field("url", &self.url)
+            .field("user", &self.user)
+            .field("token", &redacted(&self.token))
+            .finish()
+    }
+}
+
+/// Programs run with the day as JSON on stdin, only configured in the settings file.
+///
+/// Every command is a program followed by its arguments
+#[derive(Clone, Debug, Default, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct HooksConfig {
+    /// Run after every store of a day
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub after_store: Vec<String>,
+    /// Run after a day was exported to the clipboard or to a file
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub after_export: Vec<String>,
+}
+
+impl HooksConfig {
+    pub fn is_empty(&self) -> bool {
+        self.after_store.is_empty() && self.after_export.is_empty()
+    }
+}
+
+/// Longest work without a break, warned about in the overview
+#[derive(Copy, Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct BreakLimit {
+    /// Zero disables the warning
+    pub max_work_minutes: u32,
+    /// Shorter interruptions do not end the continuous work
+    pub min_break_minutes: u32,
+    /// Show a desktop notification when the limit is exceeded
+    #[serde(default)]
+    pub notify: bool,
+}
+
+impl Default for BreakLimit {
+    fn default() -> Self {
+        BreakLimit {
+            max_work_minutes: 6 * 60,
+            min_break_minutes: 30,
+            notify: false,
+        }
+    }
+}
+
+/// Soft limit of the work of today, booked and running work crossing it is highlighted
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct DailyBudget {
+    /// Zero disables the budget
+    pub minutes: u32,
+    /// Show a desktop notification when the budget is used up
+    #[serde(default)]
+    pub notify: bool,
+}
+
+/// Typing a separator at the end of a field of the inline editor moves to the next field
+#[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct FieldAdvance {
+    pub enabled: bool,
+    /// Separators of the start, end and issue fields
+    pub start: String,
+    pub end: String,
+    pub issue: String,
+    /// Separators that end the comment and store the edited entry
+    pub comment: String,
+}
+
+impl Default for FieldAdvance {
+    fn default() -> Self {
+        FieldAdvance {
+            enabled: false,
+            start: " ".to_string(),
+            end: " ".to_string(),
+            issue: " ".to_string(),
+            comment: "#".to_string(),
+        }
+    }
+}
+
+/// What happens when booked work overlaps existing bookings
+#[derive(Copy, Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OverlapHandling {
+    /// Stores only after submitting a second time
+    Warn,
+    /// Moves the work into the nearest free time
+    Adjust,
+}
+
+impl Default for OverlapHandling {
+    fn default() -> Self {
+        OverlapHandling::Warn
+    }
+}
+
+/// Where tracing spans are written to
+#[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TraceOutput {
+    Off,
+    /// Pretty printed to stdout
+    Stdout,
+    /// Log file in the DB directory, rotated when it grows too large
+    File,
+    /// OTLP/HTTP collector endpoint
+    Otlp(String),
+}
+
+impl TraceOutput {
+    /// Parses `off`, `stdout`, `file`, `otlp` or `otlp=<endpoint>`
+    pub fn parse(input: &str) -> Option<TraceOutput> {
+        match input.split_once('=') {
+            Some(("otlp", endpoint)) if !endpoint.is_empty() => {
+                Some(TraceOutput::Otlp(endpoint.to_string()))
+            }
+            Some(_) => None,
+            None => match input {
+                "off" => Some(TraceOutput::Off),
+                "stdout" => Some(TraceOutput::Stdout),
+                "file" => Some(TraceOutput::File),
+                "otlp" => Some(TraceOutput::Otlp(DEFAULT_OTLP_ENDPOINT.to_string())),
+                _ => None,
+            },
+        }
+    }
+}
+
+impl Default for TraceOutput {
+    fn default() -> Self {
+        TraceOutput::Off
+    }
+}
+
+const DEFAULT_OTLP_ENDPOINT: &str = "http://localhost:4318/v1/traces";
+
+/// User defined kind of absence and the code used for it in exports
+#[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct AbsenceCategory {
+    pub name: String,
+    pub code: String,
+}
+
+impl AbsenceCategory {
+    /// Export code of the category, the category itself if it is not configured
+    pub fn code_of<'a>(categories: &'a [AbsenceCategory], category: &'a str) -> &'a str {
+        categories
+            .iter()
+            .find(|c| c.name == category)
+            .map(|c| c.code.as_str())
+            .unwrap_or(category)
+    }
+}
+
+/// Work planned on the same weekdays, like a daily standup
+#[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct RecurringBooking {
+    /// Every day from monday to friday if empty
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub weekdays: Vec<Weekday>,
+    pub start: Time,
+    pub end: Time,
+    pub task: JiraIssue,
+    #[serde(default)]
+    pub description: String,
+}
+
+impl RecurringBooking {
+    pub fn applies_to(&self, day: Day) -> bool {
+        let weekday = day.day_of_week();
+        if self.weekdays.is_empty() {
+            !matches!(weekday, Weekday::Sat | Weekday::Sun)
+        } else {
+            self.weekdays.contains(&weekday)
+        }
+    }
+
+    pub fn work(&self) -> Work {
+        Work {
+            start: self.start,
+            end: self.end,
+            task: self.task.clone(),
+            description: self.description.clone(),
+            links: Vec::new(),
+        }
+    }
+}
+
+impl BreaksConfig {
+    /// Break rules applying to `day`, without further overrides
+    pub fn for_day(&self, day: Day) -> BreaksConfig {
+        let weekday = day.day_of_week();
+        match self.weekday_overrides.iter().find(|o| o.weekday == weekday) {
+            Some(o) => BreaksConfig {
+                min_breaks_minutes: o.min_breaks_minutes,
+                min_work_time_minutes: o.min_work_time_minutes,
+                default_break: o.default_break,
+                weekday_overrides: Vec::new(),
+            },
+            None => BreaksConfig {
+                weekday_overrides: Vec::new(),
+                ..self.clone()
+            },
+        }
+    }
 }
 
 #[cfg(test)]
@@ -140,8 +977,15 @@ mod test {
     use std::collections::BTreeMap;
     use std::path::Path;
 
-    use crate::conf::{BreaksConfig, SettingsSer};
-    use crate::data::JiraIssue;
+    use chrono::Weekday;
+
+    use crate::conf::{
+        migrate_settings, write_settings, AbsenceCategory, BreakLimit, BreaksConfig, DailyBudget,
+        EmailConfig, FieldAdvance, HooksConfig, JiraConfig, OverlapHandling, RecurringBooking,
+        Settings, SettingsFile, SettingsSer, TraceOutput, WebhookConfig, WeekdayBreaks,
+    };
+    use crate::data::{Day, JiraIssue, RecentRanking, UtcOffset};
+    use crate::parsing::locale::{DateFormat, TimeFormat};
     use crate::parsing::time::Time;
 
     #[test]
@@ -182,13 +1026,285 @@ mod test {
                 min_breaks_minutes: 45,
                 min_work_time_minutes: 360,
                 default_break: (Time::hm(11, 30), Time::hm(12, 15)),
+                weekday_overrides: vec![WeekdayBreaks {
+                    weekday: Weekday::Fri,
+                    min_breaks_minutes: 30,
+                    min_work_time_minutes: 300,
+                    default_break: (Time::hm(12, 0), Time::hm(12, 30)),
+                }],
             },
             max_recent_issues: 15,
+            seconds_precision: true,
+            home_zone: UtcOffset::from_minutes(60),
+            recent_ranking: RecentRanking::Frequency,
+            locations: vec!["Client A".to_string()],
+            projects: vec!["A".to_string(), "QUA".to_string()],
+            meeting_prefixes: vec!["MEET".to_string(), "I-15".to_string()],
+            absence_categories: vec![
+                AbsenceCategory {
+                    name: "doctor".to_string(),
+                    code: "ARZT".to_string(),
+                },
+                AbsenceCategory {
+                    name: "authority".to_string(),
+                    code: "BEH".to_string(),
+                },
+            ],
+            recurring_bookings: vec![RecurringBooking {
+                weekdays: vec![Weekday::Mon, Weekday::Thu],
+                start: Time::hm(9, 30),
+                end: Time::hm(9, 45),
+                task: JiraIssue::create("MEET-1").unwrap(),
+                description: "standup".to_string(),
+            }],
+            holidays: vec![Day::ymd(2022, 12, 25), Day::ymd(2022, 12, 26)],
+            daily_target_minutes: 462,
+            weekly_target_minutes: 2310,
+            max_daily_minutes: Some(600),
+            max_weekly_minutes: Some(48 * 60),
+            break_limit: BreakLimit {
+                max_work_minutes: 5 * 60,
+                min_break_minutes: 15,
+                notify: true,
+            },
+            daily_budget: DailyBudget {
+                minutes: 8 * 60,
+                notify: true,
+            },
+            day_start_reminder: Some(Time::hm(9, 30)),
+            overlap_handling: OverlapHandling::Adjust,
+            auto_close_work: true,
+            field_advance: FieldAdvance {
+                enabled: true,
+                comment: "#;".to_string(),
+                ..FieldAdvance::default()
+            },
+            time_format: TimeFormat::H12,
+            date_format: DateFormat::Dotted,
+            high_contrast: true,
+            text_summary: true,
+            confirm_deletes: false,
+            collapsed_projects: ["MEET".to_string()].into_iter().collect(),
+            issue_colors: [("QUA-1".to_string(), "#ff8000".to_string())]
+                .into_iter()
+                .collect(),
+            project_targets: [("QUA".to_string(), 60), ("MEET".to_string(), 20)]
+                .into_iter()
+                .collect(),
+            hourly_rates: [("QUA".to_string(), 8000), ("QUA-7".to_string(), 10050)]
+                .into_iter()
+                .collect(),
+            export_file_pattern: "~/reports/{year}/{month}/{date}.csv".to_string(),
+            auto_export: true,
+            extended_export: true,
+            git_commits: true,
+            email: Some(EmailConfig {
+                server: "smtp.example.com".to_string(),
+                port: 2525,
+                user: "user".to_string(),
+                password: "secret".to_string(),
+                from: "me@example.com".to_string(),
+                recipients: vec!["lead@example.com".to_string()],
+                weekly_on: Some(Weekday::Fri),
+            }),
+            webhook: Some(WebhookConfig {
+                url: "https://hooks.example.com/time".to_string(),
+                token: Some("token".to_string()),
+                retries: 5,
+                on_day_end: true,
+            }),
+            jira: Some(JiraConfig {
+                url: "https://jira.example.com".to_string(),
+                user: Some("me".to_string()),
+                token: Some("secret".to_string()),
+            }),
+            hooks: HooksConfig {
+                after_store: vec!["sync.sh".to_string(), "--quiet".to_string()],
+                after_export: vec!["notify.sh".to_string()],
+            },
+            export_template: Some("{% for w in work %}{{ w.issue }}\n{% endfor %}".to_string()),
+            global_hotkey: Some("super+shift+B".to_string()),
+            trace: TraceOutput::Otlp("http://localhost:4318/v1/traces".to_string()),
         };
 
         let pretty = serde_json::to_string_pretty(&orig).unwrap();
 
         let parsed = serde_json::from_str(&pretty).unwrap();
         assert_eq!(orig, parsed);
+
+        // files without profiles stay compatible
+        let file: SettingsFile = serde_json::from_str(&pretty).unwrap();
+        assert_eq!(file.default, orig);
+        assert!(file.profiles.is_empty());
+
+        // older files only know doctor's appointments
+        let old: SettingsSer = serde_json::from_str(r#"{"db_dir": "db/dir"}"#).unwrap();
+        assert_eq!(
+            old.absence_categories,
+            Settings::default().absence_categories
+        );
+        assert!(old.confirm_deletes);
+        assert_eq!(old.weekly_target_minutes, 40 * 60);
+        assert_eq!(old.break_limit.max_work_minutes, 6 * 60);
+        assert_eq!(
+            AbsenceCategory::code_of(&orig.absence_categories, "doctor"),
+            "ARZT"
+        );
+        assert_eq!(
+            AbsenceCategory::code_of(&orig.absence_categories, "other"),
+            "other"
+        );
+    }
+
+    #[test]
+    fn switch_profiles() {
+        let default = Settings {
+            db_dir: Path::new("db/default").to_owned(),
+            ..Settings::default()
+        };
+        let client_a = SettingsSer {
+            db_dir: Path::new("db/client_a").to_owned(),
+            ..SettingsSer::from_settings(&default)
+        };
+        let file = SettingsFile {
+            default: SettingsSer::from_settings(&default),
+            profiles: BTreeMap::from([("client_a".to_string(), client_a.clone())]),
+        };
+        let pretty = serde_json::to_string_pretty(&file).unwrap();
+        assert_eq!(serde_json::from_str::<SettingsFile>(&pretty).unwrap(), file);
+
+        let (selected, others) = file.clone().select(Some("client_a"));
+        assert_eq!(selected, client_a);
+        let settings = Settings {
+            profile: Some("client_a".to_string()),
+            other_profiles: others,
+            ..Settings::from_ser(Some(selected))
+        };
+        assert_eq!(SettingsFile::from_settings(&settings), file);
+        assert_eq!(
+            settings.profile_names(),
+            vec![None, Some("client_a".to_string())]
+        );
+
+        let switched = settings.switch_profile(None);
+        assert_eq!(switched.db_dir, Path::new("db/default"));
+        assert_eq!(SettingsFile::from_settings(&switched), file);
+
+        let created = switched.switch_profile(Some("client_b".to_string()));
+        assert_eq!(created.db_dir, Path::new("db/default"));
+        assert_eq!(SettingsFile::from_settings(&created).profiles.len(), 2);
+
+        let (unknown, _) = file.select(Some("unknown"));
+        assert_eq!(unknown.db_dir, Path::new("db/default"));
+    }
+
+    #[test]
+    fn validates_projects() {
+        let mut settings = Settings::default();
+        assert!(settings.is_valid_project("ANY-1"));
+
+        settings.projects = vec!["QUA".to_string()];
+        assert!(settings.is_valid_project("QUA-12"));
+        assert!(settings.is_valid_project("qua-12"));
+        assert!(!settings.is_valid_project("QAU-12"));
+    }
+
+    #[test]
+    fn debug_output_hides_secrets() {
+        let settings = Settings {
+            email: Some(EmailConfig {
+                server: "smtp.example.com".to_string(),
+                port: 587,
+                user: "me".to_string(),
+                password: "mail-secret".to_string(),
+                from: "me@example.com".to_string(),
+                recipients: vec![],
+                weekly_on: None,
+            }),
+            webhook: Some(WebhookConfig {
+                url: "https://example.com/hook".to_string(),
+                token: Some("hook-secret".to_string()),
+                retries: 3,
+                on_day_end: false,
+            }),
+            jira: Some(JiraConfig {
+                url: "https://example.atlassian.net".to_string(),
+                user: None,
+                token: Some("jira-secret".to_string()),
+            }),
+            ..Settings::default()
+        };
+
+        let debug = format!("{:?}", settings);
+        assert!(debug.contains("smtp.example.com"));
+        assert!(!debug.contains("secret"));
+    }
+
+    #[test]
+    fn parses_trace_output() {
+        assert_eq!(TraceOutput::parse("file"), Some(TraceOutput::File));
+        assert_eq!(
+            TraceOutput::parse("otlp"),
+            Some(TraceOutput::Otlp(
+                "http://localhost:4318/v1/traces".to_string()
+            ))
+        );
+        assert_eq!(
+            TraceOutput::parse("otlp=http://collector:4318/v1/traces"),
+            Some(TraceOutput::Otlp(
+                "http://collector:4318/v1/traces".to_string()
+            ))
+        );
+        assert_eq!(TraceOutput::parse("otlp="), None);
+        assert_eq!(TraceOutput::parse("stderr"), None);
+    }
+
+    #[test]
+    fn migrates_only_when_enabled() {
+        let dir = tempfile::tempdir().unwrap();
+        let location = dir.path().join("settings").join("quarble_settings.json");
+        let settings = Settings {
+            settings_location: Some(location.clone()),
+            migrate_settings: true,
+            ..Settings::default()
+        };
+
+        assert!(migrate_settings(&settings).unwrap());
+        assert!(!migrate_settings(&settings).unwrap());
+        let current = std::fs::read(&location).unwrap();
+
+        std::fs::write(&location, "{}").unwrap();
+        let disabled = Settings {
+            migrate_settings: false,
+            ..settings
+        };
+        assert!(!migrate_settings(&disabled).unwrap());
+        assert_eq!(std::fs::read_to_string(&location).unwrap(), "{}");
+
+        // submitted settings are written anyway
+        write_settings(&disabled).unwrap();
+        assert_eq!(std::fs::read(&location).unwrap(), current);
+        assert!(!location.with_extension("json.tmp").exists());
+    }
+
+    #[test]
+    fn reloads_changed_files() {
+        let settings = Settings {
+            db_dir: Path::new("db/explicit").to_owned(),
+            ..Settings::default()
+        };
+        let unchanged = SettingsFile::from_settings(&settings);
+        assert!(matches!(settings.reload(unchanged.clone()), Ok(None)));
+
+        let mut changed = unchanged.clone();
+        changed.default.db_dir = Path::new("db/other").to_owned();
+        changed.default.daily_target_minutes = 360;
+        let reloaded = settings.reload(changed).unwrap().unwrap();
+        assert_eq!(reloaded.daily_target_minutes, 360);
+        assert_eq!(reloaded.db_dir, Path::new("db/explicit"));
+
+        let mut invalid = unchanged;
+        invalid.default.resolution_minutes = 0;
+        assert!(settings.reload(invalid).is_err());
     }
 }
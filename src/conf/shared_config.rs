@@ -0,0 +1,130 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::Path;
+
+use crate::data::JiraIssue;
+
+/// Issue shortcuts and recent issues in a standalone file, so that teams can share
+/// a common set of bookings
+#[derive(Clone, Debug, Default, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct SharedConfig {
+    #[serde(default)]
+    pub issue_shortcuts: BTreeMap<char, JiraIssue>,
+    #[serde(default)]
+    pub recent_issues: Vec<JiraIssue>,
+}
+
+/// How imported entries are handled that already exist with different values
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum MergeMode {
+    Skip,
+    Overwrite,
+}
+
+impl SharedConfig {
+    pub fn read(path: &Path) -> Result<SharedConfig, String> {
+        let file =
+            File::open(path).map_err(|e| format!("Cannot open {}: {}", path.display(), e))?;
+        serde_json::from_reader(BufReader::new(file))
+            .map_err(|e| format!("Invalid file {}: {}", path.display(), e))
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), String> {
+        let file =
+            File::create(path).map_err(|e| format!("Cannot write {}: {}", path.display(), e))?;
+        let mut writer = BufWriter::new(file);
+        serde_json::to_writer_pretty(&mut writer, self)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        writer
+            .flush()
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+
+    /// Shortcuts that are already configured for a different issue
+    pub fn conflicting_shortcuts(&self, existing: &BTreeMap<char, JiraIssue>) -> Vec<char> {
+        self.issue_shortcuts
+            .iter()
+            .filter(|(sc, issue)| matches!(existing.get(sc), Some(e) if e != *issue))
+            .map(|(sc, _)| *sc)
+            .collect()
+    }
+
+    /// Adds the shared shortcuts to `existing`, returns the number of added or changed shortcuts
+    pub fn merge_shortcuts(
+        &self,
+        existing: &mut BTreeMap<char, JiraIssue>,
+        mode: MergeMode,
+    ) -> usize {
+        let mut changed = 0;
+        for (sc, issue) in &self.issue_shortcuts {
+            match existing.get(sc) {
+                Some(e) if e == issue => (),
+                Some(_) if mode == MergeMode::Skip => (),
+                _ => {
+                    existing.insert(*sc, issue.clone());
+                    changed += 1;
+                }
+            }
+        }
+        changed
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use crate::conf::{MergeMode, SharedConfig};
+    use crate::data::JiraIssue;
+
+    fn issue(ident: &str, description: &str) -> JiraIssue {
+        JiraIssue {
+            ident: ident.to_string(),
+            description: Some(description.to_string()),
+            default_action: None,
+        }
+    }
+
+    #[test]
+    fn merges_shortcuts() {
+        let shared = SharedConfig {
+            issue_shortcuts: BTreeMap::from([
+                ('a', issue("A-1", "same")),
+                ('b', issue("B-1", "new")),
+                ('c', issue("C-1", "added")),
+            ]),
+            recent_issues: vec![],
+        };
+        let existing = BTreeMap::from([('a', issue("A-1", "same")), ('b', issue("B-2", "old"))]);
+
+        assert_eq!(shared.conflicting_shortcuts(&existing), vec!['b']);
+
+        let mut skipped = existing.clone();
+        assert_eq!(shared.merge_shortcuts(&mut skipped, MergeMode::Skip), 1);
+        assert_eq!(skipped[&'b'], issue("B-2", "old"));
+        assert_eq!(skipped[&'c'], issue("C-1", "added"));
+
+        let mut overwritten = existing;
+        assert_eq!(
+            shared.merge_shortcuts(&mut overwritten, MergeMode::Overwrite),
+            2
+        );
+        assert_eq!(overwritten[&'b'], issue("B-1", "new"));
+    }
+
+    #[test]
+    fn read_write_shared_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("shared.json");
+        let shared = SharedConfig {
+            issue_shortcuts: BTreeMap::from([('a', issue("A-1", "a"))]),
+            recent_issues: vec![issue("R-1", "recent")],
+        };
+        shared.write(&path).unwrap();
+        assert_eq!(SharedConfig::read(&path).unwrap(), shared);
+
+        std::fs::write(&path, "{}").unwrap();
+        assert_eq!(SharedConfig::read(&path).unwrap(), SharedConfig::default());
+    }
+}
@@ -5,6 +5,7 @@ use std::fmt::{Display, Formatter};
 use crate::data::location::Location;
 use crate::data::work::{Work, WorkEnd, WorkEvent, WorkStart};
 use crate::parsing::time::Time;
+use crate::parsing::time_relative::TimeRelative;
 
 #[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
 pub enum Action {
@@ -18,7 +19,10 @@ pub enum Action {
     ZA(ZA),
     Vacation,
     Sick,
-    Doctor(Doctor),
+    /// Stored as `Doctor` by older versions
+    #[serde(alias = "Doctor")]
+    Absence(Absence),
+    Standby(Standby),
 }
 
 impl Action {
@@ -26,6 +30,11 @@ impl Action {
         NoTimeDisplay(self)
     }
 
+    /// The action as a sentence, for screen readers
+    pub fn as_sentence(&self) -> SentenceDisplay {
+        SentenceDisplay(self)
+    }
+
     pub fn start(&self) -> Option<Time> {
         match self {
             Action::Work(w) => Some(w.start),
@@ -33,7 +42,8 @@ impl Action {
             Action::WorkStart(w) => Some(w.ts),
             Action::DayStart(w) => Some(w.ts),
             Action::ZA(w) => Some(w.start),
-            Action::Doctor(w) => Some(w.start),
+            Action::Absence(w) => Some(w.start),
+            Action::Standby(s) => Some(s.start),
             _ => None,
         }
     }
@@ -45,7 +55,19 @@ impl Action {
             Action::WorkEnd(w) => Some(w.ts),
             Action::DayEnd(w) => Some(w.ts),
             Action::ZA(w) => Some(w.end),
-            Action::Doctor(w) => Some(w.end),
+            Action::Absence(w) => Some(w.end),
+            Action::Standby(s) => Some(s.end),
+            _ => None,
+        }
+    }
+
+    /// Booked minutes of actions covering a time range
+    pub fn duration_minutes(&self) -> Option<u32> {
+        match self {
+            Action::Work(_) | Action::ZA(_) | Action::Absence(_) | Action::Standby(_) => {
+                let (start, end) = (self.start()?, self.end()?);
+                Some(start.minutes_until(end))
+            }
             _ => None,
         }
     }
@@ -85,10 +107,120 @@ impl Action {
             Action::ZA(_) => 7,
             Action::Vacation => 8,
             Action::Sick => 9,
-            Action::Doctor(_) => 10,
+            Action::Absence(_) => 10,
+            Action::Standby(_) => 11,
         }
     }
 
+    /// True if `next` is the part of this action that was moved to the following day,
+    /// see [Action::split_at_midnight]
+    pub fn continued_by(&self, next: &Action) -> bool {
+        match (self, next) {
+            (Action::Work(w), Action::Work(n)) => {
+                w.end == Time::MAX
+                    && n.start == Time::ZERO
+                    && w.task == n.task
+                    && w.description == n.description
+            }
+            (Action::Standby(s), Action::Standby(n)) => s.end == Time::MAX && n.start == Time::ZERO,
+            _ => false,
+        }
+    }
+
+    /// Splits work and standby crossing midnight, see [Work::split_at_midnight]
+    pub fn split_at_midnight(self) -> (Action, Option<Action>) {
+        match self {
+            Action::Work(w) => {
+                let (w, continuation) = w.split_at_midnight();
+                (Action::Work(w), continuation.map(Action::Work))
+            }
+            Action::Standby(s) => {
+                let (s, continuation) = s.split_at_midnight();
+                (Action::Standby(s), continuation.map(Action::Standby))
+            }
+            a => (a, None),
+        }
+    }
+
+    /// Applies `f` to all times of this action
+    pub fn map_times(self, f: impl Fn(Time) -> Time) -> Action {
+        self.try_map_times(|t| Some(f(t))).unwrap()
+    }
+
+    /// Applies `f` to all times of this action, fails if any time cannot be mapped
+    pub fn try_map_times(self, f: impl Fn(Time) -> Option<Time>) -> Option<Action> {
+        let action = match self {
+            Action::Work(w) => Action::Work(Work {
+                start: f(w.start)?,
+                end: f(w.end)?,
+                ..w
+            }),
+            Action::WorkEvent(w) => Action::WorkEvent(WorkEvent { ts: f(w.ts)?, ..w }),
+            Action::WorkStart(w) => Action::WorkStart(WorkStart { ts: f(w.ts)?, ..w }),
+            Action::WorkEnd(w) => Action::WorkEnd(WorkEnd { ts: f(w.ts)?, ..w }),
+            Action::DayStart(d) => Action::DayStart(DayStart { ts: f(d.ts)?, ..d }),
+            Action::DayEnd(d) => Action::DayEnd(DayEnd { ts: f(d.ts)? }),
+            Action::ZA(z) => Action::ZA(ZA {
+                start: f(z.start)?,
+                end: f(z.end)?,
+            }),
+            Action::Absence(a) => Action::Absence(Absence {
+                start: f(a.start)?,
+                end: f(a.end)?,
+                ..a
+            }),
+            Action::Standby(s) => Action::Standby(Standby {
+                start: f(s.start)?,
+                end: f(s.end)?,
+            }),
+            a @ (Action::DayOff | Action::Vacation | Action::Sick) => a,
+        };
+        Some(action)
+    }
+
+    /// Moves the start of a time range, actions at a single time move as a whole
+    pub fn shift_start(self, offset: TimeRelative) -> Option<Action> {
+        self.shift_bound(true, offset)
+    }
+
+    /// Moves the end of a time range, actions at a single time move as a whole
+    pub fn shift_end(self, offset: TimeRelative) -> Option<Action> {
+        self.shift_bound(false, offset)
+    }
+
+    fn shift_bound(self, start: bool, offset: TimeRelative) -> Option<Action> {
+        let range = |from: Time, to: Time| {
+            let (from, to) = if start {
+                (from.try_add_relative(offset)?, to)
+            } else {
+                (from, to.try_add_relative(offset)?)
+            };
+            (from != to).then_some((from, to))
+        };
+
+        let action = match self {
+            Action::Work(w) => {
+                let (start, end) = range(w.start, w.end)?;
+                Action::Work(Work { start, end, ..w })
+            }
+            Action::ZA(z) => {
+                let (start, end) = range(z.start, z.end)?;
+                Action::ZA(ZA { start, end })
+            }
+            Action::Absence(a) => {
+                let (start, end) = range(a.start, a.end)?;
+                Action::Absence(Absence { start, end, ..a })
+            }
+            Action::Standby(s) => {
+                let (start, end) = range(s.start, s.end)?;
+                Action::Standby(Standby { start, end })
+            }
+            a => a.try_map_times(|t| t.try_add_relative(offset))?,
+        };
+        Some(action)
+    }
+
+    /// End of the booked work, standby does not count as work
     pub fn action_end(&self) -> Option<Time> {
         match self {
             Action::Work(w) => Some(w.end),
@@ -98,7 +230,7 @@ impl Action {
             Action::DayStart(w) => Some(w.ts),
             Action::DayEnd(w) => Some(w.ts),
             Action::ZA(w) => Some(w.end),
-            Action::Doctor(w) => Some(w.end),
+            Action::Absence(w) => Some(w.end),
             _ => None,
         }
     }
@@ -115,7 +247,8 @@ impl TimedAction for Action {
             Action::DayEnd(DayEnd { ts, .. }) => (ts, None),
             Action::DayOff | Action::Vacation | Action::Sick => (&Time::ZERO, None),
             Action::ZA(ZA { start, end }) => (start, Some(end)),
-            Action::Doctor(Doctor { start, end }) => (start, Some(end)),
+            Action::Absence(Absence { start, end, .. }) => (start, Some(end)),
+            Action::Standby(Standby { start, end }) => (start, Some(end)),
         };
         (*start, end.cloned())
     }
@@ -171,8 +304,11 @@ impl<'a> Display for NoTimeDisplay<'a> {
             Action::Sick => {
                 write!(f, "Sick leave")
             }
-            Action::Doctor(_) => {
-                write!(f, "doctor")
+            Action::Absence(a) => {
+                write!(f, "{}", a.category)
+            }
+            Action::Standby(_) => {
+                write!(f, "standby")
             }
         }
     }
@@ -215,10 +351,83 @@ impl Display for Action {
             Action::Sick => {
                 write!(f, "Sick leave")
             }
-            Action::Doctor(d) => {
-                write!(f, "{} - {} | doctor", d.start, d.end)
+            Action::Absence(a) => {
+                write!(f, "{} - {} | {}", a.start, a.end, a.category)
+            }
+            Action::Standby(s) => {
+                write!(f, "{} - {} | standby", s.start, s.end)
+            }
+        }
+    }
+}
+
+pub struct SentenceDisplay<'a>(pub &'a Action);
+
+impl<'a> Display for SentenceDisplay<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.0 {
+            Action::Work(w) => {
+                write!(f, "Work on {} from {} to {}", w.task.ident, w.start, w.end)?;
+                if let Some(minutes) = self.0.duration_minutes() {
+                    write!(f, ", {}", spoken_duration(minutes))?;
+                }
+            }
+            Action::WorkEvent(e) => {
+                write!(f, "Event on {} at {}", e.task.ident, e.ts)?;
+            }
+            Action::WorkStart(s) => {
+                write!(f, "Started work on {} at {}", s.task.ident, s.ts)?;
+            }
+            Action::WorkEnd(e) => {
+                write!(f, "Ended work on {} at {}", e.task.ident, e.ts)?;
+            }
+            Action::DayStart(s) => {
+                write!(f, "Day started at {} in {}", s.ts, s.location)?;
+            }
+            Action::DayEnd(e) => {
+                write!(f, "Day ended at {}", e.ts)?;
+            }
+            Action::DayOff => {
+                write!(f, "Day off")?;
+            }
+            Action::ZA(z) => {
+                write!(f, "Time off from {} to {}", z.start, z.end)?;
+            }
+            Action::Vacation => {
+                write!(f, "Vacation")?;
+            }
+            Action::Sick => {
+                write!(f, "Sick leave")?;
+            }
+            Action::Absence(a) => {
+                write!(f, "{} from {} to {}", a.category, a.start, a.end)?;
+                if let Some(credited) = a.credited_minutes {
+                    write!(f, ", credited {}", spoken_duration(credited))?;
+                }
+            }
+            Action::Standby(s) => {
+                write!(f, "Standby from {} to {}", s.start, s.end)?;
             }
         }
+        match self.0.description() {
+            Some(description) if !description.is_empty() => write!(f, ": {}.", description),
+            _ => write!(f, "."),
+        }
+    }
+}
+
+/// `1 hour 5 minutes`, units without a value are left out
+fn spoken_duration(minutes: u32) -> String {
+    let unit = |value: u32, name: &str| match value {
+        0 => None,
+        1 => Some(format!("1 {}", name)),
+        _ => Some(format!("{} {}s", value, name)),
+    };
+    match (unit(minutes / 60, "hour"), unit(minutes % 60, "minute")) {
+        (Some(h), Some(m)) => format!("{} {}", h, m),
+        (Some(h), None) => h,
+        (None, Some(m)) => m,
+        (None, None) => "0 minutes".to_string(),
     }
 }
 
@@ -239,12 +448,19 @@ pub trait TimedAction {
     }
 }
 
+/// Time off, compensated by overtime
 #[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
 pub struct ZA {
     pub start: Time,
     pub end: Time,
 }
 
+impl ZA {
+    pub fn minutes(&self) -> u32 {
+        self.start.minutes_until(self.end)
+    }
+}
+
 #[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
 pub struct DayStart {
     pub location: Location,
@@ -256,18 +472,97 @@ pub struct DayEnd {
     pub ts: Time,
 }
 
+/// Absence during the day, e.g. a doctor's appointment
+#[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct Absence {
+    /// Name of one of the configured absence categories
+    #[serde(default = "default_absence_category")]
+    pub category: String,
+    pub start: Time,
+    pub end: Time,
+    /// Minutes counted towards the daily target instead of the duration, e.g. half of the
+    /// target for a half-day vacation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credited_minutes: Option<u32>,
+}
+
+impl Absence {
+    /// Category of absences stored as `Doctor` by older versions
+    pub const DOCTOR: &'static str = "doctor";
+
+    /// Minutes the absence reduces the required work time of the day
+    pub fn credit(&self) -> u32 {
+        self.credited_minutes
+            .unwrap_or_else(|| self.start.minutes_until(self.end))
+    }
+}
+
+fn default_absence_category() -> String {
+    Absence::DOCTOR.to_string()
+}
+
+impl From<Absence> for Action {
+    fn from(a: Absence) -> Self {
+        Action::Absence(a)
+    }
+}
+
+/// On-call period, not counted as work time
 #[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
-pub struct Doctor {
+pub struct Standby {
     pub start: Time,
     pub end: Time,
 }
 
+impl Standby {
+    /// Standby that ends before it starts continues after midnight on the following day
+    pub fn crosses_midnight(&self) -> bool {
+        self.end < self.start
+    }
+
+    /// Splits standby crossing midnight into the part until 24:00 and the continuation
+    /// starting at 00:00 of the following day.
+    pub fn split_at_midnight(self) -> (Standby, Option<Standby>) {
+        if self.crosses_midnight() {
+            let continuation = Standby {
+                start: Time::ZERO,
+                end: self.end,
+            };
+            (
+                Standby {
+                    end: Time::MAX,
+                    ..self
+                },
+                Some(continuation),
+            )
+        } else {
+            (self, None)
+        }
+    }
+
+    pub fn minutes(&self) -> u32 {
+        self.start.minutes_until(self.end)
+    }
+}
+
+impl From<Standby> for Action {
+    fn from(s: Standby) -> Self {
+        Action::Standby(s)
+    }
+}
+
 impl From<Work> for Action {
     fn from(w: Work) -> Self {
         Action::Work(w)
     }
 }
 
+impl From<WorkEvent> for Action {
+    fn from(e: WorkEvent) -> Self {
+        Action::WorkEvent(e)
+    }
+}
+
 impl From<WorkStart> for Action {
     fn from(s: WorkStart) -> Self {
         Action::WorkStart(s)
@@ -291,3 +586,67 @@ impl From<DayEnd> for Action {
         Action::DayEnd(e)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::data::test_support::{day_start, time, work};
+    use crate::data::{Absence, Action};
+    use crate::parsing::time_relative::TimeRelative;
+
+    #[test]
+    fn reads_old_doctor_entries() {
+        let old: Action =
+            serde_json::from_str(r#"{"Doctor":{"start":"09:00","end":"10:30"}}"#).unwrap();
+        let expected = Action::Absence(Absence {
+            category: Absence::DOCTOR.to_string(),
+            start: time("9"),
+            end: time("10:30"),
+            credited_minutes: None,
+        });
+        assert_eq!(old, expected);
+
+        let stored = serde_json::to_string(&expected).unwrap();
+        assert_eq!(serde_json::from_str::<Action>(&stored).unwrap(), expected);
+    }
+
+    #[test]
+    fn reads_as_sentence() {
+        assert_eq!(
+            work("9", "10:30", "A-1", "review")
+                .as_sentence()
+                .to_string(),
+            "Work on A-1 from 09:00 to 10:30, 1 hour 30 minutes: review."
+        );
+        assert_eq!(
+            day_start("o8").as_sentence().to_string(),
+            "Day started at 08:00 in Office."
+        );
+    }
+
+    #[test]
+    fn duration_of_ranges() {
+        assert_eq!(work("9", "10:30", "A-1", "a").duration_minutes(), Some(90));
+        assert_eq!(day_start("o8").duration_minutes(), None);
+    }
+
+    #[test]
+    fn shift_bounds() {
+        let quarter = TimeRelative::from_minutes_sat(15);
+        let back = TimeRelative::from_minutes_sat(-15);
+        let w = work("9", "10", "A-1", "a");
+        assert_eq!(
+            w.clone().shift_start(quarter),
+            Some(work("9:15", "10", "A-1", "a"))
+        );
+        assert_eq!(
+            w.clone().shift_end(back),
+            Some(work("9", "9:45", "A-1", "a"))
+        );
+        assert_eq!(work("9", "9:15", "A-1", "a").shift_start(quarter), None);
+        assert_eq!(day_start("o8").shift_end(back), Some(day_start("o7:45")));
+        assert_eq!(
+            w.shift_start(TimeRelative::from_minutes_sat(-10 * 60)),
+            None
+        );
+    }
+}
@@ -1,6 +1,10 @@
-use crate::data::{Action, Day, JiraIssue, Location, TimedAction, WorkStart};
+use crate::data::{
+    Action, Day, ExportFile, ExportRecord, ExportStatus, JiraIssue, Location, TimedAction,
+    UtcOffset, WebhookDelivery, Work, WorkEnd, WorkStart,
+};
 use crate::parsing::time::Time;
 use std::collections::BTreeSet;
+use std::path::PathBuf;
 
 pub struct ActiveDayBuilder {
     pub day: Day,
@@ -28,8 +32,25 @@ pub struct ActiveDay {
     main_location: Location,
     /// The jira issue that had a start event in previous days, but never ended
     active_issue: Option<JiraIssue>,
+    /// Time zone the day was started in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    zone: Option<UtcOffset>,
 
     actions: BTreeSet<Action>,
+    /// The last export of the day
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    export: Option<ExportRecord>,
+    /// Files the exports were written to, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    export_files: Vec<ExportFile>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    webhook_delivery: Option<WebhookDelivery>,
+    /// A closed day is not modified until it is unlocked again
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    closed: bool,
+    /// Free text about the day that is no booking, like an incident
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    notes: String,
 }
 
 impl ActiveDay {
@@ -38,7 +59,13 @@ impl ActiveDay {
             day,
             main_location,
             active_issue,
+            zone: None,
             actions: BTreeSet::new(),
+            export: None,
+            export_files: Vec::new(),
+            webhook_delivery: None,
+            closed: false,
+            notes: String::new(),
         }
     }
 
@@ -55,6 +82,107 @@ impl ActiveDay {
         &self.main_location
     }
 
+    /// Location of the first DayStart, the main location if the day was not started
+    pub fn location(&self) -> &Location {
+        self.actions
+            .iter()
+            .find_map(|a| match a {
+                Action::DayStart(s) => Some(&s.location),
+                _ => None,
+            })
+            .unwrap_or(&self.main_location)
+    }
+
+    pub fn has_day_start(&self) -> bool {
+        self.actions
+            .iter()
+            .any(|a| matches!(a, Action::DayStart(_)))
+    }
+
+    pub fn has_day_end(&self) -> bool {
+        self.actions.iter().any(|a| matches!(a, Action::DayEnd(_)))
+    }
+
+    pub fn zone(&self) -> Option<UtcOffset> {
+        self.zone
+    }
+
+    /// Records the time zone of the first DayStart
+    pub fn record_zone(&mut self, zone: UtcOffset) {
+        if self.zone.is_none() {
+            self.zone = Some(zone);
+        }
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    pub fn set_closed(&mut self, closed: bool) {
+        self.closed = closed;
+    }
+
+    pub fn notes(&self) -> &str {
+        &self.notes
+    }
+
+    pub fn set_notes(&mut self, notes: String) {
+        self.notes = notes;
+    }
+
+    pub fn last_export(&self) -> Option<&ExportRecord> {
+        self.export.as_ref()
+    }
+
+    pub fn export_status(&self) -> ExportStatus {
+        match &self.export {
+            None => ExportStatus::NeverExported,
+            Some(e) if self.actions.iter().eq(e.actions.iter()) => ExportStatus::Exported(e.at),
+            Some(e) => ExportStatus::ModifiedSinceExport(e.at),
+        }
+    }
+
+    /// The day with the actions of the last export
+    pub fn exported_day(&self) -> Option<ActiveDay> {
+        let export = self.export.as_ref()?;
+        Some(ActiveDay {
+            actions: export.actions.iter().cloned().collect(),
+            ..self.clone()
+        })
+    }
+
+    /// Records the export of the current actions
+    pub fn mark_exported(&mut self, at: chrono::NaiveDateTime, text: String) {
+        self.export = Some(ExportRecord {
+            at,
+            actions: self.actions.iter().cloned().collect(),
+            text,
+        });
+    }
+
+    /// Records the export of the current actions to a file
+    pub fn mark_exported_to_file(
+        &mut self,
+        at: chrono::NaiveDateTime,
+        text: String,
+        path: PathBuf,
+    ) {
+        self.mark_exported(at, text);
+        self.export_files.push(ExportFile { at, path });
+    }
+
+    pub fn export_files(&self) -> &[ExportFile] {
+        &self.export_files
+    }
+
+    pub fn set_webhook_delivery(&mut self, delivery: WebhookDelivery) {
+        self.webhook_delivery = Some(delivery);
+    }
+
+    pub fn webhook_delivery(&self) -> Option<&WebhookDelivery> {
+        self.webhook_delivery.as_ref()
+    }
+
     pub fn actions(&self) -> &BTreeSet<Action> {
         &self.actions
     }
@@ -96,10 +224,208 @@ impl ActiveDay {
         }
     }
 
+    /// The start of the work that runs at `now`, `None` if it was ended or the day is over.
+    ///
+    /// An end only stops work on its issue, so switching issues at the same time keeps
+    /// the new issue running
+    pub fn running_work(&self, now: Time) -> Option<&WorkStart> {
+        let mut running = None;
+        for action in self.actions.iter().take_while(|a| a.times().0 <= now) {
+            match action {
+                Action::WorkStart(start) => running = Some(start),
+                Action::WorkEnd(end)
+                    if running.map_or(false, |r| r.task.ident == end.task.ident) =>
+                {
+                    running = None
+                }
+                Action::DayEnd(_) => running = None,
+                _ => (),
+            }
+        }
+        running
+    }
+
+    /// Stored work on the same issue that contains `work`, likely booked twice by accident
+    pub fn duplicate_of(&self, work: &Work) -> Option<&Work> {
+        self.actions.iter().find_map(|a| match a {
+            Action::Work(w)
+                if w.task.ident == work.task.ident
+                    && !w.crosses_midnight()
+                    && w.start <= work.start
+                    && work.end <= w.end =>
+            {
+                Some(w)
+            }
+            _ => None,
+        })
+    }
+
+    /// The end of the work that `action` stops implicitly: a start of another issue or the day
+    /// end while work is running. `None` if the running work has a later end already
+    pub fn implicit_end(&self, action: &Action) -> Option<WorkEnd> {
+        let ts = match action {
+            Action::WorkStart(start) => start.ts,
+            Action::DayEnd(end) => end.ts,
+            _ => return None,
+        };
+        let running = self.running_work(ts)?;
+        if let Action::WorkStart(start) = action {
+            if start.task.ident == running.task.ident {
+                return None;
+            }
+        }
+        let ended_later = self.actions.iter().any(|a| {
+            matches!(a, Action::WorkEnd(end) if end.ts > ts && end.task.ident == running.task.ident)
+        });
+        if ended_later {
+            None
+        } else {
+            Some(WorkEnd {
+                ts,
+                task: running.task.clone(),
+            })
+        }
+    }
+
+    /// Adds the action, with `auto_close_work` also the end of the work it stops implicitly,
+    /// see [ActiveDay::implicit_end]
+    pub fn add_closing_action(&mut self, action: Action, auto_close_work: bool) {
+        if auto_close_work {
+            if let Some(end) = self.implicit_end(&action) {
+                self.add_action(Action::WorkEnd(end));
+            }
+        }
+        self.add_action(action);
+    }
+
+    /// Work on the issue booked last, from the end of the last action until `now`.
+    /// `None` while work is running
+    pub fn continue_last_issue(&self, now: Time) -> Option<Work> {
+        if self.running_work(now).is_some() {
+            return None;
+        }
+        let start = self.last_action_end(now).filter(|start| *start < now)?;
+        let last = self.actions.iter().rev().find(|a| {
+            matches!(a.action_end(), Some(end) if end <= now)
+                && a.issue().is_some()
+                && a.description().is_some()
+        })?;
+        Some(Work {
+            start,
+            end: now,
+            task: last.issue()?.clone(),
+            description: last.description()?.to_string(),
+            links: Vec::new(),
+        })
+    }
+
     pub fn last_action_end(&self, now: Time) -> Option<Time> {
         self.actions()
-                .iter()
-                .filter_map(|t| t.action_end().filter(|end| *end <= now))
-                .last()
+            .iter()
+            .filter_map(|t| t.action_end().filter(|end| *end <= now))
+            .last()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::data::test_support::*;
+    use crate::data::{Action, ActiveDayBuilder, Day, Location};
+
+    #[test]
+    fn finds_running_work() {
+        let day = ActiveDayBuilder {
+            day: Day::ymd(2022, 3, 7),
+            main_location: Location::Office,
+            active_issue: None,
+            actions: vec![
+                day_start("8"),
+                issue_start("9", "A-1", "review", "dev"),
+                issue_end("10", "A-1"),
+                issue_start("11", "B-2", "meeting", "dev"),
+                issue_start("12", "A-1", "review", "dev"),
+                issue_end("12", "B-2"),
+                day_end("17"),
+            ],
+        }
+        .build();
+
+        let running = |now| day.running_work(time(now)).map(|s| s.task.ident.as_str());
+        assert_eq!(running("8:30"), None);
+        assert_eq!(running("9"), Some("A-1"));
+        assert_eq!(running("10"), None);
+        assert_eq!(running("11"), Some("B-2"));
+        assert_eq!(running("12"), Some("A-1"));
+        assert_eq!(running("18"), None);
+    }
+
+    #[test]
+    fn continues_the_last_issue() {
+        let mut day = ActiveDayBuilder {
+            day: Day::ymd(2022, 3, 7),
+            main_location: Location::Office,
+            active_issue: None,
+            actions: vec![
+                day_start("8"),
+                work("8", "9", "B-2", "meeting"),
+                issue_start("9", "A-1", "review", "dev"),
+            ],
+        }
+        .build();
+        assert_eq!(day.continue_last_issue(time("10")), None);
+
+        day.add_action(issue_end("10", "A-1"));
+        let work = day.continue_last_issue(time("10:20")).unwrap();
+        assert_eq!((work.start, work.end), (time("10"), time("10:20")));
+        assert_eq!(work.task.ident, "A-1");
+        assert_eq!(work.description, "dev");
+        assert_eq!(day.continue_last_issue(time("10")), None);
+    }
+
+    #[test]
+    fn ends_running_work_implicitly() {
+        let mut day = ActiveDayBuilder {
+            day: Day::ymd(2022, 3, 7),
+            main_location: Location::Office,
+            active_issue: None,
+            actions: vec![day_start("8"), issue_start("9", "A-1", "", "dev")],
+        }
+        .build();
+
+        let end = day.implicit_end(&issue_start("10", "B-2", "", "meeting"));
+        assert_eq!(end.map(Action::WorkEnd), Some(issue_end("10", "A-1")));
+        assert_eq!(
+            day.implicit_end(&issue_start("10", "A-1", "", "test")),
+            None
+        );
+        assert_eq!(
+            day.implicit_end(&day_end("17")).map(Action::WorkEnd),
+            Some(issue_end("17", "A-1"))
+        );
+        assert_eq!(day.implicit_end(&issue_start("8:30", "B-2", "", "")), None);
+
+        day.add_action(issue_end("12", "A-1"));
+        assert_eq!(day.implicit_end(&issue_start("10", "B-2", "", "")), None);
+        assert_eq!(day.implicit_end(&day_end("17")), None);
+    }
+
+    #[test]
+    fn finds_duplicate_work() {
+        let day = ActiveDayBuilder {
+            day: Day::ymd(2022, 3, 7),
+            main_location: Location::Office,
+            active_issue: None,
+            actions: vec![day_start("8"), work("9", "11", "A-1", "review")],
+        }
+        .build();
+
+        let duplicate = |start, end, issue| day.duplicate_of(&workn(start, end, issue, "other"));
+        assert_eq!(
+            duplicate("9", "11", "A-1"),
+            Some(&workn("9", "11", "A-1", "review"))
+        );
+        assert!(duplicate("9:30", "10", "A-1").is_some());
+        assert!(duplicate("9", "11:15", "A-1").is_none());
+        assert!(duplicate("9", "11", "B-2").is_none());
     }
 }
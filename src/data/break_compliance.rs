@@ -0,0 +1,114 @@
+use std::cmp::max;
+use std::collections::BTreeSet;
+
+use crate::conf::BreakLimit;
+use crate::data::Action;
+use crate::parsing::time::Time;
+
+/// Continuous work since the last break that was long enough to count
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct BreakCompliance {
+    pub since_break_minutes: u32,
+    pub exceeded: bool,
+}
+
+impl BreakCompliance {
+    /// Issues that were started but not ended yet run until `now`, they are ignored without `now`
+    pub fn of(actions: &BTreeSet<Action>, limit: BreakLimit, now: Option<Time>) -> BreakCompliance {
+        let mut since_break = 0;
+        let mut last_end: Option<Time> = None;
+        for (start, end) in work_ranges(actions, now) {
+            let start = match last_end {
+                Some(prev) if prev.minutes_until(start) >= limit.min_break_minutes => {
+                    since_break = 0;
+                    start
+                }
+                // overlapping work is counted once
+                Some(prev) => max(prev, start),
+                None => start,
+            };
+            since_break += start.minutes_until(end);
+            last_end = Some(last_end.map_or(end, |prev| max(prev, end)));
+        }
+        // the break is still running
+        if let (Some(now), Some(last_end)) = (now, last_end) {
+            if last_end.minutes_until(now) >= limit.min_break_minutes {
+                since_break = 0;
+            }
+        }
+
+        BreakCompliance {
+            since_break_minutes: since_break,
+            exceeded: limit.max_work_minutes > 0 && since_break > limit.max_work_minutes,
+        }
+    }
+}
+
+/// Booked work and work between issue start and end, sorted by start
+pub(super) fn work_ranges(actions: &BTreeSet<Action>, now: Option<Time>) -> Vec<(Time, Time)> {
+    let mut result = Vec::new();
+    let mut running: Option<Time> = None;
+    for action in actions {
+        match action {
+            Action::Work(w) => result.push((w.start, w.end)),
+            Action::WorkStart(s) => {
+                if let Some(start) = running.replace(s.ts) {
+                    result.push((start, s.ts));
+                }
+            }
+            Action::WorkEnd(e) => {
+                if let Some(start) = running.take() {
+                    result.push((start, e.ts));
+                }
+            }
+            Action::DayEnd(e) => {
+                if let Some(start) = running.take() {
+                    result.push((start, e.ts));
+                }
+            }
+            _ => (),
+        }
+    }
+    if let (Some(start), Some(now)) = (running, now) {
+        if now > start {
+            result.push((start, now));
+        }
+    }
+    result.sort();
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeSet;
+
+    use crate::conf::BreakLimit;
+    use crate::data::test_support::{issue_start, time, work};
+    use crate::data::BreakCompliance;
+
+    #[test]
+    fn counts_work_since_the_last_break() {
+        let limit = BreakLimit::default();
+        let mut actions: BTreeSet<_> = [
+            work("7", "9", "A-1", "a"),
+            work("9:10", "11", "A-2", "b"),
+            work("11:30", "14", "A-1", "c"),
+            work("14:15", "16", "A-1", "c"),
+        ]
+        .into_iter()
+        .collect();
+
+        let compliance = BreakCompliance::of(&actions, limit, None);
+        assert_eq!(compliance.since_break_minutes, 150 + 105);
+        assert!(!compliance.exceeded);
+        assert_eq!(
+            BreakCompliance::of(&actions, limit, Some(time("16:30"))).since_break_minutes,
+            0
+        );
+
+        actions.insert(issue_start("16:10", "A-3", "", "d"));
+        let compliance = BreakCompliance::of(&actions, limit, Some(time("18")));
+        assert_eq!(compliance.since_break_minutes, 255 + 110);
+        assert!(compliance.exceeded);
+    }
+}
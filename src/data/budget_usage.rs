@@ -0,0 +1,70 @@
+use std::cmp::max;
+use std::collections::BTreeSet;
+
+use crate::conf::DailyBudget;
+use crate::data::break_compliance::work_ranges;
+use crate::data::Action;
+use crate::parsing::time::Time;
+
+/// Booked and running work of a day against the daily budget
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct BudgetUsage {
+    pub booked_minutes: u32,
+    pub exceeded: bool,
+}
+
+impl BudgetUsage {
+    /// Issues that were started but not ended yet run until `now`, overlapping work is counted
+    /// once
+    pub fn of(actions: &BTreeSet<Action>, budget: DailyBudget, now: Option<Time>) -> BudgetUsage {
+        let mut booked = 0;
+        let mut last_end: Option<Time> = None;
+        for (start, end) in work_ranges(actions, now) {
+            let start = last_end.map_or(start, |prev| max(prev, start));
+            booked += start.minutes_until(end);
+            last_end = Some(last_end.map_or(end, |prev| max(prev, end)));
+        }
+
+        BudgetUsage {
+            booked_minutes: booked,
+            exceeded: budget.minutes > 0 && booked > budget.minutes,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeSet;
+
+    use crate::conf::DailyBudget;
+    use crate::data::test_support::{issue_start, time, work};
+    use crate::data::BudgetUsage;
+
+    #[test]
+    fn counts_booked_and_running_work() {
+        let budget = DailyBudget {
+            minutes: 8 * 60,
+            notify: false,
+        };
+        let mut actions: BTreeSet<_> = [
+            work("8", "12", "A-1", "a"),
+            work("11", "13", "A-2", "overlapping"),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(
+            BudgetUsage::of(&actions, budget, None),
+            BudgetUsage {
+                booked_minutes: 5 * 60,
+                exceeded: false
+            }
+        );
+
+        actions.insert(issue_start("14", "A-3", "", "running"));
+        assert!(!BudgetUsage::of(&actions, budget, Some(time("17"))).exceeded);
+        let usage = BudgetUsage::of(&actions, budget, Some(time("17:30")));
+        assert_eq!(usage.booked_minutes, 8 * 60 + 30);
+        assert!(usage.exceeded);
+        assert!(!BudgetUsage::of(&actions, DailyBudget::default(), Some(time("23"))).exceeded);
+    }
+}
@@ -0,0 +1,72 @@
+use crate::data::{fuzzy_match, ActiveDay};
+
+/// Comments previously booked on an issue, most recent first and without duplicates
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CommentHistory {
+    pub ident: String,
+    pub comments: Vec<String>,
+}
+
+impl CommentHistory {
+    /// Collects the comments of `ident`. `recent` is the comment stored with the recent issue,
+    /// `days` have to be ordered newest first.
+    pub fn collect<'a>(
+        ident: &str,
+        recent: Option<&str>,
+        days: impl IntoIterator<Item = &'a ActiveDay>,
+    ) -> CommentHistory {
+        let mut history = CommentHistory {
+            ident: ident.to_string(),
+            comments: Vec::new(),
+        };
+        history.add(recent);
+        for day in days {
+            for action in day.actions().iter().rev() {
+                if action.issue_id() == Some(ident) {
+                    history.add(action.description());
+                }
+            }
+        }
+        history
+    }
+
+    fn add(&mut self, comment: Option<&str>) {
+        match comment.map(str::trim) {
+            Some(c) if !c.is_empty() && !self.comments.iter().any(|e| e == c) => {
+                self.comments.push(c.to_string())
+            }
+            _ => (),
+        }
+    }
+
+    /// Comments that fuzzy match `typed`, all comments if `typed` is empty
+    pub fn matching(&self, typed: &str) -> Vec<&str> {
+        self.comments
+            .iter()
+            .map(String::as_str)
+            .filter(|c| fuzzy_match(c, typed.trim()).is_some())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::data::comment_history::CommentHistory;
+    use crate::data::test_support::work;
+    use crate::data::{ActiveDay, Day, Location};
+
+    #[test]
+    fn collects_comments_newest_first() {
+        let mut older = ActiveDay::new(Day::ymd(2022, 1, 19), Location::Office, None);
+        older.add_action(work("8", "9", "A-1", "review"));
+        older.add_action(work("9", "10", "A-1", "planning"));
+        let mut newer = ActiveDay::new(Day::ymd(2022, 1, 20), Location::Office, None);
+        newer.add_action(work("8", "9", "A-1", "review"));
+        newer.add_action(work("9", "10", "B-1", "other"));
+
+        let history = CommentHistory::collect("A-1", Some("daily"), [&newer, &older]);
+        assert_eq!(history.comments, vec!["daily", "review", "planning"]);
+        assert_eq!(history.matching("rv"), vec!["review"]);
+        assert_eq!(history.matching(" ").len(), 3);
+    }
+}
@@ -1,12 +1,12 @@
+use crate::parsing::locale::{self, DateFormat};
 use crate::parsing::parse_result::ParseResult;
-use crate::util::Timeline;
+use crate::util::{DefaultTimeline, Timeline, TimelineProvider};
 use chrono::{Datelike, Duration, Weekday};
 use regex::Regex;
 use serde::{Deserializer, Serializer};
 use std::fmt::{Display, Formatter};
 use std::ops::{Add, Sub};
 use std::str::FromStr;
-use std::time::{SystemTime, UNIX_EPOCH};
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Day {
@@ -14,14 +14,11 @@ pub struct Day {
 }
 
 impl Day {
+    /// The current day in the system time zone.
+    ///
+    /// Prefer [crate::Settings::today], which respects the configured home time zone
     pub fn today() -> Day {
-        let secs = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs() as i64;
-        let date = chrono::NaiveDateTime::from_timestamp(secs, 0).date();
-
-        Day { date }
+        DefaultTimeline.today()
     }
 
     pub fn next_work_day(&self) -> Day {
@@ -50,8 +47,30 @@ impl Day {
         }
     }
 
+    /// ISO dates like `2022-03-07` and dotted dates like `07.03.2022`
     pub fn parse(input: &str) -> Result<Day, String> {
-        parse_day(input)
+        match DOTTED_DAY.captures(input) {
+            Some(c) if c.name("year").is_some() => {
+                let number = |name| u32::from_str(&c[name]).unwrap();
+                chrono::NaiveDate::from_ymd_opt(
+                    number("year") as i32,
+                    number("month"),
+                    number("day"),
+                )
+                .map(Day::from)
+                .ok_or_else(|| format!("Invalid date: {}", input))
+            }
+            _ => parse_day(input),
+        }
+    }
+
+    /// The day in the given format
+    pub fn format(self, format: DateFormat) -> String {
+        let (y, m, d) = (self.date.year(), self.date.month(), self.date.day());
+        match format {
+            DateFormat::Iso => format!("{:04}-{:02}-{:02}", y, m, d),
+            DateFormat::Dotted => format!("{:02}.{:02}.{:04}", d, m, y),
+        }
     }
 
     pub fn iter<F: DayForwarder>(self, forwarder: F) -> DayIter<F> {
@@ -65,7 +84,37 @@ impl Day {
         self.date.weekday()
     }
 
+    /// ISO week and weekday, e.g. `W03 Thu`
+    pub fn week_label(self) -> String {
+        format!("W{:02} {}", self.date.iso_week().week(), self.day_of_week())
+    }
+
+    /// The monday of the week
+    pub fn first_of_week(self) -> Day {
+        self - self.day_of_week().num_days_from_monday() as i64
+    }
+
+    pub fn first_of_month(self) -> Day {
+        Day::ymd(self.date.year(), self.date.month(), 1)
+    }
+
+    pub fn last_of_month(self) -> Day {
+        let (year, month) = if self.date.month() == 12 {
+            (self.date.year() + 1, 1)
+        } else {
+            (self.date.year(), self.date.month() + 1)
+        };
+        Day::ymd(year, month, 1).prev_day()
+    }
+
+    /// Parses a day relative to today.
+    ///
+    /// Accepts `+N`/`-N` days, ISO dates, `today`, `yesterday`, `tomorrow`, weekdays of the
+    /// current week (`mon`, `friday`), `last <weekday>` and `next <weekday>` for the weeks
+    /// before and after, `d.m.` or `d.m.yyyy` and ISO weeks of the current year like `w23.2`
+    /// (tuesday of week 23) or `w23` (its monday). Keywords do not depend on the locale.
     pub fn parse_day_relative(timeline: &Timeline, input: &str) -> ParseResult<Day, ()> {
+        let input = input.trim();
         if let Some(c) = RELATIVE_DAY.captures(input) {
             let sign = c.name("sign").unwrap().as_str() == "+";
             let days = i32::from_str(c.name("days").unwrap().as_str()).unwrap();
@@ -80,6 +129,27 @@ impl Day {
                 }
             }
             ParseResult::Valid(value)
+        } else if let Some(day) = parse_day_keyword(timeline.today(), input) {
+            ParseResult::Valid(day)
+        } else if let Some(c) = WEEK_DAY.captures(input) {
+            let number = |name| c.name(name).map(|m| u32::from_str(m.as_str()).unwrap());
+            let weekday = number("weekday").unwrap_or(1);
+            let year = timeline.today().date.iso_week().year();
+            match chrono::NaiveDate::from_isoywd_opt(year, number("week").unwrap(), Weekday::Mon) {
+                Some(monday) if (1..=7).contains(&weekday) => {
+                    ParseResult::Valid(Day::from(monday) + (weekday - 1) as i64)
+                }
+                _ => ParseResult::Invalid(()),
+            }
+        } else if let Some(c) = DOTTED_DAY.captures(input) {
+            let number = |name| c.name(name).map(|m| u32::from_str(m.as_str()).unwrap());
+            let year = number("year")
+                .map(|y| y as i32)
+                .unwrap_or_else(|| timeline.today().date.year());
+            chrono::NaiveDate::from_ymd_opt(year, number("month").unwrap(), number("day").unwrap())
+                .map(Day::from)
+                .ok_or(())
+                .into()
         } else {
             parse_day(input).map_err(|_| ()).into()
         }
@@ -108,6 +178,26 @@ impl Day {
     }
 }
 
+/// `today`, `yesterday`, `tomorrow` and weekdays, optionally of the `last` or `next` week
+fn parse_day_keyword(today: Day, input: &str) -> Option<Day> {
+    let input = input.to_ascii_lowercase();
+    match input.as_str() {
+        "today" => return Some(today),
+        "yesterday" => return Some(today.prev_day()),
+        "tomorrow" => return Some(today.next_day()),
+        _ => (),
+    }
+
+    let (week_offset, weekday) = match input.split_once(char::is_whitespace) {
+        Some(("last", weekday)) => (-7, weekday.trim_start()),
+        Some(("next", weekday)) => (7, weekday.trim_start()),
+        Some(_) => return None,
+        None => (0, input.as_str()),
+    };
+    let weekday = Weekday::from_str(weekday).ok()?;
+    Some(today.first_of_week() + weekday.num_days_from_monday() as i64 + week_offset)
+}
+
 fn parse_day(input: &str) -> Result<Day, String> {
     if let Some((year, month_day)) = input.split_once('-') {
         if let Some((month, day)) = month_day.split_once('-') {
@@ -131,6 +221,10 @@ fn parse_day(input: &str) -> Result<Day, String> {
 
 lazy_static::lazy_static! {
     static ref RELATIVE_DAY: Regex = Regex::new(r"^(?P<sign>\+|-)(?P<days>[0-9]{1,2})\b").unwrap();
+    static ref WEEK_DAY: Regex =
+        Regex::new(r"^[wW](?P<week>[0-9]{1,2})(\.(?P<weekday>[0-9]))?$").unwrap();
+    static ref DOTTED_DAY: Regex =
+        Regex::new(r"^(?P<day>[0-9]{1,2})\.(?P<month>[0-9]{1,2})\.(?P<year>[0-9]{4})?$").unwrap();
 }
 
 impl Default for Day {
@@ -141,13 +235,7 @@ impl Default for Day {
 
 impl Display for Day {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{:04}-{:02}-{:02}",
-            self.date.year(),
-            self.date.month(),
-            self.date.day()
-        )
+        f.write_str(&self.format(locale::date_format()))
     }
 }
 
@@ -168,7 +256,7 @@ impl serde::Serialize for Day {
     where
         S: Serializer,
     {
-        serializer.serialize_str(&self.to_string())
+        serializer.serialize_str(&self.format(DateFormat::Iso))
     }
 }
 
@@ -258,6 +346,18 @@ impl DayForwarder for WeekDayForwarder {
     }
 }
 
+/// Workdays that are not holidays
+#[derive(Clone, Debug)]
+pub struct HolidayForwarder {
+    pub holidays: Vec<Day>,
+}
+
+impl DayForwarder for HolidayForwarder {
+    fn is_valid(&self, day: Day) -> bool {
+        WeekDayForwarder.is_valid(day) && !self.holidays.contains(&day)
+    }
+}
+
 pub struct DayIter<Forwarder> {
     day: Day,
     forwarder: Forwarder,
@@ -275,7 +375,9 @@ impl<F: DayForwarder> Iterator for DayIter<F> {
 mod test {
     use crate::data::day::Day;
     use crate::data::WeekDayForwarder;
-    use crate::util::{DefaultTimeline, TimelineProvider};
+    use crate::parsing::locale::DateFormat;
+    use crate::parsing::parse_result::ParseResult;
+    use crate::util::{DefaultTimeline, StaticTimeline, Timeline, TimelineProvider};
 
     #[test]
     fn day_serde_json() {
@@ -286,6 +388,16 @@ mod test {
         assert_eq!(from_str, day);
     }
 
+    #[test]
+    fn dotted_format() {
+        let day = Day::ymd(2022, 3, 7);
+        assert_eq!(day.format(DateFormat::Dotted), "07.03.2022");
+        assert_eq!(day.format(DateFormat::Iso), "2022-03-07");
+        assert_eq!(Day::parse("07.03.2022"), Ok(day));
+        assert_eq!(Day::parse("2022-03-07"), Ok(day));
+        assert!(Day::parse("31.02.2022").is_err());
+    }
+
     #[test]
     fn next_work_day() {
         let start_friday = Day::ymd(2021, 11, 26);
@@ -334,4 +446,41 @@ mod test {
         eprintln!("{}", prev.day_of_week());
         assert_eq!(prev, Day::ymd(2022, 1, 7));
     }
+
+    #[test]
+    fn parse_day_relative() {
+        // a thursday
+        let timeline: Timeline = StaticTimeline::parse("2022-03-10 12:00").into();
+        let p = |input| Day::parse_day_relative(&timeline, input);
+        let valid = |m, d| ParseResult::Valid(Day::ymd(2022, m, d));
+
+        assert_eq!(p("+1"), valid(3, 11));
+        assert_eq!(p("-3"), valid(3, 7));
+        assert_eq!(p("2022-02-01"), valid(2, 1));
+        assert_eq!(p("today"), valid(3, 10));
+        assert_eq!(p(" Yesterday "), valid(3, 9));
+        assert_eq!(p("tomorrow"), valid(3, 11));
+        assert_eq!(p("mon"), valid(3, 7));
+        assert_eq!(p("Friday"), valid(3, 11));
+        assert_eq!(p("last fri"), valid(3, 4));
+        assert_eq!(p("next  mon"), valid(3, 14));
+        assert_eq!(p("1.3."), valid(3, 1));
+        assert_eq!(p("31.12.2021"), ParseResult::Valid(Day::ymd(2021, 12, 31)));
+        assert_eq!(p("30.2."), ParseResult::Invalid(()));
+        assert_eq!(p("last"), ParseResult::Invalid(()));
+        assert_eq!(p("some fri"), ParseResult::Invalid(()));
+    }
+
+    #[test]
+    fn parse_iso_week() {
+        let timeline: Timeline = StaticTimeline::parse("2022-03-10 12:00").into();
+        let p = |input| Day::parse_day_relative(&timeline, input);
+
+        assert_eq!(p("w23.2"), ParseResult::Valid(Day::ymd(2022, 6, 7)));
+        assert_eq!(p("W1"), ParseResult::Valid(Day::ymd(2022, 1, 3)));
+        assert_eq!(p("w52.7"), ParseResult::Valid(Day::ymd(2023, 1, 1)));
+        assert_eq!(p("w23.8"), ParseResult::Invalid(()));
+        assert_eq!(p("w54"), ParseResult::Invalid(()));
+        assert_eq!(Day::ymd(2022, 6, 7).week_label(), "W23 Tue");
+    }
 }
@@ -1,14 +1,16 @@
 use crate::conf::BreaksConfig;
 use crate::data::day_normalizer::we::We;
+use crate::data::rounding_report::RoundedEntry;
 use crate::data::work_day::WorkDay;
 use crate::data::{
-    Action, ActiveDay, Day, DayEnd, DayStart, JiraIssue, TimedAction, Work, WorkStart,
+    Absence, Action, ActiveDay, Day, DayEnd, DayForwarder, DayStart, JiraIssue, Location, Standby,
+    TimedAction, UtcOffset, WeekDayForwarder, Work, WorkStart, ZA,
 };
 use crate::parsing::round_mode::RoundMode;
 use crate::parsing::time::Time;
 use crate::parsing::time_limit::{InvalidTime, TimeRange, TimeResult};
 use crate::parsing::time_relative::TimeRelative;
-use std::collections::BTreeSet;
+use std::collections::{BTreeSet, HashMap};
 use std::num::NonZeroU32;
 
 #[cfg(test)]
@@ -17,9 +19,22 @@ mod we;
 
 pub struct NormalizedDay {
     pub date: Day,
+    pub location: Location,
     pub entries: Vec<Work>,
+    /// On-call periods, kept separate from the work entries
+    pub standby: Vec<Standby>,
+    /// Absences like doctor's appointments, exported with the code of their category
+    pub absences: Vec<Absence>,
+    /// Time off taken from the compensation balance
+    pub za: Vec<ZA>,
     pub orig_breaks: BreaksInfo,
     pub final_breaks: BreaksInfo,
+    /// Notes of the day, exported by templates only
+    pub notes: String,
+    /// The bookings before and after rounding, before they were combined
+    pub rounding: Vec<RoundedEntry>,
+    /// Weekend or holiday, all work is classified as weekend work
+    pub day_off: bool,
 }
 
 impl From<&NormalizedDay> for WorkDay {
@@ -38,11 +53,37 @@ pub struct Normalizer {
     pub breaks_config: BreaksConfig,
     pub combine_bookings: bool,
     pub add_break: bool,
+    /// Times of days started in a different time zone are shifted into this zone
+    pub home_zone: Option<UtcOffset>,
+    /// Days off besides weekends
+    pub holidays: Vec<Day>,
 }
 
 impl Normalizer {
     pub fn create_normalized(&self, current_day: &ActiveDay) -> Result<NormalizedDay, String> {
-        let mut actions = current_day.actions().clone();
+        // bookings may have second precision, normalized entries never have
+        let mut actions = current_day
+            .actions()
+            .iter()
+            .cloned()
+            .map(|a| a.map_times(Time::round_seconds))
+            .collect();
+        if let (Some(home), Some(zone)) = (self.home_zone, current_day.zone()) {
+            actions = shift_to_zone(actions, zone, home)?;
+        }
+        let links = work_links(&actions);
+        let standby = take_actions(&mut actions, |a| match a {
+            Action::Standby(s) => Some(s.clone()),
+            _ => None,
+        });
+        let absences = take_actions(&mut actions, |a| match a {
+            Action::Absence(a) => Some(a.clone()),
+            _ => None,
+        });
+        let za = take_actions(&mut actions, |a| match a {
+            Action::ZA(z) => Some(z.clone()),
+            _ => None,
+        });
         let mut active_issue = current_day.active_issue().cloned();
 
         let mut splits = day_splits(&mut actions, &mut active_issue)?;
@@ -52,36 +93,137 @@ impl Normalizer {
 
         let orig_breaks = calc_breaks(&splits);
 
+        let mut rounding = Vec::new();
         for range in &mut splits {
+            let original = range.work.clone();
             round_bookings(range, self.resolution)?;
+            rounding.extend(
+                original
+                    .into_iter()
+                    .zip(&range.work)
+                    .map(|(o, r)| RoundedEntry {
+                        original: o.range(),
+                        rounded: r.range(),
+                        ident: o.id,
+                        description: o.description,
+                        implicit: o.implicit,
+                    }),
+            );
             if self.combine_bookings {
                 combine_bookings(&mut range.work);
             }
         }
 
+        check_credited_absences(&absences, &rounding)?;
+
         let mut entries = flatten_ranges(splits);
 
         // when there are only automatic bookings around noon, may punch a hole
         // to add an automatic breaks
+        let breaks_config = self.breaks_config.for_day(current_day.get_day());
         if orig_breaks.break_time == TimeRelative::ZERO
-            && self.breaks_config.min_breaks_minutes > 0
-            && orig_breaks.work_time.offset_minutes()
-                >= self.breaks_config.min_work_time_minutes as i32
+            && breaks_config.min_breaks_minutes > 0
+            && orig_breaks.work_time.offset_minutes() >= breaks_config.min_work_time_minutes as i32
         {
-            try_insert_break(&self.breaks_config, &mut entries);
+            try_insert_break(&breaks_config, &mut entries);
         }
 
         let final_breaks = calc_breaks(&entries);
 
+        let date = current_day.get_day();
         Ok(NormalizedDay {
-            date: current_day.get_day(),
-            entries: entries.into_iter().map(Work::from).collect(),
+            date,
+            location: current_day.location().clone(),
+            entries: entries
+                .into_iter()
+                .map(|e| with_links(Work::from(e), &links))
+                .collect(),
+            standby,
+            absences,
+            za,
             orig_breaks,
             final_breaks,
+            notes: current_day.notes().to_string(),
+            rounding,
+            day_off: !WeekDayForwarder.is_valid(date) || self.holidays.contains(&date),
         })
     }
 }
 
+/// Removes and returns the actions selected by `f`.
+///
+/// Standby, absences and ZA are no work time, they must neither fill gaps nor extend the day
+fn take_actions<T>(actions: &mut BTreeSet<Action>, f: impl Fn(&Action) -> Option<T>) -> Vec<T> {
+    let taken = actions.iter().filter_map(&f).collect();
+    actions.retain(|a| f(a).is_none());
+    taken
+}
+
+/// A credited absence replaces its duration in the work time of the day,
+/// work booked during it would be counted twice
+fn check_credited_absences(absences: &[Absence], work: &[RoundedEntry]) -> Result<(), String> {
+    for absence in absences.iter().filter(|a| a.credited_minutes.is_some()) {
+        let overlapping = work
+            .iter()
+            .find(|e| absence.start < e.original.max() && e.original.min() < absence.end);
+        if let Some(entry) = overlapping {
+            return Err(format!(
+                "Work on {} overlaps the credited {} from {} to {}",
+                entry.ident, absence.category, absence.start, absence.end
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Issue, time range and links of the work booked with links
+fn work_links(actions: &BTreeSet<Action>) -> Vec<(String, TimeRange, Vec<String>)> {
+    actions
+        .iter()
+        .filter_map(|a| match a {
+            Action::Work(w) if !w.links.is_empty() => Some((
+                w.task.ident.clone(),
+                TimeRange::new(w.start, w.end),
+                w.links.clone(),
+            )),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Normalized entries lose their links, they get the links of the overlapping bookings on
+/// their issue back
+fn with_links(mut work: Work, links: &[(String, TimeRange, Vec<String>)]) -> Work {
+    for (ident, range, urls) in links {
+        if *ident == work.task.ident && work.start < range.max() && range.min() < work.end {
+            for url in urls {
+                if !work.links.contains(url) {
+                    work.links.push(url.clone());
+                }
+            }
+        }
+    }
+    work
+}
+
+fn shift_to_zone(
+    actions: BTreeSet<Action>,
+    from: UtcOffset,
+    to: UtcOffset,
+) -> Result<BTreeSet<Action>, String> {
+    if from == to {
+        return Ok(actions);
+    }
+    let offset = from.offset_to(to);
+    actions
+        .into_iter()
+        .map(|a| {
+            a.try_map_times(|t| t.try_add_relative(offset))
+                .ok_or_else(|| format!("Booking moved out of day by time zone {}", from))
+        })
+        .collect()
+}
+
 fn flatten_ranges(ranges: Vec<FilledRange>) -> Vec<We> {
     ranges.into_iter().flat_map(|r| r.work).collect()
 }
@@ -102,6 +244,8 @@ fn start_end_spans(actions: &BTreeSet<Action>) -> Result<Vec<TimeRange>, String>
             Action::DayEnd(DayEnd { ts }) => {
                 if let Some(start) = std::mem::take(&mut current_start) {
                     result.push(TimeRange::new(start, *ts));
+                } else if result.is_empty() && continues_from_previous_day(actions) {
+                    result.push(TimeRange::new(Time::ZERO, *ts));
                 } else {
                     return Err(format!("Unmatched DayEnd: at {}", ts));
                 }
@@ -111,12 +255,30 @@ fn start_end_spans(actions: &BTreeSet<Action>) -> Result<Vec<TimeRange>, String>
     }
 
     if let Some(start) = current_start {
-        return Err(format!("Missing DayEnd: started at {}", start));
+        if continues_into_next_day(actions) {
+            result.push(TimeRange::new(start, Time::MAX));
+        } else {
+            return Err(format!("Missing DayEnd: started at {}", start));
+        }
     }
 
     Ok(result)
 }
 
+/// Work booked from 00:00 is the continuation of work started on the previous day
+fn continues_from_previous_day(actions: &BTreeSet<Action>) -> bool {
+    actions
+        .iter()
+        .any(|a| matches!(a, Action::Work(Work { start, .. }) if *start == Time::ZERO))
+}
+
+/// Work booked until 24:00 is continued on the following day
+fn continues_into_next_day(actions: &BTreeSet<Action>) -> bool {
+    actions
+        .iter()
+        .any(|a| matches!(a, Action::Work(Work { end, .. }) if *end == Time::MAX))
+}
+
 #[derive(Debug, Eq, PartialEq)]
 struct FilledRange {
     range: TimeRange,
@@ -157,36 +319,80 @@ fn unbooked_times(mut range: TimeRange, work: &[We]) -> Vec<TimeRange> {
     result
 }
 
-fn fill_gap(
-    range: TimeRange,
-    active_issue: &Option<JiraIssue>,
-    work: &mut Vec<We>,
-) -> Vec<TimeRange> {
-    let unbooked = unbooked_times(range, work);
-    if unbooked.is_empty() || active_issue.is_none() {
-        return unbooked;
-    };
+/// The work of a range, sorted by time, equal entries in the order they were booked.
+///
+/// Everything from `start` to `filled` is booked, so filling the next gap only needs to look
+/// at the entries after `open`. Keeps days with hundreds of issue starts linear
+struct Booking {
+    start: Time,
+    filled: Time,
+    /// Index of the first entry that does not start before `filled`
+    open: usize,
+    /// The latest end of the entries before `open`
+    booked_until: Time,
+    work: Vec<We>,
+}
 
-    let active_issue = active_issue.clone().unwrap();
-
-    for r in unbooked {
-        let description = active_issue
-            .default_action
-            .as_deref()
-            .unwrap_or("work")
-            .to_string();
-        work.push(We {
-            start: r.min(),
-            end: r.max(),
-            id: active_issue.ident.clone(),
-            description,
-            implicit: true,
-        });
+impl Booking {
+    fn new(start: Time) -> Self {
+        Booking {
+            start,
+            filled: start,
+            open: 0,
+            booked_until: start,
+            work: Vec::new(),
+        }
     }
 
-    work.sort();
+    fn insert(&mut self, entry: We) {
+        let index = self.work.partition_point(|w| w <= &entry);
+        if index < self.open {
+            // work that started before the range, rescan on the next fill
+            self.open = 0;
+            self.booked_until = self.start;
+        }
+        self.work.insert(index, entry);
+    }
+
+    /// Books the unbooked times of `range` on the active issue.
+    ///
+    /// Returns the unbooked times if there is no active issue
+    fn fill_gaps(&mut self, range: TimeRange, active_issue: &Option<JiraIssue>) -> Vec<TimeRange> {
+        while let Some(w) = self.work.get(self.open).filter(|w| w.start < self.filled) {
+            self.booked_until = self.booked_until.max(w.end);
+            self.open += 1;
+        }
 
-    Vec::new()
+        let unbooked = if range.min() >= self.start && range.min() <= self.filled {
+            let rest = range.with_min(self.filled.max(self.booked_until));
+            unbooked_times(rest, &self.work[self.open..])
+        } else {
+            unbooked_times(range, &self.work)
+        };
+
+        let mut result = Vec::new();
+        match active_issue {
+            Some(active_issue) => {
+                let description = active_issue.default_action.as_deref().unwrap_or("work");
+                for r in unbooked {
+                    self.insert(We {
+                        start: r.min(),
+                        end: r.max(),
+                        id: active_issue.ident.clone(),
+                        description: description.to_string(),
+                        implicit: true,
+                    });
+                }
+            }
+            None => result = unbooked,
+        }
+
+        // unbooked times are reported once, a later active issue must not fill them
+        if range.min() <= self.filled {
+            self.filled = self.filled.max(range.max());
+        }
+        result
+    }
 }
 
 fn fail_unbooked(unbooked: Vec<TimeRange>) -> Result<(), String> {
@@ -206,32 +412,29 @@ fn fill_range(
     range: TimeRange,
     active_issue: &mut Option<JiraIssue>,
     actions: &mut BTreeSet<Action>,
-) -> Result<FilledRange, String> {
-    let overlapping: Vec<_> = actions
-        .iter()
-        .filter(|action| within_range(range, action))
-        .cloned()
-        .collect();
+    unbooked: &mut Vec<TimeRange>,
+) -> FilledRange {
+    let (overlapping, others): (BTreeSet<_>, _) = std::mem::take(actions)
+        .into_iter()
+        .partition(|action| within_range(range, action));
+    *actions = others;
 
     let mut remaining_range = range;
-    let mut work = Vec::new();
+    let mut booking = Booking::new(range.min());
 
     for action in overlapping {
-        actions.remove(&action);
         match action {
             Action::WorkStart(s) => {
-                let unbooked = fill_gap(range.with_max(s.ts), active_issue, &mut work);
-                fail_unbooked(unbooked)?;
+                unbooked.append(&mut booking.fill_gaps(range.with_max(s.ts), active_issue));
                 remaining_range = range.with_min(s.ts);
                 *active_issue = Some(JiraIssue {
-                    ident: s.task.ident.clone(),
-                    description: s.task.description.clone(),
-                    default_action: Some(s.description.clone()),
+                    ident: s.task.ident,
+                    description: s.task.description,
+                    default_action: Some(s.description),
                 });
             }
             Action::WorkEnd(e) => {
-                let unbooked = fill_gap(range.with_max(e.ts), active_issue, &mut work);
-                fail_unbooked(unbooked)?;
+                unbooked.append(&mut booking.fill_gaps(range.with_max(e.ts), active_issue));
                 remaining_range = range.with_min(e.ts);
                 if e.task.ident
                     == active_issue
@@ -243,7 +446,7 @@ fn fill_range(
                 }
             }
             Action::Work(w) => {
-                work.push(We {
+                booking.insert(We {
                     id: w.task.ident,
                     description: w.description,
                     start: w.start,
@@ -255,43 +458,137 @@ fn fill_range(
         }
     }
 
-    let unbooked = fill_gap(remaining_range, active_issue, &mut work);
-    fail_unbooked(unbooked)?;
+    unbooked.append(&mut booking.fill_gaps(remaining_range, active_issue));
 
+    let work = booking.work;
     if !work.is_empty() {
-        Ok(FilledRange {
+        FilledRange {
             range: TimeRange::new(work.first().unwrap().start, work.last().unwrap().end),
             work,
-        })
+        }
     } else {
-        Ok(FilledRange { range, work })
+        FilledRange { range, work }
     }
 }
 
 fn day_splits(
     entries: &mut BTreeSet<Action>,
     active_issue: &mut Option<JiraIssue>,
+) -> Result<Vec<FilledRange>, String> {
+    let mut unbooked = Vec::new();
+    let parts = collect_splits(entries, active_issue, &mut unbooked)?;
+    fail_unbooked(unbooked)?;
+    Ok(parts)
+}
+
+fn collect_splits(
+    entries: &mut BTreeSet<Action>,
+    active_issue: &mut Option<JiraIssue>,
+    unbooked: &mut Vec<TimeRange>,
 ) -> Result<Vec<FilledRange>, String> {
     let time_spans = start_end_spans(entries);
 
     let mut parts = Vec::new();
 
     for range in time_spans? {
-        let range = fill_range(range, active_issue, entries)?;
+        let range = fill_range(range, active_issue, entries, unbooked);
         parts.push(range);
     }
 
     Ok(parts)
 }
 
+/// Times between day start and day end that are neither booked nor covered by a started issue.
+///
+/// [Normalizer::create_normalized] fails for days with unbooked times. The times are those of
+/// the day, without rounding or time zone shifts
+pub fn unbooked_gaps(day: &ActiveDay) -> Result<Vec<TimeRange>, String> {
+    let mut actions = gap_actions(day);
+    let mut active_issue = day.active_issue().cloned();
+    let mut unbooked = Vec::new();
+    collect_splits(&mut actions, &mut active_issue, &mut unbooked)?;
+    Ok(unbooked)
+}
+
+/// Time of the day without booked work
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ImplicitGap {
+    pub range: TimeRange,
+    /// Issue and comment of the started issue the normalizer books, `None` if unbooked
+    pub filled_by: Option<(JiraIssue, String)>,
+}
+
+impl ImplicitGap {
+    pub fn work(&self, task: JiraIssue, description: String) -> Work {
+        Work {
+            start: self.range.min(),
+            end: self.range.max(),
+            task,
+            description,
+            links: Vec::new(),
+        }
+    }
+}
+
+/// The times the normalizer fills with the started issue, and the [unbooked_gaps]
+pub fn implicit_gaps(day: &ActiveDay) -> Result<Vec<ImplicitGap>, String> {
+    let mut actions = gap_actions(day);
+    let mut active_issue = day.active_issue().cloned();
+    let mut unbooked = Vec::new();
+    let parts = collect_splits(&mut actions, &mut active_issue, &mut unbooked)?;
+
+    // implicit work only knows the issue id
+    let issue = |id: String| {
+        day.actions()
+            .iter()
+            .filter_map(Action::issue)
+            .chain(day.active_issue())
+            .find(|i| i.ident == id)
+            .cloned()
+            .unwrap_or(JiraIssue {
+                ident: id,
+                description: None,
+                default_action: None,
+            })
+    };
+    let mut gaps: Vec<ImplicitGap> = parts
+        .into_iter()
+        .flat_map(|p| p.work)
+        .filter(|w| w.implicit)
+        .map(|w| ImplicitGap {
+            range: w.range(),
+            filled_by: Some((issue(w.id), w.description)),
+        })
+        .collect();
+    gaps.extend(unbooked.into_iter().map(|range| ImplicitGap {
+        range,
+        filled_by: None,
+    }));
+    gaps.sort_by_key(|g| g.range.min());
+    Ok(gaps)
+}
+
+/// Actions splitting the day into booked and unbooked times
+fn gap_actions(day: &ActiveDay) -> BTreeSet<Action> {
+    day.actions()
+        .iter()
+        .filter(|a| !matches!(a, Action::Standby(_) | Action::Absence(_) | Action::ZA(_)))
+        .cloned()
+        .map(|a| a.map_times(Time::round_seconds))
+        .collect()
+}
+
 /// Combine entries with the same issue id into one. Total booked time stays constant.
 fn combine_bookings(work: &mut Vec<We>) {
     let orig: Vec<We> = std::mem::take(work);
+    let mut by_issue: HashMap<String, usize> = HashMap::new();
 
     for w in orig {
-        if let Some(existing) = work.iter_mut().find(|r| r.same_issue(&w)) {
+        if let Some(&index) = by_issue.get(&w.id) {
+            let existing = &mut work[index];
             existing.end = existing.end + w.duration();
         } else {
+            by_issue.insert(w.id.clone(), work.len());
             work.push(w)
         }
     }
@@ -1,11 +1,13 @@
 use super::*;
+use crate::conf::WeekdayBreaks;
 use crate::data::active_day::ActiveDayBuilder;
 use crate::data::day_normalizer::day_splits;
 use crate::data::test_support::*;
-use crate::data::{JiraIssue, Location};
+use crate::data::{JiraIssue, Location, UtcOffset};
 use crate::parsing::time::Time;
 use crate::parsing::time_limit::TimeRange;
 
+use chrono::Weekday;
 use std::collections::BTreeSet;
 
 #[test]
@@ -50,6 +52,21 @@ fn test_missing_start() {
     assert!(matches!(start_end_spans(&actions), Err(_)));
 }
 
+#[test]
+fn test_spans_across_midnight() {
+    let actions = BTreeSet::from_iter([day_start("h22"), work("23", "24", "N-1", "night")]);
+    assert_eq!(
+        start_end_spans(&actions),
+        Ok(vec![TimeRange::new(Time::hm(22, 0), Time::MAX)])
+    );
+
+    let actions = BTreeSet::from_iter([work("0", "2", "N-1", "night"), day_end("3")]);
+    assert_eq!(
+        start_end_spans(&actions),
+        Ok(vec![TimeRange::new(Time::ZERO, Time::hm(3, 0))])
+    );
+}
+
 #[test]
 fn too_many_starts() {
     let actions = BTreeSet::from_iter([day_start("h9"), day_start("10"), day_end("11")]);
@@ -85,6 +102,25 @@ fn times_only_default_action() {
     )
 }
 
+#[test]
+fn overlapping_work_leaves_no_gaps() {
+    let mut actions = BTreeSet::from_iter([
+        day_start("h8"),
+        issue_start("8", "A-1", "", "dev"),
+        work("8", "12", "B-1", "workshop"),
+        work("9", "10", "C-1", "call"),
+        issue_start("11", "D-1", "", "dev"),
+        work("11:30", "13", "C-1", "call"),
+        day_end("13"),
+    ]);
+    let mut active_issue = None;
+
+    let result = day_splits(&mut actions, &mut active_issue).unwrap();
+
+    let implicit: Vec<_> = result[0].work.iter().filter(|w| w.implicit).collect();
+    assert_eq!(implicit, Vec::<&We>::new());
+}
+
 #[test]
 fn times_work_interrupting() {
     let mut actions = BTreeSet::from_iter([
@@ -367,6 +403,7 @@ static ref CONFIG: BreaksConfig = BreaksConfig {
         min_breaks_minutes: 45,
         min_work_time_minutes: 6 * 60,
         default_break: (time("12"), time("12:45")),
+        weekday_overrides: vec![],
     };
 }
 
@@ -623,9 +660,12 @@ fn integration_test() {
             min_breaks_minutes: 45,
             min_work_time_minutes: 6 * 60,
             default_break: (time("1145"), time("1230")),
+            weekday_overrides: vec![],
         },
         combine_bookings: true,
         add_break: true,
+        home_zone: None,
+        holidays: Vec::new(),
     };
 
     let normalized = n
@@ -690,9 +730,12 @@ fn integration_test_free_issue() {
             min_breaks_minutes: 45,
             min_work_time_minutes: 6 * 60,
             default_break: (time("1145"), time("1230")),
+            weekday_overrides: vec![],
         },
         combine_bookings: true,
         add_break: true,
+        home_zone: None,
+        holidays: Vec::new(),
     };
 
     let normalized = n
@@ -736,3 +779,377 @@ fn integration_test_free_issue() {
         ]
     );
 }
+
+#[test]
+fn applies_weekday_break_rules() {
+    let n = Normalizer {
+        resolution: NonZeroU32::new(15).unwrap(),
+        breaks_config: BreaksConfig {
+            weekday_overrides: vec![WeekdayBreaks {
+                weekday: Weekday::Fri,
+                min_breaks_minutes: 30,
+                min_work_time_minutes: 6 * 60,
+                default_break: (time("12:30"), time("13")),
+            }],
+            ..CONFIG.clone()
+        },
+        combine_bookings: true,
+        add_break: true,
+        home_zone: None,
+        holidays: Vec::new(),
+    };
+
+    let normalize = |day: Day| {
+        n.create_normalized(
+            &ActiveDayBuilder {
+                active_issue: None,
+                actions: vec![
+                    day_start("h8"),
+                    issue_start("8", "A-1", "First", "doFirst"),
+                    day_end("17"),
+                ],
+                day,
+                main_location: Location::Home,
+            }
+            .build(),
+        )
+        .unwrap()
+    };
+
+    // Thursday uses the default rules
+    let thursday = normalize(Day::ymd(2022, 1, 6));
+    assert_eq!(
+        thursday.final_breaks.breaks,
+        vec![TimeRange::new(time("12"), time("12:45"))]
+    );
+
+    let friday = normalize(Day::ymd(2022, 1, 7));
+    assert_eq!(
+        friday.final_breaks.breaks,
+        vec![TimeRange::new(time("12:30"), time("13"))]
+    );
+}
+
+#[test]
+fn keeps_standby_separate() {
+    let day = ActiveDayBuilder {
+        active_issue: None,
+        actions: vec![
+            day_start("h8"),
+            issue_start("8", "A-1", "First", "doFirst"),
+            day_end("12"),
+            Action::Standby(Standby {
+                start: time("18"),
+                end: Time::MAX,
+            }),
+        ],
+        day: Day::ymd(2022, 1, 6),
+        main_location: Location::Home,
+    }
+    .build();
+
+    let n = Normalizer {
+        resolution: NonZeroU32::new(15).unwrap(),
+        breaks_config: BreaksConfig::default(),
+        combine_bookings: true,
+        add_break: false,
+        home_zone: None,
+        holidays: Vec::new(),
+    };
+
+    let normalized = n.create_normalized(&day).unwrap();
+    assert_eq!(
+        &normalized.entries[..],
+        &[workn("8", "12", "A-1", "doFirst")]
+    );
+    assert_eq!(
+        normalized.standby,
+        vec![Standby {
+            start: time("18"),
+            end: Time::MAX,
+        }]
+    );
+}
+
+#[test]
+fn keeps_links_of_rounded_and_combined_work() {
+    let linked = |start, end, url: &str| {
+        let mut w = workn(start, end, "A-1", "review");
+        w.links = vec![url.to_string()];
+        Action::Work(w)
+    };
+    let day = ActiveDayBuilder {
+        active_issue: None,
+        actions: vec![
+            day_start("h8"),
+            linked("8", "9:02", "https://git/pr/1"),
+            linked("9:02", "10:04", "https://git/pr/2"),
+            work("10:04", "11", "B-2", "dev"),
+            day_end("11"),
+        ],
+        day: Day::ymd(2022, 1, 6),
+        main_location: Location::Home,
+    }
+    .build();
+
+    let n = Normalizer {
+        resolution: NonZeroU32::new(15).unwrap(),
+        breaks_config: BreaksConfig::default(),
+        combine_bookings: true,
+        add_break: false,
+        home_zone: None,
+        holidays: Vec::new(),
+    };
+
+    let normalized = n.create_normalized(&day).unwrap();
+    let links: Vec<_> = normalized.entries.iter().map(|w| w.links.len()).collect();
+    assert_eq!(links, vec![2, 0]);
+    assert_eq!(
+        normalized.entries[0].links,
+        vec![
+            "https://git/pr/1".to_string(),
+            "https://git/pr/2".to_string()
+        ]
+    );
+}
+
+#[test]
+fn shifts_days_started_in_other_time_zone() {
+    let mut day = ActiveDayBuilder {
+        active_issue: None,
+        actions: vec![
+            day_start("h8"),
+            work("8", "12", "A-1", "abroad"),
+            day_end("12"),
+        ],
+        day: Day::ymd(2022, 1, 6),
+        main_location: Location::Home,
+    }
+    .build();
+    day.record_zone(UtcOffset::from_minutes(-5 * 60).unwrap());
+
+    let mut n = Normalizer {
+        resolution: NonZeroU32::new(15).unwrap(),
+        breaks_config: BreaksConfig::default(),
+        combine_bookings: true,
+        add_break: false,
+        home_zone: UtcOffset::from_minutes(60),
+        holidays: Vec::new(),
+    };
+
+    let normalized = n.create_normalized(&day).unwrap();
+    assert_eq!(
+        &normalized.entries[..],
+        &[workn("14", "18", "A-1", "abroad")]
+    );
+
+    n.home_zone = UtcOffset::from_minutes(-5 * 60);
+    let normalized = n.create_normalized(&day).unwrap();
+    assert_eq!(
+        &normalized.entries[..],
+        &[workn("8", "12", "A-1", "abroad")]
+    );
+
+    n.home_zone = UtcOffset::from_minutes(14 * 60);
+    assert!(n.create_normalized(&day).is_err());
+}
+
+#[test]
+fn keeps_absences_separate() {
+    let absence = Absence {
+        category: "doctor".to_string(),
+        start: time("7"),
+        end: time("8"),
+        credited_minutes: None,
+    };
+    let day = ActiveDayBuilder {
+        active_issue: None,
+        actions: vec![
+            Action::Absence(absence.clone()),
+            day_start("o8"),
+            work("8", "12", "A-1", "a"),
+            day_end("12"),
+        ],
+        day: Day::ymd(2022, 1, 6),
+        main_location: Location::Office,
+    }
+    .build();
+
+    let n = Normalizer {
+        resolution: NonZeroU32::new(15).unwrap(),
+        breaks_config: BreaksConfig::default(),
+        combine_bookings: true,
+        add_break: false,
+        home_zone: None,
+        holidays: Vec::new(),
+    };
+
+    let normalized = n.create_normalized(&day).unwrap();
+    assert_eq!(&normalized.entries[..], &[workn("8", "12", "A-1", "a")]);
+    assert_eq!(normalized.absences, vec![absence]);
+}
+
+#[test]
+fn rejects_work_during_credited_absences() {
+    let absence = |start: &str, end: &str| {
+        Action::Absence(Absence {
+            category: "vacation".to_string(),
+            start: time(start),
+            end: time(end),
+            credited_minutes: Some(3 * 60 + 51),
+        })
+    };
+    let day = |absence| {
+        ActiveDayBuilder {
+            active_issue: None,
+            actions: vec![
+                absence,
+                day_start("o12"),
+                work("12", "16", "A-1", "a"),
+                day_end("16"),
+            ],
+            day: Day::ymd(2022, 1, 6),
+            main_location: Location::Office,
+        }
+        .build()
+    };
+
+    let n = Normalizer {
+        resolution: NonZeroU32::new(15).unwrap(),
+        breaks_config: BreaksConfig::default(),
+        combine_bookings: true,
+        add_break: false,
+        home_zone: None,
+        holidays: Vec::new(),
+    };
+
+    assert!(n.create_normalized(&day(absence("8", "12"))).is_ok());
+    assert_eq!(
+        n.create_normalized(&day(absence("8", "13"))).err(),
+        Some("Work on A-1 overlaps the credited vacation from 08:00 to 13:00".to_string())
+    );
+}
+
+#[test]
+fn reports_all_unbooked_times() {
+    let day = ActiveDayBuilder {
+        active_issue: None,
+        actions: vec![
+            day_start("o8"),
+            work("9", "10", "A-1", "a"),
+            issue_start("11", "A-2", "", "b"),
+            issue_end("12", "A-2"),
+            day_end("13"),
+        ],
+        day: Day::ymd(2022, 1, 6),
+        main_location: Location::Office,
+    }
+    .build();
+
+    assert_eq!(
+        unbooked_gaps(&day),
+        Ok(vec![
+            TimeRange::new(time("8"), time("9")),
+            TimeRange::new(time("10"), time("11")),
+            TimeRange::new(time("12"), time("13")),
+        ])
+    );
+    let n = Normalizer {
+        resolution: NonZeroU32::new(1).unwrap(),
+        breaks_config: BreaksConfig::default(),
+        combine_bookings: false,
+        add_break: false,
+        home_zone: None,
+        holidays: Vec::new(),
+    };
+    assert_eq!(
+        n.create_normalized(&day).err(),
+        Some("Unbooked times: 08:00-09:00, 10:00-11:00, 12:00-13:00".to_string())
+    );
+}
+
+#[test]
+fn lists_implicitly_booked_times() {
+    let day = ActiveDayBuilder {
+        active_issue: None,
+        actions: vec![
+            day_start("o8"),
+            work("8", "9", "A-1", "a"),
+            issue_start("9", "A-2", "Second", "b"),
+            work("10", "11", "A-1", "a"),
+            issue_end("12", "A-2"),
+            day_end("13"),
+        ],
+        day: Day::ymd(2022, 1, 6),
+        main_location: Location::Office,
+    }
+    .build();
+
+    let gaps = implicit_gaps(&day).unwrap();
+    let ranges: Vec<_> = gaps
+        .iter()
+        .map(|g| (g.range.min(), g.range.max()))
+        .collect();
+    assert_eq!(
+        ranges,
+        vec![
+            (time("9"), time("10")),
+            (time("11"), time("12")),
+            (time("12"), time("13")),
+        ]
+    );
+    let (issue, comment) = gaps[1].filled_by.clone().unwrap();
+    assert_eq!(issue.description.as_deref(), Some("Second"));
+    let work = gaps[1].work(issue, comment);
+    assert_eq!(Action::Work(work).to_string(), "11:00 - 12:00 | A-2: b");
+    assert_eq!(gaps[2].filled_by, None);
+}
+
+/// A day with `count` actions one minute apart from 08:00, alternating between issue
+/// starts and short meetings, like after importing a calendar and idle times
+fn dense_day(count: u32) -> ActiveDay {
+    let at = |minute: u32| Time::new(8 * 60 + minute);
+    let mut actions = vec![day_start("o8")];
+    for i in 0..count {
+        actions.push(if i % 2 == 0 {
+            Action::WorkStart(WorkStart {
+                ts: at(i),
+                task: JiraIssue::create(&format!("DEV-{}", i % 40)).unwrap(),
+                description: "import".to_string(),
+            })
+        } else {
+            Action::Work(Work {
+                start: at(i),
+                end: at(i + 1),
+                task: JiraIssue::create(&format!("MEET-{}", i)).unwrap(),
+                description: "meeting".to_string(),
+                links: Vec::new(),
+            })
+        });
+    }
+    actions.push(Action::DayEnd(DayEnd { ts: at(count + 1) }));
+    ActiveDayBuilder {
+        day: Day::ymd(2022, 1, 6),
+        main_location: Location::Office,
+        active_issue: None,
+        actions,
+    }
+    .build()
+}
+
+#[test]
+fn dense_day_is_booked_completely() {
+    let n = Normalizer {
+        resolution: NonZeroU32::new(1).unwrap(),
+        breaks_config: CONFIG.clone(),
+        combine_bookings: true,
+        add_break: true,
+        home_zone: None,
+        holidays: Vec::new(),
+    };
+    let normalized = n.create_normalized(&dense_day(500)).unwrap();
+    assert_eq!(normalized.orig_breaks.break_time, TimeRelative::ZERO);
+    assert_eq!(normalized.final_breaks.work_time.offset_minutes(), 501);
+    assert_eq!(normalized.entries.first().unwrap().start, time("8"));
+    assert_eq!(normalized.entries.last().unwrap().end, time("16:21"));
+}
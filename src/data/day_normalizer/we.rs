@@ -74,6 +74,7 @@ impl From<We> for Work {
                 default_action: None,
             },
             description: w.description,
+            links: Vec::new(),
         }
     }
 }
@@ -0,0 +1,107 @@
+use crate::data::{ActiveDay, Day, DayForwarder, Normalizer};
+
+/// A past workday that cannot be exported as it is
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DayToFix {
+    pub day: Day,
+    pub reason: FixReason,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum FixReason {
+    NothingBooked,
+    NotNormalized(String),
+}
+
+impl FixReason {
+    pub fn label(&self) -> String {
+        match self {
+            FixReason::NothingBooked => "nothing booked".to_string(),
+            FixReason::NotNormalized(e) => e.clone(),
+        }
+    }
+}
+
+impl DayToFix {
+    /// The `count` workdays before `today`, newest first
+    pub fn workdays_before(today: Day, count: usize, forwarder: &impl DayForwarder) -> Vec<Day> {
+        let mut days = Vec::with_capacity(count);
+        let mut day = today;
+        for _ in 0..count {
+            day = day.prev(forwarder);
+            days.push(day);
+        }
+        days
+    }
+
+    /// Workdays without actions or with actions that cannot be normalized
+    pub fn check(workdays: &[Day], stored: &[ActiveDay], normalizer: &Normalizer) -> Vec<DayToFix> {
+        let mut result = Vec::new();
+        for day in workdays.iter().copied() {
+            let reason = match stored.iter().find(|d| d.get_day() == day) {
+                Some(active_day) if !active_day.actions().is_empty() => {
+                    match normalizer.create_normalized(active_day) {
+                        Ok(_) => continue,
+                        Err(e) => FixReason::NotNormalized(e),
+                    }
+                }
+                _ => FixReason::NothingBooked,
+            };
+            result.push(DayToFix { day, reason });
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::num::NonZeroU32;
+
+    use crate::conf::BreaksConfig;
+    use crate::data::test_support::{day_end, day_start, work};
+    use crate::data::{
+        ActiveDay, Day, DayToFix, FixReason, HolidayForwarder, Location, Normalizer,
+    };
+
+    #[test]
+    fn finds_unbooked_and_broken_days() {
+        let forwarder = HolidayForwarder {
+            holidays: vec![Day::ymd(2022, 1, 6)],
+        };
+        // monday, the thursday before is a holiday
+        let workdays = DayToFix::workdays_before(Day::ymd(2022, 1, 10), 4, &forwarder);
+        assert_eq!(
+            workdays,
+            vec![
+                Day::ymd(2022, 1, 7),
+                Day::ymd(2022, 1, 5),
+                Day::ymd(2022, 1, 4),
+                Day::ymd(2022, 1, 3),
+            ]
+        );
+
+        let mut booked = ActiveDay::new(Day::ymd(2022, 1, 7), Location::Office, None);
+        booked.add_action(day_start("8"));
+        booked.add_action(work("8", "12", "A-1", "a"));
+        booked.add_action(day_end("12"));
+        let mut unfinished = ActiveDay::new(Day::ymd(2022, 1, 5), Location::Office, None);
+        unfinished.add_action(day_start("8"));
+        let empty = ActiveDay::new(Day::ymd(2022, 1, 4), Location::Office, None);
+        let normalizer = Normalizer {
+            resolution: NonZeroU32::new(15).unwrap(),
+            breaks_config: BreaksConfig::default(),
+            combine_bookings: true,
+            add_break: false,
+            home_zone: None,
+            holidays: Vec::new(),
+        };
+
+        let to_fix = DayToFix::check(&workdays, &[booked, unfinished, empty], &normalizer);
+        let reasons: Vec<_> = to_fix.iter().map(|d| (d.day, d.reason.clone())).collect();
+        assert_eq!(reasons.len(), 3);
+        assert_eq!(reasons[0].0, Day::ymd(2022, 1, 5));
+        assert!(matches!(reasons[0].1, FixReason::NotNormalized(_)));
+        assert_eq!(reasons[1], (Day::ymd(2022, 1, 4), FixReason::NothingBooked));
+        assert_eq!(reasons[2], (Day::ymd(2022, 1, 3), FixReason::NothingBooked));
+    }
+}
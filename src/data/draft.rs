@@ -0,0 +1,7 @@
+/// Input of a view that was not stored yet, restored after a restart
+#[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct Draft {
+    /// Name of the view the input was typed into
+    pub view: String,
+    pub input: String,
+}
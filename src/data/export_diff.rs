@@ -0,0 +1,112 @@
+use std::fmt::{Display, Formatter};
+
+use crate::data::Work;
+
+/// Difference of an exported entry between two exports of a day
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum EntryChange {
+    Added(Work),
+    Removed(Work),
+    /// The entry on the same issue at an overlapping time
+    Changed {
+        before: Work,
+        after: Work,
+    },
+}
+
+/// Compares the entries of the previous export with the entries exported now.
+///
+/// Entries that are not equal are paired as changed when they are on the same issue and
+/// overlap, all others are added or removed.
+pub fn export_diff(before: &[Work], after: &[Work]) -> Vec<EntryChange> {
+    let mut added: Vec<&Work> = after.iter().filter(|w| !before.contains(w)).collect();
+    let mut changes = Vec::new();
+    for old in before.iter().filter(|w| !after.contains(w)) {
+        let counterpart = added
+            .iter()
+            .position(|new| new.task.ident == old.task.ident && overlaps(old, new));
+        changes.push(match counterpart {
+            Some(index) => EntryChange::Changed {
+                before: old.clone(),
+                after: added.remove(index).clone(),
+            },
+            None => EntryChange::Removed(old.clone()),
+        });
+    }
+    changes.extend(added.into_iter().cloned().map(EntryChange::Added));
+    changes.sort_by_key(|c| match c {
+        EntryChange::Added(w) | EntryChange::Removed(w) => w.start,
+        EntryChange::Changed { before, after } => before.start.min(after.start),
+    });
+    changes
+}
+
+fn overlaps(a: &Work, b: &Work) -> bool {
+    a.start < b.end && b.start < a.end
+}
+
+struct EntryDisplay<'a>(&'a Work);
+
+impl<'a> Display for EntryDisplay<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let w = self.0;
+        write!(
+            f,
+            "{} - {} {} {}",
+            w.start, w.end, w.task.ident, w.description
+        )
+    }
+}
+
+impl Display for EntryChange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EntryChange::Added(w) => write!(f, "added    {}", EntryDisplay(w)),
+            EntryChange::Removed(w) => write!(f, "removed  {}", EntryDisplay(w)),
+            EntryChange::Changed { before, after } => write!(
+                f,
+                "changed  {}  to  {}",
+                EntryDisplay(before),
+                EntryDisplay(after)
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::data::export_diff::{export_diff, EntryChange};
+    use crate::data::test_support::workn;
+
+    #[test]
+    fn pairs_changed_entries() {
+        let before = vec![
+            workn("8", "9", "A-1", "dev"),
+            workn("9", "10", "B-2", "review"),
+            workn("10", "12", "C-3", "meeting"),
+        ];
+        let after = vec![
+            workn("8", "9", "A-1", "dev"),
+            workn("9", "10:30", "B-2", "review"),
+            workn("10:30", "12", "D-4", "support"),
+        ];
+
+        let changes = export_diff(&before, &after);
+        assert_eq!(
+            changes,
+            vec![
+                EntryChange::Changed {
+                    before: workn("9", "10", "B-2", "review"),
+                    after: workn("9", "10:30", "B-2", "review"),
+                },
+                EntryChange::Removed(workn("10", "12", "C-3", "meeting")),
+                EntryChange::Added(workn("10:30", "12", "D-4", "support")),
+            ]
+        );
+        assert_eq!(
+            changes[0].to_string(),
+            "changed  09:00 - 10:00 B-2 review  to  09:00 - 10:30 B-2 review"
+        );
+        assert!(export_diff(&after, &after).is_empty());
+    }
+}
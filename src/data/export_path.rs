@@ -0,0 +1,63 @@
+use std::path::PathBuf;
+
+use chrono::Datelike;
+
+use crate::data::Day;
+use crate::parsing::locale::DateFormat;
+
+/// Export file of `day` from a pattern like `~/reports/{year}/{month}/{date}.csv`.
+///
+/// Known placeholders are `{year}`, `{month}`, `{day}`, `{week}` and `{date}`,
+/// a leading `~` is the home directory
+pub fn export_path(pattern: &str, day: Day) -> Result<PathBuf, String> {
+    let date: chrono::NaiveDate = day.into();
+    let mut result = String::new();
+    let mut rest = pattern;
+    while let Some(start) = rest.find('{') {
+        result.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find('}')
+            .ok_or_else(|| format!("Unclosed placeholder in '{}'", pattern))?;
+        let value = match &rest[start + 1..start + end] {
+            "year" => format!("{:04}", date.year()),
+            "month" => format!("{:02}", date.month()),
+            "day" => format!("{:02}", date.day()),
+            "week" => format!("{:02}", date.iso_week().week()),
+            "date" => day.format(DateFormat::Iso),
+            other => return Err(format!("Unknown placeholder {{{}}}", other)),
+        };
+        result.push_str(&value);
+        rest = &rest[start + end + 1..];
+    }
+    result.push_str(rest);
+
+    match result.strip_prefix('~') {
+        Some(relative) if relative.is_empty() || relative.starts_with(['/', '\\']) => {
+            let home = dirs::home_dir().ok_or("Unknown home directory")?;
+            Ok(home.join(relative.trim_start_matches(['/', '\\'])))
+        }
+        _ => Ok(PathBuf::from(result)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use crate::data::{export_path, Day};
+
+    #[test]
+    fn replaces_placeholders() {
+        let day = Day::ymd(2022, 3, 7);
+        assert_eq!(
+            export_path("reports/{year}/{month}/{date}-w{week}.csv", day),
+            Ok(PathBuf::from("reports/2022/03/2022-03-07-w10.csv"))
+        );
+        assert_eq!(
+            export_path("~/{day}.csv", day),
+            Ok(dirs::home_dir().unwrap().join("07.csv"))
+        );
+        assert!(export_path("{weekday}.csv", day).is_err());
+        assert!(export_path("{year.csv", day).is_err());
+    }
+}
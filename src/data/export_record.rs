@@ -0,0 +1,112 @@
+use std::path::PathBuf;
+
+use chrono::NaiveDateTime;
+
+use crate::data::Action;
+
+/// Snapshot of the last export of a day
+#[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct ExportRecord {
+    pub at: NaiveDateTime,
+    /// Actions of the day at the time of the export
+    pub actions: Vec<Action>,
+    /// The exported text
+    pub text: String,
+}
+
+/// A file the export of a day was written to
+#[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct ExportFile {
+    pub at: NaiveDateTime,
+    pub path: PathBuf,
+}
+
+/// Outcome of the last post of a day to the webhook
+#[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct WebhookDelivery {
+    pub at: NaiveDateTime,
+    pub attempts: u32,
+    /// Error of the last attempt, `None` if the day was delivered
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl WebhookDelivery {
+    pub fn label(&self) -> String {
+        let at = self.at.format("%Y-%m-%d %H:%M");
+        match &self.error {
+            None => format!("delivered to webhook on {}", at),
+            Some(e) => format!(
+                "webhook failed on {} after {} attempts: {}",
+                at, self.attempts, e
+            ),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ExportStatus {
+    NeverExported,
+    Exported(NaiveDateTime),
+    /// Exported, but the actions of the day changed afterwards
+    ModifiedSinceExport(NaiveDateTime),
+}
+
+impl ExportStatus {
+    pub fn is_exported(self) -> bool {
+        !matches!(self, ExportStatus::NeverExported)
+    }
+
+    pub fn label(self) -> String {
+        let format = |at: NaiveDateTime| at.format("%Y-%m-%d %H:%M");
+        match self {
+            ExportStatus::NeverExported => "never exported".to_string(),
+            ExportStatus::Exported(at) => format!("exported on {}", format(at)),
+            ExportStatus::ModifiedSinceExport(at) => {
+                format!("modified since export on {}", format(at))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use crate::data::test_support::work;
+    use crate::data::{ActiveDay, Day, ExportStatus, Location, WebhookDelivery};
+
+    #[test]
+    fn tracks_modifications_after_export() {
+        let mut day = ActiveDay::new(Day::ymd(2022, 1, 20), Location::Office, None);
+        day.add_action(work("8", "9", "A-1", "a"));
+        assert_eq!(day.export_status(), ExportStatus::NeverExported);
+
+        let at = chrono::NaiveDate::from_ymd(2022, 1, 20).and_hms(17, 3, 0);
+        day.mark_exported(at, "exported".to_string());
+        assert_eq!(day.export_status(), ExportStatus::Exported(at));
+        assert_eq!(day.export_status().label(), "exported on 2022-01-20 17:03");
+
+        day.add_action(work("9", "10", "A-1", "b"));
+        assert_eq!(day.export_status(), ExportStatus::ModifiedSinceExport(at));
+        assert_eq!(day.exported_day().unwrap().actions().len(), 1);
+
+        let again = at + chrono::Duration::minutes(5);
+        day.mark_exported_to_file(again, "exported".to_string(), PathBuf::from("a.csv"));
+        assert_eq!(day.export_status(), ExportStatus::Exported(again));
+        assert_eq!(day.export_files()[0].path, PathBuf::from("a.csv"));
+
+        day.set_webhook_delivery(WebhookDelivery {
+            at: again,
+            attempts: 2,
+            error: Some("HTTP 503".to_string()),
+        });
+        assert_eq!(
+            day.webhook_delivery().unwrap().label(),
+            "webhook failed on 2022-01-20 17:08 after 2 attempts: HTTP 503"
+        );
+
+        let json = serde_json::to_string(&day).unwrap();
+        assert_eq!(serde_json::from_str::<ActiveDay>(&json).unwrap(), day);
+    }
+}
@@ -1,54 +1,225 @@
-use crate::data::NormalizedDay;
+use crate::conf::AbsenceCategory;
+use crate::data::{NormalizedDay, Surcharges};
+use crate::parsing::locale::{DateFormat, TimeFormat};
+use crate::parsing::time::Time;
+use serde_json::{json, Value};
 use std::fmt::Write;
 
+/// The default export, a `date|start|end|issue|description` line per work entry.
+///
+/// The extended format appends the location and the kind of the entry (`work`, `standby` or
+/// `absence`) as columns and adds lines for standby and absences
 pub struct TimeCockpitExporter;
 
 impl TimeCockpitExporter {
-    pub fn export(day: &NormalizedDay) -> String {
+    pub fn export(
+        day: &NormalizedDay,
+        absence_categories: &[AbsenceCategory],
+        extended: bool,
+    ) -> Result<String, String> {
         let mut out = String::new();
+        // importers expect fixed formats, regardless of the display formats
+        let date = day.date.format(DateFormat::Iso);
+        let time = |t: Time| t.format(TimeFormat::H24);
+        let location = column(day.location.name())?;
 
         for w in &day.entries {
-            writeln!(
+            write!(
                 out,
                 "{}|{}|{}|{}|{}",
-                day.date, w.start, w.end, w.task.ident, w.description
+                date,
+                time(w.start),
+                time(w.end),
+                w.task.ident,
+                column(&w.description)?
+            )
+            .unwrap();
+            if extended {
+                writeln!(out, "|{}|work", location).unwrap();
+            } else {
+                writeln!(out).unwrap();
+            }
+        }
+        if !extended {
+            return Ok(out);
+        }
+
+        // standby has no issue, the last column separates it from work
+        for s in &day.standby {
+            writeln!(
+                out,
+                "{}|{}|{}|||{}|standby",
+                date,
+                time(s.start),
+                time(s.end),
+                location
             )
             .unwrap();
         }
 
-        out
+        for a in &day.absences {
+            writeln!(
+                out,
+                "{}|{}|{}|{}||{}|absence",
+                date,
+                time(a.start),
+                time(a.end),
+                column(AbsenceCategory::code_of(absence_categories, &a.category))?,
+                location
+            )
+            .unwrap();
+        }
+
+        Ok(out)
+    }
+}
+
+/// Columns are separated by `|`, values containing it would shift the following columns
+fn column(value: &str) -> Result<&str, String> {
+    if value.contains('|') {
+        Err(format!("Cannot export \"{}\", it contains '|'", value))
+    } else {
+        Ok(value)
+    }
+}
+
+fn surcharges_json(surcharges: Surcharges) -> Value {
+    json!({
+        "regular": surcharges.regular,
+        "night": surcharges.night,
+        "weekend": surcharges.weekend,
+    })
+}
+
+/// The normalized day as JSON document, times are formatted like `08:45` regardless of the
+/// configured display formats
+pub struct JsonExporter;
+
+impl JsonExporter {
+    pub fn export(day: &NormalizedDay, absence_categories: &[AbsenceCategory]) -> Value {
+        let work: Vec<Value> = day
+            .entries
+            .iter()
+            .map(|w| {
+                json!({
+                    "start": w.start.format(TimeFormat::H24),
+                    "end": w.end.format(TimeFormat::H24),
+                    "issue": w.task.ident,
+                    "description": w.description,
+                    "links": w.links,
+                    "surcharges": surcharges_json(day.surcharges_of(w)),
+                })
+            })
+            .collect();
+        let absences: Vec<Value> = day
+            .absences
+            .iter()
+            .map(|a| {
+                json!({
+                    "start": a.start.format(TimeFormat::H24),
+                    "end": a.end.format(TimeFormat::H24),
+                    "code": AbsenceCategory::code_of(absence_categories, &a.category),
+                    "credited_minutes": a.credit(),
+                })
+            })
+            .collect();
+        let ranges = |ranges: Vec<(Time, Time)>| -> Vec<Value> {
+            ranges
+                .into_iter()
+                .map(|(start, end)| json!({"start": start.format(TimeFormat::H24), "end": end.format(TimeFormat::H24)}))
+                .collect()
+        };
+
+        json!({
+            "date": day.date.format(DateFormat::Iso),
+            "location": day.location.name(),
+            "work": work,
+            "standby": ranges(day.standby.iter().map(|s| (s.start, s.end)).collect()),
+            "absences": absences,
+            "za": ranges(day.za.iter().map(|z| (z.start, z.end)).collect()),
+            "breaks": ranges(day.final_breaks.breaks.iter().map(|b| (b.min(), b.max())).collect()),
+            "notes": day.notes,
+            "surcharges": surcharges_json(day.surcharges()),
+        })
+    }
+}
+
+/// Export format defined by a minijinja template in the settings.
+///
+/// The template renders the document of the [JsonExporter], e.g.
+/// `{% for w in work %}{{ date }};{{ w.start }};{{ w.end }};{{ w.issue }}\n{% endfor %}`
+pub struct TemplateExporter;
+
+impl TemplateExporter {
+    pub fn export(
+        template: &str,
+        day: &NormalizedDay,
+        absence_categories: &[AbsenceCategory],
+    ) -> Result<String, String> {
+        let document = JsonExporter::export(day, absence_categories);
+        minijinja::Environment::new()
+            .render_str(template, document)
+            .map_err(|e| format!("Invalid export template: {}", e))
     }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::data::exporter::TimeCockpitExporter;
-    use crate::data::{BreaksInfo, Day, JiraIssue, NormalizedDay, Work};
+    use crate::conf::AbsenceCategory;
+    use crate::data::exporter::{JsonExporter, TemplateExporter, TimeCockpitExporter};
+    use crate::data::{
+        Absence, BreaksInfo, Day, JiraIssue, Location, NormalizedDay, Standby, Work,
+    };
     use crate::parsing::time::Time;
     use crate::parsing::time_limit::TimeRange;
     use crate::parsing::time_relative::TimeRelative;
+    use serde_json::json;
 
-    #[test]
-    fn test_export() {
+    fn sample_day() -> NormalizedDay {
         let breaks = BreaksInfo {
             work_time: TimeRelative::from_minutes_sat(300),
             break_time: TimeRelative::from_minutes_sat(45),
             breaks: vec![TimeRange::new(Time::hm(12, 00), Time::hm(12, 45))],
         };
-        let d = NormalizedDay {
+        NormalizedDay {
             date: Day::ymd(2022, 1, 6),
+            location: Location::Home,
             entries: vec![
                 work(845, 900, "I-15", "some meeting+org"),
                 work(900, 1200, "ISSUE-12345", "other"),
                 work(1245, 1700, "A-51", "the afternoon"),
             ],
+            standby: vec![Standby {
+                start: Time::hm(18, 0),
+                end: Time::MAX,
+            }],
+            absences: vec![Absence {
+                category: "doctor".to_string(),
+                start: Time::hm(7, 30),
+                end: Time::hm(8, 30),
+                credited_minutes: None,
+            }],
+            za: vec![],
             orig_breaks: breaks.clone(),
             final_breaks: breaks,
-        };
+            notes: String::new(),
+            rounding: Vec::new(),
+            day_off: false,
+        }
+    }
 
-        let exported = TimeCockpitExporter::export(&d);
+    fn categories() -> [AbsenceCategory; 1] {
+        [AbsenceCategory {
+            name: "doctor".to_string(),
+            code: "ARZT".to_string(),
+        }]
+    }
+
+    #[test]
+    fn test_export() {
+        let exported = TimeCockpitExporter::export(&sample_day(), &categories(), false);
         assert_eq!(
-            exported,
+            exported.unwrap(),
             r#"2022-01-06|08:45|09:00|I-15|some meeting+org
 2022-01-06|09:00|12:00|ISSUE-12345|other
 2022-01-06|12:45|17:00|A-51|the afternoon
@@ -56,12 +227,138 @@ mod test {
         )
     }
 
+    #[test]
+    fn test_extended_export() {
+        let exported = TimeCockpitExporter::export(&sample_day(), &categories(), true);
+        assert_eq!(
+            exported.unwrap(),
+            r#"2022-01-06|08:45|09:00|I-15|some meeting+org|Home Office|work
+2022-01-06|09:00|12:00|ISSUE-12345|other|Home Office|work
+2022-01-06|12:45|17:00|A-51|the afternoon|Home Office|work
+2022-01-06|18:00|24:00|||Home Office|standby
+2022-01-06|07:30|08:30|ARZT||Home Office|absence
+"#
+        )
+    }
+
+    #[test]
+    fn rejects_the_column_separator() {
+        let mut day = sample_day();
+        day.entries[1].description = "review|merge".to_string();
+        assert_eq!(
+            TimeCockpitExporter::export(&day, &categories(), false).unwrap_err(),
+            "Cannot export \"review|merge\", it contains '|'"
+        );
+    }
+
+    #[test]
+    fn test_json_export() {
+        let exported = JsonExporter::export(&sample_day(), &categories());
+        assert_eq!(exported["date"], "2022-01-06");
+        assert_eq!(exported["location"], "Home Office");
+        assert_eq!(exported["work"].as_array().unwrap().len(), 3);
+        assert_eq!(
+            exported["work"][1],
+            json!({
+                "start": "09:00",
+                "end": "12:00",
+                "issue": "ISSUE-12345",
+                "description": "other",
+                "links": [],
+                "surcharges": {"regular": 180, "night": 0, "weekend": 0},
+            })
+        );
+        assert_eq!(
+            exported["standby"][0],
+            json!({"start": "18:00", "end": "24:00"})
+        );
+        assert_eq!(
+            exported["absences"][0],
+            json!({"start": "07:30", "end": "08:30", "code": "ARZT", "credited_minutes": 60})
+        );
+        assert_eq!(
+            exported["breaks"][0],
+            json!({"start": "12:00", "end": "12:45"})
+        );
+        assert_eq!(
+            exported["surcharges"],
+            json!({"regular": 7 * 60 + 30, "night": 0, "weekend": 0})
+        );
+    }
+
+    #[test]
+    fn exports_surcharge_totals() {
+        let day = NormalizedDay {
+            entries: vec![work(1900, 2130, "A-51", "release")],
+            standby: vec![],
+            absences: vec![],
+            ..sample_day()
+        };
+        let exported = JsonExporter::export(&day, &categories());
+        assert_eq!(
+            exported["surcharges"],
+            json!({"regular": 60, "night": 90, "weekend": 0})
+        );
+        // the lines are bookings, totals would be imported as work on an issue "night"
+        assert_eq!(
+            TimeCockpitExporter::export(&day, &categories(), true).unwrap(),
+            "2022-01-06|19:00|21:30|A-51|release|Home Office|work\n"
+        );
+
+        let day = NormalizedDay {
+            day_off: true,
+            ..day
+        };
+        assert_eq!(
+            JsonExporter::export(&day, &categories())["work"][0]["surcharges"],
+            json!({"regular": 0, "night": 0, "weekend": 150})
+        );
+    }
+
+    #[test]
+    fn test_template_export() {
+        let template = "{{ date }} {{ location }}\n\
+            {% for w in work %}{{ w.start }}-{{ w.end }} {{ w.issue }}\n{% endfor %}\
+            {% for a in absences %}{{ a.code }}\n{% endfor %}";
+        let exported = TemplateExporter::export(template, &sample_day(), &categories());
+        assert_eq!(
+            exported.unwrap(),
+            "2022-01-06 Home Office
+08:45-09:00 I-15
+09:00-12:00 ISSUE-12345
+12:45-17:00 A-51
+ARZT
+"
+        );
+
+        let error = TemplateExporter::export("{% for w in work %}", &sample_day(), &categories());
+        assert!(error.unwrap_err().starts_with("Invalid export template:"));
+    }
+
+    #[test]
+    fn templates_may_include_notes() {
+        let template = "# {{ date }}\n{% if notes %}> {{ notes }}\n{% endif %}";
+        let day = NormalizedDay {
+            notes: "prod incident".to_string(),
+            ..sample_day()
+        };
+        assert_eq!(
+            TemplateExporter::export(template, &day, &categories()).unwrap(),
+            "# 2022-01-06\n> prod incident\n"
+        );
+        assert_eq!(
+            TemplateExporter::export(template, &sample_day(), &categories()).unwrap(),
+            "# 2022-01-06\n"
+        );
+    }
+
     fn work(start: u32, end: u32, task: &str, description: &str) -> Work {
         Work {
             start: Time::hm(start / 100, start % 100),
             end: Time::hm(end / 100, end % 100),
             task: JiraIssue::create(task).unwrap(),
             description: description.to_string(),
+            links: Vec::new(),
         }
     }
 }
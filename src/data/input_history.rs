@@ -0,0 +1,68 @@
+use std::collections::BTreeMap;
+
+/// Number of inputs kept per view
+pub const MAX_INPUT_HISTORY: usize = 50;
+
+/// Stored inputs of the entry views, oldest first and without duplicates
+#[derive(Clone, Debug, Default, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct InputHistory {
+    /// Inputs by the name of the view they were submitted in
+    views: BTreeMap<String, Vec<String>>,
+}
+
+impl InputHistory {
+    /// Remembers the input stored in `view`, a repeated input moves to the end
+    pub fn add(&mut self, view: &str, input: &str) {
+        let input = input.trim();
+        if input.is_empty() {
+            return;
+        }
+        let inputs = self.views.entry(view.to_string()).or_default();
+        inputs.retain(|i| i != input);
+        inputs.push(input.to_string());
+        if inputs.len() > MAX_INPUT_HISTORY {
+            inputs.drain(..inputs.len() - MAX_INPUT_HISTORY);
+        }
+    }
+
+    /// The input `back` steps back in the history of `view`, 0 is the last input
+    pub fn recall(&self, view: &str, back: usize) -> Option<&str> {
+        let inputs = self.views.get(view)?;
+        let index = inputs.len().checked_sub(back + 1)?;
+        Some(inputs[index].as_str())
+    }
+
+    pub fn len(&self, view: &str) -> usize {
+        self.views.get(view).map(Vec::len).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::data::input_history::{InputHistory, MAX_INPUT_HISTORY};
+
+    #[test]
+    fn recalls_the_last_inputs_first() {
+        let mut history = InputHistory::default();
+        history.add("book", "9 10 A-1 daily");
+        history.add("book", "10 12 B-2 review ");
+        history.add("book", "9 10 A-1 daily");
+        history.add("book", "  ");
+        history.add("issue_start", "A-1 dev");
+
+        assert_eq!(history.len("book"), 2);
+        assert_eq!(history.recall("book", 0), Some("9 10 A-1 daily"));
+        assert_eq!(history.recall("book", 1), Some("10 12 B-2 review"));
+        assert_eq!(history.recall("book", 2), None);
+        assert_eq!(history.recall("day_start", 0), None);
+
+        for i in 0..MAX_INPUT_HISTORY {
+            history.add("issue_start", &format!("A-{}", i));
+        }
+        assert_eq!(history.len("issue_start"), MAX_INPUT_HISTORY);
+        assert_eq!(
+            history.recall("issue_start", MAX_INPUT_HISTORY - 1),
+            Some("A-0")
+        );
+    }
+}
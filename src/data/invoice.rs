@@ -0,0 +1,218 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use crate::data::{format_hours, Day, NormalizedDay};
+
+/// Normalized work of a period per issue, priced with the hourly rates of the issues
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Invoice {
+    pub first_day: Day,
+    pub last_day: Day,
+    pub projects: Vec<InvoiceProject>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InvoiceProject {
+    pub project: String,
+    pub lines: Vec<InvoiceLine>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InvoiceLine {
+    pub ident: String,
+    pub minutes: u32,
+    /// Cents per hour, `None` if no rate matches the issue
+    pub rate_cents: Option<u32>,
+}
+
+impl InvoiceLine {
+    pub fn amount_cents(&self) -> u64 {
+        let rate = self.rate_cents.unwrap_or(0) as u64;
+        (self.minutes as u64 * rate + 30) / 60
+    }
+}
+
+impl InvoiceProject {
+    pub fn minutes(&self) -> u32 {
+        self.lines.iter().map(|l| l.minutes).sum()
+    }
+
+    pub fn subtotal_cents(&self) -> u64 {
+        self.lines.iter().map(InvoiceLine::amount_cents).sum()
+    }
+}
+
+impl Invoice {
+    /// Collects all days between `first_day` and `last_day`, inclusive.
+    ///
+    /// `rates` maps issue id prefixes to cents per hour, the longest matching prefix wins
+    pub fn collect<'a>(
+        first_day: Day,
+        last_day: Day,
+        days: impl IntoIterator<Item = &'a NormalizedDay>,
+        rates: &BTreeMap<String, u32>,
+    ) -> Invoice {
+        let mut minutes: BTreeMap<String, BTreeMap<String, u32>> = BTreeMap::new();
+        for day in days {
+            if day.date < first_day || day.date > last_day {
+                continue;
+            }
+            for w in &day.entries {
+                let ident = w.task.ident.to_ascii_uppercase();
+                let (project, _) = ident.split_once('-').unwrap_or((&ident, ""));
+                *minutes
+                    .entry(project.to_string())
+                    .or_default()
+                    .entry(ident.clone())
+                    .or_default() += w.minutes();
+            }
+        }
+
+        let projects = minutes
+            .into_iter()
+            .map(|(project, issues)| InvoiceProject {
+                project,
+                lines: issues
+                    .into_iter()
+                    .map(|(ident, minutes)| InvoiceLine {
+                        rate_cents: rate_of(rates, &ident),
+                        ident,
+                        minutes,
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        Invoice {
+            first_day,
+            last_day,
+            projects,
+        }
+    }
+
+    pub fn total_cents(&self) -> u64 {
+        self.projects
+            .iter()
+            .map(InvoiceProject::subtotal_cents)
+            .sum()
+    }
+
+    /// One line per issue, subtotals of the projects and the total as CSV
+    pub fn to_csv(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "project,issue,hours,rate,amount").unwrap();
+        for p in &self.projects {
+            for l in &p.lines {
+                writeln!(
+                    out,
+                    "{},{},{},{},{}",
+                    p.project,
+                    l.ident,
+                    format_hours(l.minutes),
+                    l.rate_cents.map(format_cents).unwrap_or_default(),
+                    format_cents(l.amount_cents())
+                )
+                .unwrap();
+            }
+            writeln!(
+                out,
+                "{},subtotal,{},,{}",
+                p.project,
+                format_hours(p.minutes()),
+                format_cents(p.subtotal_cents())
+            )
+            .unwrap();
+        }
+        writeln!(out, "total,,,,{}", format_cents(self.total_cents())).unwrap();
+        out
+    }
+
+    /// A table per project with its subtotal
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "# Invoice {} - {}", self.first_day, self.last_day).unwrap();
+        for p in &self.projects {
+            writeln!(out, "\n## {}\n", p.project).unwrap();
+            writeln!(out, "| Issue | Hours | Rate | Amount |").unwrap();
+            writeln!(out, "|---|---:|---:|---:|").unwrap();
+            for l in &p.lines {
+                writeln!(
+                    out,
+                    "| {} | {} | {} | {} |",
+                    l.ident,
+                    format_hours(l.minutes),
+                    l.rate_cents.map_or_else(|| "-".to_string(), format_cents),
+                    format_cents(l.amount_cents())
+                )
+                .unwrap();
+            }
+            writeln!(
+                out,
+                "| **Subtotal** | {} | | {} |",
+                format_hours(p.minutes()),
+                format_cents(p.subtotal_cents())
+            )
+            .unwrap();
+        }
+        writeln!(out, "\n**Total: {}**", format_cents(self.total_cents())).unwrap();
+        out
+    }
+}
+
+fn rate_of(rates: &BTreeMap<String, u32>, ident: &str) -> Option<u32> {
+    rates
+        .iter()
+        .filter(|(prefix, _)| ident.starts_with(prefix.as_str()))
+        .max_by_key(|(prefix, _)| prefix.len())
+        .map(|(_, rate)| *rate)
+}
+
+/// An amount in cents with two decimals
+pub fn format_cents(cents: impl Into<u64>) -> String {
+    let cents = cents.into();
+    format!("{}.{:02}", cents / 100, cents % 100)
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use crate::data::test_support::{normalized_day, workn};
+    use crate::data::{Day, Invoice};
+
+    #[test]
+    fn prices_issues_with_the_longest_matching_prefix() {
+        let days = vec![
+            normalized_day(
+                Day::ymd(2022, 1, 3),
+                vec![
+                    workn("8", "10", "QUA-1", "a"),
+                    workn("10", "10:45", "QUA-7", "b"),
+                    workn("11", "12", "OPS-2", "c"),
+                ],
+            ),
+            normalized_day(Day::ymd(2022, 1, 4), vec![workn("8", "9:30", "QUA-1", "a")]),
+        ];
+        let rates: BTreeMap<String, u32> =
+            [("QUA".to_string(), 8000), ("QUA-7".to_string(), 10050)]
+                .into_iter()
+                .collect();
+
+        let invoice = Invoice::collect(Day::ymd(2022, 1, 1), Day::ymd(2022, 1, 31), &days, &rates);
+        assert_eq!(invoice.total_cents(), 28000 + 7538);
+        assert_eq!(
+            invoice.to_csv(),
+            "project,issue,hours,rate,amount
+OPS,OPS-2,1.00,,0.00
+OPS,subtotal,1.00,,0.00
+QUA,QUA-1,3.50,80.00,280.00
+QUA,QUA-7,0.75,100.50,75.38
+QUA,subtotal,4.25,,355.38
+total,,,,355.38
+"
+        );
+        assert!(invoice
+            .to_markdown()
+            .contains("| QUA-7 | 0.75 | 100.50 | 75.38 |\n| **Subtotal** | 4.25 | | 355.38 |"));
+    }
+}
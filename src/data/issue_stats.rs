@@ -0,0 +1,134 @@
+use crate::data::{Day, NormalizedDay};
+
+/// Aggregated bookings of a single issue over all stored days
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IssueStats {
+    pub ident: String,
+    pub total_minutes: u32,
+    pub sessions: u32,
+    pub last_booked: Option<Day>,
+    /// Booked minutes per week, oldest first. The last entry is the week of `today`.
+    pub weekly_minutes: Vec<u32>,
+}
+
+impl IssueStats {
+    pub fn collect<'a>(
+        ident: &str,
+        today: Day,
+        weeks: usize,
+        days: impl IntoIterator<Item = &'a NormalizedDay>,
+    ) -> IssueStats {
+        let mut stats = IssueStats {
+            ident: ident.to_string(),
+            total_minutes: 0,
+            sessions: 0,
+            last_booked: None,
+            weekly_minutes: vec![0; weeks],
+        };
+        let current_week = week_start(today);
+
+        for day in days {
+            let mut minutes = 0;
+            for w in day.entries.iter().filter(|w| w.task.ident == ident) {
+                stats.sessions += 1;
+                minutes += w.minutes();
+            }
+            if minutes == 0 {
+                continue;
+            }
+
+            stats.total_minutes += minutes;
+            if stats.last_booked.map(|l| l < day.date).unwrap_or(true) {
+                stats.last_booked = Some(day.date);
+            }
+
+            let weeks_ago = (current_week - week_start(day.date)).num_weeks();
+            if weeks_ago >= 0 && (weeks_ago as usize) < weeks {
+                stats.weekly_minutes[weeks - 1 - weeks_ago as usize] += minutes;
+            }
+        }
+
+        stats
+    }
+
+    pub fn average_session_minutes(&self) -> Option<u32> {
+        self.total_minutes.checked_div(self.sessions)
+    }
+
+    /// Weekly minutes scaled to `0..=height`
+    pub fn sparkline(&self, height: u32) -> Vec<u32> {
+        let max = self
+            .weekly_minutes
+            .iter()
+            .copied()
+            .max()
+            .unwrap_or_default();
+        self.weekly_minutes
+            .iter()
+            .map(|m| {
+                if max == 0 {
+                    0
+                } else {
+                    (m * height).div_ceil(max)
+                }
+            })
+            .collect()
+    }
+}
+
+/// Formats minutes as `<h>h <m>m`, without the 24 hour limit of `TimeRelative`
+pub fn format_minutes(minutes: u32) -> String {
+    format!("{}h {:02}m", minutes / 60, minutes % 60)
+}
+
+/// Formats minutes as decimal hours with two digits, e.g. `7.50` for 7h 30m
+pub fn format_hours(minutes: u32) -> String {
+    format!("{}.{:02}", minutes / 60, (minutes % 60) * 100 / 60)
+}
+
+fn week_start(day: Day) -> chrono::NaiveDate {
+    day.first_of_week().into()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::data::issue_stats::{format_hours, format_minutes, IssueStats};
+    use crate::data::test_support::{normalized_day, workn};
+    use crate::data::Day;
+
+    #[test]
+    fn collects_issue_stats() {
+        // 2022-01-12 is a Wednesday
+        let today = Day::ymd(2022, 1, 12);
+        let days = vec![
+            normalized_day(Day::ymd(2021, 12, 1), vec![workn("8", "9", "A-1", "old")]),
+            normalized_day(
+                Day::ymd(2022, 1, 3),
+                vec![
+                    workn("8", "10", "A-1", "a"),
+                    workn("10", "11", "B-1", "b"),
+                    workn("11", "11:30", "A-1", "a"),
+                ],
+            ),
+            normalized_day(Day::ymd(2022, 1, 10), vec![workn("9", "10", "A-1", "a")]),
+            normalized_day(Day::ymd(2022, 1, 11), vec![workn("9", "10", "B-1", "b")]),
+        ];
+
+        let stats = IssueStats::collect("A-1", today, 3, &days);
+        assert_eq!(stats.total_minutes, 60 + 120 + 30 + 60);
+        assert_eq!(stats.sessions, 4);
+        assert_eq!(stats.last_booked, Some(Day::ymd(2022, 1, 10)));
+        assert_eq!(stats.weekly_minutes, vec![0, 150, 60]);
+        assert_eq!(stats.average_session_minutes(), Some(67));
+        assert_eq!(stats.sparkline(4), vec![0, 4, 2]);
+
+        let none = IssueStats::collect("C-1", today, 3, &days);
+        assert_eq!(none.last_booked, None);
+        assert_eq!(none.average_session_minutes(), None);
+        assert_eq!(none.sparkline(4), vec![0, 0, 0]);
+
+        assert_eq!(format_minutes(270), "4h 30m");
+        assert_eq!(format_hours(270), "4.50");
+        assert_eq!(format_hours(61), "1.01");
+    }
+}
@@ -1,5 +1,7 @@
 use std::fmt::{Display, Formatter};
 
+use crate::parsing::parse_result::ParseResult;
+
 #[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
 pub enum Location {
     Office,
@@ -17,6 +19,87 @@ impl Default for Location {
 #[allow(clippy::box_collection)]
 pub struct OtherLocation(pub Box<String>);
 
+impl Location {
+    pub fn other(name: impl Into<String>) -> Location {
+        Location::Other(OtherLocation(Box::new(name.into())))
+    }
+
+    /// Office, home and the configured custom locations, in the order used for cycling
+    pub fn choices(custom: &[String]) -> Vec<Location> {
+        let mut result = vec![Location::Office, Location::Home];
+        result.extend(custom.iter().map(|name| Location::other(name.as_str())));
+        result
+    }
+
+    /// Name without the `Other: ` prefix of custom locations
+    pub fn name(&self) -> &str {
+        match self {
+            Location::Office => "Office",
+            Location::Home => "Home Office",
+            Location::Other(l) => l.0.as_str(),
+        }
+    }
+
+    /// Short form used in text inputs
+    pub fn input_name(&self) -> &str {
+        match self {
+            Location::Office => "o",
+            Location::Home => "h",
+            Location::Other(l) => l.0.as_str(),
+        }
+    }
+
+    /// Parses a leading location: `h`, `o`, a configured custom location or a unique prefix of it.
+    ///
+    /// Returns the remaining input
+    pub fn parse_prefix<'a>(
+        input: &'a str,
+        custom: &[String],
+    ) -> (ParseResult<Location, ()>, &'a str) {
+        let input = input.trim_start();
+        let word_len = input
+            .find(|c: char| !c.is_alphabetic())
+            .unwrap_or(input.len());
+        if word_len == 0 {
+            return (ParseResult::None, input);
+        }
+
+        let full_match = custom
+            .iter()
+            .filter(|name| {
+                input.len() >= name.len()
+                    && input.is_char_boundary(name.len())
+                    && input[..name.len()].eq_ignore_ascii_case(name)
+                    && !input[name.len()..].starts_with(char::is_alphabetic)
+            })
+            .max_by_key(|name| name.len());
+        if let Some(name) = full_match {
+            return (
+                ParseResult::Valid(Location::other(name.as_str())),
+                input[name.len()..].trim_start(),
+            );
+        }
+
+        let (word, rest) = input.split_at(word_len);
+        let rest = rest.trim_start();
+        if word.eq_ignore_ascii_case("h") {
+            return (ParseResult::Valid(Location::Home), rest);
+        } else if word.eq_ignore_ascii_case("o") {
+            return (ParseResult::Valid(Location::Office), rest);
+        }
+
+        let word = word.to_lowercase();
+        let mut candidates = custom
+            .iter()
+            .filter(|name| name.to_lowercase().starts_with(&word));
+        match (candidates.next(), candidates.next()) {
+            (Some(name), None) => (ParseResult::Valid(Location::other(name.as_str())), rest),
+            (Some(_), Some(_)) => (ParseResult::Incomplete, rest),
+            (None, _) => (ParseResult::Invalid(()), rest),
+        }
+    }
+}
+
 impl Display for Location {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -29,3 +112,37 @@ impl Display for Location {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::data::Location;
+    use crate::parsing::parse_result::ParseResult;
+
+    #[test]
+    fn parse_location_prefix() {
+        let custom = vec![
+            "Client A".to_string(),
+            "Client B".to_string(),
+            "Hotel".to_string(),
+        ];
+        let p = |input| Location::parse_prefix(input, &custom);
+
+        assert_eq!(p("h8"), (ParseResult::Valid(Location::Home), "8"));
+        assert_eq!(p("o +1h"), (ParseResult::Valid(Location::Office), "+1h"));
+        assert_eq!(p("-15"), (ParseResult::None, "-15"));
+        assert_eq!(
+            p("client b 9"),
+            (ParseResult::Valid(Location::other("Client B")), "9")
+        );
+        assert_eq!(
+            p("hot 9"),
+            (ParseResult::Valid(Location::other("Hotel")), "9")
+        );
+        assert_eq!(p("cli 9"), (ParseResult::Incomplete, "9"));
+        assert_eq!(p("x9"), (ParseResult::Invalid(()), "9"));
+
+        let choices = Location::choices(&custom);
+        assert_eq!(choices.len(), 5);
+        assert_eq!(choices[2].input_name(), "Client A");
+    }
+}
@@ -0,0 +1,197 @@
+use std::fmt::Write;
+
+use crate::data::{format_hours, Action, ActiveDay, Day, Location};
+use crate::parsing::time::Time;
+
+/// Work time per location, derived from the DayStart segments of the days
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LocationReport {
+    pub first_day: Day,
+    pub last_day: Day,
+    pub entries: Vec<LocationTotals>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LocationTotals {
+    pub location: Location,
+    /// Days with at least one segment at the location
+    pub days: u32,
+    pub minutes: u32,
+}
+
+impl LocationReport {
+    /// Collects all days between `first_day` and `last_day`, inclusive
+    pub fn collect<'a>(
+        first_day: Day,
+        last_day: Day,
+        days: impl IntoIterator<Item = &'a ActiveDay>,
+    ) -> LocationReport {
+        let mut entries: Vec<LocationTotals> = Vec::new();
+
+        for day in days {
+            if day.get_day() < first_day || day.get_day() > last_day {
+                continue;
+            }
+
+            let mut counted: Vec<&Location> = Vec::new();
+            for (location, minutes) in segments(day) {
+                let index = match entries.iter().position(|e| e.location == *location) {
+                    Some(index) => index,
+                    None => {
+                        entries.push(LocationTotals {
+                            location: location.clone(),
+                            days: 0,
+                            minutes: 0,
+                        });
+                        entries.len() - 1
+                    }
+                };
+                let totals = &mut entries[index];
+                totals.minutes += minutes;
+                if !counted.contains(&location) {
+                    counted.push(location);
+                    totals.days += 1;
+                }
+            }
+        }
+
+        entries.sort_by(|a, b| sort_key(&a.location).cmp(&sort_key(&b.location)));
+
+        LocationReport {
+            first_day,
+            last_day,
+            entries,
+        }
+    }
+
+    /// Days and hours per location as CSV, one row per location
+    pub fn to_csv(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "from,to,location,days,hours").unwrap();
+        for e in &self.entries {
+            writeln!(
+                out,
+                "{},{},{},{},{}",
+                self.first_day,
+                self.last_day,
+                e.location.name(),
+                e.days,
+                format_hours(e.minutes)
+            )
+            .unwrap();
+        }
+        out
+    }
+}
+
+/// Locations and minutes of the DayStart segments of the day.
+///
+/// A segment ends with the next DayEnd or DayStart, open segments end with the
+/// last booked action.
+fn segments(day: &ActiveDay) -> Vec<(&Location, u32)> {
+    let last_end = day
+        .actions()
+        .iter()
+        .filter_map(Action::action_end)
+        .max()
+        .unwrap_or(Time::ZERO);
+
+    let mut result = Vec::new();
+    let mut current: Option<(&Location, Time)> = None;
+    for action in day.actions() {
+        match action {
+            Action::DayStart(s) => {
+                if let Some((location, start)) = current.take() {
+                    result.push((location, start.minutes_until(s.ts)));
+                }
+                current = Some((&s.location, s.ts));
+            }
+            Action::DayEnd(e) => {
+                if let Some((location, start)) = current.take() {
+                    result.push((location, start.minutes_until(e.ts)));
+                }
+            }
+            _ => (),
+        }
+    }
+    if let Some((location, start)) = current {
+        result.push((location, start.minutes_until(last_end)));
+    }
+    result
+}
+
+fn sort_key(location: &Location) -> (u8, &str) {
+    match location {
+        Location::Office => (0, ""),
+        Location::Home => (1, ""),
+        Location::Other(l) => (2, l.0.as_str()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::data::location_report::{LocationReport, LocationTotals};
+    use crate::data::test_support::{day_end, day_start, work};
+    use crate::data::{ActiveDayBuilder, Day, Location};
+
+    #[test]
+    fn collects_locations_per_segment() {
+        let day = |day, actions| {
+            ActiveDayBuilder {
+                day,
+                main_location: Location::Office,
+                active_issue: None,
+                actions,
+            }
+            .build()
+        };
+        let days = vec![
+            day(
+                Day::ymd(2022, 1, 3),
+                vec![
+                    day_start("h8"),
+                    day_end("12"),
+                    day_start("o13"),
+                    day_end("17"),
+                ],
+            ),
+            day(
+                Day::ymd(2022, 1, 4),
+                vec![day_start("h8"), work("8", "16:30", "A-1", "a")],
+            ),
+            day(Day::ymd(2022, 2, 1), vec![day_start("o8"), day_end("16")]),
+        ];
+
+        let report = LocationReport::collect(Day::ymd(2022, 1, 1), Day::ymd(2022, 1, 31), &days);
+        assert_eq!(
+            report.entries,
+            vec![
+                LocationTotals {
+                    location: Location::Office,
+                    days: 1,
+                    minutes: 4 * 60,
+                },
+                LocationTotals {
+                    location: Location::Home,
+                    days: 2,
+                    minutes: 4 * 60 + 8 * 60 + 30,
+                },
+            ]
+        );
+        assert_eq!(
+            report.to_csv(),
+            "from,to,location,days,hours
+2022-01-01,2022-01-31,Office,1,4.00
+2022-01-01,2022-01-31,Home Office,2,12.50
+"
+        );
+
+        let february = Day::ymd(2022, 2, 14);
+        assert_eq!(february.first_of_month(), Day::ymd(2022, 2, 1));
+        assert_eq!(february.last_of_month(), Day::ymd(2022, 2, 28));
+        assert_eq!(
+            Day::ymd(2021, 12, 3).last_of_month(),
+            Day::ymd(2021, 12, 31)
+        );
+    }
+}
@@ -1,23 +1,74 @@
-pub use action::{Action, DayEnd, DayStart, Doctor, TimedAction, ZA};
+pub use action::{Absence, Action, DayEnd, DayStart, Standby, TimedAction, ZA};
 pub use active_day::{ActiveDay, ActiveDayBuilder};
-pub use day::{Day, DayForwarder, SimpleDayForwarder, WeekDayForwarder};
-pub use day_normalizer::{BreaksInfo, NormalizedDay, Normalizer};
-pub use exporter::TimeCockpitExporter;
+pub use break_compliance::BreakCompliance;
+pub use budget_usage::BudgetUsage;
+pub use comment_history::CommentHistory;
+pub use day::{Day, DayForwarder, HolidayForwarder, SimpleDayForwarder, WeekDayForwarder};
+pub use day_normalizer::{
+    implicit_gaps, unbooked_gaps, BreaksInfo, ImplicitGap, NormalizedDay, Normalizer,
+};
+pub use day_to_fix::{DayToFix, FixReason};
+pub use draft::Draft;
+pub use export_diff::{export_diff, EntryChange};
+pub use export_path::export_path;
+pub use export_record::{ExportFile, ExportRecord, ExportStatus, WebhookDelivery};
+pub use exporter::{JsonExporter, TemplateExporter, TimeCockpitExporter};
+pub use input_history::InputHistory;
+pub use invoice::{format_cents, Invoice};
+pub use issue_stats::{format_hours, format_minutes, IssueStats};
 pub use jira_issue::JiraIssue;
 pub use location::Location;
-pub use recent_issues::{RecentIssue, RecentIssues, RecentIssuesData, RecentIssuesRef};
+pub use location_report::LocationReport;
+pub use reassign::DayReassignment;
+pub use recent_issues::{
+    fuzzy_match, group_by_project, issue_project, RecentIssue, RecentIssues, RecentIssuesData,
+    RecentIssuesRef, RecentRanking,
+};
+pub use rounding_report::rounding_report;
+pub use surcharges::Surcharges;
+pub use title_fetches::TitleFetches;
+pub use utc_offset::UtcOffset;
+pub use utilization_report::UtilizationReport;
+pub use week_calendar::{CalendarBlock, CalendarDay, WeekCalendar};
+pub use week_summary::{TargetProgress, WeekSummary};
 pub use work::{Work, WorkEnd, WorkEvent, WorkStart};
+pub use work_patterns::{WorkPatterns, DEEP_WORK_MINUTES};
+pub use year_overview::YearOverview;
+pub use za_balance::{format_balance, ZaBalance};
 
 mod action;
 mod active_day;
+mod break_compliance;
+mod budget_usage;
+mod comment_history;
 mod day;
 mod day_normalizer;
+mod day_to_fix;
+mod draft;
+mod export_diff;
+mod export_path;
+mod export_record;
 mod exporter;
+mod input_history;
+mod invoice;
+mod issue_stats;
 mod jira_issue;
 mod location;
+mod location_report;
+mod reassign;
 mod recent_issues;
+mod rounding_report;
+mod surcharges;
+mod title_fetches;
+mod utc_offset;
+mod utilization_report;
+mod week_calendar;
+mod week_summary;
 mod work;
 mod work_day;
+mod work_patterns;
+mod year_overview;
+mod za_balance;
 
 #[cfg(test)]
 pub mod test_support;
@@ -0,0 +1,98 @@
+use crate::data::{Action, ActiveDay, Day, JiraIssue, Work, WorkEnd, WorkEvent, WorkStart};
+
+/// Actions of one day that move from one issue to another
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DayReassignment {
+    pub day: Day,
+    /// Original and updated action
+    pub changes: Vec<(Action, Action)>,
+}
+
+impl DayReassignment {
+    /// All actions of `day` booked on `from`, `None` if there are none
+    pub fn collect(day: &ActiveDay, from: &str, to: &JiraIssue) -> Option<DayReassignment> {
+        let changes: Vec<_> = day
+            .actions()
+            .iter()
+            .filter_map(|a| with_issue(a, from, to).map(|updated| (a.clone(), updated)))
+            .collect();
+        if changes.is_empty() {
+            None
+        } else {
+            Some(DayReassignment {
+                day: day.get_day(),
+                changes,
+            })
+        }
+    }
+
+    /// Replaces the actions, fails without changes if the day was modified after collecting
+    pub fn apply(&self, day: &mut ActiveDay) -> bool {
+        let actions = day.actions_mut();
+        // the set only compares times, the issue has to match as well
+        if !self
+            .changes
+            .iter()
+            .all(|(orig, _)| actions.get(orig) == Some(orig))
+        {
+            return false;
+        }
+        for (orig, _) in &self.changes {
+            actions.remove(orig);
+        }
+        for (_, updated) in &self.changes {
+            actions.insert(updated.clone());
+        }
+        true
+    }
+}
+
+/// The action booked on `to`, if it is booked on `from`
+fn with_issue(action: &Action, from: &str, to: &JiraIssue) -> Option<Action> {
+    if !action.issue()?.ident.eq_ignore_ascii_case(from) {
+        return None;
+    }
+    let task = to.clone();
+    let updated = match action.clone() {
+        Action::Work(w) => Action::Work(Work { task, ..w }),
+        Action::WorkEvent(w) => Action::WorkEvent(WorkEvent { task, ..w }),
+        Action::WorkStart(w) => Action::WorkStart(WorkStart { task, ..w }),
+        Action::WorkEnd(w) => Action::WorkEnd(WorkEnd { task, ..w }),
+        _ => return None,
+    };
+    Some(updated)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::data::reassign::DayReassignment;
+    use crate::data::test_support::{day_start, issue_end, work};
+    use crate::data::{ActiveDay, Day, JiraIssue, Location};
+
+    #[test]
+    fn reassigns_all_actions_of_an_issue() {
+        let mut day = ActiveDay::new(Day::ymd(2022, 1, 20), Location::Office, None);
+        day.add_action(day_start("o8"));
+        day.add_action(work("8", "9", "OLD-1", "a"));
+        day.add_action(work("9", "10", "B-1", "b"));
+        day.add_action(issue_end("12", "OLD-1"));
+        let to = JiraIssue::create("NEW-2").unwrap();
+
+        let reassignment = DayReassignment::collect(&day, "old-1", &to).unwrap();
+        assert_eq!(
+            reassignment.changes,
+            vec![
+                (work("8", "9", "OLD-1", "a"), work("8", "9", "NEW-2", "a")),
+                (issue_end("12", "OLD-1"), issue_end("12", "NEW-2")),
+            ]
+        );
+        assert_eq!(DayReassignment::collect(&day, "C-1", &to), None);
+
+        assert!(reassignment.apply(&mut day));
+        assert_eq!(
+            day.actions().get(&work("8", "9", "OLD-1", "a")),
+            Some(&work("8", "9", "NEW-2", "a"))
+        );
+        assert!(!reassignment.apply(&mut day));
+    }
+}
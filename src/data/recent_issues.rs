@@ -5,15 +5,40 @@ use std::num::NonZeroUsize;
 use std::ops::Deref;
 use std::sync::Arc;
 
-use crate::conf::SettingsRef;
+use crate::conf::{MergeMode, SettingsRef};
 use crate::data::JiraIssue;
 use crate::util::update_arcswap;
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
 pub struct RecentIssuesData {
     pub issues: Vec<RecentIssue>,
+    /// Number of bookings per issue id
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub use_counts: BTreeMap<String, u32>,
+    /// Favorite issues, listed before the recent issues
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pinned: Vec<JiraIssue>,
 }
 
+/// Order of the recent issues list
+#[derive(Copy, Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecentRanking {
+    /// Most recently used first
+    Recency,
+    /// Blend of usage count and recency. The weight of the usage count halves
+    /// every [FREQUENCY_HALF_LIFE_DAYS] the issue was not used
+    Frequency,
+}
+
+impl Default for RecentRanking {
+    fn default() -> Self {
+        RecentRanking::Recency
+    }
+}
+
+pub const FREQUENCY_HALF_LIFE_DAYS: f64 = 7.0;
+
 #[derive(Clone)]
 pub struct RecentIssuesRef(Arc<ArcSwap<RecentIssues>>);
 
@@ -39,6 +64,30 @@ impl RecentIssuesRef {
         })
     }
 
+    pub fn pin(&self, ident: &str) {
+        update_arcswap(&self.0, |r: &mut RecentIssues| r.pin(ident))
+    }
+
+    pub fn unpin(&self, ident: &str) {
+        update_arcswap(&self.0, |r: &mut RecentIssues| r.unpin(ident))
+    }
+
+    pub fn replace(&self, inner: RecentIssues) {
+        self.0.store(Arc::new(inner))
+    }
+
+    pub fn import(&self, issues: &[JiraIssue], mode: MergeMode) {
+        update_arcswap(&self.0, |r: &mut RecentIssues| {
+            r.import(issues, mode);
+        })
+    }
+
+    pub fn set_missing_description(&self, ident: &str, description: &str) {
+        update_arcswap(&self.0, |r: &mut RecentIssues| {
+            r.set_missing_description(ident, description)
+        })
+    }
+
     pub fn borrow(&self) -> Guard<Arc<RecentIssues>> {
         self.0.load()
     }
@@ -57,7 +106,9 @@ pub struct RecentIssue {
 
 #[derive(Clone, Debug)]
 pub struct RecentIssues {
+    pinned: Vec<JiraIssue>,
     issues: Vec<RecentIssue>,
+    use_counts: BTreeMap<String, u32>,
     settings: SettingsRef,
     max_len: NonZeroUsize,
 }
@@ -69,6 +120,8 @@ impl RecentIssues {
         let guard = settings.load();
         let shortcuts = guard.issue_parser.shortcuts();
 
+        let use_counts = issues.use_counts;
+        let pinned = issues.pinned;
         let issues_sorted =
             BTreeMap::from_iter(issues.issues.into_iter().map(|e| (e.last_used, e)));
 
@@ -76,15 +129,19 @@ impl RecentIssues {
             .into_values()
             .rev()
             .filter(|r| !shortcuts.values().any(|sc| sc.ident == r.issue.ident))
-            .take(max_len.get())
+            .filter(|r| !pinned.iter().any(|p| p.ident == r.issue.ident))
             .map(normalize_recent)
             .collect();
 
-        Self {
+        let mut result = Self {
+            pinned,
             issues,
+            use_counts,
             settings,
             max_len,
-        }
+        };
+        result.rank();
+        result
     }
 
     pub fn issue_used(&mut self, issue: &JiraIssue) {
@@ -93,6 +150,11 @@ impl RecentIssues {
         }
 
         let last_used = self.settings.load().timeline.now();
+        *self.use_counts.entry(issue.ident.clone()).or_default() += 1;
+
+        if self.is_pinned(&issue.ident) {
+            return;
+        }
 
         if let Some(recent) =
             find_and_move_to_front(&mut self.issues, |i| i.issue.ident == issue.ident)
@@ -104,7 +166,6 @@ impl RecentIssues {
                 issue.default_action.as_deref(),
             );
         } else {
-            self.issues.truncate(self.max_len.get() - 1);
             self.issues.insert(
                 0,
                 RecentIssue {
@@ -113,6 +174,143 @@ impl RecentIssues {
                 },
             )
         }
+        self.rank();
+    }
+
+    /// Orders by the configured [RecentRanking] and drops the issues exceeding the maximum
+    fn rank(&mut self) {
+        let settings = self.settings.load();
+        if settings.recent_ranking == RecentRanking::Frequency {
+            let now = settings.timeline.now();
+            let mut scored: Vec<_> = std::mem::take(&mut self.issues)
+                .into_iter()
+                .map(|r| (self.score(&r, now), r))
+                .collect();
+            // stable sort keeps recency order for equal scores
+            scored.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+            self.issues = scored.into_iter().map(|(_, r)| r).collect();
+        }
+        self.issues.truncate(self.max_len.get());
+        let issues = &self.issues;
+        let pinned = &self.pinned;
+        self.use_counts.retain(|ident, _| {
+            issues.iter().any(|r| &r.issue.ident == ident)
+                || pinned.iter().any(|p| &p.ident == ident)
+        });
+    }
+
+    /// Moves the issue from the recent issues to the end of the pinned issues.
+    /// Pinned issues keep their position, so their `r<n>` index is stable
+    pub fn pin(&mut self, ident: &str) {
+        if let Some(index) = self.issues.iter().position(|r| r.issue.ident == ident) {
+            let recent = self.issues.remove(index);
+            self.pinned.push(recent.issue);
+        }
+    }
+
+    /// Moves a pinned issue back to the front of the recent issues
+    pub fn unpin(&mut self, ident: &str) {
+        if let Some(index) = self.pinned.iter().position(|p| p.ident == ident) {
+            let issue = self.pinned.remove(index);
+            let last_used = self.settings.load().timeline.now();
+            self.issues.insert(0, RecentIssue { last_used, issue });
+            self.rank();
+        }
+    }
+
+    /// Adds the shared issues to the end of the recent issues, existing issues keep their
+    /// position and get the shared description and default action only with
+    /// [MergeMode::Overwrite]. Returns the number of added or changed issues that are kept
+    pub fn import(&mut self, issues: &[JiraIssue], mode: MergeMode) -> usize {
+        let last_used = self.settings.load().timeline.now();
+        let mut changed = Vec::new();
+        for issue in issues {
+            if self.is_shortcut(issue) {
+                continue;
+            }
+            let existing = self
+                .pinned
+                .iter_mut()
+                .chain(self.issues.iter_mut().map(|r| &mut r.issue))
+                .find(|i| i.ident == issue.ident);
+            match existing {
+                Some(existing) if mode == MergeMode::Overwrite && *existing != *issue => {
+                    existing.description = issue.description.clone();
+                    existing.default_action = issue.default_action.clone();
+                    changed.push(issue.ident.as_str());
+                }
+                Some(_) => (),
+                None => {
+                    self.issues.push(normalize_recent(RecentIssue {
+                        last_used,
+                        issue: issue.clone(),
+                    }));
+                    changed.push(issue.ident.as_str());
+                }
+            }
+        }
+        self.rank();
+        // ranking drops the issues exceeding the maximum
+        changed
+            .into_iter()
+            .filter(|ident| self.filtered("").iter().any(|(i, _)| i.ident == *ident))
+            .count()
+    }
+
+    /// Number of shared issues [RecentIssues::import] would add or change
+    pub fn count_imported(&self, issues: &[JiraIssue], mode: MergeMode) -> usize {
+        self.clone().import(issues, mode)
+    }
+
+    /// Number of shared issues that already exist with a different description or default action
+    pub fn count_conflicts(&self, issues: &[JiraIssue]) -> usize {
+        issues
+            .iter()
+            .filter(|issue| {
+                self.pinned
+                    .iter()
+                    .chain(self.issues.iter().map(|r| &r.issue))
+                    .any(|i| i.ident == issue.ident && i != *issue)
+            })
+            .count()
+    }
+
+    /// Pinned and recent issues that fuzzy match the query on ident, description or
+    /// default action, in display order. Empty queries match all issues
+    pub fn filtered(&self, query: &str) -> Vec<(&JiraIssue, bool)> {
+        let pinned = self.pinned.iter().map(|i| (i, true));
+        let recent = self.issues.iter().map(|r| (&r.issue, false));
+        pinned
+            .chain(recent)
+            .filter(|(issue, _)| issue_matches(issue, query))
+            .collect()
+    }
+
+    pub fn is_pinned(&self, ident: &str) -> bool {
+        self.pinned.iter().any(|p| p.ident == ident)
+    }
+
+    pub fn list_pinned(&self) -> &[JiraIssue] {
+        self.pinned.as_slice()
+    }
+
+    fn score(&self, recent: &RecentIssue, now: chrono::NaiveDateTime) -> f64 {
+        let count = self.use_count(&recent.issue.ident).max(1) as f64;
+        let age_days = (now - recent.last_used).num_minutes().max(0) as f64 / (24.0 * 60.0);
+        count * 0.5f64.powf(age_days / FREQUENCY_HALF_LIFE_DAYS)
+    }
+
+    /// Number of bookings of the issue since it is in the recent issues list
+    pub fn use_count(&self, ident: &str) -> u32 {
+        self.use_counts.get(ident).copied().unwrap_or_default()
+    }
+
+    pub fn export_data(&self) -> RecentIssuesData {
+        RecentIssuesData {
+            issues: self.issues.clone(),
+            use_counts: self.use_counts.clone(),
+            pinned: self.pinned.clone(),
+        }
     }
 
     pub fn issue_used_with_comment(&mut self, issue: &JiraIssue, comment: Option<&str>) {
@@ -126,12 +324,41 @@ impl RecentIssues {
         }
     }
 
+    /// Pinned and recent issues without description
+    pub fn missing_descriptions(&self) -> Vec<String> {
+        self.pinned
+            .iter()
+            .chain(self.issues.iter().map(|r| &r.issue))
+            .filter(|i| i.description.is_none())
+            .map(|i| i.ident.clone())
+            .collect()
+    }
+
+    /// Sets the description, like a summary fetched from Jira, unless the issue got one meanwhile
+    pub fn set_missing_description(&mut self, ident: &str, description: &str) {
+        let issue = self
+            .pinned
+            .iter_mut()
+            .chain(self.issues.iter_mut().map(|r| &mut r.issue))
+            .find(|i| i.ident == ident);
+        if let Some(issue) = issue {
+            if issue.description.is_none() {
+                update_string(&mut issue.description, Some(description));
+            }
+        }
+    }
+
     pub fn list_recent(&self) -> &[RecentIssue] {
         self.issues.as_slice()
     }
 
-    pub fn find_recent(&self, num: usize) -> Option<&RecentIssue> {
-        self.issues.get(num)
+    /// Finds the issue with the zero based index. Pinned issues come first
+    pub fn find_recent(&self, num: usize) -> Option<&JiraIssue> {
+        if num < self.pinned.len() {
+            self.pinned.get(num)
+        } else {
+            self.issues.get(num - self.pinned.len()).map(|r| &r.issue)
+        }
     }
 
     fn is_shortcut(&self, issue: &JiraIssue) -> bool {
@@ -144,6 +371,73 @@ impl RecentIssues {
     }
 }
 
+/// Project of an issue id, the prefix before the dash
+pub fn issue_project(ident: &str) -> String {
+    let (project, _) = ident.split_once('-').unwrap_or((ident, ""));
+    project.to_ascii_uppercase()
+}
+
+/// Orders the entries by the project of their issue, projects in the order of their first
+/// entry. Entries of a project keep their order
+pub fn group_by_project<T>(entries: Vec<T>, ident: impl Fn(&T) -> &str) -> Vec<T> {
+    let mut projects: Vec<String> = Vec::new();
+    let mut keyed: Vec<(usize, T)> = entries
+        .into_iter()
+        .map(|e| {
+            let project = issue_project(ident(&e));
+            let key = match projects.iter().position(|p| *p == project) {
+                Some(key) => key,
+                None => {
+                    projects.push(project);
+                    projects.len() - 1
+                }
+            };
+            (key, e)
+        })
+        .collect();
+    keyed.sort_by_key(|(key, _)| *key);
+    keyed.into_iter().map(|(_, e)| e).collect()
+}
+
+fn issue_matches(issue: &JiraIssue, query: &str) -> bool {
+    let query = query.trim();
+    query.is_empty()
+        || fuzzy_match(&issue.ident, query).is_some()
+        || issue
+            .description
+            .as_deref()
+            .and_then(|d| fuzzy_match(d, query))
+            .is_some()
+        || issue
+            .default_action
+            .as_deref()
+            .and_then(|d| fuzzy_match(d, query))
+            .is_some()
+}
+
+/// Case insensitive match of all characters of `query` in order.
+///
+/// Returns the byte offsets of the matched characters in `text`
+pub fn fuzzy_match(text: &str, query: &str) -> Option<Vec<usize>> {
+    let mut result = Vec::new();
+    let mut query = query.chars().flat_map(char::to_lowercase).peekable();
+    for (offset, c) in text.char_indices() {
+        match query.peek() {
+            Some(q) if c.to_lowercase().eq(std::iter::once(*q)) => {
+                result.push(offset);
+                query.next();
+            }
+            Some(_) => (),
+            None => break,
+        }
+    }
+    if query.peek().is_none() {
+        Some(result)
+    } else {
+        None
+    }
+}
+
 fn normalize_recent(mut recent: RecentIssue) -> RecentIssue {
     empty_to_none(&mut recent.issue.default_action);
     empty_to_none(&mut recent.issue.description);
@@ -191,9 +485,9 @@ mod test {
 
     use chrono::NaiveDateTime;
 
-    use crate::conf::{into_settings_ref, Settings};
-    use crate::data::recent_issues::vec_move_to_front;
-    use crate::data::{JiraIssue, RecentIssue, RecentIssues, RecentIssuesData};
+    use crate::conf::{into_settings_ref, MergeMode, Settings};
+    use crate::data::recent_issues::{fuzzy_match, group_by_project, vec_move_to_front};
+    use crate::data::{JiraIssue, RecentIssue, RecentIssues, RecentIssuesData, RecentRanking};
     use crate::parsing::JiraIssueParser;
     use crate::util::{StaticTimeline, TimelineProvider};
 
@@ -203,6 +497,51 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_fuzzy_match() {
+        assert_eq!(fuzzy_match("Daily Standup", "dsu"), Some(vec![0, 6, 11]));
+        assert_eq!(fuzzy_match("ABC-12", "c1"), Some(vec![2, 4]));
+        assert_eq!(fuzzy_match("ABC-12", ""), Some(vec![]));
+        assert_eq!(fuzzy_match("ABC-12", "cb"), None);
+    }
+
+    #[test]
+    fn filters_pinned_and_recent() {
+        let timeline = Arc::new(StaticTimeline::parse("2022-01-10 12:00"));
+        let settings = into_settings_ref(Settings {
+            timeline: timeline.clone(),
+            ..Default::default()
+        });
+
+        let mut recent = RecentIssues::new(RecentIssuesData::default(), settings);
+        let standup = JiraIssue {
+            ident: "M-1".to_string(),
+            description: Some("Daily standup".to_string()),
+            default_action: None,
+        };
+        recent.issue_used(&standup);
+        recent.issue_used(&issue("DEV-7"));
+        recent.issue_used(&issue("DOC-3"));
+        recent.pin("M-1");
+
+        assert_eq!(
+            recent.filtered("d"),
+            vec![
+                (&standup, true),
+                (&issue("DOC-3"), false),
+                (&issue("DEV-7"), false)
+            ]
+        );
+        assert_eq!(recent.filtered("dv"), vec![(&issue("DEV-7"), false)]);
+        assert_eq!(recent.filtered("stup"), vec![(&standup, true)]);
+    }
+
+    #[test]
+    fn groups_by_project() {
+        let grouped = group_by_project(vec!["qua-3", "DEV-7", "QUA-1", "misc", "DEV-2"], |i| i);
+        assert_eq!(grouped, vec!["qua-3", "QUA-1", "DEV-7", "DEV-2", "misc"]);
+    }
+
     #[test]
     fn test_vec_move_to_front() {
         let mut v = vec![1];
@@ -234,6 +573,8 @@ mod test {
                     .into_iter()
                     .cloned()
                     .collect(),
+                use_counts: Default::default(),
+                pinned: Vec::new(),
             },
             settings,
         );
@@ -270,6 +611,8 @@ mod test {
                     .into_iter()
                     .cloned()
                     .collect(),
+                use_counts: Default::default(),
+                pinned: Vec::new(),
             },
             settings,
         );
@@ -356,6 +699,147 @@ mod test {
         );
     }
 
+    #[test]
+    fn ranks_by_frequency() {
+        let timeline = Arc::new(StaticTimeline::parse("2022-01-10 12:00"));
+        let settings = into_settings_ref(Settings {
+            timeline: timeline.clone(),
+            max_recent_issues: 3,
+            recent_ranking: RecentRanking::Frequency,
+            ..Default::default()
+        });
+
+        let mut recent = RecentIssues::new(RecentIssuesData::default(), settings);
+
+        let daily = next_recent(&timeline, "daily");
+        for _ in 0..5 {
+            recent.issue_used(&daily.issue);
+        }
+        let i1 = next_recent(&timeline, "i1");
+        recent.issue_used(&i1.issue);
+        let i2 = next_recent(&timeline, "i2");
+        recent.issue_used(&i2.issue);
+        let i3 = next_recent(&timeline, "i3");
+        recent.issue_used(&i3.issue);
+
+        let idents: Vec<_> = recent
+            .list_recent()
+            .iter()
+            .map(|r| r.issue.ident.as_str())
+            .collect();
+        assert_eq!(idents, vec!["daily", "i3", "i2"]);
+        assert_eq!(recent.use_count("daily"), 5);
+        assert_eq!(recent.use_count("i1"), 0);
+
+        let reloaded = RecentIssues::new(recent.export_data(), recent.settings.clone());
+        assert_eq!(reloaded.list_recent(), recent.list_recent());
+        assert_eq!(reloaded.use_count("daily"), 5);
+    }
+
+    #[test]
+    fn pinned_issues_have_stable_index() {
+        let timeline = Arc::new(StaticTimeline::parse("2022-01-10 12:00"));
+        let settings = into_settings_ref(Settings {
+            timeline: timeline.clone(),
+            max_recent_issues: 3,
+            ..Default::default()
+        });
+
+        let mut recent = RecentIssues::new(RecentIssuesData::default(), settings);
+        for i in ["i1", "i2", "i3"] {
+            timeline.advance();
+            recent.issue_used(&issue(i));
+        }
+        recent.pin("i2");
+
+        assert_eq!(recent.list_pinned(), &[issue("i2")]);
+        assert_eq!(recent.find_recent(0), Some(&issue("i2")));
+        assert_eq!(recent.find_recent(1), Some(&issue("i3")));
+
+        for i in ["i4", "i5", "i2"] {
+            timeline.advance();
+            recent.issue_used(&issue(i));
+        }
+        assert_eq!(recent.find_recent(0), Some(&issue("i2")));
+        assert_eq!(recent.find_recent(1), Some(&issue("i5")));
+        assert_eq!(recent.list_recent().len(), 3);
+
+        let reloaded = RecentIssues::new(recent.export_data(), recent.settings.clone());
+        assert_eq!(reloaded.list_pinned(), &[issue("i2")]);
+        assert_eq!(reloaded.list_recent(), recent.list_recent());
+
+        recent.unpin("i2");
+        assert!(recent.list_pinned().is_empty());
+        assert_eq!(recent.find_recent(0), Some(&issue("i2")));
+    }
+
+    #[test]
+    fn imports_shared_issues() {
+        let settings = into_settings_ref(Settings {
+            timeline: Arc::new(StaticTimeline::parse("2022-01-10 12:00")),
+            max_recent_issues: 3,
+            ..Default::default()
+        });
+        let described = |ident: &str, description: &str| JiraIssue {
+            description: Some(description.to_string()),
+            ..issue(ident)
+        };
+
+        let mut recent = RecentIssues::new(RecentIssuesData::default(), settings);
+        recent.issue_used(&described("i1", "mine"));
+        recent.issue_used(&issue("i2"));
+
+        let shared = [
+            described("i1", "shared"),
+            issue("i2"),
+            issue("i3"),
+            issue("i4"),
+        ];
+        assert_eq!(recent.count_conflicts(&shared), 1);
+
+        assert_eq!(recent.count_imported(&shared, MergeMode::Skip), 1);
+        let mut skipped = recent.clone();
+        assert_eq!(skipped.import(&shared, MergeMode::Skip), 1);
+        assert_eq!(
+            skipped
+                .filtered("")
+                .into_iter()
+                .map(|(i, _)| i.clone())
+                .collect::<Vec<_>>(),
+            vec![issue("i2"), described("i1", "mine"), issue("i3")]
+        );
+
+        assert_eq!(recent.import(&shared, MergeMode::Overwrite), 2);
+        assert_eq!(recent.find_recent(1), Some(&described("i1", "shared")));
+        assert_eq!(recent.count_conflicts(&shared), 0);
+    }
+
+    #[test]
+    fn completes_missing_descriptions() {
+        let described = |ident: &str, description: &str| JiraIssue {
+            description: Some(description.to_string()),
+            ..issue(ident)
+        };
+        let settings = into_settings_ref(Settings::default());
+        let mut recent = RecentIssues::new(RecentIssuesData::default(), settings);
+        recent.issue_used(&issue("DEV-7"));
+        recent.issue_used(&described("DOC-3", "docs"));
+        recent.issue_used(&issue("M-1"));
+        recent.pin("M-1");
+        assert_eq!(recent.missing_descriptions(), vec!["M-1", "DEV-7"]);
+
+        recent.set_missing_description("DEV-7", " Parser rewrite ");
+        recent.set_missing_description("DOC-3", "fetched");
+        recent.set_missing_description("M-1", "Standup");
+        assert!(recent.missing_descriptions().is_empty());
+        assert_eq!(recent.find_recent(0), Some(&described("M-1", "Standup")));
+        assert_eq!(recent.find_recent(1), Some(&described("DOC-3", "docs")));
+        assert_eq!(
+            recent.find_recent(2),
+            Some(&described("DEV-7", "Parser rewrite"))
+        );
+    }
+
     fn issue(issue: &str) -> JiraIssue {
         JiraIssue {
             ident: issue.to_string(),
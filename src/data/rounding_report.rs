@@ -0,0 +1,101 @@
+use std::fmt::Write;
+
+use crate::data::NormalizedDay;
+use crate::parsing::time_limit::TimeRange;
+
+/// A booking before and after rounding to the resolution of the export
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RoundedEntry {
+    pub ident: String,
+    pub description: String,
+    pub original: TimeRange,
+    pub rounded: TimeRange,
+    /// Filled into a gap between the bookings
+    pub implicit: bool,
+}
+
+impl RoundedEntry {
+    /// Minutes added by rounding, negative when the booking got shorter
+    pub fn delta_minutes(&self) -> i32 {
+        self.rounded.duration().offset_minutes() - self.original.duration().offset_minutes()
+    }
+}
+
+/// Original and rounded duration of every booking of the day, with the total of the deltas
+pub fn rounding_report(day: &NormalizedDay) -> String {
+    let mut out = String::new();
+    writeln!(out, "Rounding of {}:", day.date).unwrap();
+    for e in &day.rounding {
+        writeln!(
+            out,
+            "{}-{} {} {}{}  {}m -> {}m  {}",
+            e.original.min(),
+            e.original.max(),
+            e.ident,
+            e.description,
+            if e.implicit { " (gap)" } else { "" },
+            e.original.duration().offset_minutes(),
+            e.rounded.duration().offset_minutes(),
+            format_delta(e.delta_minutes())
+        )
+        .unwrap();
+    }
+    let total: i32 = day.rounding.iter().map(RoundedEntry::delta_minutes).sum();
+    writeln!(out, "Total delta: {}", format_delta(total)).unwrap();
+    out
+}
+
+fn format_delta(minutes: i32) -> String {
+    format!("{:+}m", minutes)
+}
+
+#[cfg(test)]
+mod test {
+    use std::num::NonZeroU32;
+
+    use crate::conf::BreaksConfig;
+    use crate::data::active_day::ActiveDayBuilder;
+    use crate::data::rounding_report::rounding_report;
+    use crate::data::test_support::{day_end, day_start, time, work};
+    use crate::data::{Day, Location, Normalizer};
+
+    #[test]
+    fn reports_rounding_deltas() {
+        let n = Normalizer {
+            resolution: NonZeroU32::new(15).unwrap(),
+            breaks_config: BreaksConfig::default(),
+            combine_bookings: true,
+            add_break: false,
+            home_zone: None,
+            holidays: Vec::new(),
+        };
+        let day = ActiveDayBuilder {
+            active_issue: None,
+            actions: vec![
+                day_start("h9:03"),
+                work("9:03", "10:01", "A-1", "dev"),
+                work("10:01", "10:08", "A-2", "call"),
+                day_end("10:08"),
+            ],
+            day: Day::ymd(2022, 1, 6),
+            main_location: Location::Home,
+        }
+        .build();
+        let normalized = n.create_normalized(&day).unwrap();
+
+        let deltas: Vec<i32> = normalized
+            .rounding
+            .iter()
+            .map(|e| e.delta_minutes())
+            .collect();
+        assert_eq!(deltas, vec![2, 8]);
+        assert_eq!(normalized.rounding[0].rounded.min(), time("9"));
+        assert_eq!(
+            rounding_report(&normalized),
+            "Rounding of 2022-01-06:\n\
+             09:03-10:01 A-1 dev  58m -> 60m  +2m\n\
+             10:01-10:08 A-2 call  7m -> 15m  +8m\n\
+             Total delta: +10m\n"
+        );
+    }
+}
@@ -0,0 +1,108 @@
+use std::ops::AddAssign;
+
+use crate::data::{NormalizedDay, Work};
+use crate::parsing::time::Time;
+
+/// Night work before this time
+pub const NIGHT_END: Time = Time::hm(6, 0);
+/// Night work after this time
+pub const NIGHT_START: Time = Time::hm(20, 0);
+
+/// Work minutes per payroll bucket. Work on weekends and holidays is weekend work, also at night
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct Surcharges {
+    pub regular: u32,
+    pub night: u32,
+    pub weekend: u32,
+}
+
+impl Surcharges {
+    /// Splits the work from `start` to `end` into the buckets
+    pub fn classify(day_off: bool, start: Time, end: Time) -> Surcharges {
+        let within = |from: Time, to: Time| from.max(start).minutes_until(to.min(end));
+        let total = within(Time::ZERO, Time::MAX);
+        if day_off {
+            return Surcharges {
+                weekend: total,
+                ..Surcharges::default()
+            };
+        }
+        let night = within(Time::ZERO, NIGHT_END) + within(NIGHT_START, Time::MAX);
+        Surcharges {
+            regular: total - night,
+            night,
+            weekend: 0,
+        }
+    }
+
+    pub fn total(&self) -> u32 {
+        self.regular + self.night + self.weekend
+    }
+}
+
+impl AddAssign for Surcharges {
+    fn add_assign(&mut self, rhs: Self) {
+        self.regular += rhs.regular;
+        self.night += rhs.night;
+        self.weekend += rhs.weekend;
+    }
+}
+
+impl NormalizedDay {
+    pub fn surcharges_of(&self, work: &Work) -> Surcharges {
+        Surcharges::classify(self.day_off, work.start, work.end)
+    }
+
+    /// The buckets of all work entries of the day
+    pub fn surcharges(&self) -> Surcharges {
+        let mut total = Surcharges::default();
+        for w in &self.entries {
+            total += self.surcharges_of(w);
+        }
+        total
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::data::surcharges::Surcharges;
+    use crate::data::test_support::time;
+
+    #[test]
+    fn splits_night_and_weekend_work() {
+        let classify = |day_off, start, end| Surcharges::classify(day_off, time(start), time(end));
+        assert_eq!(
+            classify(false, "8", "17"),
+            Surcharges {
+                regular: 9 * 60,
+                night: 0,
+                weekend: 0
+            }
+        );
+        assert_eq!(
+            classify(false, "5:30", "9"),
+            Surcharges {
+                regular: 3 * 60,
+                night: 30,
+                weekend: 0
+            }
+        );
+        assert_eq!(
+            classify(false, "19", "24"),
+            Surcharges {
+                regular: 60,
+                night: 4 * 60,
+                weekend: 0
+            }
+        );
+        assert_eq!(
+            classify(true, "19", "21"),
+            Surcharges {
+                regular: 0,
+                night: 0,
+                weekend: 2 * 60
+            }
+        );
+        assert_eq!(classify(false, "2", "3").total(), 60);
+    }
+}
@@ -2,6 +2,7 @@ use crate::data::*;
 use crate::parsing::parse_result::ParseResult;
 use crate::parsing::time::Time;
 use crate::parsing::time_limit::TimeRange;
+use crate::parsing::time_relative::TimeRelative;
 use crate::ui::fast_day_start::DayStartBuilder;
 use crate::util::{DefaultTimeline, TimelineProvider};
 use std::sync::Arc;
@@ -12,6 +13,7 @@ pub fn workn(start: &str, end: &str, issue: &str, description: &str) -> Work {
         end: time(end),
         task: JiraIssue::create(issue).unwrap(),
         description: description.to_string(),
+        links: Vec::new(),
     }
 }
 
@@ -63,3 +65,25 @@ pub fn day_end(input: &str) -> Action {
         .map(|ts| Action::DayEnd(DayEnd { ts }))
         .unwrap()
 }
+
+/// Day in the office without breaks, standby, absences and ZA
+pub fn normalized_day(date: Day, entries: Vec<Work>) -> NormalizedDay {
+    let no_breaks = BreaksInfo {
+        work_time: TimeRelative::ZERO,
+        break_time: TimeRelative::ZERO,
+        breaks: Vec::new(),
+    };
+    NormalizedDay {
+        date,
+        location: Location::Office,
+        entries,
+        standby: vec![],
+        absences: vec![],
+        za: vec![],
+        orig_breaks: no_breaks.clone(),
+        final_breaks: no_breaks,
+        notes: String::new(),
+        rounding: Vec::new(),
+        day_off: false,
+    }
+}
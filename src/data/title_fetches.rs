@@ -0,0 +1,80 @@
+use std::collections::BTreeSet;
+
+use chrono::{Duration, NaiveDateTime};
+
+/// Summaries fetched at the same time
+pub const MAX_RUNNING_FETCHES: usize = 4;
+/// Pause after Jira was unreachable, before the next attempt
+pub const OFFLINE_PAUSE_MINUTES: i64 = 5;
+
+/// Issues whose summary is being fetched from Jira or was refused
+#[derive(Debug, Default)]
+pub struct TitleFetches {
+    running: BTreeSet<String>,
+    /// Jira refused them, not asked again in this session
+    refused: BTreeSet<String>,
+    offline_until: Option<NaiveDateTime>,
+}
+
+impl TitleFetches {
+    /// The issues to fetch now, they stay running until their outcome is reported
+    pub fn start(&mut self, missing: Vec<String>, now: NaiveDateTime) -> Vec<String> {
+        if matches!(self.offline_until, Some(until) if now < until) {
+            return Vec::new();
+        }
+        self.offline_until = None;
+        let free = MAX_RUNNING_FETCHES.saturating_sub(self.running.len());
+        let started: Vec<String> = missing
+            .into_iter()
+            .filter(|i| !self.running.contains(i) && !self.refused.contains(i))
+            .take(free)
+            .collect();
+        self.running.extend(started.iter().cloned());
+        started
+    }
+
+    pub fn fetched(&mut self, ident: &str) {
+        self.running.remove(ident);
+    }
+
+    pub fn refused(&mut self, ident: &str) {
+        self.running.remove(ident);
+        self.refused.insert(ident.to_string());
+    }
+
+    /// The issue is fetched again after the pause
+    pub fn unreachable(&mut self, ident: &str, now: NaiveDateTime) {
+        self.running.remove(ident);
+        self.offline_until = Some(now + Duration::minutes(OFFLINE_PAUSE_MINUTES));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::data::title_fetches::{TitleFetches, MAX_RUNNING_FETCHES};
+    use crate::util::{StaticTimeline, TimelineProvider};
+
+    #[test]
+    fn pauses_while_offline() {
+        let timeline = StaticTimeline::parse("2022-01-20 12:00");
+        let missing = |n: usize| (0..n).map(|i| format!("A-{}", i)).collect::<Vec<_>>();
+        let mut fetches = TitleFetches::default();
+
+        let started = fetches.start(missing(6), timeline.now());
+        assert_eq!(started, missing(MAX_RUNNING_FETCHES));
+        assert!(fetches.start(missing(4), timeline.now()).is_empty());
+
+        fetches.fetched("A-0");
+        fetches.refused("A-1");
+        fetches.unreachable("A-2", timeline.now());
+        fetches.unreachable("A-3", timeline.now());
+        assert!(fetches.start(missing(6), timeline.now()).is_empty());
+
+        let later = timeline.now() + chrono::Duration::minutes(5);
+        assert_eq!(
+            fetches.start(missing(6)[2..].to_vec(), later),
+            vec!["A-2", "A-3", "A-4", "A-5"]
+        );
+        assert!(fetches.start(vec!["A-1".to_string()], later).is_empty());
+    }
+}
@@ -0,0 +1,151 @@
+use crate::parsing::time_relative::TimeRelative;
+use chrono::Offset;
+use regex::Regex;
+use serde::{Deserializer, Serializer};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// Fixed offset of a time zone to UTC, e.g. `+01:00`
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd)]
+pub struct UtcOffset {
+    minutes: i32,
+}
+
+impl UtcOffset {
+    pub const UTC: UtcOffset = UtcOffset { minutes: 0 };
+
+    pub fn from_minutes(minutes: i32) -> Option<UtcOffset> {
+        if minutes.abs() <= 14 * 60 {
+            Some(UtcOffset { minutes })
+        } else {
+            None
+        }
+    }
+
+    /// Current offset of the system time zone
+    pub fn local() -> UtcOffset {
+        let seconds = chrono::Local::now().offset().fix().local_minus_utc();
+        UtcOffset {
+            minutes: seconds / 60,
+        }
+    }
+
+    pub fn minutes(&self) -> i32 {
+        self.minutes
+    }
+
+    /// Offset that has to be added to times in this zone to get times in `other`
+    pub fn offset_to(&self, other: UtcOffset) -> TimeRelative {
+        TimeRelative::from_minutes_sat(other.minutes - self.minutes)
+    }
+
+    pub fn parse(input: &str) -> Result<UtcOffset, String> {
+        let input = input.trim();
+        if input.eq_ignore_ascii_case("utc") || input == "Z" {
+            return Ok(UtcOffset::UTC);
+        }
+        if let Some(c) = UTC_OFFSET.captures(input) {
+            let h = i32::from_str(c.name("hours").unwrap().as_str()).unwrap();
+            let m = c
+                .name("minutes")
+                .map(|m| i32::from_str(m.as_str()).unwrap())
+                .unwrap_or_default();
+            if m >= 60 {
+                return Err(format!("Invalid minutes: {}", input));
+            }
+            let minutes = h * 60 + m;
+            let minutes = if &c["sign"] == "-" { -minutes } else { minutes };
+            UtcOffset::from_minutes(minutes).ok_or_else(|| format!("Out of range: {}", input))
+        } else {
+            Err(format!("Invalid utc offset: {}", input))
+        }
+    }
+}
+
+lazy_static::lazy_static! {
+    static ref UTC_OFFSET: Regex = Regex::new(r"^(?P<sign>\+|-)(?P<hours>[0-9]{1,2})(:?(?P<minutes>[0-9]{2}))?$").unwrap();
+}
+
+impl Display for UtcOffset {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let sign = if self.minutes < 0 { '-' } else { '+' };
+        let abs = self.minutes.abs();
+        write!(f, "{}{:02}:{:02}", sign, abs / 60, abs % 60)
+    }
+}
+
+impl serde::Serialize for UtcOffset {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for UtcOffset {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(UtcOffsetVisitor)
+    }
+}
+
+struct UtcOffsetVisitor;
+
+impl<'de> serde::de::Visitor<'de> for UtcOffsetVisitor {
+    type Value = UtcOffset;
+
+    fn expecting(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "Utc offset in format '+hh:mm'")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        UtcOffset::parse(v).map_err(E::custom)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::data::UtcOffset;
+    use crate::parsing::time_relative::TimeRelative;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            UtcOffset::parse("+02:00"),
+            Ok(UtcOffset::from_minutes(120).unwrap())
+        );
+        assert_eq!(
+            UtcOffset::parse("-0530"),
+            Ok(UtcOffset::from_minutes(-330).unwrap())
+        );
+        assert_eq!(
+            UtcOffset::parse("+1"),
+            Ok(UtcOffset::from_minutes(60).unwrap())
+        );
+        assert_eq!(UtcOffset::parse("UTC"), Ok(UtcOffset::UTC));
+        assert!(UtcOffset::parse("+15:00").is_err());
+        assert!(UtcOffset::parse("+01:75").is_err());
+        assert!(UtcOffset::parse("01:00").is_err());
+    }
+
+    #[test]
+    fn test_display_and_offset() {
+        let vienna = UtcOffset::from_minutes(60).unwrap();
+        let new_york = UtcOffset::from_minutes(-300).unwrap();
+        assert_eq!(vienna.to_string(), "+01:00");
+        assert_eq!(new_york.to_string(), "-05:00");
+        assert_eq!(
+            new_york.offset_to(vienna),
+            TimeRelative::from_minutes_sat(360)
+        );
+
+        let json = serde_json::to_string(&new_york).unwrap();
+        assert_eq!(serde_json::from_str::<UtcOffset>(&json).unwrap(), new_york);
+    }
+}
@@ -0,0 +1,183 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use crate::data::{format_hours, Day, NormalizedDay};
+
+/// Deviations from the target of more percentage points are highlighted
+pub const TOLERATED_DEVIATION: i32 = 5;
+
+/// Booked work time per project compared to the planned share of the projects
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UtilizationReport {
+    pub first_day: Day,
+    pub last_day: Day,
+    pub work_minutes: u32,
+    pub entries: Vec<ProjectUtilization>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProjectUtilization {
+    pub project: String,
+    pub minutes: u32,
+    /// Planned share in percent, 0 for projects without target
+    pub target_percent: u32,
+    pub actual_percent: u32,
+}
+
+impl ProjectUtilization {
+    /// Actual minus planned share in percentage points
+    pub fn deviation(&self) -> i32 {
+        self.actual_percent as i32 - self.target_percent as i32
+    }
+
+    pub fn exceeds_tolerance(&self) -> bool {
+        self.deviation().abs() > TOLERATED_DEVIATION
+    }
+}
+
+impl UtilizationReport {
+    /// Collects all days between `first_day` and `last_day`, inclusive.
+    ///
+    /// The project of an issue is the prefix of its id, projects with a target are listed
+    /// even if nothing was booked on them
+    pub fn collect<'a>(
+        first_day: Day,
+        last_day: Day,
+        days: impl IntoIterator<Item = &'a NormalizedDay>,
+        targets: &BTreeMap<String, u32>,
+    ) -> UtilizationReport {
+        let mut minutes: BTreeMap<String, u32> =
+            targets.keys().map(|project| (project.clone(), 0)).collect();
+        for day in days {
+            if day.date < first_day || day.date > last_day {
+                continue;
+            }
+            for w in &day.entries {
+                let ident = &w.task.ident;
+                let (project, _) = ident.split_once('-').unwrap_or((ident, ""));
+                *minutes.entry(project.to_ascii_uppercase()).or_default() += w.minutes();
+            }
+        }
+
+        let work_minutes = minutes.values().sum();
+        let entries = minutes
+            .into_iter()
+            .map(|(project, minutes)| ProjectUtilization {
+                target_percent: targets.get(&project).copied().unwrap_or(0),
+                actual_percent: percent(minutes, work_minutes),
+                project,
+                minutes,
+            })
+            .collect();
+
+        UtilizationReport {
+            first_day,
+            last_day,
+            work_minutes,
+            entries,
+        }
+    }
+
+    /// Hours and actual and planned shares per project as CSV
+    pub fn to_csv(&self) -> String {
+        let mut out = String::new();
+        writeln!(
+            out,
+            "from,to,project,hours,actual_percent,target_percent,deviation"
+        )
+        .unwrap();
+        for e in &self.entries {
+            writeln!(
+                out,
+                "{},{},{},{},{},{},{}",
+                self.first_day,
+                self.last_day,
+                e.project,
+                format_hours(e.minutes),
+                e.actual_percent,
+                e.target_percent,
+                e.deviation()
+            )
+            .unwrap();
+        }
+        out
+    }
+}
+
+/// Rounded share of `minutes` in `total`
+fn percent(minutes: u32, total: u32) -> u32 {
+    if total == 0 {
+        0
+    } else {
+        (minutes * 200 + total) / (2 * total)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use crate::data::test_support::{normalized_day, workn};
+    use crate::data::{Day, UtilizationReport};
+
+    #[test]
+    fn compares_projects_with_targets() {
+        let days = vec![
+            normalized_day(
+                Day::ymd(2022, 1, 3),
+                vec![
+                    workn("8", "9", "MEET-1", "standup"),
+                    workn("9", "14", "PROJ-1", "a"),
+                ],
+            ),
+            normalized_day(
+                Day::ymd(2022, 1, 4),
+                vec![
+                    workn("8", "10", "OPS-3", "b"),
+                    workn("10", "12", "PROJ-2", "c"),
+                ],
+            ),
+            // outside of the period
+            normalized_day(
+                Day::ymd(2022, 1, 10),
+                vec![workn("8", "12", "MAINT-1", "d")],
+            ),
+        ];
+        let targets: BTreeMap<String, u32> = [
+            ("PROJ".to_string(), 65),
+            ("MAINT".to_string(), 15),
+            ("MEET".to_string(), 20),
+        ]
+        .into_iter()
+        .collect();
+
+        let report =
+            UtilizationReport::collect(Day::ymd(2022, 1, 3), Day::ymd(2022, 1, 9), &days, &targets);
+        assert_eq!(report.work_minutes, 10 * 60);
+        let summary: Vec<_> = report
+            .entries
+            .iter()
+            .map(|e| {
+                (
+                    e.project.as_str(),
+                    e.actual_percent,
+                    e.deviation(),
+                    e.exceeds_tolerance(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("MAINT", 0, -15, true),
+                ("MEET", 10, -10, true),
+                ("OPS", 20, 20, true),
+                ("PROJ", 70, 5, false),
+            ]
+        );
+        assert_eq!(
+            report.to_csv().lines().nth(4),
+            Some("2022-01-03,2022-01-09,PROJ,7.00,70,65,5")
+        );
+    }
+}
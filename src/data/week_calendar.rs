@@ -0,0 +1,202 @@
+use crate::data::{Action, ActiveDay, Day};
+use crate::parsing::time::Time;
+
+/// Booked time ranges of the days of a single week
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WeekCalendar {
+    pub first_day: Day,
+    /// One entry per day, monday first
+    pub days: Vec<CalendarDay>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CalendarDay {
+    pub date: Day,
+    pub blocks: Vec<CalendarBlock>,
+    /// Unbooked ranges between the start and the end of the day
+    pub gaps: Vec<(Time, Time)>,
+    /// Number of columns needed to show overlapping blocks side by side
+    pub lanes: usize,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CalendarBlock {
+    pub start: Time,
+    pub end: Time,
+    pub action: Action,
+    /// Column of the block, overlapping blocks are moved to the right
+    pub lane: usize,
+    pub overlaps: bool,
+}
+
+impl WeekCalendar {
+    /// Calendar of the week containing `day`, other days are ignored
+    pub fn collect<'a>(day: Day, days: impl IntoIterator<Item = &'a ActiveDay>) -> WeekCalendar {
+        let first_day = day.first_of_week();
+        let mut calendar = WeekCalendar {
+            first_day,
+            days: (0..7)
+                .map(|offset| CalendarDay {
+                    date: first_day + offset,
+                    blocks: Vec::new(),
+                    gaps: Vec::new(),
+                    lanes: 1,
+                })
+                .collect(),
+        };
+
+        for day in days {
+            if let Some(c) = calendar.days.iter_mut().find(|c| c.date == day.get_day()) {
+                *c = CalendarDay::of(day);
+            }
+        }
+
+        calendar
+    }
+
+    /// Earliest start and latest end of all days, full hours
+    pub fn time_range(&self) -> Option<(Time, Time)> {
+        let ranges = self.days.iter().flat_map(|d| {
+            let gaps = d.gaps.iter().copied();
+            gaps.chain(d.blocks.iter().map(|b| (b.start, b.end)))
+        });
+        let (start, end) = ranges.fold(None, |acc, (s, e)| match acc {
+            None => Some((s, e)),
+            Some((start, end)) => Some((std::cmp::min(start, s), std::cmp::max(end, e))),
+        })?;
+        let end = if end.m() == 0 && end.s() == 0 {
+            end
+        } else {
+            end.next_h()
+        };
+        Some((Time::hm(start.h(), 0), end))
+    }
+}
+
+impl CalendarDay {
+    fn of(day: &ActiveDay) -> CalendarDay {
+        let mut blocks: Vec<CalendarBlock> = day
+            .actions()
+            .iter()
+            .filter(|a| a.duration_minutes().is_some())
+            .filter_map(|a| {
+                Some(CalendarBlock {
+                    start: a.start()?,
+                    end: a.end()?,
+                    action: a.clone(),
+                    lane: 0,
+                    overlaps: false,
+                })
+            })
+            .collect();
+        blocks.sort_by_key(|b| (b.start, b.end));
+
+        // the end of the last block in each lane
+        let mut lane_ends: Vec<Time> = Vec::new();
+        for block in &mut blocks {
+            block.lane = match lane_ends.iter().position(|end| *end <= block.start) {
+                Some(lane) => lane,
+                None => {
+                    lane_ends.push(block.start);
+                    lane_ends.len() - 1
+                }
+            };
+            lane_ends[block.lane] = block.end;
+        }
+        let ranges: Vec<_> = blocks.iter().map(|b| (b.start, b.end)).collect();
+        for (index, block) in blocks.iter_mut().enumerate() {
+            block.overlaps = ranges
+                .iter()
+                .enumerate()
+                .any(|(i, (s, e))| i != index && *s < block.end && block.start < *e);
+        }
+
+        CalendarDay {
+            date: day.get_day(),
+            gaps: gaps(day, &blocks),
+            lanes: lane_ends.len().max(1),
+            blocks,
+        }
+    }
+}
+
+/// Ranges between day start and day end without work or absences
+fn gaps(day: &ActiveDay, blocks: &[CalendarBlock]) -> Vec<(Time, Time)> {
+    let booked: Vec<_> = blocks
+        .iter()
+        .filter(|b| !matches!(b.action, Action::Standby(_)))
+        .map(|b| (b.start, b.end))
+        .collect();
+    let day_start = day.actions().iter().find_map(|a| match a {
+        Action::DayStart(d) => Some(d.ts),
+        _ => None,
+    });
+    let day_end = day.actions().iter().find_map(|a| match a {
+        Action::DayEnd(d) => Some(d.ts),
+        _ => None,
+    });
+    let first = day_start.or_else(|| booked.iter().map(|b| b.0).min());
+    let last = day_end.or_else(|| booked.iter().map(|b| b.1).max());
+    let (mut current, last) = match (first, last) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return Vec::new(),
+    };
+
+    let mut result = Vec::new();
+    // blocks are sorted by start
+    for (start, end) in booked {
+        if start > current && current < last {
+            result.push((current, std::cmp::min(start, last)));
+        }
+        current = std::cmp::max(current, end);
+    }
+    if current < last {
+        result.push((current, last));
+    }
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use crate::data::test_support::{time, work};
+    use crate::data::{Action, ActiveDay, Day, DayEnd, DayStart, Location, WeekCalendar};
+    use crate::parsing::time::Time;
+
+    #[test]
+    fn finds_gaps_and_overlaps() {
+        let mut day = ActiveDay::new(Day::ymd(2022, 1, 5), Location::Office, None);
+        day.add_action(Action::DayStart(DayStart {
+            location: Location::Office,
+            ts: time("8"),
+        }));
+        day.add_action(work("9", "11", "A-1", "a"));
+        day.add_action(work("10", "12", "A-2", "b"));
+        day.add_action(work("13", "15", "A-1", "c"));
+        day.add_action(Action::DayEnd(DayEnd { ts: time("16") }));
+
+        let calendar = WeekCalendar::collect(Day::ymd(2022, 1, 6), [&day]);
+        assert_eq!(calendar.first_day, Day::ymd(2022, 1, 3));
+        assert_eq!(calendar.days.len(), 7);
+        assert!(calendar.days[0].blocks.is_empty());
+
+        let wednesday = &calendar.days[2];
+        assert_eq!(wednesday.lanes, 2);
+        let lanes: Vec<_> = wednesday.blocks.iter().map(|b| b.lane).collect();
+        assert_eq!(lanes, vec![0, 1, 0]);
+        let overlaps: Vec<_> = wednesday.blocks.iter().map(|b| b.overlaps).collect();
+        assert_eq!(overlaps, vec![true, true, false]);
+        assert_eq!(
+            wednesday.gaps,
+            vec![
+                (time("8"), time("9")),
+                (time("12"), time("13")),
+                (time("15"), time("16"))
+            ]
+        );
+
+        assert_eq!(
+            calendar.time_range(),
+            Some((Time::hm(8, 0), Time::hm(16, 0)))
+        );
+    }
+}
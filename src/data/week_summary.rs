@@ -0,0 +1,228 @@
+use crate::data::{format_minutes, Day, NormalizedDay, Surcharges, Work};
+
+/// Work and standby totals of the days of a single week
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WeekSummary {
+    pub first_day: Day,
+    /// One entry per day, monday first
+    pub days: Vec<DaySummary>,
+}
+
+/// Work of a day and of its week up to that day compared to the targets
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct TargetProgress {
+    pub day_minutes: u32,
+    pub day_target: u32,
+    pub week_minutes: u32,
+    pub week_target: u32,
+}
+
+impl TargetProgress {
+    /// Describes the first cap exceeded by the work time, `None` caps are not checked
+    pub fn exceeded_cap(&self, max_day: Option<u32>, max_week: Option<u32>) -> Option<String> {
+        match (max_day, max_week) {
+            (Some(max), _) if self.day_minutes > max => Some(format!(
+                "Work time of the day {} exceeds the cap of {}",
+                format_minutes(self.day_minutes),
+                format_minutes(max)
+            )),
+            (_, Some(max)) if self.week_minutes > max => Some(format!(
+                "Work time of the week {} exceeds the cap of {}",
+                format_minutes(self.week_minutes),
+                format_minutes(max)
+            )),
+            _ => None,
+        }
+    }
+
+    /// Like [TargetProgress::exceeded_cap], but only if the work time of the day grew since
+    /// `before`. Only the day changes, so the week grows with it
+    pub fn exceeded_cap_since(
+        &self,
+        before: &TargetProgress,
+        max_day: Option<u32>,
+        max_week: Option<u32>,
+    ) -> Option<String> {
+        if self.day_minutes <= before.day_minutes {
+            None
+        } else {
+            self.exceeded_cap(max_day, max_week)
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DaySummary {
+    pub date: Day,
+    pub work_minutes: u32,
+    pub standby_minutes: u32,
+    /// The work minutes split into payroll buckets
+    pub surcharges: Surcharges,
+}
+
+impl WeekSummary {
+    /// Summary of the week containing `day`, other days are ignored
+    pub fn collect<'a>(day: Day, days: impl IntoIterator<Item = &'a NormalizedDay>) -> WeekSummary {
+        let first_day = day.first_of_week();
+        let mut summary = WeekSummary {
+            first_day,
+            days: (0..7)
+                .map(|offset| DaySummary {
+                    date: first_day + offset,
+                    work_minutes: 0,
+                    standby_minutes: 0,
+                    surcharges: Surcharges::default(),
+                })
+                .collect(),
+        };
+
+        for day in days {
+            if let Some(s) = summary.days.iter_mut().find(|s| s.date == day.date) {
+                s.work_minutes += day.entries.iter().map(Work::minutes).sum::<u32>();
+                s.standby_minutes += day.standby.iter().map(|s| s.minutes()).sum::<u32>();
+                s.surcharges += day.surcharges();
+            }
+        }
+
+        summary
+    }
+
+    pub fn work_minutes(&self) -> u32 {
+        self.days.iter().map(|d| d.work_minutes).sum()
+    }
+
+    pub fn standby_minutes(&self) -> u32 {
+        self.days.iter().map(|d| d.standby_minutes).sum()
+    }
+
+    pub fn surcharges(&self) -> Surcharges {
+        let mut total = Surcharges::default();
+        for d in &self.days {
+            total += d.surcharges;
+        }
+        total
+    }
+
+    /// Progress of `day`, later days of the week are not included in the week-to-date time
+    pub fn progress(&self, day: Day, day_target: u32, week_target: u32) -> TargetProgress {
+        let until_day = self.days.iter().filter(|d| d.date <= day);
+        TargetProgress {
+            day_minutes: self
+                .days
+                .iter()
+                .find(|d| d.date == day)
+                .map_or(0, |d| d.work_minutes),
+            day_target,
+            week_minutes: until_day.map(|d| d.work_minutes).sum(),
+            week_target,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::data::test_support::{normalized_day, workn};
+    use crate::data::week_summary::{TargetProgress, WeekSummary};
+    use crate::data::{Day, NormalizedDay, Standby};
+    use crate::parsing::time::Time;
+
+    #[test]
+    fn sums_work_and_standby_separately() {
+        let days = vec![
+            NormalizedDay {
+                standby: vec![Standby {
+                    start: Time::hm(18, 0),
+                    end: Time::MAX,
+                }],
+                ..normalized_day(
+                    Day::ymd(2022, 1, 4),
+                    vec![workn("8", "12", "A-1", "a"), workn("13", "17", "A-1", "a")],
+                )
+            },
+            NormalizedDay {
+                standby: vec![Standby {
+                    start: Time::ZERO,
+                    end: Time::hm(6, 0),
+                }],
+                ..normalized_day(Day::ymd(2022, 1, 5), vec![])
+            },
+            normalized_day(Day::ymd(2022, 1, 10), vec![workn("8", "12", "A-1", "a")]),
+        ];
+
+        let summary = WeekSummary::collect(Day::ymd(2022, 1, 6), &days);
+        assert_eq!(summary.first_day, Day::ymd(2022, 1, 3));
+        assert_eq!(summary.days.len(), 7);
+        assert_eq!(summary.days[1].work_minutes, 8 * 60);
+        assert_eq!(summary.days[1].standby_minutes, 6 * 60);
+        assert_eq!(summary.work_minutes(), 8 * 60);
+        assert_eq!(summary.standby_minutes(), 12 * 60);
+        assert_eq!(summary.surcharges().regular, 8 * 60);
+
+        assert_eq!(
+            summary.progress(Day::ymd(2022, 1, 4), 8 * 60, 40 * 60),
+            TargetProgress {
+                day_minutes: 8 * 60,
+                day_target: 8 * 60,
+                week_minutes: 8 * 60,
+                week_target: 40 * 60,
+            }
+        );
+        assert_eq!(
+            summary
+                .progress(Day::ymd(2022, 1, 3), 8 * 60, 40 * 60)
+                .week_minutes,
+            0
+        );
+    }
+
+    #[test]
+    fn checks_work_time_caps() {
+        let progress = TargetProgress {
+            day_minutes: 10 * 60 + 30,
+            day_target: 8 * 60,
+            week_minutes: 45 * 60,
+            week_target: 40 * 60,
+        };
+        assert_eq!(progress.exceeded_cap(None, None), None);
+        assert_eq!(progress.exceeded_cap(Some(11 * 60), Some(48 * 60)), None);
+        assert_eq!(
+            progress.exceeded_cap(Some(10 * 60), Some(40 * 60)),
+            Some("Work time of the day 10h 30m exceeds the cap of 10h 00m".to_string())
+        );
+        assert_eq!(
+            progress.exceeded_cap(None, Some(40 * 60)),
+            Some("Work time of the week 45h 00m exceeds the cap of 40h 00m".to_string())
+        );
+    }
+
+    #[test]
+    fn checks_caps_only_when_work_is_added() {
+        let before = TargetProgress {
+            day_minutes: 10 * 60 + 30,
+            day_target: 8 * 60,
+            week_minutes: 45 * 60,
+            week_target: 40 * 60,
+        };
+        let cap = (Some(10 * 60), Some(40 * 60));
+
+        // a day end or a changed description adds no work
+        assert_eq!(before.exceeded_cap_since(&before, cap.0, cap.1), None);
+        let less = TargetProgress {
+            day_minutes: 9 * 60,
+            week_minutes: 43 * 60 + 30,
+            ..before
+        };
+        assert_eq!(less.exceeded_cap_since(&before, cap.0, cap.1), None);
+
+        let more = TargetProgress {
+            day_minutes: 11 * 60,
+            week_minutes: 45 * 60 + 30,
+            ..before
+        };
+        assert_eq!(
+            more.exceeded_cap_since(&before, cap.0, cap.1),
+            Some("Work time of the day 11h 00m exceeds the cap of 10h 00m".to_string())
+        );
+        assert_eq!(more.exceeded_cap_since(&before, None, None), None);
+    }
+}
@@ -1,5 +1,6 @@
 use crate::data::{JiraIssue, TimedAction};
 use crate::parsing::time::Time;
+use crate::parsing::time_relative::TimeRelative;
 use std::cmp::Ordering;
 
 #[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
@@ -8,6 +9,122 @@ pub struct Work {
     pub end: Time,
     pub task: JiraIssue,
     pub description: String,
+    /// URLs belonging to the work, like pull requests or meeting links
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub links: Vec<String>,
+}
+
+impl Work {
+    pub fn minutes(&self) -> u32 {
+        self.start.minutes_until(self.end)
+    }
+
+    /// Work that ends before it starts continues after midnight on the following day
+    pub fn crosses_midnight(&self) -> bool {
+        self.end < self.start
+    }
+
+    /// Splits work crossing midnight into the part until 24:00 and the continuation
+    /// starting at 00:00 of the following day.
+    pub fn split_at_midnight(self) -> (Work, Option<Work>) {
+        if self.crosses_midnight() {
+            let continuation = Work {
+                start: Time::ZERO,
+                end: self.end,
+                task: self.task.clone(),
+                description: self.description.clone(),
+                links: self.links.clone(),
+            };
+            (
+                Work {
+                    end: Time::MAX,
+                    ..self
+                },
+                Some(continuation),
+            )
+        } else {
+            (self, None)
+        }
+    }
+
+    /// Joins `next` starting at the end of this work on the same issue.
+    /// Different comments are kept separated by `; `.
+    pub fn merge(&self, next: &Work) -> Option<Work> {
+        if self.end != next.start || self.task.ident != next.task.ident {
+            return None;
+        }
+        let description = if next.description.is_empty() || next.description == self.description {
+            self.description.clone()
+        } else if self.description.is_empty() {
+            next.description.clone()
+        } else {
+            format!("{}; {}", self.description, next.description)
+        };
+        let mut links = self.links.clone();
+        links.extend(
+            next.links
+                .iter()
+                .filter(|l| !self.links.contains(l))
+                .cloned(),
+        );
+        Some(Work {
+            start: self.start,
+            end: next.end,
+            task: self.task.clone(),
+            description,
+            links,
+        })
+    }
+
+    /// Splits at `at`, the second half is booked on `task` with `description`
+    pub fn split_at(self, at: Time, task: JiraIssue, description: String) -> Option<(Work, Work)> {
+        if self.crosses_midnight() || at <= self.start || at >= self.end {
+            return None;
+        }
+        let second = Work {
+            start: at,
+            end: self.end,
+            task,
+            description,
+            links: Vec::new(),
+        };
+        Some((Work { end: at, ..self }, second))
+    }
+
+    /// Splits into consecutive parts on each issue, with durations proportional to the weights.
+    /// Parts that would be empty are left out.
+    pub fn split_weighted(&self, shares: &[(JiraIssue, u32)]) -> Option<Vec<Work>> {
+        let total_weight: u32 = shares.iter().map(|(_, w)| w).sum();
+        if total_weight == 0 || self.end <= self.start {
+            return None;
+        }
+        let minutes = (self.end - self.start).offset_minutes() as i64;
+
+        let mut result = Vec::new();
+        let mut start = self.start;
+        let mut weight = 0;
+        for (task, w) in shares {
+            weight += w;
+            let end = if weight == total_weight {
+                self.end
+            } else {
+                let offset = minutes * weight as i64 / total_weight as i64;
+                self.start
+                    .try_add_relative(TimeRelative::from_minutes_sat(offset as i32))?
+            };
+            if start < end {
+                result.push(Work {
+                    start,
+                    end,
+                    task: task.clone(),
+                    description: self.description.clone(),
+                    links: self.links.clone(),
+                });
+            }
+            start = end;
+        }
+        Some(result)
+    }
 }
 
 impl PartialOrd<Self> for Work {
@@ -47,3 +164,60 @@ pub struct WorkEnd {
     pub ts: Time,
     pub task: JiraIssue,
 }
+
+#[cfg(test)]
+mod test {
+    use crate::data::test_support::{time, workn};
+    use crate::data::JiraIssue;
+
+    #[test]
+    fn merge_adjacent_work() {
+        let first = workn("9", "10", "A-1", "daily");
+        assert_eq!(
+            first.merge(&workn("10", "11", "A-1", "daily")),
+            Some(workn("9", "11", "A-1", "daily"))
+        );
+        assert_eq!(
+            first.merge(&workn("10", "11", "A-1", "review")),
+            Some(workn("9", "11", "A-1", "daily; review"))
+        );
+        assert_eq!(first.merge(&workn("10:15", "11", "A-1", "daily")), None);
+        assert_eq!(first.merge(&workn("10", "11", "B-1", "daily")), None);
+    }
+
+    #[test]
+    fn split_work() {
+        let work = workn("9", "11", "A-1", "daily");
+        let issue = JiraIssue::create("B-2").unwrap();
+        assert_eq!(
+            work.clone()
+                .split_at(time("10"), issue.clone(), "review".to_string()),
+            Some((
+                workn("9", "10", "A-1", "daily"),
+                workn("10", "11", "B-2", "review")
+            ))
+        );
+        assert_eq!(work.split_at(time("11"), issue, "review".to_string()), None);
+    }
+
+    #[test]
+    fn split_weighted_work() {
+        let work = workn("9", "12", "A-1", "refactoring");
+        let issue = |id| JiraIssue::create(id).unwrap();
+        assert_eq!(
+            work.split_weighted(&[(issue("A-1"), 1), (issue("B-2"), 1)]),
+            Some(vec![
+                workn("9", "10:30", "A-1", "refactoring"),
+                workn("10:30", "12", "B-2", "refactoring")
+            ])
+        );
+        assert_eq!(
+            work.split_weighted(&[(issue("A-1"), 70), (issue("B-2"), 30), (issue("C-3"), 0)]),
+            Some(vec![
+                workn("9", "11:06", "A-1", "refactoring"),
+                workn("11:06", "12", "B-2", "refactoring")
+            ])
+        );
+        assert_eq!(work.split_weighted(&[(issue("A-1"), 0)]), None);
+    }
+}
@@ -0,0 +1,154 @@
+use crate::data::{Day, NormalizedDay, Work};
+use crate::parsing::time::Time;
+
+/// Uninterrupted work outside of meetings of at least this length counts as deep work
+pub const DEEP_WORK_MINUTES: u32 = 90;
+
+/// Working hours, meeting load and deep work of the days of a period
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WorkPatterns {
+    pub first_day: Day,
+    pub last_day: Day,
+    /// Days with booked work
+    pub days: u32,
+    pub average_start: Option<Time>,
+    pub average_end: Option<Time>,
+    pub work_minutes: u32,
+    pub meeting_minutes: u32,
+    pub deep_work_minutes: u32,
+}
+
+impl WorkPatterns {
+    /// Collects all days between `first_day` and `last_day`, inclusive
+    pub fn collect<'a>(
+        first_day: Day,
+        last_day: Day,
+        days: impl IntoIterator<Item = &'a NormalizedDay>,
+        meeting_prefixes: &[String],
+    ) -> WorkPatterns {
+        let mut patterns = WorkPatterns {
+            first_day,
+            last_day,
+            days: 0,
+            average_start: None,
+            average_end: None,
+            work_minutes: 0,
+            meeting_minutes: 0,
+            deep_work_minutes: 0,
+        };
+        let (mut start_sum, mut end_sum) = (0, 0);
+        for day in days {
+            if day.date < first_day || day.date > last_day {
+                continue;
+            }
+            let mut entries: Vec<&Work> = day.entries.iter().collect();
+            entries.sort_by_key(|w| (w.start, w.end));
+            let (first, last_end) = match (entries.first(), entries.iter().map(|w| w.end).max()) {
+                (Some(first), Some(end)) => (first.start, end),
+                _ => continue,
+            };
+            patterns.days += 1;
+            start_sum += minutes_of_day(first);
+            end_sum += minutes_of_day(last_end);
+
+            let mut stretch: Option<(Time, Time)> = None;
+            for w in entries {
+                let minutes = w.minutes();
+                patterns.work_minutes += minutes;
+                let meeting = is_meeting(&w.task.ident, meeting_prefixes);
+                if meeting {
+                    patterns.meeting_minutes += minutes;
+                }
+                stretch = match stretch {
+                    Some((start, end)) if !meeting && w.start <= end => {
+                        Some((start, end.max(w.end)))
+                    }
+                    _ => {
+                        patterns.deep_work_minutes += deep_work(stretch);
+                        (!meeting).then(|| (w.start, w.end))
+                    }
+                };
+            }
+            patterns.deep_work_minutes += deep_work(stretch);
+        }
+
+        if patterns.days > 0 {
+            patterns.average_start = Some(time_of_day(start_sum / patterns.days));
+            patterns.average_end = Some(time_of_day(end_sum / patterns.days));
+        }
+        patterns
+    }
+
+    /// Percentage of the work time spent in deep work
+    pub fn deep_work_percent(&self) -> Option<u32> {
+        self.deep_work_minutes
+            .checked_mul(100)
+            .and_then(|minutes| minutes.checked_div(self.work_minutes))
+    }
+}
+
+/// Whether the issue id starts with one of the prefixes, ignoring case
+fn is_meeting(ident: &str, meeting_prefixes: &[String]) -> bool {
+    let ident = ident.to_ascii_uppercase();
+    meeting_prefixes
+        .iter()
+        .any(|p| ident.starts_with(&p.to_ascii_uppercase()))
+}
+
+fn deep_work(stretch: Option<(Time, Time)>) -> u32 {
+    match stretch {
+        Some((start, end)) if start.minutes_until(end) >= DEEP_WORK_MINUTES => {
+            start.minutes_until(end)
+        }
+        _ => 0,
+    }
+}
+
+fn minutes_of_day(t: Time) -> u32 {
+    Time::ZERO.minutes_until(t)
+}
+
+fn time_of_day(minutes: u32) -> Time {
+    Time::hm(minutes / 60, minutes % 60)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::data::test_support::{normalized_day, workn};
+    use crate::data::{Day, WorkPatterns};
+    use crate::parsing::time::Time;
+
+    #[test]
+    fn averages_days_and_splits_meetings_from_deep_work() {
+        let days = vec![
+            normalized_day(
+                Day::ymd(2022, 1, 3),
+                vec![
+                    workn("8", "9", "MEET-1", "standup"),
+                    workn("9", "10", "A-1", "a"),
+                    workn("10", "11", "A-2", "b"),
+                    workn("11", "11:30", "I-15", "sync"),
+                    workn("12:30", "13:30", "A-1", "a"),
+                ],
+            ),
+            normalized_day(
+                Day::ymd(2022, 1, 4),
+                vec![workn("9", "12", "A-1", "a"), workn("13", "18", "A-1", "a")],
+            ),
+            // outside of the period
+            normalized_day(Day::ymd(2022, 1, 10), vec![workn("5", "6", "A-1", "a")]),
+        ];
+        let prefixes = vec!["meet".to_string(), "I-15".to_string()];
+
+        let patterns =
+            WorkPatterns::collect(Day::ymd(2022, 1, 3), Day::ymd(2022, 1, 9), &days, &prefixes);
+        assert_eq!(patterns.days, 2);
+        assert_eq!(patterns.average_start, Some(Time::hm(8, 30)));
+        assert_eq!(patterns.average_end, Some(Time::hm(15, 45)));
+        assert_eq!(patterns.work_minutes, 270 + 480);
+        assert_eq!(patterns.meeting_minutes, 90);
+        // 9-11 on the first day, both blocks of the second day
+        assert_eq!(patterns.deep_work_minutes, 120 + 480);
+        assert_eq!(patterns.deep_work_percent(), Some(80));
+    }
+}
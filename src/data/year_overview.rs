@@ -0,0 +1,99 @@
+use chrono::Datelike;
+
+use crate::data::{Day, NormalizedDay, Work};
+
+/// Booked work of every day of a year
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct YearOverview {
+    pub year: i32,
+    /// One entry per day, January 1st first
+    pub work_minutes: Vec<u32>,
+}
+
+impl YearOverview {
+    /// Days of other years are ignored
+    pub fn collect<'a>(
+        year: i32,
+        days: impl IntoIterator<Item = &'a NormalizedDay>,
+    ) -> YearOverview {
+        let last: chrono::NaiveDate = Day::ymd(year, 12, 31).into();
+        let mut work_minutes = vec![0; last.ordinal() as usize];
+        for day in days {
+            let date: chrono::NaiveDate = day.date.into();
+            if date.year() == year {
+                work_minutes[date.ordinal0() as usize] +=
+                    day.entries.iter().map(Work::minutes).sum::<u32>();
+            }
+        }
+        YearOverview { year, work_minutes }
+    }
+
+    /// Columns of the heatmap, one per week with monday first, `None` for days of other years
+    pub fn weeks(&self) -> Vec<[Option<(Day, u32)>; 7]> {
+        let first = Day::ymd(self.year, 1, 1);
+        let mut weeks = Vec::new();
+        let mut week = [None; 7];
+        for (offset, minutes) in self.work_minutes.iter().enumerate() {
+            let day = first + offset as i64;
+            let weekday = day.day_of_week().num_days_from_monday() as usize;
+            week[weekday] = Some((day, *minutes));
+            if weekday == 6 {
+                weeks.push(week);
+                week = [None; 7];
+            }
+        }
+        if week.iter().any(Option::is_some) {
+            weeks.push(week);
+        }
+        weeks
+    }
+
+    /// Heat from 0 for nothing booked to 4 for at least the daily target
+    pub fn level(minutes: u32, daily_target: u32) -> usize {
+        if minutes == 0 {
+            0
+        } else if minutes >= daily_target {
+            4
+        } else {
+            1 + (3 * minutes / daily_target) as usize
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::data::test_support::{normalized_day, workn};
+    use crate::data::{Day, YearOverview};
+
+    #[test]
+    fn collects_work_per_day() {
+        let day = normalized_day(
+            Day::ymd(2022, 1, 4),
+            vec![
+                workn("8", "10", "A-1", "a"),
+                workn("10", "11:30", "A-2", "b"),
+            ],
+        );
+
+        let overview = YearOverview::collect(2022, [&day]);
+        assert_eq!(overview.work_minutes.len(), 365);
+        assert_eq!(overview.work_minutes[3], 210);
+        assert_eq!(overview.work_minutes.iter().sum::<u32>(), 210);
+        assert!(YearOverview::collect(2021, [&day])
+            .work_minutes
+            .iter()
+            .all(|m| *m == 0));
+
+        // 2022 starts on a saturday
+        let weeks = overview.weeks();
+        assert_eq!(weeks.len(), 53);
+        assert_eq!(weeks[0][0], None);
+        assert_eq!(weeks[0][5], Some((Day::ymd(2022, 1, 1), 0)));
+        assert_eq!(weeks[1][1], Some((Day::ymd(2022, 1, 4), 210)));
+        assert_eq!(weeks[52][5], Some((Day::ymd(2022, 12, 31), 0)));
+
+        assert_eq!(YearOverview::level(0, 480), 0);
+        assert_eq!(YearOverview::level(210, 480), 2);
+        assert_eq!(YearOverview::level(500, 480), 4);
+    }
+}
@@ -0,0 +1,174 @@
+use crate::data::{Absence, Day, NormalizedDay, Work};
+
+/// Compensation time ledger: overtime accrues, ZA is taken from the balance
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ZaBalance {
+    /// Balance after all completed days, negative if more ZA was taken than accrued
+    pub balance_minutes: i64,
+    /// ZA booked after the last completed day
+    pub planned_za_minutes: u32,
+}
+
+impl ZaBalance {
+    /// Balance of all days up to `last_complete`, ZA on later days is planned.
+    ///
+    /// Every workday with work, ZA or absences has to reach `target_minutes`, all work on days
+    /// off is overtime. ZA counts towards the target and is subtracted from the balance
+    /// afterwards, absences count with their credit and are not subtracted.
+    pub fn collect<'a>(
+        target_minutes: u32,
+        last_complete: Day,
+        days: impl IntoIterator<Item = &'a NormalizedDay>,
+    ) -> ZaBalance {
+        let mut balance_minutes = 0i64;
+        let mut planned_za_minutes = 0;
+
+        for day in days {
+            let za_minutes = day.za.iter().map(|z| z.minutes()).sum::<u32>();
+            if day.date > last_complete {
+                planned_za_minutes += za_minutes;
+                continue;
+            }
+            if day.entries.is_empty() && za_minutes == 0 && day.absences.is_empty() {
+                continue;
+            }
+            let credited_minutes = day.absences.iter().map(Absence::credit).sum::<u32>();
+
+            let work_minutes = day.entries.iter().map(Work::minutes).sum::<u32>();
+            let target_minutes = if day.day_off { 0 } else { target_minutes };
+            let overtime =
+                (work_minutes + za_minutes + credited_minutes) as i64 - target_minutes as i64;
+            balance_minutes += overtime - za_minutes as i64;
+        }
+
+        ZaBalance {
+            balance_minutes,
+            planned_za_minutes,
+        }
+    }
+
+    /// Planned ZA is not covered by the accrued overtime
+    pub fn planned_exceeds_balance(&self) -> bool {
+        self.planned_za_minutes as i64 > self.balance_minutes
+    }
+}
+
+/// Balance with sign, e.g. `-1h 30m`
+pub fn format_balance(minutes: i64) -> String {
+    let sign = if minutes < 0 { "-" } else { "+" };
+    let minutes = minutes.unsigned_abs();
+    format!("{}{}h {:02}m", sign, minutes / 60, minutes % 60)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::data::test_support::{normalized_day, time, workn};
+    use crate::data::za_balance::{format_balance, ZaBalance};
+    use crate::data::{Absence, Day, NormalizedDay, Work, ZA};
+
+    fn day(date: Day, entries: Vec<Work>, za: Vec<ZA>) -> NormalizedDay {
+        NormalizedDay {
+            za,
+            ..normalized_day(date, entries)
+        }
+    }
+
+    fn za(start: &str, end: &str) -> ZA {
+        ZA {
+            start: time(start),
+            end: time(end),
+        }
+    }
+
+    #[test]
+    fn accrues_overtime_and_subtracts_za() {
+        let days = vec![
+            day(
+                Day::ymd(2022, 1, 3),
+                vec![workn("8", "18", "A-1", "a")],
+                vec![],
+            ),
+            day(
+                Day::ymd(2022, 1, 4),
+                vec![workn("8", "17", "A-1", "a")],
+                vec![],
+            ),
+            day(
+                Day::ymd(2022, 1, 5),
+                vec![workn("8", "12", "A-1", "a")],
+                vec![za("12", "14")],
+            ),
+            day(Day::ymd(2022, 1, 6), vec![], vec![]),
+            day(Day::ymd(2022, 1, 10), vec![], vec![za("8", "16")]),
+        ];
+
+        let balance = ZaBalance::collect(8 * 60, Day::ymd(2022, 1, 6), &days);
+        assert_eq!(
+            balance,
+            ZaBalance {
+                balance_minutes: 2 * 60 + 60 - 2 * 60 - 2 * 60,
+                planned_za_minutes: 8 * 60,
+            }
+        );
+        assert!(balance.planned_exceeds_balance());
+        assert_eq!(format_balance(balance.balance_minutes), "-1h 00m");
+        assert_eq!(format_balance(90), "+1h 30m");
+    }
+
+    #[test]
+    fn credits_partial_absences() {
+        let absence = |start: &str, end: &str, credited_minutes| Absence {
+            category: "vacation".to_string(),
+            start: time(start),
+            end: time(end),
+            credited_minutes,
+        };
+        let days = vec![
+            NormalizedDay {
+                absences: vec![absence("8", "10", None)],
+                ..day(
+                    Day::ymd(2022, 1, 3),
+                    vec![workn("10", "16", "A-1", "a")],
+                    vec![],
+                )
+            },
+            NormalizedDay {
+                absences: vec![absence("8", "12", Some(3 * 60 + 51))],
+                ..day(
+                    Day::ymd(2022, 1, 4),
+                    vec![workn("12", "16", "A-1", "a")],
+                    vec![],
+                )
+            },
+            NormalizedDay {
+                absences: vec![absence("8", "12", Some(3 * 60 + 51))],
+                ..day(Day::ymd(2022, 1, 5), vec![], vec![])
+            },
+        ];
+
+        let balance = ZaBalance::collect(7 * 60 + 42, Day::ymd(2022, 1, 6), &days);
+        assert_eq!(balance.balance_minutes, 18 + 9 - (3 * 60 + 51));
+    }
+
+    #[test]
+    fn work_on_days_off_is_overtime() {
+        let days = vec![
+            NormalizedDay {
+                day_off: true,
+                ..day(
+                    Day::ymd(2022, 1, 8),
+                    vec![workn("10", "12", "A-1", "a")],
+                    vec![],
+                )
+            },
+            day(
+                Day::ymd(2022, 1, 10),
+                vec![workn("8", "12", "A-1", "a")],
+                vec![],
+            ),
+        ];
+
+        let balance = ZaBalance::collect(8 * 60, Day::ymd(2022, 1, 9), &days);
+        assert_eq!(balance.balance_minutes, 2 * 60);
+    }
+}
@@ -0,0 +1,92 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use crate::data::{ActiveDay, Day};
+
+/// A year of days, so the year overview is served from the cache
+const CAPACITY: usize = 400;
+
+/// Parsed days, dropping the least recently used.
+///
+/// Entries are only used while the size and modification time of their file are unchanged,
+/// days stored by another process, like `quarble capture`, are read again
+#[derive(Debug, Default)]
+pub struct DayCache(Mutex<Entries>);
+
+#[derive(Debug, Default)]
+struct Entries {
+    days: BTreeMap<Day, Cached>,
+    /// Incremented on every access, to find the least recently used entry
+    clock: u64,
+}
+
+#[derive(Debug)]
+struct Cached {
+    file: FileState,
+    day: Option<ActiveDay>,
+    used: u64,
+}
+
+/// Size and modification time of a day file, `None` if it does not exist
+pub type FileState = Option<(u64, SystemTime)>;
+
+/// The state of the file, `Err` if it cannot be determined and must not be cached
+pub fn file_state(path: &Path) -> Result<FileState, ()> {
+    match std::fs::metadata(path) {
+        Ok(m) => m.modified().map(|t| Some((m.len(), t))).map_err(|_| ()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(_) => Err(()),
+    }
+}
+
+impl DayCache {
+    /// The cached day, if its file is still in `file` state
+    pub fn get(&self, day: Day, file: FileState) -> Option<Option<ActiveDay>> {
+        let mut entries = self.0.lock().ok()?;
+        entries.clock += 1;
+        let clock = entries.clock;
+        let cached = entries.days.get_mut(&day).filter(|c| c.file == file)?;
+        cached.used = clock;
+        Some(cached.day.clone())
+    }
+
+    pub fn insert(&self, day: Day, file: FileState, loaded: Option<ActiveDay>) {
+        let mut entries = match self.0.lock() {
+            Ok(entries) => entries,
+            Err(_) => return,
+        };
+        if entries.days.len() >= CAPACITY && !entries.days.contains_key(&day) {
+            let oldest = entries
+                .days
+                .iter()
+                .min_by_key(|(_, c)| c.used)
+                .map(|(day, _)| *day);
+            if let Some(oldest) = oldest {
+                entries.days.remove(&oldest);
+            }
+        }
+        entries.clock += 1;
+        let used = entries.clock;
+        entries.days.insert(
+            day,
+            Cached {
+                file,
+                day: loaded,
+                used,
+            },
+        );
+    }
+
+    pub fn invalidate(&self, day: Day) {
+        if let Ok(mut entries) = self.0.lock() {
+            entries.days.remove(&day);
+        }
+    }
+
+    #[cfg(test)]
+    pub fn len(&self) -> usize {
+        self.0.lock().unwrap().days.len()
+    }
+}
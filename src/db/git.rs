@@ -0,0 +1,168 @@
+use std::path::Path;
+use std::process::{Command, Output};
+
+/// Day files, the only files committed by [commit_file]
+const DAY_FILES: &str = "????-??-??.json";
+
+/// Working tree state of the git repository containing the DB directory
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct GitStatus {
+    /// Uncommitted changes of the day files in the DB directory
+    pub dirty: bool,
+    /// Commits not pushed to the upstream branch
+    pub ahead: u32,
+    /// Commits of the upstream branch that are not merged
+    pub behind: u32,
+}
+
+impl GitStatus {
+    /// Short description, `None` if the repository is clean and in sync
+    pub fn label(self) -> Option<String> {
+        let mut parts = Vec::new();
+        if self.dirty {
+            parts.push("uncommitted changes".to_string());
+        }
+        if self.ahead > 0 {
+            parts.push(format!("{} ahead", self.ahead));
+        }
+        if self.behind > 0 {
+            parts.push(format!("{} behind", self.behind));
+        }
+        if parts.is_empty() {
+            None
+        } else {
+            Some(format!("git: {}", parts.join(", ")))
+        }
+    }
+
+    /// Parses the output of `git status --porcelain=v2 --branch`
+    fn parse(porcelain: &str) -> GitStatus {
+        let mut status = GitStatus::default();
+        for line in porcelain.lines() {
+            if let Some(ab) = line.strip_prefix("# branch.ab ") {
+                for count in ab.split_whitespace() {
+                    if let Some(ahead) = count.strip_prefix('+') {
+                        status.ahead = ahead.parse().unwrap_or(0);
+                    } else if let Some(behind) = count.strip_prefix('-') {
+                        status.behind = behind.parse().unwrap_or(0);
+                    }
+                }
+            } else if !line.starts_with('#') && !line.is_empty() {
+                status.dirty = true;
+            }
+        }
+        status
+    }
+}
+
+/// Status of the DB directory, `None` if it is not part of a git repository.
+///
+/// Other files like the recent issues are never committed and are ignored
+pub fn status(dir: &Path) -> Option<GitStatus> {
+    match git(
+        dir,
+        &["status", "--porcelain=v2", "--branch", "--", DAY_FILES],
+    ) {
+        Ok(output) => Some(GitStatus::parse(&String::from_utf8_lossy(&output.stdout))),
+        Err(e) => {
+            log::debug!("No git status for {}: {}", dir.display(), e);
+            None
+        }
+    }
+}
+
+/// Commits the file in `dir` if it changed since the last commit
+pub fn commit_file(dir: &Path, file: &str, message: &str) -> Result<(), String> {
+    git(dir, &["add", "--", file])?;
+    // exits with 1 if the staged file differs from the last commit
+    let unchanged = Command::new("git")
+        .current_dir(dir)
+        .args(["diff", "--cached", "--quiet", "--", file])
+        .status()
+        .map_err(|e| format!("Cannot run git: {}", e))?
+        .success();
+    if !unchanged {
+        git(dir, &["commit", "--quiet", "-m", message, "--", file])?;
+    }
+    Ok(())
+}
+
+fn git(dir: &Path, args: &[&str]) -> Result<Output, String> {
+    let output = Command::new("git")
+        .current_dir(dir)
+        .args(args)
+        .output()
+        .map_err(|e| format!("Cannot run git: {}", e))?;
+    if output.status.success() {
+        Ok(output)
+    } else {
+        Err(format!(
+            "git {} failed: {}",
+            args[0],
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::process::Command;
+
+    use tempfile::TempDir;
+
+    use crate::db::git::{commit_file, status, GitStatus};
+
+    #[test]
+    fn parses_porcelain_status() {
+        let clean = "# branch.oid abc\n# branch.head main\n# branch.upstream origin/main\n# branch.ab +0 -0\n";
+        assert_eq!(GitStatus::parse(clean), GitStatus::default());
+        assert_eq!(GitStatus::parse(clean).label(), None);
+
+        let diverged = "# branch.head main\n# branch.ab +2 -1\n1 .M N... 100644 100644 100644 a b 2022-01-10.json\n";
+        let status = GitStatus::parse(diverged);
+        assert_eq!(
+            status,
+            GitStatus {
+                dirty: true,
+                ahead: 2,
+                behind: 1
+            }
+        );
+        assert_eq!(
+            status.label().unwrap(),
+            "git: uncommitted changes, 2 ahead, 1 behind"
+        );
+    }
+
+    #[test]
+    fn commits_changed_files() {
+        if Command::new("git").arg("--version").output().is_err() {
+            return;
+        }
+        let dir = TempDir::new().unwrap();
+        let run = |args: &[&str]| {
+            let output = Command::new("git")
+                .current_dir(dir.path())
+                .args(args)
+                .output()
+                .unwrap();
+            String::from_utf8_lossy(&output.stdout).to_string()
+        };
+        assert_eq!(status(dir.path()), None);
+        run(&["init", "--quiet"]);
+        run(&["config", "user.name", "Quarble"]);
+        run(&["config", "user.email", "quarble@example.com"]);
+
+        std::fs::write(dir.path().join("2022-01-10.json"), "{}").unwrap();
+        assert!(status(dir.path()).unwrap().dirty);
+        commit_file(dir.path(), "2022-01-10.json", "2022-01-10: 0 entries").unwrap();
+        assert_eq!(status(dir.path()), Some(GitStatus::default()));
+
+        std::fs::write(dir.path().join("recent.json"), "{}").unwrap();
+        assert_eq!(status(dir.path()), Some(GitStatus::default()));
+
+        // storing the same content again creates no commit
+        commit_file(dir.path(), "2022-01-10.json", "2022-01-10: 0 entries").unwrap();
+        assert_eq!(run(&["log", "--format=%s"]), "2022-01-10: 0 entries\n");
+    }
+}
@@ -0,0 +1,98 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::thread::JoinHandle;
+
+/// Starts the hook with `input` on stdin, failures are logged.
+///
+/// Does not wait for the hook to finish, slow scripts must not block the UI
+pub fn run(command: &[String], event: &str, input: &serde_json::Value) {
+    if let Err(e) = start(command, event, input) {
+        log::warn!("Cannot run the {} hook: {}", event, e);
+    }
+}
+
+fn start(
+    command: &[String],
+    event: &str,
+    input: &serde_json::Value,
+) -> Result<JoinHandle<Result<(), String>>, String> {
+    let (program, args) = command
+        .split_first()
+        .ok_or_else(|| "no command configured".to_string())?;
+    let mut child = Command::new(program)
+        .args(args)
+        .env("QUARBLE_EVENT", event)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Cannot start {}: {}", program, e))?;
+
+    // a day is smaller than the pipe buffer, closing stdin signals the end of the input
+    if let Some(mut stdin) = child.stdin.take() {
+        if let Err(e) = stdin.write_all(input.to_string().as_bytes()) {
+            log::warn!("Cannot write to the {} hook {}: {}", event, program, e);
+        }
+    }
+    let event = event.to_string();
+    let program = program.clone();
+    Ok(std::thread::spawn(move || {
+        let result = match child.wait_with_output() {
+            Ok(output) if output.status.success() => Ok(()),
+            Ok(output) => Err(format!(
+                "{} failed with {}: {}",
+                program,
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )),
+            Err(e) => Err(format!("Cannot wait for {}: {}", program, e)),
+        };
+        if let Err(e) = &result {
+            log::warn!("The {} hook failed: {}", event, e);
+        }
+        result
+    }))
+}
+
+#[cfg(test)]
+mod test {
+    use std::process::Command;
+
+    use tempfile::TempDir;
+
+    use crate::db::hook::start;
+
+    #[test]
+    fn passes_input_on_stdin() {
+        if Command::new("sh").arg("-c").arg("true").output().is_err() {
+            return;
+        }
+        let dir = TempDir::new().unwrap();
+        let out = dir.path().join("out.json");
+        let command = vec![
+            "sh".to_string(),
+            "-c".to_string(),
+            "echo $QUARBLE_EVENT > \"$1\" && cat >> \"$1\"".to_string(),
+            "hook".to_string(),
+            out.display().to_string(),
+        ];
+        let input = serde_json::json!({"event": "store"});
+        start(&command, "store", &input)
+            .unwrap()
+            .join()
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&out).unwrap(),
+            "store\n{\"event\":\"store\"}"
+        );
+
+        let failing = vec!["sh".to_string(), "-c".to_string(), "exit 3".to_string()];
+        assert!(start(&failing, "store", &input)
+            .unwrap()
+            .join()
+            .unwrap()
+            .is_err());
+        assert!(start(&[], "store", &input).is_err());
+    }
+}
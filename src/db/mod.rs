@@ -3,12 +3,22 @@ use std::fs::{DirEntry, File, OpenOptions};
 use std::io::{BufReader, BufWriter, ErrorKind};
 use std::ops::RangeBounds;
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 
 use thiserror::Error;
 
-use crate::data::{ActiveDay, Day, RecentIssuesData};
+use crate::conf::HooksConfig;
+use crate::data::{Action, ActiveDay, Day, Draft, InputHistory, JiraIssue, RecentIssuesData};
+use crate::parsing::locale::DateFormat;
 use crate::parsing::time::Time;
 
+pub use git::GitStatus;
+
+use cache::DayCache;
+
+mod cache;
+mod git;
+mod hook;
 #[cfg(test)]
 mod test;
 
@@ -24,13 +34,44 @@ pub enum DBErr {
     InvalidDBFile(PathBuf, serde_json::Error),
     #[error("Failed to write {0}")]
     FailedToWrite(PathBuf),
+    #[error("{0} is closed")]
+    DayClosed(Day),
+    #[error("{0} does not exist any more")]
+    DayMissing(Day),
+    #[error("{0} changed, load the preview again")]
+    DayChanged(Day),
 }
 
 type DBResult<T> = Result<T, DBErr>;
 
+/// Written before a day is stored and removed afterwards.
+/// A journal found on startup means that storing the day was interrupted.
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct Journal {
+    /// The stored day before the interrupted write
+    pub previous: Option<ActiveDay>,
+    pub pending: ActiveDay,
+}
+
 #[derive(Debug, Clone)]
 pub struct DB {
     root: PathBuf,
+    /// Commits every stored day to the git repository the DB directory is part of
+    git_commits: bool,
+    hooks: HooksConfig,
+    /// Shared by all clones, so the UI and background loads see the same days
+    cache: Arc<DayCache>,
+    /// Days stored since the last [DB::take_commits], shared by all clones
+    pending_commits: Arc<Mutex<Vec<PendingCommit>>>,
+    /// Keeps the git processes of concurrent [DB::sync_git] calls apart
+    git_lock: Arc<Mutex<()>>,
+}
+
+/// Commit of a stored day, run by [DB::sync_git]
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingCommit {
+    file: String,
+    message: String,
 }
 
 impl DB {
@@ -38,6 +79,11 @@ impl DB {
         if location.is_dir() {
             Ok(DB {
                 root: location.to_path_buf(),
+                git_commits: false,
+                hooks: HooksConfig::default(),
+                cache: Arc::default(),
+                pending_commits: Arc::default(),
+                git_lock: Arc::default(),
             })
         } else if location.exists() {
             Err(DBErr::NotADirectory(location.display().to_string()))
@@ -50,11 +96,50 @@ impl DB {
             } else {
                 Ok(DB {
                     root: location.to_path_buf(),
+                    git_commits: false,
+                    hooks: HooksConfig::default(),
+                    cache: Arc::default(),
+                    pending_commits: Arc::default(),
+                    git_lock: Arc::default(),
                 })
             }
         }
     }
 
+    pub fn set_git_commits(&mut self, enabled: bool) {
+        self.git_commits = enabled;
+    }
+
+    pub fn set_hooks(&mut self, hooks: &HooksConfig) {
+        if self.hooks != *hooks {
+            self.hooks = hooks.clone();
+        }
+    }
+
+    /// Commits of the days stored since the last call, they are run by [DB::sync_git]
+    pub fn take_commits(&self) -> Vec<PendingCommit> {
+        std::mem::take(&mut *self.pending_commits.lock().unwrap())
+    }
+
+    /// Runs the commits and returns the status of the git repository afterwards,
+    /// `None` if commits are disabled or there is no repository.
+    ///
+    /// Waits for git, the UI calls it on a background thread
+    pub fn sync_git(&self, commits: Vec<PendingCommit>) -> Option<GitStatus> {
+        let _git = self.git_lock.lock().unwrap();
+        for commit in commits {
+            // a failed commit leaves the day stored, the working tree is shown as dirty
+            if let Err(e) = git::commit_file(&self.root, &commit.file, &commit.message) {
+                log::warn!("Cannot commit {}: {}", commit.file, e);
+            }
+        }
+        if self.git_commits {
+            git::status(&self.root)
+        } else {
+            None
+        }
+    }
+
     pub fn get_day(&self, day: Day) -> DBResult<ActiveDay> {
         let work_day = self.load_day(day)?;
         if let Some(work_day) = work_day {
@@ -64,28 +149,17 @@ impl DB {
         }
     }
 
+    /// An empty day at the location of the previous workday.
+    ///
+    /// Work that was not ended on the previous workday is not carried over, the start of day
+    /// wizard offers to continue it, see [DB::open_issue_before]
     pub fn new_day(&self, day: Day) -> DBResult<ActiveDay> {
-        let mut prev_day = day.prev_day();
-        let mut remaining = 6;
-        let prev_work_day = loop {
-            if let Some(work_day) = self.load_day(prev_day)? {
-                break Some(work_day);
-            } else if remaining <= 0 {
-                break None;
-            }
-            {
-                remaining -= 1;
-                prev_day = prev_day.prev_day();
-            }
-        };
-
         let new_day = ActiveDay::new(
             day,
-            prev_work_day
-                .as_ref()
+            self.prev_work_day(day)?
                 .map(|w| w.main_location().clone())
                 .unwrap_or_default(),
-            prev_work_day.and_then(|w| w.current_issue(Time::MAX)),
+            None,
         );
 
         eprintln!("New: {:?}", new_day);
@@ -93,9 +167,51 @@ impl DB {
         Ok(new_day)
     }
 
+    /// The issue started on the last workday before `day` and never ended
+    pub fn open_issue_before(&self, day: Day) -> DBResult<Option<JiraIssue>> {
+        Ok(self
+            .prev_work_day(day)?
+            .and_then(|w| w.current_issue(Time::MAX)))
+    }
+
+    /// The last stored day of the week before `day`
+    fn prev_work_day(&self, day: Day) -> DBResult<Option<ActiveDay>> {
+        let mut prev_day = day.prev_day();
+        let mut remaining = 6;
+        loop {
+            if let Some(work_day) = self.load_day(prev_day)? {
+                return Ok(Some(work_day));
+            } else if remaining <= 0 {
+                return Ok(None);
+            }
+            remaining -= 1;
+            prev_day = prev_day.prev_day();
+        }
+    }
+
     pub fn load_day(&self, day: Day) -> DBResult<Option<ActiveDay>> {
         let to_load = self.work_day_path(day);
-        self.read_file(to_load)
+        let state = match cache::file_state(&to_load) {
+            Ok(state) => state,
+            Err(()) => return self.read_file(to_load),
+        };
+        if let Some(cached) = self.cache.get(day, state) {
+            return Ok(cached);
+        }
+        let loaded: Option<ActiveDay> = self.read_file(to_load)?;
+        self.cache.insert(day, state, loaded.clone());
+        Ok(loaded)
+    }
+
+    /// All stored days in `range`, ordered by day
+    pub fn load_range(&self, range: impl RangeBounds<Day>) -> DBResult<Vec<ActiveDay>> {
+        let mut result = Vec::new();
+        for day in self.list_days(range)? {
+            if let Some(loaded) = self.load_day(day)? {
+                result.push(loaded);
+            }
+        }
+        Ok(result)
     }
 
     pub fn list_days(&self, range: impl RangeBounds<Day>) -> DBResult<Vec<Day>> {
@@ -116,15 +232,141 @@ impl DB {
     }
 
     pub fn store_day(&self, work_day: &ActiveDay) -> DBResult<()> {
+        let journal = Journal {
+            // an unreadable day must not be rolled back to "not stored"
+            previous: self.load_day(work_day.get_day())?,
+            pending: work_day.clone(),
+        };
+        self.write_journal(&journal)?;
+        self.write_day(work_day)?;
+        remove_file(self.journal_file())?;
+        if self.git_commits {
+            let day = work_day.get_day();
+            self.pending_commits.lock().unwrap().push(PendingCommit {
+                file: work_day_file(day),
+                message: format!(
+                    "{}: {} entries",
+                    day.format(DateFormat::Iso),
+                    work_day.actions().len()
+                ),
+            });
+        }
+        run_hook(&self.hooks.after_store, "store", work_day);
+        Ok(())
+    }
+
+    /// Runs the export hook, the export itself is stored with [DB::store_day]
+    pub fn exported(&self, work_day: &ActiveDay) {
+        run_hook(&self.hooks.after_export, "export", work_day);
+    }
+
+    fn write_day(&self, work_day: &ActiveDay) -> DBResult<()> {
+        self.cache.invalidate(work_day.get_day());
         let to_store = self.work_day_path(work_day.get_day());
+        Self::write_synced(&to_store, |w| serde_json::to_writer_pretty(w, work_day))
+    }
 
-        let file = Self::open_for_write(&to_store)?;
+    fn journal_file(&self) -> PathBuf {
+        self.root.join("journal.json")
+    }
 
-        let write = BufWriter::new(file);
-        serde_json::to_writer_pretty(write, work_day)
-            .map_err(|_| DBErr::FailedToWrite(to_store.clone()))?;
+    fn write_journal(&self, journal: &Journal) -> DBResult<()> {
+        Self::write_synced(&self.journal_file(), |w| serde_json::to_writer(w, journal))
+    }
 
-        Ok(())
+    /// The journal of a write that did not complete
+    pub fn interrupted_write(&self) -> DBResult<Option<Journal>> {
+        self.read_file(self.journal_file())
+    }
+
+    /// Completes the interrupted write
+    pub fn replay_journal(&self, journal: &Journal) -> DBResult<()> {
+        self.write_day(&journal.pending)?;
+        remove_file(self.journal_file())
+    }
+
+    /// Restores the day as it was before the interrupted write
+    pub fn roll_back_journal(&self, journal: &Journal) -> DBResult<()> {
+        match &journal.previous {
+            Some(previous) => self.write_day(previous)?,
+            None => {
+                let day = journal.pending.get_day();
+                self.cache.invalidate(day);
+                remove_file(self.work_day_path(day))?
+            }
+        }
+        remove_file(self.journal_file())
+    }
+
+    /// Replaces the continuations of the `replaced` actions on the following day with
+    /// `continuations`, see [Action::continued_by].
+    ///
+    /// Nothing is written if the following day is closed and would have to change
+    pub fn store_continuations(
+        &self,
+        day: Day,
+        continuations: Vec<Action>,
+        replaced: &[Action],
+    ) -> DBResult<()> {
+        match self.continued_day(day, continuations, replaced)? {
+            Some(next_day) => self.store_day(&next_day),
+            None => Ok(()),
+        }
+    }
+
+    /// Fails if [DB::store_continuations] would have to change a closed day
+    pub fn check_continuations(
+        &self,
+        day: Day,
+        continuations: &[Action],
+        replaced: &[Action],
+    ) -> DBResult<()> {
+        self.continued_day(day, continuations.to_vec(), replaced)
+            .map(|_| ())
+    }
+
+    /// The following day with the continuations replaced, `None` if it does not change
+    fn continued_day(
+        &self,
+        day: Day,
+        continuations: Vec<Action>,
+        replaced: &[Action],
+    ) -> DBResult<Option<ActiveDay>> {
+        let next = day.next_day();
+        let mut next_day = match self.load_day(next)? {
+            Some(next_day) => next_day,
+            None if continuations.is_empty() => return Ok(None),
+            None => self.new_day(next)?,
+        };
+        let before = next_day.actions().len();
+        next_day
+            .actions_mut()
+            .retain(|a| !replaced.iter().any(|r| r.continued_by(a)));
+        if continuations.is_empty() && next_day.actions().len() == before {
+            return Ok(None);
+        }
+        if next_day.is_closed() {
+            return Err(DBErr::DayClosed(next));
+        }
+        for continuation in continuations {
+            next_day.add_action(continuation);
+        }
+        Ok(Some(next_day))
+    }
+
+    /// Writes a temporary file that replaces `to_store` once it is on disk, so a crash leaves
+    /// either the old or the new content
+    fn write_synced<F>(to_store: &Path, write: F) -> DBResult<()>
+    where
+        F: FnOnce(&mut BufWriter<File>) -> serde_json::Result<()>,
+    {
+        let failed = || DBErr::FailedToWrite(to_store.to_owned());
+        let tmp = to_store.with_extension("json.tmp");
+        let mut writer = BufWriter::new(Self::open_for_write(&tmp)?);
+        write(&mut writer).map_err(|_| failed())?;
+        let file = writer.into_inner().map_err(|_| failed())?;
+        file.sync_all().map_err(|_| failed())?;
+        std::fs::rename(&tmp, to_store).map_err(|_| failed())
     }
 
     fn open_for_write(to_store: &Path) -> DBResult<File> {
@@ -155,9 +397,39 @@ impl DB {
             .map_err(|_| DBErr::FailedToWrite(to_store.clone()))
     }
 
+    fn draft_file(&self) -> PathBuf {
+        self.root.join("draft.json")
+    }
+
+    pub fn load_draft(&self) -> DBResult<Option<Draft>> {
+        self.read_file(self.draft_file())
+    }
+
+    pub fn store_draft(&self, draft: &Draft) -> DBResult<()> {
+        Self::write_synced(&self.draft_file(), |w| serde_json::to_writer(w, draft))
+    }
+
+    pub fn clear_draft(&self) -> DBResult<()> {
+        remove_file(self.draft_file())
+    }
+
+    fn input_history_file(&self) -> PathBuf {
+        self.root.join("history.json")
+    }
+
+    pub fn load_input_history(&self) -> DBResult<InputHistory> {
+        let loaded: Option<InputHistory> = self.read_file(self.input_history_file())?;
+        Ok(loaded.unwrap_or_default())
+    }
+
+    pub fn store_input_history(&self, history: &InputHistory) -> DBResult<()> {
+        Self::write_synced(&self.input_history_file(), |w| {
+            serde_json::to_writer(w, history)
+        })
+    }
+
     fn work_day_path(&self, day: Day) -> PathBuf {
-        let formatted = format!("{}.json", day);
-        self.root.join(formatted)
+        self.root.join(work_day_file(day))
     }
 
     fn read_file<T: serde::de::DeserializeOwned>(&self, to_load: PathBuf) -> DBResult<Option<T>> {
@@ -171,6 +443,37 @@ impl DB {
         }
     }
 }
+
+fn work_day_file(day: Day) -> String {
+    format!("{}.json", day.format(DateFormat::Iso))
+}
+
+/// Passes the event and the day to the hook, nothing happens without a configured command
+fn run_hook(command: &[String], event: &str, work_day: &ActiveDay) {
+    if command.is_empty() {
+        return;
+    }
+    match serde_json::to_value(work_day) {
+        Ok(day) => hook::run(
+            command,
+            event,
+            &serde_json::json!({ "event": event, "day": day }),
+        ),
+        Err(e) => log::warn!(
+            "Cannot serialize {} for the hook: {}",
+            work_day.get_day(),
+            e
+        ),
+    }
+}
+
+/// Removes the file, if it exists
+fn remove_file(to_remove: PathBuf) -> DBResult<()> {
+    handle_not_found(std::fs::remove_file(&to_remove))
+        .map(|_| ())
+        .map_err(|e| DBErr::CannotOpen(to_remove, e))
+}
+
 fn handle_not_found<T>(e: std::io::Result<T>) -> std::io::Result<Option<T>> {
     match e {
         Ok(t) => Ok(Some(t)),
@@ -1,10 +1,11 @@
 use crate::data::test_support::*;
 use crate::data::*;
 
-use crate::db::{DBResult, DB};
+use crate::db::{DBErr, DBResult, Journal, DB};
 use crate::parsing::time::Time;
 use crate::util::{DefaultTimeline, TimelineProvider};
 use chrono::Datelike;
+use std::collections::BTreeMap;
 use std::ops::Deref;
 use tempfile::TempDir;
 
@@ -52,6 +53,17 @@ fn test_load_just_stored_day() {
     assert_eq!(reloaded, day0_data);
 }
 
+#[test]
+fn keeps_closed_days_closed() {
+    let db = TmpDB::new();
+
+    let mut day0_data = build_test_day(*DAY0);
+    day0_data.set_closed(true);
+    db.store_day(&day0_data).unwrap();
+
+    assert!(db.get_day(*DAY0).unwrap().is_closed());
+}
+
 #[test]
 fn test_load_previous_day() {
     let db = TmpDB::new();
@@ -64,10 +76,12 @@ fn test_load_previous_day() {
     }));
     db.store_day(&day0_data).unwrap();
 
-    let next_day = db.get_day(DAY0.next(&SimpleDayForwarder)).unwrap();
+    // the start of day wizard offers to continue the issue
+    let next = DAY0.next(&SimpleDayForwarder);
+    assert_eq!(db.get_day(next).unwrap().active_issue(), None);
     assert_eq!(
-        next_day.active_issue(),
-        Some(&JiraIssue {
+        db.open_issue_before(next).unwrap(),
+        Some(JiraIssue {
             ident: "A-1".to_string(),
             description: None,
             default_action: Some("Description1".to_string()),
@@ -75,6 +89,121 @@ fn test_load_previous_day() {
     );
 }
 
+#[test]
+fn test_store_continuation() {
+    let db = TmpDB::new();
+
+    let mut day0_data = ActiveDay::new(*DAY0, Location::Office, None);
+    let (night, continuation) = work("22", "2", "N-1", "night shift").split_at_midnight();
+    day0_data.add_action(night);
+    db.store_day(&day0_data).unwrap();
+    db.store_continuations(*DAY0, vec![continuation.unwrap()], &[])
+        .unwrap();
+
+    let reloaded = db.load_day(*DAY0).unwrap().unwrap();
+    assert_eq!(
+        reloaded.actions().iter().collect::<Vec<_>>(),
+        vec![&work("22", "24", "N-1", "night shift")]
+    );
+
+    let next_day = db.load_day(DAY0.next_day()).unwrap().unwrap();
+    assert_eq!(next_day.main_location(), &Location::Office);
+    assert_eq!(
+        next_day.actions().iter().collect::<Vec<_>>(),
+        vec![&work("0", "2", "N-1", "night shift")]
+    );
+}
+
+#[test]
+fn store_continuation_replaces_previous() {
+    let db = TmpDB::new();
+
+    let (night, continuation) = work("22", "2", "N-1", "night shift").split_at_midnight();
+    db.store_continuations(*DAY0, vec![continuation.unwrap()], &[])
+        .unwrap();
+
+    // the entry is edited to end later
+    let (_, continuation) = work("22", "3", "N-1", "night shift").split_at_midnight();
+    db.store_continuations(*DAY0, vec![continuation.unwrap()], &[night])
+        .unwrap();
+
+    let next_day = db.load_day(DAY0.next_day()).unwrap().unwrap();
+    assert_eq!(
+        next_day.actions().iter().collect::<Vec<_>>(),
+        vec![&work("0", "3", "N-1", "night shift")]
+    );
+}
+
+#[test]
+fn deleted_work_removes_its_continuation() {
+    let db = TmpDB::new();
+
+    let (night, continuation) = work("22", "2", "N-1", "night shift").split_at_midnight();
+    db.store_continuations(*DAY0, vec![continuation.unwrap()], &[])
+        .unwrap();
+    let mut next_day = db.load_day(DAY0.next_day()).unwrap().unwrap();
+    next_day.add_action(work("9", "10", "A-1", "other"));
+    db.store_day(&next_day).unwrap();
+
+    db.store_continuations(*DAY0, Vec::new(), &[night]).unwrap();
+
+    let next_day = db.load_day(DAY0.next_day()).unwrap().unwrap();
+    assert_eq!(
+        next_day.actions().iter().collect::<Vec<_>>(),
+        vec![&work("9", "10", "A-1", "other")]
+    );
+}
+
+#[test]
+fn shortened_work_removes_its_continuation() {
+    let db = TmpDB::new();
+
+    let (night, continuation) = work("22", "2", "N-1", "night shift").split_at_midnight();
+    db.store_continuations(*DAY0, vec![continuation.unwrap()], &[])
+        .unwrap();
+
+    // the entry is edited to end before midnight
+    let (_, continuation) = work("22", "23", "N-1", "night shift").split_at_midnight();
+    assert_eq!(continuation, None);
+    db.store_continuations(*DAY0, Vec::new(), &[night]).unwrap();
+
+    let next_day = db.load_day(DAY0.next_day()).unwrap().unwrap();
+    assert!(next_day.actions().is_empty());
+}
+
+#[test]
+fn continuations_do_not_change_closed_days() {
+    let db = TmpDB::new();
+
+    let (night, continuation) = work("22", "2", "N-1", "night shift").split_at_midnight();
+    let continuation = continuation.unwrap();
+    db.store_continuations(*DAY0, vec![continuation.clone()], &[])
+        .unwrap();
+    let mut next_day = db.load_day(DAY0.next_day()).unwrap().unwrap();
+    next_day.set_closed(true);
+    db.store_day(&next_day).unwrap();
+
+    let (_, longer) = work("22", "3", "N-1", "night shift").split_at_midnight();
+    let longer = vec![longer.unwrap()];
+    assert!(matches!(
+        db.check_continuations(*DAY0, &longer, &[night.clone()]),
+        Err(DBErr::DayClosed(d)) if d == DAY0.next_day()
+    ));
+    assert!(db
+        .store_continuations(*DAY0, longer, &[night.clone()])
+        .is_err());
+    assert!(db.store_continuations(*DAY0, Vec::new(), &[night]).is_err());
+
+    let next_day = db.load_day(DAY0.next_day()).unwrap().unwrap();
+    assert_eq!(
+        next_day.actions().iter().collect::<Vec<_>>(),
+        vec![&continuation]
+    );
+    // days without continuations of the changed actions are not touched
+    db.check_continuations(*DAY0, &[], &[work("8", "9", "A-1", "day")])
+        .unwrap();
+}
+
 #[test]
 fn store_load_active_day() {
     let orig = ActiveDayBuilder {
@@ -148,12 +277,144 @@ fn store_load_recent() {
                 },
             },
         ],
+        use_counts: BTreeMap::from_iter([("R-453433".to_string(), 3)]),
+        pinned: vec![JiraIssue::create("P-1").unwrap()],
     };
 
     db.store_recent(&with_entries).unwrap();
     assert_eq!(db.load_recent().unwrap(), with_entries)
 }
 
+#[test]
+fn stores_and_clears_drafts() {
+    let db = TmpDB::new();
+    assert_eq!(db.load_draft().unwrap(), None);
+
+    let draft = Draft {
+        view: "book".to_string(),
+        input: "9 10 A-1 half typed".to_string(),
+    };
+    db.store_draft(&draft).unwrap();
+    assert_eq!(db.load_draft().unwrap(), Some(draft));
+    assert!(db.list_days(..).unwrap().is_empty());
+
+    db.clear_draft().unwrap();
+    assert_eq!(db.load_draft().unwrap(), None);
+    db.clear_draft().unwrap();
+}
+
+#[test]
+fn stores_the_input_history() {
+    let db = TmpDB::new();
+    assert_eq!(db.load_input_history().unwrap(), InputHistory::default());
+
+    let mut history = InputHistory::default();
+    history.add("book", "9 10 A-1 daily");
+    db.store_input_history(&history).unwrap();
+    assert_eq!(db.load_input_history().unwrap(), history);
+    assert!(db.list_days(..).unwrap().is_empty());
+}
+
+#[test]
+fn recovers_interrupted_writes() {
+    let db = TmpDB::new();
+    let stored = build_test_day(*DAY0);
+    db.store_day(&stored).unwrap();
+    assert_eq!(db.interrupted_write().unwrap(), None);
+
+    let mut pending = stored.clone();
+    pending.add_action(work("11", "12", "A-1", "lost"));
+    let journal = Journal {
+        previous: Some(stored.clone()),
+        pending: pending.clone(),
+    };
+
+    db.write_journal(&journal).unwrap();
+    assert_eq!(db.interrupted_write().unwrap(), Some(journal.clone()));
+    db.roll_back_journal(&journal).unwrap();
+    assert_eq!(db.load_day(*DAY0).unwrap(), Some(stored));
+    assert_eq!(db.interrupted_write().unwrap(), None);
+
+    db.write_journal(&journal).unwrap();
+    db.replay_journal(&journal).unwrap();
+    assert_eq!(db.load_day(*DAY0).unwrap(), Some(pending.clone()));
+    assert_eq!(db.interrupted_write().unwrap(), None);
+
+    let new_day = Journal {
+        previous: None,
+        pending: build_test_day(DAY0.next_day()),
+    };
+    db.write_journal(&new_day).unwrap();
+    db.roll_back_journal(&new_day).unwrap();
+    assert_eq!(db.load_day(DAY0.next_day()).unwrap(), None);
+}
+
+#[test]
+fn stored_day_replaces_cached_day() {
+    let db = TmpDB::new();
+    let mut day0_data = build_test_day(*DAY0);
+    db.store_day(&day0_data).unwrap();
+    assert_eq!(db.load_day(*DAY0).unwrap(), Some(day0_data.clone()));
+
+    day0_data.add_action(work("12", "13", "A-1", "changed"));
+    db.store_day(&day0_data).unwrap();
+    assert_eq!(db.load_day(*DAY0).unwrap(), Some(day0_data));
+}
+
+#[test]
+fn keeps_unreadable_days() {
+    let db = TmpDB::new();
+    let path = db.work_day_path(*DAY0);
+    std::fs::write(&path, "{ broken").unwrap();
+
+    assert!(db.store_day(&build_test_day(*DAY0)).is_err());
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "{ broken");
+    assert_eq!(db.interrupted_write().unwrap(), None);
+}
+
+#[test]
+fn queues_commits_of_stored_days() {
+    let dir = TempDir::new().unwrap();
+    let mut db = DB::init(dir.as_ref()).unwrap();
+    db.store_day(&build_test_day(*DAY0)).unwrap();
+    assert!(db.take_commits().is_empty());
+
+    db.set_git_commits(true);
+    db.store_day(&build_test_day(*DAY0)).unwrap();
+    db.clone()
+        .store_day(&build_test_day(DAY0.next_day()))
+        .unwrap();
+    assert_eq!(db.take_commits().len(), 2);
+    assert!(db.take_commits().is_empty());
+}
+
+#[test]
+fn reloads_days_stored_by_other_processes() {
+    let db = TmpDB::new();
+    let mut day0_data = build_test_day(*DAY0);
+    db.store_day(&day0_data).unwrap();
+    assert_eq!(db.load_day(*DAY0).unwrap(), Some(day0_data.clone()));
+
+    let other = DB::init(&db.root).unwrap();
+    day0_data.add_action(work("12", "13", "A-1", "captured"));
+    other.store_day(&day0_data).unwrap();
+    assert_eq!(db.load_day(*DAY0).unwrap(), Some(day0_data));
+}
+
+#[test]
+fn loads_ranges_of_stored_days() {
+    let db = TmpDB::new();
+    let days: Vec<_> = (0..5).map(|i| build_test_day(*DAY0 + i * 2)).collect();
+    for day in days.iter().rev() {
+        db.store_day(day).unwrap();
+    }
+
+    assert_eq!(db.load_range(..).unwrap(), days);
+    assert_eq!(db.load_range(*DAY0 + 1..=*DAY0 + 6).unwrap(), days[1..4]);
+    assert_eq!(db.load_range(*DAY0 + 9..).unwrap(), vec![]);
+    assert_eq!(db.cache.len(), 5);
+}
+
 fn build_test_day(day: Day) -> ActiveDay {
     let cd: chrono::NaiveDate = day.into();
     let day_str = format!("{}{}{}", cd.year(), cd.month(), cd.day());
@@ -0,0 +1,75 @@
+use std::path::Path;
+
+use thiserror::Error;
+
+use crate::db::DBErr;
+
+/// Errors shown to the user
+#[derive(Debug, Error)]
+pub enum QuarbleError {
+    #[error("Cannot {action}: {source}")]
+    Db {
+        /// What the user tried to do, e.g. "store the day"
+        action: &'static str,
+        source: DBErr,
+    },
+    #[error("{0}")]
+    Message(String),
+}
+
+impl QuarbleError {
+    /// The file involved in the error
+    pub fn file(&self) -> Option<&Path> {
+        match self {
+            QuarbleError::Db { source, .. } => match source {
+                DBErr::CannotOpen(path, _)
+                | DBErr::InvalidDBFile(path, _)
+                | DBErr::FailedToWrite(path) => Some(path),
+                DBErr::NotADirectory(_)
+                | DBErr::FailedCreation(_)
+                | DBErr::DayClosed(_)
+                | DBErr::DayMissing(_)
+                | DBErr::DayChanged(_) => None,
+            },
+            QuarbleError::Message(_) => None,
+        }
+    }
+
+    /// All details of the error, for bug reports
+    pub fn diagnostics(&self) -> String {
+        let mut result = self.to_string();
+        if let Some(file) = self.file() {
+            result.push_str(&format!("\nFile: {}", file.display()));
+        }
+        result.push_str(&format!("\nVersion: {}", env!("CARGO_PKG_VERSION")));
+        result.push_str(&format!("\nDetails: {:?}", self));
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use crate::db::DBErr;
+    use crate::error::QuarbleError;
+
+    #[test]
+    fn describes_db_errors() {
+        let error = QuarbleError::Db {
+            action: "store the day",
+            source: DBErr::FailedToWrite(PathBuf::from("db/2022-01-20.json")),
+        };
+        assert_eq!(
+            error.to_string(),
+            "Cannot store the day: Failed to write db/2022-01-20.json"
+        );
+        assert_eq!(
+            error.file(),
+            Some(PathBuf::from("db/2022-01-20.json").as_path())
+        );
+        assert!(error.diagnostics().starts_with(
+            "Cannot store the day: Failed to write db/2022-01-20.json\nFile: db/2022-01-20.json\n"
+        ));
+    }
+}
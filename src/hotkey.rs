@@ -0,0 +1,49 @@
+use std::process::Command;
+
+use anyhow::{bail, Context};
+use global_hotkey::hotkey::HotKey;
+use global_hotkey::{GlobalHotKeyEvent, GlobalHotKeyManager, HotKeyState};
+
+use crate::conf::Settings;
+
+pub const DEFAULT_HOTKEY: &str = "ctrl+alt+Q";
+
+/// Registers the configured chord with the X server and opens the booking view whenever
+/// it is pressed. Runs until the process is killed.
+///
+/// Wayland does not allow applications to grab keys, there the chord has to be bound to
+/// `quarble book` in the settings of the compositor.
+/// `options` are passed on to the started instances, so they use the same settings and profile
+pub fn run_daemon(settings: &Settings, options: &[String]) -> anyhow::Result<()> {
+    if std::env::var_os("DISPLAY").is_none() {
+        bail!("Global hotkeys need an X11 display, bind the key to `quarble book` on Wayland");
+    }
+    let chord = settings.global_hotkey.as_deref().unwrap_or(DEFAULT_HOTKEY);
+    let hotkey: HotKey = chord
+        .parse()
+        .with_context(|| format!("Invalid hotkey: {}", chord))?;
+    let manager = GlobalHotKeyManager::new().context("Cannot access the X11 display")?;
+    manager.register(hotkey).with_context(|| {
+        format!(
+            "Cannot register {}, it may be used by another program",
+            chord
+        )
+    })?;
+    let exe = std::env::current_exe().context("Cannot find the quarble executable")?;
+    log::info!("Listening for {}", chord);
+
+    let events = GlobalHotKeyEvent::receiver();
+    while let Ok(event) = events.recv() {
+        if event.id != hotkey.id() || event.state != HotKeyState::Pressed {
+            continue;
+        }
+        match Command::new(&exe).args(options).arg("book").spawn() {
+            // the window reports its errors itself, waiting only reaps the process
+            Ok(mut child) => {
+                std::thread::spawn(move || child.wait());
+            }
+            Err(e) => log::warn!("Cannot start {}: {}", exe.display(), e),
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use std::time::Duration;
+
+use reqwest::blocking::Client;
+use thiserror::Error;
+
+use crate::conf::JiraConfig;
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Why the summary of an issue is missing
+#[derive(Clone, Debug, Eq, PartialEq, Error)]
+pub enum JiraError {
+    /// No connection, worth another attempt later
+    #[error("Jira is unreachable: {0}")]
+    Unreachable(String),
+    /// Jira answered, but without the summary
+    #[error("Jira refused: {0}")]
+    Refused(String),
+}
+
+/// Fetches the summary of the issue, blocks until Jira answered
+pub fn fetch_summary(config: &JiraConfig, ident: &str) -> Result<String, JiraError> {
+    let client = Client::builder()
+        .timeout(TIMEOUT)
+        .build()
+        .map_err(|e| JiraError::Refused(format!("Cannot create the HTTP client: {}", e)))?;
+    let url = format!(
+        "{}/rest/api/2/issue/{}?fields=summary",
+        config.url.trim_end_matches('/'),
+        ident
+    );
+    let request = match (&config.user, &config.token) {
+        (Some(user), token) => client.get(url).basic_auth(user, token.as_ref()),
+        (None, Some(token)) => client.get(url).bearer_auth(token),
+        (None, None) => client.get(url),
+    };
+    let response = request.send().map_err(|e| {
+        if e.is_connect() || e.is_timeout() {
+            JiraError::Unreachable(e.to_string())
+        } else {
+            JiraError::Refused(e.to_string())
+        }
+    })?;
+    if !response.status().is_success() {
+        return Err(JiraError::Refused(format!("HTTP {}", response.status())));
+    }
+    let body: serde_json::Value = response
+        .json()
+        .map_err(|e| JiraError::Refused(e.to_string()))?;
+    body.pointer("/fields/summary")
+        .and_then(serde_json::Value::as_str)
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .ok_or_else(|| JiraError::Refused(format!("{} has no summary", ident)))
+}
@@ -0,0 +1,35 @@
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{Message, SmtpTransport, Transport};
+
+use crate::conf::EmailConfig;
+
+/// Sends a plain text mail to all configured recipients
+pub fn send_mail(config: &EmailConfig, subject: &str, body: String) -> Result<(), String> {
+    let from = config
+        .from
+        .parse()
+        .map_err(|e| format!("Invalid sender {}: {}", config.from, e))?;
+    let mut builder = Message::builder().from(from).subject(subject);
+    for recipient in &config.recipients {
+        let to = recipient
+            .parse()
+            .map_err(|e| format!("Invalid recipient {}: {}", recipient, e))?;
+        builder = builder.to(to);
+    }
+    let mail = builder
+        .body(body)
+        .map_err(|e| format!("Cannot create the mail: {}", e))?;
+
+    let transport = SmtpTransport::starttls_relay(&config.server)
+        .map_err(|e| format!("Invalid server {}: {}", config.server, e))?
+        .port(config.port)
+        .credentials(Credentials::new(
+            config.user.clone(),
+            config.password.clone(),
+        ))
+        .build();
+    transport
+        .send(&mail)
+        .map_err(|e| format!("Cannot send the mail: {}", e))?;
+    Ok(())
+}
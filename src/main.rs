@@ -1,79 +1,144 @@
 #![allow(dead_code)]
 #![allow(clippy::or_fun_call)]
 use std::cell::RefCell;
-use std::fs::OpenOptions;
-use std::io::{BufReader, Write};
-use std::path::PathBuf;
+use std::fs::{File, OpenOptions};
+use std::path::{Path, PathBuf};
 use std::process;
 use std::rc::Rc;
 use std::str::FromStr;
 
 use anyhow::{bail, Context};
 use opentelemetry::sdk::export::trace::stdout;
-use tracing::{debug, error, info, span};
+use tracing::{debug, error, info, span, warn, Dispatch};
 use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::Registry;
 
-use crate::conf::SettingsSer;
-use crate::conf::{into_settings_ref, Settings};
+use crate::conf::{into_settings_ref, migrate_settings, read_settings_file, Settings};
+use crate::conf::{SettingsSer, TraceOutput};
 use crate::ui::main_action::{CmdId, InitialAction, MainAction};
+use crate::ui::single_instance;
 use crate::ui::ViewId;
 
 mod cmd;
 mod conf;
 mod data;
 mod db;
+mod error;
+mod hotkey;
+mod jira;
+mod mail;
 mod parsing;
 mod ui;
 mod util;
+mod webhook;
 
 fn main() {
-    // Create a new OpenTelemetry pipeline
-    let tracer = stdout::new_pipeline()
-        .with_pretty_print(true)
-        .install_simple();
-
-    // Create a tracing layer with the configured tracer
-    let telemetry = tracing_opentelemetry::layer().with_tracer(tracer);
+    env_logger::init();
+    let args: Vec<String> = std::env::args().collect();
+    let args_ref: Vec<&str> = args.iter().map(String::as_str).collect();
+    let (settings, args_ref) = match parse_settings(&args_ref) {
+        Ok((settings, args_ref)) => (settings, args_ref),
+        Err(e) => {
+            error!("{:?}", e);
+            process::exit(-1);
+        }
+    };
 
-    // Use the tracing subscriber `Registry`, or any other subscriber
-    // that impls `LookupSpan`
-    let subscriber = Registry::default().with(telemetry);
+    let dispatch = match tracing_dispatch(&settings) {
+        Ok(dispatch) => dispatch,
+        Err(e) => {
+            error!("{:?}", e);
+            process::exit(-1);
+        }
+    };
 
     // Trace executed code
-    tracing::subscriber::with_default(subscriber, || {
+    tracing::dispatcher::with_default(&dispatch, || {
         // Spans will be sent to the configured OpenTelemetry exporter
         let root = span!(tracing::Level::DEBUG, "quarble", work_units = 2);
         let _enter = root.enter();
 
-        main_inner()
+        main_inner(settings, args_ref)
     })
     .unwrap();
 }
 
-fn main_inner() -> anyhow::Result<()> {
-    env_logger::init();
-    let args: Vec<String> = std::env::args().collect();
-    let args_ref: Vec<&str> = args.iter().map(String::as_str).collect();
-    let (settings, args_ref) = match parse_settings(&args_ref) {
-        Ok((settings, args_ref)) => (settings, args_ref),
-        Err(e) => {
-            error!("{:?}", e);
-            process::exit(-1);
-        }
+/// Sends tracing spans to the configured output
+fn tracing_dispatch(settings: &Settings) -> anyhow::Result<Dispatch> {
+    let tracer = match &settings.trace {
+        TraceOutput::Off => return Ok(Dispatch::none()),
+        TraceOutput::Stdout => stdout::new_pipeline()
+            .with_pretty_print(true)
+            .install_simple(),
+        TraceOutput::File => stdout::new_pipeline()
+            .with_writer(open_trace_file(&settings.db_dir)?)
+            .install_simple(),
+        TraceOutput::Otlp(endpoint) => opentelemetry_otlp::new_pipeline()
+            .tracing()
+            .with_exporter(
+                opentelemetry_otlp::new_exporter()
+                    .http()
+                    .with_endpoint(endpoint),
+            )
+            .install_simple()
+            .context("Cannot export traces")?,
     };
 
-    let db = db::DB::init(&settings.db_dir)?;
+    // Create a tracing layer with the configured tracer
+    let telemetry = tracing_opentelemetry::layer().with_tracer(tracer);
+
+    // Use the tracing subscriber `Registry`, or any other subscriber
+    // that impls `LookupSpan`
+    Ok(Dispatch::new(Registry::default().with(telemetry)))
+}
+
+const TRACE_FILE_NAME: &str = "trace.log";
+const MAX_TRACE_FILE_BYTES: u64 = 1024 * 1024;
+
+/// Opens the trace file for appending, moves a too large file to `trace.log.1`
+fn open_trace_file(db_dir: &Path) -> anyhow::Result<File> {
+    std::fs::create_dir_all(db_dir).context("Cannot create DB directory")?;
+    let location = db_dir.join(TRACE_FILE_NAME);
+    let size = std::fs::metadata(&location).map(|m| m.len()).unwrap_or(0);
+    if size > MAX_TRACE_FILE_BYTES {
+        std::fs::rename(&location, location.with_extension("log.1"))
+            .context("Cannot rotate trace file")?;
+    }
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&location)
+        .with_context(|| format!("Cannot open trace file {}", location.display()))
+}
+
+fn main_inner(settings: Settings, args_ref: &[&str]) -> anyhow::Result<()> {
+    let mut db = db::DB::init(&settings.db_dir)?;
+    db.set_git_commits(settings.git_commits);
+    db.set_hooks(&settings.hooks);
+    parsing::locale::set_display_formats(settings.time_format, settings.date_format);
 
     debug!("{:?}", settings);
     debug!("{:?}", args_ref);
 
+    match migrate_settings(&settings) {
+        Ok(true) => info!("Updated the settings file to the current format"),
+        Ok(false) => (),
+        Err(e) => warn!("Cannot migrate the settings file: {:#}", e),
+    }
+
     let initial_action = match args_ref {
         ["day_start"] => InitialAction::Ui(ViewId::FastDayStart),
-        ["day_end"] => InitialAction::Ui(ViewId::FastDayEnd),
+        ["day_end"] => InitialAction::Ui(ViewId::DayEndWizard),
+        ["day_start", "--prompt"] => InitialAction::Cmd(CmdId::Prompt(ViewId::FastDayStart)),
+        ["day_end", "--prompt"] => InitialAction::Cmd(CmdId::Prompt(ViewId::FastDayEnd)),
         ["book"] => InitialAction::Ui(ViewId::BookSingle),
+        ["switch"] => InitialAction::Ui(ViewId::QuickSwitch),
         ["show"] | [] => InitialAction::Ui(ViewId::CurrentDayUi),
         ["print_day"] => InitialAction::Cmd(CmdId::PrintDay),
+        ["hotkey"] => InitialAction::Cmd(CmdId::Hotkey),
+        ["capture", input @ ..] if !input.is_empty() => {
+            InitialAction::Cmd(CmdId::Capture(input.join(" ")))
+        }
         unexpected => bail!("Unexpected arguments: {}", unexpected.join(" ")),
     };
 
@@ -81,9 +146,32 @@ fn main_inner() -> anyhow::Result<()> {
         InitialAction::Cmd(CmdId::PrintDay) => {
             cmd::print_active_day(db.load_day(settings.active_date)?)
         }
+        InitialAction::Cmd(CmdId::Prompt(id)) => {
+            cmd::prompt_action(into_settings_ref(settings), &db, id)
+        }
+        InitialAction::Cmd(CmdId::Capture(input)) => {
+            cmd::capture_issue(into_settings_ref(settings), &db, &input)
+        }
+        InitialAction::Cmd(CmdId::Hotkey) => {
+            let args: Vec<String> = std::env::args().skip(1).collect();
+            // the settings options before the command
+            let options = &args[..args.len() - args_ref.len()];
+            hotkey::run_daemon(&settings, options)?;
+            return Ok(());
+        }
         InitialAction::Ui(id) => id,
     };
 
+    // a second window would race with the running instance on the same day file
+    if single_instance::forward(&settings.db_dir, initial_action) {
+        info!(
+            "Forwarded {} to the running instance",
+            initial_action.name()
+        );
+        return Ok(());
+    }
+    let instance = single_instance::InstanceListener::bind(&settings.db_dir);
+
     let work_day = if let Some(work_day) = db.load_day(settings.active_date)? {
         work_day
     } else {
@@ -95,46 +183,10 @@ fn main_inner() -> anyhow::Result<()> {
         initial_view: initial_action,
         db,
         work_day: Rc::new(RefCell::new(work_day)),
+        instance,
     };
-    let settings_out = ui::show_ui(main_action);
-    let settings_out = settings_out.load();
-    if let Err(e) = do_write_settings(&settings_out) {
-        error!("{:?}", e);
-        process::exit(2);
-    }
-
-    Ok(())
-}
-
-fn do_write_settings(settings: &Settings) -> anyhow::Result<()> {
-    if settings.write_settings {
-        let location = settings
-            .settings_location
-            .as_ref()
-            .context("Missing settings location")?;
-
-        info!("Writing settings to {}", location.display());
-
-        if let Some(dir) = location.parent() {
-            if !dir.is_dir() {
-                std::fs::create_dir_all(dir).with_context(|| {
-                    format!("Failed to create settings directory: {}", dir.display())
-                })?;
-            }
-        }
-
-        let to_write = SettingsSer::from_settings(settings);
-        let buffer =
-            serde_json::to_vec_pretty(&to_write).context("Failed to serialize settings")?;
-        let mut file = OpenOptions::new()
-            .write(true)
-            .create(true)
-            .truncate(true)
-            .open(location)
-            .context("Cannot open settings for writing")?;
-        file.write_all(&buffer)
-            .context("Failed to write settings")?;
-    }
+    // changed settings are written by the UI
+    ui::show_ui(main_action);
 
     Ok(())
 }
@@ -148,12 +200,15 @@ fn parse_settings<'a>(args: &'a [&'a str]) -> anyhow::Result<(Settings, &'a [&'a
         config_file: Option<PathBuf>,
         db_dir: Option<PathBuf>,
         resolution_minutes: Option<String>,
-        write_settings: bool,
+        profile: Option<String>,
+        migrate_settings: bool,
         debug: bool,
+        renderer: Option<String>,
+        trace: Option<TraceOutput>,
     }
 
     let mut b = SettingsBuilder {
-        write_settings: true,
+        migrate_settings: true,
         ..Default::default()
     };
     loop {
@@ -171,14 +226,37 @@ fn parse_settings<'a>(args: &'a [&'a str]) -> anyhow::Result<(Settings, &'a [&'a
                 b.db_dir = Some(PathBuf::from(db_dir));
                 remaining_args = rest;
             }
-            ["-W" | "--no_write-settings", rest @ ..] => {
-                b.write_settings = false;
+            ["-P" | "--profile", profile, rest @ ..] => {
+                b.profile = Some(profile.to_string());
+                remaining_args = rest;
+            }
+            ["-W" | "--no-migrate-settings" | "--no_write-settings", rest @ ..] => {
+                b.migrate_settings = false;
                 remaining_args = rest;
             }
             ["--debug", rest @ ..] => {
                 b.debug = true;
                 remaining_args = rest;
             }
+            ["--trace", trace, rest @ ..] => {
+                b.trace = Some(TraceOutput::parse(trace).with_context(|| {
+                    format!(
+                        "Invalid trace output '{}', expected off, stdout, file, otlp or otlp=<endpoint>",
+                        trace
+                    )
+                })?);
+                remaining_args = rest;
+            }
+            ["--renderer", renderer, rest @ ..] => {
+                if ui::renderer_backends(renderer).is_none() {
+                    bail!(
+                        "Unknown renderer '{}', expected vulkan, metal, dx12, dx11, gl or primary",
+                        renderer
+                    );
+                }
+                b.renderer = Some(renderer.to_string());
+                remaining_args = rest;
+            }
             _ => {
                 break;
             }
@@ -189,18 +267,14 @@ fn parse_settings<'a>(args: &'a [&'a str]) -> anyhow::Result<(Settings, &'a [&'a
 
     let from_file = if let Some(ref file) = b.config_file {
         let exists = file.is_file();
-        if b.explicit_config_file && !b.write_settings && !exists {
+        if b.explicit_config_file && !b.migrate_settings && !exists {
             bail!(
-                "Settings file {} does not exist and is not configured to be written",
+                "Settings file {} does not exist and is not migrated",
                 file.display()
             );
         }
         if exists {
-            let file = std::fs::File::open(file).context("Failed to open settings file")?;
-            let reader = BufReader::new(file);
-            let explicit: SettingsSer =
-                serde_json::from_reader(reader).context("Failed to read settings")?;
-            Some(explicit)
+            Some(read_settings_file(file)?)
         } else {
             None
         }
@@ -208,14 +282,28 @@ fn parse_settings<'a>(args: &'a [&'a str]) -> anyhow::Result<(Settings, &'a [&'a
         None
     };
 
+    let (from_file, other_profiles) = match from_file {
+        Some(file) => {
+            let (selected, others) = file.select(b.profile.as_deref());
+            (Some(selected), others)
+        }
+        None => (None, Default::default()),
+    };
+
     let db_dir = db_location(b.db_dir, from_file.as_ref())?;
     let resolution = resolution(b.resolution_minutes, from_file.as_ref())?;
     let mut settings = Settings::from_ser(from_file);
     settings.db_dir = db_dir;
-    settings.write_settings = b.write_settings;
+    settings.migrate_settings = b.migrate_settings;
     settings.resolution = resolution;
     settings.settings_location = b.config_file;
     settings.debug = b.debug;
+    settings.renderer = b.renderer;
+    if let Some(trace) = b.trace {
+        settings.trace = trace;
+    }
+    settings.profile = b.profile;
+    settings.other_profiles = other_profiles;
 
     Ok((settings, remaining_args))
 }
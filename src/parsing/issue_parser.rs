@@ -1,4 +1,5 @@
 use std::collections::BTreeMap;
+use std::ops::Bound;
 use std::str::FromStr;
 
 use lazy_static::lazy_static;
@@ -13,9 +14,14 @@ lazy_static! {
     static ref ISSUE: Regex = Regex::new(r"^(?P<id>([a-zA-Z]+-[0-9]+))").unwrap();
     static ref ISSUE_CLIPBOARD: Regex =
         Regex::new(r"(?P<id>(?:[a-zA-Z]+)-(?:[0-9]{1,10}))(?:(?:\W)+(?P<comment>.*))?").unwrap();
+    static ref ISSUE_URL: Regex =
+        Regex::new(r"(?:/browse/|[?&](?:selectedIssue|issueKey)=)(?P<id>[a-zA-Z]+-[0-9]{1,10})\b")
+            .unwrap();
     static ref ISSUE_DESCRIPTION: Regex =
         Regex::new(r"^(?P<id>([a-zA-Z]+-[0-9]+))(?:\W+)(?P<comment>[^#]+)#").unwrap();
     static ref RECENT_ISSUE: Regex = Regex::new(r"^r(?P<recent>[1-9][0-9]{0,2})").unwrap();
+    static ref RECENT_FILTER: Regex = Regex::new(r"^/(?P<query>\S*)").unwrap();
+    static ref WORD: Regex = Regex::new(r"\S+").unwrap();
 }
 
 pub trait IssueParser {
@@ -42,14 +48,45 @@ impl JiraIssueParser {
     pub fn shortcuts(&self) -> &BTreeMap<char, JiraIssue> {
         &self.shortcuts
     }
-    
+
+    /// The shortcut typed as issue, with its position in `input`.
+    ///
+    /// The issue is the first word starting with a letter or `/`, times start with digits
+    pub fn shortcut_in(&self, input: &str) -> Option<(usize, char, &JiraIssue)> {
+        let word = WORD.find_iter(input).find(|w| {
+            w.as_str()
+                .starts_with(|c: char| c.is_ascii_alphabetic() || c == '/')
+        })?;
+        let mut chars = word.as_str().chars();
+        match (chars.next(), chars.next()) {
+            (Some(ch), None) => self.shortcuts.get(&ch).map(|i| (word.start(), ch, i)),
+            _ => None,
+        }
+    }
+
+    /// `input` with its shortcut replaced by the next configured one, the last wraps around
+    pub fn cycle_shortcut(&self, input: &str) -> Option<String> {
+        let (at, ch, _) = self.shortcut_in(input)?;
+        let (next, _) = self
+            .shortcuts
+            .range((Bound::Excluded(ch), Bound::Unbounded))
+            .next()
+            .or_else(|| self.shortcuts.iter().next())?;
+        Some(format!(
+            "{}{}{}",
+            &input[..at],
+            next,
+            &input[at + ch.len_utf8()..]
+        ))
+    }
+
     pub fn valid_id(text: &str) -> bool {
         if let Some(c) = ISSUE.captures(text) {
             rest(c, text).is_empty()
-        } else { 
+        } else {
             false
         }
-    } 
+    }
 }
 
 impl IssueParser for JiraIssueParser {
@@ -60,7 +97,7 @@ impl IssueParser for JiraIssueParser {
             let comment = Some(comment.trim_end().to_string()).filter(|e| !e.is_empty());
             IssueParsed {
                 r: ParseResult::Valid(JiraIssue {
-                    ident: id.to_string(),
+                    ident: id.to_ascii_uppercase(),
                     description: comment,
                     default_action: None,
                 }),
@@ -70,7 +107,7 @@ impl IssueParser for JiraIssueParser {
         } else if let Some(c) = ISSUE.captures(input) {
             let id = c.name("id").unwrap().as_str();
             IssueParsed {
-                r: ParseResult::Valid(JiraIssue::create(id).unwrap()),
+                r: JiraIssue::create(id).ok().into(),
                 input: matching(&c),
                 rest: rest(c, input),
             }
@@ -120,30 +157,95 @@ impl<'a> IssueParserWithRecent<'a> {
 impl<'a> IssueParser for IssueParserWithRecent<'a> {
     fn parse_task<'b>(&self, input: &'b str) -> IssueParsed<'b> {
         if let Some(c) = RECENT_ISSUE.captures(input) {
-            let index = usize::from_str(c.name("recent").unwrap().as_str()).unwrap();
-            let recent = self.recent.find_recent(index - 1).map(|r| r.issue.clone());
+            let recent = usize::from_str(&c["recent"])
+                .ok()
+                .and_then(|index| index.checked_sub(1))
+                .and_then(|index| self.recent.find_recent(index))
+                .cloned();
             IssueParsed {
                 r: recent.ok_or(()).into(),
                 input,
                 rest: rest(c, input),
             }
+        } else if let Some(c) = RECENT_FILTER.captures(input) {
+            let query = c.name("query").unwrap().as_str();
+            let r = if query.is_empty() {
+                ParseResult::Incomplete
+            } else {
+                let first = self
+                    .recent
+                    .filtered(query)
+                    .first()
+                    .map(|(i, _)| (*i).clone());
+                first.ok_or(()).into()
+            };
+            IssueParsed {
+                r,
+                input: matching(&c),
+                rest: rest(c, input),
+            }
         } else {
             self.delegate.parse_task(input)
         }
     }
 }
 
+/// The query for filtering recent issues, when the issue input is `/<query>`
+pub fn recent_filter(issue_input: &str) -> &str {
+    issue_input.strip_prefix('/').unwrap_or_default()
+}
+
+/// Puts the issue in place of the recent filter `/<query>` of the input, or appends it
+pub fn insert_issue(input: &str, ident: &str) -> String {
+    let filter = input
+        .match_indices('/')
+        .map(|(start, _)| start)
+        .find(|start| input[..*start].is_empty() || input[..*start].ends_with(char::is_whitespace));
+    match filter {
+        Some(start) => {
+            let rest = &input[start..];
+            let rest = &rest[rest.find(char::is_whitespace).unwrap_or(rest.len())..];
+            let rest = if rest.is_empty() { " " } else { rest };
+            format!("{}{}{}", &input[..start], ident, rest)
+        }
+        None if input.trim().is_empty() => format!("{} ", ident),
+        None if input.ends_with(char::is_whitespace) => format!("{}{} ", input, ident),
+        None => format!("{} {} ", input, ident),
+    }
+}
+
 pub fn parse_issue_clipboard(input: &str) -> Option<JiraIssue> {
     let c = ISSUE_CLIPBOARD.captures(input)?;
     let id = c.name("id")?;
 
     Some(JiraIssue {
-        ident: id.as_str().to_string(),
+        ident: id.as_str().to_ascii_uppercase(),
         description: c.name("comment").map(|m| m.as_str().to_string()),
         default_action: None,
     })
 }
 
+/// Finds an issue in a Jira link or in arbitrary text, like the title of a browser tab.
+///
+/// Text around a link is used as description, the issue id in the link takes precedence
+/// over other ids in the text
+pub fn parse_issue_capture(input: &str) -> Option<JiraIssue> {
+    let id = match input.split_whitespace().find_map(|w| ISSUE_URL.captures(w)) {
+        Some(c) => c.name("id")?,
+        None => return parse_issue_clipboard(input.trim()),
+    };
+    let description = input
+        .split_whitespace()
+        .filter(|w| !ISSUE_URL.is_match(w))
+        .collect::<Vec<_>>()
+        .join(" ");
+    Some(JiraIssue {
+        ident: id.as_str().to_ascii_uppercase(),
+        description: (!description.is_empty()).then_some(description),
+        default_action: None,
+    })
+}
+
 fn matching<'a, 'b>(c: &'b Captures<'a>) -> &'a str {
     c.get(0).unwrap().as_str()
 }
@@ -152,9 +254,15 @@ fn matching<'a, 'b>(c: &'b Captures<'a>) -> &'a str {
 mod test {
     use std::collections::BTreeMap;
 
-    use crate::data::JiraIssue;
-    use crate::parsing::issue_parser::{IssueParsed, IssueParser, JiraIssueParser};
+    use crate::conf::into_settings_ref;
+    use crate::data::{JiraIssue, RecentIssues, RecentIssuesData};
+    use crate::parsing::issue_parser::{
+        insert_issue, parse_issue_capture, parse_issue_clipboard, recent_filter, IssueParsed,
+        IssueParser, IssueParserWithRecent, JiraIssueParser,
+    };
     use crate::parsing::parse_result::ParseResult;
+    use crate::Settings;
+    use proptest::prelude::*;
 
     #[test]
     fn parse_shortcut() {
@@ -180,6 +288,25 @@ mod test {
         );
     }
 
+    #[test]
+    fn cycles_shortcuts() {
+        let p = new_parser();
+
+        assert_eq!(
+            p.shortcut_in("9 10 a fix a bug")
+                .map(|(at, ch, _)| (at, ch)),
+            Some((5, 'a'))
+        );
+        assert_eq!(
+            p.cycle_shortcut("9 10 a fix a bug").unwrap(),
+            "9 10 b fix a bug"
+        );
+        assert_eq!(p.cycle_shortcut("b").unwrap(), "a");
+        assert_eq!(p.cycle_shortcut("9 10 A-5 a"), None);
+        assert_eq!(p.cycle_shortcut("/ab a"), None);
+        assert_eq!(p.cycle_shortcut("9 10 x"), None);
+    }
+
     #[test]
     fn parse_issue() {
         let p = new_parser();
@@ -237,6 +364,104 @@ mod test {
                 rest: "work 1"
             }
         );
+
+        assert_eq!(
+            p.parse_task("qu-789 An issue#work"),
+            valid_desc("qu-789 An issue#", "QU-789", "An issue", "work")
+        );
+        assert_eq!(
+            parse_issue_clipboard("see qu-12: fix").map(|i| i.ident),
+            Some("QU-12".to_string())
+        );
+    }
+
+    #[test]
+    fn parse_capture() {
+        let captured = parse_issue_capture(
+            "https://jira.example.com/browse/QUA-123?focusedCommentId=2 Fix the login-2 page",
+        )
+        .unwrap();
+        assert_eq!(captured.ident, "QUA-123");
+        assert_eq!(
+            captured.description.as_deref(),
+            Some("Fix the login-2 page")
+        );
+
+        let board = parse_issue_capture(
+            "https://jira-2.example.com/secure/RapidBoard.jspa?rapidView=7&selectedIssue=ops-9",
+        )
+        .unwrap();
+        assert_eq!(board.ident, "OPS-9");
+        assert_eq!(board.description, None);
+
+        let title = parse_issue_capture(" [QUA-7] Export is slow - Jira ").unwrap();
+        assert_eq!(title.ident, "QUA-7");
+        assert_eq!(title.description.as_deref(), Some("Export is slow - Jira"));
+
+        assert_eq!(parse_issue_capture("https://example.com/browse/"), None);
+    }
+
+    #[test]
+    fn invalid_recent_index() {
+        let settings = into_settings_ref(Settings::default());
+        let recent = RecentIssues::new(RecentIssuesData::default(), settings);
+        let parser = new_parser();
+        let parser = IssueParserWithRecent::new(&parser, &recent);
+        assert_eq!(parser.parse_task("r1 fix").r, ParseResult::Invalid(()));
+        assert_eq!(parser.parse_task("r999").r, ParseResult::Invalid(()));
+    }
+
+    proptest! {
+        #[test]
+        fn parsing_never_panics(input in "[a-zA-Z0-9 #/?&=:.r-]{0,24}|\\PC{0,24}") {
+            let settings = into_settings_ref(Settings::default());
+            let recent = RecentIssues::new(RecentIssuesData::default(), settings);
+            let parser = new_parser();
+            let IssueParsed { rest, .. } = parser.parse_task(&input);
+            prop_assert!(input.ends_with(rest));
+            let _ = IssueParserWithRecent::new(&parser, &recent).parse_task(&input);
+            let _ = parse_issue_clipboard(&input);
+            let _ = parse_issue_capture(&input);
+        }
+    }
+
+    #[test]
+    fn parse_recent_filter() {
+        let p = new_parser();
+        let settings = into_settings_ref(Settings::default());
+        let mut recent = RecentIssues::new(RecentIssuesData::default(), settings);
+        recent.issue_used(&JiraIssue::create("DEV-7").unwrap());
+        recent.issue_used(&JiraIssue::create("DOC-3").unwrap());
+        let p = IssueParserWithRecent::new(&p, &recent);
+
+        assert_eq!(
+            p.parse_task("/dv rest"),
+            IssueParsed {
+                r: ParseResult::Valid(JiraIssue::create("DEV-7").unwrap()),
+                input: "/dv",
+                rest: " rest"
+            }
+        );
+        assert_eq!(
+            p.parse_task("/d").r,
+            ParseResult::Valid(JiraIssue::create("DOC-3").unwrap())
+        );
+        assert_eq!(p.parse_task("/").r, ParseResult::Incomplete);
+        assert_eq!(p.parse_task("/xy").r, ParseResult::Invalid(()));
+        assert_eq!(recent_filter("/dv"), "dv");
+        assert_eq!(recent_filter("DEV-7"), "");
+    }
+
+    #[test]
+    fn inserts_issue_for_filter() {
+        assert_eq!(insert_issue("", "DEV-7"), "DEV-7 ");
+        assert_eq!(insert_issue("9 10", "DEV-7"), "9 10 DEV-7 ");
+        assert_eq!(insert_issue("9 10 ", "DEV-7"), "9 10 DEV-7 ");
+        assert_eq!(insert_issue("9 10 /dv", "DEV-7"), "9 10 DEV-7 ");
+        assert_eq!(
+            insert_issue("/dv fix and/or test", "DEV-7"),
+            "DEV-7 fix and/or test"
+        );
     }
 
     fn valid_short<'a>(id: &'a str, input: &'a str, rest: &'a str) -> IssueParsed<'a> {
@@ -0,0 +1,60 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// How times are shown in the UI and in exports
+#[derive(Copy, Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimeFormat {
+    /// `13:30`
+    H24,
+    /// `1:30 PM`
+    H12,
+}
+
+impl Default for TimeFormat {
+    fn default() -> Self {
+        TimeFormat::H24
+    }
+}
+
+/// How dates are shown in the UI and in exports
+#[derive(Copy, Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DateFormat {
+    /// `2022-03-07`
+    Iso,
+    /// `07.03.2022`
+    Dotted,
+}
+
+impl Default for DateFormat {
+    fn default() -> Self {
+        DateFormat::Iso
+    }
+}
+
+static TWELVE_HOUR: AtomicBool = AtomicBool::new(false);
+static DOTTED_DATE: AtomicBool = AtomicBool::new(false);
+
+/// Formats used by the `Display` impls of `Time` and `Day`.
+///
+/// Stored days and settings always use ISO formats
+pub fn set_display_formats(time: TimeFormat, date: DateFormat) {
+    TWELVE_HOUR.store(time == TimeFormat::H12, Ordering::Relaxed);
+    DOTTED_DATE.store(date == DateFormat::Dotted, Ordering::Relaxed);
+}
+
+pub fn time_format() -> TimeFormat {
+    if TWELVE_HOUR.load(Ordering::Relaxed) {
+        TimeFormat::H12
+    } else {
+        TimeFormat::H24
+    }
+}
+
+pub fn date_format() -> DateFormat {
+    if DOTTED_DATE.load(Ordering::Relaxed) {
+        DateFormat::Dotted
+    } else {
+        DateFormat::Iso
+    }
+}
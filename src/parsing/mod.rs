@@ -1,8 +1,10 @@
 pub use issue_parser::{
-    parse_issue_clipboard, IssueParsed, IssueParser, IssueParserWithRecent, JiraIssueParser,
+    insert_issue, parse_issue_clipboard, recent_filter, IssueParsed, IssueParser,
+    IssueParserWithRecent, JiraIssueParser,
 };
 
 mod issue_parser;
+pub mod locale;
 pub mod parse_result;
 pub mod round_mode;
 pub mod time;
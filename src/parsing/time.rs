@@ -3,12 +3,14 @@ use std::num::NonZeroU32;
 use std::ops::{Add, Sub};
 use std::str::FromStr;
 
+use crate::parsing::locale::{self, TimeFormat};
 use crate::parsing::parse_result::ParseResult;
 use crate::parsing::rest;
 use crate::parsing::round_mode::RoundMode;
 use chrono::Timelike;
 use regex::{Captures, Regex};
 use serde::{Deserializer, Serializer};
+use thiserror::Error;
 
 use crate::parsing::time_relative::TimeRelative;
 use crate::util::Timeline;
@@ -17,6 +19,7 @@ use crate::util::Timeline;
 pub struct Time {
     h: u8,
     m: u8,
+    s: u8,
 }
 
 impl Time {
@@ -24,10 +27,11 @@ impl Time {
     pub const MAX: Time = Time::hm(24, 0);
     pub const fn hm(h: u32, m: u32) -> Self {
         if m == 60 {
-            if h < 23 {
+            if h < 24 {
                 Time {
                     h: h as u8 + 1,
                     m: 0,
+                    s: 0,
                 }
             } else {
                 panic!("Invalid time");
@@ -38,16 +42,27 @@ impl Time {
             Time {
                 h: h as u8,
                 m: m as u8,
+                s: 0,
             }
         }
     }
 
+    pub const fn hms(h: u32, m: u32, s: u32) -> Self {
+        debug_assert!(s < 60);
+        debug_assert!(h < 24 || s == 0);
+        Time {
+            s: s as u8,
+            ..Self::hm(h, m)
+        }
+    }
+
     pub fn try_hm(h: u32, m: u32) -> Option<Self> {
         if m == 60 {
-            if h < 23 {
+            if h < 24 {
                 Some(Time {
                     h: h as u8 + 1,
                     m: 0,
+                    s: 0,
                 })
             } else {
                 None
@@ -56,12 +71,21 @@ impl Time {
             Some(Time {
                 h: h as u8,
                 m: m as u8,
+                s: 0,
             })
         } else {
             None
         }
     }
 
+    pub fn try_hms(h: u32, m: u32, s: u32) -> Option<Self> {
+        match Self::try_hm(h, m) {
+            Some(t) if s == 0 => Some(t),
+            Some(t) if s < 60 && t < Self::MAX => Some(Time { s: s as u8, ..t }),
+            _ => None,
+        }
+    }
+
     pub fn try_new(t: i32) -> Option<Self> {
         if !(0..=24 * 60).contains(&t) {
             return None;
@@ -69,39 +93,59 @@ impl Time {
         Self::try_hm(t as u32 / 60, t as u32 % 60)
     }
 
+    /// The time `t` minutes after midnight, saturating at [Time::MAX]
     pub fn new(t: u32) -> Self {
+        let t = t.min(24 * 60);
         Self::hm(t / 60, t % 60)
     }
 
     pub fn parse_prefix(input: &str) -> (ParseResult<Time, ()>, &str) {
-        if let Some(c) = TIME_HM.captures(input) {
-            (convert_hm(&c).into(), rest(c, input))
-        } else if let Some(c) = TIME_DEC.captures(input) {
-            let h = u32::from_str(c.name("hour").unwrap().as_str()).unwrap();
-            let dec = u32::from_str(c.name("dec").unwrap().as_str()).unwrap();
-            (Self::try_hm(h, (dec * 60) / 100).into(), rest(c, input))
-        } else if let Some(c) = TIME_SHORT.captures(input) {
-            (convert_hm(&c).into(), rest(c, input))
-        } else if let Some(c) = TIME_H.captures(input) {
-            let h = u32::from_str(c.name("hour").unwrap().as_str()).unwrap();
-            (Self::try_hm(h, 0).into(), rest(c, input))
-        } else {
-            (ParseResult::None, input)
+        let (time, rest) = Self::parse_prefix_checked(input);
+        (time.map_invalid(|_| ()), rest)
+    }
+
+    /// Like [Time::parse_prefix], but tells why the input is not a valid time
+    pub fn parse_prefix_checked(input: &str) -> (ParseResult<Time, TimeError>, &str) {
+        let formats: [(&Regex, Convert); 6] = [
+            (&TIME_12H, convert_12h),
+            (&TIME_HMS, convert_hms),
+            (&TIME_HM, convert_hm),
+            (&TIME_DEC, convert_dec),
+            (&TIME_SHORT, convert_hm),
+            (&TIME_H, convert_h),
+        ];
+        for (regex, convert) in formats {
+            if let Some(c) = regex.captures(input) {
+                let time = convert(&c).ok_or(TimeError::OutOfRange);
+                return (time.into(), rest(c, input));
+            }
         }
+        (ParseResult::None, input)
     }
 
     pub fn parse_with_offset<'a, 'b>(
         timeline: &'b Timeline,
         input: &'a str,
     ) -> (ParseResult<Time, ()>, &'a str) {
-        let t1 = Time::parse_prefix(input);
-        match t1 {
+        let (time, rest) = Self::parse_with_offset_checked(timeline, input);
+        (time.map_invalid(|_| ()), rest)
+    }
+
+    /// Like [Time::parse_with_offset], but tells why the input is not a valid time
+    pub fn parse_with_offset_checked<'a>(
+        timeline: &Timeline,
+        input: &'a str,
+    ) -> (ParseResult<Time, TimeError>, &'a str) {
+        match Time::parse_prefix_checked(input) {
             (ParseResult::None | ParseResult::Incomplete, _) => {
                 let (tr, rest) = TimeRelative::parse_relative(input);
-                (
-                    tr.and_then(|r| timeline.time_now().try_add_relative(r).into()),
-                    rest,
-                )
+                let time = tr.map_invalid(|_| TimeError::OutOfRange).and_then(|r| {
+                    let now = timeline.time_now();
+                    now.try_add_relative(r)
+                        .ok_or(TimeError::LeavesDay(r))
+                        .into()
+                });
+                (time, rest)
             }
             absolute => absolute,
         }
@@ -126,7 +170,40 @@ impl Time {
 
     pub fn try_add_relative(self, tr: TimeRelative) -> Option<Self> {
         let v = self.h as i32 * 60 + self.m as i32 + tr.offset_minutes();
-        Self::try_new(v)
+        Self::try_new(v).and_then(|t| Self::try_hms(t.h(), t.m(), self.s()))
+    }
+
+    /// Adds the offset, wrapping around midnight instead of failing.
+    /// Used for work that continues on the following day.
+    pub fn wrapping_add_relative(self, tr: TimeRelative) -> Self {
+        const DAY: i32 = 24 * 60 * 60;
+        let v = self.seconds() + tr.offset_minutes() * 60;
+        let v = if v == DAY { v } else { v.rem_euclid(DAY) };
+        Self::try_hms((v / 3600) as u32, (v / 60 % 60) as u32, (v % 60) as u32).unwrap()
+    }
+
+    fn seconds(self) -> i32 {
+        (self.h as i32 * 60 + self.m as i32) * 60 + self.s as i32
+    }
+
+    /// The time in the given format, `24:00` is kept in both formats
+    pub fn format(self, format: TimeFormat) -> String {
+        let (h, suffix) = match (format, self.h()) {
+            (TimeFormat::H24, h) | (TimeFormat::H12, h @ 24) => (h, ""),
+            (TimeFormat::H12, 0) => (12, " AM"),
+            (TimeFormat::H12, h @ 1..=11) => (h, " AM"),
+            (TimeFormat::H12, 12) => (12, " PM"),
+            (TimeFormat::H12, h) => (h - 12, " PM"),
+        };
+        let h = match format {
+            TimeFormat::H24 => format!("{:02}", h),
+            TimeFormat::H12 => h.to_string(),
+        };
+        if self.s > 0 {
+            format!("{}:{:02}:{:02}{}", h, self.m(), self.s(), suffix)
+        } else {
+            format!("{}:{:02}{}", h, self.m(), suffix)
+        }
     }
 
     pub fn h(&self) -> u32 {
@@ -135,8 +212,26 @@ impl Time {
     pub fn m(&self) -> u32 {
         self.m as u32
     }
+    pub fn s(&self) -> u32 {
+        self.s as u32
+    }
+    /// Whole minutes until `end`, 0 if `end` is earlier
+    pub fn minutes_until(self, end: Time) -> u32 {
+        (end - self).offset_minutes().max(0) as u32
+    }
+    pub fn without_seconds(self) -> Self {
+        Time { s: 0, ..self }
+    }
+    /// Rounds to the nearest full minute
+    pub fn round_seconds(self) -> Self {
+        if self.s >= 30 {
+            Self::new(self.h() * 60 + self.m() + 1)
+        } else {
+            self.without_seconds()
+        }
+    }
     pub fn with_m(self, m: u32) -> Self {
-        Self::hm(self.h(), m)
+        Self::new(self.h() * 60 + m)
     }
     pub fn next_h(self) -> Self {
         if self.h() >= 23 {
@@ -147,6 +242,15 @@ impl Time {
     }
 
     pub fn round(self, mode: RoundMode, resolution: NonZeroU32) -> Self {
+        if self.s > 0 {
+            let minutes = match mode {
+                RoundMode::None => return self,
+                RoundMode::Normal => self.round_seconds(),
+                RoundMode::Down => self.without_seconds(),
+                RoundMode::Up => Self::new(self.h() * 60 + self.m() + 1),
+            };
+            return minutes.round(mode, resolution);
+        }
         let h = self.h();
         let m = self.m();
         match mode {
@@ -154,15 +258,15 @@ impl Time {
             RoundMode::Normal => {
                 let rem = m % resolution;
                 if rem <= resolution.get() / 2 {
-                    Self::hm(h, (m / resolution) * resolution.get())
+                    Self::new(h * 60 + (m / resolution) * resolution.get())
                 } else {
                     let m = (m / resolution + 1) * resolution.get();
-                    Self::hm(h, m)
+                    Self::new(h * 60 + m)
                 }
             }
             RoundMode::Down => {
                 let m = (m / resolution) * resolution.get();
-                Self::hm(h, m)
+                Self::new(h * 60 + m)
             }
             RoundMode::Up => {
                 let rem = m % resolution;
@@ -170,17 +274,89 @@ impl Time {
                     self
                 } else {
                     let m = ((m / resolution) + 1) * resolution.get();
-                    Self::hm(h, m)
+                    Self::new(h * 60 + m)
                 }
             }
         }
     }
+
+    /// Moves the time by `steps` resolution steps, staying within the day.
+    /// A time off the resolution grid snaps to the grid with its first step.
+    pub fn step(self, steps: i32, resolution: NonZeroU32) -> Self {
+        if steps == 0 {
+            return self;
+        }
+        let mode = if steps > 0 {
+            RoundMode::Up
+        } else {
+            RoundMode::Down
+        };
+        let snapped = self.round(mode, resolution);
+        let steps = if snapped == self {
+            steps
+        } else {
+            steps - steps.signum()
+        };
+        let minutes = (snapped.h() * 60 + snapped.m()) as i32 + steps * resolution.get() as i32;
+        Self::new(minutes.clamp(0, 24 * 60) as u32)
+    }
+}
+
+/// Why an input is not a time of the day
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Error)]
+pub enum TimeError {
+    /// Looks like a time, but the hour or the minute is too large, like `25:00`
+    #[error("not a time of the day, use 0:00 to 24:00")]
+    OutOfRange,
+    /// The relative time ends before midnight or after the end of the day
+    #[error("now {0} is outside of the day")]
+    LeavesDay(TimeRelative),
+}
+
+type Convert = fn(&Captures) -> Option<Time>;
+
+/// The number in the group `name`, `None` for a missing group or an overflow
+fn number(c: &Captures, name: &str) -> Option<u32> {
+    u32::from_str(c.name(name)?.as_str()).ok()
+}
+
+fn convert_hms(c: &Captures) -> Option<Time> {
+    Time::try_hms(
+        number(c, "hour")?,
+        number(c, "minute")?,
+        number(c, "second")?,
+    )
+}
+
+/// `12 AM` is midnight, `12 PM` is noon
+fn convert_12h(c: &Captures) -> Option<Time> {
+    let optional = |name| match c.name(name) {
+        Some(_) => number(c, name),
+        None => Some(0),
+    };
+    let h = match number(c, "hour")? {
+        h @ 1..=12 => h % 12,
+        _ => return None,
+    };
+    let pm = c.name("meridiem")?.as_str().eq_ignore_ascii_case("pm");
+    Time::try_hms(
+        if pm { h + 12 } else { h },
+        optional("minute")?,
+        optional("second")?,
+    )
 }
 
 fn convert_hm(c: &Captures) -> Option<Time> {
-    let h = u32::from_str(c.name("hour").unwrap().as_str()).unwrap();
-    let m = u32::from_str(c.name("minute").unwrap().as_str()).unwrap();
-    Time::try_hm(h, m)
+    Time::try_hm(number(c, "hour")?, number(c, "minute")?)
+}
+
+/// `8.5` is half past eight
+fn convert_dec(c: &Captures) -> Option<Time> {
+    Time::try_hm(number(c, "hour")?, (number(c, "dec")? * 60) / 100)
+}
+
+fn convert_h(c: &Captures) -> Option<Time> {
+    Time::try_hm(number(c, "hour")?, 0)
 }
 
 impl serde::Serialize for Time {
@@ -188,7 +364,7 @@ impl serde::Serialize for Time {
     where
         S: Serializer,
     {
-        serializer.serialize_str(&self.to_string())
+        serializer.serialize_str(&self.format(TimeFormat::H24))
     }
 }
 
@@ -206,14 +382,16 @@ impl<'de> serde::de::Visitor<'de> for TimeVisitor {
     type Value = Time;
 
     fn expecting(&self, f: &mut Formatter) -> std::fmt::Result {
-        write!(f, "Time in format 'hh:mm'")
+        write!(f, "Time in format 'hh:mm' or 'hh:mm:ss'")
     }
 
     fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
     where
         E: serde::de::Error,
     {
-        if let Some(c) = TIME_HM.captures(v) {
+        if let Some(c) = TIME_HMS.captures(v) {
+            convert_hms(&c).ok_or_else(|| E::custom(format!("Out of range: {}", v)))
+        } else if let Some(c) = TIME_HM.captures(v) {
             convert_hm(&c).ok_or_else(|| E::custom(format!("Out of range: {}", v)))
         } else {
             Err(E::custom(format!("invalid time: {}", v)))
@@ -222,6 +400,8 @@ impl<'de> serde::de::Visitor<'de> for TimeVisitor {
 }
 
 lazy_static::lazy_static! {
+    static ref TIME_12H: Regex = Regex::new(r"^(?P<hour>[0-9]{1,2})(:(?P<minute>[0-9]{2})(:(?P<second>[0-9]{2}))?)? ?(?P<meridiem>[aApP][mM])\b").unwrap();
+    static ref TIME_HMS: Regex = Regex::new(r"^(?P<hour>[0-9]{1,2}):(?P<minute>[0-9]{1,2}):(?P<second>[0-9]{1,2})\b").unwrap();
     static ref TIME_HM: Regex = Regex::new(r"^(?P<hour>[0-9]{1,2}):(?P<minute>[0-9]{1,2})\b").unwrap();
     static ref TIME_SHORT: Regex = Regex::new(r"^(?P<hour>[0-9]{1,2})(?P<minute>[0-9]{2})\b").unwrap();
     static ref TIME_H: Regex = Regex::new(r"^(?P<hour>[0-9]{1,2})\b").unwrap();
@@ -233,7 +413,7 @@ impl From<Time> for chrono::NaiveTime {
         if t.h == 24 {
             chrono::NaiveTime::from_hms(23, 59, 59)
         } else {
-            chrono::NaiveTime::from_hms(t.h(), t.m(), 0)
+            chrono::NaiveTime::from_hms(t.h(), t.m(), t.s())
         }
     }
 }
@@ -264,7 +444,7 @@ impl From<&chrono::NaiveTime> for Time {
 
 impl Display for Time {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:02}:{:02}", self.h(), self.m())
+        f.write_str(&self.format(locale::time_format()))
     }
 }
 
@@ -272,12 +452,9 @@ impl Add<TimeRelative> for Time {
     type Output = Time;
 
     fn add(self, rhs: TimeRelative) -> Self::Output {
-        let x = TimeRelative::new(false, self.h, self.m).unwrap() + rhs;
-        if x.offset_minutes() < 0 {
-            Time::ZERO
-        } else {
-            Time::try_new(x.offset_minutes()).unwrap()
-        }
+        let minutes = self.h() as i32 * 60 + self.m() as i32 + rhs.offset_minutes();
+        let t = Time::new(minutes.max(0) as u32);
+        Time::try_hms(t.h(), t.m(), self.s()).unwrap_or(t)
     }
 }
 
@@ -285,18 +462,22 @@ impl Sub for Time {
     type Output = TimeRelative;
 
     fn sub(self, rhs: Time) -> Self::Output {
-        let r = (rhs.h as i32) * 60 + rhs.m as i32;
-        let l = (self.h as i32) * 60 + self.m as i32;
-
-        let diff_minutes = l - r;
+        let diff_minutes = (self.seconds() - rhs.seconds()) / 60;
         TimeRelative::from_minutes(diff_minutes).unwrap()
     }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::parsing::time::Time;
+    use crate::parsing::locale::TimeFormat;
+    use crate::parsing::parse_result::ParseResult;
+    use crate::parsing::round_mode::RoundMode;
+    use crate::parsing::time::{Time, TimeError};
     use crate::parsing::time_relative::TimeRelative;
+    use crate::util::{StaticTimeline, Timeline};
+    use proptest::prelude::*;
+    use std::num::NonZeroU32;
+    use std::sync::Arc;
 
     #[test]
     fn test_sub() {
@@ -318,6 +499,38 @@ mod test {
         );
     }
 
+    #[test]
+    fn minutes_until_later_times() {
+        assert_eq!(Time::hm(8, 30).minutes_until(Time::hm(10, 0)), 90);
+        assert_eq!(Time::hm(10, 0).minutes_until(Time::hm(8, 30)), 0);
+        assert_eq!(Time::ZERO.minutes_until(Time::MAX), 24 * 60);
+    }
+
+    #[test]
+    fn twelve_hour_times() {
+        assert_eq!(Time::hm(13, 30).format(TimeFormat::H12), "1:30 PM");
+        assert_eq!(Time::hm(0, 15).format(TimeFormat::H12), "12:15 AM");
+        assert_eq!(Time::hms(12, 0, 5).format(TimeFormat::H12), "12:00:05 PM");
+        assert_eq!(Time::MAX.format(TimeFormat::H12), "24:00");
+        assert_eq!(Time::hm(9, 5).format(TimeFormat::H24), "09:05");
+
+        let parse = |input| Time::parse_prefix(input);
+        assert_eq!(parse("1:30 PM"), (ParseResult::Valid(Time::hm(13, 30)), ""));
+        assert_eq!(
+            parse("12am rest"),
+            (ParseResult::Valid(Time::ZERO), " rest")
+        );
+        assert_eq!(
+            parse("12:00:05pm"),
+            (ParseResult::Valid(Time::hms(12, 0, 5)), "")
+        );
+        assert_eq!(parse("13pm"), (ParseResult::Invalid(()), ""));
+        assert_eq!(
+            parse("8 amend"),
+            (ParseResult::Valid(Time::hm(8, 0)), " amend")
+        );
+    }
+
     #[test]
     fn test_try_add_relative() {
         let time = Time::hm(1, 0);
@@ -327,6 +540,23 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_wrapping_add_relative() {
+        let time = Time::hm(22, 30);
+        assert_eq!(
+            time.wrapping_add_relative(TimeRelative::from_minutes_sat(90)),
+            Time::MAX
+        );
+        assert_eq!(
+            time.wrapping_add_relative(TimeRelative::from_minutes_sat(180)),
+            Time::hm(1, 30)
+        );
+        assert_eq!(
+            Time::hm(0, 30).wrapping_add_relative(TimeRelative::from_minutes_sat(-60)),
+            Time::hm(23, 30)
+        );
+    }
+
     #[test]
     fn test_time_add_duration() {
         assert_eq!(
@@ -359,4 +589,143 @@ mod test {
         assert!(t8 >= t8);
         assert!(t9 > t8)
     }
+
+    #[test]
+    fn test_seconds() {
+        assert_eq!(
+            Time::parse_prefix("10:15:30 rest"),
+            (ParseResult::Valid(Time::hms(10, 15, 30)), " rest")
+        );
+        assert_eq!(
+            Time::parse_prefix("24:00:01"),
+            (ParseResult::Invalid(()), "")
+        );
+        assert_eq!(Time::hms(10, 15, 30).to_string(), "10:15:30");
+        assert_eq!(Time::hms(10, 15, 0).to_string(), "10:15");
+        assert!(Time::hm(10, 15) < Time::hms(10, 15, 1));
+
+        let json = serde_json::to_string(&Time::hms(8, 0, 5)).unwrap();
+        assert_eq!(json, "\"08:00:05\"");
+        assert_eq!(
+            serde_json::from_str::<Time>(&json).unwrap(),
+            Time::hms(8, 0, 5)
+        );
+
+        assert_eq!(Time::hms(10, 15, 30).round_seconds(), Time::hm(10, 16));
+        assert_eq!(Time::hms(10, 15, 29).round_seconds(), Time::hm(10, 15));
+        assert_eq!(Time::hms(23, 59, 45).round_seconds(), Time::MAX);
+        assert_eq!(
+            Time::hms(10, 7, 40).round(RoundMode::Normal, NonZeroU32::new(15).unwrap()),
+            Time::hm(10, 15)
+        );
+        assert_eq!(
+            Time::hms(10, 7, 20).round(RoundMode::Normal, NonZeroU32::new(15).unwrap()),
+            Time::hm(10, 0)
+        );
+        assert_eq!(
+            Time::hms(10, 15, 20) - Time::hms(10, 0, 40),
+            TimeRelative::from_minutes_sat(14)
+        );
+    }
+
+    #[test]
+    fn tells_why_time_is_invalid() {
+        let timeline: Timeline = Arc::new(StaticTimeline::parse("2022-01-31 23:00"));
+        assert_eq!(
+            Time::parse_prefix_checked("25:00 QUA-1"),
+            (ParseResult::Invalid(TimeError::OutOfRange), " QUA-1")
+        );
+        let (late, rest) = Time::parse_with_offset_checked(&timeline, "+2h QUA-1");
+        assert_eq!(rest, " QUA-1");
+        assert_eq!(
+            late,
+            ParseResult::Invalid(TimeError::LeavesDay(TimeRelative::from_minutes_sat(120)))
+        );
+        assert_eq!(
+            TimeError::LeavesDay(TimeRelative::from_minutes_sat(120)).to_string(),
+            "now +2h is outside of the day"
+        );
+    }
+
+    #[test]
+    fn steps_by_resolution() {
+        let quarter = NonZeroU32::new(15).unwrap();
+        assert_eq!(Time::hm(10, 0).step(1, quarter), Time::hm(10, 15));
+        assert_eq!(Time::hm(10, 0).step(-2, quarter), Time::hm(9, 30));
+        assert_eq!(Time::hm(10, 7).step(1, quarter), Time::hm(10, 15));
+        assert_eq!(Time::hm(10, 7).step(-1, quarter), Time::hm(10, 0));
+        assert_eq!(Time::hms(10, 0, 20).step(-1, quarter), Time::hm(10, 0));
+        assert_eq!(Time::hm(23, 50).step(3, quarter), Time::MAX);
+        assert_eq!(Time::hm(0, 10).step(-3, quarter), Time::hm(0, 0));
+    }
+
+    #[test]
+    fn saturates_at_the_end_of_the_day() {
+        let quarter = NonZeroU32::new(15).unwrap();
+        assert_eq!(Time::hm(23, 53).round(RoundMode::Up, quarter), Time::MAX);
+        assert_eq!(
+            Time::hm(23, 53).round(RoundMode::Normal, quarter),
+            Time::MAX
+        );
+        assert_eq!(Time::hm(23, 30).with_m(75), Time::MAX);
+        assert_eq!(Time::new(2000), Time::MAX);
+        assert_eq!(
+            Time::hm(20, 0) + TimeRelative::from_minutes_sat(300),
+            Time::MAX
+        );
+        assert_eq!(
+            Time::hm(10, 0) + TimeRelative::from_minutes_sat(-720),
+            Time::ZERO
+        );
+    }
+
+    const ROUND_MODES: [RoundMode; 4] = [
+        RoundMode::None,
+        RoundMode::Up,
+        RoundMode::Down,
+        RoundMode::Normal,
+    ];
+
+    proptest! {
+        #[test]
+        fn parsing_never_panics(input in "[0-9:.+nowhmaApPM -]{0,12}|\\PC{0,12}") {
+            let timeline: Timeline = Arc::new(StaticTimeline::parse("2022-01-31 12:00"));
+            let _ = Time::parse_prefix(&input);
+            let (time, rest) = Time::parse_with_offset_checked(&timeline, &input);
+            prop_assert!(input.ends_with(rest));
+            if let ParseResult::Valid(time) = time {
+                prop_assert!(time <= Time::MAX);
+            }
+        }
+
+        #[test]
+        fn formatted_times_parse_again(t in 0u32..=24 * 60, s in 0u32..60) {
+            let time = Time::try_hms(t / 60, t % 60, s).unwrap_or_else(|| Time::new(t));
+            for format in [TimeFormat::H24, TimeFormat::H12] {
+                let text = time.format(format);
+                prop_assert_eq!(Time::parse_prefix(&text), (ParseResult::Valid(time), ""));
+            }
+        }
+
+        #[test]
+        fn arithmetic_stays_in_the_day(
+            t in 0u32..=24 * 60,
+            s in 0u32..60,
+            offset in -24 * 60..=24 * 60,
+            mode in 0usize..4,
+            resolution in 1u32..=24 * 60,
+        ) {
+            let time = Time::try_hms(t / 60, t % 60, s).unwrap_or_else(|| Time::new(t));
+            let offset = TimeRelative::from_minutes_sat(offset);
+            prop_assert!(time + offset <= Time::MAX);
+            prop_assert!(time.wrapping_add_relative(offset) <= Time::MAX);
+            if let Some(sum) = time.try_add_relative(offset) {
+                prop_assert_eq!(sum, time + offset);
+            }
+            let resolution = NonZeroU32::new(resolution).unwrap();
+            prop_assert!(time.round(ROUND_MODES[mode], resolution) <= Time::MAX);
+            let _ = offset.round(ROUND_MODES[mode], resolution);
+            let _ = time - Time::new(t / 2);
+        }
+    }
 }
@@ -1,7 +1,7 @@
 use crate::parsing::parse_result::ParseResult;
-use crate::parsing::time::Time;
+use crate::parsing::time::{Time, TimeError};
 use crate::parsing::time_relative::TimeRelative;
-use std::fmt::Write;
+use std::fmt::{Display, Formatter, Write};
 use std::str::FromStr;
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
@@ -171,10 +171,22 @@ pub fn check_any_limit_overlaps(t: Time, limits: &[TimeRange]) -> TimeResult {
 #[derive(Debug, Copy, Clone)]
 pub enum InvalidTime {
     Bad,
+    Parse(TimeError),
     TooEarly { t: Time, min: Time },
     TooLate { t: Time, max: Time },
 }
 
+impl Display for InvalidTime {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InvalidTime::Bad => f.write_str("invalid time"),
+            InvalidTime::Parse(e) => e.fmt(f),
+            InvalidTime::TooEarly { t, min } => write!(f, "{} is before {}", t, min),
+            InvalidTime::TooLate { t, max } => write!(f, "{} is after {}", t, max),
+        }
+    }
+}
+
 pub type TimeResult = ParseResult<Time, InvalidTime>;
 
 #[cfg(test)]
@@ -227,24 +227,28 @@ mod parse {
     }
 
     fn take_negative(c: &Captures) -> bool {
-        let sign = c.name("sign").unwrap().as_str();
-        sign == "-"
+        matches!(c.name("sign"), Some(sign) if sign.as_str() == "-")
+    }
+
+    /// The number in the optional group `name`, `Invalid` if it does not fit
+    fn take_number<T: FromStr + Default>(c: &Captures, name: &str) -> ParseResult<T, ()> {
+        match c.name(name) {
+            Some(m) => T::from_str(m.as_str()).ok().into(),
+            None => ParseResult::Valid(T::default()),
+        }
     }
 
     fn take_hm(negative: bool, c: &Captures) -> ParseResult<TimeRelative, ()> {
-        let h = u8::from_str(c.name("hour").unwrap().as_str()).unwrap();
-        let m = c
-            .name("minute")
-            .map(|m| u8::from_str(m.as_str()).unwrap())
-            .unwrap_or(0);
-        TimeRelative::new(negative, h, m).into()
+        take_number::<u8>(c, "hour").and_then(|h| {
+            take_number::<u8>(c, "minute").and_then(|m| TimeRelative::new(negative, h, m).into())
+        })
     }
 
     fn take_minutes<'a>(negative: bool, c: &'a Captures<'a>) -> ParseResult<TimeRelative, ()> {
-        let m = c
-            .name("minute")
-            .map(|m| u16::from_str(m.as_str()).unwrap())
-            .unwrap_or(0);
+        let m = match take_number::<u16>(c, "minute") {
+            ParseResult::Valid(m) => m,
+            _ => return ParseResult::Invalid(()),
+        };
         if m > 24 * 60 {
             return ParseResult::Invalid(());
         }
@@ -321,6 +325,7 @@ mod parse {
 mod test {
     use crate::parsing::parse_result::ParseResult;
     use crate::parsing::time_relative::TimeRelative;
+    use proptest::prelude::*;
 
     #[test]
     fn parse_simple_num() {
@@ -416,4 +421,23 @@ mod test {
 
         Ok(())
     }
+
+    proptest! {
+        #[test]
+        fn parsing_never_panics(input in "[0-9+hmnow -]{0,10}|\\PC{0,10}") {
+            let (_, rest) = TimeRelative::parse_relaxed(&input);
+            prop_assert!(input.ends_with(rest));
+            let _ = TimeRelative::parse_duration(&input);
+        }
+
+        #[test]
+        fn displayed_offsets_parse_again(minutes in -24 * 60..=24 * 60) {
+            let offset = TimeRelative::from_minutes_sat(minutes);
+            let text = offset.to_string();
+            prop_assert_eq!(
+                TimeRelative::parse_relaxed(&text),
+                (ParseResult::Valid(offset), "")
+            );
+        }
+    }
 }
@@ -0,0 +1,286 @@
+use std::hash::Hash;
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use iced_futures::futures::channel::mpsc;
+use iced_futures::futures::StreamExt;
+use iced_futures::BoxStream;
+use iced_native::subscription::Recipe;
+use iced_native::{event, Event, Hasher};
+use iced_winit::Subscription;
+
+use crate::conf::{write_settings, Settings};
+use crate::data::{Draft, InputHistory, RecentIssuesData};
+use crate::db::DB;
+use crate::ui::Message;
+
+/// Quiet time before the pending changes are written
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Longest time a change waits for the write while more changes keep arriving
+const MAX_DELAY: Duration = Duration::from_secs(5);
+
+/// Longest wait for the writer on exit
+const FLUSH_TIMEOUT: Duration = Duration::from_secs(5);
+
+enum Job {
+    Recent(DB, RecentIssuesData),
+    Draft(DB, Option<Draft>),
+    History(DB, InputHistory),
+    Settings(Arc<Settings>),
+    Flush(Sender<()>),
+}
+
+/// Writes the recent issues, the settings, the input draft and the input history on a
+/// background thread.
+///
+/// Only the latest state is written once no change arrived for [DEBOUNCE], or at the latest
+/// [MAX_DELAY] after the first unwritten change, so a slow disk never blocks the UI.
+/// Failures are reported as [Message::Error] by [Autosave::subscription]
+pub struct Autosave {
+    jobs: Sender<Job>,
+    failures: Failures,
+}
+
+impl Autosave {
+    pub fn start() -> Autosave {
+        let (jobs, receiver) = channel();
+        let (failure_sender, failures) = mpsc::unbounded();
+        std::thread::spawn(move || write_pending(receiver, failure_sender, DEBOUNCE, MAX_DELAY));
+        Autosave {
+            jobs,
+            failures: Failures(Arc::new(Mutex::new(Some(failures)))),
+        }
+    }
+
+    /// Stores the recent issues of `db`, only the latest data is written
+    pub fn recent(&self, db: &DB, data: RecentIssuesData) {
+        self.send(Job::Recent(db.clone(), data));
+    }
+
+    /// Stores the draft, `None` removes the stored draft
+    pub fn draft(&self, db: &DB, draft: Option<Draft>) {
+        self.send(Job::Draft(db.clone(), draft));
+    }
+
+    /// Stores the submitted inputs of the entry views
+    pub fn history(&self, db: &DB, history: InputHistory) {
+        self.send(Job::History(db.clone(), history));
+    }
+
+    pub fn settings(&self, settings: Arc<Settings>) {
+        self.send(Job::Settings(settings));
+    }
+
+    /// Writes the pending changes and waits until they are written
+    pub fn flush(&self) {
+        let (done, wait) = channel();
+        self.send(Job::Flush(done));
+        if wait.recv_timeout(FLUSH_TIMEOUT).is_err() {
+            log::warn!("Pending changes were not written in time");
+        }
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        Subscription::from_recipe(self.failures.clone())
+    }
+
+    fn send(&self, job: Job) {
+        if self.jobs.send(job).is_err() {
+            log::error!("The autosave stopped, changes are not written");
+        }
+    }
+}
+
+impl Drop for Autosave {
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
+
+#[derive(Default)]
+struct Pending {
+    /// Arrival of the oldest change that is not written yet
+    since: Option<Instant>,
+    recent: Option<(DB, RecentIssuesData)>,
+    draft: Option<(DB, Option<Draft>)>,
+    history: Option<(DB, InputHistory)>,
+    settings: Option<Arc<Settings>>,
+}
+
+impl Pending {
+    fn is_empty(&self) -> bool {
+        self.recent.is_none()
+            && self.draft.is_none()
+            && self.history.is_none()
+            && self.settings.is_none()
+    }
+
+    fn changed(&mut self) {
+        self.since.get_or_insert_with(Instant::now);
+    }
+
+    /// Time to wait for further changes before writing
+    fn wait(&self, debounce: Duration, max_delay: Duration) -> Duration {
+        match self.since {
+            Some(since) => debounce.min(max_delay.saturating_sub(since.elapsed())),
+            None => debounce,
+        }
+    }
+
+    fn write(&mut self, failures: &mpsc::UnboundedSender<Message>) {
+        self.since = None;
+        if let Some((db, data)) = self.recent.take() {
+            if let Err(e) = db.store_recent(&data) {
+                report(failures, format!("Cannot store the recent issues: {}", e));
+            }
+        }
+        if let Some((db, draft)) = self.draft.take() {
+            let result = match &draft {
+                Some(draft) => db.store_draft(draft),
+                None => db.clear_draft(),
+            };
+            // the draft is written on every change, a failure is not worth an error message
+            if let Err(e) = result {
+                log::warn!("Cannot store draft: {:?}", e);
+            }
+        }
+        if let Some((db, history)) = self.history.take() {
+            if let Err(e) = db.store_input_history(&history) {
+                log::warn!("Cannot store the input history: {:?}", e);
+            }
+        }
+        if let Some(settings) = self.settings.take() {
+            if let Err(e) = write_settings(&settings) {
+                report(failures, format!("Cannot store the settings: {:#}", e));
+            }
+        }
+    }
+}
+
+fn write_pending(
+    jobs: Receiver<Job>,
+    failures: mpsc::UnboundedSender<Message>,
+    debounce: Duration,
+    max_delay: Duration,
+) {
+    let mut pending = Pending::default();
+    loop {
+        let job = if pending.is_empty() {
+            jobs.recv().map_err(|_| RecvTimeoutError::Disconnected)
+        } else {
+            let wait = pending.wait(debounce, max_delay);
+            if wait.is_zero() {
+                // a steady stream of changes does not postpone the write
+                Err(RecvTimeoutError::Timeout)
+            } else {
+                jobs.recv_timeout(wait)
+            }
+        };
+        match job {
+            Ok(Job::Recent(db, data)) => {
+                pending.recent = Some((db, data));
+                pending.changed();
+            }
+            Ok(Job::Draft(db, draft)) => {
+                pending.draft = Some((db, draft));
+                pending.changed();
+            }
+            Ok(Job::History(db, history)) => {
+                pending.history = Some((db, history));
+                pending.changed();
+            }
+            Ok(Job::Settings(settings)) => {
+                pending.settings = Some(settings);
+                pending.changed();
+            }
+            Ok(Job::Flush(done)) => {
+                pending.write(&failures);
+                let _ = done.send(());
+            }
+            Err(RecvTimeoutError::Timeout) => pending.write(&failures),
+            Err(RecvTimeoutError::Disconnected) => {
+                pending.write(&failures);
+                return;
+            }
+        }
+    }
+}
+
+fn report(failures: &mpsc::UnboundedSender<Message>, error: String) {
+    log::error!("{}", error);
+    // without the subscription the log is the only trace
+    let _ = failures.unbounded_send(Message::Error(error));
+}
+
+/// Handed to the subscription once, later recipes with the same hash are not started
+#[derive(Clone)]
+struct Failures(Arc<Mutex<Option<mpsc::UnboundedReceiver<Message>>>>);
+
+impl Recipe<Hasher, (Event, event::Status)> for Failures {
+    type Output = Message;
+
+    fn hash(&self, state: &mut Hasher) {
+        std::any::TypeId::of::<Self>().hash(state);
+    }
+
+    fn stream(self: Box<Self>, _input: BoxStream<(Event, event::Status)>) -> BoxStream<Message> {
+        let receiver = self.0.lock().ok().and_then(|mut r| r.take());
+        match receiver {
+            Some(receiver) => receiver.boxed(),
+            None => iced_futures::futures::stream::empty().boxed(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::mpsc::channel;
+    use std::time::{Duration, Instant};
+
+    use iced_futures::futures::channel::mpsc;
+    use tempfile::TempDir;
+
+    use crate::data::RecentIssuesData;
+    use crate::db::DB;
+    use crate::ui::autosave::{write_pending, Job, DEBOUNCE, MAX_DELAY};
+
+    #[test]
+    fn flush_writes_the_pending_changes() {
+        let db_dir = TempDir::new().unwrap();
+        let db = DB::init(db_dir.path()).unwrap();
+        let (jobs, receiver) = channel();
+        let (failures, _) = mpsc::unbounded();
+        std::thread::spawn(move || write_pending(receiver, failures, DEBOUNCE, MAX_DELAY));
+
+        let (done, wait) = channel();
+        jobs.send(Job::Recent(db, RecentIssuesData::default()))
+            .unwrap();
+        jobs.send(Job::Flush(done)).unwrap();
+        wait.recv_timeout(Duration::from_secs(5)).unwrap();
+
+        assert!(db_dir.path().join("recent.json").exists());
+    }
+
+    #[test]
+    fn steady_changes_are_written_after_the_max_delay() {
+        let db_dir = TempDir::new().unwrap();
+        let db = DB::init(db_dir.path()).unwrap();
+        let (jobs, receiver) = channel();
+        let (failures, _) = mpsc::unbounded();
+        // the debounce alone would never write while the changes keep arriving
+        let debounce = Duration::from_secs(3600);
+        let max_delay = Duration::from_millis(50);
+        std::thread::spawn(move || write_pending(receiver, failures, debounce, max_delay));
+
+        let written = db_dir.path().join("recent.json");
+        let started = Instant::now();
+        while !written.exists() && started.elapsed() < Duration::from_secs(10) {
+            jobs.send(Job::Recent(db.clone(), RecentIssuesData::default()))
+                .unwrap();
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert!(written.exists());
+    }
+}
@@ -1,24 +1,41 @@
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
 use iced_native::widget::Row;
-use iced_wgpu::TextInput;
-use iced_winit::widget::{text_input, Column};
+use iced_winit::widget::{text_input, Column, Text};
 
-use parsing::WorkBuilder;
+pub(in crate::ui) use parsing::WorkBuilder;
+use parsing::{TIME_COMBINATIONS, TIME_FORMATS};
 
-use crate::conf::SettingsRef;
-use crate::data::{ActiveDay, JiraIssue, RecentIssuesRef, Work};
+use crate::conf::{OverlapHandling, SettingsRef};
+use crate::data::{Action, ActiveDay, CommentHistory, JiraIssue, RecentIssuesRef, Work};
+use crate::parsing::insert_issue;
 use crate::parsing::parse_result::ParseResult;
 use crate::parsing::time::Time;
+use crate::parsing::time_limit::TimeRange;
+use crate::parsing::time_relative::TimeRelative;
 use crate::ui::clip_read::ClipRead;
+use crate::ui::recent_issues_view::filter_message;
 use crate::ui::single_edit_ui::SingleEditUi;
+use crate::ui::stay_active::StayActive;
 use crate::ui::top_bar::TopBar;
-use crate::ui::util::{h_space, v_space};
-use crate::ui::{day_info_message, style, text, time_info, MainView, Message, QElement};
+use crate::ui::util::{h_space, step_input_time, v_space};
+use crate::ui::wheel_steps::WheelSteps;
+use crate::ui::widget::TextInput;
+use crate::ui::{
+    cycle_shortcut, day_info_message, shortcut_preview, style, text, time_info,
+    unbooked_time_for_day, MainView, Message, QElement,
+};
 
 mod parsing;
 
+/// Number of previous comments listed below the input
+const MAX_COMMENT_SUGGESTIONS: usize = 5;
+
 #[derive(Clone, Debug)]
 pub enum BookSingleMessage {
     TextChanged(String),
+    CommentsLoaded(Arc<CommentHistory>),
 }
 
 pub struct BookSingleUI {
@@ -29,12 +46,41 @@ pub struct BookSingleUI {
     settings: SettingsRef,
     orig: Option<Work>,
     recent_issues: RecentIssuesRef,
+    recent_filter: String,
     last_end: Option<Time>,
+    /// Actions of the day, for detecting overlaps
+    booked: BTreeSet<Action>,
+    /// Overlapping work is stored when submitted again
+    overlap_confirmed: bool,
+    /// Previous comments of the selected issue
+    comments: Option<Arc<CommentHistory>>,
+    /// Issue of the comments being loaded in the background
+    comments_requested: Option<String>,
+    comment_cycle: Option<CommentCycle>,
+}
+
+/// State of cycling through the previous comments with Tab
+#[derive(Debug)]
+struct CommentCycle {
+    /// Input without the comment
+    base: String,
+    /// Comment typed before cycling, filters the suggestions
+    typed: String,
+    index: usize,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum Overlap {
+    None,
+    /// Overlaps, but fits into the free time nearby
+    Adjustable(Work),
+    Unresolvable,
 }
 
 impl SingleEditUi<Work> for BookSingleUI {
     fn update_input(&mut self, input: String) {
         self.input = input;
+        self.overlap_confirmed = false;
         let recent = self.recent_issues.borrow();
 
         self.builder
@@ -77,25 +123,233 @@ impl BookSingleUI {
             settings,
             orig: None,
             recent_issues,
+            recent_filter: String::new(),
             last_end,
+            booked: active_day.map(|d| d.actions().clone()).unwrap_or_default(),
+            overlap_confirmed: false,
+            comments: None,
+            comments_requested: None,
+            comment_cycle: None,
         })
     }
 
+    /// Free time of the day, not counting the edited work
+    fn free_time(&self) -> Vec<TimeRange> {
+        let orig = self.orig.clone().map(Action::Work);
+        let booked = self
+            .booked
+            .iter()
+            .filter(|a| a.duration_minutes().is_some() && Some(*a) != orig.as_ref())
+            .cloned()
+            .collect();
+        unbooked_time_for_day(&booked)
+    }
+
+    /// The first free time after the start of the day, ending at the latest now
+    fn next_free(&self) -> Option<TimeRange> {
+        let day_start = self.booked.iter().find_map(|a| a.start())?;
+        let now = self.settings.load().timeline.time_now();
+        self.free_time()
+            .into_iter()
+            .map(|r| TimeRange::new(r.min().max(day_start), r.max().min(now)))
+            .find(|r| !r.is_empty())
+    }
+
+    /// Fills the empty input with the next free time
+    fn accept_next_free(&mut self) -> bool {
+        if !self.input.trim().is_empty() {
+            return false;
+        }
+        if let Some(free) = self.next_free() {
+            self.update_input(format!("{} {} ", free.min(), free.max()));
+            self.input_state.move_cursor_to_end();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Requests the previous comments, if the selected issue changed
+    fn load_comments(&mut self) -> Option<Message> {
+        let ident = &self.builder.task.get_ref()?.ident;
+        let loaded = self.comments.as_ref().map(|c| &c.ident);
+        if loaded == Some(ident) || self.comments_requested.as_ref() == Some(ident) {
+            return None;
+        }
+        self.comments_requested = Some(ident.clone());
+        Some(Message::LoadComments(ident.clone()))
+    }
+
+    /// The input split into the part before the comment and the typed comment
+    fn split_comment(&self) -> (&str, &str) {
+        let input = self.input.trim_end();
+        let typed = self
+            .builder
+            .msg
+            .as_deref()
+            .filter(|m| input.ends_with(m))
+            .unwrap_or("");
+        (input[..input.len() - typed.len()].trim_end(), typed)
+    }
+
+    /// Previous comments of the selected issue matching the typed comment
+    fn comment_suggestions(&self) -> Vec<&str> {
+        let task = match self.builder.task.get_ref() {
+            Some(task) => task,
+            None => return Vec::new(),
+        };
+        let typed = match &self.comment_cycle {
+            Some(cycle) => cycle.typed.as_str(),
+            None => self.split_comment().1,
+        };
+        match &self.comments {
+            Some(c) if c.ident == task.ident => c.matching(typed),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Replaces the comment by the next previous comment of the issue
+    fn cycle_comment(&mut self) -> bool {
+        if self.comment_cycle.is_none() {
+            let (base, typed) = self.split_comment();
+            self.comment_cycle = Some(CommentCycle {
+                base: base.to_string(),
+                typed: typed.to_string(),
+                index: usize::MAX,
+            });
+        }
+        let suggestions = self.comment_suggestions();
+        if suggestions.is_empty() {
+            self.comment_cycle = None;
+            return false;
+        }
+        let cycle = self.comment_cycle.as_ref().unwrap();
+        let index = cycle.index.wrapping_add(1) % suggestions.len();
+        let input = format!("{} {}", cycle.base, suggestions[index]);
+
+        self.update_input(input);
+        self.input_state.move_cursor_to_end();
+        if let Some(cycle) = &mut self.comment_cycle {
+            cycle.index = index;
+        }
+        true
+    }
+
+    fn overlap(&self, work: &Work) -> Overlap {
+        find_overlap(work, &self.free_time())
+    }
+
+    /// Adjusts or holds back overlapping work, depending on the settings
+    fn resolve_overlap(&mut self, work: Work) -> Option<Work> {
+        let handling = self.settings.load().overlap_handling;
+        match self.overlap(&work) {
+            Overlap::None => Some(work),
+            Overlap::Adjustable(adjusted) if handling == OverlapHandling::Adjust => Some(adjusted),
+            _ if self.overlap_confirmed => Some(work),
+            _ => {
+                self.overlap_confirmed = true;
+                None
+            }
+        }
+    }
+
+    fn overlap_info(&self, work: &Work) -> Option<String> {
+        let handling = self.settings.load().overlap_handling;
+        match self.overlap(work) {
+            Overlap::None => None,
+            Overlap::Adjustable(a) if handling == OverlapHandling::Adjust => Some(format!(
+                "Overlaps existing bookings, will be stored as {} - {}",
+                a.start, a.end
+            )),
+            _ if self.overlap_confirmed => {
+                Some("Overlaps existing bookings, submit again to store anyway".to_string())
+            }
+            _ => Some("Overlaps existing bookings".to_string()),
+        }
+    }
+
+    /// Work split across multiple issues, the whole range is checked for overlaps
+    fn build_shares(&self) -> Option<Vec<Work>> {
+        self.try_build()?.split_weighted(&self.builder.shares)
+    }
+
+    /// Stores one work entry per issue of a split booking
+    fn submit_shares(&mut self, stay_active: StayActive) -> Option<Message> {
+        let work = self.try_build().and_then(|w| self.resolve_overlap(w))?;
+        let update: Vec<Action> = work
+            .split_weighted(&self.builder.shares)?
+            .into_iter()
+            .map(Action::Work)
+            .collect();
+        match self.orig.take() {
+            Some(orig) => Some(Message::ModifyActions {
+                stay_active,
+                orig: vec![Action::Work(orig)],
+                update,
+            }),
+            None => Some(Message::StoreActions(stay_active, update)),
+        }
+    }
+
     fn follow_up_msg(&mut self) -> Option<Message> {
         if self.builder.needs_clipboard() {
             self.builder.clipboard_reading = ClipRead::Reading;
             Some(Message::ReadClipboard)
         } else {
-            None
+            filter_message(&mut self.recent_filter, &self.builder.last_task_input)
         }
     }
 }
 
 impl MainView for BookSingleUI {
+    fn draft(&self) -> Option<&str> {
+        Some(self.input.as_str()).filter(|_| self.orig.is_none())
+    }
+
     fn view(&mut self) -> QElement {
-        let input = TextInput::new(&mut self.input_state, "", &self.input, |s| {
+        // before the input borrows the state
+        let overlap_info = self.try_build().and_then(|w| self.overlap_info(&w));
+        let preview = shortcut_preview(&self.settings.load(), &self.input);
+        let suggestions = self
+            .comment_suggestions()
+            .into_iter()
+            .take(MAX_COMMENT_SUGGESTIONS)
+            .collect::<Vec<_>>()
+            .join(" | ");
+        let unknown_project = self
+            .builder
+            .task
+            .get_ref()
+            .map(|t| !self.settings.load().is_valid_project(&t.ident))
+            .unwrap_or(false);
+        let shares_info = self.build_shares().map(|parts| {
+            parts
+                .iter()
+                .map(|w| format!("{} {} - {}", w.task.ident, w.start, w.end))
+                .collect::<Vec<_>>()
+                .join(", ")
+        });
+        let feedback: Vec<(String, bool)> = self
+            .builder
+            .feedback
+            .iter()
+            .map(|f| (f.to_string(), f.meaning.is_err()))
+            .chain(
+                self.builder
+                    .times_error
+                    .map(|e| (format!("Times: {}", e), true)),
+            )
+            .collect();
+        let times_rejected =
+            !self.input.trim().is_empty() && matches!(self.builder.start, ParseResult::Invalid(_));
+        let placeholder = self
+            .next_free()
+            .map(|r| format!("Next free: {} {} (Tab)", r.min(), r.max()))
+            .unwrap_or_default();
+        let input = TextInput::new(&mut self.input_state, &placeholder, &self.input, |s| {
             Message::Bs(BookSingleMessage::TextChanged(s))
         });
+        let input = WheelSteps::new(input, Message::ScrollTime);
 
         let now = self.settings.load().timeline.time_now();
 
@@ -107,10 +361,25 @@ impl MainView for BookSingleUI {
             text("End:"),
             h_space(style::SPACE),
             time_info(now, self.builder.end.clone()),
+            text(if self.builder.crosses_midnight(now) {
+                " (next day)"
+            } else {
+                ""
+            }),
             h_space(style::DSPACE),
             text("Task:"),
             h_space(style::SPACE),
-            task_info(self.builder.task.as_ref(), &self.builder.clipboard_reading),
+            match shares_info {
+                Some(info) => text(info),
+                None => task_info(self.builder.task.as_ref(), &self.builder.clipboard_reading),
+            },
+            Text::new(if unknown_project {
+                " (unknown project)"
+            } else {
+                ""
+            })
+            .color(style::ERROR_COLOR)
+            .into(),
             h_space(style::DSPACE),
             text("Message:"),
             h_space(style::SPACE),
@@ -123,35 +392,134 @@ impl MainView for BookSingleUI {
             ),
         ]);
 
-        Column::with_children(vec![
-            self.top_bar.view(),
-            v_space(style::SPACE),
-            input.into(),
-            v_space(style::SPACE),
-            status.into(),
-        ])
-        .into()
+        let mut content = vec![self.top_bar.view(), v_space(style::SPACE), input.into()];
+        if let Some(preview) = preview {
+            content.push(text(preview));
+        }
+        content.push(v_space(style::SPACE));
+        content.push(status.into());
+        if !feedback.is_empty() {
+            content.push(v_space(style::SPACE));
+            for (line, rejected) in feedback {
+                content.push(if rejected {
+                    Text::new(line).color(style::ERROR_COLOR).into()
+                } else {
+                    text(line)
+                });
+            }
+        }
+        if times_rejected {
+            content.push(v_space(style::SPACE));
+            content.push(text(TIME_FORMATS));
+            content.push(text(TIME_COMBINATIONS));
+        }
+        if !self.builder.links.is_empty() {
+            content.push(v_space(style::SPACE));
+            content.push(text(format!("Links: {}", self.builder.links.join(" "))));
+        }
+        if !suggestions.is_empty() {
+            content.push(v_space(style::SPACE));
+            content.push(text(format!("Previous comments (Tab): {}", suggestions)));
+        }
+        if let Some(info) = overlap_info {
+            content.push(v_space(style::SPACE));
+            content.push(Text::new(info).color(style::ERROR_COLOR).into());
+        }
+
+        Column::with_children(content).into()
     }
 
     fn update(&mut self, msg: Message) -> Option<Message> {
         match msg {
+            Message::InsertIssue(ident) => {
+                self.input_state.move_cursor_to_end();
+                let input = insert_issue(&self.input, &ident);
+                Some(Message::Bs(BookSingleMessage::TextChanged(input)))
+            }
+            Message::CycleShortcut => cycle_shortcut(&self.settings.load(), &self.input, |input| {
+                Message::Bs(BookSingleMessage::TextChanged(input))
+            }),
             Message::Bs(BookSingleMessage::TextChanged(msg)) => {
                 self.update_input(msg);
+                self.comment_cycle = None;
+                self.load_comments().or_else(|| self.follow_up_msg())
+            }
+            Message::ScrollTime(steps) => {
+                step_input_time(&self.settings.load(), &self.input, steps)
+                    .map(|input| Message::Bs(BookSingleMessage::TextChanged(input)))
+            }
+            Message::Bs(BookSingleMessage::CommentsLoaded(comments)) => {
+                self.comments = Some(comments);
                 self.follow_up_msg()
             }
             Message::ClipboardValue(v) => {
                 self.builder.apply_clipboard(v);
                 None
             }
+            // relative times follow the clock, a confirmed overlap stays confirmed
+            Message::Tick => {
+                let overlap_confirmed = self.overlap_confirmed;
+                self.update_input(self.input.clone());
+                self.overlap_confirmed = overlap_confirmed;
+                None
+            }
+            Message::SubmitCurrent(stay_active) if !self.builder.shares.is_empty() => {
+                self.submit_shares(stay_active)
+            }
             Message::SubmitCurrent(stay_active) => {
-                Self::on_submit_message(self.try_build(), &mut self.orig, stay_active)
+                let work = self.try_build().and_then(|w| self.resolve_overlap(w));
+                Self::on_submit_message(work, &mut self.orig, stay_active)
             }
             Message::StoreSuccess(stay_active) => stay_active.on_main_view_store(),
+            Message::Next if self.accept_next_free() || self.cycle_comment() => {
+                self.follow_up_msg()
+            }
             _ => self.follow_up_msg(),
         }
     }
 }
 
+/// Checks that `work` fits into one of the `free` ranges, or finds the nearest one
+fn find_overlap(work: &Work, free: &[TimeRange]) -> Overlap {
+    let (start, end) = (work.start, work.end);
+    if work.crosses_midnight() || free.iter().any(|r| r.min() <= start && end <= r.max()) {
+        return Overlap::None;
+    }
+
+    let free = free.iter().copied().filter(|r| r.min() < r.max());
+    let overlap_minutes = |r: TimeRange| (end.min(r.max()) - start.max(r.min())).offset_minutes();
+    let distance = |r: TimeRange| {
+        if r.min() >= end {
+            (r.min() - end).offset_minutes()
+        } else {
+            (start - r.max()).offset_minutes()
+        }
+    };
+    let adjusted = match free.clone().max_by_key(|r| overlap_minutes(*r)) {
+        Some(r) if overlap_minutes(r) > 0 => Some((start.max(r.min()), end.min(r.max()))),
+        _ => free.min_by_key(|r| distance(*r)).and_then(|r| {
+            let duration = end - start;
+            if r.min() >= end {
+                let end = r.min().try_add_relative(duration)?.min(r.max());
+                Some((r.min(), end))
+            } else {
+                let back = TimeRelative::from_minutes_sat(-duration.offset_minutes());
+                let start = r.max().try_add_relative(back)?.max(r.min());
+                Some((start, r.max()))
+            }
+        }),
+    };
+
+    match adjusted {
+        Some((start, end)) if start < end => Overlap::Adjustable(Work {
+            start,
+            end,
+            ..work.clone()
+        }),
+        _ => Overlap::Unresolvable,
+    }
+}
+
 fn task_info<'a>(v: ParseResult<&'a JiraIssue, &'a ()>, clipboard: &'a ClipRead) -> QElement<'a> {
     match v {
         ParseResult::Valid(t) => task_text(t),
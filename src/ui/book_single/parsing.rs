@@ -14,6 +14,53 @@ use regex::Regex;
 lazy_static! {
     static ref SEPARATOR: Regex = Regex::new(r"[ \t\n\r]+").unwrap();
     static ref FROM_LAST: Regex = Regex::new(r"^l\b").unwrap();
+    static ref MULTI_ISSUE: Regex = Regex::new(
+        r"^(?P<issues>[a-zA-Z]+-[0-9]+(?::[0-9]{1,3})?(?:/[a-zA-Z]+-[0-9]+(?::[0-9]{1,3})?)+)\b"
+    )
+    .unwrap();
+    static ref TRAILING_LINK: Regex = Regex::new(r"(?:^|\s)>(\S+)\s*$").unwrap();
+}
+
+/// Accepted times, shown when the input is rejected
+pub const TIME_FORMATS: &str =
+    "Times: 9, 930, 9:30, 9:30pm | relative to now: +15, -1h30m, n | durations: 45m, 1h30m";
+/// Accepted combinations of the two leading times
+pub const TIME_COMBINATIONS: &str = "Start and end: <start> <end>, <start> <duration>, \
+<duration> <end>, l <end>, l <duration> (l is the end of the last booking), \
+or a single <duration> ending now";
+
+/// How a token of the input was understood
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TokenFeedback {
+    /// Byte offset of the token in the input
+    pub start: usize,
+    /// Byte offset after the token
+    pub end: usize,
+    pub token: String,
+    /// What the token was parsed as, or why it was rejected
+    pub meaning: Result<String, String>,
+}
+
+impl TokenFeedback {
+    fn new(input: &str, start: usize, end: usize, meaning: Result<String, String>) -> Self {
+        let token = input[start..end].trim_end();
+        Self {
+            start,
+            end: start + token.len(),
+            token: token.to_string(),
+            meaning,
+        }
+    }
+}
+
+impl std::fmt::Display for TokenFeedback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}..{} '{}': ", self.start, self.end, self.token)?;
+        match &self.meaning {
+            Ok(meaning) => f.write_str(meaning),
+            Err(reason) => write!(f, "rejected, {}", reason),
+        }
+    }
 }
 
 pub enum StartTime {
@@ -23,11 +70,19 @@ pub enum StartTime {
 }
 
 #[derive(Default, Debug)]
-pub(super) struct WorkBuilder {
+pub(in crate::ui) struct WorkBuilder {
     pub start: ParseResult<Time, ()>,
     pub end: ParseResult<Time, ()>,
     pub task: ParseResult<JiraIssue, ()>,
+    /// Issues and weights of work split across multiple issues, `task` is the first of them
+    pub shares: Vec<(JiraIssue, u32)>,
+    /// URLs of trailing `>url` tokens
+    pub links: Vec<String>,
     pub msg: Option<String>,
+    /// Tokens of the input in order
+    pub feedback: Vec<TokenFeedback>,
+    /// Why the leading times do not give a start and an end
+    pub times_error: Option<&'static str>,
     pub clipboard_reading: ClipRead,
     pub last_task_input: String,
 }
@@ -37,7 +92,7 @@ impl WorkBuilder {
         matches!(self.clipboard_reading, ClipRead::DoRead)
     }
 
-    pub(super) fn parse_input(
+    pub(in crate::ui) fn parse_input(
         &mut self,
         settings: &Settings,
         recent_issues: &RecentIssues,
@@ -68,7 +123,14 @@ impl WorkBuilder {
         }
     }
 
-    pub(super) fn try_build(&self, now: Time) -> Option<Work> {
+    pub(super) fn crosses_midnight(&self, now: Time) -> bool {
+        matches!(
+            (self.start.get_with_default(now), self.end.get_with_default(now)),
+            (Some(start), Some(end)) if end < start
+        )
+    }
+
+    pub(in crate::ui) fn try_build(&self, now: Time) -> Option<Work> {
         let start = self.start.get_with_default(now);
 
         let end = self.end.get_with_default(now);
@@ -99,6 +161,7 @@ impl WorkBuilder {
                     end,
                     task,
                     description,
+                    links: self.links.clone(),
                 })
             }
             _ => None,
@@ -148,18 +211,84 @@ fn parse(
         }
     }
 
+    fn time_meaning(
+        timeline: &Timeline,
+        token: &str,
+        time: &ParseResult<TorD, ()>,
+    ) -> Result<String, String> {
+        match time {
+            ParseResult::Valid(TorD::Last) => {
+                Ok("start at the end of the last booking".to_string())
+            }
+            ParseResult::Valid(TorD::Dur(dur)) => Ok(format!("duration {}", dur)),
+            ParseResult::Valid(TorD::Time(t)) if token.starts_with(['+', '-', 'n']) => {
+                Ok(format!("time {} relative to now", t))
+            }
+            ParseResult::Valid(TorD::Time(t)) => Ok(format!("time {}", t)),
+            _ => match Time::parse_with_offset_checked(timeline, token).0 {
+                ParseResult::Invalid(e) => Err(e.to_string()),
+                _ => Err("not a time or a duration".to_string()),
+            },
+        }
+    }
+
+    fn times_error(
+        t1: &ParseResult<TorD, ()>,
+        t2: &ParseResult<TorD, ()>,
+        last_end: Option<Time>,
+    ) -> Option<&'static str> {
+        match (t1, t2) {
+            (ParseResult::Invalid(_), _) | (_, ParseResult::Invalid(_)) => None,
+            (ParseResult::None | ParseResult::Incomplete, _) => {
+                Some("no start, begin with a time or a duration")
+            }
+            (_, ParseResult::Valid(TorD::Last)) => Some("l only works as the first time"),
+            (ParseResult::Valid(TorD::Dur(_)), ParseResult::Valid(TorD::Dur(_))) => {
+                Some("two durations, give a start or an end time for one of them")
+            }
+            (ParseResult::Valid(TorD::Last), _) if last_end.is_none() => {
+                Some("l needs a booking before")
+            }
+            (ParseResult::Valid(TorD::Last | TorD::Time(_)), ParseResult::None) => {
+                Some("the start needs an end time or a duration")
+            }
+            _ => None,
+        }
+    }
+
     let timeline = &settings.timeline;
-    let input = input.trim_start();
+    let full = input;
+    let (input, links) = split_links(input.trim_start());
+    b.links = links;
+    // offsets in the full input of the remaining parts of `input`
+    let leading = full.len() - full.trim_start().len();
+    let at = |rest: &str| leading + input.len() - rest.len();
+    let links_start = leading + input.len();
+    let mut feedback = Vec::new();
 
     let (t1, rest) = parse_time(&settings.timeline, input);
+    if !t1.is_empty() {
+        let meaning = time_meaning(timeline, &full[at(input)..at(rest)], &t1);
+        feedback.push(TokenFeedback::new(full, at(input), at(rest), meaning));
+    }
     let rest = rest.trim_start();
     // just avoid double_parsing when input contains no times at all
     // if may be removed for better readability but worse performance
-    let (t2, rest) = if t1.is_empty() {
+    let (t2, rest_t2) = if t1.is_empty() {
         (ParseResult::None, rest)
     } else {
         parse_time(&settings.timeline, rest)
     };
+    if !t2.is_empty() {
+        let meaning = time_meaning(timeline, &full[at(rest)..at(rest_t2)], &t2);
+        feedback.push(TokenFeedback::new(full, at(rest), at(rest_t2), meaning));
+    }
+    let rest = rest_t2;
+    b.times_error = if input.trim().is_empty() {
+        None
+    } else {
+        times_error(&t1, &t2, last_end)
+    };
 
     let (start, end) = match (t1, t2) {
         (ParseResult::Valid(TorD::Dur(_)), ParseResult::Valid(TorD::Dur(_))) => {
@@ -168,9 +297,10 @@ fn parse(
         (ParseResult::Valid(TorD::Time(s)), ParseResult::Valid(TorD::Time(e))) => {
             (ParseResult::Valid(s), ParseResult::Valid(e))
         }
-        (ParseResult::Valid(TorD::Time(s)), ParseResult::Valid(TorD::Dur(dur))) => {
-            (ParseResult::Valid(s), s.try_add_relative(dur).into())
-        }
+        (ParseResult::Valid(TorD::Time(s)), ParseResult::Valid(TorD::Dur(dur))) => (
+            ParseResult::Valid(s),
+            ParseResult::Valid(s.wrapping_add_relative(dur)),
+        ),
         (ParseResult::Valid(TorD::Last), ParseResult::Valid(TorD::Time(s)))
             if last_end.is_some() =>
         {
@@ -181,7 +311,7 @@ fn parse(
         {
             (
                 ParseResult::Valid(last_end.unwrap()),
-                ParseResult::Valid(last_end.unwrap() + s),
+                ParseResult::Valid(last_end.unwrap().wrapping_add_relative(s)),
             )
         }
         (ParseResult::Valid(TorD::Dur(dur)), ParseResult::Valid(TorD::Time(e))) => {
@@ -198,12 +328,64 @@ fn parse(
 
     let issue_parser = IssueParserWithRecent::new(&settings.issue_parser, recent_issues);
 
-    let (
-        IssueParsed {
-            r: issue, input, ..
-        },
-        comment,
-    ) = parse_from_issue(&issue_parser, rest.trim_start());
+    let rest = rest.trim_start();
+    let (issue, input, after) = if let Some(c) = MULTI_ISSUE.captures(rest) {
+        let matched = c.get(0).unwrap();
+        let shares = parse_shares(c.name("issues").unwrap().as_str());
+        let issue: ParseResult<JiraIssue, ()> = shares
+            .as_ref()
+            .and_then(|s| s.first())
+            .map(|(i, _)| i.clone())
+            .ok_or(())
+            .into();
+        let meaning = match &shares {
+            Some(shares) => Ok(format!(
+                "work split across {}",
+                shares
+                    .iter()
+                    .map(|(i, weight)| format!("{} (weight {})", i.ident, weight))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )),
+            None => Err("give weights for all issues or for none".to_string()),
+        };
+        let after = &rest[matched.end()..];
+        feedback.push(TokenFeedback::new(full, at(rest), at(after), meaning));
+        b.shares = shares.unwrap_or_default();
+        (issue, matched.as_str(), Some(after))
+    } else {
+        b.shares = Vec::new();
+        let (parsed, comment) = parse_from_issue(&issue_parser, rest);
+        let meaning = match &parsed.r {
+            ParseResult::Valid(issue) => Ok(format!("issue {}", issue.ident)),
+            ParseResult::None => Ok("issue from the clipboard".to_string()),
+            ParseResult::Incomplete => Err("incomplete issue id".to_string()),
+            ParseResult::Invalid(_) => {
+                Err("not an issue id, shortcut or number of a recent issue".to_string())
+            }
+        };
+        if at(parsed.rest) > at(rest) {
+            feedback.push(TokenFeedback::new(full, at(rest), at(parsed.rest), meaning));
+        }
+        (parsed.r, parsed.input, comment.map(|_| parsed.rest))
+    };
+    // the comment is the rest of the input after the issue
+    let comment = after.map(str::trim).filter(|c| !c.is_empty());
+    if let Some(after) = after.filter(|_| comment.is_some()) {
+        let start = at(after.trim_start());
+        let meaning = Ok("comment".to_string());
+        feedback.push(TokenFeedback::new(full, start, links_start, meaning));
+    }
+    let mut link_start = links_start;
+    for link in &b.links {
+        if let Some(offset) = full[link_start..].find(&format!(">{}", link)) {
+            let start = link_start + offset;
+            link_start = start + link.len() + 1;
+            let meaning = Ok("link".to_string());
+            feedback.push(TokenFeedback::new(full, start, link_start, meaning));
+        }
+    }
+    b.feedback = feedback;
 
     let old_issue = std::mem::take(&mut b.task);
 
@@ -229,6 +411,42 @@ fn parse(
     b.last_task_input = input.to_string();
 }
 
+/// Splits the trailing `>url` tokens off the input
+fn split_links(input: &str) -> (&str, Vec<String>) {
+    let mut rest = input;
+    let mut links = Vec::new();
+    while let Some(c) = TRAILING_LINK.captures(rest) {
+        links.insert(0, c[1].to_string());
+        rest = &rest[..c.get(0).unwrap().start()];
+    }
+    (rest, links)
+}
+
+/// Issues of `A-1/B-2` with equal weights or of `A-1:70/B-2:30` with the given weights
+fn parse_shares(input: &str) -> Option<Vec<(JiraIssue, u32)>> {
+    let parts: Vec<(&str, Option<u32>)> = input
+        .split('/')
+        .map(|part| match part.split_once(':') {
+            Some((id, weight)) => (id, weight.parse().ok()),
+            None => (part, None),
+        })
+        .collect();
+    let weighted = parts.iter().filter(|(_, w)| w.is_some()).count();
+    if weighted != 0 && weighted != parts.len() {
+        return None;
+    }
+
+    let shares = parts
+        .into_iter()
+        .map(|(id, weight)| Some((JiraIssue::create(id).ok()?, weight.unwrap_or(1))))
+        .collect::<Option<Vec<_>>>()?;
+    if shares.iter().all(|(_, w)| *w == 0) {
+        None
+    } else {
+        Some(shares)
+    }
+}
+
 fn parse_from_issue<'a, 'b>(
     ip: &'b impl IssueParser,
     input: &'a str,
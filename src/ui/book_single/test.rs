@@ -3,11 +3,12 @@
 use std::collections::BTreeMap;
 use std::sync::Arc;
 
-use crate::conf::{into_settings_ref, SettingsRef};
-use crate::data::test_support::time;
-use crate::data::{ActiveDayBuilder, JiraIssue, Location, RecentIssuesRef, Work};
+use crate::conf::{into_settings_ref, OverlapHandling, SettingsRef};
+use crate::data::test_support::{day_start, time, work, workn};
+use crate::data::{ActiveDayBuilder, CommentHistory, JiraIssue, Location, RecentIssuesRef, Work};
 use crate::parsing::parse_result::ParseResult;
 use crate::parsing::time::Time;
+use crate::parsing::time_limit::TimeRange;
 use crate::parsing::{parse_issue_clipboard, JiraIssueParser};
 use crate::ui::book_single::{BookSingleMessage, BookSingleUI};
 use crate::ui::clip_read::ClipRead;
@@ -113,6 +114,32 @@ fn test_parse_input_duration_absolute() {
     assert_eq!(ui.builder.msg.as_deref(), Some("will finish soon"));
 }
 
+#[test]
+fn test_parse_input_across_midnight() {
+    let mut ui = make_ui("23:00");
+    ui.parse_input("22 3h N-1 night shift");
+    assert_eq!(ui.builder.start, ParseResult::Valid(Time::hm(22, 0)));
+    assert_eq!(ui.builder.end, ParseResult::Valid(Time::hm(1, 0)));
+    assert!(ui.builder.crosses_midnight(Time::hm(23, 0)));
+
+    ui.parse_input("22 2 N-1 night shift");
+    assert_eq!(ui.builder.start, ParseResult::Valid(Time::hm(22, 0)));
+    assert_eq!(ui.builder.end, ParseResult::Valid(Time::hm(2, 0)));
+    assert!(ui.builder.crosses_midnight(Time::hm(23, 0)));
+
+    let (work, continuation) = ui
+        .builder
+        .try_build(Time::hm(23, 0))
+        .unwrap()
+        .split_at_midnight();
+    assert_eq!((work.start, work.end), (Time::hm(22, 0), Time::MAX));
+    let continuation = continuation.unwrap();
+    assert_eq!(
+        (continuation.start, continuation.end),
+        (Time::ZERO, Time::hm(2, 0))
+    );
+}
+
 #[test]
 fn test_parse_valid_clipboard() {
     assert_eq!(
@@ -166,7 +193,8 @@ fn book_single_integration_test() {
             start: Time::hm(1, 0),
             end: Time::hm(10, 0),
             task: JiraIssue::create("CLIP-1234").unwrap(),
-            description: "comment".to_string()
+            description: "comment".to_string(),
+            links: Vec::new(),
         }
     );
 
@@ -212,7 +240,8 @@ fn applies_recent_issues() {
                 description: Some("Description".to_string()),
                 default_action: Some("Default action".to_string())
             },
-            description: "Default action".to_string()
+            description: "Default action".to_string(),
+            links: Vec::new(),
         })
     )
 }
@@ -233,7 +262,8 @@ fn can_adapt_recent_issue() {
                 description: Some("Description".to_string()),
                 default_action: Some("Default action".to_string())
             },
-            description: "modified action".to_string()
+            description: "modified action".to_string(),
+            links: Vec::new(),
         })
     )
 }
@@ -271,3 +301,244 @@ fn setup_test_ui() -> (SettingsRef, RecentIssuesRef, Box<BookSingleUI>) {
 
     (settings, recent, ui)
 }
+
+fn make_booked_ui(overlap_handling: OverlapHandling) -> Box<BookSingleUI> {
+    let settings = into_settings_ref(Settings {
+        timeline: Arc::new(StaticTimeline::parse("2020-10-10 12:00")),
+        overlap_handling,
+        ..Default::default()
+    });
+    let active_day = ActiveDayBuilder {
+        day: settings.load().timeline.today(),
+        active_issue: None,
+        main_location: Location::Office,
+        actions: vec![day_start("o8"), work("9", "10", "A-1", "a")],
+    }
+    .build();
+
+    BookSingleUI::for_active_day(
+        settings.clone(),
+        RecentIssuesRef::empty(settings),
+        Some(&active_day),
+    )
+}
+
+#[test]
+fn warns_about_overlapping_work() {
+    let mut ui = make_booked_ui(OverlapHandling::Warn);
+    ui.parse_input("930 1030 B-1 b");
+    assert!(ui.update(Message::SubmitCurrent(StayActive::Yes)).is_none());
+    assert!(matches!(
+        ui.update(Message::SubmitCurrent(StayActive::Yes)),
+        Some(Message::StoreAction(_, action)) if action == work("9:30", "10:30", "B-1", "b")
+    ));
+
+    ui.parse_input("10 11 B-1 b");
+    assert!(matches!(
+        ui.update(Message::SubmitCurrent(StayActive::Yes)),
+        Some(Message::StoreAction(_, action)) if action == work("10", "11", "B-1", "b")
+    ));
+}
+
+#[test]
+fn moves_overlapping_work_into_free_time() {
+    let mut ui = make_booked_ui(OverlapHandling::Adjust);
+    ui.parse_input("930 1030 B-1 b");
+    assert!(matches!(
+        ui.update(Message::SubmitCurrent(StayActive::Yes)),
+        Some(Message::StoreAction(_, action)) if action == work("10", "10:30", "B-1", "b")
+    ));
+
+    ui.entry_to_edit(workn("9", "10", "A-1", "a"));
+    ui.parse_input("9 945 A-1 a");
+    assert!(matches!(
+        ui.update(Message::SubmitCurrent(StayActive::Yes)),
+        Some(Message::ModifyAction { update, .. }) if *update == work("9", "9:45", "A-1", "a")
+    ));
+}
+
+#[test]
+fn suggests_next_free_time() {
+    let mut ui = make_booked_ui(OverlapHandling::Warn);
+    assert_eq!(ui.next_free(), Some(TimeRange::new(time("8"), time("9"))));
+
+    assert!(ui.update(Message::Next).is_none());
+    assert_eq!(ui.builder.start, ParseResult::Valid(time("8")));
+    assert_eq!(ui.builder.end, ParseResult::Valid(time("9")));
+
+    ui.parse_input("10 B-1");
+    ui.update(Message::Next);
+    assert_eq!(ui.input, "10 B-1");
+}
+
+#[test]
+fn cycles_through_previous_comments() {
+    let mut ui = make_ui("12:00");
+    assert!(matches!(
+        ui.update(Message::Bs(BookSingleMessage::TextChanged("9 10 B-1".into()))),
+        Some(Message::LoadComments(ident)) if ident == "B-1"
+    ));
+    // not requested again while the comments load
+    assert!(!matches!(
+        ui.update(Message::Bs(BookSingleMessage::TextChanged(
+            "9 10 B-1".into()
+        ))),
+        Some(Message::LoadComments(_))
+    ));
+    ui.update(Message::Bs(BookSingleMessage::CommentsLoaded(Arc::new(
+        CommentHistory {
+            ident: "B-1".into(),
+            comments: vec!["review".into(), "planning".into()],
+        },
+    ))));
+
+    ui.update(Message::Next);
+    assert_eq!(ui.input, "9 10 B-1 review");
+    ui.update(Message::Next);
+    assert_eq!(ui.input, "9 10 B-1 planning");
+    assert_eq!(ui.builder.msg.as_deref(), Some("planning"));
+    ui.update(Message::Next);
+    assert_eq!(ui.input, "9 10 B-1 review");
+
+    ui.update(Message::Bs(BookSingleMessage::TextChanged(
+        "9 10 B-1 pl".into(),
+    )));
+    ui.update(Message::Next);
+    assert_eq!(ui.input, "9 10 B-1 planning");
+}
+
+#[test]
+fn splits_work_across_issues() {
+    let mut ui = make_ui("12:00");
+    match ui.update(Message::Bs(BookSingleMessage::TextChanged(
+        "9 12 A-1/b-2 refactoring".into(),
+    ))) {
+        Some(Message::LoadComments(_)) | None => (),
+        other => panic!("unexpected {:?}", other),
+    }
+    assert!(matches!(
+        ui.update(Message::SubmitCurrent(StayActive::Yes)),
+        Some(Message::StoreActions(_, actions))
+            if actions == vec![
+                work("9", "10:30", "A-1", "refactoring"),
+                work("10:30", "12", "B-2", "refactoring"),
+            ]
+    ));
+
+    ui.parse_input("9 12 A-1:70/B-2:30 refactoring");
+    assert!(matches!(
+        ui.update(Message::SubmitCurrent(StayActive::Yes)),
+        Some(Message::StoreActions(_, actions))
+            if actions == vec![
+                work("9", "11:06", "A-1", "refactoring"),
+                work("11:06", "12", "B-2", "refactoring"),
+            ]
+    ));
+
+    ui.parse_input("9 12 A-1:70/B-2 refactoring");
+    assert_eq!(ui.builder.task, ParseResult::Invalid(()));
+}
+
+#[test]
+fn parses_trailing_links() {
+    let (settings, _, mut ui) = setup_test_ui();
+    ui.parse_input("10 11 A-1 review > done >https://git/pr/1 >https://meet/x");
+
+    let result = ui.builder.try_build(settings.load().timeline.time_now());
+    assert_eq!(
+        result,
+        Some(Work {
+            start: time("10"),
+            end: time("11"),
+            task: JiraIssue::create("A-1").unwrap(),
+            description: "review > done".to_string(),
+            links: vec!["https://git/pr/1".to_string(), "https://meet/x".to_string()],
+        })
+    )
+}
+
+#[test]
+fn explains_each_token() {
+    let mut ui = make_ui("12:00");
+    ui.parse_input(" 9 +15 I-2 review >https://git/pr/1");
+    let feedback: Vec<String> = ui
+        .builder
+        .feedback
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    assert_eq!(
+        feedback,
+        vec![
+            "1..2 '9': time 09:00",
+            "3..6 '+15': time 12:15 relative to now",
+            "7..10 'I-2': issue I-2",
+            "11..17 'review': comment",
+            "18..35 '>https://git/pr/1': link",
+        ]
+    );
+    assert_eq!(ui.builder.times_error, None);
+
+    ui.parse_input("25:00 1h I-2");
+    assert_eq!(
+        ui.builder.feedback[0].meaning,
+        Err("not a time of the day, use 0:00 to 24:00".to_string())
+    );
+
+    ui.parse_input("1h 2h I-2");
+    assert_eq!(
+        ui.builder.times_error,
+        Some("two durations, give a start or an end time for one of them")
+    );
+    ui.parse_input("9 I-2");
+    assert_eq!(
+        ui.builder.times_error,
+        Some("the start needs an end time or a duration")
+    );
+}
+
+#[test]
+fn inserts_clicked_recent_issue() {
+    let mut ui = make_ui("12:00");
+    ui.update(Message::Bs(BookSingleMessage::TextChanged(
+        "9 10 /dv fix".to_string(),
+    )));
+    match ui.update(Message::InsertIssue("DEV-7".to_string())) {
+        Some(Message::Bs(BookSingleMessage::TextChanged(input))) => {
+            assert_eq!(input, "9 10 DEV-7 fix")
+        }
+        other => panic!("unexpected {:?}", other),
+    }
+}
+
+#[test]
+fn cycles_issue_shortcut() {
+    let mut ui = make_ui("12:00");
+    ui.update(Message::Bs(BookSingleMessage::TextChanged(
+        "9 10 a fix".to_string(),
+    )));
+    // the only shortcut wraps around to itself
+    match ui.update(Message::CycleShortcut) {
+        Some(Message::Bs(BookSingleMessage::TextChanged(input))) => {
+            assert_eq!(input, "9 10 a fix")
+        }
+        other => panic!("unexpected {:?}", other),
+    }
+
+    ui.update(Message::Bs(BookSingleMessage::TextChanged(
+        "9 10 M-2 fix".to_string(),
+    )));
+    assert!(ui.update(Message::CycleShortcut).is_none());
+}
+
+#[test]
+fn wheel_moves_the_last_time() {
+    let mut ui = make_ui("12:00");
+    ui.update(Message::Bs(BookSingleMessage::TextChanged(
+        "9 10 B-1 review".into(),
+    )));
+    assert!(matches!(
+        ui.update(Message::ScrollTime(-1)),
+        Some(Message::Bs(BookSingleMessage::TextChanged(input))) if input == "9 09:45 B-1 review"
+    ));
+}
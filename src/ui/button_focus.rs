@@ -0,0 +1,104 @@
+use iced_native::widget::{button, text_input, Button};
+
+use crate::ui::{style, Message, Quarble};
+
+type Renderer = <Quarble as iced_winit::Program>::Renderer;
+
+/// Keyboard focus for the buttons of a view, iced buttons cannot be focused.
+///
+/// The view registers its buttons in focus order while it is built, Tab moves the
+/// focus outline and Enter presses the focused button
+#[derive(Default)]
+pub struct ButtonFocus {
+    focused: Option<usize>,
+    /// Messages of the buttons of the last built view
+    targets: Vec<Message>,
+}
+
+impl ButtonFocus {
+    /// Forgets the buttons of the last built view
+    pub fn clear_targets(&mut self) {
+        self.targets.clear();
+    }
+
+    /// An inline button that shows the focus outline while it has the focus
+    pub fn inline_button<'a>(
+        &mut self,
+        state: &'a mut button::State,
+        text: &str,
+        on_press: Message,
+    ) -> Button<'a, Message, Renderer> {
+        let focused = self.focused == Some(self.targets.len());
+        self.targets.push(on_press.clone());
+        let button = style::inline_button(state, text).on_press(on_press);
+        if focused {
+            button.style(style::FocusedButton)
+        } else {
+            button
+        }
+    }
+
+    pub fn has_focus(&self) -> bool {
+        self.focused.is_some()
+    }
+
+    pub fn unfocus(&mut self) {
+        self.focused = None;
+    }
+
+    /// Moves the focus to the next button, false if the focus left the last button
+    pub fn focus_next(&mut self) -> bool {
+        let next = self.focused.map_or(0, |index| index + 1);
+        self.focused = (next < self.targets.len()).then_some(next);
+        self.focused.is_some()
+    }
+
+    /// Moves the focus to the previous button, false if the focus left the first button
+    pub fn focus_previous(&mut self) -> bool {
+        self.focused = match self.focused {
+            Some(index) => index.checked_sub(1),
+            None => self.targets.len().checked_sub(1),
+        };
+        self.focused.is_some()
+    }
+
+    /// Tab order of a view without text inputs, wraps around at the ends
+    pub fn cycle_next(&mut self) {
+        if !self.focus_next() {
+            self.focus_next();
+        }
+    }
+
+    pub fn cycle_previous(&mut self) {
+        if !self.focus_previous() {
+            self.focus_previous();
+        }
+    }
+
+    /// Tab order of a view with one text input followed by its buttons
+    pub fn next_with_input(&mut self, input: &mut text_input::State) {
+        self.leave_input(input);
+        if !self.focus_next() {
+            input.focus();
+        }
+    }
+
+    pub fn previous_with_input(&mut self, input: &mut text_input::State) {
+        self.leave_input(input);
+        if !self.focus_previous() {
+            input.focus();
+        }
+    }
+
+    fn leave_input(&mut self, input: &mut text_input::State) {
+        if input.is_focused() {
+            input.unfocus();
+            self.focused = None;
+        }
+    }
+
+    /// The message of the focused button
+    pub fn press(&self) -> Option<Message> {
+        self.targets.get(self.focused?).cloned()
+    }
+}
@@ -0,0 +1,42 @@
+use std::hash::Hash;
+use std::time::Duration;
+
+use iced_futures::futures::channel::mpsc;
+use iced_futures::futures::StreamExt;
+use iced_futures::BoxStream;
+use iced_native::subscription::Recipe;
+use iced_native::{event, Event, Hasher};
+use iced_winit::Subscription;
+
+use crate::ui::Message;
+
+/// Emits [Message::Tick] in a fixed interval.
+///
+/// The interval pauses while the computer sleeps, compare the wall clock on every tick
+/// to detect a resume
+pub fn every(interval: Duration) -> Subscription<Message> {
+    Subscription::from_recipe(Clock(interval))
+}
+
+struct Clock(Duration);
+
+impl Recipe<Hasher, (Event, event::Status)> for Clock {
+    type Output = Message;
+
+    fn hash(&self, state: &mut Hasher) {
+        std::any::TypeId::of::<Self>().hash(state);
+        self.0.hash(state);
+    }
+
+    fn stream(self: Box<Self>, _input: BoxStream<(Event, event::Status)>) -> BoxStream<Message> {
+        let (sender, receiver) = mpsc::unbounded();
+        let interval = self.0;
+        std::thread::spawn(move || loop {
+            std::thread::sleep(interval);
+            if sender.unbounded_send(Message::Tick).is_err() {
+                return;
+            }
+        });
+        receiver.boxed()
+    }
+}
@@ -1,15 +1,23 @@
 use iced_core::alignment::Horizontal;
-use iced_core::Length;
+use iced_core::{Color, Length};
 use iced_native::widget::{button, text_input};
-use iced_wgpu::TextInput;
-use iced_winit::widget::{scrollable, Column, Container, Row, Scrollable, Space, Text};
+use iced_winit::widget::{Column, Container, Row, Space, Text};
 
-use crate::conf::SettingsRef;
-use crate::data::{Action, ActiveDay, Day};
+use crate::conf::{FieldAdvance, SettingsRef};
+use crate::data::{
+    format_minutes, Action, ActiveDay, BreakCompliance, BudgetUsage, Day, ExportStatus, JiraIssue,
+    RecentIssues, Work, WorkStart,
+};
+use crate::parsing::parse_result::ParseResult;
 use crate::parsing::time::Time;
+use crate::parsing::time_relative::TimeRelative;
+use crate::ui::book_single::WorkBuilder;
+use crate::ui::day_gauge::day_gauge;
+use crate::ui::focus_handler::{focus_next, focus_previous};
 use crate::ui::message::{DeleteAction, EditAction};
 use crate::ui::stay_active::StayActive;
-use crate::ui::util::h_space;
+use crate::ui::util::{h_space, VirtualList};
+use crate::ui::widget::TextInput;
 use crate::ui::{style, text};
 use crate::ui::{MainView, Message, QElement};
 
@@ -20,18 +28,44 @@ pub enum CurrentDayMessage {
     CommitDayChange,
     RequestEdit(usize),
     RequestDelete(usize),
+    StartInlineEdit(usize),
+    InlineChanged(InlineField, String),
+    CommitInlineEdit,
+    CancelInlineEdit,
+    SplitChanged(String),
+    CommitSplit,
+    CancelSplit,
+    Scrolled(f32),
+    EditNotes,
+    NotesChanged(usize, String),
+    CancelNotes,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum InlineField {
+    Start = 0,
+    End = 1,
+    Issue = 2,
+    Comment = 3,
 }
 
 #[derive(Clone, Debug)]
 pub struct CurrentDayUI {
     data: ActiveDay,
-    scroll_state: scrollable::State,
+    entry_list: VirtualList,
     day_select_button: button::State,
+    close_button: button::State,
     edit_state: Option<text_input::State>,
     day_value: String,
     settings: SettingsRef,
     entries: Vec<Entry>,
     selected_entry: Option<usize>,
+    inline_edit: Option<InlineEdit>,
+    split: Option<SplitPrompt>,
+    notes_edit: Option<NotesEdit>,
+    notes_button: button::State,
+    /// The last store was a nudge, already applied to `entries`
+    nudged: bool,
 }
 
 #[derive(Clone, Debug)]
@@ -39,9 +73,180 @@ struct Entry {
     id: usize,
     edit_button: button::State,
     delete_button: button::State,
+    /// One per link of work
+    link_buttons: Vec<button::State>,
     action: Action,
 }
 
+/// Start, end, issue and comment of a row, edited in place
+#[derive(Clone, Debug)]
+struct InlineEdit {
+    id: usize,
+    inputs: [text_input::State; 4],
+    values: [String; 4],
+    has_end: bool,
+    cancel_button: button::State,
+}
+
+impl InlineEdit {
+    /// Only work and issue starts can be edited inline
+    fn for_action(id: usize, action: &Action) -> Option<InlineEdit> {
+        let (start, end, task, description) = match action {
+            Action::Work(w) => (w.start, Some(w.end), &w.task, &w.description),
+            Action::WorkStart(w) => (w.ts, None, &w.task, &w.description),
+            _ => return None,
+        };
+        let mut inputs: [text_input::State; 4] = Default::default();
+        inputs[InlineField::Start as usize] = text_input::State::focused();
+        Some(InlineEdit {
+            id,
+            inputs,
+            values: [
+                start.to_string(),
+                end.map(|e| e.to_string()).unwrap_or_default(),
+                task.ident.clone(),
+                description.clone(),
+            ],
+            has_end: end.is_some(),
+            cancel_button: button::State::new(),
+        })
+    }
+
+    /// Splits a complete entry like `9 10 A-1 comment`, entered into the start field,
+    /// into all fields
+    fn spread_line(&mut self, settings: &SettingsRef, line: &str) -> bool {
+        if !line.trim().contains(char::is_whitespace) {
+            return false;
+        }
+        let recent = RecentIssues::new(Default::default(), settings.clone());
+        let mut builder = WorkBuilder::default();
+        builder.parse_input(&settings.load(), &recent, None, line);
+
+        let start = builder.start.get();
+        let end = builder.end.get();
+        match (start, end, builder.task.get()) {
+            (Some(start), Some(end), Some(task)) => {
+                self.values = [
+                    start.to_string(),
+                    if self.has_end {
+                        end.to_string()
+                    } else {
+                        String::new()
+                    },
+                    task.ident,
+                    builder.msg.unwrap_or_default(),
+                ];
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether the change only appends a separator that moves on to the next field,
+    /// the separator is not kept
+    fn typed_separator(&self, advance: &FieldAdvance, field: InlineField, value: &str) -> bool {
+        let separators = match field {
+            InlineField::Start => &advance.start,
+            InlineField::End => &advance.end,
+            InlineField::Issue => &advance.issue,
+            InlineField::Comment => &advance.comment,
+        };
+        let mut typed = match value.strip_prefix(self.values[field as usize].as_str()) {
+            Some(typed) => typed.chars(),
+            None => return false,
+        };
+        match (typed.next(), typed.next()) {
+            (Some(c), None) => advance.enabled && separators.contains(c),
+            _ => false,
+        }
+    }
+
+    fn value(&self, field: InlineField) -> &str {
+        self.values[field as usize].trim()
+    }
+
+    fn build(&self, orig: &Action) -> Option<Action> {
+        let time = |field| match Time::parse_prefix(self.value(field)) {
+            (ParseResult::Valid(t), "") => Some(t),
+            _ => None,
+        };
+        let task = |orig: &JiraIssue| {
+            let ident = self.value(InlineField::Issue);
+            if ident.eq_ignore_ascii_case(&orig.ident) {
+                Some(orig.clone())
+            } else {
+                JiraIssue::create(ident).ok()
+            }
+        };
+        let description = self.value(InlineField::Comment).to_string();
+
+        match orig {
+            Action::Work(w) => Some(
+                Work {
+                    start: time(InlineField::Start)?,
+                    end: time(InlineField::End)?,
+                    task: task(&w.task)?,
+                    description,
+                    links: w.links.clone(),
+                }
+                .into(),
+            ),
+            Action::WorkStart(w) => Some(
+                WorkStart {
+                    ts: time(InlineField::Start)?,
+                    task: task(&w.task)?,
+                    description,
+                }
+                .into(),
+            ),
+            _ => None,
+        }
+    }
+
+    fn focus_order(&mut self) -> Vec<&mut text_input::State> {
+        let has_end = self.has_end;
+        self.inputs
+            .iter_mut()
+            .enumerate()
+            .filter(|(index, _)| has_end || *index != InlineField::End as usize)
+            .map(|(_, input)| input)
+            .collect()
+    }
+
+    fn view(&mut self, error: bool) -> QElement {
+        let has_end = self.has_end;
+        let mut row = Vec::new();
+        let fields = [
+            (InlineField::Start, Length::Units(50)),
+            (InlineField::End, Length::Units(50)),
+            (InlineField::Issue, Length::Units(120)),
+            (InlineField::Comment, Length::Fill),
+        ];
+        for ((input, value), (field, width)) in
+            self.inputs.iter_mut().zip(self.values.iter()).zip(fields)
+        {
+            if field == InlineField::End && !has_end {
+                continue;
+            }
+            row.push(
+                TextInput::new(input, "", value, move |v| {
+                    Message::Cd(CurrentDayMessage::InlineChanged(field, v))
+                })
+                .style(style::TextInput { error })
+                .width(width)
+                .into(),
+            );
+            row.push(h_space(Length::Units(3)));
+        }
+        row.push(
+            style::inline_button(&mut self.cancel_button, "Cancel")
+                .on_press(Message::Cd(CurrentDayMessage::CancelInlineEdit))
+                .into(),
+        );
+        Row::with_children(row).into()
+    }
+}
+
 impl CurrentDayUI {
     pub fn for_active_day(settings: SettingsRef, active_day: Option<&ActiveDay>) -> Box<Self> {
         let entries = if let Some(e) = active_day {
@@ -53,6 +258,10 @@ impl CurrentDayUI {
                     id,
                     edit_button: button::State::new(),
                     delete_button: button::State::new(),
+                    link_buttons: match &action {
+                        Action::Work(w) => vec![button::State::new(); w.links.len()],
+                        _ => Vec::new(),
+                    },
                     action,
                 })
                 .collect()
@@ -61,13 +270,212 @@ impl CurrentDayUI {
         };
         Box::new(Self {
             data: active_day.cloned().unwrap_or_default(),
-            scroll_state: Default::default(),
+            entry_list: Default::default(),
             day_select_button: button::State::new(),
+            close_button: button::State::new(),
             edit_state: None,
             day_value: String::new(),
             settings,
             entries,
             selected_entry: None,
+            inline_edit: None,
+            split: None,
+            notes_edit: None,
+            notes_button: button::State::new(),
+            nudged: false,
+        })
+    }
+}
+
+/// Asks for the time, issue and comment of the second half of a split entry
+#[derive(Clone, Debug)]
+struct SplitPrompt {
+    id: usize,
+    label: String,
+    input: text_input::State,
+    value: String,
+    cancel_button: button::State,
+}
+
+impl SplitPrompt {
+    /// Parses `<time> [issue] [comment]`, without an issue the second half keeps the
+    /// issue and comment of the original
+    fn build(&self, orig: &Action) -> Option<(Work, Work)> {
+        let work = match orig {
+            Action::Work(w) => w.clone(),
+            _ => return None,
+        };
+        let (at, rest) = match Time::parse_prefix(self.value.trim()) {
+            (ParseResult::Valid(at), rest) => (at, rest.trim()),
+            _ => return None,
+        };
+        let (ident, comment) = rest.split_once(' ').unwrap_or((rest, ""));
+        let (task, description) = if ident.is_empty() {
+            (work.task.clone(), work.description.clone())
+        } else if ident.eq_ignore_ascii_case(&work.task.ident) {
+            (work.task.clone(), comment.trim().to_string())
+        } else {
+            (JiraIssue::create(ident).ok()?, comment.trim().to_string())
+        };
+        work.split_at(at, task, description)
+    }
+
+    fn view(&mut self, error: bool) -> QElement {
+        let input = TextInput::new(
+            &mut self.input,
+            "<time> [issue] [comment]",
+            &self.value,
+            |v| Message::Cd(CurrentDayMessage::SplitChanged(v)),
+        )
+        .style(style::TextInput { error })
+        .width(Length::Units(300));
+        Row::with_children(vec![
+            text(format!("Split {} at:", self.label)),
+            h_space(style::SPACE),
+            input.into(),
+            h_space(style::SPACE),
+            style::inline_button(&mut self.cancel_button, "Cancel")
+                .on_press(Message::Cd(CurrentDayMessage::CancelSplit))
+                .into(),
+        ])
+        .into()
+    }
+}
+
+/// The notes of the day, one input per line. Typing into the empty last line adds another
+#[derive(Clone, Debug)]
+struct NotesEdit {
+    inputs: Vec<text_input::State>,
+    lines: Vec<String>,
+    cancel_button: button::State,
+}
+
+impl NotesEdit {
+    fn new(notes: &str) -> NotesEdit {
+        let mut lines: Vec<String> = notes.lines().map(str::to_string).collect();
+        lines.push(String::new());
+        let mut inputs = Vec::new();
+        inputs.resize_with(lines.len() - 1, text_input::State::new);
+        inputs.push(text_input::State::focused());
+        NotesEdit {
+            inputs,
+            lines,
+            cancel_button: button::State::new(),
+        }
+    }
+
+    fn set_line(&mut self, index: usize, value: String) {
+        if let Some(line) = self.lines.get_mut(index) {
+            *line = value;
+        }
+        if self.lines.last().map_or(false, |l| !l.is_empty()) {
+            self.lines.push(String::new());
+            self.inputs.push(text_input::State::new());
+        }
+    }
+
+    /// The lines without trailing empty lines
+    fn notes(&self) -> String {
+        let lines: Vec<&str> = self.lines.iter().map(|l| l.trim_end()).collect();
+        lines.join("\n").trim_end().to_string()
+    }
+
+    fn view(&mut self) -> QElement {
+        let mut rows = Vec::with_capacity(self.lines.len() + 1);
+        for (index, (input, line)) in self.inputs.iter_mut().zip(&self.lines).enumerate() {
+            rows.push(
+                TextInput::new(input, "Notes", line, move |v| {
+                    Message::Cd(CurrentDayMessage::NotesChanged(index, v))
+                })
+                .into(),
+            );
+        }
+        rows.push(
+            Row::with_children(vec![
+                text("Enter saves the notes"),
+                h_space(style::SPACE),
+                style::inline_button(&mut self.cancel_button, "Cancel")
+                    .on_press(Message::Cd(CurrentDayMessage::CancelNotes))
+                    .into(),
+            ])
+            .into(),
+        );
+        Column::with_children(rows).spacing(2).into()
+    }
+}
+
+impl CurrentDayUI {
+    fn is_editing(&self) -> bool {
+        self.inline_edit.is_some() || self.split.is_some() || self.notes_edit.is_some()
+    }
+
+    /// Merges the selected work with adjacent work on the same issue
+    fn merge_selected(&mut self) -> Option<Message> {
+        if self.is_editing() {
+            return None;
+        }
+        let work = match &self.entries.get(self.selected_entry?)?.action {
+            Action::Work(w) => w,
+            _ => return None,
+        };
+        self.entries.iter().find_map(|e| match &e.action {
+            Action::Work(next) => work.merge(next).map(|merged| Message::ModifyActions {
+                stay_active: StayActive::Yes,
+                orig: vec![work.clone().into(), next.clone().into()],
+                update: vec![merged.into()],
+            }),
+            _ => None,
+        })
+    }
+
+    fn start_split(&mut self) -> Option<Message> {
+        if self.is_editing() {
+            return None;
+        }
+        let id = self.selected_entry?;
+        let action = &self.entries.get(id)?.action;
+        if matches!(action, Action::Work(_)) {
+            self.split = Some(SplitPrompt {
+                id,
+                label: action.to_string(),
+                input: text_input::State::focused(),
+                value: String::new(),
+                cancel_button: button::State::new(),
+            });
+        }
+        None
+    }
+
+    /// Moves a time of the selected entry by resolution steps and keeps it selected
+    fn nudge_selected(
+        &mut self,
+        steps: i32,
+        shift: impl Fn(Action, TimeRelative) -> Option<Action>,
+    ) -> Option<Message> {
+        // the closed day would reject the modification after the local change
+        if self.is_editing() || self.data.is_closed() {
+            return None;
+        }
+        let orig = self.entries.get(self.selected_entry?)?.action.clone();
+        let resolution = self.settings.load().resolution.num_minutes() as i32;
+        let offset = TimeRelative::from_minutes_sat(steps * resolution);
+        let update = shift(orig.clone(), offset)?;
+
+        let mut actions: Vec<Action> = self.entries.iter().map(|e| e.action.clone()).collect();
+        actions.retain(|a| *a != orig);
+        actions.push(update.clone());
+        actions.sort();
+        for (id, (entry, action)) in self.entries.iter_mut().zip(actions).enumerate() {
+            entry.id = id;
+            entry.action = action;
+        }
+        self.selected_entry = self.entries.iter().position(|e| e.action == update);
+        self.nudged = true;
+
+        Some(Message::ModifyAction {
+            stay_active: StayActive::Yes,
+            orig: Box::new(orig),
+            update: Box::new(update),
         })
     }
 }
@@ -88,21 +496,88 @@ impl MainView for CurrentDayUI {
             Row::with_children(vec![text("No active issue")])
         };
 
-        let entries: Vec<QElement> = self
+        let work_minutes = self
             .entries
-            .iter_mut()
-            .enumerate()
-            .map(|(index, e)| edit_action_row(e, index, self.selected_entry))
-            .collect();
+            .iter()
+            .filter(|e| matches!(e.action, Action::Work(_)))
+            .filter_map(|e| e.action.duration_minutes())
+            .sum::<u32>();
 
-        let mut entries_scroll = Scrollable::new(&mut self.scroll_state).width(Length::Fill);
-        for e in entries {
-            entries_scroll = entries_scroll.push(e);
+        let split = self.split.as_mut().map(|split| {
+            let error = !split.value.trim().is_empty()
+                && self
+                    .entries
+                    .get(split.id)
+                    .and_then(|e| split.build(&e.action))
+                    .is_none();
+            split.view(error)
+        });
+
+        let settings = self.settings.load();
+        let mut inline_edit = self.inline_edit.as_mut();
+        let window = self.entry_list.window(self.entries.len());
+        let mut entries: Vec<QElement> = Vec::with_capacity(window.len());
+        let visible = self.entries.iter_mut().enumerate();
+        for (index, e) in visible.take(window.end).skip(window.start) {
+            let edit = match inline_edit.take() {
+                Some(edit) if edit.id == e.id => Some(edit),
+                other => {
+                    inline_edit = other;
+                    None
+                }
+            };
+            let color = e
+                .action
+                .issue()
+                .map(|i| style::issue_color(&settings, &i.ident));
+            entries.push(edit_action_row(e, edit, color, index, self.selected_entry));
         }
+
+        let entries_scroll = self.entry_list.view(self.entries.len(), entries, |offset| {
+            Message::Cd(CurrentDayMessage::Scrolled(offset))
+        });
         let content_style: Box<dyn iced_winit::widget::container::StyleSheet> =
             Box::new(style::ContentStyle);
 
+        let now =
+            Some(settings.timeline.time_now()).filter(|_| settings.today() == self.data.get_day());
+        let breaks = settings.breaks.for_day(self.data.get_day());
+        let gauge = day_gauge(self.data.actions(), breaks.default_break, now);
+        let compliance = BreakCompliance::of(self.data.actions(), settings.break_limit, now);
+        let since_break = format!(
+            "Work since last break: {}",
+            format_minutes(compliance.since_break_minutes)
+        );
+        let since_break: QElement = if compliance.exceeded {
+            Text::new(format!(
+                "{}, take a break of at least {} minutes",
+                since_break, settings.break_limit.min_break_minutes
+            ))
+            .color(style::ERROR_COLOR)
+            .into()
+        } else {
+            text(since_break)
+        };
+        let budget = Some(settings.daily_budget)
+            .filter(|b| b.minutes > 0)
+            .map(|budget| {
+                let usage = BudgetUsage::of(self.data.actions(), budget, now);
+                let label = format!(
+                    "Booked and running work: {} of {}",
+                    format_minutes(usage.booked_minutes),
+                    format_minutes(budget.minutes)
+                );
+                if usage.exceeded {
+                    Text::new(format!("{}, the daily budget is used up", label))
+                        .color(style::ERROR_COLOR)
+                        .into()
+                } else {
+                    text(label)
+                }
+            });
+
         let date_width = Length::Units(100);
+        let export_status = self.data.export_status();
         let mut day_row = Vec::new();
         let (on_press, message) = if self.edit_state.is_some() {
             (Message::Cd(CurrentDayMessage::CommitDayChange), "Commit")
@@ -114,11 +589,16 @@ impl MainView for CurrentDayUI {
         };
         if let Some(edit_state) = &mut self.edit_state {
             let on_submit = Message::Cd(CurrentDayMessage::CommitDayChange);
+            let error = !self.day_value.is_empty()
+                && Day::parse_day_relative(&self.settings.load().timeline, &self.day_value)
+                    .get_ref()
+                    .is_none();
             day_row.push(
                 TextInput::new(edit_state, &day, &self.day_value, |v| {
                     Message::Cd(CurrentDayMessage::DayTextChanged(v))
                 })
                 .on_submit(on_submit)
+                .style(style::TextInput { error })
                 .width(date_width)
                 .into(),
             )
@@ -136,20 +616,71 @@ impl MainView for CurrentDayUI {
                 .on_press(on_press)
                 .into(),
         );
+        day_row.push(h_space(style::DSPACE));
+        day_row.push(match export_status {
+            ExportStatus::ModifiedSinceExport(_) => Text::new(export_status.label())
+                .color(style::ERROR_COLOR)
+                .into(),
+            _ => text(export_status.label()),
+        });
+        day_row.push(h_space(style::DSPACE));
+        let close_label = if self.data.is_closed() {
+            "Closed, unlock (ctrl+K)"
+        } else {
+            "Close day (ctrl+K)"
+        };
+        day_row.push(
+            style::inline_button(&mut self.close_button, close_label)
+                .on_press(Message::ToggleDayClosed)
+                .into(),
+        );
+
+        day_row.push(h_space(style::DSPACE));
+        day_row.push(
+            style::inline_button(&mut self.notes_button, "Notes")
+                .on_press(Message::Cd(CurrentDayMessage::EditNotes))
+                .into(),
+        );
+
+        let notes = match &mut self.notes_edit {
+            Some(edit) => Some(edit.view()),
+            None if !self.data.notes().is_empty() => Some(text(self.data.notes())),
+            None => None,
+        };
+        let mut notes: Vec<QElement> = notes
+            .map(|n| vec![n, Space::with_height(style::SPACE).into()])
+            .unwrap_or_default();
 
-        Column::with_children(vec![
+        let mut content = vec![
             Row::with_children(day_row).into(),
             Space::with_height(style::SPACE).into(),
             active_issue.into(),
             Space::with_height(style::SPACE).into(),
+        ];
+        content.append(&mut notes);
+        content.extend(vec![
+            gauge,
+            Space::with_height(style::SPACE).into(),
+            since_break,
+            Space::with_height(style::SPACE).into(),
+        ]);
+        if let Some(budget) = budget {
+            content.push(budget);
+            content.push(Space::with_height(style::SPACE).into());
+        }
+        content.extend(vec![
+            split.unwrap_or_else(|| text("Merge with next (m), split (p)")),
+            Space::with_height(style::SPACE).into(),
             Container::new(entries_scroll)
                 .width(Length::Fill)
                 .height(Length::Fill)
                 .style(content_style)
                 .padding([5, 1])
                 .into(),
-        ])
-        .into()
+            Space::with_height(style::SPACE).into(),
+            text(format!("Booked work: {}", format_minutes(work_minutes))),
+        ]);
+        Column::with_children(content).into()
     }
 
     fn update(&mut self, msg: Message) -> Option<Message> {
@@ -196,18 +727,178 @@ impl MainView for CurrentDayUI {
                 };
                 None
             }
-            Message::SubmitCurrent(_) => self
-                .selected_entry
-                .map(|e| Message::Cd(CurrentDayMessage::RequestEdit(e))),
+            Message::Cd(CurrentDayMessage::StartInlineEdit(id)) => match self.entries.get(id) {
+                Some(e) => match InlineEdit::for_action(id, &e.action) {
+                    Some(edit) => {
+                        self.inline_edit = Some(edit);
+                        None
+                    }
+                    None => Some(Message::Cd(CurrentDayMessage::RequestEdit(id))),
+                },
+                None => None,
+            },
+            Message::Cd(CurrentDayMessage::InlineChanged(field, value)) => {
+                let edit = self.inline_edit.as_mut()?;
+                if edit.typed_separator(&self.settings.load().field_advance, field, &value) {
+                    if field == InlineField::Comment {
+                        Some(Message::Cd(CurrentDayMessage::CommitInlineEdit))
+                    } else {
+                        focus_next(&mut edit.focus_order(), true)
+                    }
+                } else {
+                    if field != InlineField::Start || !edit.spread_line(&self.settings, &value) {
+                        edit.values[field as usize] = value;
+                    }
+                    None
+                }
+            }
+            Message::Cd(CurrentDayMessage::CommitInlineEdit) => {
+                let edit = self.inline_edit.as_ref()?;
+                let orig = &self.entries.get(edit.id)?.action;
+                let update = edit.build(orig)?;
+                let orig = Box::new(orig.clone());
+                self.inline_edit = None;
+                Some(Message::ModifyAction {
+                    stay_active: StayActive::Yes,
+                    orig,
+                    update: Box::new(update),
+                })
+            }
+            Message::Cd(CurrentDayMessage::CancelInlineEdit) => {
+                self.inline_edit = None;
+                None
+            }
+            Message::Next => match &mut self.notes_edit {
+                Some(notes) => focus_next(&mut notes.inputs.iter_mut().collect::<Vec<_>>(), true),
+                None => {
+                    let edit = self.inline_edit.as_mut()?;
+                    focus_next(&mut edit.focus_order(), true)
+                }
+            },
+            Message::Previous => match &mut self.notes_edit {
+                Some(notes) => {
+                    focus_previous(&mut notes.inputs.iter_mut().collect::<Vec<_>>(), true)
+                }
+                None => {
+                    let edit = self.inline_edit.as_mut()?;
+                    focus_previous(&mut edit.focus_order(), true)
+                }
+            },
+            Message::Cd(CurrentDayMessage::EditNotes) => {
+                if !self.is_editing() {
+                    self.notes_edit = Some(NotesEdit::new(self.data.notes()));
+                }
+                None
+            }
+            Message::Cd(CurrentDayMessage::NotesChanged(index, value)) => {
+                if let Some(notes) = &mut self.notes_edit {
+                    notes.set_line(index, value);
+                }
+                None
+            }
+            Message::Cd(CurrentDayMessage::CancelNotes) => {
+                self.notes_edit = None;
+                None
+            }
+            Message::Cd(CurrentDayMessage::SplitChanged(value)) => {
+                if let Some(split) = &mut self.split {
+                    split.value = value;
+                }
+                None
+            }
+            Message::Cd(CurrentDayMessage::CommitSplit) => {
+                let split = self.split.as_ref()?;
+                let orig = &self.entries.get(split.id)?.action;
+                let (first, second) = split.build(orig)?;
+                let orig = orig.clone();
+                self.split = None;
+                Some(Message::ModifyActions {
+                    stay_active: StayActive::Yes,
+                    orig: vec![orig],
+                    update: vec![first.into(), second.into()],
+                })
+            }
+            Message::Cd(CurrentDayMessage::CancelSplit) => {
+                self.split = None;
+                None
+            }
+            Message::Cd(CurrentDayMessage::Scrolled(offset)) => {
+                self.entry_list.scrolled(offset);
+                None
+            }
+            Message::Merge => self.merge_selected(),
+            Message::Split => self.start_split(),
+            Message::SubmitCurrent(_) => {
+                if self.split.is_some() {
+                    Some(Message::Cd(CurrentDayMessage::CommitSplit))
+                } else if self.inline_edit.is_some() {
+                    Some(Message::Cd(CurrentDayMessage::CommitInlineEdit))
+                } else if let Some(notes) = &self.notes_edit {
+                    Some(Message::SetDayNotes(notes.notes()))
+                } else {
+                    self.selected_entry
+                        .map(|e| Message::Cd(CurrentDayMessage::StartInlineEdit(e)))
+                }
+            }
+            Message::NudgeStart(steps) => self.nudge_selected(steps, Action::shift_start),
+            Message::NudgeEnd(steps) => self.nudge_selected(steps, Action::shift_end),
+            Message::StoreSuccess(_) => {
+                if std::mem::take(&mut self.nudged) {
+                    None
+                } else {
+                    Some(Message::RefreshView)
+                }
+            }
             Message::Del => self
                 .selected_entry
                 .map(|e| Message::Cd(CurrentDayMessage::RequestDelete(e))),
             _ => None,
         }
     }
+
+    fn text_summary(&self) -> Option<String> {
+        let count = self.entries.len();
+        let mut lines = match count {
+            0 => vec!["Nothing booked.".to_string()],
+            1 => vec!["1 entry:".to_string()],
+            _ => vec![format!("{} entries:", count)],
+        };
+        for (index, e) in self.entries.iter().enumerate() {
+            let selected = if Some(index) == self.selected_entry {
+                ", selected"
+            } else {
+                ""
+            };
+            lines.push(format!(
+                "Entry {} of {}{}: {}",
+                index + 1,
+                count,
+                selected,
+                e.action.as_sentence()
+            ));
+        }
+        if !self.data.notes().is_empty() {
+            lines.push(format!("Notes: {}", self.data.notes()));
+        }
+        Some(lines.join("\n"))
+    }
 }
 
-fn edit_action_row(entry: &mut Entry, index: usize, selected_index: Option<usize>) -> QElement {
+fn edit_action_row<'a>(
+    entry: &'a mut Entry,
+    inline_edit: Option<&'a mut InlineEdit>,
+    color: Option<Color>,
+    index: usize,
+    selected_index: Option<usize>,
+) -> QElement<'a> {
+    let editing = inline_edit.is_some();
+    let content = match inline_edit {
+        Some(edit) => {
+            let error = edit.build(&entry.action).is_none();
+            edit.view(error)
+        }
+        None => action_row(&entry.action),
+    };
     let delete_button = style::inline_button(&mut entry.delete_button, "D")
         .on_press(Message::Cd(CurrentDayMessage::RequestDelete(entry.id)));
     let edit_button = style::inline_button(&mut entry.edit_button, "E")
@@ -222,17 +913,31 @@ fn edit_action_row(entry: &mut Entry, index: usize, selected_index: Option<usize
         },
     };
 
-    Container::new(Row::with_children(vec![
+    let mut row = vec![
         delete_button.into(),
         h_space(Length::Units(3)),
         edit_button.into(),
-        h_space(style::DSPACE),
-        action_row(&entry.action),
-    ]))
-    .style(background)
-    .width(Length::Fill)
-    .padding([2, 5])
-    .into()
+        h_space(style::SPACE),
+        style::issue_mark(color),
+        h_space(style::SPACE),
+        content,
+    ];
+    if let (Action::Work(w), false) = (&entry.action, editing) {
+        for (state, link) in entry.link_buttons.iter_mut().zip(&w.links) {
+            row.push(h_space(style::SPACE));
+            row.push(
+                style::inline_button(state, link)
+                    .on_press(Message::OpenLink(link.clone()))
+                    .into(),
+            );
+        }
+    }
+
+    Container::new(Row::with_children(row))
+        .style(background)
+        .width(Length::Fill)
+        .padding([2, 5])
+        .into()
 }
 
 pub fn action_row(action: &Action) -> QElement {
@@ -258,6 +963,11 @@ pub fn action_row(action: &Action) -> QElement {
             row.push(dash("-"));
             row.push(time(end));
         }
+        (Some(start), None) if matches!(action, Action::WorkEvent(_)) => {
+            row.push(time(start));
+            row.push(dash("at"));
+            row.push(h_space(w));
+        }
         (Some(start), None) => {
             row.push(time(start));
             row.push(dash("-"));
@@ -276,6 +986,13 @@ pub fn action_row(action: &Action) -> QElement {
         ),
     }
 
+    let duration = action.duration_minutes().map(format_minutes);
+    row.push(
+        Text::new(duration.unwrap_or_default())
+            .width(Length::Units(60))
+            .horizontal_alignment(Horizontal::Right)
+            .into(),
+    );
     row.push(dash(" | "));
 
     if let Some(id) = action.issue_id() {
@@ -294,3 +1011,194 @@ pub fn action_row(action: &Action) -> QElement {
 
     Row::with_children(row).into()
 }
+
+#[cfg(test)]
+mod test {
+    use crate::conf::into_settings_ref;
+    use crate::data::test_support::{day_start, work};
+    use crate::data::{ActiveDay, Day, Location};
+    use crate::ui::current_day::{CurrentDayMessage, CurrentDayUI, InlineField};
+    use crate::ui::stay_active::StayActive;
+    use crate::ui::{MainView, Message};
+    use crate::Settings;
+
+    #[test]
+    fn edits_work_inline() {
+        let mut day = ActiveDay::new(Day::ymd(2022, 1, 20), Location::Office, None);
+        day.add_action(day_start("o8"));
+        day.add_action(work("9", "10", "A-1", "a"));
+        let mut ui =
+            CurrentDayUI::for_active_day(into_settings_ref(Settings::default()), Some(&day));
+
+        let edit = |field, value: &str| {
+            Message::Cd(CurrentDayMessage::InlineChanged(field, value.to_string()))
+        };
+        assert!(matches!(
+            ui.update(Message::Cd(CurrentDayMessage::StartInlineEdit(0))),
+            Some(Message::Cd(CurrentDayMessage::RequestEdit(0)))
+        ));
+        assert!(ui
+            .update(Message::Cd(CurrentDayMessage::StartInlineEdit(1)))
+            .is_none());
+        ui.update(edit(InlineField::End, "10:30"));
+        ui.update(edit(InlineField::Issue, "b-2"));
+        ui.update(edit(InlineField::Comment, "b"));
+
+        match ui.update(Message::Cd(CurrentDayMessage::CommitInlineEdit)) {
+            Some(Message::ModifyAction { orig, update, .. }) => {
+                assert_eq!(*orig, work("9", "10", "A-1", "a"));
+                assert_eq!(*update, work("9", "10:30", "B-2", "b"));
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+
+        ui.update(Message::Cd(CurrentDayMessage::StartInlineEdit(1)));
+        ui.update(edit(InlineField::Start, "8 9:30 C-3 pasted entry"));
+        match ui.update(Message::Cd(CurrentDayMessage::CommitInlineEdit)) {
+            Some(Message::ModifyAction { update, .. }) => {
+                assert_eq!(*update, work("8", "9:30", "C-3", "pasted entry"));
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+
+        ui.update(Message::Cd(CurrentDayMessage::StartInlineEdit(1)));
+        ui.update(edit(InlineField::Start, "x"));
+        assert!(ui
+            .update(Message::Cd(CurrentDayMessage::CommitInlineEdit))
+            .is_none());
+    }
+
+    #[test]
+    fn advances_on_separators() {
+        let mut day = ActiveDay::new(Day::ymd(2022, 1, 20), Location::Office, None);
+        day.add_action(work("9", "10", "A-1", "a"));
+        let mut settings = Settings::default();
+        settings.field_advance.enabled = true;
+        let mut ui = CurrentDayUI::for_active_day(into_settings_ref(settings), Some(&day));
+
+        let edit = |field, value: &str| {
+            Message::Cd(CurrentDayMessage::InlineChanged(field, value.to_string()))
+        };
+        ui.update(Message::Cd(CurrentDayMessage::StartInlineEdit(0)));
+        ui.update(edit(InlineField::Start, "09:00 "));
+        let inline = ui.inline_edit.as_ref().unwrap();
+        assert_eq!(inline.values[InlineField::Start as usize], "09:00");
+        assert!(inline.inputs[InlineField::End as usize].is_focused());
+
+        ui.update(edit(InlineField::Comment, "a b"));
+        assert!(matches!(
+            ui.update(edit(InlineField::Comment, "a b#")),
+            Some(Message::Cd(CurrentDayMessage::CommitInlineEdit))
+        ));
+        match ui.update(Message::Cd(CurrentDayMessage::CommitInlineEdit)) {
+            Some(Message::ModifyAction { update, .. }) => {
+                assert_eq!(*update, work("9", "10", "A-1", "a b"));
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn nudges_selected_entry() {
+        let mut day = ActiveDay::new(Day::ymd(2022, 1, 20), Location::Office, None);
+        day.add_action(work("9", "10", "A-1", "a"));
+        day.add_action(work("10", "11", "B-1", "b"));
+        let mut ui =
+            CurrentDayUI::for_active_day(into_settings_ref(Settings::default()), Some(&day));
+
+        ui.update(Message::Down);
+        assert!(matches!(
+            ui.update(Message::NudgeStart(-4)),
+            Some(Message::ModifyAction { update, .. }) if *update == work("8", "10", "A-1", "a")
+        ));
+        assert!(ui.update(Message::StoreSuccess(StayActive::Yes)).is_none());
+        assert!(matches!(
+            ui.update(Message::NudgeEnd(1)),
+            Some(Message::ModifyAction { orig, update, .. })
+                if *orig == work("8", "10", "A-1", "a") && *update == work("8", "10:15", "A-1", "a")
+        ));
+        assert!(ui.update(Message::NudgeStart(9)).is_none());
+    }
+
+    #[test]
+    fn summarizes_entries() {
+        let mut day = ActiveDay::new(Day::ymd(2022, 1, 20), Location::Office, None);
+        day.add_action(work("9", "10", "A-1", "a"));
+        day.add_action(work("10", "11", "B-1", ""));
+        let mut ui =
+            CurrentDayUI::for_active_day(into_settings_ref(Settings::default()), Some(&day));
+
+        ui.update(Message::Down);
+        assert_eq!(
+            ui.text_summary().unwrap(),
+            "2 entries:
+Entry 1 of 2, selected: Work on A-1 from 09:00 to 10:00, 1 hour: a.
+Entry 2 of 2: Work on B-1 from 10:00 to 11:00, 1 hour."
+        );
+    }
+
+    #[test]
+    fn merges_and_splits_work() {
+        let mut day = ActiveDay::new(Day::ymd(2022, 1, 20), Location::Office, None);
+        day.add_action(work("9", "10", "A-1", "a"));
+        day.add_action(work("10", "11", "A-1", "a"));
+        let mut ui =
+            CurrentDayUI::for_active_day(into_settings_ref(Settings::default()), Some(&day));
+
+        ui.update(Message::Down);
+        match ui.update(Message::Merge) {
+            Some(Message::ModifyActions { orig, update, .. }) => {
+                assert_eq!(
+                    orig,
+                    vec![work("9", "10", "A-1", "a"), work("10", "11", "A-1", "a")]
+                );
+                assert_eq!(update, vec![work("9", "11", "A-1", "a")]);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+
+        assert!(ui.update(Message::Split).is_none());
+        ui.update(Message::Cd(CurrentDayMessage::SplitChanged(
+            "9:30 b-2 review".to_string(),
+        )));
+        match ui.update(Message::SubmitCurrent(StayActive::Yes)) {
+            Some(Message::Cd(CurrentDayMessage::CommitSplit)) => (),
+            other => panic!("unexpected {:?}", other),
+        }
+        match ui.update(Message::Cd(CurrentDayMessage::CommitSplit)) {
+            Some(Message::ModifyActions { orig, update, .. }) => {
+                assert_eq!(orig, vec![work("9", "10", "A-1", "a")]);
+                assert_eq!(
+                    update,
+                    vec![
+                        work("9", "9:30", "A-1", "a"),
+                        work("9:30", "10", "B-2", "review")
+                    ]
+                );
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn edits_notes_line_by_line() {
+        let mut day = ActiveDay::new(Day::ymd(2022, 1, 20), Location::Office, None);
+        day.set_notes("prod incident".to_string());
+        let mut ui =
+            CurrentDayUI::for_active_day(into_settings_ref(Settings::default()), Some(&day));
+
+        let line = |index, value: &str| {
+            Message::Cd(CurrentDayMessage::NotesChanged(index, value.to_string()))
+        };
+        ui.update(Message::Cd(CurrentDayMessage::EditNotes));
+        ui.update(line(1, "call with ops"));
+        ui.update(line(2, "  "));
+        match ui.update(Message::SubmitCurrent(StayActive::Default)) {
+            Some(Message::SetDayNotes(notes)) => assert_eq!(notes, "prod incident\ncall with ops"),
+            other => panic!("unexpected {:?}", other),
+        }
+
+        ui.update(Message::Cd(CurrentDayMessage::CancelNotes));
+        assert!(!ui.is_editing());
+    }
+}
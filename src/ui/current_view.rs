@@ -1,24 +1,48 @@
 use crate::conf::SettingsRef;
 use crate::data::{Action, ActiveDay, RecentIssuesRef};
-use crate::ui::book_single::BookSingleUI;
+use crate::ui::book_single::{BookSingleMessage, BookSingleUI};
 use crate::ui::current_day::CurrentDayUI;
+use crate::ui::day_end_wizard::DayEndWizard;
+use crate::ui::day_start_wizard::DayStartWizard;
 use crate::ui::export::DayExportUi;
-use crate::ui::fast_day_end::FastDayEnd;
-use crate::ui::fast_day_start::FastDayStart;
-use crate::ui::issue_end_edit::IssueEndEdit;
-use crate::ui::issue_start_edit::IssueStartEdit;
+use crate::ui::fast_day_end::{FastDayEnd, FastDayEndMessage};
+use crate::ui::fast_day_start::{FastDayStart, FastDayStartMessage};
+use crate::ui::gap_fill::{GapFill, GapFillMessage};
+use crate::ui::issue_end_edit::{IssueEndEdit, IssueEndMessage};
+use crate::ui::issue_start_edit::{IssueStartEdit, IssueStartMessage};
+use crate::ui::issue_stats_view::IssueStatsView;
+use crate::ui::quick_day::{QuickDay, QuickDayMessage};
+use crate::ui::quick_switch::{QuickSwitch, QuickSwitchMessage};
+use crate::ui::reassign_view::ReassignView;
 use crate::ui::settings_ui::SettingsUI;
 use crate::ui::single_edit_ui::SingleEditUi;
+use crate::ui::standby_edit::{StandbyEdit, StandbyMessage};
+use crate::ui::to_fix_view::ToFixView;
+use crate::ui::week_calendar::WeekCalendarView;
+use crate::ui::work_event_edit::{WorkEventEdit, WorkEventMessage};
+use crate::ui::year_overview::YearOverviewView;
 use crate::ui::{Exit, MainView, Message, QElement, ViewId};
 
 pub enum CurrentView {
     Fds(Box<FastDayStart>),
+    Sw(Box<DayStartWizard>),
     Fde(Box<FastDayEnd>),
+    Ew(Box<DayEndWizard>),
+    Gf(Box<GapFill>),
     CdUi(Box<CurrentDayUI>),
     Bs(Box<BookSingleUI>),
     Is(Box<IssueStartEdit>),
     Ie(Box<IssueEndEdit>),
+    Qsw(Box<QuickSwitch>),
+    Ev(Box<WorkEventEdit>),
+    Sb(Box<StandbyEdit>),
+    Qd(Box<QuickDay>),
+    Ra(Box<ReassignView>),
     Export(Box<DayExportUi>),
+    Stats(Box<IssueStatsView>),
+    Week(Box<WeekCalendarView>),
+    Year(Box<YearOverviewView>),
+    Fix(Box<ToFixView>),
     Settings(Box<SettingsUI>),
     Exit(Exit),
 }
@@ -27,12 +51,24 @@ impl CurrentView {
     pub fn view_id(&self) -> ViewId {
         match &self {
             CurrentView::Fds(_) => ViewId::FastDayStart,
+            CurrentView::Sw(_) => ViewId::DayStartWizard,
             CurrentView::Fde(_) => ViewId::FastDayEnd,
+            CurrentView::Ew(_) => ViewId::DayEndWizard,
+            CurrentView::Gf(_) => ViewId::GapFill,
             CurrentView::CdUi(_) => ViewId::CurrentDayUi,
             CurrentView::Bs(_) => ViewId::BookSingle,
             CurrentView::Is(_) => ViewId::BookIssueStart,
             CurrentView::Ie(_) => ViewId::BookIssueEnd,
+            CurrentView::Qsw(_) => ViewId::QuickSwitch,
+            CurrentView::Ev(_) => ViewId::WorkEvent,
+            CurrentView::Sb(_) => ViewId::Standby,
+            CurrentView::Qd(_) => ViewId::QuickDay,
+            CurrentView::Ra(_) => ViewId::Reassign,
             CurrentView::Export(_) => ViewId::Export,
+            CurrentView::Stats(_) => ViewId::IssueStats,
+            CurrentView::Week(_) => ViewId::WeekCalendar,
+            CurrentView::Year(_) => ViewId::YearOverview,
+            CurrentView::Fix(_) => ViewId::ToFix,
             CurrentView::Settings(_) => ViewId::Settings,
             CurrentView::Exit(_) => ViewId::Exit,
         }
@@ -48,7 +84,18 @@ impl CurrentView {
             ViewId::FastDayStart => {
                 CurrentView::Fds(FastDayStart::for_work_day(settings, active_day))
             }
+            ViewId::DayStartWizard => {
+                CurrentView::Sw(DayStartWizard::for_active_day(settings, active_day))
+            }
             ViewId::FastDayEnd => CurrentView::Fde(FastDayEnd::for_work_day(settings, active_day)),
+            ViewId::DayEndWizard => CurrentView::Ew(DayEndWizard::for_active_day(
+                settings,
+                recent_issues,
+                active_day,
+            )),
+            ViewId::GapFill => {
+                CurrentView::Gf(GapFill::for_active_day(settings, recent_issues, active_day))
+            }
             ViewId::BookSingle => CurrentView::Bs(BookSingleUI::for_active_day(
                 settings,
                 recent_issues,
@@ -62,13 +109,38 @@ impl CurrentView {
             ViewId::BookIssueEnd => {
                 CurrentView::Ie(IssueEndEdit::for_active_day(settings, active_day))
             }
+            ViewId::QuickSwitch => CurrentView::Qsw(QuickSwitch::for_active_day(
+                settings,
+                recent_issues,
+                active_day,
+            )),
+            ViewId::WorkEvent => CurrentView::Ev(WorkEventEdit::for_active_day(
+                settings,
+                recent_issues,
+                active_day,
+            )),
+            ViewId::Standby => CurrentView::Sb(StandbyEdit::for_work_day(settings, active_day)),
+            ViewId::QuickDay => CurrentView::Qd(QuickDay::for_active_day(
+                settings,
+                recent_issues,
+                active_day,
+            )),
+            ViewId::Reassign => CurrentView::Ra(ReassignView::for_active_day(settings, active_day)),
             ViewId::CurrentDayUi => {
                 CurrentView::CdUi(CurrentDayUI::for_active_day(settings, active_day))
             }
             ViewId::Export => {
                 CurrentView::Export(DayExportUi::for_active_day(settings, active_day))
             }
-            ViewId::Settings => CurrentView::Settings(SettingsUI::new(settings)),
+            ViewId::IssueStats => CurrentView::Stats(IssueStatsView::new(settings, recent_issues)),
+            ViewId::WeekCalendar => {
+                CurrentView::Week(WeekCalendarView::for_active_day(settings, active_day))
+            }
+            ViewId::YearOverview => {
+                CurrentView::Year(YearOverviewView::for_active_day(settings, active_day))
+            }
+            ViewId::ToFix => CurrentView::Fix(ToFixView::new()),
+            ViewId::Settings => CurrentView::Settings(SettingsUI::new(settings, recent_issues)),
             ViewId::Exit => CurrentView::Exit(Exit),
         }
     }
@@ -95,6 +167,11 @@ impl CurrentView {
                 ui.entry_to_edit(a);
                 CurrentView::Ie(ui)
             }
+            Action::WorkEvent(a) => {
+                let mut ui = WorkEventEdit::for_active_day(settings, recent_issues, active_day);
+                ui.entry_to_edit(a);
+                CurrentView::Ev(ui)
+            }
             Action::DayStart(a) => {
                 let mut ui = FastDayStart::for_work_day(settings, active_day);
                 ui.entry_to_edit(a);
@@ -105,21 +182,68 @@ impl CurrentView {
                 ui.entry_to_edit(a);
                 CurrentView::Fde(ui)
             }
+            Action::Standby(a) => {
+                let mut ui = StandbyEdit::for_work_day(settings, active_day);
+                ui.entry_to_edit(a);
+                CurrentView::Sb(ui)
+            }
             _ => CurrentView::create(ViewId::CurrentDayUi, settings, recent_issues, active_day),
         }
     }
 }
 
+impl CurrentView {
+    /// The message that types a restored draft into the view
+    pub fn restore_draft(&self, input: String) -> Option<Message> {
+        match self {
+            CurrentView::Fds(_) => Some(Message::Fds(FastDayStartMessage::TextChanged(input))),
+            CurrentView::Fde(_) => Some(Message::Fde(FastDayEndMessage::TextChanged(input))),
+            CurrentView::Bs(_) => Some(Message::Bs(BookSingleMessage::TextChanged(input))),
+            CurrentView::Is(_) => Some(Message::Is(IssueStartMessage::TextChanged(input))),
+            CurrentView::Ie(_) => Some(Message::Ie(IssueEndMessage::InputChanged(input))),
+            CurrentView::Qsw(_) => Some(Message::Qs(QuickSwitchMessage::TextChanged(input))),
+            CurrentView::Gf(_) => Some(Message::Gf(GapFillMessage::TextChanged(input))),
+            CurrentView::Ev(_) => Some(Message::Ev(WorkEventMessage::TextChanged(input))),
+            CurrentView::Sb(_) => Some(Message::Sb(StandbyMessage::TextChanged(input))),
+            CurrentView::Qd(_) => Some(Message::Qd(QuickDayMessage::TextChanged(input))),
+            _ => None,
+        }
+    }
+
+    /// The message loading the data the view shows from the DB
+    pub fn load_message(&self) -> Option<Message> {
+        match self {
+            CurrentView::Sw(v) => v.load_message(),
+            CurrentView::Week(v) => v.load_message(),
+            CurrentView::Year(v) => v.load_message(),
+            CurrentView::Fix(v) => v.load_message(),
+            _ => None,
+        }
+    }
+}
+
 impl MainView for CurrentView {
     fn view(&mut self) -> QElement {
         match self {
             CurrentView::Fds(v) => v.view(),
+            CurrentView::Sw(v) => v.view(),
             CurrentView::Fde(v) => v.view(),
+            CurrentView::Ew(v) => v.view(),
+            CurrentView::Gf(v) => v.view(),
             CurrentView::CdUi(v) => v.view(),
             CurrentView::Bs(v) => v.view(),
             CurrentView::Is(v) => v.view(),
             CurrentView::Ie(v) => v.view(),
+            CurrentView::Qsw(v) => v.view(),
+            CurrentView::Ev(v) => v.view(),
+            CurrentView::Sb(v) => v.view(),
+            CurrentView::Qd(v) => v.view(),
+            CurrentView::Ra(v) => v.view(),
             CurrentView::Export(v) => v.view(),
+            CurrentView::Stats(v) => v.view(),
+            CurrentView::Week(v) => v.view(),
+            CurrentView::Year(v) => v.view(),
+            CurrentView::Fix(v) => v.view(),
             CurrentView::Settings(v) => v.view(),
             CurrentView::Exit(v) => v.view(),
         }
@@ -128,14 +252,50 @@ impl MainView for CurrentView {
     fn update(&mut self, msg: Message) -> Option<Message> {
         match self {
             CurrentView::Fds(v) => v.update(msg),
+            CurrentView::Sw(v) => v.update(msg),
             CurrentView::Fde(v) => v.update(msg),
+            CurrentView::Ew(v) => v.update(msg),
+            CurrentView::Gf(v) => v.update(msg),
             CurrentView::CdUi(v) => v.update(msg),
             CurrentView::Bs(v) => v.update(msg),
             CurrentView::Is(v) => v.update(msg),
             CurrentView::Ie(v) => v.update(msg),
+            CurrentView::Qsw(v) => v.update(msg),
+            CurrentView::Ev(v) => v.update(msg),
+            CurrentView::Sb(v) => v.update(msg),
+            CurrentView::Qd(v) => v.update(msg),
+            CurrentView::Ra(v) => v.update(msg),
             CurrentView::Export(v) => v.update(msg),
+            CurrentView::Stats(v) => v.update(msg),
+            CurrentView::Week(v) => v.update(msg),
+            CurrentView::Year(v) => v.update(msg),
+            CurrentView::Fix(v) => v.update(msg),
             CurrentView::Settings(v) => v.update(msg),
             CurrentView::Exit(v) => v.update(msg),
         }
     }
+
+    fn draft(&self) -> Option<&str> {
+        match self {
+            CurrentView::Fds(v) => v.draft(),
+            CurrentView::Sw(v) => v.draft(),
+            CurrentView::Fde(v) => v.draft(),
+            CurrentView::Bs(v) => v.draft(),
+            CurrentView::Is(v) => v.draft(),
+            CurrentView::Ie(v) => v.draft(),
+            CurrentView::Qsw(v) => v.draft(),
+            CurrentView::Gf(v) => v.draft(),
+            CurrentView::Ev(v) => v.draft(),
+            CurrentView::Sb(v) => v.draft(),
+            CurrentView::Qd(v) => v.draft(),
+            _ => None,
+        }
+    }
+
+    fn text_summary(&self) -> Option<String> {
+        match self {
+            CurrentView::CdUi(v) => v.text_summary(),
+            _ => None,
+        }
+    }
 }
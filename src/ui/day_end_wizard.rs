@@ -0,0 +1,601 @@
+use iced_native::widget::{button, text_input, Column, Row};
+
+use crate::conf::SettingsRef;
+use crate::data::{
+    unbooked_gaps, Action, ActiveDay, DayEnd, DayStart, JiraIssue, NormalizedDay, RecentIssuesRef,
+    Work,
+};
+use crate::parsing::parse_result::ParseResult;
+use crate::parsing::time::Time;
+use crate::parsing::time_limit::TimeRange;
+use crate::parsing::{insert_issue, IssueParsed, IssueParser, IssueParserWithRecent};
+use crate::ui::export::export_normalizer;
+use crate::ui::fast_day_end::FastDayEnd;
+use crate::ui::recent_issues_view::filter_message;
+use crate::ui::single_edit_ui::SingleEditUi;
+use crate::ui::stay_active::StayActive;
+use crate::ui::top_bar::TopBar;
+use crate::ui::util::{h_space, v_space};
+use crate::ui::widget::TextInput;
+use crate::ui::{day_info_message, style, text, MainView, Message, QElement, ViewId};
+
+#[derive(Clone, Debug)]
+pub enum DayEndWizardMessage {
+    TextChanged(String),
+    /// Answers the current gap
+    Choose(GapChoice),
+}
+
+/// What an unbooked gap of the day was
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum GapChoice {
+    Book(JiraIssue, String),
+    Break,
+    ExtendPrevious,
+    ExtendNext,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Gap {
+    range: TimeRange,
+    /// Issue and comment of the work ending at the start of the gap
+    previous: Option<(JiraIssue, String)>,
+    /// Issue and comment of the work starting at the end of the gap
+    next: Option<(JiraIssue, String)>,
+    choice: Option<GapChoice>,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Step {
+    DayEnd,
+    Gap(usize),
+    Review,
+    Done,
+}
+
+/// Guided end of the day: confirms the day end, asks what each unbooked gap was, shows the
+/// normalized day and stores everything at once, then offers the export
+pub struct DayEndWizard {
+    step: Step,
+    day: Option<ActiveDay>,
+    settings: SettingsRef,
+    recent_issues: RecentIssuesRef,
+    day_end: Box<FastDayEnd>,
+    /// The confirmed end of the day
+    end: Option<Time>,
+    gaps: Vec<Gap>,
+    top_bar: TopBar,
+    input_state: text_input::State,
+    input: String,
+    recent_filter: String,
+    issue: ParseResult<JiraIssue, ()>,
+    comment: String,
+    review: Option<Result<NormalizedDay, String>>,
+    error: Option<String>,
+    break_button: button::State,
+    previous_button: button::State,
+    next_button: button::State,
+    export_button: button::State,
+}
+
+impl DayEndWizard {
+    pub fn for_active_day(
+        settings: SettingsRef,
+        recent_issues: RecentIssuesRef,
+        active_day: Option<&ActiveDay>,
+    ) -> Box<Self> {
+        let mut day_end = FastDayEnd::for_work_day(settings.clone(), active_day);
+        if let Some(existing) = active_day.and_then(current_end) {
+            day_end.entry_to_edit(existing.clone());
+        }
+        Box::new(Self {
+            step: Step::DayEnd,
+            day: active_day.cloned(),
+            settings: settings.clone(),
+            recent_issues,
+            day_end,
+            end: None,
+            gaps: Vec::new(),
+            top_bar: TopBar {
+                title: "Gap:",
+                help_text: "[issue] <comment> | - break | < previous | > next",
+                info: day_info_message(active_day),
+                settings,
+            },
+            input_state: text_input::State::focused(),
+            input: String::new(),
+            recent_filter: String::new(),
+            issue: ParseResult::None,
+            comment: String::new(),
+            review: None,
+            error: None,
+            break_button: button::State::new(),
+            previous_button: button::State::new(),
+            next_button: button::State::new(),
+            export_button: button::State::new(),
+        })
+    }
+
+    fn confirm_end(&mut self) -> Option<Message> {
+        let end = self.day_end.try_build()?.ts;
+        let day = match &self.day {
+            Some(day) => day,
+            None => {
+                self.error = Some("There is no active day".to_string());
+                return None;
+            }
+        };
+        match unbooked_gaps(&with_end(day, end)) {
+            Ok(gaps) => {
+                self.end = Some(end);
+                self.gaps = gaps.into_iter().map(|g| gap(day, g)).collect();
+                self.error = None;
+                self.show_gap(0)
+            }
+            Err(e) => {
+                self.error = Some(e);
+                None
+            }
+        }
+    }
+
+    /// Asks for the gap at `index`, reviews the day after the last gap
+    fn show_gap(&mut self, index: usize) -> Option<Message> {
+        self.input.clear();
+        self.issue = ParseResult::None;
+        self.comment.clear();
+        if index < self.gaps.len() {
+            self.step = Step::Gap(index);
+            filter_message(&mut self.recent_filter, "")
+        } else {
+            self.step = Step::Review;
+            self.review = self.preview().map(|day| {
+                let settings = self.settings.load();
+                export_normalizer(&settings, true, true).create_normalized(&day)
+            });
+            None
+        }
+    }
+
+    fn update_input(&mut self, input: String) -> Option<Message> {
+        self.input = input;
+        let settings = self.settings.load();
+        let recent = self.recent_issues.borrow();
+        let parser = IssueParserWithRecent::new(&settings.issue_parser, &recent);
+        let IssueParsed {
+            r: issue,
+            input: matching,
+            rest,
+        } = parser.parse_task(self.input.trim_start());
+
+        self.comment = match rest.trim() {
+            "" => issue
+                .get_ref()
+                .and_then(|i| i.default_action.clone())
+                .unwrap_or_default(),
+            rest => rest.to_string(),
+        };
+        self.issue = issue;
+        filter_message(&mut self.recent_filter, matching)
+    }
+
+    fn choose(&mut self, choice: GapChoice) -> Option<Message> {
+        let index = match self.step {
+            Step::Gap(index) => index,
+            _ => return None,
+        };
+        let gap = self.gaps.get_mut(index)?;
+        let available = match choice {
+            GapChoice::ExtendPrevious => gap.previous.is_some(),
+            GapChoice::ExtendNext => gap.next.is_some(),
+            _ => true,
+        };
+        if !available {
+            return None;
+        }
+        gap.choice = Some(choice);
+        self.show_gap(index + 1)
+    }
+
+    fn submit_gap(&mut self) -> Option<Message> {
+        let choice = match self.input.trim() {
+            "-" => GapChoice::Break,
+            "<" => GapChoice::ExtendPrevious,
+            ">" => GapChoice::ExtendNext,
+            _ => GapChoice::Book(self.issue.get_ref()?.clone(), self.comment.clone()),
+        };
+        self.choose(choice)
+    }
+
+    /// The active day with all answers applied
+    fn preview(&self) -> Option<ActiveDay> {
+        let mut day = self.day.clone()?;
+        let (orig, update) = plan(&day, self.end?, &self.gaps);
+        for o in &orig {
+            day.actions_mut().remove(o);
+        }
+        for u in update {
+            day.add_action(u);
+        }
+        Some(day)
+    }
+
+    fn on_submit(&mut self) -> Option<Message> {
+        match self.step {
+            Step::DayEnd => self.confirm_end(),
+            Step::Gap(_) => self.submit_gap(),
+            Step::Review => {
+                if !matches!(self.review, Some(Ok(_))) {
+                    return None;
+                }
+                let (orig, update) = plan(self.day.as_ref()?, self.end?, &self.gaps);
+                // stay in the wizard to offer the export
+                Some(Message::ModifyActions {
+                    stay_active: StayActive::Yes,
+                    orig,
+                    update,
+                })
+            }
+            Step::Done => Some(Message::ChangeView(ViewId::Export)),
+        }
+    }
+
+    fn gap_view(&mut self, index: usize) -> QElement {
+        let gap = &self.gaps[index];
+        let input = TextInput::new(&mut self.input_state, "", &self.input, |i| {
+            Message::Ew(DayEndWizardMessage::TextChanged(i))
+        });
+        let issue = match &self.issue {
+            ParseResult::Valid(issue) => issue.ident.as_str(),
+            ParseResult::None | ParseResult::Incomplete => "<none>",
+            _ => "<invalid>",
+        };
+
+        let mut buttons = vec![style::inline_button(&mut self.break_button, "Break (-)")
+            .on_press(choose(GapChoice::Break))
+            .into()];
+        if let Some((issue, _)) = &gap.previous {
+            buttons.push(h_space(style::SPACE));
+            buttons.push(
+                style::inline_button(
+                    &mut self.previous_button,
+                    &format!("Extend {} (<)", issue.ident),
+                )
+                .on_press(choose(GapChoice::ExtendPrevious))
+                .into(),
+            );
+        }
+        if let Some((issue, _)) = &gap.next {
+            buttons.push(h_space(style::SPACE));
+            buttons.push(
+                style::inline_button(
+                    &mut self.next_button,
+                    &format!("Extend {} (>)", issue.ident),
+                )
+                .on_press(choose(GapChoice::ExtendNext))
+                .into(),
+            );
+        }
+
+        Column::with_children(vec![
+            self.top_bar.view(),
+            v_space(style::SPACE),
+            text(format!(
+                "Gap {} of {}: nothing booked from {} to {}",
+                index + 1,
+                self.gaps.len(),
+                gap.range.min(),
+                gap.range.max()
+            )),
+            v_space(style::SPACE),
+            input.into(),
+            v_space(style::SPACE),
+            Row::with_children(vec![
+                text("Issue:"),
+                h_space(style::SPACE),
+                text(issue),
+                h_space(style::DSPACE),
+                text("Comment:"),
+                h_space(style::SPACE),
+                text(&self.comment),
+            ])
+            .into(),
+            v_space(style::SPACE),
+            Row::with_children(buttons).into(),
+        ])
+        .into()
+    }
+
+    fn review_view(&mut self) -> QElement {
+        let mut rows = vec![text(match self.step {
+            Step::Done => "The day is stored, Enter opens the export".to_string(),
+            _ => format!("The day ends at {}, Enter stores it", self.end_label()),
+        })];
+        rows.push(v_space(style::SPACE));
+        match &self.review {
+            Some(Ok(normalized)) => {
+                for w in &normalized.entries {
+                    rows.push(text(format!(
+                        "{} - {}  {}  {}",
+                        w.start, w.end, w.task.ident, w.description
+                    )));
+                }
+            }
+            Some(Err(e)) => rows.push(text(format!("Cannot normalize the day: {}", e))),
+            None => (),
+        }
+        if self.step == Step::Done {
+            rows.push(v_space(style::SPACE));
+            rows.push(
+                style::inline_button(&mut self.export_button, "Export")
+                    .on_press(Message::ChangeView(ViewId::Export))
+                    .into(),
+            );
+        }
+        Column::with_children(rows).into()
+    }
+
+    fn end_label(&self) -> String {
+        self.end.map(|t| t.to_string()).unwrap_or_default()
+    }
+}
+
+impl MainView for DayEndWizard {
+    fn view(&mut self) -> QElement {
+        let error = self.error.clone();
+        let content = match self.step {
+            Step::DayEnd => self.day_end.view(),
+            Step::Gap(index) => self.gap_view(index),
+            Step::Review | Step::Done => self.review_view(),
+        };
+        match error {
+            Some(error) => {
+                Column::with_children(vec![content, v_space(style::SPACE), text(error)]).into()
+            }
+            None => content,
+        }
+    }
+
+    fn update(&mut self, msg: Message) -> Option<Message> {
+        match msg {
+            Message::Fde(_) | Message::Tick if self.step == Step::DayEnd => {
+                self.day_end.update(msg)
+            }
+            Message::InsertIssue(ident) => {
+                self.input_state.move_cursor_to_end();
+                let input = insert_issue(&self.input, &ident);
+                Some(Message::Ew(DayEndWizardMessage::TextChanged(input)))
+            }
+            Message::Ew(DayEndWizardMessage::TextChanged(input)) => self.update_input(input),
+            Message::Ew(DayEndWizardMessage::Choose(choice)) => self.choose(choice),
+            Message::SubmitCurrent(_) => self.on_submit(),
+            Message::StoreSuccess(_) => {
+                self.step = Step::Done;
+                None
+            }
+            _ => None,
+        }
+    }
+}
+
+fn choose(choice: GapChoice) -> Message {
+    Message::Ew(DayEndWizardMessage::Choose(choice))
+}
+
+/// The DayEnd after the last DayStart
+pub(super) fn current_end(day: &ActiveDay) -> Option<&DayEnd> {
+    day.actions().iter().rev().find_map(|a| match a {
+        Action::DayEnd(end) => Some(Some(end)),
+        Action::DayStart(_) => Some(None),
+        _ => None,
+    })?
+}
+
+/// The day ending at `end`, replacing its current end
+pub(super) fn with_end(day: &ActiveDay, end: Time) -> ActiveDay {
+    let mut day = day.clone();
+    if let Some(existing) = current_end(&day).cloned() {
+        day.actions_mut().remove(&Action::DayEnd(existing));
+    }
+    day.add_action(Action::DayEnd(DayEnd { ts: end }));
+    day
+}
+
+fn gap(day: &ActiveDay, range: TimeRange) -> Gap {
+    let work = |a: &Action| {
+        let issue = a.issue()?.clone();
+        let description = a
+            .description()
+            .map(str::to_string)
+            .or_else(|| issue.default_action.clone())
+            .unwrap_or_default();
+        Some((issue, description))
+    };
+    let actions: Vec<Action> = day
+        .actions()
+        .iter()
+        .map(|a| a.clone().map_times(Time::round_seconds))
+        .collect();
+    Gap {
+        range,
+        previous: actions
+            .iter()
+            .rev()
+            .filter(|a| a.end() == Some(range.min()))
+            .find_map(work),
+        next: actions
+            .iter()
+            .filter(|a| a.start() == Some(range.max()))
+            .find_map(work),
+        choice: None,
+    }
+}
+
+/// Actions replaced (first) and added (second) to end the day at `end` with the answered gaps.
+///
+/// A break at the start of the day moves the DayStart, one at its end moves the DayEnd, other
+/// breaks end the day and start it again
+fn plan(day: &ActiveDay, end: Time, gaps: &[Gap]) -> (Vec<Action>, Vec<Action>) {
+    let mut orig: Vec<Action> = current_end(day)
+        .cloned()
+        .map(Action::DayEnd)
+        .into_iter()
+        .collect();
+    let mut update = Vec::new();
+    let mut day_end = end;
+    for gap in gaps {
+        let (start, gap_end) = (gap.range.min(), gap.range.max());
+        let book = |(task, description): &(JiraIssue, String)| {
+            Action::Work(Work {
+                start,
+                end: gap_end,
+                task: task.clone(),
+                description: description.clone(),
+                links: Vec::new(),
+            })
+        };
+        match &gap.choice {
+            Some(GapChoice::Book(task, description)) => {
+                update.push(Action::Work(Work {
+                    start,
+                    end: gap_end,
+                    task: task.clone(),
+                    description: description.clone(),
+                    links: Vec::new(),
+                }));
+            }
+            Some(GapChoice::ExtendPrevious) => update.extend(gap.previous.as_ref().map(book)),
+            Some(GapChoice::ExtendNext) => update.extend(gap.next.as_ref().map(book)),
+            Some(GapChoice::Break) => {
+                let day_start = day.actions().iter().find_map(|a| match a {
+                    Action::DayStart(s) if s.ts.round_seconds() == start => Some(s.clone()),
+                    _ => None,
+                });
+                if let Some(day_start) = day_start {
+                    update.push(Action::DayStart(DayStart {
+                        location: day_start.location.clone(),
+                        ts: gap_end,
+                    }));
+                    orig.push(Action::DayStart(day_start));
+                } else if gap_end == end {
+                    day_end = start;
+                } else {
+                    update.push(Action::DayEnd(DayEnd { ts: start }));
+                    update.push(Action::DayStart(DayStart {
+                        location: day.location().clone(),
+                        ts: gap_end,
+                    }));
+                }
+            }
+            None => (),
+        }
+    }
+    update.push(Action::DayEnd(DayEnd { ts: day_end }));
+    (orig, update)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::data::test_support::*;
+    use crate::data::{ActiveDayBuilder, Day, Location, RecentIssuesRef};
+    use crate::ui::day_end_wizard::{DayEndWizard, DayEndWizardMessage, GapChoice, Step};
+    use crate::ui::fast_day_end::FastDayEndMessage;
+    use crate::ui::stay_active::StayActive;
+    use crate::ui::{MainView, Message, ViewId};
+    use crate::util::StaticTimeline;
+    use crate::Settings;
+
+    #[test]
+    fn books_the_gaps_and_ends_the_day() {
+        let settings = Settings {
+            timeline: StaticTimeline::parse("2022-01-20 17:00").into(),
+            ..Settings::default()
+        }
+        .into_settings_ref();
+        let day = ActiveDayBuilder {
+            day: Day::ymd(2022, 1, 20),
+            main_location: Location::Office,
+            active_issue: None,
+            actions: vec![
+                day_start("o8"),
+                work("9", "10", "A-1", "a"),
+                work("11", "12", "A-2", "b"),
+                work("13", "16", "A-3", "c"),
+            ],
+        }
+        .build();
+        let recent = RecentIssuesRef::empty(settings.clone());
+        let mut ui = DayEndWizard::for_active_day(settings, recent, Some(&day));
+
+        ui.update(Message::Fde(FastDayEndMessage::TextChanged("16:30".into())));
+        ui.update(Message::SubmitCurrent(StayActive::Default));
+        assert_eq!(ui.gaps.len(), 4);
+        assert_eq!(ui.step, Step::Gap(0));
+
+        ui.update(Message::Ew(DayEndWizardMessage::TextChanged(
+            "B-1 mails".into(),
+        )));
+        ui.update(Message::SubmitCurrent(StayActive::Default));
+        ui.update(Message::Ew(DayEndWizardMessage::TextChanged(">".into())));
+        ui.update(Message::SubmitCurrent(StayActive::Default));
+        ui.update(Message::Ew(DayEndWizardMessage::Choose(GapChoice::Break)));
+        ui.update(Message::Ew(DayEndWizardMessage::Choose(
+            GapChoice::ExtendPrevious,
+        )));
+        assert_eq!(ui.step, Step::Review);
+        assert!(matches!(ui.review, Some(Ok(_))));
+
+        match ui.update(Message::SubmitCurrent(StayActive::Default)) {
+            Some(Message::ModifyActions { orig, update, .. }) => {
+                assert_eq!(orig, vec![]);
+                assert_eq!(
+                    update,
+                    vec![
+                        work("8", "9", "B-1", "mails"),
+                        work("10", "11", "A-2", "b"),
+                        day_end("12"),
+                        day_start("o13"),
+                        work("16", "16:30", "A-3", "c"),
+                        day_end("16:30"),
+                    ]
+                );
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        ui.update(Message::StoreSuccess(StayActive::Yes));
+        assert!(matches!(
+            ui.update(Message::SubmitCurrent(StayActive::Default)),
+            Some(Message::ChangeView(ViewId::Export))
+        ));
+    }
+
+    #[test]
+    fn breaks_move_the_start_and_end_of_the_day() {
+        let settings = Settings {
+            timeline: StaticTimeline::parse("2022-01-20 17:00").into(),
+            ..Settings::default()
+        }
+        .into_settings_ref();
+        let day = ActiveDayBuilder {
+            day: Day::ymd(2022, 1, 20),
+            main_location: Location::Office,
+            active_issue: None,
+            actions: vec![day_start("o8"), work("9", "16", "A-1", "a"), day_end("17")],
+        }
+        .build();
+        let recent = RecentIssuesRef::empty(settings.clone());
+        let mut ui = DayEndWizard::for_active_day(settings, recent, Some(&day));
+
+        ui.update(Message::SubmitCurrent(StayActive::Default));
+        ui.update(Message::Ew(DayEndWizardMessage::Choose(GapChoice::Break)));
+        ui.update(Message::Ew(DayEndWizardMessage::Choose(GapChoice::Break)));
+
+        match ui.update(Message::SubmitCurrent(StayActive::Default)) {
+            Some(Message::ModifyActions { orig, update, .. }) => {
+                assert_eq!(orig, vec![day_end("17"), day_start("o8")]);
+                assert_eq!(update, vec![day_start("o9"), day_end("16")]);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+}
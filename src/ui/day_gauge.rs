@@ -0,0 +1,198 @@
+use std::collections::BTreeSet;
+
+use iced_core::Length;
+use iced_native::widget::{Container, Row};
+
+use crate::data::Action;
+use crate::parsing::time::Time;
+use crate::parsing::time_limit::TimeRange;
+use crate::ui::util::h_space;
+use crate::ui::{style, text, unbooked_time_for_day, QElement};
+
+const GAUGE_HEIGHT: u16 = 14;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Segment {
+    Booked,
+    /// Unbooked time within the default break
+    Break,
+    /// Unbooked time before now
+    Unbooked,
+    /// Unbooked time after now
+    Later,
+}
+
+impl Segment {
+    fn style(self) -> style::Gauge {
+        style::Gauge(match self {
+            Segment::Booked => style::BOOKED_COLOR,
+            Segment::Break => style::BREAK_COLOR,
+            Segment::Unbooked => style::UNBOOKED_COLOR,
+            Segment::Later => style::GAP_COLOR,
+        })
+    }
+}
+
+/// Horizontal bar of the booked and unbooked ranges of a day.
+///
+/// `now` is only set for the current day and shows the current time marker
+pub fn day_gauge<'a>(
+    actions: &BTreeSet<Action>,
+    default_break: (Time, Time),
+    now: Option<Time>,
+) -> QElement<'a> {
+    let segments = segments(actions, default_break, now);
+    let (first, last) = match (segments.first(), segments.last()) {
+        (Some(first), Some(last)) => (first.1.min(), last.1.max()),
+        _ => return text("Nothing booked"),
+    };
+
+    let mut bar = Vec::new();
+    for (segment, range) in segments {
+        if now == Some(range.min()) {
+            bar.push(now_marker());
+        }
+        let minutes = range.duration().offset_minutes().max(1) as u16;
+        bar.push(
+            Container::new(h_space(Length::Fill))
+                .width(Length::FillPortion(minutes))
+                .height(Length::Units(GAUGE_HEIGHT))
+                .style(segment.style())
+                .into(),
+        );
+    }
+    if now == Some(last) {
+        bar.push(now_marker());
+    }
+
+    Row::with_children(vec![
+        text(first.to_string()),
+        h_space(style::SPACE),
+        Row::with_children(bar).width(Length::Fill).into(),
+        h_space(style::SPACE),
+        text(last.to_string()),
+    ])
+    .into()
+}
+
+fn now_marker<'a>() -> QElement<'a> {
+    Container::new(h_space(Length::Units(2)))
+        .height(Length::Units(GAUGE_HEIGHT))
+        .style(style::Gauge(style::ERROR_COLOR_FOCUSSED))
+        .into()
+}
+
+/// Ordered ranges from the first to the last booked time, or now if that is later
+fn segments(
+    actions: &BTreeSet<Action>,
+    default_break: (Time, Time),
+    now: Option<Time>,
+) -> Vec<(Segment, TimeRange)> {
+    // standby is outside of the working time
+    let work = || actions.iter().filter(|a| !matches!(a, Action::Standby(_)));
+    let first = work().filter_map(Action::start).chain(now).min();
+    let last = work()
+        .filter_map(|a| a.end().or_else(|| a.start()))
+        .chain(now)
+        .max();
+    let bounds = match (first, last) {
+        (Some(first), Some(last)) if first < last => TimeRange::new(first, last),
+        _ => return Vec::new(),
+    };
+
+    let mut booked: Vec<TimeRange> = Vec::new();
+    for action in work().filter(|a| a.duration_minutes().is_some()) {
+        let range = TimeRange::new(action.start().unwrap(), action.end().unwrap());
+        match booked.last_mut() {
+            Some(prev) if prev.max() >= range.min() => *prev = prev.extend(range.max()),
+            _ => booked.push(range),
+        }
+    }
+
+    let mut result: Vec<_> = booked.into_iter().map(|r| (Segment::Booked, r)).collect();
+    let break_range = TimeRange::new(default_break.0, default_break.1);
+    for gap in unbooked_time_for_day(actions) {
+        let gap = clamp(gap, bounds);
+        let before_break = clamp(gap, TimeRange::new(bounds.min(), break_range.min()));
+        let in_break = clamp(gap, break_range);
+        let after_break = clamp(gap, TimeRange::new(break_range.max(), bounds.max()));
+        result.push((Segment::Break, in_break));
+        for part in [before_break, after_break] {
+            match now {
+                Some(now) => {
+                    result.push((
+                        Segment::Unbooked,
+                        clamp(part, TimeRange::new(part.min(), now)),
+                    ));
+                    result.push((Segment::Later, clamp(part, TimeRange::new(now, part.max()))));
+                }
+                None => result.push((Segment::Unbooked, part)),
+            }
+        }
+    }
+
+    // the current time marker is placed between segments
+    if let Some(now) = now {
+        let containing = result
+            .iter()
+            .position(|(_, r)| r.min() < now && now < r.max());
+        if let Some(index) = containing {
+            let (segment, range) = result.remove(index);
+            result.push((segment, TimeRange::new(range.min(), now)));
+            result.push((segment, TimeRange::new(now, range.max())));
+        }
+    }
+
+    result.retain(|(_, r)| !r.is_empty());
+    result.sort_by_key(|(_, r)| r.min());
+    result
+}
+
+/// The part of `range` within `bounds`, empty if they do not overlap
+fn clamp(range: TimeRange, bounds: TimeRange) -> TimeRange {
+    let min = std::cmp::max(range.min(), bounds.min());
+    let max = std::cmp::min(range.max(), bounds.max());
+    TimeRange::new(min, std::cmp::max(min, max))
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeSet;
+
+    use crate::data::test_support::{time, work};
+    use crate::parsing::time_limit::TimeRange;
+    use crate::ui::day_gauge::{segments, Segment};
+
+    #[test]
+    fn splits_unbooked_time() {
+        let actions: BTreeSet<_> = [
+            work("8", "11", "A-1", "a"),
+            work("10", "12", "A-2", "b"),
+            work("13", "14", "A-1", "c"),
+        ]
+        .into_iter()
+        .collect();
+        let range = |s: &str, e: &str| TimeRange::new(time(s), time(e));
+
+        assert_eq!(
+            segments(&actions, (time("12"), time("12:30")), Some(time("15"))),
+            vec![
+                (Segment::Booked, range("8", "12")),
+                (Segment::Break, range("12", "12:30")),
+                (Segment::Unbooked, range("12:30", "13")),
+                (Segment::Booked, range("13", "14")),
+                (Segment::Unbooked, range("14", "15")),
+            ]
+        );
+        assert_eq!(
+            segments(&actions, (time("12"), time("12:30")), Some(time("13:30"))),
+            vec![
+                (Segment::Booked, range("8", "12")),
+                (Segment::Break, range("12", "12:30")),
+                (Segment::Unbooked, range("12:30", "13")),
+                (Segment::Booked, range("13", "13:30")),
+                (Segment::Booked, range("13:30", "14")),
+            ]
+        );
+    }
+}
@@ -0,0 +1,65 @@
+use iced_core::Length;
+use iced_native::widget::button;
+use iced_winit::widget::{Container, Row};
+
+use crate::data::{Day, DayForwarder, HolidayForwarder};
+use crate::ui::notification::desktop_notification;
+use crate::ui::util::h_space;
+use crate::ui::{style, text, Message, QElement, ViewId};
+use crate::Settings;
+
+/// Reminds about today's missing day start after the configured time
+#[derive(Debug, Default)]
+pub struct DayStartReminder {
+    /// Today is a workday without day start and the reminder time has passed
+    due: bool,
+    /// The day of the last notification
+    notified: Option<Day>,
+    start_button: button::State,
+}
+
+impl DayStartReminder {
+    /// `started` tells whether today has a day start.
+    /// Notifies once per day, unless the reminder is due when the app opens and the banner
+    /// is visible anyway
+    pub fn check(&mut self, settings: &Settings, started: impl FnOnce() -> bool, startup: bool) {
+        let today = settings.today();
+        let workday = HolidayForwarder {
+            holidays: settings.holidays.clone(),
+        }
+        .is_valid(today);
+        self.due = match settings.day_start_reminder {
+            Some(after) if workday && settings.timeline.time_now() >= after => !started(),
+            _ => false,
+        };
+        if self.due && self.notified != Some(today) {
+            if !startup {
+                desktop_notification(
+                    "Day not started",
+                    "There is no day start booked today, press ctrl+O to add it",
+                );
+            }
+            self.notified = Some(today);
+        }
+    }
+
+    pub fn view(&mut self) -> Option<QElement> {
+        if !self.due {
+            return None;
+        }
+        let row = vec![
+            text("Today has no day start"),
+            h_space(Length::Fill),
+            style::inline_button(&mut self.start_button, "Start day (ctrl+O)")
+                .on_press(Message::ChangeView(ViewId::FastDayStart))
+                .into(),
+        ];
+        Some(
+            Container::new(Row::with_children(row))
+                .style(style::ContentStyle)
+                .width(Length::Fill)
+                .padding(style::WINDOW_PADDING)
+                .into(),
+        )
+    }
+}
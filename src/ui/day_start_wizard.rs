@@ -0,0 +1,231 @@
+use iced_native::widget::Column;
+
+use crate::conf::SettingsRef;
+use crate::data::{Action, ActiveDay, JiraIssue, Work, WorkStart};
+use crate::parsing::time::Time;
+use crate::ui::fast_day_start::FastDayStart;
+use crate::ui::single_edit_ui::SingleEditUi;
+use crate::ui::util::v_space;
+use crate::ui::widget::Checkbox;
+use crate::ui::{style, text, MainView, Message, QElement, ViewId};
+
+#[derive(Clone, Debug)]
+pub enum DayStartWizardMessage {
+    /// The issue left open on the previous workday
+    Loaded(Option<JiraIssue>),
+    Toggle(usize, bool),
+}
+
+/// Something the new day can take over
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum Offer {
+    /// Continues the issue at the start of the day
+    Continue(JiraIssue),
+    /// A recurring booking of the settings
+    Planned(Work),
+}
+
+impl Offer {
+    fn label(&self) -> String {
+        match self {
+            Offer::Continue(issue) => format!("Continue {} from the previous workday", issue.ident),
+            Offer::Planned(w) => format!(
+                "{} - {}  {}  {}",
+                w.start, w.end, w.task.ident, w.description
+            ),
+        }
+    }
+
+    fn action(&self, day_start: Time) -> Action {
+        match self {
+            Offer::Continue(task) => Action::WorkStart(WorkStart {
+                ts: day_start,
+                task: task.clone(),
+                description: task.default_action.clone().unwrap_or_default(),
+            }),
+            Offer::Planned(work) => Action::Work(work.clone()),
+        }
+    }
+}
+
+/// Starts a new day: the day start, the work left open on the previous workday and the
+/// recurring bookings of the weekday, stored at once
+pub struct DayStartWizard {
+    day_start: Box<FastDayStart>,
+    day: Option<ActiveDay>,
+    offers: Vec<(Offer, bool)>,
+}
+
+impl DayStartWizard {
+    pub fn for_active_day(settings: SettingsRef, active_day: Option<&ActiveDay>) -> Box<Self> {
+        let offers = match active_day {
+            Some(day) => settings
+                .load()
+                .recurring_bookings
+                .iter()
+                .filter(|b| b.applies_to(day.get_day()))
+                .map(|b| (Offer::Planned(b.work()), true))
+                .collect(),
+            None => Vec::new(),
+        };
+        Box::new(Self {
+            day_start: FastDayStart::for_work_day(settings, active_day),
+            day: active_day.cloned(),
+            offers,
+        })
+    }
+
+    pub fn load_message(&self) -> Option<Message> {
+        self.day
+            .as_ref()
+            .map(|d| Message::LoadOpenIssue(d.get_day()))
+    }
+
+    fn on_loaded(&mut self, issue: Option<JiraIssue>) {
+        self.offers
+            .retain(|(o, _)| !matches!(o, Offer::Continue(_)));
+        if let Some(issue) = issue {
+            self.offers.insert(0, (Offer::Continue(issue), true));
+        }
+    }
+
+    fn on_submit(&self) -> Option<Vec<Action>> {
+        let day_start = self.day_start.try_build()?;
+        let ts = day_start.ts;
+        let mut actions = vec![Action::DayStart(day_start)];
+        actions.extend(
+            self.offers
+                .iter()
+                .filter(|(_, selected)| *selected)
+                .map(|(o, _)| o.action(ts)),
+        );
+        Some(actions)
+    }
+}
+
+impl MainView for DayStartWizard {
+    fn view(&mut self) -> QElement {
+        let mut rows = vec![self.day_start.view()];
+        if !self.offers.is_empty() {
+            rows.push(v_space(style::DSPACE));
+            rows.push(text("Take over:"));
+        }
+        for (index, (offer, selected)) in self.offers.iter().enumerate() {
+            rows.push(v_space(style::SPACE));
+            rows.push(
+                Checkbox::new(*selected, offer.label(), move |b| {
+                    Message::Sw(DayStartWizardMessage::Toggle(index, b))
+                })
+                .into(),
+            );
+        }
+        Column::with_children(rows).into()
+    }
+
+    fn update(&mut self, msg: Message) -> Option<Message> {
+        match msg {
+            Message::Sw(DayStartWizardMessage::Loaded(issue)) => {
+                self.on_loaded(issue);
+                None
+            }
+            Message::Sw(DayStartWizardMessage::Toggle(index, selected)) => {
+                if let Some(offer) = self.offers.get_mut(index) {
+                    offer.1 = selected;
+                }
+                None
+            }
+            Message::Fds(_) | Message::Tick | Message::Up | Message::Down => {
+                self.day_start.update(msg)
+            }
+            Message::SubmitCurrent(stay_active) => self
+                .on_submit()
+                .map(|actions| Message::StoreActions(stay_active, actions)),
+            // the wizard would start the day again
+            Message::StoreSuccess(stay_active) if !stay_active.do_close() => {
+                Some(Message::ChangeView(ViewId::CurrentDayUi))
+            }
+            Message::StoreSuccess(stay_active) => stay_active.on_main_view_store(),
+            _ => None,
+        }
+    }
+
+    fn draft(&self) -> Option<&str> {
+        self.day_start.draft()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::conf::RecurringBooking;
+    use crate::data::test_support::*;
+    use crate::data::{Action, ActiveDay, Day, DayStart, JiraIssue, Location, WorkStart};
+    use crate::ui::day_start_wizard::{DayStartWizard, DayStartWizardMessage};
+    use crate::ui::fast_day_start::FastDayStartMessage;
+    use crate::ui::stay_active::StayActive;
+    use crate::ui::{MainView, Message};
+    use crate::util::StaticTimeline;
+    use crate::Settings;
+    use chrono::Weekday;
+
+    #[test]
+    fn starts_the_day_with_open_work_and_planned_meetings() {
+        let booking = |weekdays, task: &str| RecurringBooking {
+            weekdays,
+            start: time("9:30"),
+            end: time("9:45"),
+            task: JiraIssue::create(task).unwrap(),
+            description: "standup".to_string(),
+        };
+        let settings = Settings {
+            timeline: StaticTimeline::parse("2022-01-20 8:00").into(),
+            recurring_bookings: vec![
+                booking(vec![], "MEET-1"),
+                booking(vec![Weekday::Mon], "MEET-2"),
+            ],
+            ..Settings::default()
+        }
+        .into_settings_ref();
+        let day = ActiveDay::new(Day::ymd(2022, 1, 20), Location::Office, None);
+        let mut ui = DayStartWizard::for_active_day(settings, Some(&day));
+
+        assert!(matches!(ui.load_message(), Some(Message::LoadOpenIssue(d)) if d == day.get_day()));
+        let open = JiraIssue {
+            ident: "A-1".to_string(),
+            description: None,
+            default_action: Some("dev".to_string()),
+        };
+        ui.update(Message::Sw(DayStartWizardMessage::Loaded(Some(
+            open.clone(),
+        ))));
+        assert_eq!(ui.offers.len(), 2);
+
+        ui.update(Message::Fds(FastDayStartMessage::TextChanged(
+            "h7:45".into(),
+        )));
+        match ui.update(Message::SubmitCurrent(StayActive::Default)) {
+            Some(Message::StoreActions(_, actions)) => assert_eq!(
+                actions,
+                vec![
+                    Action::DayStart(DayStart {
+                        location: Location::Home,
+                        ts: time("7:45"),
+                    }),
+                    Action::WorkStart(WorkStart {
+                        ts: time("7:45"),
+                        task: open,
+                        description: "dev".to_string(),
+                    }),
+                    work("9:30", "9:45", "MEET-1", "standup"),
+                ]
+            ),
+            other => panic!("unexpected {:?}", other),
+        }
+
+        ui.update(Message::Sw(DayStartWizardMessage::Toggle(0, false)));
+        ui.update(Message::Sw(DayStartWizardMessage::Toggle(1, false)));
+        match ui.update(Message::SubmitCurrent(StayActive::Default)) {
+            Some(Message::StoreActions(_, actions)) => assert_eq!(actions.len(), 1),
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+}
@@ -1,18 +1,52 @@
 use crate::conf::SettingsRef;
-use crate::data::{Action, ActiveDay, NormalizedDay, Normalizer, TimeCockpitExporter};
-use crate::ui::util::{h_space, v_space};
+use crate::data::{
+    export_diff, export_path, rounding_report, Action, ActiveDay, EntryChange, ExportStatus,
+    JsonExporter, NormalizedDay, Normalizer, TemplateExporter, TimeCockpitExporter, WeekSummary,
+};
+use crate::ui::button_focus::ButtonFocus;
+use crate::ui::util::{h_space, v_space, VirtualList};
+use crate::ui::widget::TextInput;
 use crate::ui::{style, text, MainView, Message, QElement};
+use crate::util::TimelineProvider;
+use crate::Settings;
 use iced_core::Length;
-use iced_native::widget::{
-    button, scrollable, Button, Checkbox, Column, Container, Row, Scrollable,
-};
+use iced_native::widget::{button, text_input, Button, Checkbox, Column, Container, Row, Text};
 use std::num::NonZeroU32;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 #[derive(Clone, Debug)]
 pub enum DayExportMessage {
     ChangeNormalize(bool),
+    AppendRounding(bool),
     TriggerExport,
+    /// Edits of the export lines are discarded, they are only selectable
+    LineEdited,
+    PathChanged(String),
+    SaveToFile,
+    /// Opens the save dialog, the path field is the fallback without a dialog
+    ChooseFile,
+    /// Path chosen in the save dialog, `None` if the dialog was cancelled
+    FileChosen(Option<PathBuf>),
+    Scrolled(f32),
+}
+
+/// Days included in a mailed report
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ReportPeriod {
+    Day,
+    /// The week of the day
+    Week,
+}
+
+/// Older written files are not shown
+const MAX_SHOWN_FILES: usize = 5;
+
+/// A line of the export text with its own copy button
+struct ExportLine {
+    text: Arc<String>,
+    input: text_input::State,
+    copy_button: button::State,
 }
 
 pub struct DayExportUi {
@@ -20,32 +54,75 @@ pub struct DayExportUi {
     normalized: Option<NormalizedDay>,
     actions: Vec<Action>,
     export_text: Option<Arc<String>>,
+    lines: Vec<ExportLine>,
+    file_path: String,
+    file_input: text_input::State,
+    save_button: button::State,
+    dialog_button: button::State,
+    mail_day_button: button::State,
+    mail_week_button: button::State,
+    webhook_button: button::State,
     msg: Option<String>,
     error: String,
+    /// The export template of the settings cannot be rendered
+    template_error: Option<String>,
+    /// The normalized day exceeds the daily work time cap
+    cap_warning: Option<String>,
+    /// The exceeded cap was acknowledged by exporting again
+    cap_confirmed: bool,
+    /// Differences to the entries of the previous export and its time, if the day was modified
+    changes: Option<(chrono::NaiveDateTime, Vec<EntryChange>)>,
     clip_button: button::State,
     settings: SettingsRef,
     combine_bookings: bool,
     add_break: bool,
-    scroll_state: scrollable::State,
+    /// The rounding report is appended to the export text
+    append_rounding: bool,
+    preview: VirtualList,
+    /// An already exported day is only exported again on the second trigger
+    reexport_confirmed: bool,
+    focus: ButtonFocus,
 }
 
 impl DayExportUi {
     pub fn for_active_day(settings: SettingsRef, current_day: Option<&ActiveDay>) -> Box<Self> {
         let combine_bookings = true;
         let add_break = true;
+        let pattern = settings.load().export_file_pattern.clone();
+        let file_path = match current_day {
+            Some(day) if !pattern.is_empty() => export_path(&pattern, day.get_day())
+                .map(|p| p.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            _ => String::new(),
+        };
 
         let mut ui = Box::new(Self {
             active_day: current_day.cloned(),
             normalized: None,
             actions: Vec::new(),
             export_text: None,
+            lines: Vec::new(),
+            file_path,
+            file_input: text_input::State::new(),
+            save_button: button::State::new(),
+            dialog_button: button::State::new(),
+            mail_day_button: button::State::new(),
+            mail_week_button: button::State::new(),
+            webhook_button: button::State::new(),
             msg: None,
             error: String::new(),
+            template_error: None,
+            cap_warning: None,
+            cap_confirmed: false,
+            changes: None,
             clip_button: button::State::new(),
             settings,
             combine_bookings,
             add_break,
-            scroll_state: scrollable::State::new(),
+            append_rounding: false,
+            preview: VirtualList::default(),
+            reexport_confirmed: false,
+            focus: ButtonFocus::default(),
         });
 
         ui.normalize_day();
@@ -55,15 +132,10 @@ impl DayExportUi {
 
     fn normalize_day(&mut self) {
         let s = self.settings.load();
+        self.template_error = None;
+        let normalizer = export_normalizer(&s, self.combine_bookings, self.add_break);
         let (normalized, actions, error) = if let Some(current_day) = self.active_day.as_ref() {
-            let n = Normalizer {
-                resolution: NonZeroU32::new(s.resolution.num_minutes() as u32)
-                    .unwrap_or_else(|| NonZeroU32::new(1).unwrap()),
-                breaks_config: s.breaks.clone(),
-                combine_bookings: self.combine_bookings,
-                add_break: self.add_break,
-            }
-            .create_normalized(current_day);
+            let n = normalizer.create_normalized(current_day);
 
             match n {
                 Ok(n) => {
@@ -76,16 +148,109 @@ impl DayExportUi {
             (None, Vec::new(), None)
         };
 
-        let export_text = normalized
-            .as_ref()
-            .map(|w| Arc::new(TimeCockpitExporter::export(w)));
+        self.changes = match (self.active_day.as_ref(), normalized.as_ref()) {
+            (Some(day), Some(n)) => match day.export_status() {
+                ExportStatus::ModifiedSinceExport(at) => day
+                    .exported_day()
+                    .and_then(|d| normalizer.create_normalized(&d).ok())
+                    .map(|before| (at, export_diff(&before.entries, &n.entries))),
+                _ => None,
+            },
+            _ => None,
+        };
+
+        self.cap_warning = normalized.as_ref().and_then(|n| {
+            WeekSummary::collect(n.date, [n])
+                .progress(n.date, s.daily_target_minutes, s.weekly_target_minutes)
+                .exceeded_cap(s.max_daily_minutes, None)
+        });
+
+        let unknown = normalized.as_ref().and_then(|n| unknown_projects(&s, n));
+        // typos in the project must not reach the time tracking
+        let (export_text, error) = match unknown {
+            None => (
+                normalized
+                    .as_ref()
+                    .and_then(|n| match format_export(&s, n) {
+                        Ok(text) if self.append_rounding => {
+                            Some(Arc::new(format!("{}\n{}", text, rounding_report(n))))
+                        }
+                        Ok(text) => Some(Arc::new(text)),
+                        Err(e) => {
+                            self.template_error = Some(e);
+                            None
+                        }
+                    }),
+                error,
+            ),
+            Some(unknown) => (None, Some(unknown)),
+        };
 
         self.normalized = normalized;
         self.actions = actions;
         self.error = error.unwrap_or_default();
+        self.lines = export_text
+            .iter()
+            .flat_map(|t| t.lines())
+            .map(|line| ExportLine {
+                text: Arc::new(line.to_string()),
+                input: text_input::State::new(),
+                copy_button: button::State::new(),
+            })
+            .collect();
         self.export_text = export_text;
     }
 
+    /// Ctrl+C in a text input copies the selection instead of the whole export
+    fn input_focused(&self) -> bool {
+        self.file_input.is_focused() || self.lines.iter().any(|l| l.input.is_focused())
+    }
+
+    /// A day above the daily cap is only exported after a second attempt
+    fn cap_acknowledged(&mut self) -> bool {
+        match &self.cap_warning {
+            Some(cap) if !self.cap_confirmed => {
+                self.cap_confirmed = true;
+                self.msg = Some(format!("{}, export again to continue", cap));
+                false
+            }
+            _ => true,
+        }
+    }
+
+    fn save_to_file(&mut self) -> Option<Message> {
+        let path = PathBuf::from(self.file_path.trim());
+        let text = match &self.export_text {
+            Some(_) if path.as_os_str().is_empty() => {
+                self.msg = Some("Enter a file name".to_string());
+                return None;
+            }
+            Some(text) => text.clone(),
+            None => {
+                self.msg = Some("Nothing to export".to_string());
+                return self.follow_up();
+            }
+        };
+        if !self.cap_acknowledged() {
+            return None;
+        }
+
+        if let Err(e) = write_export_file(&path, &text) {
+            self.msg = Some(e);
+            return None;
+        }
+
+        self.msg = Some(format!(
+            "Saved {} entries to {}",
+            self.lines.len(),
+            path.display()
+        ));
+        let day = self.active_day.as_mut()?;
+        let now = self.settings.load().timeline.now();
+        day.mark_exported_to_file(now, text.to_string(), path.clone());
+        Some(Message::MarkExportedToFile(day.get_day(), text, path))
+    }
+
     fn follow_up(&mut self) -> Option<Message> {
         let err = std::mem::take(&mut self.error);
         if !err.is_empty() {
@@ -94,14 +259,144 @@ impl DayExportUi {
             None
         }
     }
+
+    fn trigger_export(&mut self) -> Option<Message> {
+        let text = match self.export_text {
+            Some(ref t) => t.clone(),
+            None => {
+                self.msg = Some("Nothing to export".to_string());
+                return self.follow_up();
+            }
+        };
+        let status = self.active_day.as_ref().map(ActiveDay::export_status);
+        if status.map_or(false, ExportStatus::is_exported) && !self.reexport_confirmed {
+            self.reexport_confirmed = true;
+            self.msg = Some("Day was already exported, copy again to export anyway".to_string());
+            return None;
+        }
+        self.reexport_confirmed = false;
+        if !self.cap_acknowledged() {
+            return None;
+        }
+
+        let entries = text.lines().count();
+        self.msg = Some(format!("exported {} entries", entries));
+        match self.active_day {
+            Some(ref mut day) => {
+                day.mark_exported(self.settings.load().timeline.now(), text.to_string());
+                Some(Message::MarkExported(day.get_day(), text))
+            }
+            None => Some(Message::WriteClipboard(text)),
+        }
+    }
+}
+
+pub fn export_normalizer(
+    settings: &Settings,
+    combine_bookings: bool,
+    add_break: bool,
+) -> Normalizer {
+    Normalizer {
+        resolution: NonZeroU32::new(settings.resolution.num_minutes() as u32)
+            .unwrap_or_else(|| NonZeroU32::new(1).unwrap()),
+        breaks_config: settings.breaks.clone(),
+        combine_bookings,
+        add_break,
+        home_zone: settings.home_zone,
+        holidays: settings.holidays.clone(),
+    }
+}
+
+/// Error listing the projects that are not configured as valid
+fn unknown_projects(settings: &Settings, normalized: &NormalizedDay) -> Option<String> {
+    let mut unknown: Vec<&str> = normalized
+        .entries
+        .iter()
+        .map(|w| w.task.ident.as_str())
+        .filter(|ident| !settings.is_valid_project(ident))
+        .collect();
+    unknown.sort_unstable();
+    unknown.dedup();
+    if unknown.is_empty() {
+        None
+    } else {
+        Some(format!("Unknown projects: {}", unknown.join(", ")))
+    }
+}
+
+/// Export text of the day with the default options, if the day can be exported without errors
+pub fn export_text(settings: &Settings, day: &ActiveDay) -> Result<String, String> {
+    let normalized = export_normalizer(settings, true, true).create_normalized(day)?;
+    match unknown_projects(settings, &normalized) {
+        Some(unknown) => Err(unknown),
+        None => format_export(settings, &normalized),
+    }
+}
+
+/// The day in the format of the export template, the default format without template
+fn format_export(settings: &Settings, normalized: &NormalizedDay) -> Result<String, String> {
+    match &settings.export_template {
+        Some(template) => {
+            TemplateExporter::export(template, normalized, &settings.absence_categories)
+        }
+        None => TimeCockpitExporter::export(
+            normalized,
+            &settings.absence_categories,
+            settings.extended_export,
+        ),
+    }
+}
+
+/// The normalized day as JSON, if the day can be exported without errors
+pub fn export_json(settings: &Settings, day: &ActiveDay) -> Result<serde_json::Value, String> {
+    let normalized = export_normalizer(settings, true, true).create_normalized(day)?;
+    match unknown_projects(settings, &normalized) {
+        Some(unknown) => Err(unknown),
+        None => Ok(JsonExporter::export(
+            &normalized,
+            &settings.absence_categories,
+        )),
+    }
+}
+
+/// Export texts of the days, each headed by its date
+pub fn report_text(settings: &Settings, days: &[ActiveDay]) -> String {
+    let mut result = String::new();
+    for day in days {
+        result.push_str(&format!("{}\n", day.get_day()));
+        match export_text(settings, day) {
+            Ok(text) => result.push_str(&text),
+            Err(e) => result.push_str(&format!("Not exported: {}\n", e)),
+        }
+        result.push('\n');
+    }
+    result
+}
+
+/// Writes the export to the file, missing directories are created
+pub fn write_export_file(path: &Path, text: &str) -> Result<(), String> {
+    match path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        Some(dir) => std::fs::create_dir_all(dir),
+        None => Ok(()),
+    }
+    .and_then(|_| std::fs::write(path, text.as_bytes()))
+    .map_err(|e| format!("Cannot write {}: {}", path.display(), e))
 }
 
 impl MainView for DayExportUi {
     fn view(&mut self) -> QElement {
+        self.focus.clear_targets();
         let title_text = self
             .active_day
             .as_ref()
-            .map(|a| a.get_day().to_string())
+            .map(|a| {
+                format!(
+                    "{} ({}, {})",
+                    a.get_day(),
+                    a.location().name(),
+                    a.export_status().label()
+                )
+            })
             .unwrap_or_else(|| "No active day".to_string());
 
         let top_row = Row::with_children(vec![
@@ -109,17 +404,50 @@ impl MainView for DayExportUi {
             h_space(style::DSPACE),
             text(self.msg.as_deref().unwrap_or("Export with <ctrl>+C")),
         ]);
+        let template_error = self
+            .template_error
+            .as_ref()
+            .map(|e| Text::new(e.clone()).color(style::ERROR_COLOR).into());
 
-        let mut scroll = Scrollable::new(&mut self.scroll_state).width(Length::Fill);
-        for e in self.actions.iter().map(super::current_day::action_row) {
-            scroll = scroll.push(e);
+        let len = if self.lines.is_empty() {
+            self.actions.len()
+        } else {
+            self.lines.len()
+        };
+        let window = self.preview.window(len);
+        let mut rows = Vec::with_capacity(window.len());
+        if self.lines.is_empty() {
+            let actions = &self.actions[window.clone()];
+            rows.extend(actions.iter().map(super::current_day::action_row));
+        }
+        for line in self.lines.iter_mut().take(window.end).skip(window.start) {
+            rows.push(
+                Row::with_children(vec![
+                    TextInput::new(&mut line.input, "", &line.text, |_| {
+                        Message::Export(DayExportMessage::LineEdited)
+                    })
+                    .into(),
+                    h_space(style::SPACE),
+                    self.focus
+                        .inline_button(
+                            &mut line.copy_button,
+                            "Copy line",
+                            Message::WriteClipboard(line.text.clone()),
+                        )
+                        .into(),
+                ])
+                .into(),
+            );
         }
+        let scroll = self.preview.view(len, rows, |offset| {
+            Message::Export(DayExportMessage::Scrolled(offset))
+        });
 
         let scroll = Container::new(scroll)
             .style(style::ContentStyle)
             .width(Length::Fill)
             .height(Length::Fill);
-        let buttons = Column::with_children(vec![
+        let mut buttons = Column::with_children(vec![
             Button::new(&mut self.clip_button, text("Copy"))
                 .on_press(Message::Export(DayExportMessage::TriggerExport))
                 .into(),
@@ -128,14 +456,115 @@ impl MainView for DayExportUi {
                 Message::Export(DayExportMessage::ChangeNormalize(b))
             })
             .into(),
+            v_space(style::SPACE),
+            Checkbox::new(self.append_rounding, "Append rounding", |b| {
+                Message::Export(DayExportMessage::AppendRounding(b))
+            })
+            .into(),
+            v_space(style::DSPACE),
+            TextInput::new(&mut self.file_input, "file", &self.file_path, |p| {
+                Message::Export(DayExportMessage::PathChanged(p))
+            })
+            .into(),
+            v_space(style::SPACE),
+            self.focus
+                .inline_button(
+                    &mut self.save_button,
+                    "Save to file",
+                    Message::Export(DayExportMessage::SaveToFile),
+                )
+                .into(),
+            v_space(style::SPACE),
+            self.focus
+                .inline_button(
+                    &mut self.dialog_button,
+                    "Save as...",
+                    Message::Export(DayExportMessage::ChooseFile),
+                )
+                .into(),
         ])
         .width(Length::Units(200));
 
+        let mail_day = self
+            .active_day
+            .as_ref()
+            .map(ActiveDay::get_day)
+            .filter(|_| self.settings.load().email.is_some());
+        if let Some(day) = mail_day {
+            buttons = buttons
+                .push(v_space(style::DSPACE))
+                .push(self.focus.inline_button(
+                    &mut self.mail_day_button,
+                    "Mail day report",
+                    Message::MailReport(day, ReportPeriod::Day),
+                ))
+                .push(v_space(style::SPACE))
+                .push(self.focus.inline_button(
+                    &mut self.mail_week_button,
+                    "Mail week report",
+                    Message::MailReport(day, ReportPeriod::Week),
+                ));
+        }
+        let webhook_day = self
+            .active_day
+            .as_ref()
+            .map(ActiveDay::get_day)
+            .filter(|_| self.settings.load().webhook.is_some());
+        if let Some(day) = webhook_day {
+            buttons = buttons
+                .push(v_space(style::DSPACE))
+                .push(self.focus.inline_button(
+                    &mut self.webhook_button,
+                    "Post to webhook",
+                    Message::PostWebhook(day),
+                ));
+        }
+
         let body = Row::with_children(vec![scroll.into(), h_space(style::SPACE), buttons.into()]);
 
-        Column::with_children(vec![top_row.into(), v_space(style::SPACE), body.into()])
-            .height(Length::Fill)
-            .into()
+        let mut content = vec![top_row.into(), v_space(style::SPACE)];
+        if let Some(template_error) = template_error {
+            content.push(template_error);
+            content.push(v_space(style::SPACE));
+        }
+        if let Some(cap) = &self.cap_warning {
+            content.push(Text::new(cap.clone()).color(style::ERROR_COLOR).into());
+            content.push(v_space(style::SPACE));
+        }
+        if let Some((at, changes)) = &self.changes {
+            content.push(text(format!(
+                "Changes to the export on {}:",
+                at.format("%Y-%m-%d %H:%M")
+            )));
+            if changes.is_empty() {
+                content.push(text("none of the exported entries"));
+            }
+            content.extend(changes.iter().map(|c| text(c.to_string())));
+            content.push(v_space(style::SPACE));
+        }
+        content.push(body.into());
+        if let Some(n) = self.normalized.as_ref().filter(|_| !self.append_rounding) {
+            content.push(v_space(style::SPACE));
+            content.extend(rounding_report(n).lines().map(text));
+        }
+        let files = self.active_day.iter().flat_map(|d| d.export_files());
+        for (index, file) in files.rev().take(MAX_SHOWN_FILES).enumerate() {
+            if index == 0 {
+                content.push(v_space(style::SPACE));
+                content.push(text("Written files:"));
+            }
+            content.push(text(format!(
+                "{}  {}",
+                file.at.format("%Y-%m-%d %H:%M"),
+                file.path.display()
+            )));
+        }
+        if let Some(delivery) = self.active_day.as_ref().and_then(|d| d.webhook_delivery()) {
+            content.push(v_space(style::SPACE));
+            content.push(text(delivery.label()));
+        }
+
+        Column::with_children(content).height(Length::Fill).into()
     }
 
     fn update(&mut self, msg: Message) -> Option<Message> {
@@ -146,17 +575,48 @@ impl MainView for DayExportUi {
 
                 self.follow_up()
             }
-            Message::Export(DayExportMessage::TriggerExport) => match self.export_text {
-                Some(ref t) => {
-                    let entries = t.lines().count();
-                    self.msg = Some(format!("exported {} entries", entries));
-                    Some(Message::WriteClipboard(t.clone()))
-                }
-                None => {
-                    self.msg = Some("Nothing to export".to_string());
-                    self.follow_up()
+            Message::Export(DayExportMessage::AppendRounding(append)) => {
+                self.append_rounding = append;
+                self.normalize_day();
+
+                self.follow_up()
+            }
+            Message::Export(DayExportMessage::TriggerExport) => {
+                if self.input_focused() {
+                    None
+                } else {
+                    self.trigger_export()
                 }
-            },
+            }
+            Message::Export(DayExportMessage::LineEdited) => None,
+            Message::Export(DayExportMessage::PathChanged(path)) => {
+                self.focus.unfocus();
+                self.file_path = path;
+                None
+            }
+            Message::Export(DayExportMessage::SaveToFile) => self.save_to_file(),
+            Message::Export(DayExportMessage::ChooseFile) => Some(Message::ChooseExportFile(
+                PathBuf::from(self.file_path.trim()),
+            )),
+            Message::Export(DayExportMessage::FileChosen(Some(path))) => {
+                self.file_path = path.to_string_lossy().to_string();
+                self.save_to_file()
+            }
+            Message::Export(DayExportMessage::FileChosen(None)) => None,
+            Message::Export(DayExportMessage::Scrolled(offset)) => {
+                self.preview.scrolled(offset);
+                None
+            }
+            Message::SubmitCurrent(_) if self.file_input.is_focused() => self.save_to_file(),
+            Message::SubmitCurrent(_) if self.focus.has_focus() => self.focus.press(),
+            Message::Next => {
+                self.focus.next_with_input(&mut self.file_input);
+                None
+            }
+            Message::Previous => {
+                self.focus.previous_with_input(&mut self.file_input);
+                None
+            }
             _ => self.follow_up(),
         }
     }
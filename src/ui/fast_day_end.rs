@@ -1,4 +1,3 @@
-use iced_wgpu::TextInput;
 use iced_winit::widget::{text_input, Column, Row, Text};
 
 use crate::conf::SettingsRef;
@@ -8,7 +7,9 @@ use crate::parsing::time::Time;
 use crate::parsing::time_limit::{check_any_limit_overlaps, InvalidTime, TimeRange, TimeResult};
 use crate::ui::single_edit_ui::SingleEditUi;
 use crate::ui::top_bar::TopBar;
-use crate::ui::util::v_space;
+use crate::ui::util::{step_input_time, v_space};
+use crate::ui::wheel_steps::WheelSteps;
+use crate::ui::widget::TextInput;
 use crate::ui::{day_info_message, style, unbooked_time, MainView, Message, QElement};
 
 #[derive(Clone, Debug)]
@@ -62,14 +63,14 @@ impl SingleEditUi<DayEnd> for FastDayEnd {
         self.bad_input = false;
 
         let timeline = &self.settings.load().timeline;
-        let (mut result, rest) = Time::parse_with_offset(timeline, &self.text);
-        if !rest.trim_start().is_empty() {
-            result = ParseResult::Invalid(());
-        }
+        let (result, rest) = Time::parse_with_offset_checked(timeline, &self.text);
+        let result = if rest.trim_start().is_empty() {
+            result.map_invalid(InvalidTime::Parse)
+        } else {
+            ParseResult::Invalid(InvalidTime::Bad)
+        };
 
-        let result = result
-            .map_invalid(|_| InvalidTime::Bad)
-            .and_then(|r| check_any_limit_overlaps(r, &self.limits));
+        let result = result.and_then(|r| check_any_limit_overlaps(r, &self.limits));
 
         self.builder.ts = result;
     }
@@ -88,21 +89,31 @@ impl SingleEditUi<DayEnd> for FastDayEnd {
 }
 
 impl MainView for FastDayEnd {
+    fn draft(&self) -> Option<&str> {
+        Some(self.text.as_str()).filter(|_| self.original_entry.is_none())
+    }
+
     fn view(&mut self) -> QElement {
-        let time_str = self
-            .value
-            .as_ref()
-            .map(|e| e.ts.to_string())
-            .unwrap_or_default();
+        let time_str = match self.builder.ts.as_ref() {
+            ParseResult::Invalid(e) => e.to_string(),
+            _ => self
+                .value
+                .as_ref()
+                .map(|e| e.ts.to_string())
+                .unwrap_or_default(),
+        };
 
         Column::with_children(vec![
             self.top_bar.view(),
             v_space(style::SPACE),
-            TextInput::new(
-                &mut self.text_state,
-                "now",
-                &self.text,
-                on_input_change_message,
+            WheelSteps::new(
+                TextInput::new(
+                    &mut self.text_state,
+                    "now",
+                    &self.text,
+                    on_input_change_message,
+                ),
+                Message::ScrollTime,
             )
             .into(),
             v_space(style::SPACE),
@@ -113,10 +124,23 @@ impl MainView for FastDayEnd {
 
     fn update(&mut self, msg: Message) -> Option<Message> {
         match msg {
+            Message::ScrollTime(steps) => step_input_time(&self.settings.load(), &self.text, steps)
+                .map(|text| Message::Fde(FastDayEndMessage::TextChanged(text))),
             Message::Fde(FastDayEndMessage::TextChanged(new_value)) => {
                 self.update_input(new_value);
                 None
             }
+            Message::Tick if self.text.is_empty() => {
+                let now = self.settings.load().timeline.time_now();
+                self.builder.ts = ParseResult::Valid(now);
+                self.value = Some(DayEnd { ts: now });
+                None
+            }
+            // relative times follow the clock
+            Message::Tick => {
+                self.update_input(self.text.clone());
+                None
+            }
             Message::SubmitCurrent(stay_active) => {
                 Self::on_submit_message(self.try_build(), &mut self.original_entry, stay_active)
             }
@@ -143,6 +167,8 @@ impl DayEndBuilder {
 
 #[cfg(test)]
 mod test {
+    use std::sync::Arc;
+
     use crate::data::test_support::time;
     use crate::data::{Action, ActiveDay, DayEnd, Location};
 
@@ -169,6 +195,31 @@ mod test {
         ])
     }
 
+    #[test]
+    fn follows_the_clock() {
+        let timeline = Arc::new(StaticTimeline::parse("2022-01-31 12:00"));
+        let settings = Settings {
+            timeline: timeline.clone(),
+            ..Settings::default()
+        }
+        .into_settings_ref();
+        let mut fde = FastDayEnd::for_work_day(
+            settings,
+            Some(&ActiveDay::new(timeline.today(), Location::Office, None)),
+        );
+
+        timeline.advance();
+        fde.update(Message::Tick);
+        assert_eq!(fde.try_build(), Some(DayEnd { ts: time("12:01") }));
+
+        fde.update(Message::Fde(FastDayEndMessage::TextChanged(
+            "-1".to_string(),
+        )));
+        timeline.advance();
+        fde.update(Message::Tick);
+        assert_eq!(fde.try_build(), Some(DayEnd { ts: time("12:01") }));
+    }
+
     fn p(i: &[(&str, Option<Time>)]) {
         let timeline = StaticTimeline::parse("2022-01-31 12:00");
         let today = timeline.today();
@@ -1,13 +1,15 @@
-use crate::conf::{SettingsRef};
+use crate::conf::SettingsRef;
 use crate::data::{ActiveDay, DayStart, Location};
 use crate::parsing::parse_result::ParseResult;
 use crate::parsing::time::Time;
 use crate::parsing::time_limit::{check_any_limit_overlaps, InvalidTime, TimeRange, TimeResult};
 use crate::ui::single_edit_ui::SingleEditUi;
 use crate::ui::top_bar::TopBar;
+use crate::ui::util::step_input_time;
+use crate::ui::wheel_steps::WheelSteps;
+use crate::ui::widget::TextInput;
 use crate::ui::{day_info_message, style, unbooked_time, MainView, Message, QElement};
 use crate::util::Timeline;
-use iced_wgpu::TextInput;
 use iced_winit::widget::{text_input, Column, Row, Space, Text};
 
 #[derive(Clone, Debug)]
@@ -29,11 +31,12 @@ pub struct FastDayStart {
 impl FastDayStart {
     pub fn for_work_day(settings: SettingsRef, work_day: Option<&ActiveDay>) -> Box<Self> {
         let timeline = settings.load().timeline.clone();
+        let locations = settings.load().locations.clone();
         let limits = unbooked_time(work_day);
         Box::new(FastDayStart {
             top_bar: TopBar {
                 title: "Start day",
-                help_text: "[h|o] [+|-]hours or minute",
+                help_text: "[h|o|location] [+|-]hours or minute, up/down changes location",
                 info: day_info_message(work_day),
                 settings,
             },
@@ -47,6 +50,7 @@ impl FastDayStart {
             builder: DayStartBuilder {
                 ts: TimeResult::Valid(timeline.time_now()),
                 location: ParseResult::Valid(Location::Office),
+                locations,
             },
             timeline,
             orig: None,
@@ -62,12 +66,7 @@ impl SingleEditUi<DayStart> for FastDayStart {
     }
 
     fn as_text(&self, e: &DayStart) -> String {
-        let loc = match e.location {
-            Location::Office => "o",
-            Location::Home => "h",
-            Location::Other(ref l) => l.0.as_str(),
-        };
-        format!("{} {}", loc, e.ts)
+        format!("{} {}", e.location.input_name(), e.ts)
     }
 
     fn set_orig(&mut self, orig: DayStart) {
@@ -79,17 +78,36 @@ impl SingleEditUi<DayStart> for FastDayStart {
     }
 }
 
+impl FastDayStart {
+    /// Replaces the location in the input by the next or previous configured location
+    fn cycle_location(&mut self, step: isize) {
+        let locations = &self.builder.locations;
+        let choices = Location::choices(locations);
+        let (current, rest) = Location::parse_prefix(&self.text, locations);
+        let current = current.get().unwrap_or_default();
+        let index = choices.iter().position(|l| *l == current).unwrap_or(0) as isize;
+        let next = &choices[(index + step).rem_euclid(choices.len() as isize) as usize];
+        let text = format!("{} {}", next.input_name(), rest);
+        self.update_input(text.trim_end().to_string());
+    }
+}
+
 impl MainView for FastDayStart {
+    fn draft(&self) -> Option<&str> {
+        Some(self.text.as_str()).filter(|_| self.orig.is_none())
+    }
+
     fn view(&mut self) -> QElement {
         let loc_str = match self.builder.location.as_ref() {
             ParseResult::Valid(t) => t.to_string(),
-            ParseResult::Invalid(_) | ParseResult::Incomplete => "Invalid location".to_string(),
+            ParseResult::Invalid(_) => "Invalid location".to_string(),
+            ParseResult::Incomplete => "Ambiguous location".to_string(),
             ParseResult::None => Location::Office.to_string(),
         };
 
         let time_str = match self.builder.ts.as_ref() {
             ParseResult::Valid(t) => t.to_string(),
-            ParseResult::Invalid(e) => format!("{:?}", e),
+            ParseResult::Invalid(e) => e.to_string(),
             ParseResult::Incomplete => "invalid".to_string(),
             ParseResult::None => "now".to_string(),
         };
@@ -97,6 +115,7 @@ impl MainView for FastDayStart {
         let input_widget = TextInput::new(&mut self.text_state, "now", &self.text, move |input| {
             on_input_change(input)
         });
+        let input_widget = WheelSteps::new(input_widget, Message::ScrollTime);
 
         let status_row = Row::with_children(vec![
             Text::new(loc_str).into(),
@@ -116,10 +135,31 @@ impl MainView for FastDayStart {
 
     fn update(&mut self, msg: Message) -> Option<Message> {
         match msg {
+            Message::ScrollTime(steps) => {
+                step_input_time(&self.top_bar.settings.load(), &self.text, steps)
+                    .map(|text| Message::Fds(FastDayStartMessage::TextChanged(text)))
+            }
             Message::Fds(FastDayStartMessage::TextChanged(new_value)) => {
                 self.update_input(new_value);
                 None
             }
+            Message::Tick if self.text.is_empty() => {
+                self.builder.ts = TimeResult::Valid(self.timeline.time_now());
+                None
+            }
+            // relative times follow the clock
+            Message::Tick => {
+                self.update_input(self.text.clone());
+                None
+            }
+            Message::Up => {
+                self.cycle_location(-1);
+                None
+            }
+            Message::Down => {
+                self.cycle_location(1);
+                None
+            }
             Message::SubmitCurrent(stay_active) => {
                 Self::on_submit_message(self.try_build(), &mut self.orig, stay_active)
             }
@@ -133,9 +173,18 @@ impl MainView for FastDayStart {
 pub struct DayStartBuilder {
     location: ParseResult<Location, ()>,
     ts: TimeResult,
+    /// Configured custom locations
+    locations: Vec<String>,
 }
 
 impl DayStartBuilder {
+    pub fn new(locations: Vec<String>) -> Self {
+        Self {
+            locations,
+            ..Self::default()
+        }
+    }
+
     pub fn try_build(&self, timeline: &Timeline) -> Option<DayStart> {
         let location = self.location.clone().or_default().get();
 
@@ -149,30 +198,18 @@ impl DayStartBuilder {
     }
 
     pub fn parse_value(&mut self, timeline: &Timeline, limits: &[TimeRange], text: &str) {
-        fn parse_location(text: &str) -> (ParseResult<Location, ()>, &str) {
-            let text = text.trim();
-            let (location, text) = if text.starts_with(&['h', 'H'][..]) {
-                (ParseResult::Valid(Location::Home), (&text[1..]).trim())
-            } else if text.starts_with(&['o', 'O'][..]) {
-                (ParseResult::Valid(Location::Office), (&text[1..]).trim())
-            } else {
-                (ParseResult::None, text)
-            };
-            (location, text)
-        }
-
-        let (location, text) = parse_location(text);
+        let (location, text) = Location::parse_prefix(text.trim(), &self.locations);
 
         self.location = location;
 
-        let (mut result, rest) = Time::parse_with_offset(timeline, text);
-        if !rest.trim_start().is_empty() {
-            result = ParseResult::Invalid(())
+        let (result, rest) = Time::parse_with_offset_checked(timeline, text);
+        let result = if rest.trim_start().is_empty() {
+            result.map_invalid(InvalidTime::Parse)
+        } else {
+            ParseResult::Invalid(InvalidTime::Bad)
         };
 
-        let result = result
-            .map_invalid(|_| InvalidTime::Bad)
-            .and_then(|r| check_any_limit_overlaps(r, limits));
+        let result = result.and_then(|r| check_any_limit_overlaps(r, limits));
 
         self.ts = result;
     }
@@ -221,6 +258,40 @@ mod test {
         ])
     }
 
+    #[test]
+    fn custom_locations() {
+        let timeline = StaticTimeline::parse("2021-12-29 12:00");
+        let settings = into_settings_ref(Settings {
+            timeline: Arc::new(timeline),
+            locations: vec!["Client A".to_string(), "Coworking".to_string()],
+            ..Settings::default()
+        });
+        let mut fds = FastDayStart::for_work_day(settings, None);
+        let client_a = Location::other("Client A");
+
+        assert_eq!(
+            fds.convert_input("client a 8"),
+            Some(DayStart {
+                location: client_a.clone(),
+                ts: time("8"),
+            })
+        );
+        assert_eq!(
+            fds.convert_input("cl 8").map(|d| d.location),
+            Some(client_a)
+        );
+        assert_eq!(fds.convert_input("c 8"), None);
+
+        fds.update_input("h 8".to_string());
+        fds.update(Message::Down);
+        assert_eq!(fds.text, "Client A 8");
+        fds.update(Message::Down);
+        fds.update(Message::Down);
+        assert_eq!(fds.text, "o 8");
+        fds.update(Message::Up);
+        assert_eq!(fds.text, "Coworking 8");
+    }
+
     fn p(i: &[(&str, Option<DayStart>)]) {
         let timeline = StaticTimeline::parse("2021-12-29 12:00");
         let today = timeline.today();
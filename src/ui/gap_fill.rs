@@ -0,0 +1,271 @@
+use iced_native::widget::{text_input, Column, Row};
+
+use crate::conf::SettingsRef;
+use crate::data::{implicit_gaps, Action, ActiveDay, ImplicitGap, JiraIssue, RecentIssuesRef};
+use crate::parsing::parse_result::ParseResult;
+use crate::parsing::{insert_issue, IssueParsed, IssueParser, IssueParserWithRecent};
+use crate::ui::day_end_wizard::{current_end, with_end};
+use crate::ui::recent_issues_view::filter_message;
+use crate::ui::stay_active::StayActive;
+use crate::ui::top_bar::TopBar;
+use crate::ui::util::{h_space, v_space};
+use crate::ui::widget::TextInput;
+use crate::ui::{
+    cycle_shortcut, day_info_message, shortcut_preview, style, text, MainView, Message, QElement,
+};
+
+#[derive(Clone, Debug)]
+pub enum GapFillMessage {
+    TextChanged(String),
+}
+
+/// Books the gaps of the day as real work, either with the started issue the export would
+/// fill them with, or all of them with a chosen issue
+#[derive(Debug)]
+pub struct GapFill {
+    top_bar: TopBar,
+    input_state: text_input::State,
+    input: String,
+    settings: SettingsRef,
+    recent_issues: RecentIssuesRef,
+    recent_filter: String,
+    gaps: Result<Vec<ImplicitGap>, String>,
+    issue: ParseResult<JiraIssue, ()>,
+    comment: Option<String>,
+}
+
+impl GapFill {
+    pub fn for_active_day(
+        settings: SettingsRef,
+        recent_issues: RecentIssuesRef,
+        active_day: Option<&ActiveDay>,
+    ) -> Box<GapFill> {
+        let gaps = match active_day {
+            Some(day) => day_gaps(&settings, day),
+            None => Ok(Vec::new()),
+        };
+        Box::new(Self {
+            top_bar: TopBar {
+                title: "Fill with:",
+                help_text: "[issue] <comment>",
+                info: day_info_message(active_day),
+                settings: settings.clone(),
+            },
+            input_state: text_input::State::focused(),
+            input: String::new(),
+            settings,
+            recent_issues,
+            recent_filter: String::new(),
+            gaps,
+            issue: ParseResult::None,
+            comment: None,
+        })
+    }
+
+    fn update_input(&mut self, input: String) -> Option<Message> {
+        self.input = input;
+        let settings = self.settings.load();
+        let recent = self.recent_issues.borrow();
+        let parser = IssueParserWithRecent::new(&settings.issue_parser, &recent);
+        let IssueParsed {
+            r: issue,
+            input: matching,
+            rest,
+        } = parser.parse_task(self.input.trim_start());
+
+        let rest = rest.trim();
+        self.comment = if rest.is_empty() {
+            issue
+                .get_ref()
+                .and_then(|e| e.default_action.clone().or_else(|| e.description.clone()))
+        } else {
+            Some(rest.to_string())
+        };
+        self.issue = issue;
+        filter_message(&mut self.recent_filter, matching)
+    }
+
+    /// The work booked on submit
+    fn fill(&self) -> Vec<Action> {
+        let gaps = match &self.gaps {
+            Ok(gaps) => gaps,
+            Err(_) => return Vec::new(),
+        };
+        match self.issue.get_ref() {
+            Some(issue) => gaps
+                .iter()
+                .map(|g| g.work(issue.clone(), self.comment.clone().unwrap_or_default()))
+                .map(Action::Work)
+                .collect(),
+            None => gaps
+                .iter()
+                .filter_map(|g| {
+                    let (issue, comment) = g.filled_by.clone()?;
+                    Some(Action::Work(g.work(issue, comment)))
+                })
+                .collect(),
+        }
+    }
+
+    fn on_submit(&self, stay_active: StayActive) -> Option<Message> {
+        if !self.input.trim().is_empty() && self.issue.get_ref().is_none() {
+            return None;
+        }
+        let work = self.fill();
+        if work.is_empty() {
+            None
+        } else {
+            Some(Message::StoreActions(stay_active, work))
+        }
+    }
+
+    fn submit_info(&self) -> String {
+        let count = self.fill().len();
+        match (self.issue.get_ref(), count) {
+            (_, 0) => "Nothing to fill".to_string(),
+            (Some(issue), _) => format!("Enter books {} gaps with {}", count, issue.ident),
+            (None, _) => format!("Enter books {} gaps with the started issues", count),
+        }
+    }
+}
+
+/// Gaps of the day. A running day ends now
+fn day_gaps(settings: &SettingsRef, day: &ActiveDay) -> Result<Vec<ImplicitGap>, String> {
+    let timeline = settings.load().timeline.clone();
+    if current_end(day).is_none() && day.get_day() == timeline.today() {
+        implicit_gaps(&with_end(day, timeline.time_now()))
+    } else {
+        implicit_gaps(day)
+    }
+}
+
+impl MainView for GapFill {
+    fn draft(&self) -> Option<&str> {
+        Some(self.input.as_str())
+    }
+
+    fn view(&mut self) -> QElement {
+        let input = TextInput::new(&mut self.input_state, "", &self.input, |i| {
+            Message::Gf(GapFillMessage::TextChanged(i))
+        });
+        let issue = match &self.issue {
+            ParseResult::Valid(issue) => issue.ident.as_str(),
+            ParseResult::None | ParseResult::Incomplete => "<started issue>",
+            _ => "<invalid>",
+        };
+
+        let mut rows = vec![self.top_bar.view(), v_space(style::SPACE), input.into()];
+        if let Some(preview) = shortcut_preview(&self.settings.load(), &self.input) {
+            rows.push(text(preview));
+        }
+        rows.push(v_space(style::SPACE));
+        rows.push(
+            Row::with_children(vec![
+                text("Issue:"),
+                h_space(style::SPACE),
+                text(issue),
+                h_space(style::DSPACE),
+                text("Comment:"),
+                h_space(style::SPACE),
+                text(self.comment.as_deref().unwrap_or("<none>")),
+            ])
+            .into(),
+        );
+        rows.push(v_space(style::SPACE));
+        match &self.gaps {
+            Ok(gaps) if gaps.is_empty() => rows.push(text("The day has no gaps")),
+            Ok(gaps) => {
+                for gap in gaps {
+                    rows.push(text(match &gap.filled_by {
+                        Some((issue, comment)) => format!(
+                            "{} - {}  {} {} (started issue)",
+                            gap.range.min(),
+                            gap.range.max(),
+                            issue.ident,
+                            comment
+                        ),
+                        None => format!("{} - {}  unbooked", gap.range.min(), gap.range.max()),
+                    }));
+                }
+                rows.push(v_space(style::SPACE));
+                rows.push(text(self.submit_info()));
+            }
+            Err(e) => rows.push(text(format!("Cannot list the gaps: {}", e))),
+        }
+        Column::with_children(rows).into()
+    }
+
+    fn update(&mut self, msg: Message) -> Option<Message> {
+        match msg {
+            Message::InsertIssue(ident) => {
+                self.input_state.move_cursor_to_end();
+                let input = insert_issue(&self.input, &ident);
+                Some(Message::Gf(GapFillMessage::TextChanged(input)))
+            }
+            Message::CycleShortcut => cycle_shortcut(&self.settings.load(), &self.input, |input| {
+                Message::Gf(GapFillMessage::TextChanged(input))
+            }),
+            Message::Gf(GapFillMessage::TextChanged(input)) => self.update_input(input),
+            Message::SubmitCurrent(stay_active) => self.on_submit(stay_active),
+            Message::StoreSuccess(stay_active) => stay_active.on_main_view_store(),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::data::test_support::*;
+    use crate::data::{ActiveDayBuilder, Location, RecentIssuesRef};
+    use crate::ui::gap_fill::{GapFill, GapFillMessage};
+    use crate::ui::stay_active::StayActive;
+    use crate::ui::{MainView, Message};
+    use crate::util::{StaticTimeline, Timeline, TimelineProvider};
+    use crate::Settings;
+
+    #[test]
+    fn books_the_gaps_of_a_running_day() {
+        let timeline: Timeline = StaticTimeline::parse("2022-01-20 12:00").into();
+        let settings = Settings {
+            timeline: timeline.clone(),
+            ..Settings::default()
+        }
+        .into_settings_ref();
+        let day = ActiveDayBuilder {
+            day: timeline.today(),
+            main_location: Location::Office,
+            active_issue: None,
+            actions: vec![
+                day_start("o8"),
+                issue_start("9", "A-1", "", "dev"),
+                work("10", "11", "B-1", "meeting"),
+                issue_end("11", "A-1"),
+            ],
+        }
+        .build();
+        let recent = RecentIssuesRef::empty(settings.clone());
+
+        let mut ui = GapFill::for_active_day(settings, recent, Some(&day));
+        match ui.update(Message::SubmitCurrent(StayActive::Yes)) {
+            Some(Message::StoreActions(_, actions)) => {
+                assert_eq!(actions, vec![work("9", "10", "A-1", "dev")])
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+
+        ui.update(Message::Gf(GapFillMessage::TextChanged(
+            "C-3 admin".to_string(),
+        )));
+        match ui.update(Message::SubmitCurrent(StayActive::Yes)) {
+            Some(Message::StoreActions(_, actions)) => assert_eq!(
+                actions,
+                vec![
+                    work("8", "9", "C-3", "admin"),
+                    work("9", "10", "C-3", "admin"),
+                    work("11", "12", "C-3", "admin"),
+                ]
+            ),
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+}
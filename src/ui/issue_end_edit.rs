@@ -1,14 +1,15 @@
 use iced_native::widget::{text_input, Column, Row};
-use iced_wgpu::TextInput;
 
 use crate::conf::SettingsRef;
 use crate::data::{ActiveDay, JiraIssue, WorkEnd};
 use crate::parsing::parse_result::ParseResult;
-use crate::parsing::time::Time;
+use crate::parsing::time::{Time, TimeError};
 use crate::parsing::{IssueParsed, IssueParser};
 use crate::ui::single_edit_ui::SingleEditUi;
 use crate::ui::top_bar::TopBar;
-use crate::ui::util::{h_space, v_space};
+use crate::ui::util::{h_space, step_input_time, v_space};
+use crate::ui::wheel_steps::WheelSteps;
+use crate::ui::widget::TextInput;
 use crate::ui::{day_info_message, style, text, time_info, MainView, Message, QElement};
 
 #[derive(Clone, Debug)]
@@ -21,7 +22,7 @@ pub struct IssueEndEdit {
     top_bar: TopBar,
     input_state: text_input::State,
     input: String,
-    time: ParseResult<Time, ()>,
+    time: ParseResult<Time, TimeError>,
     issue: ParseResult<JiraIssue, ()>,
     settings: SettingsRef,
     default_issue: Option<JiraIssue>,
@@ -58,7 +59,7 @@ impl SingleEditUi<WorkEnd> for IssueEndEdit {
     fn update_input(&mut self, input: String) {
         self.input = input;
         let guard = self.settings.load();
-        let (time, input) = Time::parse_with_offset(&guard.timeline, &self.input);
+        let (time, input) = Time::parse_with_offset_checked(&guard.timeline, &self.input);
         self.time = time;
         let IssueParsed { r, rest, .. } = guard.issue_parser.parse_task(input.trim_start());
         if rest.is_empty() {
@@ -94,10 +95,15 @@ impl SingleEditUi<WorkEnd> for IssueEndEdit {
 }
 
 impl MainView for IssueEndEdit {
+    fn draft(&self) -> Option<&str> {
+        Some(self.input.as_str()).filter(|_| self.orig.is_none())
+    }
+
     fn view(&mut self) -> QElement {
         let input = TextInput::new(&mut self.input_state, "now", &self.input, |e| {
             Message::Ie(IssueEndMessage::InputChanged(e))
         });
+        let input = WheelSteps::new(input, Message::ScrollTime);
 
         let issue_text: String = if let ParseResult::Valid(i) = &self.issue {
             i.ident.clone()
@@ -109,10 +115,17 @@ impl MainView for IssueEndEdit {
             "<none>".to_string()
         };
 
+        let time = match &self.time {
+            ParseResult::Invalid(e) => text(e.to_string()),
+            t => time_info(
+                self.settings.load().timeline.time_now(),
+                t.clone().map_invalid(|_| ()),
+            ),
+        };
         let info = Row::with_children(vec![
             text("Time:"),
             h_space(style::SPACE),
-            time_info(self.settings.load().timeline.time_now(), self.time.clone()),
+            time,
             h_space(style::DSPACE),
             text("Issue:"),
             h_space(style::SPACE),
@@ -131,10 +144,23 @@ impl MainView for IssueEndEdit {
 
     fn update(&mut self, msg: Message) -> Option<Message> {
         match msg {
+            Message::ScrollTime(steps) => {
+                step_input_time(&self.settings.load(), &self.input, steps)
+                    .map(|input| Message::Ie(IssueEndMessage::InputChanged(input)))
+            }
             Message::Ie(IssueEndMessage::InputChanged(text)) => {
                 self.update_input(text);
                 None
             }
+            Message::Tick if self.input.is_empty() => {
+                self.time = ParseResult::Valid(self.settings.load().timeline.time_now());
+                None
+            }
+            // relative times follow the clock
+            Message::Tick => {
+                self.update_input(self.input.clone());
+                None
+            }
             Message::SubmitCurrent(stay_active) => {
                 Self::on_submit_message(self.try_build(), &mut self.orig, stay_active)
             }
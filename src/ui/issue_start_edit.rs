@@ -1,17 +1,24 @@
 use iced_native::widget::{text_input, Column, Row};
-use iced_wgpu::TextInput;
 
 use crate::conf::SettingsRef;
-use crate::data::{ActiveDay, JiraIssue, RecentIssues, RecentIssuesRef, WorkStart};
+use crate::data::{ActiveDay, JiraIssue, RecentIssues, RecentIssuesRef, WorkEvent, WorkStart};
 use crate::parsing::parse_result::ParseResult;
 use crate::parsing::time::Time;
-use crate::parsing::{parse_issue_clipboard, IssueParsed, IssueParser, IssueParserWithRecent};
+use crate::parsing::{
+    insert_issue, parse_issue_clipboard, IssueParsed, IssueParser, IssueParserWithRecent,
+};
 use crate::ui::clip_read::ClipRead;
+use crate::ui::recent_issues_view::filter_message;
 use crate::ui::single_edit_ui::SingleEditUi;
 use crate::ui::stay_active::StayActive;
 use crate::ui::top_bar::TopBar;
-use crate::ui::util::{h_space, v_space};
-use crate::ui::{day_info_message, style, text, time_info, MainView, Message, QElement};
+use crate::ui::util::{h_space, step_input_time, v_space};
+use crate::ui::wheel_steps::WheelSteps;
+use crate::ui::widget::TextInput;
+use crate::ui::{
+    cycle_shortcut, day_info_message, shortcut_preview, style, text, time_info, MainView, Message,
+    QElement,
+};
 use crate::Settings;
 
 #[derive(Clone, Debug)]
@@ -29,6 +36,7 @@ pub struct IssueStartEdit {
     orig: Option<WorkStart>,
     last_end: Option<Time>,
     recent_issues: RecentIssuesRef,
+    recent_filter: String,
 }
 
 impl IssueStartEdit {
@@ -53,18 +61,10 @@ impl IssueStartEdit {
             orig: None,
             last_end,
             recent_issues,
+            recent_filter: String::new(),
         })
     }
 
-    fn follow_up(&mut self) -> Option<Message> {
-        if matches!(self.builder.clipboard, ClipRead::DoRead) {
-            self.builder.clipboard = ClipRead::Reading;
-            Some(Message::ReadClipboard)
-        } else {
-            None
-        }
-    }
-
     fn on_submit(&mut self, stay_active: StayActive) -> Option<Message> {
         let value = self.builder.try_build();
 
@@ -98,51 +98,55 @@ impl SingleEditUi<WorkStart> for IssueStartEdit {
 }
 
 impl MainView for IssueStartEdit {
+    fn draft(&self) -> Option<&str> {
+        Some(self.input.as_str()).filter(|_| self.orig.is_none())
+    }
+
     fn view(&mut self) -> QElement {
         let input = TextInput::new(&mut self.input_state, "", &self.input, |i| {
             Message::Is(IssueStartMessage::TextChanged(i))
         });
+        let input = WheelSteps::new(input, Message::ScrollTime);
         let settings = self.settings.load();
+        let now = settings.timeline.time_now();
 
-        let info = Row::with_children(vec![
-            text("Time:"),
-            h_space(style::SPACE),
-            time_info(settings.timeline.time_now(), self.builder.time.clone()),
-            h_space(style::DSPACE),
-            text("Issue:"),
-            h_space(style::SPACE),
-            text(
-                self.builder
-                    .issue
-                    .get_ref()
-                    .map(|i| i.ident.as_str())
-                    .unwrap_or("<none>"),
-            ),
-            h_space(style::DSPACE),
-            text("Comment:"),
-            h_space(style::SPACE),
-            text(self.builder.comment.as_deref().unwrap_or("<none>")),
-        ]);
-        Column::with_children(vec![
-            self.top_bar.view(),
-            v_space(style::SPACE),
-            input.into(),
-            v_space(style::SPACE),
-            info.into(),
-        ])
-        .into()
+        let mut content = vec![self.top_bar.view(), v_space(style::SPACE), input.into()];
+        if let Some(preview) = shortcut_preview(&settings, &self.input) {
+            content.push(text(preview));
+        }
+        content.push(v_space(style::SPACE));
+        content.push(self.builder.info_row(now));
+
+        Column::with_children(content).into()
     }
 
     fn update(&mut self, msg: Message) -> Option<Message> {
         match msg {
+            Message::InsertIssue(ident) => {
+                self.input_state.move_cursor_to_end();
+                let input = insert_issue(&self.input, &ident);
+                Some(Message::Is(IssueStartMessage::TextChanged(input)))
+            }
+            Message::ScrollTime(steps) => {
+                step_input_time(&self.settings.load(), &self.input, steps)
+                    .map(|input| Message::Is(IssueStartMessage::TextChanged(input)))
+            }
             Message::Is(IssueStartMessage::TextChanged(input)) => {
                 self.update_input(input);
-                self.follow_up()
+                self.builder.follow_up(&mut self.recent_filter)
             }
             Message::ClipboardValue(value) => {
                 self.builder.apply_clipboard(value);
                 None
             }
+            // relative times follow the clock
+            Message::Tick => {
+                self.update_input(self.input.clone());
+                None
+            }
+            Message::CycleShortcut => cycle_shortcut(&self.settings.load(), &self.input, |input| {
+                Message::Is(IssueStartMessage::TextChanged(input))
+            }),
             Message::SubmitCurrent(stay_active) => self.on_submit(stay_active),
             Message::StoreSuccess(stay_active) => stay_active.on_main_view_store(),
             _ => None,
@@ -151,7 +155,7 @@ impl MainView for IssueStartEdit {
 }
 
 #[derive(Debug, Default)]
-struct IssueStartBuilder {
+pub(in crate::ui) struct IssueStartBuilder {
     time: ParseResult<Time, ()>,
     issue: ParseResult<JiraIssue, ()>,
     clipboard: ClipRead,
@@ -160,7 +164,7 @@ struct IssueStartBuilder {
 }
 
 impl IssueStartBuilder {
-    fn try_build(&self) -> Option<WorkStart> {
+    pub(in crate::ui) fn try_build(&self) -> Option<WorkStart> {
         match (&self.time, &self.issue, &self.comment) {
             (ParseResult::Valid(time), ParseResult::Valid(i), Some(c)) => Some(WorkStart {
                 ts: *time,
@@ -171,7 +175,47 @@ impl IssueStartBuilder {
         }
     }
 
-    fn parse_input(
+    pub(in crate::ui) fn try_build_event(&self) -> Option<WorkEvent> {
+        self.try_build().map(|s| WorkEvent {
+            ts: s.ts,
+            task: s.task,
+            description: s.description,
+        })
+    }
+
+    /// Reads the clipboard if the issue is missing, otherwise filters the recent issues
+    pub(in crate::ui) fn follow_up(&mut self, recent_filter: &mut String) -> Option<Message> {
+        if matches!(self.clipboard, ClipRead::DoRead) {
+            self.clipboard = ClipRead::Reading;
+            Some(Message::ReadClipboard)
+        } else {
+            filter_message(recent_filter, &self.issue_input)
+        }
+    }
+
+    pub(in crate::ui) fn info_row(&self, now: Time) -> QElement {
+        Row::with_children(vec![
+            text("Time:"),
+            h_space(style::SPACE),
+            time_info(now, self.time.clone()),
+            h_space(style::DSPACE),
+            text("Issue:"),
+            h_space(style::SPACE),
+            text(
+                self.issue
+                    .get_ref()
+                    .map(|i| i.ident.as_str())
+                    .unwrap_or("<none>"),
+            ),
+            h_space(style::DSPACE),
+            text("Comment:"),
+            h_space(style::SPACE),
+            text(self.comment.as_deref().unwrap_or("<none>")),
+        ])
+        .into()
+    }
+
+    pub(in crate::ui) fn parse_input(
         &mut self,
         settings: &Settings,
         last_end: Option<Time>,
@@ -201,7 +245,9 @@ impl IssueStartBuilder {
 
         let rest = rest.trim();
         self.comment = if rest.is_empty() {
-            issue.get_ref().and_then(|e| e.default_action.clone())
+            issue
+                .get_ref()
+                .and_then(|e| e.default_action.clone().or_else(|| e.description.clone()))
         } else {
             Some(rest.to_string())
         };
@@ -220,7 +266,7 @@ impl IssueStartBuilder {
         self.issue_input = matching.to_string();
     }
 
-    fn apply_clipboard(&mut self, value: Option<String>) {
+    pub(in crate::ui) fn apply_clipboard(&mut self, value: Option<String>) {
         self.clipboard = ClipRead::None;
         if let ParseResult::None = self.issue {
             let value = value.as_deref().unwrap_or("");
@@ -0,0 +1,635 @@
+use std::sync::Arc;
+
+use iced_core::Length;
+use iced_native::widget::{button, text_input, Column, Container, Row, Text};
+
+use crate::conf::SettingsRef;
+use crate::data::{
+    format_balance, format_cents, format_minutes, Day, Invoice, IssueStats, JiraIssue,
+    LocationReport, RecentIssuesRef, UtilizationReport, WeekSummary, WorkPatterns, ZaBalance,
+    DEEP_WORK_MINUTES,
+};
+use crate::parsing::parse_result::ParseResult;
+use crate::parsing::{insert_issue, IssueParser, IssueParserWithRecent};
+use crate::ui::button_focus::ButtonFocus;
+use crate::ui::recent_issues_view::filter_message;
+use crate::ui::util::{h_space, v_space};
+use crate::ui::widget::TextInput;
+use crate::ui::{style, text, MainView, Message, QElement};
+
+/// Height of the highest bar of the weekly sparkline
+const SPARKLINE_HEIGHT: u32 = 40;
+
+#[derive(Clone, Debug)]
+pub enum IssueStatsMessage {
+    TextChanged(String),
+    Loaded(Arc<IssueStats>),
+    LocationsLoaded(Arc<LocationReport>),
+    WeekLoaded(Arc<WeekSummary>, Arc<ZaBalance>),
+    PatternsLoaded(Arc<WorkPatterns>),
+    UtilizationLoaded(Arc<UtilizationReport>),
+    InvoiceLoaded(Arc<Invoice>),
+}
+
+pub struct IssueStatsView {
+    input_state: text_input::State,
+    input: String,
+    issue: ParseResult<JiraIssue, ()>,
+    settings: SettingsRef,
+    recent_issues: RecentIssuesRef,
+    recent_filter: String,
+    stats: Option<Arc<IssueStats>>,
+    locations: Option<Arc<LocationReport>>,
+    locations_button: button::State,
+    csv_button: button::State,
+    week: Option<(Arc<WeekSummary>, Arc<ZaBalance>)>,
+    week_button: button::State,
+    patterns: Option<Arc<WorkPatterns>>,
+    patterns_week_button: button::State,
+    patterns_month_button: button::State,
+    utilization: Option<Arc<UtilizationReport>>,
+    utilization_week_button: button::State,
+    utilization_month_button: button::State,
+    utilization_csv_button: button::State,
+    invoice: Option<Arc<Invoice>>,
+    invoice_button: button::State,
+    invoice_csv_button: button::State,
+    invoice_markdown_button: button::State,
+    focus: ButtonFocus,
+}
+
+impl IssueStatsView {
+    pub fn new(settings: SettingsRef, recent_issues: RecentIssuesRef) -> Box<Self> {
+        Box::new(Self {
+            input_state: text_input::State::focused(),
+            input: String::new(),
+            issue: ParseResult::None,
+            settings,
+            recent_issues,
+            recent_filter: String::new(),
+            stats: None,
+            locations: None,
+            locations_button: button::State::new(),
+            csv_button: button::State::new(),
+            week: None,
+            week_button: button::State::new(),
+            patterns: None,
+            patterns_week_button: button::State::new(),
+            patterns_month_button: button::State::new(),
+            utilization: None,
+            utilization_week_button: button::State::new(),
+            utilization_month_button: button::State::new(),
+            utilization_csv_button: button::State::new(),
+            invoice: None,
+            invoice_button: button::State::new(),
+            invoice_csv_button: button::State::new(),
+            invoice_markdown_button: button::State::new(),
+            focus: ButtonFocus::default(),
+        })
+    }
+
+    fn update_input(&mut self, input: String) -> Option<Message> {
+        self.input = input;
+        let settings = self.settings.load();
+        let recent = self.recent_issues.borrow();
+        let parser = IssueParserWithRecent::new(&settings.issue_parser, &recent);
+        let parsed = parser.parse_task(self.input.trim_start());
+        self.issue = parsed.r;
+        filter_message(&mut self.recent_filter, parsed.input)
+    }
+
+    fn stats_view(stats: &IssueStats) -> QElement {
+        let last_booked = stats
+            .last_booked
+            .map(|d| d.to_string())
+            .unwrap_or_else(|| "never".to_string());
+        let average = stats
+            .average_session_minutes()
+            .map(format_minutes)
+            .unwrap_or_else(|| "-".to_string());
+
+        let sparkline = stats
+            .sparkline(SPARKLINE_HEIGHT)
+            .into_iter()
+            .map(|height| -> QElement {
+                Container::new(v_space(Length::Units(height.max(1) as u16)))
+                    .width(Length::Units(12))
+                    .style(style::ContentRow {
+                        state: style::RowState::Selected,
+                    })
+                    .into()
+            })
+            .flat_map(|bar| [bar, h_space(Length::Units(2))]);
+
+        Column::with_children(vec![
+            stat_row("Issue:", stats.ident.clone()),
+            stat_row("Total:", format_minutes(stats.total_minutes)),
+            stat_row("Sessions:", stats.sessions.to_string()),
+            stat_row("Average:", average),
+            stat_row("Last booked:", last_booked),
+            v_space(style::SPACE),
+            text(format!("Last {} weeks:", stats.weekly_minutes.len())),
+            v_space(style::SPACE),
+            Row::with_children(sparkline.collect())
+                .height(Length::Units(SPARKLINE_HEIGHT as u16))
+                .align_items(iced_core::Alignment::End)
+                .into(),
+        ])
+        .into()
+    }
+}
+
+fn locations_view<'a>(
+    focus: &mut ButtonFocus,
+    report: Option<&LocationReport>,
+    active_date: Day,
+    load_button: &'a mut button::State,
+    csv_button: &'a mut button::State,
+) -> QElement<'a> {
+    let mut header = Row::with_children(vec![
+        text("Work time per location:"),
+        h_space(style::DSPACE),
+        focus
+            .inline_button(
+                load_button,
+                "Load month",
+                Message::LoadLocationReport(active_date),
+            )
+            .into(),
+    ]);
+
+    let report = if let Some(report) = report {
+        report
+    } else {
+        return header.into();
+    };
+
+    header = header.push(h_space(style::SPACE)).push(focus.inline_button(
+        csv_button,
+        "Copy CSV",
+        Message::WriteClipboard(Arc::new(report.to_csv())),
+    ));
+
+    let mut rows = vec![
+        header.into(),
+        v_space(style::SPACE),
+        text(format!("{} - {}", report.first_day, report.last_day)),
+    ];
+    if report.entries.is_empty() {
+        rows.push(text("No days started"));
+    }
+    for e in &report.entries {
+        rows.push(stat_row(
+            e.location.name(),
+            format!("{} days, {}", e.days, format_minutes(e.minutes)),
+        ));
+    }
+    Column::with_children(rows).into()
+}
+
+fn week_view<'a>(
+    focus: &mut ButtonFocus,
+    summary: Option<&(Arc<WeekSummary>, Arc<ZaBalance>)>,
+    active_date: Day,
+    load_button: &'a mut button::State,
+) -> QElement<'a> {
+    let header = Row::with_children(vec![
+        text("Work and standby per day:"),
+        h_space(style::DSPACE),
+        focus
+            .inline_button(
+                load_button,
+                "Load week",
+                Message::LoadWeekSummary(active_date),
+            )
+            .into(),
+    ]);
+
+    let (summary, balance) = if let Some(summary) = summary {
+        summary
+    } else {
+        return header.into();
+    };
+
+    let mut rows = vec![header.into(), v_space(style::SPACE)];
+    for d in &summary.days {
+        rows.push(stat_row(
+            format!("{} {}", d.date.day_of_week(), d.date),
+            format!(
+                "work {}, standby {}",
+                format_minutes(d.work_minutes),
+                format_minutes(d.standby_minutes)
+            ),
+        ));
+    }
+    rows.push(stat_row(
+        "Total:",
+        format!(
+            "work {}, standby {}",
+            format_minutes(summary.work_minutes()),
+            format_minutes(summary.standby_minutes())
+        ),
+    ));
+    let surcharges = summary.surcharges();
+    rows.push(stat_row(
+        "Surcharges:",
+        format!(
+            "night {}, weekend {}",
+            format_minutes(surcharges.night),
+            format_minutes(surcharges.weekend)
+        ),
+    ));
+    rows.push(v_space(style::SPACE));
+    rows.push(stat_row(
+        "ZA balance:",
+        format_balance(balance.balance_minutes),
+    ));
+    if balance.planned_za_minutes > 0 {
+        rows.push(stat_row(
+            "Planned ZA:",
+            format_minutes(balance.planned_za_minutes),
+        ));
+    }
+    if balance.planned_exceeds_balance() {
+        rows.push(
+            Text::new("Planned ZA exceeds the accrued balance")
+                .color(style::ERROR_COLOR)
+                .into(),
+        );
+    }
+    Column::with_children(rows).into()
+}
+
+fn patterns_view<'a>(
+    focus: &mut ButtonFocus,
+    patterns: Option<&WorkPatterns>,
+    active_date: Day,
+    week_button: &'a mut button::State,
+    month_button: &'a mut button::State,
+) -> QElement<'a> {
+    let first_of_week = active_date.first_of_week();
+    let header = Row::with_children(vec![
+        text("Work patterns:"),
+        h_space(style::DSPACE),
+        focus
+            .inline_button(
+                week_button,
+                "Load week",
+                Message::LoadWorkPatterns(first_of_week, first_of_week + 6),
+            )
+            .into(),
+        h_space(style::SPACE),
+        focus
+            .inline_button(
+                month_button,
+                "Load month",
+                Message::LoadWorkPatterns(
+                    active_date.first_of_month(),
+                    active_date.last_of_month(),
+                ),
+            )
+            .into(),
+    ]);
+
+    let patterns = if let Some(patterns) = patterns {
+        patterns
+    } else {
+        return header.into();
+    };
+
+    let time = |t: Option<_>| t.map_or_else(|| "-".to_string(), |t| format!("{}", t));
+    let share = |minutes: u32| match patterns.work_minutes {
+        0 => format_minutes(minutes),
+        work => format!("{} ({}%)", format_minutes(minutes), minutes * 100 / work),
+    };
+    Column::with_children(vec![
+        header.into(),
+        v_space(style::SPACE),
+        text(format!(
+            "{} - {}, {} days worked",
+            patterns.first_day, patterns.last_day, patterns.days
+        )),
+        stat_row("Average start:", time(patterns.average_start)),
+        stat_row("Average end:", time(patterns.average_end)),
+        stat_row("Work:", format_minutes(patterns.work_minutes)),
+        stat_row("Meetings:", share(patterns.meeting_minutes)),
+        stat_row(
+            "Deep work:",
+            match patterns.deep_work_percent() {
+                Some(percent) => format!(
+                    "{} ({}%)",
+                    format_minutes(patterns.deep_work_minutes),
+                    percent
+                ),
+                None => format_minutes(patterns.deep_work_minutes),
+            },
+        ),
+        text(format!(
+            "Deep work is work without meetings and gaps for at least {}",
+            format_minutes(DEEP_WORK_MINUTES)
+        )),
+    ])
+    .into()
+}
+
+fn utilization_view<'a>(
+    focus: &mut ButtonFocus,
+    report: Option<&UtilizationReport>,
+    active_date: Day,
+    week_button: &'a mut button::State,
+    month_button: &'a mut button::State,
+    csv_button: &'a mut button::State,
+) -> QElement<'a> {
+    let first_of_week = active_date.first_of_week();
+    let mut header = Row::with_children(vec![
+        text("Target utilization:"),
+        h_space(style::DSPACE),
+        focus
+            .inline_button(
+                week_button,
+                "Load week",
+                Message::LoadUtilization(first_of_week, first_of_week + 6),
+            )
+            .into(),
+        h_space(style::SPACE),
+        focus
+            .inline_button(
+                month_button,
+                "Load month",
+                Message::LoadUtilization(active_date.first_of_month(), active_date.last_of_month()),
+            )
+            .into(),
+    ]);
+
+    let report = if let Some(report) = report {
+        report
+    } else {
+        return header.into();
+    };
+
+    header = header.push(h_space(style::SPACE)).push(focus.inline_button(
+        csv_button,
+        "Copy CSV",
+        Message::WriteClipboard(Arc::new(report.to_csv())),
+    ));
+
+    let mut rows = vec![
+        header.into(),
+        v_space(style::SPACE),
+        text(format!(
+            "{} - {}, {} booked",
+            report.first_day,
+            report.last_day,
+            format_minutes(report.work_minutes)
+        )),
+    ];
+    if report.entries.is_empty() {
+        rows.push(text("No work booked and no targets configured"));
+    }
+    for e in &report.entries {
+        let value = Text::new(format!(
+            "{}, {}% of {}% planned ({:+})",
+            format_minutes(e.minutes),
+            e.actual_percent,
+            e.target_percent,
+            e.deviation()
+        ));
+        rows.push(
+            Row::with_children(vec![
+                Text::new(e.project.clone())
+                    .width(Length::Units(120))
+                    .into(),
+                if e.exceeds_tolerance() {
+                    value.color(style::ERROR_COLOR).into()
+                } else {
+                    value.into()
+                },
+            ])
+            .into(),
+        );
+    }
+    Column::with_children(rows).into()
+}
+
+fn invoice_view<'a>(
+    focus: &mut ButtonFocus,
+    invoice: Option<&Invoice>,
+    active_date: Day,
+    load_button: &'a mut button::State,
+    csv_button: &'a mut button::State,
+    markdown_button: &'a mut button::State,
+) -> QElement<'a> {
+    let mut header = Row::with_children(vec![
+        text("Invoice:"),
+        h_space(style::DSPACE),
+        focus
+            .inline_button(
+                load_button,
+                "Load month",
+                Message::LoadInvoice(active_date.first_of_month(), active_date.last_of_month()),
+            )
+            .into(),
+    ]);
+
+    let invoice = if let Some(invoice) = invoice {
+        invoice
+    } else {
+        return header.into();
+    };
+
+    header = header
+        .push(h_space(style::SPACE))
+        .push(focus.inline_button(
+            csv_button,
+            "Copy CSV",
+            Message::WriteClipboard(Arc::new(invoice.to_csv())),
+        ))
+        .push(h_space(style::SPACE))
+        .push(focus.inline_button(
+            markdown_button,
+            "Copy Markdown",
+            Message::WriteClipboard(Arc::new(invoice.to_markdown())),
+        ));
+
+    let mut rows = vec![
+        header.into(),
+        v_space(style::SPACE),
+        text(format!("{} - {}", invoice.first_day, invoice.last_day)),
+    ];
+    for p in &invoice.projects {
+        rows.push(stat_row(
+            p.project.clone(),
+            format!(
+                "{}, {}",
+                format_minutes(p.minutes()),
+                format_cents(p.subtotal_cents())
+            ),
+        ));
+        let unpriced: Vec<&str> = p
+            .lines
+            .iter()
+            .filter(|l| l.rate_cents.is_none())
+            .map(|l| l.ident.as_str())
+            .collect();
+        if !unpriced.is_empty() {
+            rows.push(
+                Text::new(format!("No hourly rate for {}", unpriced.join(", ")))
+                    .color(style::ERROR_COLOR)
+                    .into(),
+            );
+        }
+    }
+    rows.push(stat_row("Total:", format_cents(invoice.total_cents())));
+    Column::with_children(rows).into()
+}
+
+fn stat_row<'a>(label: impl Into<String>, value: String) -> QElement<'a> {
+    Row::with_children(vec![
+        Text::new(label).width(Length::Units(120)).into(),
+        h_space(style::SPACE),
+        text(value),
+    ])
+    .into()
+}
+
+impl MainView for IssueStatsView {
+    fn view(&mut self) -> QElement {
+        self.focus.clear_targets();
+        let input = TextInput::new(&mut self.input_state, "", &self.input, |i| {
+            Message::Stats(IssueStatsMessage::TextChanged(i))
+        })
+        .style(style::TextInput {
+            error: matches!(self.issue, ParseResult::Invalid(_)),
+        });
+
+        let selected = self
+            .issue
+            .get_ref()
+            .map(|i| i.ident.as_str())
+            .unwrap_or("<none>");
+
+        let stats = match &self.stats {
+            Some(stats) => Self::stats_view(stats),
+            None => text("Press enter to load the statistics of the issue"),
+        };
+
+        let locations = locations_view(
+            &mut self.focus,
+            self.locations.as_deref(),
+            self.settings.load().active_date,
+            &mut self.locations_button,
+            &mut self.csv_button,
+        );
+
+        let week = week_view(
+            &mut self.focus,
+            self.week.as_ref(),
+            self.settings.load().active_date,
+            &mut self.week_button,
+        );
+
+        let patterns = patterns_view(
+            &mut self.focus,
+            self.patterns.as_deref(),
+            self.settings.load().active_date,
+            &mut self.patterns_week_button,
+            &mut self.patterns_month_button,
+        );
+
+        let utilization = utilization_view(
+            &mut self.focus,
+            self.utilization.as_deref(),
+            self.settings.load().active_date,
+            &mut self.utilization_week_button,
+            &mut self.utilization_month_button,
+            &mut self.utilization_csv_button,
+        );
+
+        let invoice = invoice_view(
+            &mut self.focus,
+            self.invoice.as_deref(),
+            self.settings.load().active_date,
+            &mut self.invoice_button,
+            &mut self.invoice_csv_button,
+            &mut self.invoice_markdown_button,
+        );
+
+        Column::with_children(vec![
+            Row::with_children(vec![
+                text("Issue statistics:"),
+                h_space(style::DSPACE),
+                text("<issue id>"),
+                h_space(style::DSPACE),
+                text(selected),
+            ])
+            .into(),
+            v_space(style::SPACE),
+            input.into(),
+            v_space(style::SPACE),
+            stats,
+            v_space(style::DSPACE),
+            locations,
+            v_space(style::DSPACE),
+            week,
+            v_space(style::DSPACE),
+            patterns,
+            v_space(style::DSPACE),
+            utilization,
+            v_space(style::DSPACE),
+            invoice,
+        ])
+        .into()
+    }
+
+    fn update(&mut self, msg: Message) -> Option<Message> {
+        match msg {
+            Message::InsertIssue(ident) => {
+                self.input_state.move_cursor_to_end();
+                let input = insert_issue(&self.input, &ident);
+                Some(Message::Stats(IssueStatsMessage::TextChanged(input)))
+            }
+            Message::Stats(IssueStatsMessage::TextChanged(input)) => {
+                self.focus.unfocus();
+                self.update_input(input)
+            }
+            Message::Stats(IssueStatsMessage::Loaded(stats)) => {
+                self.stats = Some(stats);
+                None
+            }
+            Message::Stats(IssueStatsMessage::LocationsLoaded(report)) => {
+                self.locations = Some(report);
+                None
+            }
+            Message::Stats(IssueStatsMessage::WeekLoaded(summary, balance)) => {
+                self.week = Some((summary, balance));
+                None
+            }
+            Message::Stats(IssueStatsMessage::PatternsLoaded(patterns)) => {
+                self.patterns = Some(patterns);
+                None
+            }
+            Message::Stats(IssueStatsMessage::UtilizationLoaded(report)) => {
+                self.utilization = Some(report);
+                None
+            }
+            Message::Stats(IssueStatsMessage::InvoiceLoaded(invoice)) => {
+                self.invoice = Some(invoice);
+                None
+            }
+            Message::Next => {
+                self.focus.next_with_input(&mut self.input_state);
+                None
+            }
+            Message::Previous => {
+                self.focus.previous_with_input(&mut self.input_state);
+                None
+            }
+            Message::SubmitCurrent(_) if self.focus.has_focus() => self.focus.press(),
+            Message::SubmitCurrent(_) => match &self.issue {
+                ParseResult::Valid(issue) => Some(Message::LoadIssueStats(issue.ident.clone())),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
@@ -40,10 +40,12 @@ fn handle_control_keyboard_event(key_event: iced_winit::keyboard::Event) -> Opti
                     _ => None,
                 }
             } else if modifiers.control() && modifiers.shift() {
-                if matches!(key_code, KeyCode::Tab) {
-                    Some(Message::PrevTab)
-                } else {
-                    None
+                match key_code {
+                    KeyCode::Tab => Some(Message::PrevTab),
+                    KeyCode::Key1 => Some(Message::SwitchRunningWork(0)),
+                    KeyCode::Key2 => Some(Message::SwitchRunningWork(1)),
+                    KeyCode::Key3 => Some(Message::SwitchRunningWork(2)),
+                    _ => None,
                 }
             } else if modifiers == Modifiers::CTRL {
                 handle_control_shortcuts(key_code)
@@ -61,16 +63,38 @@ fn handle_control_shortcuts(key_code: KeyCode) -> Option<Message> {
         KeyCode::D => Some(Message::RequestDayChange),
         KeyCode::I => Some(Message::ChangeView(ViewId::BookSingle)),
         KeyCode::O => Some(Message::ChangeView(ViewId::FastDayStart)),
+        KeyCode::U => Some(Message::ChangeView(ViewId::DayStartWizard)),
         KeyCode::L => Some(Message::ChangeView(ViewId::FastDayEnd)),
+        KeyCode::G => Some(Message::ChangeView(ViewId::DayEndWizard)),
+        KeyCode::Z => Some(Message::ChangeView(ViewId::GapFill)),
         KeyCode::S => Some(Message::ChangeView(ViewId::BookIssueStart)),
         KeyCode::E => Some(Message::ChangeView(ViewId::BookIssueEnd)),
+        KeyCode::N => Some(Message::ChangeView(ViewId::QuickSwitch)),
+        KeyCode::V => Some(Message::ChangeView(ViewId::WorkEvent)),
+        KeyCode::B => Some(Message::ChangeView(ViewId::Standby)),
+        KeyCode::Q => Some(Message::ChangeView(ViewId::QuickDay)),
+        KeyCode::R => Some(Message::ChangeView(ViewId::Reassign)),
         KeyCode::X => Some(Message::ChangeView(ViewId::Export)),
+        KeyCode::A => Some(Message::ChangeView(ViewId::IssueStats)),
+        KeyCode::W => Some(Message::ChangeView(ViewId::WeekCalendar)),
+        KeyCode::Y => Some(Message::ChangeView(ViewId::YearOverview)),
+        KeyCode::F => Some(Message::ChangeView(ViewId::ToFix)),
+        KeyCode::T => Some(Message::ChangeView(ViewId::Settings)),
         KeyCode::C => Some(Message::CopyValue),
+        KeyCode::H => Some(Message::CopyTextSummary),
+        KeyCode::K => Some(Message::ToggleDayClosed),
+        KeyCode::P => Some(Message::StopRunningWork),
+        KeyCode::J => Some(Message::ContinueLastIssue),
         KeyCode::Key1 => Some(Message::ChangeView(ViewId::CurrentDayUi)),
         KeyCode::Enter | KeyCode::NumpadEnter => Some(Message::SubmitCurrent(StayActive::Yes)),
         KeyCode::Left => Some(Message::ChangeDayRelative(-1, Arc::new(WeekDayForwarder))),
         KeyCode::Right => Some(Message::ChangeDayRelative(1, Arc::new(WeekDayForwarder))),
+        KeyCode::Up => Some(Message::RecallInput(-1)),
+        KeyCode::Down => Some(Message::RecallInput(1)),
+        KeyCode::PageUp => Some(Message::ChangeWeek(-1)),
+        KeyCode::PageDown => Some(Message::ChangeWeek(1)),
         KeyCode::Tab => Some(Message::NextTab),
+        KeyCode::Space => Some(Message::CycleShortcut),
         _ => None,
     }
 }
@@ -88,10 +112,23 @@ fn handle_keyboard_event(key_event: iced_winit::keyboard::Event) -> Option<Messa
                     KeyCode::Tab => Some(Message::Next),
                     KeyCode::I => Some(Message::ChangeView(ViewId::BookSingle)),
                     KeyCode::O => Some(Message::ChangeView(ViewId::FastDayStart)),
+                    KeyCode::U => Some(Message::ChangeView(ViewId::DayStartWizard)),
                     KeyCode::L => Some(Message::ChangeView(ViewId::FastDayEnd)),
+                    KeyCode::G => Some(Message::ChangeView(ViewId::DayEndWizard)),
+                    KeyCode::Z => Some(Message::ChangeView(ViewId::GapFill)),
                     KeyCode::S => Some(Message::ChangeView(ViewId::BookIssueStart)),
                     KeyCode::E => Some(Message::ChangeView(ViewId::BookIssueEnd)),
+                    KeyCode::N => Some(Message::ChangeView(ViewId::QuickSwitch)),
+                    KeyCode::V => Some(Message::ChangeView(ViewId::WorkEvent)),
+                    KeyCode::B => Some(Message::ChangeView(ViewId::Standby)),
+                    KeyCode::Q => Some(Message::ChangeView(ViewId::QuickDay)),
+                    KeyCode::R => Some(Message::ChangeView(ViewId::Reassign)),
                     KeyCode::X => Some(Message::ChangeView(ViewId::Export)),
+                    KeyCode::A => Some(Message::ChangeView(ViewId::IssueStats)),
+                    KeyCode::W => Some(Message::ChangeView(ViewId::WeekCalendar)),
+                    KeyCode::Y => Some(Message::ChangeView(ViewId::YearOverview)),
+                    KeyCode::F => Some(Message::ChangeView(ViewId::ToFix)),
+                    KeyCode::T => Some(Message::ChangeView(ViewId::Settings)),
                     KeyCode::Key1 => Some(Message::ChangeView(ViewId::CurrentDayUi)),
                     KeyCode::Enter | KeyCode::NumpadEnter => {
                         Some(Message::SubmitCurrent(StayActive::Default))
@@ -99,16 +136,25 @@ fn handle_keyboard_event(key_event: iced_winit::keyboard::Event) -> Option<Messa
                     KeyCode::Up => Some(Message::Up),
                     KeyCode::Down => Some(Message::Down),
                     KeyCode::Delete => Some(Message::Del),
+                    KeyCode::M => Some(Message::Merge),
+                    KeyCode::P => Some(Message::Split),
                     _ => None,
                 }
             } else if modifiers == Modifiers::SHIFT | Modifiers::CTRL {
                 match key_code {
                     KeyCode::Tab => Some(Message::PrevTab),
+                    KeyCode::Key1 => Some(Message::SwitchRunningWork(0)),
+                    KeyCode::Key2 => Some(Message::SwitchRunningWork(1)),
+                    KeyCode::Key3 => Some(Message::SwitchRunningWork(2)),
+                    KeyCode::Up => Some(Message::NudgeEnd(-1)),
+                    KeyCode::Down => Some(Message::NudgeEnd(1)),
                     _ => None,
                 }
             } else if modifiers == Modifiers::SHIFT {
                 match key_code {
                     KeyCode::Tab => Some(Message::Previous),
+                    KeyCode::Up => Some(Message::NudgeStart(-1)),
+                    KeyCode::Down => Some(Message::NudgeStart(1)),
                     _ => None,
                 }
             } else if modifiers == Modifiers::CTRL {
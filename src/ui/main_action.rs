@@ -1,16 +1,19 @@
 use crate::conf::SettingsRef;
 use crate::data::ActiveDay;
 use crate::db::DB;
+use crate::ui::single_instance::InstanceListener;
 use crate::ui::ViewId;
 use std::cell::RefCell;
 use std::rc::Rc;
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct MainAction {
     pub settings: SettingsRef,
     pub initial_view: ViewId,
     pub db: DB,
     pub work_day: Rc<RefCell<ActiveDay>>,
+    /// Receives the launches of further instances, `None` if the socket is not available
+    pub instance: Option<InstanceListener>,
 }
 
 #[derive(Debug)]
@@ -22,6 +25,12 @@ pub enum InitialAction {
 #[derive(Clone, Debug)]
 pub enum CmdId {
     PrintDay,
+    /// Reads the input of the view from the terminal instead of opening a window
+    Prompt(ViewId),
+    /// Starts work on the issue found in a link or text
+    Capture(String),
+    /// Listens for the global hotkey
+    Hotkey,
 }
 
 impl Default for InitialAction {
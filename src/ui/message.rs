@@ -1,15 +1,33 @@
+use std::path::PathBuf;
 use std::sync::Arc;
 
-use crate::data::{Action, Day, DayForwarder};
+use crate::conf::MergeMode;
+use crate::data::{Action, Day, DayForwarder, DayReassignment, JiraIssue};
+use crate::db::GitStatus;
+use crate::error::QuarbleError;
+use crate::jira::JiraError;
+use crate::parsing::time::Time;
 use crate::ui::book_single::BookSingleMessage;
 use crate::ui::current_day::CurrentDayMessage;
-use crate::ui::export::DayExportMessage;
+use crate::ui::day_end_wizard::DayEndWizardMessage;
+use crate::ui::day_start_wizard::DayStartWizardMessage;
+use crate::ui::export::{DayExportMessage, ReportPeriod};
 use crate::ui::fast_day_end::FastDayEndMessage;
 use crate::ui::fast_day_start::FastDayStartMessage;
+use crate::ui::gap_fill::GapFillMessage;
 use crate::ui::issue_end_edit::IssueEndMessage;
 use crate::ui::issue_start_edit::IssueStartMessage;
+use crate::ui::issue_stats_view::IssueStatsMessage;
+use crate::ui::quick_day::QuickDayMessage;
+use crate::ui::quick_switch::QuickSwitchMessage;
+use crate::ui::reassign_view::ReassignMessage;
 use crate::ui::settings_ui::SettingsUIMessage;
+use crate::ui::standby_edit::StandbyMessage;
 use crate::ui::stay_active::StayActive;
+use crate::ui::to_fix_view::ToFixMessage;
+use crate::ui::week_calendar::WeekCalendarMessage;
+use crate::ui::work_event_edit::WorkEventMessage;
+use crate::ui::year_overview::YearOverviewMessage;
 use crate::ui::ViewId;
 
 #[derive(Debug, Clone)]
@@ -23,19 +41,77 @@ pub enum Message {
     Up,
     Down,
     Del,
+    /// Moves the start of the selected entry by resolution steps
+    NudgeStart(i32),
+    /// Moves the end of the selected entry by resolution steps
+    NudgeEnd(i32),
+    /// Replaces the input by an older (-1) or newer (1) input of the view's history
+    RecallInput(i32),
+    /// Moves the time in the input of the booking view by resolution steps, sent by the
+    /// mouse wheel over the input
+    ScrollTime(i32),
+    /// Merges the selected entry with adjacent work on the same issue
+    Merge,
+    /// Splits the selected entry
+    Split,
     CopyValue,
+    /// Copies the text summary of the view, for screen readers
+    CopyTextSummary,
     RequestDayChange,
     ReadClipboard,
     WriteClipboard(Arc<String>),
     ChangeView(ViewId),
     RefreshView,
+    /// Emitted by the clock, updates times that follow the current time and the date
+    Tick,
     Reset,
     SubmitCurrent(StayActive),
     ChangeDay(Day),
     ChangeDayRelative(i64, Arc<dyn DayForwarder>),
+    /// Moves the active day by whole weeks, keeping the weekday
+    ChangeWeek(i64),
     ClipboardValue(Option<String>),
     IssueInput(String),
+    PinIssue(String),
+    /// Puts the issue into the issue input of the current view
+    InsertIssue(String),
+    /// Replaces the issue shortcut in the input of the current view with the next one
+    CycleShortcut,
+    /// Collapses or expands the recent issues of the project
+    ToggleProject(String),
+    UnpinIssue(String),
+    LoadIssueStats(String),
+    /// Loads the previous comments of the issue for the book single view
+    LoadComments(String),
+    /// Loads work and standby totals of the week of the day and the ZA balance
+    LoadWeekSummary(Day),
+    /// Loads the entries of the week of the day for the calendar
+    LoadWeekCalendar(Day),
+    /// Loads the issue left open on the last workday before the day
+    LoadOpenIssue(Day),
+    /// Loads the booked work of every day of the year for the heatmap
+    LoadYearOverview(i32),
+    /// Checks the last workdays for missing bookings
+    LoadDaysToFix,
+    /// Makes the day the active day, then handles the message
+    OpenOnDay(Day, Box<Message>),
+    /// Opens book single with the time range as input
+    BookRange(Time, Time),
+    /// Loads the location report of the month of the day
+    LoadLocationReport(Day),
+    /// Loads start and end times, meetings and deep work of the days in the range
+    LoadWorkPatterns(Day, Day),
+    /// Compares the booked time per project of the days in the range with the targets
+    LoadUtilization(Day, Day),
+    /// Prices the normalized work of the days in the range with the hourly rates
+    LoadInvoice(Day, Day),
+    ImportRecentIssues(Vec<JiraIssue>, MergeMode),
     UpdateCloseOnSafe(bool),
+    SwitchProfile(Option<String>),
+    /// Writes the submitted settings now instead of with the next autosave
+    WriteSettings,
+    /// The settings file was changed, possibly outside of quarble
+    SettingsFileChanged,
     UpdateStart {
         id: usize,
         input: String,
@@ -52,22 +128,102 @@ pub enum Message {
     },
     Export(DayExportMessage),
     Fds(FastDayStartMessage),
+    Sw(DayStartWizardMessage),
     Fde(FastDayEndMessage),
+    Ew(DayEndWizardMessage),
+    Gf(GapFillMessage),
     Bs(BookSingleMessage),
     Is(IssueStartMessage),
+    Ev(WorkEventMessage),
     Ie(IssueEndMessage),
+    Sb(StandbyMessage),
+    Qd(QuickDayMessage),
+    Qs(QuickSwitchMessage),
+    Ra(ReassignMessage),
     Cd(CurrentDayMessage),
+    Stats(IssueStatsMessage),
+    Cal(WeekCalendarMessage),
+    Year(YearOverviewMessage),
+    Fix(ToFixMessage),
     SettingsUi(SettingsUIMessage),
     EditAction(EditAction),
     DeleteAction(DeleteAction),
+    /// Deletes the entry waiting for confirmation
+    ConfirmDelete,
+    CancelDelete,
+    /// Stores the work duplicating a stored entry anyway
+    ConfirmStore,
+    CancelStore,
     StoreAction(StayActive, Action),
+    /// Adds all actions to the active day and stores it once
+    StoreActions(StayActive, Vec<Action>),
+    /// Stores an action forwarded by a later launch on the day
+    StoreForwarded(Day, Action),
     ModifyAction {
         stay_active: StayActive,
         orig: Box<Action>,
         update: Box<Action>,
     },
+    /// Replaces all `orig` actions by `update` and stores the day once
+    ModifyActions {
+        stay_active: StayActive,
+        orig: Vec<Action>,
+        update: Vec<Action>,
+    },
+    /// Loads all actions on `from` between `first` and `last` to replace them by `to`
+    PreviewReassign {
+        from: String,
+        to: JiraIssue,
+        first: Day,
+        last: Day,
+    },
+    ApplyReassign(Arc<Vec<DayReassignment>>),
+    /// The reassignments and the number of changed entries were stored
+    Reassigned(Arc<Vec<DayReassignment>>, usize),
+    /// Completes a write of a day that was interrupted in the last session
+    ReplayJournal,
+    RollBackJournal,
+    /// Restores the input of the last session
+    RestoreDraft,
+    DiscardDraft,
+    /// Closes the active day against modifications, or unlocks it again
+    ToggleDayClosed,
+    /// Replaces the notes of the active day
+    SetDayNotes(String),
+    /// Opens a link of work in the browser
+    OpenLink(String),
+    /// Ends the running work now
+    StopRunningWork,
+    /// Books the issue booked last from the end of the last action until now
+    ContinueLastIssue,
+    /// Ends the running work and starts the issue with the index in the work banner now
+    SwitchRunningWork(usize),
+    /// Records the export of the active day and writes the text to the clipboard
+    MarkExported(Day, Arc<String>),
+    /// Records the export of the active day to a file that was already written
+    MarkExportedToFile(Day, Arc<String>, PathBuf),
+    /// Opens the native save dialog in the background, prefilled with the path
+    ChooseExportFile(PathBuf),
+    DismissNotice,
+    /// Sends the report of the day or its week to the configured recipients
+    MailReport(Day, ReportPeriod),
+    /// Result of sending a report
+    MailSent(Result<String, String>),
+    /// Status of the git repository after the stored days were committed
+    GitSynced(Option<GitStatus>),
+    /// Posts the export of the day to the configured webhook in the background
+    PostWebhook(Day),
+    /// Result of a webhook post after the given number of attempts
+    WebhookDelivered(Day, u32, Result<(), String>),
+    /// Summary of a recent issue fetched from Jira
+    IssueTitleFetched(String, Result<String, JiraError>),
     StoreSuccess(StayActive),
     Error(String),
+    Failed(Arc<QuarbleError>),
+    ToggleErrorDetails,
+    /// Answer to the question whether the input of the view may be dropped
+    ConfirmTransition(Confirmation),
+    DismissError,
     TextChanged(String),
 }
 
@@ -82,3 +238,14 @@ pub struct EditAction(pub Box<Action>);
 
 #[derive(Clone, Debug)]
 pub struct DeleteAction(pub StayActive, pub Box<Action>);
+
+/// How to continue when leaving a view with input that was not stored
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Confirmation {
+    /// Store the input, then leave the view
+    Save,
+    /// Drop the input and leave the view
+    Discard,
+    /// Stay in the view
+    Cancel,
+}
@@ -1,14 +1,18 @@
 use std::collections::BTreeSet;
-use std::rc::Rc;
+use std::num::NonZeroU32;
+use std::ops::RangeBounds;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
 
-use arc_swap::ArcSwap;
 use iced_core::alignment::Vertical;
 use iced_core::keyboard::Event;
-use iced_core::{Color, Padding};
+use iced_core::{Color, Length, Padding};
 use iced_native::clipboard;
-use iced_wgpu::Text;
+use iced_native::widget::button;
+use iced_wgpu::wgpu::Backends;
 use iced_winit::settings::SettingsWindowConfigurator;
-use iced_winit::widget::{Column, Container};
+use iced_winit::widget::{Column, Container, Row};
 use iced_winit::Program;
 use iced_winit::{Command, Subscription};
 use iced_winit::{Element, Mode};
@@ -18,50 +22,165 @@ pub use message::Message;
 use stay_active::StayActive;
 pub use view_id::ViewId;
 
-use crate::conf::{update_settings, SettingsRef};
+use crate::conf::{
+    read_settings_file, update_settings, write_settings, EmailConfig, SettingsRef, SettingsSer,
+};
 use crate::data::{
-    Action, ActiveDay, Day, RecentIssues, RecentIssuesData, RecentIssuesRef, TimedAction,
+    export_path, format_minutes, Action, ActiveDay, BreakCompliance, BudgetUsage, CommentHistory,
+    Day, DayReassignment, DayToFix, Draft, HolidayForwarder, InputHistory, Invoice, IssueStats,
+    JiraIssue, LocationReport, NormalizedDay, Normalizer, RecentIssues, RecentIssuesData,
+    RecentIssuesRef, TargetProgress, TimedAction, TitleFetches, UtilizationReport, WebhookDelivery,
+    WeekCalendar, WeekSummary, WorkEnd, WorkPatterns, WorkStart, YearOverview, ZaBalance,
 };
-use crate::db::DB;
+use crate::db::{DBErr, Journal, PendingCommit, DB};
+use crate::error::QuarbleError;
+use crate::jira::{fetch_summary, JiraError};
+use crate::mail::send_mail;
+use crate::parsing::locale;
 use crate::parsing::parse_result::ParseResult;
 use crate::parsing::time::Time;
 use crate::parsing::time_limit::TimeRange;
-use crate::ui::current_day::CurrentDayMessage;
-use crate::ui::export::DayExportMessage;
+use crate::ui::autosave::Autosave;
+use crate::ui::book_single::BookSingleMessage;
+use crate::ui::current_day::{action_row, CurrentDayMessage};
+use crate::ui::day_start_reminder::DayStartReminder;
+use crate::ui::day_start_wizard::DayStartWizardMessage;
+use crate::ui::export::{
+    export_json, export_text, report_text, write_export_file, DayExportMessage, ReportPeriod,
+};
+use crate::ui::fast_day_end::FastDayEnd;
+use crate::ui::fast_day_start::FastDayStart;
+use crate::ui::issue_stats_view::IssueStatsMessage;
 use crate::ui::main_action::MainAction;
-use crate::ui::message::{DeleteAction, EditAction};
+use crate::ui::message::{Confirmation, DeleteAction, EditAction};
+use crate::ui::reassign_view::ReassignMessage;
 use crate::ui::recent_issues_view::RecentIssuesView;
+use crate::ui::settings_ui::SettingsUIMessage;
+use crate::ui::single_edit_ui::SingleEditUi;
+use crate::ui::single_instance::InstanceListener;
 use crate::ui::tab_bar::TabBar;
-use crate::ui::util::v_space;
+use crate::ui::to_fix_view::{ToFixMessage, CHECKED_WORKDAYS};
+use crate::ui::util::{background, h_space, v_space};
+use crate::ui::week_calendar::WeekCalendarMessage;
+use crate::ui::widget::Text;
 use crate::ui::window_configurator::{DisplaySelection, MyWindowConfigurator};
+use crate::ui::work_banner::{switch_actions, WorkBanner, SWITCH_ISSUES};
+use crate::ui::year_overview::YearOverviewMessage;
+use crate::util::TimelineProvider;
+use crate::webhook::post_json;
 use crate::Settings;
 
+mod autosave;
 mod book_single;
+mod button_focus;
 mod clip_read;
+mod clock;
 mod current_day;
 mod current_view;
+mod day_end_wizard;
+mod day_gauge;
+mod day_start_reminder;
+mod day_start_wizard;
 mod export;
 pub mod fast_day_end;
 pub mod fast_day_start;
 mod focus_handler;
+mod gap_fill;
 mod issue_end_edit;
 mod issue_start_edit;
+mod issue_stats_view;
 mod keyboard_handler;
 pub mod main_action;
 mod message;
+mod notification;
+mod open_link;
+mod quick_day;
+mod quick_switch;
+mod reassign_view;
 mod recent_issues_view;
 mod settings_ui;
+mod settings_watcher;
 mod single_edit_ui;
+#[cfg(unix)]
+pub mod single_instance;
+#[cfg(not(unix))]
+#[path = "single_instance_fallback.rs"]
+pub mod single_instance;
+mod standby_edit;
 mod stay_active;
 mod style;
 mod tab_bar;
+mod to_fix_view;
 mod top_bar;
+#[cfg(all(test, feature = "ui-tests"))]
+mod ui_test;
 mod util;
 mod view_id;
+mod week_calendar;
+mod wheel_steps;
+mod widget;
 mod window_configurator;
+mod work_banner;
+mod work_event_edit;
+mod year_overview;
+
+/// Parses the input of the day start or end view, without opening a window
+pub fn parse_fast_input(
+    view_id: ViewId,
+    settings: SettingsRef,
+    active_day: &ActiveDay,
+    input: &str,
+) -> Option<Action> {
+    let action = match view_id {
+        ViewId::FastDayStart => {
+            let mut view = FastDayStart::for_work_day(settings.clone(), Some(active_day));
+            view.update_input(input.to_string());
+            view.try_build().map(Action::DayStart)
+        }
+        ViewId::FastDayEnd => {
+            let mut view = FastDayEnd::for_work_day(settings.clone(), Some(active_day));
+            view.update_input(input.to_string());
+            view.try_build().map(Action::DayEnd)
+        }
+        _ => None,
+    }?;
+    Some(apply_time_precision(&settings.load(), action))
+}
+
+/// Graphics backend of the renderer selected by `name`, None for unknown names
+pub fn renderer_backends(name: &str) -> Option<Backends> {
+    match name.to_lowercase().as_str() {
+        "vulkan" => Some(Backends::VULKAN),
+        "metal" => Some(Backends::METAL),
+        "dx12" => Some(Backends::DX12),
+        "dx11" => Some(Backends::DX11),
+        "gl" => Some(Backends::GL),
+        "primary" => Some(Backends::PRIMARY),
+        _ => None,
+    }
+}
 
-pub fn show_ui(main_action: MainAction) -> Rc<ArcSwap<Settings>> {
+pub fn show_ui(main_action: MainAction) {
     let config_settings = main_action.settings.clone();
+    style::set_high_contrast(config_settings.load().high_contrast);
+    let explicit = config_settings
+        .load()
+        .renderer
+        .as_deref()
+        .and_then(renderer_backends);
+
+    let result = run_ui(main_action.clone(), explicit);
+    match result {
+        Err(e) if explicit.is_none() => {
+            // the GL backend also works with software rendering, e.g. in VMs without GPU drivers
+            log::warn!("Cannot start the renderer, falling back to GL: {:?}", e);
+            run_ui(main_action, Some(Backends::GL)).expect("Cannot start the renderer");
+        }
+        result => result.expect("Cannot start the renderer"),
+    }
+}
+
+fn run_ui(main_action: MainAction, backends: Option<Backends>) -> Result<(), iced_winit::Error> {
     let window_configurator = MyWindowConfigurator {
         base: SettingsWindowConfigurator {
             window: Default::default(),
@@ -70,10 +189,12 @@ pub fn show_ui(main_action: MainAction) -> Rc<ArcSwap<Settings>> {
         },
         display_selection: DisplaySelection::Largest,
     };
+    let from_env = iced_wgpu::Settings::from_env();
     let renderer_settings = iced_wgpu::Settings {
         antialiasing: Some(iced_wgpu::settings::Antialiasing::MSAAx4),
         default_text_size: 18,
-        ..iced_wgpu::Settings::from_env()
+        internal_backend: backends.unwrap_or(from_env.internal_backend),
+        ..from_env
     };
     iced_winit::application::run_with_window_configurator::<
         Quarble,
@@ -81,9 +202,30 @@ pub fn show_ui(main_action: MainAction) -> Rc<ArcSwap<Settings>> {
         iced_wgpu::window::Compositor,
         _,
     >(main_action, renderer_settings, window_configurator, true)
-    .unwrap();
+}
 
-    config_settings
+/// Position in the input history of a view while recalling inputs
+struct InputRecall {
+    view: ViewId,
+    /// Steps back from the last input
+    back: usize,
+    /// Input typed before recalling, restored when stepping past the last input
+    typed: String,
+}
+
+/// Why actions are held back until the store is confirmed
+enum StoreHold {
+    /// Books work of this stored entry again
+    Duplicate(Action),
+    /// Exceeds a configured cap of the work time
+    WorkTimeCap(String),
+}
+
+/// Actions held back until the store is confirmed
+struct PendingStore {
+    stay_active: StayActive,
+    actions: Vec<Action>,
+    hold: StoreHold,
 }
 
 pub struct Quarble {
@@ -95,7 +237,575 @@ pub struct Quarble {
     tab_bar: TabBar,
     recent_issues: RecentIssuesRef,
     recent_view: RecentIssuesView,
-    current_error: String,
+    current_error: Option<Arc<QuarbleError>>,
+    show_error_details: bool,
+    error_buttons: [button::State; 3],
+    /// Draft of the last session, until it is restored or discarded
+    pending_draft: Option<Draft>,
+    stored_draft: Option<Draft>,
+    draft_buttons: [button::State; 2],
+    interrupted_write: Option<Journal>,
+    journal_buttons: [button::State; 2],
+    /// Day change, view change or exit that waits for confirmation to drop the input
+    pending_transition: Option<Message>,
+    /// Transition that continues after the input was stored
+    after_store: Option<Message>,
+    /// The current update may leave the view without asking
+    transition_confirmed: bool,
+    transition_buttons: [button::State; 3],
+    /// Delete waiting for confirmation
+    pending_delete: Option<DeleteAction>,
+    delete_confirmed: bool,
+    delete_buttons: [button::State; 2],
+    /// Work duplicating a stored entry or exceeding a cap, waiting for confirmation
+    pending_store: Option<PendingStore>,
+    store_confirmed: bool,
+    store_buttons: [button::State; 2],
+    /// Inputs stored in the entry views, see [ViewId::has_input_history]
+    input_history: InputHistory,
+    input_recall: Option<InputRecall>,
+    /// Input of the last submit, added to the history once it is stored
+    submitted_input: Option<(ViewId, String)>,
+    /// The break notification was shown for the current stretch of work
+    break_notified: bool,
+    /// The budget notification was shown since the work crossed the daily budget
+    budget_notified: bool,
+    title_fetches: TitleFetches,
+    /// Outcomes of automatic exports and mails, shown until dismissed
+    notices: Vec<Result<String, String>>,
+    notice_button: button::State,
+    instance: Option<InstanceListener>,
+    work_banner: WorkBanner,
+    day_start_reminder: DayStartReminder,
+    /// Date and time of the last clock tick
+    last_today: Day,
+    last_tick: chrono::NaiveDateTime,
+    autosave: Autosave,
+    /// The settings last handed to the autosave
+    saved_settings: Arc<Settings>,
+}
+
+impl Quarble {
+    /// Persists the input of the current view with the autosave, so that it survives a crash
+    fn sync_draft(&mut self) {
+        if self.pending_draft.is_some() {
+            return;
+        }
+        let view_id = self.current_view.view_id();
+        let draft = self
+            .current_view
+            .draft()
+            .filter(|input| !input.trim().is_empty())
+            .map(|input| Draft {
+                view: view_id.name().to_string(),
+                input: input.to_string(),
+            });
+        // exiting keeps the draft
+        if draft == self.stored_draft || view_id == ViewId::Exit {
+            return;
+        }
+        self.autosave.draft(&self.db, draft.clone());
+        self.stored_draft = draft;
+    }
+
+    /// The transition would drop input of the current view
+    fn needs_confirmation(&self, transition: &Message) -> bool {
+        let leaves_view = match transition {
+            Message::ChangeView(view_id) => *view_id != self.current_view.view_id(),
+            Message::RequestDayChange => !matches!(self.current_view, CurrentView::CdUi(_)),
+            _ => true,
+        };
+        leaves_view && !self.transition_confirmed && self.current_view.is_dirty()
+    }
+
+    /// What the window shows, as sentences for screen readers
+    fn text_summary(&self) -> String {
+        let mut lines = vec![
+            format!("{} view.", self.current_view.view_id().title()),
+            format!("{}.", day_info_message(self.active_day.as_ref())),
+        ];
+        if let Some(error) = &self.current_error {
+            lines.push(format!("Error: {}.", error));
+        }
+        for notice in &self.notices {
+            match notice {
+                Ok(text) => lines.push(text.clone()),
+                Err(text) => lines.push(format!("Error: {}", text)),
+            }
+        }
+        match self.pending_store.as_ref().map(|p| &p.hold) {
+            Some(StoreHold::Duplicate(existing)) => lines.push(format!(
+                "Already booked, Enter stores anyway, Escape cancels. {}",
+                existing.as_sentence()
+            )),
+            Some(StoreHold::WorkTimeCap(cap)) => {
+                lines.push(format!("{}, Enter stores anyway, Escape cancels.", cap))
+            }
+            None => (),
+        }
+        if let Some(DeleteAction(_, action)) = &self.pending_delete {
+            lines.push(format!(
+                "Delete this entry? Enter deletes, Escape cancels. {}",
+                action.as_sentence()
+            ));
+        }
+        lines.extend(self.current_view.text_summary());
+        lines.join("\n")
+    }
+
+    /// The current time, if the active day is today
+    fn today_now(&self) -> Option<Time> {
+        let settings = self.settings.load();
+        let active = self.active_day.as_ref().map(ActiveDay::get_day);
+        (active == Some(settings.today())).then(|| settings.timeline.time_now())
+    }
+
+    /// The work running now, with the current time
+    fn running_work(&self) -> Option<(WorkStart, Time)> {
+        let now = self.today_now()?;
+        let running = self.active_day.as_ref()?.running_work(now)?;
+        Some((running.clone(), now))
+    }
+
+    /// The recent issues offered by the work banner, without the running issue
+    fn switch_candidates(&self, running: Option<&WorkStart>) -> Vec<JiraIssue> {
+        let recent = self.recent_issues.borrow();
+        (0..)
+            .map_while(|index| recent.find_recent(index))
+            .filter(|issue| running.map_or(true, |r| r.task.ident != issue.ident))
+            .take(SWITCH_ISSUES)
+            .cloned()
+            .collect()
+    }
+
+    /// Stores actions of the work banner, the input of the current view is kept
+    fn store_banner_actions(&mut self, actions: Vec<Action>) -> Option<Message> {
+        let settings = self.settings.load_full();
+        let active_day = self.active_day.as_mut()?;
+        if active_day.is_closed() {
+            return Some(Message::Error(DAY_CLOSED.to_string()));
+        }
+        for action in actions {
+            if let Some(issue) = action.issue() {
+                self.recent_issues
+                    .issue_used_with_comment(issue, action.description());
+            }
+            active_day.add_action(apply_time_precision(&settings, action));
+        }
+        let stored = self.db.store_day(active_day);
+        self.store_recent();
+        match stored {
+            Ok(()) if self.current_view.is_dirty() => {
+                self.refresh_progress();
+                None
+            }
+            Ok(()) => Some(Message::RefreshView),
+            Err(e) => Some(db_failure("store the day", e)),
+        }
+    }
+
+    fn store_recent(&mut self) {
+        self.recent_view.reload();
+        self.autosave
+            .recent(&self.db, self.recent_view.export_data());
+    }
+
+    /// Adds the stored input of the current view to its history
+    fn remember_input(&mut self) {
+        self.input_recall = None;
+        let view = self.current_view.view_id();
+        match self.submitted_input.take() {
+            Some((submitted_in, input)) if submitted_in == view => {
+                self.input_history.add(view.name(), &input);
+                self.autosave.history(&self.db, self.input_history.clone());
+            }
+            _ => (),
+        }
+    }
+
+    /// Replaces the input of the current view by an older (`step < 0`) or newer input
+    fn recall_input(&mut self, step: i32) -> Option<Message> {
+        let view = self.current_view.view_id();
+        let available = self.input_history.len(view.name());
+        let recall = self.input_recall.take().filter(|r| r.view == view);
+        let back = match (&recall, step < 0) {
+            (None, true) if available > 0 => 0,
+            (Some(r), true) => (r.back + 1).min(available - 1),
+            (Some(r), false) if r.back > 0 => r.back - 1,
+            (Some(r), false) => return self.current_view.restore_draft(r.typed.clone()),
+            _ => return None,
+        };
+        let typed = match recall {
+            Some(r) => r.typed,
+            None => self.current_view.draft().unwrap_or_default().to_string(),
+        };
+        let input = self.input_history.recall(view.name(), back)?.to_string();
+        self.input_recall = Some(InputRecall { view, back, typed });
+        self.current_view.restore_draft(input)
+    }
+
+    fn clear_draft(&mut self) {
+        if self.stored_draft.take().is_some() {
+            self.autosave.draft(&self.db, None);
+        }
+    }
+
+    /// Recomputes the target progress shown in the tab bar
+    fn refresh_progress(&mut self) {
+        let progress = match &self.active_day {
+            Some(day) => match load_target_progress(&self.db, &self.settings.load(), day) {
+                Ok(progress) => Some(progress),
+                Err(e) => {
+                    log::warn!("Cannot load target progress: {:?}", e);
+                    None
+                }
+            },
+            None => None,
+        };
+        self.tab_bar.set_progress(progress);
+    }
+
+    /// Starts committing the stored days, the git status afterwards arrives as
+    /// [`Message::GitSynced`]
+    fn sync_git(&self, commits: Vec<PendingCommit>) -> Command<Message> {
+        let db = self.db.clone();
+        Command::perform(background(move || db.sync_git(commits)), Message::GitSynced)
+    }
+
+    /// Notifies once when today's work without break exceeds the limit, again after a break
+    fn check_break_limit(&mut self) {
+        let settings = self.settings.load();
+        let day = match &self.active_day {
+            Some(day) if settings.break_limit.notify && day.get_day() == settings.today() => day,
+            _ => return,
+        };
+        let now = settings.timeline.time_now();
+        let compliance = BreakCompliance::of(day.actions(), settings.break_limit, Some(now));
+        if compliance.exceeded && !self.break_notified {
+            notification::desktop_notification(
+                "Time for a break",
+                &format!(
+                    "Worked {} without a break",
+                    format_minutes(compliance.since_break_minutes)
+                ),
+            );
+        }
+        self.break_notified = compliance.exceeded;
+    }
+
+    /// Notifies once when today's booked and running work crosses the daily budget
+    fn check_daily_budget(&mut self) {
+        let settings = self.settings.load();
+        let day = match &self.active_day {
+            Some(day) if settings.daily_budget.notify && day.get_day() == settings.today() => day,
+            _ => return,
+        };
+        let now = settings.timeline.time_now();
+        let usage = BudgetUsage::of(day.actions(), settings.daily_budget, Some(now));
+        if usage.exceeded && !self.budget_notified {
+            notification::desktop_notification(
+                "Daily budget used up",
+                &format!(
+                    "Booked {} of {} today",
+                    format_minutes(usage.booked_minutes),
+                    format_minutes(settings.daily_budget.minutes)
+                ),
+            );
+        }
+        self.budget_notified = usage.exceeded;
+    }
+
+    /// Looks into the stored day when another day than today is shown
+    fn check_day_start(&mut self, startup: bool) {
+        let settings = self.settings.load();
+        let today = settings.today();
+        let (active_day, db) = (self.active_day.as_ref(), &self.db);
+        let started = || match active_day {
+            Some(day) if day.get_day() == today => day.has_day_start(),
+            _ => match db.load_day(today) {
+                Ok(day) => day.map_or(false, |d| d.has_day_start()),
+                Err(e) => {
+                    log::warn!("Cannot load today: {:?}", e);
+                    true
+                }
+            },
+        };
+        self.day_start_reminder.check(&settings, started, startup);
+    }
+
+    /// A stored entry that booked work of `actions` already
+    fn find_duplicate(&self, actions: &[Action]) -> Option<Action> {
+        let day = self.active_day.as_ref()?;
+        actions.iter().find_map(|a| match a {
+            Action::Work(w) => day.duplicate_of(w).cloned().map(Action::Work),
+            _ => None,
+        })
+    }
+
+    /// The cap of the work time exceeded after storing the actions.
+    ///
+    /// Actions that do not add work time, like the day end or a fixed description,
+    /// are never held
+    fn exceeded_cap(&self, actions: &[Action]) -> Option<String> {
+        let settings = self.settings.load();
+        if settings.max_daily_minutes.is_none() && settings.max_weekly_minutes.is_none() {
+            return None;
+        }
+        let before = self.active_day.as_ref()?;
+        let mut after = before.clone();
+        for action in actions {
+            after.add_action(action.clone());
+        }
+        let progress = load_target_progress(&self.db, &settings, before).and_then(|before| {
+            load_target_progress(&self.db, &settings, &after).map(|after| (before, after))
+        });
+        match progress {
+            Ok((before, after)) => after.exceeded_cap_since(
+                &before,
+                settings.max_daily_minutes,
+                settings.max_weekly_minutes,
+            ),
+            Err(e) => {
+                log::warn!("Cannot check the work time caps: {:?}", e);
+                None
+            }
+        }
+    }
+
+    fn shows_day(&self, day: Day) -> bool {
+        self.active_day.as_ref().map(ActiveDay::get_day) == Some(day)
+    }
+
+    /// Switches to the new day when midnight passed while today was shown, refreshes the
+    /// view after a resume from suspend unless that would drop input
+    fn follow_clock(&mut self) -> Option<Message> {
+        let settings = self.settings.load();
+        let now = settings.timeline.now();
+        let today = settings.today();
+        let resumed = (now - self.last_tick).num_seconds() > 2 * TICK_INTERVAL.as_secs() as i64;
+        let showed_today = self.shows_day(self.last_today);
+        let date_changed = today != self.last_today;
+        self.last_today = today;
+        self.last_tick = now;
+
+        if date_changed && showed_today {
+            Some(Message::ChangeDay(today))
+        } else if resumed && !self.current_view.is_dirty() {
+            Some(Message::RefreshView)
+        } else {
+            None
+        }
+    }
+
+    /// Runs the exports configured for the end of the active day
+    fn after_day_end(&mut self) -> Command<Message> {
+        self.auto_export();
+        let weekly_on = self
+            .settings
+            .load()
+            .email
+            .as_ref()
+            .and_then(|e| e.weekly_on);
+        let mut command = Command::none();
+        if let Some(day) = self.active_day.as_ref().map(ActiveDay::get_day) {
+            if weekly_on == Some(day.day_of_week()) {
+                command = self.mail_report(day, ReportPeriod::Week);
+            }
+        }
+        let post_day = self
+            .settings
+            .load()
+            .webhook
+            .as_ref()
+            .filter(|w| w.on_day_end)
+            .and(self.active_day.as_ref().map(ActiveDay::get_day));
+        match post_day {
+            Some(day) => Command::batch([command, self.post_webhook(day)]),
+            None => command,
+        }
+    }
+
+    /// Starts posting the day to the webhook, the result arrives as [`Message::WebhookDelivered`]
+    fn post_webhook(&mut self, day: Day) -> Command<Message> {
+        let settings = self.settings.load();
+        let body = match &self.active_day {
+            Some(active_day) if active_day.get_day() == day => Ok(Some(active_day.clone())),
+            _ => self.db.load_day(day),
+        }
+        .map_err(|e| format!("Cannot load {}: {:?}", day, e))
+        .and_then(|active_day| active_day.ok_or_else(|| format!("Nothing stored on {}", day)))
+        .and_then(|active_day| export_json(&settings, &active_day));
+        let config = settings
+            .webhook
+            .clone()
+            .ok_or_else(|| "No webhook configured in the settings file".to_string());
+        match config.and_then(|config| body.map(|body| (config, body))) {
+            Ok((config, body)) => Command::perform(
+                background(move || post_json(&config, &body)),
+                move |(attempts, result)| Message::WebhookDelivered(day, attempts, result),
+            ),
+            Err(e) => {
+                self.notices.push(Err(e));
+                Command::none()
+            }
+        }
+    }
+
+    /// Starts fetching the summaries of recent issues without description from Jira,
+    /// each arrives as [`Message::IssueTitleFetched`]
+    fn fetch_issue_titles(&mut self) -> Command<Message> {
+        let settings = self.settings.load();
+        let config = match &settings.jira {
+            Some(config) => config,
+            None => return Command::none(),
+        };
+        let missing = self.recent_issues.borrow().missing_descriptions();
+        let started = self.title_fetches.start(missing, settings.timeline.now());
+        Command::batch(started.into_iter().map(|ident| {
+            let config = config.clone();
+            Command::perform(
+                background(move || {
+                    let summary = fetch_summary(&config, &ident);
+                    (ident, summary)
+                }),
+                |(ident, summary)| Message::IssueTitleFetched(ident, summary),
+            )
+        }))
+    }
+
+    /// Stores the outcome of a webhook post with its day
+    fn webhook_delivered(&mut self, day: Day, delivery: WebhookDelivery) -> Result<(), DBErr> {
+        match &mut self.active_day {
+            Some(active_day) if active_day.get_day() == day => {
+                active_day.set_webhook_delivery(delivery);
+                self.db.store_day(active_day)
+            }
+            _ => match self.db.load_day(day)? {
+                Some(mut stored) => {
+                    stored.set_webhook_delivery(delivery);
+                    self.db.store_day(&stored)
+                }
+                None => Ok(()),
+            },
+        }
+    }
+
+    /// Starts sending the report of the day or its week to the configured recipients,
+    /// the result arrives as [`Message::MailSent`]
+    fn mail_report(&mut self, day: Day, period: ReportPeriod) -> Command<Message> {
+        match self.prepare_report(day, period) {
+            Ok((config, subject, body)) => Command::perform(
+                background(move || -> Result<String, String> {
+                    send_mail(&config, &subject, body)?;
+                    Ok(format!(
+                        "Mailed '{}' to {}",
+                        subject,
+                        config.recipients.join(", ")
+                    ))
+                }),
+                Message::MailSent,
+            ),
+            Err(e) => {
+                self.notices.push(Err(e));
+                Command::none()
+            }
+        }
+    }
+
+    /// Account, subject and text of the report of the day or its week
+    fn prepare_report(
+        &self,
+        day: Day,
+        period: ReportPeriod,
+    ) -> Result<(EmailConfig, String, String), String> {
+        let settings = self.settings.load();
+        let config = settings
+            .email
+            .clone()
+            .ok_or("No mail account configured in the settings file")?;
+        let (first, last, subject) = match period {
+            ReportPeriod::Day => (day, day, format!("Time report {}", day)),
+            ReportPeriod::Week => {
+                let first = day.first_of_week();
+                (first, first + 6, format!("Time report week of {}", first))
+            }
+        };
+        let days = self
+            .db
+            .load_range(first..=last)
+            .map_err(|e| format!("Cannot load the days of the report: {:?}", e))?;
+        if days.is_empty() {
+            return Err(format!("Nothing stored from {} to {}", first, last));
+        }
+        let body = report_text(&settings, &days);
+        Ok((config, subject, body))
+    }
+
+    /// Writes the export file of the active day
+    fn auto_export(&mut self) {
+        let settings = self.settings.load();
+        let day = match &mut self.active_day {
+            Some(day) if settings.auto_export && !settings.export_file_pattern.is_empty() => day,
+            _ => return,
+        };
+        let result = export_text(&settings, day).and_then(|text| {
+            let path = export_path(&settings.export_file_pattern, day.get_day())?;
+            write_export_file(&path, &text)?;
+            Ok((text, path))
+        });
+        self.notices.push(match result {
+            Ok((text, path)) => {
+                let message = format!("Exported {} to {}", day.get_day(), path.display());
+                day.mark_exported_to_file(settings.timeline.now(), text, path);
+                match self.db.store_day(day) {
+                    Ok(()) => {
+                        self.db.exported(day);
+                        Ok(message)
+                    }
+                    Err(e) => Err(format!("{}, but storing the day failed: {:?}", message, e)),
+                }
+            }
+            Err(e) => Err(format!(
+                "Automatic export of {} failed: {}",
+                day.get_day(),
+                e
+            )),
+        });
+    }
+
+    /// Applies changes of the settings file made outside of quarble
+    fn reload_settings(&mut self) -> Option<Message> {
+        // pending changes win over the file
+        self.autosave.flush();
+        let current = self.settings.load_full();
+        let location = current.settings_location.as_ref()?;
+        let before = Box::new(SettingsSer::from_settings(&current));
+        let reloaded = read_settings_file(location)
+            .map_err(|e| format!("{:#}", e))
+            .and_then(|file| current.reload(file));
+        match reloaded {
+            Ok(Some(settings)) => {
+                let settings = Arc::new(settings);
+                self.settings.store(settings.clone());
+                self.saved_settings = settings;
+                self.refresh_progress();
+                self.notices
+                    .push(Ok("Reloaded the changed settings file".to_string()));
+                match self.current_view {
+                    CurrentView::Settings(_) => {
+                        Some(Message::SettingsUi(SettingsUIMessage::FileChanged(before)))
+                    }
+                    _ => None,
+                }
+            }
+            Ok(None) => None,
+            Err(e) => {
+                self.notices
+                    .push(Err(format!("Ignoring the changed settings file: {}", e)));
+                None
+            }
+        }
+    }
 }
 
 impl iced_winit::Program for Quarble {
@@ -103,10 +813,86 @@ impl iced_winit::Program for Quarble {
     type Message = Message;
 
     fn update(&mut self, message: Message) -> Command<Message> {
+        let mut command = Command::none();
         let mut message = Some(message);
+        // the setting may have been changed in the settings view
+        self.db.set_git_commits(self.settings.load().git_commits);
+        self.db.set_hooks(&self.settings.load().hooks);
+        self.transition_confirmed = false;
+        self.delete_confirmed = false;
+        self.store_confirmed = false;
         while let Some(current) = message.take() {
             match current {
-                Message::Error(msg) => self.current_error = msg,
+                Message::SubmitCurrent(_) if self.pending_delete.is_some() => {
+                    message = Some(Message::ConfirmDelete);
+                }
+                Message::Exit if self.pending_delete.is_some() => {
+                    message = Some(Message::CancelDelete);
+                }
+                Message::ConfirmDelete => {
+                    self.delete_confirmed = true;
+                    message = self.pending_delete.take().map(Message::DeleteAction);
+                }
+                Message::CancelDelete => self.pending_delete = None,
+                Message::SubmitCurrent(_) if self.pending_store.is_some() => {
+                    message = Some(Message::ConfirmStore);
+                }
+                Message::Exit if self.pending_store.is_some() => {
+                    message = Some(Message::CancelStore);
+                }
+                Message::ConfirmStore => {
+                    self.store_confirmed = true;
+                    message = self
+                        .pending_store
+                        .take()
+                        .map(|p| Message::StoreActions(p.stay_active, p.actions));
+                }
+                Message::CancelStore => self.pending_store = None,
+                Message::Exit if self.pending_transition.is_some() => {
+                    self.pending_transition = None;
+                }
+                Message::ChangeDay(_)
+                | Message::ChangeView(_)
+                | Message::RequestDayChange
+                | Message::Exit
+                    if self.needs_confirmation(&current) =>
+                {
+                    self.pending_transition = Some(current);
+                }
+                Message::ConfirmTransition(confirmation) => {
+                    let transition = self.pending_transition.take();
+                    match confirmation {
+                        Confirmation::Save => {
+                            self.after_store = transition;
+                            message = self
+                                .current_view
+                                .update(Message::SubmitCurrent(StayActive::Yes));
+                            if message.is_none() {
+                                self.after_store = None;
+                                message = Some(Message::Error(
+                                    "The input is not valid and cannot be stored".to_string(),
+                                ));
+                            }
+                        }
+                        Confirmation::Discard => {
+                            self.transition_confirmed = true;
+                            message = transition;
+                        }
+                        Confirmation::Cancel => (),
+                    }
+                }
+                Message::Error(msg) => {
+                    message = Some(Message::Failed(Arc::new(QuarbleError::Message(msg))));
+                }
+                Message::Failed(error) => {
+                    log::warn!("{:?}", error);
+                    // a failed save keeps the view
+                    self.after_store = None;
+                    self.current_error = Some(error);
+                    self.show_error_details = false;
+                }
+                Message::ToggleErrorDetails => self.show_error_details = !self.show_error_details,
+                Message::DismissError => self.current_error = None,
                 Message::Exit => {
                     self.tab_bar.set_active_view(ViewId::CurrentDayUi);
                     self.current_view = CurrentView::Exit(Exit);
@@ -114,6 +900,40 @@ impl iced_winit::Program for Quarble {
                 Message::UpdateCloseOnSafe(new_value) => update_settings(&self.settings, |s| {
                     s.close_on_safe = new_value;
                 }),
+                Message::WriteSettings => {
+                    // an older pending write must not replace the submitted settings
+                    self.autosave.flush();
+                    let settings = self.settings.load_full();
+                    self.notices.push(match write_settings(&settings) {
+                        Ok(()) => Ok("Settings saved".to_string()),
+                        Err(e) => Err(format!("Cannot store the settings: {:#}", e)),
+                    });
+                    self.saved_settings = settings;
+                }
+                Message::SettingsFileChanged => message = self.reload_settings(),
+                Message::SwitchProfile(profile) => {
+                    let switched = self.settings.load().switch_profile(profile);
+                    message = match DB::init(&switched.db_dir) {
+                        Ok(mut db) => {
+                            self.autosave.flush();
+                            db.set_git_commits(switched.git_commits);
+                            db.set_hooks(&switched.hooks);
+                            self.settings.store(Arc::new(switched));
+                            self.db = db;
+                            self.recent_issues.replace(RecentIssues::new(
+                                self.db.load_recent().unwrap_or_default(),
+                                self.settings.clone(),
+                            ));
+                            let day = self
+                                .active_day
+                                .as_ref()
+                                .map(ActiveDay::get_day)
+                                .unwrap_or_else(|| self.settings.load().today());
+                            Some(Message::ChangeDay(day))
+                        }
+                        Err(e) => Some(db_failure("open the profile database", e)),
+                    };
+                }
                 Message::RequestDayChange => {
                     if let CurrentView::CdUi(ui) = &mut self.current_view {
                         message = ui.update(Message::Cd(CurrentDayMessage::StartDayChange))
@@ -136,18 +956,36 @@ impl iced_winit::Program for Quarble {
                         message = Some(Message::ChangeDay(day))
                     }
                 }
+                Message::ChangeWeek(amount) => {
+                    if let Some(active) = &self.active_day {
+                        message = Some(Message::ChangeDay(active.get_day() + amount * 7))
+                    }
+                }
                 Message::ChangeDay(day) => match self.db.get_day(day) {
                     Ok(day) => {
                         self.active_day = Some(day);
                         message = Some(Message::RefreshView);
                     }
                     Err(e) => {
-                        message = Some(Message::Error(format!("{:?}", e)));
+                        message = Some(db_failure("load the day", e));
                         self.active_day = None;
                     }
                 },
+                Message::Tick => {
+                    command = Command::batch([command, self.fetch_issue_titles()]);
+                    self.check_break_limit();
+                    self.check_daily_budget();
+                    self.check_day_start(false);
+                    if self.shows_day(self.settings.load().today()) {
+                        self.refresh_progress();
+                    }
+                    message = self
+                        .follow_clock()
+                        .or_else(|| self.current_view.update(Message::Tick));
+                }
                 Message::ChangeView(view_id) => {
                     if self.current_view.view_id() != view_id {
+                        self.input_recall = None;
                         self.tab_bar.set_active_view(view_id);
                         self.recent_view.refresh();
                         self.current_view = CurrentView::create(
@@ -156,9 +994,14 @@ impl iced_winit::Program for Quarble {
                             self.recent_issues.clone(),
                             self.active_day.as_ref(),
                         );
+                        message = self.current_view.load_message();
                     }
                 }
                 Message::RefreshView => {
+                    self.refresh_progress();
+                    self.check_break_limit();
+                    self.check_daily_budget();
+                    self.check_day_start(false);
                     self.tab_bar.set_active_view(self.current_view.view_id());
                     self.recent_view.refresh();
                     self.current_view = CurrentView::create(
@@ -167,10 +1010,241 @@ impl iced_winit::Program for Quarble {
                         self.recent_issues.clone(),
                         self.active_day.as_ref(),
                     );
+                    message = self.current_view.load_message();
+                }
+                Message::OpenOnDay(day, then) => match self.db.get_day(day) {
+                    Ok(day) => {
+                        self.active_day = Some(day);
+                        message = Some(*then);
+                    }
+                    Err(e) => message = Some(db_failure("load the day", e)),
+                },
+                Message::BookRange(start, end) => {
+                    self.tab_bar.set_active_view(ViewId::BookSingle);
+                    self.recent_view.refresh();
+                    self.current_view = CurrentView::create(
+                        ViewId::BookSingle,
+                        self.settings.clone(),
+                        self.recent_issues.clone(),
+                        self.active_day.as_ref(),
+                    );
+                    let input = format!("{} {} ", start, end);
+                    message = Some(Message::Bs(BookSingleMessage::TextChanged(input)));
                 }
                 Message::Reset => {
                     message = Some(Message::ChangeView(self.initial_view));
                 }
+                Message::IssueInput(filter) => {
+                    message = self.recent_view.update(Message::IssueInput(filter));
+                }
+                Message::LoadIssueStats(ident) => {
+                    // the statistics cover all stored days
+                    let db = self.db.clone();
+                    let settings = self.settings.load_full();
+                    let load = Command::perform(
+                        background(move || load_issue_stats(&db, &settings, &ident)),
+                        |result| match result {
+                            Ok(stats) => Message::Stats(IssueStatsMessage::Loaded(Arc::new(stats))),
+                            Err(e) => db_failure("load the issue statistics", e),
+                        },
+                    );
+                    command = Command::batch([command, load]);
+                }
+                Message::LoadComments(ident) => {
+                    let recent = self
+                        .recent_issues
+                        .borrow()
+                        .list_recent()
+                        .iter()
+                        .find(|r| r.issue.ident == ident)
+                        .and_then(|r| r.issue.default_action.clone());
+                    let db = self.db.clone();
+                    let load = Command::perform(
+                        background(move || load_comment_history(&db, &ident, recent.as_deref())),
+                        |result| match result {
+                            Ok(history) => {
+                                Message::Bs(BookSingleMessage::CommentsLoaded(Arc::new(history)))
+                            }
+                            Err(e) => db_failure("load previous comments", e),
+                        },
+                    );
+                    command = Command::batch([command, load]);
+                }
+                Message::LoadWeekSummary(day) => {
+                    // the balance covers all stored days
+                    let db = self.db.clone();
+                    let settings = self.settings.load_full();
+                    let load = Command::perform(
+                        background(move || load_week_summary(&db, &settings, day)),
+                        |result| match result {
+                            Ok((summary, balance)) => Message::Stats(
+                                IssueStatsMessage::WeekLoaded(Arc::new(summary), Arc::new(balance)),
+                            ),
+                            Err(e) => db_failure("load the week summary", e),
+                        },
+                    );
+                    command = Command::batch([command, load]);
+                }
+                Message::LoadOpenIssue(day) => {
+                    message = match self.db.open_issue_before(day) {
+                        Ok(issue) => Some(Message::Sw(DayStartWizardMessage::Loaded(issue))),
+                        Err(e) => Some(db_failure("load the open work", e)),
+                    };
+                }
+                Message::LoadWeekCalendar(day) => {
+                    let db = self.db.clone();
+                    let load = Command::perform(
+                        background(move || load_week_calendar(&db, day)),
+                        |result| match result {
+                            Ok(calendar) => {
+                                Message::Cal(WeekCalendarMessage::Loaded(Arc::new(calendar)))
+                            }
+                            Err(e) => db_failure("load the week calendar", e),
+                        },
+                    );
+                    command = Command::batch([command, load]);
+                }
+                Message::LoadYearOverview(year) => {
+                    let db = self.db.clone();
+                    let settings = self.settings.load_full();
+                    let load = Command::perform(
+                        background(move || load_year_overview(&db, &settings, year)),
+                        |result| match result {
+                            Ok(overview) => {
+                                Message::Year(YearOverviewMessage::Loaded(Arc::new(overview)))
+                            }
+                            Err(e) => db_failure("load the year overview", e),
+                        },
+                    );
+                    command = Command::batch([command, load]);
+                }
+                Message::LoadDaysToFix => {
+                    let db = self.db.clone();
+                    let settings = self.settings.load_full();
+                    let load = Command::perform(
+                        background(move || load_days_to_fix(&db, &settings)),
+                        |result| match result {
+                            Ok(days) => Message::Fix(ToFixMessage::Loaded(Arc::new(days))),
+                            Err(e) => db_failure("check the last workdays", e),
+                        },
+                    );
+                    command = Command::batch([command, load]);
+                }
+                Message::LoadLocationReport(day) => {
+                    let db = self.db.clone();
+                    let load = Command::perform(
+                        background(move || load_location_report(&db, day)),
+                        |result| match result {
+                            Ok(report) => {
+                                Message::Stats(IssueStatsMessage::LocationsLoaded(Arc::new(report)))
+                            }
+                            Err(e) => db_failure("load the location report", e),
+                        },
+                    );
+                    command = Command::batch([command, load]);
+                }
+                Message::LoadWorkPatterns(first, last) => {
+                    let db = self.db.clone();
+                    let settings = self.settings.load_full();
+                    let load = Command::perform(
+                        background(move || load_work_patterns(&db, &settings, first, last)),
+                        |result| match result {
+                            Ok(patterns) => Message::Stats(IssueStatsMessage::PatternsLoaded(
+                                Arc::new(patterns),
+                            )),
+                            Err(e) => db_failure("load the work patterns", e),
+                        },
+                    );
+                    command = Command::batch([command, load]);
+                }
+                Message::LoadUtilization(first, last) => {
+                    let db = self.db.clone();
+                    let settings = self.settings.load_full();
+                    let load = Command::perform(
+                        background(move || load_utilization(&db, &settings, first, last)),
+                        |result| match result {
+                            Ok(report) => Message::Stats(IssueStatsMessage::UtilizationLoaded(
+                                Arc::new(report),
+                            )),
+                            Err(e) => db_failure("load the utilization report", e),
+                        },
+                    );
+                    command = Command::batch([command, load]);
+                }
+                Message::LoadInvoice(first, last) => {
+                    let db = self.db.clone();
+                    let settings = self.settings.load_full();
+                    let load = Command::perform(
+                        background(move || load_invoice(&db, &settings, first, last)),
+                        |result| match result {
+                            Ok(invoice) => {
+                                Message::Stats(IssueStatsMessage::InvoiceLoaded(Arc::new(invoice)))
+                            }
+                            Err(e) => db_failure("load the invoice", e),
+                        },
+                    );
+                    command = Command::batch([command, load]);
+                }
+                Message::PreviewReassign {
+                    from,
+                    to,
+                    first,
+                    last,
+                } => {
+                    let db = self.db.clone();
+                    let load = Command::perform(
+                        background(move || load_reassignments(&db, &from, &to, first, last)),
+                        |result| match result {
+                            Ok(preview) => {
+                                Message::Ra(ReassignMessage::PreviewLoaded(Arc::new(preview)))
+                            }
+                            Err(e) => db_failure("load the reassignment preview", e),
+                        },
+                    );
+                    command = Command::batch([command, load]);
+                }
+                Message::ApplyReassign(reassignments) => {
+                    let db = self.db.clone();
+                    let apply = Command::perform(
+                        background(move || {
+                            apply_reassignments(&db, &reassignments)
+                                .map(|count| (reassignments, count))
+                        }),
+                        |result| match result {
+                            Ok((reassignments, count)) => Message::Reassigned(reassignments, count),
+                            Err(e) => db_failure("reassign the issue", e),
+                        },
+                    );
+                    command = Command::batch([command, apply]);
+                }
+                Message::Reassigned(reassignments, count) => {
+                    let active = self.active_day.as_ref().map(|d| d.get_day());
+                    if let Some(day) = active.filter(|d| reassignments.iter().any(|r| r.day == *d))
+                    {
+                        self.active_day = self.db.get_day(day).ok();
+                    }
+                    message = Some(Message::Ra(ReassignMessage::Applied(count)));
+                }
+                Message::ImportRecentIssues(issues, mode) => {
+                    self.recent_issues.import(&issues, mode);
+                    self.store_recent();
+                }
+                Message::PinIssue(ident) => {
+                    self.recent_issues.pin(&ident);
+                    self.store_recent();
+                }
+                Message::UnpinIssue(ident) => {
+                    self.recent_issues.unpin(&ident);
+                    self.store_recent();
+                }
+                Message::ToggleProject(project) => {
+                    // stored with the settings by the autosave
+                    update_settings(&self.settings, |s| {
+                        if !s.collapsed_projects.remove(&project) {
+                            s.collapsed_projects.insert(project);
+                        }
+                    });
+                }
                 Message::NextTab => {
                     message = self.tab_bar.select_next().map(Message::ChangeView);
                 }
@@ -187,12 +1261,30 @@ impl iced_winit::Program for Quarble {
                     );
                     self.tab_bar.set_active_view(self.current_view.view_id());
                 }
+                Message::StoreActions(..)
+                | Message::ModifyActions { .. }
+                | Message::DeleteAction(_)
+                | Message::SetDayNotes(_)
+                    if self.active_day.as_ref().map_or(false, ActiveDay::is_closed) =>
+                {
+                    message = Some(Message::Error(DAY_CLOSED.to_string()));
+                }
+                Message::DeleteAction(delete)
+                    if !self.delete_confirmed && self.settings.load().confirm_deletes =>
+                {
+                    self.pending_delete = Some(delete);
+                }
                 Message::DeleteAction(DeleteAction(_stay_active, action)) => {
                     if let Some(ref mut active_day) = self.active_day {
-                        if active_day.actions_mut().remove(&action) {
+                        let replaced = [action];
+                        let day = active_day.get_day();
+                        if let Err(e) = self.db.check_continuations(day, &[], &replaced) {
+                            message = Some(db_failure("delete the entry", e));
+                        } else if active_day.actions_mut().remove(&replaced[0]) {
                             message = match self.db.store_day(active_day) {
-                                Ok(()) => Some(Message::RefreshView),
-                                Err(e) => Some(Message::Error(format!("{:?}", e))),
+                                Ok(()) => store_continuations(&self.db, day, Vec::new(), &replaced)
+                                    .or(Some(Message::RefreshView)),
+                                Err(e) => Some(db_failure("store the day", e)),
                             }
                         } else {
                             message =
@@ -200,20 +1292,160 @@ impl iced_winit::Program for Quarble {
                         }
                     }
                 }
+                Message::StopRunningWork => {
+                    message = self.running_work().and_then(|(running, now)| {
+                        self.store_banner_actions(vec![Action::WorkEnd(WorkEnd {
+                            ts: now,
+                            task: running.task,
+                        })])
+                    });
+                }
+                Message::ContinueLastIssue => {
+                    let work = self
+                        .today_now()
+                        .and_then(|now| self.active_day.as_ref()?.continue_last_issue(now));
+                    message = match work {
+                        Some(work) => {
+                            self.notices.push(Ok(format!(
+                                "Continued {} from {}",
+                                work.task.ident, work.start
+                            )));
+                            self.store_banner_actions(vec![Action::Work(work)])
+                        }
+                        None => Some(Message::Error(
+                            "Nothing to continue today, or work is running".to_string(),
+                        )),
+                    };
+                }
+                Message::SwitchRunningWork(index) => {
+                    let running = self.running_work();
+                    let issue = self
+                        .switch_candidates(running.as_ref().map(|(r, _)| r))
+                        .into_iter()
+                        .nth(index);
+                    if let (Some(issue), Some(now)) = (issue, self.today_now()) {
+                        let start = WorkStart {
+                            ts: now,
+                            description: issue.default_action.clone().unwrap_or_default(),
+                            task: issue,
+                        };
+                        let actions = switch_actions(running.map(|(r, _)| r), start);
+                        message = self.store_banner_actions(actions);
+                    }
+                }
+                Message::ToggleDayClosed => {
+                    if let Some(ref mut active_day) = self.active_day {
+                        active_day.set_closed(!active_day.is_closed());
+                        message = match self.db.store_day(active_day) {
+                            Ok(()) => Some(Message::RefreshView),
+                            Err(e) => Some(db_failure("store the day", e)),
+                        };
+                    }
+                }
+                Message::OpenLink(url) => open_link::open_link(&url),
+                Message::SetDayNotes(notes) => {
+                    if let Some(ref mut active_day) = self.active_day {
+                        active_day.set_notes(notes);
+                        message = match self.db.store_day(active_day) {
+                            Ok(()) => Some(Message::RefreshView),
+                            Err(e) => Some(db_failure("store the day", e)),
+                        };
+                    }
+                }
+                Message::MarkExported(day, text) => {
+                    message = match self.active_day {
+                        Some(ref mut active_day) if active_day.get_day() == day => {
+                            let now = self.settings.load().timeline.now();
+                            active_day.mark_exported(now, text.to_string());
+                            match self.db.store_day(active_day) {
+                                Ok(()) => {
+                                    self.db.exported(active_day);
+                                    Some(Message::WriteClipboard(text))
+                                }
+                                Err(e) => Some(db_failure("store the day", e)),
+                            }
+                        }
+                        _ => Some(Message::WriteClipboard(text)),
+                    };
+                }
+                Message::MarkExportedToFile(day, text, path) => {
+                    if let Some(ref mut active_day) = self.active_day {
+                        if active_day.get_day() == day {
+                            let now = self.settings.load().timeline.now();
+                            active_day.mark_exported_to_file(now, text.to_string(), path);
+                            message = match self.db.store_day(active_day) {
+                                Ok(()) => {
+                                    self.db.exported(active_day);
+                                    None
+                                }
+                                Err(e) => Some(db_failure("store the day", e)),
+                            };
+                        }
+                    }
+                }
                 Message::StoreAction(stay_active, action) => {
+                    message = Some(Message::StoreActions(stay_active, vec![action]));
+                }
+                Message::StoreForwarded(day, action) => {
+                    let store = Message::StoreAction(StayActive::Yes, action);
+                    // keep the unsaved changes of the open day
+                    message = match self.active_day {
+                        Some(ref active_day) if active_day.get_day() == day => Some(store),
+                        _ => Some(Message::OpenOnDay(day, Box::new(store))),
+                    };
+                }
+                Message::StoreActions(stay_active, actions) if !self.store_confirmed => {
+                    let hold = self
+                        .find_duplicate(&actions)
+                        .map(StoreHold::Duplicate)
+                        .or_else(|| self.exceeded_cap(&actions).map(StoreHold::WorkTimeCap));
+                    match hold {
+                        Some(hold) => {
+                            self.pending_store = Some(PendingStore {
+                                stay_active,
+                                actions,
+                                hold,
+                            })
+                        }
+                        None => {
+                            self.store_confirmed = true;
+                            message = Some(Message::StoreActions(stay_active, actions));
+                        }
+                    }
+                }
+                Message::StoreActions(stay_active, actions) => {
+                    let day_end = actions.iter().any(|a| matches!(a, Action::DayEnd(_)));
                     if let Some(ref mut active_day) = self.active_day {
-                        if let Some(issue) = action.issue() {
-                            self.recent_issues
-                                .issue_used_with_comment(issue, action.description())
+                        let settings = self.settings.load();
+                        let (actions, continuations) = split_at_midnight(&settings, actions);
+                        let day = active_day.get_day();
+                        if let Err(e) = self.db.check_continuations(day, &continuations, &[]) {
+                            message = Some(db_failure("store the next day", e));
+                        } else {
+                            for action in actions {
+                                if let Some(issue) = action.issue() {
+                                    self.recent_issues
+                                        .issue_used_with_comment(issue, action.description())
+                                }
+                                if let Action::DayStart(_) = action {
+                                    active_day.record_zone(settings.timeline.utc_offset());
+                                }
+                                active_day.add_closing_action(action, settings.auto_close_work);
+                            }
+                            message = store_active_day(
+                                &self.db,
+                                &self.autosave,
+                                &settings,
+                                stay_active,
+                                active_day,
+                                self.recent_view.export_data(),
+                            );
+                            message =
+                                store_continuations(&self.db, day, continuations, &[]).or(message);
                         }
-                        active_day.add_action(action);
-                        message = store_active_day(
-                            &self.db,
-                            &self.settings.load(),
-                            stay_active,
-                            active_day,
-                            self.recent_view.export_data(),
-                        );
+                    }
+                    if day_end && matches!(message, Some(Message::StoreSuccess(_))) {
+                        command = Command::batch([command, self.after_day_end()]);
                     }
                 }
                 Message::ModifyAction {
@@ -221,28 +1453,60 @@ impl iced_winit::Program for Quarble {
                     orig,
                     update,
                 } => {
+                    message = Some(Message::ModifyActions {
+                        stay_active,
+                        orig: vec![*orig],
+                        update: vec![*update],
+                    });
+                }
+                Message::ModifyActions {
+                    stay_active,
+                    orig,
+                    update,
+                } => {
+                    let day_end = update.iter().any(|a| matches!(a, Action::DayEnd(_)));
                     if let Some(ref mut active_day) = self.active_day {
+                        let settings = self.settings.load();
+                        let (update, continuations) = split_at_midnight(&settings, update);
+                        let day = active_day.get_day();
                         let actions = active_day.actions_mut();
-                        if actions.remove(&orig) {
-                            if let Some(issue) = update.issue() {
-                                self.recent_issues
-                                    .issue_used_with_comment(issue, update.description());
+                        if !orig.iter().all(|o| actions.contains(o)) {
+                            message = Some(Message::Error(
+                                "Could not update action. Did not find original".to_string(),
+                            ));
+                        } else if let Err(e) =
+                            self.db.check_continuations(day, &continuations, &orig)
+                        {
+                            message = Some(db_failure("store the next day", e));
+                        } else {
+                            for o in &orig {
+                                actions.remove(o);
+                            }
+                            for update in update {
+                                if let Some(issue) = update.issue() {
+                                    self.recent_issues
+                                        .issue_used_with_comment(issue, update.description());
+                                }
+                                actions.insert(update);
                             }
-                            actions.insert(*update);
 
                             message = store_active_day(
                                 &self.db,
-                                &self.settings.load(),
+                                &self.autosave,
+                                &settings,
                                 stay_active,
                                 active_day,
                                 self.recent_view.export_data(),
                             );
-                        } else {
-                            message = Some(Message::Error(
-                                "Could not update action. Did not find original".to_string(),
-                            ));
+                            // the continuations of the original actions are removed, even if
+                            // the update ends before midnight
+                            message = store_continuations(&self.db, day, continuations, &orig)
+                                .or(message);
                         }
                     }
+                    if day_end && matches!(message, Some(Message::StoreSuccess(_))) {
+                        command = Command::batch([command, self.after_day_end()]);
+                    }
                 }
                 Message::CopyValue => match self.current_view.view_id() {
                     ViewId::Export => {
@@ -260,38 +1524,203 @@ impl iced_winit::Program for Quarble {
                     }
                     _ => (),
                 },
+                Message::CopyTextSummary => {
+                    message = Some(Message::WriteClipboard(Arc::new(self.text_summary())));
+                }
                 Message::ReadClipboard => {
                     let clipboard = iced_native::command::Action::Clipboard(
                         clipboard::Action::Read(Box::new(Message::ClipboardValue)),
                     );
-                    return Command::single(clipboard);
+                    command = Command::single(clipboard);
                 }
                 Message::WriteClipboard(value) => {
                     let clipboard = iced_native::command::Action::Clipboard(
                         clipboard::Action::Write(value.to_string()),
                     );
-                    return Command::single(clipboard);
+                    command = Command::single(clipboard);
+                }
+                Message::RestoreDraft => {
+                    let draft = self.pending_draft.take();
+                    if let Some((view_id, draft)) =
+                        draft.and_then(|d| Some((ViewId::from_name(&d.view)?, d)))
+                    {
+                        self.tab_bar.set_active_view(view_id);
+                        self.current_view = CurrentView::create(
+                            view_id,
+                            self.settings.clone(),
+                            self.recent_issues.clone(),
+                            self.active_day.as_ref(),
+                        );
+                        message = self.current_view.restore_draft(draft.input);
+                    }
+                }
+                Message::ReplayJournal | Message::RollBackJournal => {
+                    if let Some(journal) = self.interrupted_write.take() {
+                        let result = if let Message::ReplayJournal = current {
+                            self.db.replay_journal(&journal)
+                        } else {
+                            self.db.roll_back_journal(&journal)
+                        };
+                        let day = self
+                            .active_day
+                            .as_ref()
+                            .map(ActiveDay::get_day)
+                            .unwrap_or_else(|| self.settings.load().today());
+                        message = match result {
+                            Ok(()) => Some(Message::ChangeDay(day)),
+                            Err(e) => Some(db_failure("recover the interrupted write", e)),
+                        };
+                    }
+                }
+                Message::DiscardDraft => {
+                    self.pending_draft = None;
+                    self.clear_draft();
+                }
+                Message::DismissNotice => self.notices.clear(),
+                Message::ChooseExportFile(path) => {
+                    let choose =
+                        Command::perform(background(move || choose_export_file(&path)), |chosen| {
+                            Message::Export(DayExportMessage::FileChosen(chosen))
+                        });
+                    command = Command::batch([command, choose]);
+                }
+                Message::MailReport(day, period) => {
+                    command = Command::batch([command, self.mail_report(day, period)]);
+                }
+                Message::MailSent(result) => self.notices.push(result),
+                Message::GitSynced(status) => self.tab_bar.set_git_status(status),
+                Message::PostWebhook(day) => {
+                    command = Command::batch([command, self.post_webhook(day)]);
+                }
+                Message::IssueTitleFetched(ident, result) => {
+                    let now = self.settings.load().timeline.now();
+                    match result {
+                        Ok(summary) => {
+                            self.title_fetches.fetched(&ident);
+                            self.recent_issues.set_missing_description(&ident, &summary);
+                            self.store_recent();
+                        }
+                        Err(e @ JiraError::Unreachable(_)) => {
+                            log::warn!("Cannot fetch the summary of {}: {}", ident, e);
+                            self.title_fetches.unreachable(&ident, now);
+                        }
+                        Err(e) => {
+                            log::warn!("Cannot fetch the summary of {}: {}", ident, e);
+                            self.title_fetches.refused(&ident);
+                        }
+                    }
+                }
+                Message::WebhookDelivered(day, attempts, result) => {
+                    let delivery = WebhookDelivery {
+                        at: self.settings.load().timeline.now(),
+                        attempts,
+                        error: result.err(),
+                    };
+                    let notice = format!("{}: {}", day, delivery.label());
+                    self.notices.push(match delivery.error {
+                        None => Ok(notice),
+                        Some(_) => Err(notice),
+                    });
+                    message = match self.webhook_delivered(day, delivery) {
+                        Err(e) => Some(db_failure("store the day", e)),
+                        Ok(()) if self.current_view.view_id() == ViewId::Export => {
+                            Some(Message::RefreshView)
+                        }
+                        Ok(()) => None,
+                    };
+                }
+                Message::SubmitCurrent(stay_active)
+                    if self.current_view.view_id().has_input_history() =>
+                {
+                    self.submitted_input = self
+                        .current_view
+                        .draft()
+                        .map(|input| (self.current_view.view_id(), input.to_string()));
+                    message = self
+                        .current_view
+                        .update(Message::SubmitCurrent(stay_active));
+                }
+                Message::Up | Message::Down
+                    if self.current_view.view_id().recalls_with_arrows() =>
+                {
+                    let step = if matches!(current, Message::Up) {
+                        -1
+                    } else {
+                        1
+                    };
+                    message = self.recall_input(step);
+                }
+                Message::RecallInput(step) if self.current_view.view_id().has_input_history() => {
+                    message = self.recall_input(step);
+                }
+                Message::StoreSuccess(stay_active) => {
+                    self.remember_input();
+                    self.clear_draft();
+                    self.refresh_progress();
+                    self.check_break_limit();
+                    self.check_daily_budget();
+                    // the stored input is no longer dirty
+                    self.transition_confirmed = true;
+                    message = match self.after_store.take() {
+                        Some(transition) => Some(transition),
+                        None => self.current_view.update(Message::StoreSuccess(stay_active)),
+                    };
                 }
                 m => message = self.current_view.update(m),
             }
         }
-        Command::none()
+        self.sync_draft();
+        let commits = self.db.take_commits();
+        if !commits.is_empty() {
+            command = Command::batch([command, self.sync_git(commits)]);
+        }
+        // the formats and colors may have been changed in the settings view
+        let settings = self.settings.load_full();
+        locale::set_display_formats(settings.time_format, settings.date_format);
+        style::set_high_contrast(settings.high_contrast);
+        if !Arc::ptr_eq(&settings, &self.saved_settings) {
+            self.autosave.settings(settings.clone());
+            self.saved_settings = settings;
+        }
+        if matches!(&self.current_view, CurrentView::Exit(_)) {
+            self.autosave.flush();
+            // the event loop ends the process without dropping the listener
+            if let Some(instance) = &self.instance {
+                instance.close();
+            }
+        }
+        command
     }
 
     fn view(&mut self) -> Element<'_, Self::Message, Self::Renderer> {
         let view_id = self.current_view.view_id();
+        let summary = self
+            .settings
+            .load()
+            .text_summary
+            .then(|| self.text_summary());
+        let running = self.running_work().map(|(running, now)| {
+            let switch_to = self.switch_candidates(Some(&running));
+            (running, now, switch_to)
+        });
         let content = self.current_view.view();
         let element = Container::new(content).padding(Padding::new(style::WINDOW_PADDING));
 
         let mut main = Column::new();
         main = main.push(self.tab_bar.view());
-        if !self.current_error.is_empty() {
+        if let Some((running, now, switch_to)) = &running {
+            main = main.push(self.work_banner.view(running, *now, switch_to));
+        }
+        if let Some(reminder) = self.day_start_reminder.view() {
+            main = main.push(reminder);
+        }
+        if let Some(error) = &self.current_error {
             main = main.push(
-                Container::new(
-                    Text::new(&self.current_error)
-                        .color(style::ERROR_COLOR)
-                        .size(20),
-                )
+                Container::new(error_panel(
+                    error,
+                    self.show_error_details,
+                    &mut self.error_buttons,
+                ))
                 .padding([
                     style::WINDOW_PADDING,
                     style::WINDOW_PADDING,
@@ -302,12 +1731,121 @@ impl iced_winit::Program for Quarble {
             )
         }
 
+        if !self.notices.is_empty() {
+            let notices = self.notices.iter().map(|notice| {
+                let (text, color) = match notice {
+                    Ok(text) => (text, style::SUCCESS_COLOR),
+                    Err(text) => (text, style::ERROR_COLOR),
+                };
+                Text::new(text).color(color).into()
+            });
+            main = main.push(
+                Container::new(Row::with_children(vec![
+                    Column::with_children(notices.collect()).into(),
+                    h_space(style::SPACE),
+                    style::inline_button(&mut self.notice_button, "Dismiss")
+                        .on_press(Message::DismissNotice)
+                        .into(),
+                ]))
+                .padding([
+                    style::WINDOW_PADDING,
+                    style::WINDOW_PADDING,
+                    0,
+                    style::WINDOW_PADDING,
+                ]),
+            );
+        }
+        if let Some(journal) = &self.interrupted_write {
+            main = main.push(prompt(
+                format!(
+                    "Storing {} was interrupted, keep the unsaved changes?",
+                    journal.pending.get_day()
+                ),
+                &mut self.journal_buttons,
+                vec![
+                    ("Replay", Message::ReplayJournal),
+                    ("Roll back", Message::RollBackJournal),
+                ],
+            ));
+        }
+        if let Some(draft) = &self.pending_draft {
+            main = main.push(prompt(
+                format!("Restore unsaved input '{}'?", draft.input),
+                &mut self.draft_buttons,
+                vec![
+                    ("Restore", Message::RestoreDraft),
+                    ("Discard", Message::DiscardDraft),
+                ],
+            ));
+        }
+        if let Some(DeleteAction(_, action)) = &self.pending_delete {
+            main = main
+                .push(prompt(
+                    "Delete this entry?".to_string(),
+                    &mut self.delete_buttons,
+                    vec![
+                        ("Delete (Enter)", Message::ConfirmDelete),
+                        ("Cancel (Esc)", Message::CancelDelete),
+                    ],
+                ))
+                .push(Container::new(action_row(action)).padding([
+                    style::SPACE_PX,
+                    style::WINDOW_PADDING,
+                    0,
+                    style::WINDOW_PADDING,
+                ]));
+        }
+        if let Some(pending) = &self.pending_store {
+            let title = match &pending.hold {
+                StoreHold::Duplicate(_) => "The work is already booked by this entry".to_string(),
+                StoreHold::WorkTimeCap(cap) => cap.clone(),
+            };
+            main = main.push(prompt(
+                title,
+                &mut self.store_buttons,
+                vec![
+                    ("Store anyway (Enter)", Message::ConfirmStore),
+                    ("Cancel (Esc)", Message::CancelStore),
+                ],
+            ));
+            if let StoreHold::Duplicate(existing) = &pending.hold {
+                main = main.push(Container::new(action_row(existing)).padding([
+                    style::SPACE_PX,
+                    style::WINDOW_PADDING,
+                    0,
+                    style::WINDOW_PADDING,
+                ]));
+            }
+        }
+        if self.pending_transition.is_some() {
+            main = main.push(prompt(
+                "The input was not stored yet".to_string(),
+                &mut self.transition_buttons,
+                vec![
+                    ("Save", Message::ConfirmTransition(Confirmation::Save)),
+                    ("Discard", Message::ConfirmTransition(Confirmation::Discard)),
+                    (
+                        "Cancel (Esc)",
+                        Message::ConfirmTransition(Confirmation::Cancel),
+                    ),
+                ],
+            ));
+        }
+
         main = main.push(element);
         if view_id.show_recent() {
             main = main
                 .push(v_space(style::SPACE))
                 .push(Container::new(self.recent_view.view()).padding(style::WINDOW_PADDING));
         }
+        if let Some(summary) = summary {
+            main = main.push(
+                Container::new(text(summary))
+                    .style(style::ContentStyle)
+                    .width(Length::Fill)
+                    .padding(style::WINDOW_PADDING),
+            );
+        }
 
         let main: QElement = main.into();
         if self.settings.load().debug {
@@ -325,10 +1863,19 @@ impl iced_winit::Application for Quarble {
         let db = flags.db;
 
         let settings = flags.settings;
-        let active_day = db.get_day(Day::today()).map(Option::from);
+        let interrupted_write = db.interrupted_write().unwrap_or_else(|e| {
+            log::warn!("Cannot read journal: {:?}", e);
+            None
+        });
+        let today = settings.load().today();
+        let first_view = match db.load_day(today) {
+            Ok(None) => flags.initial_view.for_new_day(),
+            _ => flags.initial_view,
+        };
+        let active_day = db.get_day(today).map(Option::from);
         let (initial_message, active_day) = match active_day {
             Ok(active_day) => (None, active_day),
-            Err(e) => (Some(Message::Error(format!("{:?}", e))), None),
+            Err(e) => (Some(db_failure("load the day", e)), None),
         };
 
         let recent = db.load_recent().unwrap_or_default();
@@ -336,31 +1883,85 @@ impl iced_winit::Application for Quarble {
         let recent_issues = RecentIssuesRef::new(recent_issues);
 
         let current_view = CurrentView::create(
-            flags.initial_view,
+            first_view,
             settings.clone(),
             recent_issues.clone(),
             active_day.as_ref(),
         );
+        let initial_message = initial_message.or_else(|| current_view.load_message());
+
+        let recent_view = RecentIssuesView::create(recent_issues.clone(), settings.clone());
+        let stored_draft = db.load_draft().unwrap_or_else(|e| {
+            log::warn!("Cannot load draft: {:?}", e);
+            None
+        });
 
-        let recent_view = RecentIssuesView::create(recent_issues.clone());
+        let input_history = db.load_input_history().unwrap_or_else(|e| {
+            log::warn!("Cannot load the input history: {:?}", e);
+            InputHistory::default()
+        });
 
+        let last_today = settings.load().today();
+        let last_tick = settings.load().timeline.now();
+        let saved_settings = settings.load_full();
         let mut quarble = Quarble {
             current_view,
             settings,
             db,
             active_day,
             initial_view: flags.initial_view,
-            tab_bar: TabBar::new(flags.initial_view),
+            tab_bar: TabBar::new(first_view),
             recent_view,
             recent_issues,
-            current_error: String::new(),
+            current_error: None,
+            show_error_details: false,
+            error_buttons: Default::default(),
+            pending_draft: stored_draft.clone(),
+            stored_draft,
+            draft_buttons: Default::default(),
+            interrupted_write,
+            journal_buttons: Default::default(),
+            pending_transition: None,
+            after_store: None,
+            transition_confirmed: false,
+            transition_buttons: Default::default(),
+            pending_delete: None,
+            delete_confirmed: false,
+            delete_buttons: Default::default(),
+            pending_store: None,
+            store_confirmed: false,
+            store_buttons: Default::default(),
+            input_history,
+            input_recall: None,
+            submitted_input: None,
+            title_fetches: TitleFetches::default(),
+            break_notified: false,
+            budget_notified: false,
+            notices: Vec::new(),
+            notice_button: button::State::new(),
+            instance: flags.instance,
+            work_banner: WorkBanner::default(),
+            day_start_reminder: DayStartReminder::default(),
+            last_today,
+            last_tick,
+            autosave: Autosave::start(),
+            saved_settings,
         };
+        quarble.refresh_progress();
+        quarble.check_break_limit();
+        quarble.check_daily_budget();
+        quarble.check_day_start(true);
 
         let command = if let Some(initial_message) = initial_message {
             quarble.update(initial_message)
         } else {
             Command::none()
         };
+        let command = Command::batch([
+            command,
+            quarble.fetch_issue_titles(),
+            quarble.sync_git(Vec::new()),
+        ]);
 
         (quarble, command)
     }
@@ -370,7 +1971,22 @@ impl iced_winit::Application for Quarble {
     }
 
     fn subscription(&self) -> Subscription<Self::Message> {
-        iced_winit::subscription::events_with(keyboard_handler::global_keyboard_handler)
+        let keyboard =
+            iced_winit::subscription::events_with(keyboard_handler::global_keyboard_handler);
+        let mut subscriptions = vec![
+            keyboard,
+            clock::every(TICK_INTERVAL),
+            self.autosave.subscription(),
+        ];
+        subscriptions.extend(self.instance.as_ref().map(InstanceListener::subscription));
+        subscriptions.extend(
+            self.settings
+                .load()
+                .settings_location
+                .clone()
+                .map(settings_watcher::watch),
+        );
+        Subscription::batch(subscriptions)
     }
 
     fn should_exit(&self) -> bool {
@@ -378,23 +1994,360 @@ impl iced_winit::Application for Quarble {
     }
 }
 
+/// The error message, with its diagnostics when expanded
+fn error_panel<'a>(
+    error: &QuarbleError,
+    show_details: bool,
+    buttons: &'a mut [button::State; 3],
+) -> QElement<'a> {
+    let [details_button, copy_button, dismiss_button] = buttons;
+    let diagnostics = error.diagnostics();
+    let details_label = if show_details {
+        "Hide details"
+    } else {
+        "Details"
+    };
+    let mut panel = Column::new().push(Row::with_children(vec![
+        Text::new(error.to_string())
+            .color(style::ERROR_COLOR)
+            .size(20)
+            .into(),
+        h_space(style::SPACE),
+        style::inline_button(details_button, details_label)
+            .on_press(Message::ToggleErrorDetails)
+            .into(),
+        h_space(style::SPACE),
+        style::inline_button(copy_button, "Copy diagnostics")
+            .on_press(Message::WriteClipboard(Arc::new(diagnostics.clone())))
+            .into(),
+        h_space(style::SPACE),
+        style::inline_button(dismiss_button, "Dismiss")
+            .on_press(Message::DismissError)
+            .into(),
+    ]));
+    if show_details {
+        panel = panel
+            .push(v_space(style::SPACE))
+            .push(Text::new(diagnostics));
+    }
+    panel.into()
+}
+
+/// A question with a button for each answer, shown above the current view
+fn prompt<'a>(
+    question: String,
+    buttons: &'a mut [button::State],
+    answers: Vec<(&str, Message)>,
+) -> QElement<'a> {
+    let mut row = vec![Text::new(question).into()];
+    for (state, (label, on_press)) in buttons.iter_mut().zip(answers) {
+        row.push(h_space(style::SPACE));
+        row.push(style::inline_button(state, label).on_press(on_press).into());
+    }
+    Container::new(Row::with_children(row))
+        .padding([
+            style::WINDOW_PADDING,
+            style::WINDOW_PADDING,
+            0,
+            style::WINDOW_PADDING,
+        ])
+        .into()
+}
+
 fn store_active_day(
     db: &DB,
+    autosave: &Autosave,
     settings: &Settings,
     stay_active: StayActive,
     active_day: &ActiveDay,
     recent_data: RecentIssuesData,
 ) -> Option<Message> {
-    let issue_store_msg = match db.store_day(active_day) {
+    autosave.recent(db, recent_data);
+    match db.store_day(active_day) {
         Ok(()) => Some(Message::StoreSuccess(stay_active.apply_settings(settings))),
-        Err(e) => Some(Message::Error(format!("{:?}", e))),
+        Err(e) => Some(db_failure("store the day", e)),
+    }
+}
+
+/// Number of weeks shown in the issue statistics
+const STATS_WEEKS: usize = 12;
+
+/// Number of stored days searched for previous comments of an issue
+const COMMENT_HISTORY_DAYS: usize = 60;
+
+/// Interval of [Message::Tick]
+const TICK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Error for modifications of a closed day
+const DAY_CLOSED: &str = "The day is closed, unlock it first (ctrl+K)";
+
+/// Normalizer for statistics, does not add breaks
+fn stats_normalizer(settings: &Settings) -> Normalizer {
+    Normalizer {
+        resolution: NonZeroU32::new(settings.resolution.num_minutes() as u32)
+            .unwrap_or_else(|| NonZeroU32::new(1).unwrap()),
+        breaks_config: settings.breaks.clone(),
+        combine_bookings: true,
+        add_break: false,
+        home_zone: settings.home_zone,
+        holidays: settings.holidays.clone(),
+    }
+}
+
+/// The stored days in `range` that can be normalized, the others are logged and left out of
+/// the `purpose`
+fn normalized_range(
+    db: &DB,
+    settings: &Settings,
+    range: impl RangeBounds<Day>,
+    purpose: &str,
+) -> Result<Vec<NormalizedDay>, DBErr> {
+    let normalizer = stats_normalizer(settings);
+    Ok(db
+        .load_range(range)?
+        .iter()
+        .filter_map(|active_day| normalized(&normalizer, active_day, purpose))
+        .collect())
+}
+
+fn normalized(
+    normalizer: &Normalizer,
+    active_day: &ActiveDay,
+    purpose: &str,
+) -> Option<NormalizedDay> {
+    match normalizer.create_normalized(active_day) {
+        Ok(normalized) => Some(normalized),
+        Err(e) => {
+            log::warn!(
+                "Day {} not included in {}: {}",
+                active_day.get_day(),
+                purpose,
+                e
+            );
+            None
+        }
+    }
+}
+
+fn load_issue_stats(db: &DB, settings: &Settings, ident: &str) -> Result<IssueStats, DBErr> {
+    let days = normalized_range(db, settings, .., "statistics")?;
+    Ok(IssueStats::collect(
+        ident,
+        settings.today(),
+        STATS_WEEKS,
+        &days,
+    ))
+}
+
+/// Comments of the issue in the recent issues and the last [COMMENT_HISTORY_DAYS] stored days,
+/// unreadable days are skipped
+fn load_comment_history(
+    db: &DB,
+    ident: &str,
+    recent: Option<&str>,
+) -> Result<CommentHistory, DBErr> {
+    let stored = db.list_days(..)?;
+    let mut days = Vec::new();
+    for day in stored.into_iter().rev().take(COMMENT_HISTORY_DAYS) {
+        match db.load_day(day) {
+            Ok(active_day) => days.extend(active_day),
+            Err(e) => log::warn!("Skipping {} in the previous comments: {}", day, e),
+        }
+    }
+    Ok(CommentHistory::collect(ident, recent, &days))
+}
+
+/// Summary of the week of `day` and the compensation balance of all days
+fn load_week_summary(
+    db: &DB,
+    settings: &Settings,
+    day: Day,
+) -> Result<(WeekSummary, ZaBalance), DBErr> {
+    let today = settings.today();
+    let days = normalized_range(db, settings, .., "week summary")?;
+    // today counts once its end is stored
+    let last_complete = match db.load_day(today)? {
+        Some(active_day) if active_day.has_day_end() => today,
+        _ => today.prev_day(),
+    };
+
+    Ok((
+        WeekSummary::collect(day, &days),
+        ZaBalance::collect(settings.daily_target_minutes, last_complete, &days),
+    ))
+}
+
+/// Work of `current` and the week until `current` against the configured targets, the
+/// earlier days of the week are loaded from the database
+fn load_target_progress(
+    db: &DB,
+    settings: &Settings,
+    current: &ActiveDay,
+) -> Result<TargetProgress, DBErr> {
+    let purpose = "target progress";
+    let day = current.get_day();
+    let mut days = normalized_range(db, settings, day.first_of_week()..day, purpose)?;
+    days.extend(normalized(&stats_normalizer(settings), current, purpose));
+
+    Ok(WeekSummary::collect(day, &days).progress(
+        day,
+        settings.daily_target_minutes,
+        settings.weekly_target_minutes,
+    ))
+}
+
+fn load_week_calendar(db: &DB, day: Day) -> Result<WeekCalendar, DBErr> {
+    let first_day = day.first_of_week();
+    let days = db.load_range(first_day..=first_day + 6)?;
+    Ok(WeekCalendar::collect(first_day, &days))
+}
+
+fn load_year_overview(db: &DB, settings: &Settings, year: i32) -> Result<YearOverview, DBErr> {
+    let year_days = Day::ymd(year, 1, 1)..=Day::ymd(year, 12, 31);
+    let days = normalized_range(db, settings, year_days, "year overview")?;
+    Ok(YearOverview::collect(year, &days))
+}
+
+fn load_days_to_fix(db: &DB, settings: &Settings) -> Result<Vec<DayToFix>, DBErr> {
+    let today = settings.today();
+    let forwarder = HolidayForwarder {
+        holidays: settings.holidays.clone(),
     };
+    let workdays = DayToFix::workdays_before(today, CHECKED_WORKDAYS, &forwarder);
+    let first = workdays.last().copied().unwrap_or(today);
+    let stored = db.load_range(first..=today)?;
+    Ok(DayToFix::check(
+        &workdays,
+        &stored,
+        &stats_normalizer(settings),
+    ))
+}
+
+fn load_reassignments(
+    db: &DB,
+    from: &str,
+    to: &JiraIssue,
+    first: Day,
+    last: Day,
+) -> Result<Vec<DayReassignment>, DBErr> {
+    let mut result = Vec::new();
+    for active_day in db.load_range(first..=last)? {
+        result.extend(DayReassignment::collect(&active_day, from, to));
+    }
+    Ok(result)
+}
+
+/// Stores all days or none, if any of them changed since the preview
+fn apply_reassignments(db: &DB, reassignments: &[DayReassignment]) -> Result<usize, DBErr> {
+    let mut days = Vec::new();
+    for r in reassignments {
+        let mut day = db.load_day(r.day)?.ok_or(DBErr::DayMissing(r.day))?;
+        if day.is_closed() {
+            return Err(DBErr::DayClosed(r.day));
+        }
+        if !r.apply(&mut day) {
+            return Err(DBErr::DayChanged(r.day));
+        }
+        days.push(day);
+    }
+    for day in &days {
+        db.store_day(day)?;
+    }
+    Ok(reassignments.iter().map(|r| r.changes.len()).sum())
+}
+
+fn load_location_report(db: &DB, day: Day) -> Result<LocationReport, DBErr> {
+    let (first_day, last_day) = (day.first_of_month(), day.last_of_month());
+    let days = db.load_range(first_day..=last_day)?;
+    Ok(LocationReport::collect(first_day, last_day, &days))
+}
+
+fn load_work_patterns(
+    db: &DB,
+    settings: &Settings,
+    first: Day,
+    last: Day,
+) -> Result<WorkPatterns, DBErr> {
+    let days = normalized_range(db, settings, first..=last, "work patterns")?;
+    Ok(WorkPatterns::collect(
+        first,
+        last,
+        &days,
+        &settings.meeting_prefixes,
+    ))
+}
+
+fn load_utilization(
+    db: &DB,
+    settings: &Settings,
+    first: Day,
+    last: Day,
+) -> Result<UtilizationReport, DBErr> {
+    let days = normalized_range(db, settings, first..=last, "utilization report")?;
+    Ok(UtilizationReport::collect(
+        first,
+        last,
+        &days,
+        &settings.project_targets,
+    ))
+}
+
+fn load_invoice(db: &DB, settings: &Settings, first: Day, last: Day) -> Result<Invoice, DBErr> {
+    let days = normalized_range(db, settings, first..=last, "invoice")?;
+    Ok(Invoice::collect(first, last, &days, &settings.hourly_rates))
+}
+
+fn apply_time_precision(settings: &Settings, action: Action) -> Action {
+    if settings.seconds_precision {
+        action
+    } else {
+        action.map_times(Time::without_seconds)
+    }
+}
 
-    if let Err(e) = db.store_recent(&recent_data) {
-        eprintln!("Storing recent issues failed: {:?}", e);
+/// Asks for the file to save the export to, starting in the directory of `prefill`.
+///
+/// The dialog blocks until it is closed, it must not run on the UI thread
+fn choose_export_file(prefill: &Path) -> Option<PathBuf> {
+    let mut dialog = rfd::FileDialog::new().set_title("Save the export");
+    if let Some(dir) = prefill.parent().filter(|d| d.is_dir()) {
+        dialog = dialog.set_directory(dir);
+    }
+    if let Some(name) = prefill.file_name() {
+        dialog = dialog.set_file_name(name.to_string_lossy());
     }
+    dialog.save_file()
+}
+
+/// Reports the failed DB access while trying to do `action`
+fn db_failure(action: &'static str, source: DBErr) -> Message {
+    Message::Failed(Arc::new(QuarbleError::Db { action, source }))
+}
 
-    issue_store_msg
+/// Applies the time precision and splits the actions crossing midnight, returns the
+/// actions of the day and their continuations on the following day
+fn split_at_midnight(settings: &Settings, actions: Vec<Action>) -> (Vec<Action>, Vec<Action>) {
+    let mut continuations = Vec::new();
+    let actions = actions
+        .into_iter()
+        .map(|action| {
+            let (action, continuation) = apply_time_precision(settings, action).split_at_midnight();
+            continuations.extend(continuation);
+            action
+        })
+        .collect();
+    (actions, continuations)
+}
+
+fn store_continuations(
+    db: &DB,
+    day: Day,
+    continuations: Vec<Action>,
+    replaced: &[Action],
+) -> Option<Message> {
+    db.store_continuations(day, continuations, replaced)
+        .err()
+        .map(|e| db_failure("store the next day", e))
 }
 
 trait MainView {
@@ -402,9 +2355,25 @@ trait MainView {
 
     fn update(&mut self, msg: Message) -> Option<Message>;
 
+    /// Input that is not stored yet, None if the view does not keep drafts.
+    /// Edits of existing entries are no drafts, they would be restored as new entries.
+    fn draft(&self) -> Option<&str> {
+        None
+    }
+
+    /// The view has input that would be lost when leaving it
+    fn is_dirty(&self) -> bool {
+        self.draft().map_or(false, |d| !d.trim().is_empty())
+    }
+
     fn handle_keyboard_event(&self, _: Event, _: iced_winit::event::Status) -> Option<Message> {
         None
     }
+
+    /// The state of the view as plain text, iced widgets are invisible to screen readers
+    fn text_summary(&self) -> Option<String> {
+        None
+    }
 }
 
 type QElement<'a> = Element<'a, Message, <Quarble as iced_winit::Program>::Renderer>;
@@ -424,14 +2393,22 @@ impl MainView for Exit {
 
 fn day_info_message(d: Option<&ActiveDay>) -> String {
     if let Some(d) = d {
+        let day = format!("{} {}", d.get_day(), d.get_day().week_label());
         match min_max_booked(d.actions()) {
-            (None, None) => format!("{} - nothing booked", d.get_day()),
-            (Some(start), None) | (None, Some(start)) => {
-                format!("{}: first action on {}", d.get_day(), start)
-            }
-            (Some(start), Some(end)) => {
-                format!("{}: booked from {} to {}", d.get_day(), start, end)
-            }
+            (None, None) => format!("{} - nothing booked", day),
+            (Some(start), None) | (None, Some(start)) => format!(
+                "{} ({}): first action on {}",
+                day,
+                d.location().name(),
+                start
+            ),
+            (Some(start), Some(end)) => format!(
+                "{} ({}): booked from {} to {}",
+                day,
+                d.location().name(),
+                start,
+                end
+            ),
         }
     } else {
         "No day selected".to_string()
@@ -468,7 +2445,8 @@ fn min_max_booked(actions: &BTreeSet<Action>) -> (Option<Time>, Option<Time>) {
 fn unbooked_time_for_day(actions: &BTreeSet<Action>) -> Vec<TimeRange> {
     let mut result = Vec::new();
     let mut current_limit = TimeRange::default();
-    for action in actions {
+    // standby may overlap booked work
+    for action in actions.iter().filter(|a| !matches!(a, Action::Standby(_))) {
         let (min, max) = action.times();
         let (f, s) = if let Some(max) = max {
             let sep = TimeRange::new(min, max);
@@ -496,6 +2474,30 @@ fn text<'a>(t: impl Into<String>) -> QElement<'a> {
     Text::new(t).into()
 }
 
+/// The issue of the shortcut typed into an issue input
+fn shortcut_preview(settings: &Settings, input: &str) -> Option<String> {
+    let (_, ch, issue) = settings.issue_parser.shortcut_in(input)?;
+    Some(format!(
+        "{} = {}{} (Ctrl+Space: next shortcut)",
+        ch,
+        issue.ident,
+        issue
+            .description
+            .as_deref()
+            .map(|d| format!(" {}", d))
+            .unwrap_or_default()
+    ))
+}
+
+/// Replaces the shortcut typed into an issue input by the next one, the cursor stays where it was
+fn cycle_shortcut(
+    settings: &Settings,
+    input: &str,
+    changed: impl FnOnce(String) -> Message,
+) -> Option<Message> {
+    settings.issue_parser.cycle_shortcut(input).map(changed)
+}
+
 fn time_info<'a>(now: Time, v: ParseResult<Time, ()>) -> QElement<'a> {
     Text::new(
         v.get_with_default(now)
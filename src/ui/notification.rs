@@ -0,0 +1,16 @@
+use std::process::Command;
+
+/// Shows a desktop notification with `notify-send`, failures are only logged
+pub fn desktop_notification(summary: &str, body: &str) {
+    let mut command = Command::new("notify-send");
+    command.arg("--app-name=Quarble").arg(summary).arg(body);
+    let summary = summary.to_string();
+    // does not block the UI if the notification daemon is slow
+    std::thread::spawn(move || match command.status() {
+        Ok(status) if !status.success() => {
+            log::warn!("Cannot show notification '{}': {}", summary, status)
+        }
+        Ok(_) => (),
+        Err(e) => log::warn!("Cannot show notification '{}': {:?}", summary, e),
+    });
+}
@@ -0,0 +1,14 @@
+use std::process::Command;
+
+/// Opens `url` with `xdg-open`, failures are only logged
+pub fn open_link(url: &str) {
+    let mut command = Command::new("xdg-open");
+    command.arg(url);
+    let url = url.to_string();
+    // the browser may take a while to start
+    std::thread::spawn(move || match command.status() {
+        Ok(status) if !status.success() => log::warn!("Cannot open '{}': {}", url, status),
+        Ok(_) => (),
+        Err(e) => log::warn!("Cannot open '{}': {:?}", url, e),
+    });
+}
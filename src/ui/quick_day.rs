@@ -0,0 +1,297 @@
+use iced_native::widget::{text_input, Column, Row};
+
+use crate::conf::SettingsRef;
+use crate::data::{Absence, Action, ActiveDay, DayEnd, RecentIssues, RecentIssuesRef};
+use crate::parsing::parse_result::ParseResult;
+use crate::parsing::time::Time;
+use crate::parsing::time_limit::TimeRange;
+use crate::parsing::time_relative::TimeRelative;
+use crate::ui::book_single::WorkBuilder;
+use crate::ui::fast_day_start::DayStartBuilder;
+use crate::ui::issue_start_edit::IssueStartBuilder;
+use crate::ui::stay_active::StayActive;
+use crate::ui::top_bar::TopBar;
+use crate::ui::util::{h_space, v_space};
+use crate::ui::widget::TextInput;
+use crate::ui::{day_info_message, style, text, unbooked_time, MainView, Message, QElement};
+use crate::Settings;
+
+#[derive(Clone, Debug)]
+pub enum QuickDayMessage {
+    TextChanged(String),
+}
+
+/// Books a whole day from one line, e.g. `o8 | 9 930 M-1 daily | 16 PROJ-2 review | e17`.
+///
+/// Absences are booked with their category, e.g. `vacation 8 12 3h51m` credits 3h 51m
+/// instead of the 4 hours towards the daily target
+pub struct QuickDay {
+    top_bar: TopBar,
+    input_state: text_input::State,
+    input: String,
+    settings: SettingsRef,
+    recent_issues: RecentIssuesRef,
+    limits: Vec<TimeRange>,
+    last_end: Option<Time>,
+    actions: Vec<ParseResult<Action, ()>>,
+}
+
+impl QuickDay {
+    pub fn for_active_day(
+        settings: SettingsRef,
+        recent_issues: RecentIssuesRef,
+        active_day: Option<&ActiveDay>,
+    ) -> Box<Self> {
+        let now = settings.load().timeline.time_now();
+        let last_end = active_day.and_then(|d| d.last_action_end(now));
+        Box::new(Self {
+            top_bar: TopBar {
+                title: "Quick day:",
+                help_text: "<day start> | <work, issue start or absence> | ... | e<day end>",
+                info: day_info_message(active_day),
+                settings: settings.clone(),
+            },
+            input_state: text_input::State::focused(),
+            input: String::new(),
+            settings,
+            recent_issues,
+            limits: unbooked_time(active_day),
+            last_end,
+            actions: Vec::new(),
+        })
+    }
+
+    fn update_input(&mut self, input: String) {
+        self.input = input;
+        self.actions = parse_quick_day(
+            &self.settings.load(),
+            &self.recent_issues.borrow(),
+            &self.limits,
+            self.last_end,
+            &self.input,
+        );
+    }
+
+    fn try_build(&self) -> Option<Vec<Action>> {
+        if self.actions.is_empty() {
+            return None;
+        }
+        self.actions.iter().map(|a| a.get_ref().cloned()).collect()
+    }
+
+    fn on_submit(&mut self, stay_active: StayActive) -> Option<Message> {
+        self.try_build()
+            .map(|actions| Message::StoreActions(stay_active, actions))
+    }
+}
+
+/// Parses the `|` separated parts of the input with the parsers of the single edit views.
+///
+/// A part is a day end (`e<time>`), an absence (`<category> <start> <end> [credited]`),
+/// a day start (`<location><time>`), booked work or the start of an issue, tried in this order.
+fn parse_quick_day(
+    settings: &Settings,
+    recent_issues: &RecentIssues,
+    limits: &[TimeRange],
+    mut last_end: Option<Time>,
+    input: &str,
+) -> Vec<ParseResult<Action, ()>> {
+    let mut result = Vec::new();
+    for part in input.split('|').map(str::trim).filter(|p| !p.is_empty()) {
+        let action = parse_part(settings, recent_issues, limits, last_end, part);
+        if let Some(end) = action.get_ref().and_then(Action::action_end) {
+            last_end = Some(end);
+        }
+        result.push(action);
+    }
+    result
+}
+
+fn parse_part(
+    settings: &Settings,
+    recent_issues: &RecentIssues,
+    limits: &[TimeRange],
+    last_end: Option<Time>,
+    part: &str,
+) -> ParseResult<Action, ()> {
+    if let Some(rest) = part.strip_prefix('e') {
+        if let (ParseResult::Valid(ts), "") = Time::parse_with_offset(&settings.timeline, rest) {
+            return ParseResult::Valid(Action::DayEnd(DayEnd { ts }));
+        }
+    }
+
+    if let Some(absence) = parse_absence(settings, part) {
+        return absence;
+    }
+
+    if part.starts_with(char::is_alphabetic) {
+        let mut day_start = DayStartBuilder::new(settings.locations.clone());
+        day_start.parse_value(&settings.timeline, limits, part);
+        if let Some(day_start) = day_start.try_build(&settings.timeline) {
+            return ParseResult::Valid(day_start.into());
+        }
+    }
+
+    let mut work = WorkBuilder::default();
+    work.parse_input(settings, recent_issues, last_end, part);
+    if let Some(work) = work.try_build(settings.timeline.time_now()) {
+        return ParseResult::Valid(work.into());
+    }
+
+    let mut issue_start = IssueStartBuilder::default();
+    issue_start.parse_input(settings, last_end, recent_issues, part);
+    match issue_start.try_build() {
+        Some(issue_start) => ParseResult::Valid(issue_start.into()),
+        None => ParseResult::Invalid(()),
+    }
+}
+
+/// `None` if the part does not start with one of the configured absence categories
+fn parse_absence(settings: &Settings, part: &str) -> Option<ParseResult<Action, ()>> {
+    let (category, rest) = part.split_once(char::is_whitespace)?;
+    let category = settings
+        .absence_categories
+        .iter()
+        .find(|c| c.name.eq_ignore_ascii_case(category))?;
+    let (start, rest) = Time::parse_prefix(rest.trim_start());
+    let (end, rest) = Time::parse_prefix(rest.trim_start());
+    let (credited, rest) = TimeRelative::parse_duration(rest.trim_start());
+    let credited_minutes = match credited {
+        ParseResult::None => None,
+        ParseResult::Valid(credited) => Some(credited.offset_minutes() as u32),
+        _ => return Some(ParseResult::Invalid(())),
+    };
+    Some(match (start, end) {
+        (ParseResult::Valid(start), ParseResult::Valid(end))
+            if start < end && rest.trim().is_empty() =>
+        {
+            ParseResult::Valid(Action::Absence(Absence {
+                category: category.name.clone(),
+                start,
+                end,
+                credited_minutes,
+            }))
+        }
+        _ => ParseResult::Invalid(()),
+    })
+}
+
+impl MainView for QuickDay {
+    fn draft(&self) -> Option<&str> {
+        Some(self.input.as_str())
+    }
+
+    fn view(&mut self) -> QElement {
+        let input = TextInput::new(&mut self.input_state, "", &self.input, |i| {
+            Message::Qd(QuickDayMessage::TextChanged(i))
+        });
+
+        let mut parts = vec![
+            self.top_bar.view(),
+            v_space(style::SPACE),
+            input.into(),
+            v_space(style::SPACE),
+        ];
+        for (index, action) in self.actions.iter().enumerate() {
+            let action = match action {
+                ParseResult::Valid(a) => a.to_string(),
+                _ => "invalid".to_string(),
+            };
+            parts.push(
+                Row::with_children(vec![
+                    text(format!("{}:", index + 1)),
+                    h_space(style::SPACE),
+                    text(action),
+                ])
+                .into(),
+            );
+        }
+
+        Column::with_children(parts).into()
+    }
+
+    fn update(&mut self, msg: Message) -> Option<Message> {
+        match msg {
+            Message::Qd(QuickDayMessage::TextChanged(input)) => {
+                self.update_input(input);
+                None
+            }
+            Message::SubmitCurrent(stay_active) => self.on_submit(stay_active),
+            Message::StoreSuccess(stay_active) => stay_active.on_main_view_store(),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::conf::into_settings_ref;
+    use crate::data::test_support::{day_end, day_start, issue_start, time, work};
+    use crate::data::{Absence, Action, ActiveDay, Location, RecentIssuesRef};
+    use crate::parsing::parse_result::ParseResult;
+    use crate::ui::quick_day::{QuickDay, QuickDayMessage};
+    use crate::ui::stay_active::StayActive;
+    use crate::ui::{MainView, Message};
+    use crate::util::{StaticTimeline, TimelineProvider};
+    use crate::Settings;
+    use std::sync::Arc;
+
+    #[test]
+    fn parses_all_parts() {
+        let timeline = StaticTimeline::parse("2022-01-20 18:00");
+        let today = timeline.today();
+        let settings = into_settings_ref(Settings {
+            timeline: Arc::new(timeline),
+            ..Settings::default()
+        });
+        let recent = RecentIssuesRef::empty(settings.clone());
+        let mut ui = QuickDay::for_active_day(
+            settings,
+            recent,
+            Some(&ActiveDay::new(today, Location::Office, None)),
+        );
+
+        ui.update(Message::Qd(QuickDayMessage::TextChanged(
+            "o8 | 9 930 M-1 daily | 16 PROJ-2 review# | e17".to_string(),
+        )));
+        assert_eq!(
+            ui.actions,
+            vec![
+                ParseResult::Valid(day_start("o8")),
+                ParseResult::Valid(work("9", "9:30", "M-1", "daily")),
+                ParseResult::Valid(issue_start("16", "PROJ-2", "review", "review")),
+                ParseResult::Valid(day_end("17")),
+            ]
+        );
+        assert!(matches!(
+            ui.update(Message::SubmitCurrent(StayActive::Yes)),
+            Some(Message::StoreActions(_, actions)) if actions.len() == 4
+        ));
+
+        ui.update(Message::Qd(QuickDayMessage::TextChanged(
+            "o8 | x | e17".to_string(),
+        )));
+        assert_eq!(ui.actions[1], ParseResult::Invalid(()));
+        assert!(ui.update(Message::SubmitCurrent(StayActive::Yes)).is_none());
+
+        ui.update(Message::Qd(QuickDayMessage::TextChanged(
+            "Doctor 8 10 | doctor 10 12 1h30m | doctor 12 11".to_string(),
+        )));
+        let absence = |start, end, credited_minutes| {
+            ParseResult::Valid(Action::Absence(Absence {
+                category: Absence::DOCTOR.to_string(),
+                start: time(start),
+                end: time(end),
+                credited_minutes,
+            }))
+        };
+        assert_eq!(
+            ui.actions,
+            vec![
+                absence("8", "10", None),
+                absence("10", "12", Some(90)),
+                ParseResult::Invalid(()),
+            ]
+        );
+    }
+}
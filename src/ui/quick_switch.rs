@@ -0,0 +1,215 @@
+use iced_native::widget::{text_input, Column, Row};
+
+use crate::conf::SettingsRef;
+use crate::data::{ActiveDay, JiraIssue, RecentIssuesRef, WorkStart};
+use crate::parsing::parse_result::ParseResult;
+use crate::parsing::{insert_issue, IssueParsed, IssueParser, IssueParserWithRecent};
+use crate::ui::recent_issues_view::filter_message;
+use crate::ui::stay_active::StayActive;
+use crate::ui::top_bar::TopBar;
+use crate::ui::util::{h_space, v_space};
+use crate::ui::widget::TextInput;
+use crate::ui::work_banner::switch_actions;
+use crate::ui::{
+    cycle_shortcut, day_info_message, shortcut_preview, style, text, MainView, Message, QElement,
+};
+
+#[derive(Clone, Debug)]
+pub enum QuickSwitchMessage {
+    TextChanged(String),
+}
+
+/// Ends the running work and starts another issue now, with a single store
+#[derive(Debug)]
+pub struct QuickSwitch {
+    top_bar: TopBar,
+    input_state: text_input::State,
+    input: String,
+    settings: SettingsRef,
+    recent_issues: RecentIssuesRef,
+    recent_filter: String,
+    /// Work running when the view was opened
+    running: Option<WorkStart>,
+    issue: ParseResult<JiraIssue, ()>,
+    comment: Option<String>,
+}
+
+impl QuickSwitch {
+    pub fn for_active_day(
+        settings: SettingsRef,
+        recent_issues: RecentIssuesRef,
+        active_day: Option<&ActiveDay>,
+    ) -> Box<QuickSwitch> {
+        let now = settings.load().timeline.time_now();
+        let running = active_day.and_then(|d| d.running_work(now)).cloned();
+        let info = match &running {
+            Some(r) => format!(
+                "{}, running {} since {}",
+                day_info_message(active_day),
+                r.task.ident,
+                r.ts
+            ),
+            None => format!("{}, nothing running", day_info_message(active_day)),
+        };
+        Box::new(Self {
+            top_bar: TopBar {
+                title: "Switch to:",
+                help_text: "[issue] <comment>",
+                info,
+                settings: settings.clone(),
+            },
+            input_state: text_input::State::focused(),
+            input: String::new(),
+            settings,
+            recent_issues,
+            recent_filter: String::new(),
+            running,
+            issue: ParseResult::None,
+            comment: None,
+        })
+    }
+
+    fn update_input(&mut self, input: String) -> Option<Message> {
+        self.input = input;
+        let settings = self.settings.load();
+        let recent = self.recent_issues.borrow();
+        let parser = IssueParserWithRecent::new(&settings.issue_parser, &recent);
+        let IssueParsed {
+            r: issue,
+            input: matching,
+            rest,
+        } = parser.parse_task(self.input.trim_start());
+
+        let rest = rest.trim();
+        self.comment = if rest.is_empty() {
+            issue
+                .get_ref()
+                .and_then(|e| e.default_action.clone().or_else(|| e.description.clone()))
+        } else {
+            Some(rest.to_string())
+        };
+        self.issue = issue;
+        filter_message(&mut self.recent_filter, matching)
+    }
+
+    fn on_submit(&self, stay_active: StayActive) -> Option<Message> {
+        let issue = self.issue.get_ref()?;
+        let start = WorkStart {
+            ts: self.settings.load().timeline.time_now(),
+            task: issue.clone(),
+            description: self.comment.clone().unwrap_or_default(),
+        };
+        Some(Message::StoreActions(
+            stay_active,
+            switch_actions(self.running.clone(), start),
+        ))
+    }
+}
+
+impl MainView for QuickSwitch {
+    fn draft(&self) -> Option<&str> {
+        Some(self.input.as_str())
+    }
+
+    fn view(&mut self) -> QElement {
+        let input = TextInput::new(&mut self.input_state, "", &self.input, |i| {
+            Message::Qs(QuickSwitchMessage::TextChanged(i))
+        });
+        let issue = match &self.issue {
+            ParseResult::Valid(issue) => issue.ident.as_str(),
+            ParseResult::None | ParseResult::Incomplete => "<none>",
+            _ => "<invalid>",
+        };
+
+        let mut content = vec![self.top_bar.view(), v_space(style::SPACE), input.into()];
+        if let Some(preview) = shortcut_preview(&self.settings.load(), &self.input) {
+            content.push(text(preview));
+        }
+        content.push(v_space(style::SPACE));
+        content.push(
+            Row::with_children(vec![
+                text("Issue:"),
+                h_space(style::SPACE),
+                text(issue),
+                h_space(style::DSPACE),
+                text("Comment:"),
+                h_space(style::SPACE),
+                text(self.comment.as_deref().unwrap_or("<none>")),
+            ])
+            .into(),
+        );
+
+        Column::with_children(content).into()
+    }
+
+    fn update(&mut self, msg: Message) -> Option<Message> {
+        match msg {
+            Message::InsertIssue(ident) => {
+                self.input_state.move_cursor_to_end();
+                let input = insert_issue(&self.input, &ident);
+                Some(Message::Qs(QuickSwitchMessage::TextChanged(input)))
+            }
+            Message::CycleShortcut => cycle_shortcut(&self.settings.load(), &self.input, |input| {
+                Message::Qs(QuickSwitchMessage::TextChanged(input))
+            }),
+            Message::Qs(QuickSwitchMessage::TextChanged(input)) => self.update_input(input),
+            Message::SubmitCurrent(stay_active) => self.on_submit(stay_active),
+            Message::StoreSuccess(stay_active) => stay_active.on_main_view_store(),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::data::test_support::*;
+    use crate::data::{
+        Action, ActiveDayBuilder, JiraIssue, Location, RecentIssuesRef, WorkEnd, WorkStart,
+    };
+    use crate::ui::quick_switch::{QuickSwitch, QuickSwitchMessage};
+    use crate::ui::stay_active::StayActive;
+    use crate::ui::{MainView, Message};
+    use crate::util::{StaticTimeline, Timeline, TimelineProvider};
+    use crate::Settings;
+
+    #[test]
+    fn ends_running_work_and_starts_the_issue() {
+        let timeline: Timeline = StaticTimeline::parse("2022-01-20 10:15").into();
+        let settings = Settings {
+            timeline: timeline.clone(),
+            ..Settings::default()
+        }
+        .into_settings_ref();
+        let day = ActiveDayBuilder {
+            day: timeline.today(),
+            main_location: Location::Office,
+            active_issue: None,
+            actions: vec![issue_start("9", "A-1", "", "dev")],
+        }
+        .build();
+        let recent = RecentIssuesRef::empty(settings.clone());
+
+        let mut ui = QuickSwitch::for_active_day(settings, recent, Some(&day));
+        ui.update(Message::Qs(QuickSwitchMessage::TextChanged(
+            "B-2 meeting".to_string(),
+        )));
+
+        match ui.update(Message::SubmitCurrent(StayActive::Yes)) {
+            Some(Message::StoreActions(_, actions)) => assert_eq!(
+                actions,
+                vec![
+                    Action::WorkEnd(WorkEnd {
+                        ts: time("10:15"),
+                        task: JiraIssue::create("A-1").unwrap(),
+                    }),
+                    Action::WorkStart(WorkStart {
+                        ts: time("10:15"),
+                        task: JiraIssue::create("B-2").unwrap(),
+                        description: "meeting".to_string(),
+                    }),
+                ]
+            ),
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,275 @@
+use std::sync::Arc;
+
+use iced_core::Length;
+use iced_native::widget::{button, text_input, Column, Row};
+
+use crate::conf::SettingsRef;
+use crate::data::{ActiveDay, Day, DayReassignment, JiraIssue};
+use crate::parsing::parse_result::ParseResult;
+use crate::ui::current_day::action_row;
+use crate::ui::focus_handler::{focus_next, focus_previous};
+use crate::ui::util::{h_space, v_space};
+use crate::ui::widget::TextInput;
+use crate::ui::{style, text, MainView, Message, QElement};
+use crate::util::Timeline;
+
+#[derive(Clone, Debug)]
+pub enum ReassignMessage {
+    FromChanged(String),
+    ToChanged(String),
+    RangeChanged(String),
+    Preview,
+    PreviewLoaded(Arc<Vec<DayReassignment>>),
+    Applied(usize),
+}
+
+/// Replaces an issue in all actions of a day or a range of days
+pub struct ReassignView {
+    settings: SettingsRef,
+    active_day: Option<Day>,
+    inputs: [text_input::State; 3],
+    from: String,
+    to: String,
+    range: String,
+    preview: Option<Arc<Vec<DayReassignment>>>,
+    preview_button: button::State,
+    apply_button: button::State,
+    applied: Option<usize>,
+}
+
+impl ReassignView {
+    pub fn for_active_day(settings: SettingsRef, active_day: Option<&ActiveDay>) -> Box<Self> {
+        let mut inputs: [text_input::State; 3] = Default::default();
+        inputs[0] = text_input::State::focused();
+        Box::new(Self {
+            settings,
+            active_day: active_day.map(|d| d.get_day()),
+            inputs,
+            from: String::new(),
+            to: String::new(),
+            range: String::new(),
+            preview: None,
+            preview_button: button::State::new(),
+            apply_button: button::State::new(),
+            applied: None,
+        })
+    }
+
+    fn request_preview(&self) -> Option<Message> {
+        let from = self.from.trim();
+        if from.is_empty() {
+            return None;
+        }
+        let to = JiraIssue::create(self.to.trim()).ok()?;
+        let (first, last) = parse_range(&self.settings.load().timeline, &self.range)
+            .get()
+            .or_else(|| self.active_day.map(|d| (d, d)))?;
+        Some(Message::PreviewReassign {
+            from: from.to_string(),
+            to,
+            first,
+            last,
+        })
+    }
+
+    fn apply(&self) -> Option<Message> {
+        self.preview
+            .as_ref()
+            .filter(|p| !p.is_empty())
+            .map(|p| Message::ApplyReassign(p.clone()))
+    }
+
+    fn preview_view(preview: &[DayReassignment]) -> QElement {
+        if preview.is_empty() {
+            return text("No entries found");
+        }
+        let mut rows = Vec::new();
+        for day in preview {
+            rows.push(text(format!("{}:", day.day)));
+            for (orig, updated) in &day.changes {
+                rows.push(action_row(orig));
+                rows.push(Row::with_children(vec![h_space(style::DSPACE), text("->")]).into());
+                rows.push(action_row(updated));
+            }
+            rows.push(v_space(style::SPACE));
+        }
+        Column::with_children(rows).into()
+    }
+}
+
+fn input<'a>(
+    state: &'a mut text_input::State,
+    placeholder: &str,
+    value: &str,
+    error: bool,
+    f: fn(String) -> ReassignMessage,
+) -> QElement<'a> {
+    TextInput::new(state, placeholder, value, move |v| Message::Ra(f(v)))
+        .style(style::TextInput { error })
+        .width(Length::Units(200))
+        .into()
+}
+
+/// An empty input is the active day, otherwise a single day or `<first>..<last>`
+fn parse_range(timeline: &Timeline, input: &str) -> ParseResult<(Day, Day), ()> {
+    let input = input.trim();
+    if input.is_empty() {
+        return ParseResult::None;
+    }
+    // the last `..`, so that `1.3...5.3.` works
+    match input.rsplit_once("..") {
+        Some((first, last)) => Day::parse_day_relative(timeline, first).and_then(|first| {
+            Day::parse_day_relative(timeline, last).and_then(|last| {
+                if first <= last {
+                    ParseResult::Valid((first, last))
+                } else {
+                    ParseResult::Invalid(())
+                }
+            })
+        }),
+        None => Day::parse_day_relative(timeline, input).map(|d| (d, d)),
+    }
+}
+
+impl MainView for ReassignView {
+    fn view(&mut self) -> QElement {
+        let range_error = matches!(
+            parse_range(&self.settings.load().timeline, &self.range),
+            ParseResult::Invalid(_)
+        );
+        let [from_state, to_state, range_state] = &mut self.inputs;
+        let to_error = !self.to.trim().is_empty() && JiraIssue::create(self.to.trim()).is_err();
+
+        let mut parts = vec![
+            text("Replace issue"),
+            v_space(style::SPACE),
+            Row::with_children(vec![
+                input(
+                    from_state,
+                    "issue",
+                    &self.from,
+                    false,
+                    ReassignMessage::FromChanged,
+                ),
+                h_space(style::SPACE),
+                text("with"),
+                h_space(style::SPACE),
+                input(
+                    to_state,
+                    "new issue",
+                    &self.to,
+                    to_error,
+                    ReassignMessage::ToChanged,
+                ),
+                h_space(style::SPACE),
+                text("on"),
+                h_space(style::SPACE),
+                input(
+                    range_state,
+                    "active day, <day> or <first>..<last>",
+                    &self.range,
+                    range_error,
+                    ReassignMessage::RangeChanged,
+                ),
+                h_space(style::SPACE),
+                style::inline_button(&mut self.preview_button, "Preview")
+                    .on_press(Message::Ra(ReassignMessage::Preview))
+                    .into(),
+            ])
+            .into(),
+            v_space(style::SPACE),
+        ];
+
+        if let Some(count) = self.applied {
+            parts.push(text(format!("Replaced {} entries", count)));
+        } else if let Some(preview) = &self.preview {
+            let count: usize = preview.iter().map(|d| d.changes.len()).sum();
+            parts.push(Self::preview_view(preview));
+            if count > 0 {
+                parts.push(v_space(style::SPACE));
+                parts.push(
+                    style::inline_button(
+                        &mut self.apply_button,
+                        &format!("Replace {} entries (Enter)", count),
+                    )
+                    .on_press(Message::ApplyReassign(preview.clone()))
+                    .into(),
+                );
+            }
+        }
+
+        Column::with_children(parts).into()
+    }
+
+    fn update(&mut self, msg: Message) -> Option<Message> {
+        match msg {
+            Message::Ra(ReassignMessage::FromChanged(v)) => {
+                self.from = v;
+                self.preview = None;
+                None
+            }
+            Message::Ra(ReassignMessage::ToChanged(v)) => {
+                self.to = v;
+                self.preview = None;
+                None
+            }
+            Message::Ra(ReassignMessage::RangeChanged(v)) => {
+                self.range = v;
+                self.preview = None;
+                None
+            }
+            Message::Ra(ReassignMessage::Preview) => {
+                self.applied = None;
+                self.request_preview()
+            }
+            Message::Ra(ReassignMessage::PreviewLoaded(preview)) => {
+                self.preview = Some(preview);
+                None
+            }
+            Message::Ra(ReassignMessage::Applied(count)) => {
+                self.preview = None;
+                self.applied = Some(count);
+                None
+            }
+            Message::SubmitCurrent(_) => {
+                if self.preview.is_some() {
+                    self.apply()
+                } else {
+                    self.applied = None;
+                    self.request_preview()
+                }
+            }
+            Message::Next => focus_next(&mut self.inputs.iter_mut().collect::<Vec<_>>(), true),
+            Message::Previous => {
+                focus_previous(&mut self.inputs.iter_mut().collect::<Vec<_>>(), true)
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::data::Day;
+    use crate::parsing::parse_result::ParseResult;
+    use crate::ui::reassign_view::parse_range;
+    use crate::util::{StaticTimeline, Timeline};
+
+    #[test]
+    fn parses_day_ranges() {
+        let timeline: Timeline = StaticTimeline::parse("2022-03-10 12:00").into();
+        assert_eq!(parse_range(&timeline, " "), ParseResult::None);
+        assert_eq!(
+            parse_range(&timeline, "yesterday"),
+            ParseResult::Valid((Day::ymd(2022, 3, 9), Day::ymd(2022, 3, 9)))
+        );
+        assert_eq!(
+            parse_range(&timeline, "1.3...today"),
+            ParseResult::Valid((Day::ymd(2022, 3, 1), Day::ymd(2022, 3, 10)))
+        );
+        assert_eq!(
+            parse_range(&timeline, "today..mon"),
+            ParseResult::Invalid(())
+        );
+    }
+}
@@ -1,59 +1,100 @@
-use iced_core::Length;
-use iced_native::widget::{Column, Row, Text};
+use iced_core::{Color, Length};
+use iced_native::widget::{button, Column, Row, Text};
 use unicode_segmentation::UnicodeSegmentation;
 
-use crate::data::{RecentIssue, RecentIssuesData, RecentIssuesRef};
+use crate::conf::SettingsRef;
+use crate::data::{
+    fuzzy_match, group_by_project, issue_project, JiraIssue, RecentIssuesData, RecentIssuesRef,
+};
+use crate::parsing::recent_filter;
 use crate::ui::util::{h_space, v_space};
 use crate::ui::{style, text, MainView, Message, QElement};
 
 pub struct RecentIssuesView {
     recent: RecentIssuesRef,
+    settings: SettingsRef,
     filter: String,
-    visible: Vec<RecentIssue>,
+    /// Visible issues with their `r<n>` index and whether they are pinned
+    visible: Vec<(usize, JiraIssue, bool)>,
+    pin_buttons: Vec<button::State>,
+    insert_buttons: Vec<button::State>,
+    /// Headings of the projects of the recent issues, in display order
+    projects: Vec<(String, usize)>,
+    project_buttons: Vec<button::State>,
 }
 
 impl RecentIssuesView {
-    pub fn create(r: RecentIssuesRef) -> Self {
-        let guard = r.borrow();
-        let visible: Vec<_> = guard.list_recent().to_vec();
-        RecentIssuesView {
+    pub fn create(r: RecentIssuesRef, settings: SettingsRef) -> Self {
+        let mut view = RecentIssuesView {
             recent: r,
+            settings,
             filter: String::new(),
-            visible,
-        }
+            visible: Vec::new(),
+            pin_buttons: Vec::new(),
+            insert_buttons: Vec::new(),
+            projects: Vec::new(),
+            project_buttons: Vec::new(),
+        };
+        view.refresh();
+        view
     }
 
     pub fn export_data(&self) -> RecentIssuesData {
-        RecentIssuesData {
-            issues: self.recent.borrow().list_recent().to_vec(),
-        }
+        self.recent.borrow().export_data()
     }
 
     pub fn refresh(&mut self) {
         self.update_filter(String::new())
     }
 
+    /// Reloads the issues, keeping the current filter
+    pub fn reload(&mut self) {
+        let filter = std::mem::take(&mut self.filter);
+        self.update_filter(filter)
+    }
+
+    /// Visible entries keep the index of their `r<n>` token while filtered.
+    /// The recent issues are shown grouped by project, below the pinned issues
     fn update_filter(&mut self, input: String) {
         self.filter = input;
         let guard = self.recent.borrow();
-        if self.filter.trim().is_empty() {
-            self.visible = guard.list_recent().to_vec();
-        } else {
-            let input = self.filter.as_str();
-            self.visible = guard
-                .list_recent()
-                .iter()
-                .filter(|e| {
-                    e.issue.ident.contains(input)
-                        || e.issue
-                            .description
-                            .as_deref()
-                            .filter(|d| d.contains(input))
-                            .is_some()
-                })
-                .cloned()
-                .collect();
+        let all = guard.filtered("");
+        let (pinned, recent): (Vec<_>, Vec<_>) = guard
+            .filtered(&self.filter)
+            .into_iter()
+            .filter_map(|(issue, pinned)| {
+                let index = all.iter().position(|(i, _)| i.ident == issue.ident)?;
+                Some((index + 1, issue.clone(), pinned))
+            })
+            .partition(|(_, _, pinned)| *pinned);
+        let recent = group_by_project(recent, |(_, issue, _)| &issue.ident);
+
+        self.projects.clear();
+        for (_, issue, _) in &recent {
+            let project = issue_project(&issue.ident);
+            match self.projects.last_mut() {
+                Some((last, count)) if *last == project => *count += 1,
+                _ => self.projects.push((project, 1)),
+            }
         }
+        self.visible = pinned.into_iter().chain(recent).collect();
+        self.pin_buttons
+            .resize_with(self.visible.len(), button::State::new);
+        self.insert_buttons
+            .resize_with(self.visible.len(), button::State::new);
+        self.project_buttons
+            .resize_with(self.projects.len(), button::State::new);
+    }
+}
+
+/// Message that updates the recent issues filter, if the issue input of an edit view changed it
+pub fn filter_message(current: &mut String, issue_input: &str) -> Option<Message> {
+    let filter = recent_filter(issue_input);
+    if current.as_str() != filter {
+        *current = filter.to_string();
+        Some(Message::IssueInput(filter.to_string()))
+    } else {
+        None
     }
 }
 
@@ -61,15 +102,78 @@ impl MainView for RecentIssuesView {
     fn view(&mut self) -> QElement {
         let mut lines = Column::new();
         let mut current_row = Row::new();
+        let guard = self.recent.borrow();
+        let settings = self.settings.load();
 
-        for (num, recent) in self.visible.iter().enumerate().take(20) {
-            if num % 2 == 0 && num != 0 {
-                let mut tmp = Row::new();
-                std::mem::swap(&mut tmp, &mut current_row);
-                lines = lines.push(tmp);
+        // a filter shows its matches in collapsed projects too
+        let collapsed = |project: &str| {
+            self.filter.trim().is_empty() && settings.collapsed_projects.contains(project)
+        };
+        let mut headings = self.projects.iter().zip(self.project_buttons.iter_mut());
+        let mut project = None;
+        let mut shown = 0;
+        let mut columns = 0;
+        let entries = self.visible.iter().zip(
+            self.pin_buttons
+                .iter_mut()
+                .zip(self.insert_buttons.iter_mut()),
+        );
+        for ((num, issue, pinned), (pin_button, insert_button)) in entries {
+            // favorites are kept in separate rows above the projects
+            let heading = if *pinned {
+                None
+            } else if project.as_ref() != Some(&issue_project(&issue.ident)) {
+                headings.next()
+            } else {
+                None
+            };
+            if let Some(((name, count), button)) = heading {
+                if columns > 0 {
+                    lines = lines.push(std::mem::replace(&mut current_row, Row::new()));
+                    columns = 0;
+                }
+                let marker = if collapsed(name) { "+" } else { "-" };
+                lines = lines.push(v_space(Length::Units(3)));
+                lines = lines.push(
+                    style::inline_button(button, &format!("{} {} ({})", marker, name, count))
+                        .on_press(Message::ToggleProject(name.clone())),
+                );
                 lines = lines.push(v_space(Length::Units(3)));
+                project = Some(name.clone());
             }
-            current_row = current_row.push(build_recent(num + 1, recent));
+            if !*pinned && matches!(project.as_deref(), Some(p) if collapsed(p)) {
+                continue;
+            }
+            if shown == 20 {
+                break;
+            }
+            shown += 1;
+            if columns == 2 {
+                lines = lines.push(std::mem::replace(&mut current_row, Row::new()));
+                lines = lines.push(v_space(Length::Units(3)));
+                columns = 0;
+            }
+            columns += 1;
+            let use_count = guard.use_count(&issue.ident);
+            let pin_button = if *pinned {
+                style::inline_button(pin_button, "unpin")
+                    .on_press(Message::UnpinIssue(issue.ident.clone()))
+            } else {
+                style::inline_button(pin_button, "pin")
+                    .on_press(Message::PinIssue(issue.ident.clone()))
+            };
+            // the token that selects the issue in issue inputs, a click inserts the issue
+            let hint = style::inline_button(insert_button, &format!("r{}", num))
+                .width(Length::Units(40))
+                .on_press(Message::InsertIssue(issue.ident.clone()));
+            current_row = current_row.push(build_recent(
+                hint.into(),
+                issue,
+                style::issue_color(&settings, &issue.ident),
+                &self.filter,
+                use_count,
+                pin_button.into(),
+            ));
         }
         lines = lines.push(current_row);
 
@@ -78,43 +182,88 @@ impl MainView for RecentIssuesView {
 
     fn update(&mut self, msg: Message) -> Option<Message> {
         if let Message::IssueInput(input) = msg {
-            self.filter = input;
+            if input != self.filter {
+                self.update_filter(input);
+            }
         };
         None
     }
 }
 
-fn build_recent(num: usize, recent: &RecentIssue) -> QElement {
-    let description = recent
-        .issue
+fn build_recent<'a>(
+    hint: QElement<'a>,
+    issue: &JiraIssue,
+    color: Color,
+    filter: &str,
+    use_count: u32,
+    pin_button: QElement<'a>,
+) -> QElement<'a> {
+    let description = issue
         .description
         .as_deref()
-        .or(recent.issue.default_action.as_deref())
+        .or(issue.default_action.as_deref())
         .unwrap_or("<no description>");
 
-    let action = recent.issue.default_action.as_deref().unwrap_or("-");
+    let action = issue.default_action.as_deref().unwrap_or("-");
 
     let description = limit_text_length(description, 55);
     let action = limit_text_length(action, 25);
 
     Row::with_children(vec![
-        Text::new(format!("{}:", num))
-            .width(Length::Units(22))
-            .into(),
+        pin_button,
+        h_space(style::SPACE),
+        hint,
+        h_space(style::SPACE),
+        style::issue_mark(Some(color)),
+        h_space(Length::Units(4)),
+        highlighted(&issue.ident, filter, Length::Units(100)),
         h_space(style::SPACE),
-        Text::new(&recent.issue.ident)
-            .width(Length::Units(100))
+        Text::new(format!("{}x", use_count))
+            .width(Length::Units(35))
             .into(),
         h_space(style::SPACE),
-        Text::new(action).width(Length::Units(190)).into(),
+        highlighted(&action, filter, Length::Units(190)),
         h_space(style::SPACE),
-        Text::new(description).into(),
+        highlighted(&description, filter, Length::Shrink),
     ])
     .max_width(800)
     .width(Length::Units(800))
     .into()
 }
 
+/// Text with the characters matching `filter` in a different color
+fn highlighted<'a>(text: &str, filter: &str, width: Length) -> QElement<'a> {
+    let matches = fuzzy_match(text, filter.trim()).unwrap_or_default();
+    if matches.is_empty() {
+        return Text::new(text).width(width).into();
+    }
+
+    let mut segments: Vec<(bool, String)> = Vec::new();
+    for (offset, c) in text.char_indices() {
+        let matched = matches.binary_search(&offset).is_ok();
+        match segments.last_mut() {
+            Some((m, segment)) if *m == matched => segment.push(c),
+            _ => segments.push((matched, c.to_string())),
+        }
+    }
+
+    Row::with_children(
+        segments
+            .into_iter()
+            .map(|(matched, segment)| {
+                let t = Text::new(segment);
+                if matched {
+                    t.color(style::MATCH_COLOR).into()
+                } else {
+                    t.into()
+                }
+            })
+            .collect(),
+    )
+    .width(width)
+    .into()
+}
+
 fn limit_text_length(description: &str, length: usize) -> String {
     let description = if let Some((i, _)) = description.grapheme_indices(false).nth(length) {
         format!("{}…", &description[..i])
@@ -123,3 +272,33 @@ fn limit_text_length(description: &str, length: usize) -> String {
     };
     description
 }
+
+#[cfg(test)]
+mod test {
+    use crate::data::{JiraIssue, RecentIssuesRef};
+    use crate::parsing::{IssueParser, IssueParserWithRecent};
+    use crate::ui::recent_issues_view::RecentIssuesView;
+    use crate::ui::{MainView, Message};
+    use crate::Settings;
+
+    #[test]
+    fn filtered_entries_keep_their_token() {
+        let settings = Settings::default().into_settings_ref();
+        let recent = RecentIssuesRef::empty(settings.clone());
+        for ident in ["DEV-1", "DOC-2", "DEV-3"] {
+            recent.issue_used_with_comment(&JiraIssue::create(ident).unwrap(), None);
+        }
+        let mut view = RecentIssuesView::create(recent.clone(), settings.clone());
+
+        view.update(Message::IssueInput("DEV-1".to_string()));
+        assert!(!view.visible.is_empty());
+
+        let guard = settings.load();
+        let recent = recent.borrow();
+        let parser = IssueParserWithRecent::new(&guard.issue_parser, &recent);
+        for (num, issue, _) in &view.visible {
+            let parsed = parser.parse_task(&format!("r{}", num));
+            assert_eq!(parsed.r.get_ref(), Some(issue));
+        }
+    }
+}
@@ -1,19 +1,29 @@
 use std::collections::BTreeMap;
+use std::num::NonZeroU32;
 use std::path::PathBuf;
 use std::str::FromStr;
 use std::sync::Arc;
 
 use iced_core::Length;
 use iced_native::widget::text_input::State;
-use iced_native::widget::{button, scrollable, text_input, Button, Container, Scrollable};
+use iced_native::widget::{
+    button, scrollable, text_input, Button, Checkbox, Container, Scrollable,
+};
 use iced_native::widget::{Column, Row};
 use regex::Regex;
 
 use my_text_input::MyTextInput;
 use shortcut_ui::ShortCutUi;
+use weekday_breaks_ui::WeekdayBreaksUi;
 
-use crate::conf::{BreaksConfig, SettingsRef};
-use crate::data::JiraIssue;
+use crate::conf::{
+    AbsenceCategory, BreakLimit, BreaksConfig, DailyBudget, FieldAdvance, MergeMode,
+    OverlapHandling, SettingsRef, SharedConfig, TraceOutput, WeekdayBreaks,
+};
+use crate::data::{
+    export_path, format_cents, Day, JiraIssue, RecentIssuesRef, RecentRanking, UtcOffset,
+};
+use crate::parsing::locale::{DateFormat, TimeFormat};
 use crate::parsing::parse_result::ParseResult;
 use crate::parsing::time::Time;
 use crate::parsing::time_relative::TimeRelative;
@@ -25,34 +35,121 @@ use crate::{Settings, SettingsSer};
 
 mod my_text_input;
 mod shortcut_ui;
+mod weekday_breaks_ui;
 
 #[derive(Clone, Debug)]
 pub enum SettingsUIMessage {
     AddShortcut,
+    AddWeekdayBreaks,
     ResetSettings,
+    /// The settings were reloaded from the file, with the settings before
+    FileChanged(Box<SettingsSer>),
+    SecondsPrecision(bool),
+    RankByFrequency(bool),
+    AdjustOverlaps(bool),
+    AutoCloseWork(bool),
+    FieldAdvance(bool),
+    TwelveHourTimes(bool),
+    DottedDates(bool),
+    HighContrast(bool),
+    TextSummary(bool),
+    ConfirmDeletes(bool),
+    BreakNotification(bool),
+    BudgetNotification(bool),
+    AutoExport(bool),
+    ExtendedExport(bool),
+    GitCommits(bool),
     SubmitSettings,
+    ExportShared,
+    ImportShared,
+    ConfirmImport(MergeMode),
+    CancelImport,
+    AddProfile,
+    /// Moves the break time by resolution steps, sent by the mouse wheel over the input
+    ScrollBreak(BreakTime, i32),
+}
+
+/// Break time inputs of the form
+#[derive(Clone, Copy, Debug)]
+pub enum BreakTime {
+    DefaultStart,
+    DefaultEnd,
+    /// Start of the break rule at the index
+    WeekdayStart(usize),
+    WeekdayEnd(usize),
 }
 
 pub struct SettingsUI {
     settings: SettingsRef,
+    recent_issues: RecentIssuesRef,
     original: SettingsSer,
     db_dir: MyTextInput,
     resolution: MyTextInput,
+    daily_target: MyTextInput,
+    weekly_target: MyTextInput,
+    max_daily: MyTextInput,
+    max_weekly: MyTextInput,
     min_breaks: MyTextInput,
     min_work: MyTextInput,
     default_break_start: MyTextInput,
     default_break_end: MyTextInput,
+    weekday_breaks: Vec<WeekdayBreaksUi>,
+    add_weekday_breaks_button: button::State,
+    max_work_without_break: MyTextInput,
+    min_break: MyTextInput,
+    break_notification: bool,
+    daily_budget: MyTextInput,
+    budget_notification: bool,
     max_recent_issues: MyTextInput,
+    home_zone: MyTextInput,
+    locations: MyTextInput,
+    projects: MyTextInput,
+    meeting_prefixes: MyTextInput,
+    absence_categories: MyTextInput,
+    holidays: MyTextInput,
+    issue_colors: MyTextInput,
+    project_targets: MyTextInput,
+    hourly_rates: MyTextInput,
+    export_file_pattern: MyTextInput,
+    auto_export: bool,
+    extended_export: bool,
+    git_commits: bool,
+    seconds_precision: bool,
+    recent_ranking: RecentRanking,
+    overlap_handling: OverlapHandling,
+    auto_close_work: bool,
+    field_advance: bool,
+    time_format: TimeFormat,
+    date_format: DateFormat,
+    high_contrast: bool,
+    text_summary: bool,
+    confirm_deletes: bool,
+    trace: TraceOutput,
     shortcuts: Vec<ShortCutUi>,
     shortcuts_scroll: scrollable::State,
     add_shortcut_button: button::State,
     submit_button: button::State,
     reset_button: button::State,
     settings_changed: bool,
+    /// The settings file changed while there were unsubmitted edits
+    file_changed: bool,
+    /// All inputs are valid, settings can be submitted
+    valid: bool,
+    shared_file: MyTextInput,
+    shared_info: String,
+    pending_import: Option<SharedConfig>,
+    export_button: button::State,
+    import_button: button::State,
+    skip_button: button::State,
+    overwrite_button: button::State,
+    cancel_button: button::State,
+    profile_buttons: Vec<button::State>,
+    new_profile: MyTextInput,
+    add_profile_button: button::State,
 }
 
 impl SettingsUI {
-    pub fn new(settings: SettingsRef) -> Box<Self> {
+    pub fn new(settings: SettingsRef, recent_issues: RecentIssuesRef) -> Box<Self> {
         let settings_v: &Settings = &**settings.load();
         let original = SettingsSer::from_settings(settings_v);
         let o = SettingsSer::from_settings(settings_v);
@@ -67,32 +164,273 @@ impl SettingsUI {
         }));
         let mut max_recent_issues = MyTextInput::new(o.max_recent_issues, accept_number);
         max_recent_issues.input.focus();
-        Box::new(Self {
+        let mut ui = Box::new(Self {
             settings,
+            recent_issues,
             original,
             db_dir: MyTextInput::new(o.db_dir.to_string_lossy(), no_check),
             resolution: MyTextInput::new(o.resolution_minutes, accept_number),
+            daily_target: MyTextInput::new(o.daily_target_minutes, accept_number),
+            weekly_target: MyTextInput::new(o.weekly_target_minutes, accept_number),
+            max_daily: MyTextInput::new_opt(o.max_daily_minutes, accept_number),
+            max_weekly: MyTextInput::new_opt(o.max_weekly_minutes, accept_number),
             min_breaks: MyTextInput::new(o.breaks.min_breaks_minutes, accept_number),
             min_work: MyTextInput::new(o.breaks.min_work_time_minutes, accept_number),
             default_break_start: MyTextInput::new(o.breaks.default_break.0, accept_time),
             default_break_end: MyTextInput::new(o.breaks.default_break.1, accept_time),
+            weekday_breaks: o
+                .breaks
+                .weekday_overrides
+                .iter()
+                .map(WeekdayBreaksUi::new)
+                .collect(),
+            add_weekday_breaks_button: button::State::new(),
+            max_work_without_break: MyTextInput::new(o.break_limit.max_work_minutes, accept_number),
+            min_break: MyTextInput::new(o.break_limit.min_break_minutes, accept_number),
+            break_notification: o.break_limit.notify,
+            daily_budget: MyTextInput::new(o.daily_budget.minutes, accept_number),
+            budget_notification: o.daily_budget.notify,
             max_recent_issues,
+            home_zone: MyTextInput::new_opt(o.home_zone, accept_utc_offset),
+            locations: MyTextInput::new(o.locations.join(", "), no_check),
+            projects: MyTextInput::new(o.projects.join(", "), no_check),
+            meeting_prefixes: MyTextInput::new(o.meeting_prefixes.join(", "), no_check),
+            absence_categories: MyTextInput::new(
+                o.absence_categories
+                    .iter()
+                    .map(|c| format!("{}={}", c.name, c.code))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                no_check,
+            ),
+            holidays: MyTextInput::new(
+                o.holidays
+                    .iter()
+                    .map(Day::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                no_check,
+            ),
+            issue_colors: MyTextInput::new(
+                o.issue_colors
+                    .iter()
+                    .map(|(issue, color)| format!("{}={}", issue, color))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                no_check,
+            ),
+            project_targets: MyTextInput::new(
+                o.project_targets
+                    .iter()
+                    .map(|(project, percent)| format!("{}={}%", project, percent))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                no_check,
+            ),
+            hourly_rates: MyTextInput::new(
+                o.hourly_rates
+                    .iter()
+                    .map(|(prefix, cents)| format!("{}={}", prefix, format_cents(*cents)))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                no_check,
+            ),
+            export_file_pattern: MyTextInput::new(&o.export_file_pattern, no_check),
+            auto_export: o.auto_export,
+            extended_export: o.extended_export,
+            git_commits: o.git_commits,
+            seconds_precision: o.seconds_precision,
+            recent_ranking: o.recent_ranking,
+            overlap_handling: o.overlap_handling,
+            auto_close_work: o.auto_close_work,
+            field_advance: o.field_advance.enabled,
+            time_format: o.time_format,
+            date_format: o.date_format,
+            high_contrast: o.high_contrast,
+            text_summary: o.text_summary,
+            confirm_deletes: o.confirm_deletes,
+            trace: o.trace.clone(),
             shortcuts,
             shortcuts_scroll: scrollable::State::new(),
             add_shortcut_button: button::State::new(),
             submit_button: button::State::new(),
             reset_button: button::State::new(),
             settings_changed: false,
-        })
+            file_changed: false,
+            valid: true,
+            shared_file: MyTextInput::new("", no_check),
+            shared_info: String::new(),
+            pending_import: None,
+            export_button: button::State::new(),
+            import_button: button::State::new(),
+            skip_button: button::State::new(),
+            overwrite_button: button::State::new(),
+            cancel_button: button::State::new(),
+            profile_buttons: Vec::new(),
+            new_profile: MyTextInput::new("", accept_profile_name),
+            add_profile_button: button::State::new(),
+        });
+        ui.valid = ui.validate().is_some();
+        ui
+    }
+
+    fn add_profile(&mut self) -> Option<Message> {
+        let name = self.new_profile.text.trim().to_string();
+        let exists = self
+            .settings
+            .load()
+            .profile_names()
+            .contains(&Some(name.clone()));
+        let name = if name.is_empty() {
+            Err("Missing name".to_string())
+        } else if exists {
+            Err(format!("Profile {name} exists"))
+        } else {
+            Ok(name)
+        };
+        let name = self.new_profile.consume_err(name).ok()?;
+        Some(Message::SwitchProfile(Some(name)))
+    }
+
+    fn shared_path(&self) -> Option<PathBuf> {
+        let path = self.shared_file.text.trim();
+        if path.is_empty() {
+            None
+        } else {
+            Some(PathBuf::from(path))
+        }
+    }
+
+    fn export_shared(&mut self) {
+        let path = if let Some(path) = self.shared_path() {
+            path
+        } else {
+            self.shared_info = "Enter a file name".to_string();
+            return;
+        };
+        let issue_shortcuts = if let Some(shortcuts) = self.validate_shortcuts() {
+            shortcuts
+        } else {
+            self.shared_info = "Fix the invalid shortcuts first".to_string();
+            return;
+        };
+        let recent_issues = self
+            .recent_issues
+            .borrow()
+            .filtered("")
+            .into_iter()
+            .map(|(i, _)| i.clone())
+            .collect();
+        let shared = SharedConfig {
+            issue_shortcuts,
+            recent_issues,
+        };
+        self.shared_info = match shared.write(&path) {
+            Ok(()) => format!(
+                "Exported {} shortcuts and {} recent issues",
+                shared.issue_shortcuts.len(),
+                shared.recent_issues.len()
+            ),
+            Err(e) => e,
+        };
+    }
+
+    /// Imports directly if there are no duplicates, otherwise asks how to merge them
+    fn import_shared(&mut self) -> Option<Message> {
+        let path = if let Some(path) = self.shared_path() {
+            path
+        } else {
+            self.shared_info = "Enter a file name".to_string();
+            return None;
+        };
+        match SharedConfig::read(&path) {
+            Ok(shared) => {
+                let shortcuts = self.validate_shortcuts().unwrap_or_default();
+                let conflicts = shared.conflicting_shortcuts(&shortcuts).len()
+                    + self
+                        .recent_issues
+                        .borrow()
+                        .count_conflicts(&shared.recent_issues);
+                if conflicts == 0 {
+                    self.merge_shared(shared, MergeMode::Skip)
+                } else {
+                    self.shared_info =
+                        format!("{} entries already exist with other values", conflicts);
+                    self.pending_import = Some(shared);
+                    None
+                }
+            }
+            Err(e) => {
+                self.shared_info = e;
+                None
+            }
+        }
+    }
+
+    /// Shortcuts are added to the edited settings, recent issues are updated immediately
+    fn merge_shared(&mut self, shared: SharedConfig, mode: MergeMode) -> Option<Message> {
+        let mut shortcuts = if let Some(shortcuts) = self.validate_shortcuts() {
+            shortcuts
+        } else {
+            self.shared_info = "Fix the invalid shortcuts first".to_string();
+            return None;
+        };
+        let changed = shared.merge_shortcuts(&mut shortcuts, mode);
+        self.shortcuts = shortcuts
+            .iter()
+            .map(|(sc, i)| ShortCutUi::new(sc, i))
+            .collect();
+        let imported = self
+            .recent_issues
+            .borrow()
+            .count_imported(&shared.recent_issues, mode);
+        self.shared_info = format!(
+            "Imported {} shortcuts, submit to apply them. Imported {} recent issues",
+            changed, imported
+        );
+        Some(Message::ImportRecentIssues(shared.recent_issues, mode))
     }
 
     fn update_text(&mut self, text: String) -> Option<Message> {
         if self.db_dir.is_focused() {
             self.db_dir.text = text;
+        } else if self.shared_file.is_focused() {
+            self.shared_file.text = text;
+        } else if self.new_profile.is_focused() {
+            self.new_profile.error = None;
+            self.new_profile.accept_input(text);
         } else if self.resolution.is_focused() {
             self.resolution.accept_input(text);
+        } else if self.daily_target.is_focused() {
+            self.daily_target.accept_input(text);
+        } else if self.weekly_target.is_focused() {
+            self.weekly_target.accept_input(text);
+        } else if self.max_daily.is_focused() {
+            self.max_daily.accept_input(text);
+        } else if self.max_weekly.is_focused() {
+            self.max_weekly.accept_input(text);
         } else if self.max_recent_issues.is_focused() {
             self.max_recent_issues.accept_input(text);
+        } else if self.home_zone.is_focused() {
+            self.home_zone.accept_input(text);
+        } else if self.locations.is_focused() {
+            self.locations.accept_input(text);
+        } else if self.projects.is_focused() {
+            self.projects.accept_input(text);
+        } else if self.meeting_prefixes.is_focused() {
+            self.meeting_prefixes.accept_input(text);
+        } else if self.absence_categories.is_focused() {
+            self.absence_categories.accept_input(text);
+        } else if self.holidays.is_focused() {
+            self.holidays.accept_input(text);
+        } else if self.issue_colors.is_focused() {
+            self.issue_colors.accept_input(text);
+        } else if self.project_targets.is_focused() {
+            self.project_targets.accept_input(text);
+        } else if self.hourly_rates.is_focused() {
+            self.hourly_rates.accept_input(text);
+        } else if self.export_file_pattern.is_focused() {
+            self.export_file_pattern.accept_input(text);
         } else if self.default_break_start.is_focused() {
             self.default_break_start.accept_input(text);
         } else if self.default_break_end.is_focused() {
@@ -101,6 +439,19 @@ impl SettingsUI {
             self.min_breaks.accept_input(text);
         } else if self.min_work.is_focused() {
             self.min_work.accept_input(text);
+        } else if self.max_work_without_break.is_focused() {
+            self.max_work_without_break.accept_input(text);
+        } else if self.min_break.is_focused() {
+            self.min_break.accept_input(text);
+        } else if self.daily_budget.is_focused() {
+            self.daily_budget.accept_input(text);
+        } else if let Some(input) = self
+            .weekday_breaks
+            .iter_mut()
+            .flat_map(WeekdayBreaksUi::inputs)
+            .find(|i| i.is_focused())
+        {
+            input.accept_input(text);
         } else {
             for sc in self.shortcuts.iter_mut() {
                 if sc.shortcut.is_focused() {
@@ -119,6 +470,33 @@ impl SettingsUI {
             }
         }
 
+        self.valid = self.validate().is_some();
+        None
+    }
+
+    /// Adjusts the break time by the booking resolution of the form
+    fn scroll_break(&mut self, field: BreakTime, steps: i32) -> Option<Message> {
+        let resolution = u32::from_str(&self.resolution.text)
+            .ok()
+            .and_then(NonZeroU32::new)
+            .or_else(|| NonZeroU32::new(self.settings.load().resolution.num_minutes() as u32))
+            .unwrap_or_else(|| NonZeroU32::new(1).unwrap());
+        let input = match field {
+            BreakTime::DefaultStart => Some(&mut self.default_break_start),
+            BreakTime::DefaultEnd => Some(&mut self.default_break_end),
+            BreakTime::WeekdayStart(index) => self
+                .weekday_breaks
+                .get_mut(index)
+                .map(|wb| &mut wb.break_start),
+            BreakTime::WeekdayEnd(index) => self
+                .weekday_breaks
+                .get_mut(index)
+                .map(|wb| &mut wb.break_end),
+        };
+        if let Some(input) = input {
+            input.step_time(steps, resolution);
+            self.valid = self.validate().is_some();
+        }
         None
     }
 
@@ -151,6 +529,186 @@ impl SettingsUI {
             }
         }
 
+        fn validate_home_zone(input: &MyTextInput) -> VResult<Option<UtcOffset>> {
+            if input.text.trim().is_empty() {
+                Ok(None)
+            } else {
+                UtcOffset::parse(&input.text).map(Some)
+            }
+        }
+
+        fn validate_locations(input: &MyTextInput) -> VResult<Vec<String>> {
+            let mut result: Vec<String> = Vec::new();
+            for name in input.text.split(',').map(str::trim) {
+                if name.is_empty() {
+                    continue;
+                } else if !name.starts_with(char::is_alphabetic) {
+                    return Err(format!("{name} must start with a letter"));
+                } else if name.eq_ignore_ascii_case("h") || name.eq_ignore_ascii_case("o") {
+                    return Err(format!("{name} is reserved for home and office"));
+                } else if result.iter().any(|n| n.eq_ignore_ascii_case(name)) {
+                    return Err(format!("Duplicate location {name}"));
+                }
+                result.push(name.to_string());
+            }
+            Ok(result)
+        }
+
+        fn validate_projects(input: &MyTextInput) -> VResult<Vec<String>> {
+            let mut result: Vec<String> = Vec::new();
+            for project in input.text.split(',').map(str::trim) {
+                if project.is_empty() {
+                    continue;
+                } else if !project.chars().all(|ch| ch.is_ascii_alphabetic()) {
+                    return Err(format!("{project} must only contain letters"));
+                }
+                let project = project.to_ascii_uppercase();
+                if !result.contains(&project) {
+                    result.push(project);
+                }
+            }
+            Ok(result)
+        }
+
+        fn validate_meeting_prefixes(input: &MyTextInput) -> VResult<Vec<String>> {
+            let mut result: Vec<String> = Vec::new();
+            for prefix in input.text.split(',').map(str::trim) {
+                if prefix.is_empty() {
+                    continue;
+                } else if prefix.contains(char::is_whitespace) {
+                    return Err(format!("{prefix} must not contain spaces"));
+                }
+                let prefix = prefix.to_ascii_uppercase();
+                if !result.contains(&prefix) {
+                    result.push(prefix);
+                }
+            }
+            Ok(result)
+        }
+
+        fn validate_absence_categories(input: &MyTextInput) -> VResult<Vec<AbsenceCategory>> {
+            let mut result: Vec<AbsenceCategory> = Vec::new();
+            for entry in input.text.split(',').map(str::trim) {
+                if entry.is_empty() {
+                    continue;
+                }
+                let (name, code) = entry.split_once('=').unwrap_or((entry, entry));
+                let (name, code) = (name.trim(), code.trim());
+                if name.is_empty() || code.is_empty() {
+                    return Err(format!("{entry} must be <name>=<code>"));
+                } else if code.contains('|') {
+                    return Err(format!("{code} must not contain |"));
+                } else if result.iter().any(|c| c.name == name) {
+                    return Err(format!("Duplicate category {name}"));
+                }
+                result.push(AbsenceCategory {
+                    name: name.to_string(),
+                    code: code.to_string(),
+                });
+            }
+            Ok(result)
+        }
+
+        fn validate_holidays(input: &MyTextInput) -> VResult<Vec<Day>> {
+            let mut result = Vec::new();
+            for entry in input.text.split(',').map(str::trim) {
+                if entry.is_empty() {
+                    continue;
+                }
+                let day = Day::parse(entry)
+                    .map_err(|_| format!("{entry} must be YYYY-MM-DD or DD.MM.YYYY"))?;
+                if !result.contains(&day) {
+                    result.push(day);
+                }
+            }
+            result.sort();
+            Ok(result)
+        }
+
+        fn validate_issue_colors(input: &MyTextInput) -> VResult<BTreeMap<String, String>> {
+            let mut result = BTreeMap::new();
+            for entry in input.text.split(',').map(str::trim) {
+                if entry.is_empty() {
+                    continue;
+                }
+                let (issue, color) = match entry.split_once('=') {
+                    Some((issue, color)) => (issue.trim(), color.trim()),
+                    None => return Err(format!("{entry} must be <issue>=#rrggbb")),
+                };
+                if !JiraIssueParser::valid_id(issue) {
+                    return Err(format!("Invalid id {issue}"));
+                } else if style::parse_color(color).is_none() {
+                    return Err(format!("{color} must be #rrggbb"));
+                }
+                result.insert(issue.to_ascii_uppercase(), color.to_ascii_lowercase());
+            }
+            Ok(result)
+        }
+
+        fn validate_project_targets(input: &MyTextInput) -> VResult<BTreeMap<String, u32>> {
+            let mut result = BTreeMap::new();
+            for entry in input.text.split(',').map(str::trim) {
+                if entry.is_empty() {
+                    continue;
+                }
+                let (project, percent) = match entry.split_once('=') {
+                    Some((project, percent)) => (project.trim(), percent.trim()),
+                    None => return Err(format!("{entry} must be <project>=<percent>")),
+                };
+                if project.is_empty() || !project.chars().all(|ch| ch.is_ascii_alphabetic()) {
+                    return Err(format!("Invalid project {project}"));
+                }
+                let percent: u32 = percent
+                    .trim_end_matches('%')
+                    .trim()
+                    .parse()
+                    .map_err(|_| format!("{percent} must be a percentage"))?;
+                result.insert(project.to_ascii_uppercase(), percent);
+            }
+            if result.values().sum::<u32>() > 100 {
+                return Err("Targets must not exceed 100%".to_string());
+            }
+            Ok(result)
+        }
+
+        fn validate_hourly_rates(input: &MyTextInput) -> VResult<BTreeMap<String, u32>> {
+            let mut result = BTreeMap::new();
+            for entry in input.text.split(',').map(str::trim) {
+                if entry.is_empty() {
+                    continue;
+                }
+                let (prefix, rate) = match entry.split_once('=') {
+                    Some((prefix, rate)) => (prefix.trim(), rate.trim()),
+                    None => return Err(format!("{entry} must be <issue prefix>=<rate>")),
+                };
+                if prefix.is_empty() || prefix.contains(char::is_whitespace) {
+                    return Err(format!("Invalid issue prefix {prefix}"));
+                }
+                let (units, cents) = rate.split_once('.').unwrap_or((rate, "0"));
+                let cents = match cents.len() {
+                    1 => cents.parse::<u32>().map(|c| c * 10),
+                    2 => cents.parse::<u32>(),
+                    _ => return Err(format!("{rate} must have at most two decimals")),
+                };
+                let rate = units
+                    .parse::<u32>()
+                    .ok()
+                    .zip(cents.ok())
+                    .and_then(|(units, cents)| units.checked_mul(100)?.checked_add(cents))
+                    .ok_or_else(|| format!("{rate} must be an amount like 85.50"))?;
+                result.insert(prefix.to_ascii_uppercase(), rate);
+            }
+            Ok(result)
+        }
+
+        fn validate_export_file_pattern(input: &MyTextInput, today: Day) -> VResult<String> {
+            let pattern = input.text.trim();
+            if !pattern.is_empty() {
+                export_path(pattern, today)?;
+            }
+            Ok(pattern.to_string())
+        }
+
         fn validate_num(input: &MyTextInput, max: u32) -> VResult<u32> {
             match u32::from_str(&input.text) {
                 Ok(v) if v <= max => Ok(v),
@@ -159,6 +717,15 @@ impl SettingsUI {
             }
         }
 
+        /// No cap without input
+        fn validate_cap(input: &MyTextInput, max: u32) -> VResult<Option<u32>> {
+            if input.text.trim().is_empty() {
+                Ok(None)
+            } else {
+                validate_num(input, max).map(Some)
+            }
+        }
+
         fn validate_default_break_start(
             input: &MyTextInput,
             breaks_duration: &VResult<u32>,
@@ -208,36 +775,174 @@ impl SettingsUI {
 
         let db_dir = validate_db_dir(&self.db_dir, &self.original);
         let max_recent = validate_max_recent(&self.max_recent_issues);
+        let home_zone = validate_home_zone(&self.home_zone);
+        let locations = validate_locations(&self.locations);
+        let projects = validate_projects(&self.projects);
+        let meeting_prefixes = validate_meeting_prefixes(&self.meeting_prefixes);
+        let absence_categories = validate_absence_categories(&self.absence_categories);
+        let holidays = validate_holidays(&self.holidays);
+        let issue_colors = validate_issue_colors(&self.issue_colors);
+        let project_targets = validate_project_targets(&self.project_targets);
+        let hourly_rates = validate_hourly_rates(&self.hourly_rates);
+        let export_file_pattern =
+            validate_export_file_pattern(&self.export_file_pattern, self.settings.load().today());
         let breaks_dur = validate_num(&self.min_breaks, 6 * 60);
         let min_work = validate_num(&self.min_work, 12 * 60);
         let break_start = validate_default_break_start(&self.default_break_start, &breaks_dur);
         let break_end =
             validate_default_break_end(&self.default_break_end, &break_start, &breaks_dur);
         let resolution = validate_num(&self.resolution, 60);
+        let daily_target = validate_num(&self.daily_target, 24 * 60);
+        let weekly_target = validate_num(&self.weekly_target, 7 * 24 * 60);
+        let max_daily = validate_cap(&self.max_daily, 24 * 60);
+        let max_weekly = validate_cap(&self.max_weekly, 7 * 24 * 60);
+        let max_work_without_break = validate_num(&self.max_work_without_break, 24 * 60);
+        let min_break = validate_num(&self.min_break, 6 * 60);
+        let daily_budget = validate_num(&self.daily_budget, 24 * 60);
         let shortcuts = self.validate_shortcuts();
 
         let db_dir = self.db_dir.consume_err(db_dir);
         let max_recent = self.max_recent_issues.consume_err(max_recent);
+        let home_zone = self.home_zone.consume_err(home_zone);
+        let locations = self.locations.consume_err(locations);
+        let projects = self.projects.consume_err(projects);
+        let meeting_prefixes = self.meeting_prefixes.consume_err(meeting_prefixes);
+        let absence_categories = self.absence_categories.consume_err(absence_categories);
+        let holidays = self.holidays.consume_err(holidays);
+        let issue_colors = self.issue_colors.consume_err(issue_colors);
+        let project_targets = self.project_targets.consume_err(project_targets);
+        let hourly_rates = self.hourly_rates.consume_err(hourly_rates);
+        let export_file_pattern = self.export_file_pattern.consume_err(export_file_pattern);
         let breaks_dur = self.min_breaks.consume_err(breaks_dur);
         let min_work = self.min_work.consume_err(min_work);
         let break_start = self.default_break_start.consume_err(break_start);
         let break_end = self.default_break_end.consume_err(break_end);
         let resolution = self.resolution.consume_err(resolution);
+        let daily_target = self.daily_target.consume_err(daily_target);
+        let weekly_target = self.weekly_target.consume_err(weekly_target);
+        let caps = match (
+            self.max_daily.consume_err(max_daily),
+            self.max_weekly.consume_err(max_weekly),
+        ) {
+            (Ok(max_daily), Ok(max_weekly)) => Some((max_daily, max_weekly)),
+            _ => None,
+        };
+        let max_work_without_break = self
+            .max_work_without_break
+            .consume_err(max_work_without_break);
+        let min_break = self.min_break.consume_err(min_break);
+        let break_limit = match (max_work_without_break, min_break) {
+            (Ok(max_work_minutes), Ok(min_break_minutes)) => Some(BreakLimit {
+                max_work_minutes,
+                min_break_minutes,
+                notify: self.break_notification,
+            }),
+            _ => None,
+        };
+        let daily_budget = self
+            .daily_budget
+            .consume_err(daily_budget)
+            .map(|minutes| DailyBudget {
+                minutes,
+                notify: self.budget_notification,
+            });
+
+        let mut weekday_overrides = Some(Vec::<WeekdayBreaks>::new());
+        // rows are checked independently, so duplicates are found next to invalid rows
+        let mut seen_weekdays = Vec::new();
+        for wb in &mut self.weekday_breaks {
+            let weekday = match chrono::Weekday::from_str(wb.weekday.text.trim()) {
+                _ if wb.weekday.text.trim().is_empty() => Err("Missing weekday".to_string()),
+                Ok(w) if seen_weekdays.contains(&w) => Err(format!("Duplicate weekday {w}")),
+                Ok(w) => {
+                    seen_weekdays.push(w);
+                    Ok(w)
+                }
+                Err(_) => Err("Invalid weekday".to_string()),
+            };
+            let dur = validate_num(&wb.min_breaks, 6 * 60);
+            let mw = validate_num(&wb.min_work, 12 * 60);
+            let start = validate_default_break_start(&wb.break_start, &dur);
+            let end = validate_default_break_end(&wb.break_end, &start, &dur);
+
+            let weekday = wb.weekday.consume_err(weekday);
+            let dur = wb.min_breaks.consume_err(dur);
+            let mw = wb.min_work.consume_err(mw);
+            let start = wb.break_start.consume_err(start);
+            let end = wb.break_end.consume_err(end);
+
+            match (weekday, dur, mw, start, end) {
+                (Ok(weekday), Ok(dur), Ok(mw), Ok(s), Ok(e)) => {
+                    if let Some(overrides) = &mut weekday_overrides {
+                        overrides.push(WeekdayBreaks {
+                            weekday,
+                            min_breaks_minutes: dur,
+                            min_work_time_minutes: mw,
+                            default_break: (s, e),
+                        });
+                    }
+                }
+                _ => weekday_overrides = None,
+            }
+        }
 
-        let breaks = match (breaks_dur, min_work, break_start, break_end) {
-            (Ok(dur), Ok(mw), Ok(s), Ok(e)) => Some(BreaksConfig {
+        let breaks = match (
+            breaks_dur,
+            min_work,
+            break_start,
+            break_end,
+            weekday_overrides,
+        ) {
+            (Ok(dur), Ok(mw), Ok(s), Ok(e), Some(weekday_overrides)) => Some(BreaksConfig {
                 min_breaks_minutes: dur,
                 min_work_time_minutes: mw,
                 default_break: (s, e),
+                weekday_overrides,
             }),
             _ => None,
         };
 
-        match (db_dir, resolution, max_recent, breaks, shortcuts) {
+        match (
+            db_dir,
+            resolution,
+            max_recent,
+            home_zone,
+            locations,
+            projects,
+            meeting_prefixes,
+            absence_categories,
+            holidays,
+            issue_colors,
+            project_targets,
+            hourly_rates,
+            export_file_pattern,
+            daily_target,
+            weekly_target,
+            caps,
+            break_limit,
+            daily_budget,
+            breaks,
+            shortcuts,
+        ) {
             (
                 Ok(db_dir),
                 Ok(resolution_minutes),
                 Ok(max_recent_issues),
+                Ok(home_zone),
+                Ok(locations),
+                Ok(projects),
+                Ok(meeting_prefixes),
+                Ok(absence_categories),
+                Ok(holidays),
+                Ok(issue_colors),
+                Ok(project_targets),
+                Ok(hourly_rates),
+                Ok(export_file_pattern),
+                Ok(daily_target_minutes),
+                Ok(weekly_target_minutes),
+                Some((max_daily_minutes, max_weekly_minutes)),
+                Some(break_limit),
+                Ok(daily_budget),
                 Some(breaks),
                 Some(issue_shortcuts),
             ) => Some(SettingsSer {
@@ -246,6 +951,49 @@ impl SettingsUI {
                 issue_shortcuts,
                 breaks,
                 max_recent_issues,
+                seconds_precision: self.seconds_precision,
+                home_zone,
+                recent_ranking: self.recent_ranking,
+                locations,
+                projects,
+                meeting_prefixes,
+                absence_categories,
+                recurring_bookings: self.original.recurring_bookings.clone(),
+                holidays,
+                daily_target_minutes,
+                weekly_target_minutes,
+                max_daily_minutes,
+                max_weekly_minutes,
+                break_limit,
+                daily_budget,
+                day_start_reminder: self.original.day_start_reminder,
+                overlap_handling: self.overlap_handling,
+                auto_close_work: self.auto_close_work,
+                field_advance: FieldAdvance {
+                    enabled: self.field_advance,
+                    ..self.original.field_advance.clone()
+                },
+                time_format: self.time_format,
+                date_format: self.date_format,
+                high_contrast: self.high_contrast,
+                text_summary: self.text_summary,
+                confirm_deletes: self.confirm_deletes,
+                // collapsed in the recent issues panel while the settings are open
+                collapsed_projects: self.settings.load().collapsed_projects.clone(),
+                issue_colors,
+                project_targets,
+                hourly_rates,
+                export_file_pattern,
+                auto_export: self.auto_export,
+                extended_export: self.extended_export,
+                git_commits: self.git_commits,
+                email: self.original.email.clone(),
+                webhook: self.original.webhook.clone(),
+                jira: self.original.jira.clone(),
+                hooks: self.original.hooks.clone(),
+                export_template: self.original.export_template.clone(),
+                global_hotkey: self.original.global_hotkey.clone(),
+                trace: self.trace.clone(),
             }),
             _ => None,
         }
@@ -270,6 +1018,7 @@ impl SettingsUI {
         }
 
         let mut result = BTreeMap::new();
+        let mut valid = true;
 
         for ShortCutUi {
             shortcut,
@@ -300,10 +1049,16 @@ impl SettingsUI {
                     default_action: empty_to_none(&default_action.text),
                 };
                 result.insert(sc, issue);
+            } else {
+                valid = false;
             }
         }
 
-        Some(result)
+        if valid {
+            Some(result)
+        } else {
+            None
+        }
     }
 }
 
@@ -312,14 +1067,36 @@ type VResult<T> = Result<T, String>;
 impl<'a> FocusHandler<'a, Vec<&'a mut text_input::State>> for SettingsUI {
     fn focus_order(&'a mut self) -> Vec<&'a mut State> {
         let mut result = vec![
+            &mut self.new_profile.input,
             &mut self.db_dir.input,
             &mut self.resolution.input,
+            &mut self.daily_target.input,
+            &mut self.weekly_target.input,
+            &mut self.max_daily.input,
+            &mut self.max_weekly.input,
             &mut self.max_recent_issues.input,
+            &mut self.home_zone.input,
+            &mut self.locations.input,
+            &mut self.projects.input,
+            &mut self.meeting_prefixes.input,
+            &mut self.absence_categories.input,
+            &mut self.holidays.input,
+            &mut self.issue_colors.input,
+            &mut self.project_targets.input,
+            &mut self.hourly_rates.input,
+            &mut self.export_file_pattern.input,
             &mut self.min_breaks.input,
             &mut self.min_work.input,
             &mut self.default_break_start.input,
             &mut self.default_break_end.input,
         ];
+        for e in &mut self.weekday_breaks {
+            result.extend(e.inputs().map(|i| &mut i.input));
+        }
+        result.push(&mut self.max_work_without_break.input);
+        result.push(&mut self.min_break.input);
+        result.push(&mut self.daily_budget.input);
+        result.push(&mut self.shared_file.input);
         for e in &mut self.shortcuts {
             result.push(&mut e.shortcut.input);
             result.push(&mut e.id.input);
@@ -341,11 +1118,49 @@ impl MainView for SettingsUI {
         ]);
 
         let breaks_time = Row::with_children(vec![
-            self.default_break_start
-                .show_with_input_width("Default break start (hh:mm):", Length::Units(60)),
+            self.default_break_start.show_time_with_input_width(
+                "Default break start (hh:mm):",
+                Length::Units(60),
+                |steps| scroll_break(BreakTime::DefaultStart, steps),
+            ),
+            h_space(style::DSPACE),
+            self.default_break_end.show_time_with_input_width(
+                "Default break end (hh:mm):",
+                Length::Units(60),
+                |steps| scroll_break(BreakTime::DefaultEnd, steps),
+            ),
+        ]);
+
+        let mut weekday_breaks = Column::new().spacing(4).push(Row::with_children(vec![
+            text("Break rules per weekday (Mon, Tue, ...):"),
+            h_space(style::SPACE),
+            style::inline_button(&mut self.add_weekday_breaks_button, "+")
+                .on_press(Message::SettingsUi(SettingsUIMessage::AddWeekdayBreaks))
+                .into(),
+        ]));
+        for (index, wb) in self.weekday_breaks.iter_mut().enumerate() {
+            weekday_breaks = weekday_breaks.push(wb.show(index));
+        }
+
+        let break_limit = Row::with_children(vec![
+            self.max_work_without_break.show_with_input_width(
+                "Warn after work without break (Minutes, 0 for never):",
+                Length::Units(60),
+            ),
+            h_space(style::DSPACE),
+            self.min_break
+                .show_with_input_width("Shortest break (Minutes):", Length::Units(60)),
+        ]);
+        let daily_budget = Row::with_children(vec![
+            self.daily_budget.show_with_input_width(
+                "Daily budget of today's work (Minutes, 0 for none):",
+                Length::Units(60),
+            ),
             h_space(style::DSPACE),
-            self.default_break_end
-                .show_with_input_width("Default break end (hh:mm):", Length::Units(60)),
+            Checkbox::new(self.budget_notification, "Desktop notification", |b| {
+                Message::SettingsUi(SettingsUIMessage::BudgetNotification(b))
+            })
+            .into(),
         ]);
 
         let mut shortcuts = Scrollable::new(&mut self.shortcuts_scroll)
@@ -366,16 +1181,70 @@ impl MainView for SettingsUI {
                 reset_button.on_press(Message::SettingsUi(SettingsUIMessage::ResetSettings))
         }
 
-        let submit_button = Button::new(&mut self.submit_button, text("Submit"))
-            .style(style::Tab)
-            .on_press(Message::SettingsUi(SettingsUIMessage::SubmitSettings));
+        let mut submit_button =
+            Button::new(&mut self.submit_button, text("Submit")).style(style::Tab);
+        if self.valid {
+            submit_button =
+                submit_button.on_press(Message::SettingsUi(SettingsUIMessage::SubmitSettings))
+        }
+
+        let file_changed = if self.file_changed {
+            text("Settings file changed: Submit keeps your edits, Reset loads the file  ")
+        } else {
+            h_space(Length::Shrink)
+        };
+
+        let mut shared = Row::with_children(vec![
+            self.shared_file
+                .show_with_input_width("Shared shortcuts file:", Length::Units(400)),
+            h_space(style::SPACE),
+            style::inline_button(&mut self.export_button, "Export")
+                .on_press(Message::SettingsUi(SettingsUIMessage::ExportShared))
+                .into(),
+            h_space(style::SPACE),
+            style::inline_button(&mut self.import_button, "Import")
+                .on_press(Message::SettingsUi(SettingsUIMessage::ImportShared))
+                .into(),
+            h_space(style::DSPACE),
+            text(&self.shared_info),
+        ]);
+        if self.pending_import.is_some() {
+            shared = shared
+                .push(h_space(style::SPACE))
+                .push(
+                    style::inline_button(&mut self.skip_button, "Skip").on_press(
+                        Message::SettingsUi(SettingsUIMessage::ConfirmImport(MergeMode::Skip)),
+                    ),
+                )
+                .push(h_space(style::SPACE))
+                .push(
+                    style::inline_button(&mut self.overwrite_button, "Overwrite").on_press(
+                        Message::SettingsUi(SettingsUIMessage::ConfirmImport(MergeMode::Overwrite)),
+                    ),
+                )
+                .push(h_space(style::SPACE))
+                .push(
+                    style::inline_button(&mut self.cancel_button, "Cancel")
+                        .on_press(Message::SettingsUi(SettingsUIMessage::CancelImport)),
+                );
+        }
+        let shared = shared.into();
+
+        let profiles = profiles_view(
+            &self.settings.load(),
+            &mut self.profile_buttons,
+            &mut self.new_profile,
+            &mut self.add_profile_button,
+        );
 
         let content = Column::with_children(vec![
+            profiles,
             v_space(style::SPACE),
             Row::with_children(vec![
                 self.db_dir
                     .show_with_input_width("Storage directory:", Length::Units(400)),
                 h_space(Length::Fill),
+                file_changed,
                 submit_button.into(),
                 h_space(style::SPACE),
                 reset_button.into(),
@@ -385,12 +1254,178 @@ impl MainView for SettingsUI {
             self.resolution
                 .show_with_input_width("Booking resolution (Minutes):", Length::Units(60)),
             v_space(style::SPACE),
+            self.daily_target
+                .show_with_input_width("Daily target work time (Minutes):", Length::Units(60)),
+            v_space(style::SPACE),
+            self.weekly_target
+                .show_with_input_width("Weekly target work time (Minutes):", Length::Units(60)),
+            v_space(style::SPACE),
+            self.max_daily.show_with_input_width(
+                "Maximum daily work time (Minutes, empty for no cap):",
+                Length::Units(60),
+            ),
+            v_space(style::SPACE),
+            self.max_weekly.show_with_input_width(
+                "Maximum weekly work time (Minutes, empty for no cap):",
+                Length::Units(60),
+            ),
+            v_space(style::SPACE),
             self.max_recent_issues
                 .show("Maximum number of recent issues:"),
+            v_space(style::SPACE),
+            Checkbox::new(
+                self.recent_ranking == RecentRanking::Frequency,
+                "Rank recent issues by usage count and recency",
+                |b| Message::SettingsUi(SettingsUIMessage::RankByFrequency(b)),
+            )
+            .into(),
+            v_space(style::SPACE),
+            self.home_zone.show_with_input_width(
+                "Home time zone (+hh:mm, empty for system time zone):",
+                Length::Units(80),
+            ),
+            v_space(style::SPACE),
+            self.locations
+                .show_with_input_width("Custom locations (comma separated):", Length::Units(400)),
+            v_space(style::SPACE),
+            self.projects.show_with_input_width(
+                "Valid projects (comma separated, empty for all):",
+                Length::Units(400),
+            ),
+            v_space(style::SPACE),
+            self.meeting_prefixes.show_with_input_width(
+                "Meeting issues (id prefixes, comma separated):",
+                Length::Units(400),
+            ),
+            v_space(style::SPACE),
+            self.absence_categories.show_with_input_width(
+                "Absence categories (name=code, comma separated):",
+                Length::Units(400),
+            ),
+            v_space(style::SPACE),
+            self.holidays.show_with_input_width(
+                "Holidays (YYYY-MM-DD, comma separated):",
+                Length::Units(400),
+            ),
+            v_space(style::SPACE),
+            self.issue_colors.show_with_input_width(
+                "Issue colors (issue=#rrggbb, comma separated):",
+                Length::Units(400),
+            ),
+            v_space(style::SPACE),
+            self.project_targets.show_with_input_width(
+                "Project targets (project=percent, comma separated):",
+                Length::Units(400),
+            ),
+            v_space(style::SPACE),
+            self.hourly_rates.show_with_input_width(
+                "Hourly rates (issue prefix=rate, comma separated):",
+                Length::Units(400),
+            ),
+            v_space(style::SPACE),
+            self.export_file_pattern.show_with_input_width(
+                "Export file ({year}, {month}, {day}, {week}, {date}):",
+                Length::Units(400),
+            ),
+            v_space(style::SPACE),
+            Checkbox::new(
+                self.auto_export,
+                "Write the export file when the end of the day is stored",
+                |b| Message::SettingsUi(SettingsUIMessage::AutoExport(b)),
+            )
+            .into(),
+            v_space(style::SPACE),
+            Checkbox::new(
+                self.extended_export,
+                "Export the location, standby and absences in additional columns",
+                |b| Message::SettingsUi(SettingsUIMessage::ExtendedExport(b)),
+            )
+            .into(),
+            v_space(style::SPACE),
+            Checkbox::new(
+                self.git_commits,
+                "Commit every stored day to the git repository of the database",
+                |b| Message::SettingsUi(SettingsUIMessage::GitCommits(b)),
+            )
+            .into(),
+            v_space(style::SPACE),
+            Checkbox::new(
+                self.seconds_precision,
+                "Keep seconds of booked times (hh:mm:ss)",
+                |b| Message::SettingsUi(SettingsUIMessage::SecondsPrecision(b)),
+            )
+            .into(),
+            v_space(style::SPACE),
+            Checkbox::new(
+                self.overlap_handling == OverlapHandling::Adjust,
+                "Move work overlapping existing bookings into the nearest free time",
+                |b| Message::SettingsUi(SettingsUIMessage::AdjustOverlaps(b)),
+            )
+            .into(),
+            v_space(style::SPACE),
+            Checkbox::new(
+                self.auto_close_work,
+                "End the running issue when another issue starts or the day ends",
+                |b| Message::SettingsUi(SettingsUIMessage::AutoCloseWork(b)),
+            )
+            .into(),
+            v_space(style::SPACE),
+            Checkbox::new(
+                self.field_advance,
+                "Move to the next field of the inline editor on separators like space",
+                |b| Message::SettingsUi(SettingsUIMessage::FieldAdvance(b)),
+            )
+            .into(),
+            v_space(style::SPACE),
+            Checkbox::new(
+                self.time_format == TimeFormat::H12,
+                "Show 12-hour times (1:30 PM)",
+                |b| Message::SettingsUi(SettingsUIMessage::TwelveHourTimes(b)),
+            )
+            .into(),
+            v_space(style::SPACE),
+            Checkbox::new(
+                self.date_format == DateFormat::Dotted,
+                "Show dates as dd.mm.yyyy",
+                |b| Message::SettingsUi(SettingsUIMessage::DottedDates(b)),
+            )
+            .into(),
+            v_space(style::SPACE),
+            Checkbox::new(self.high_contrast, "High contrast colors", |b| {
+                Message::SettingsUi(SettingsUIMessage::HighContrast(b))
+            })
+            .into(),
+            v_space(style::SPACE),
+            Checkbox::new(
+                self.text_summary,
+                "Show a text summary of the view (ctrl+H copies it)",
+                |b| Message::SettingsUi(SettingsUIMessage::TextSummary(b)),
+            )
+            .into(),
+            v_space(style::SPACE),
+            Checkbox::new(self.confirm_deletes, "Ask before deleting entries", |b| {
+                Message::SettingsUi(SettingsUIMessage::ConfirmDeletes(b))
+            })
+            .into(),
             v_space(style::DSPACE),
             breaks_dur.into(),
             v_space(style::SPACE),
             breaks_time.into(),
+            v_space(style::SPACE),
+            weekday_breaks.into(),
+            v_space(style::SPACE),
+            break_limit.into(),
+            v_space(style::SPACE),
+            Checkbox::new(
+                self.break_notification,
+                "Desktop notification when working too long without break",
+                |b| Message::SettingsUi(SettingsUIMessage::BreakNotification(b)),
+            )
+            .into(),
+            v_space(style::SPACE),
+            daily_budget.into(),
+            v_space(style::DSPACE),
+            shared,
             v_space(style::DSPACE),
             Row::with_children(vec![
                 text("Configured shortcuts:"),
@@ -409,25 +1444,140 @@ impl MainView for SettingsUI {
     fn update(&mut self, msg: Message) -> Option<Message> {
         match msg {
             Message::TextChanged(value) => self.update_text(value),
+            Message::SettingsUi(SettingsUIMessage::ScrollBreak(field, steps)) => {
+                self.scroll_break(field, steps)
+            }
             Message::SettingsUi(SettingsUIMessage::AddShortcut) => {
                 self.shortcuts.push(ShortCutUi::empty());
                 self.shortcuts.last_mut().unwrap().shortcut.input.focus();
                 self.shortcuts_scroll.snap_to(1.0);
                 None
             }
+            Message::SettingsUi(SettingsUIMessage::AddWeekdayBreaks) => {
+                self.weekday_breaks.push(WeekdayBreaksUi::empty());
+                self.weekday_breaks
+                    .last_mut()
+                    .unwrap()
+                    .weekday
+                    .input
+                    .focus();
+                None
+            }
+            Message::SettingsUi(SettingsUIMessage::SecondsPrecision(seconds_precision)) => {
+                self.seconds_precision = seconds_precision;
+                None
+            }
+            Message::SettingsUi(SettingsUIMessage::AdjustOverlaps(adjust)) => {
+                self.overlap_handling = if adjust {
+                    OverlapHandling::Adjust
+                } else {
+                    OverlapHandling::Warn
+                };
+                None
+            }
+            Message::SettingsUi(SettingsUIMessage::AutoCloseWork(auto_close)) => {
+                self.auto_close_work = auto_close;
+                None
+            }
+            Message::SettingsUi(SettingsUIMessage::FieldAdvance(advance)) => {
+                self.field_advance = advance;
+                None
+            }
+            Message::SettingsUi(SettingsUIMessage::TwelveHourTimes(twelve_hour)) => {
+                self.time_format = if twelve_hour {
+                    TimeFormat::H12
+                } else {
+                    TimeFormat::H24
+                };
+                None
+            }
+            Message::SettingsUi(SettingsUIMessage::DottedDates(dotted)) => {
+                self.date_format = if dotted {
+                    DateFormat::Dotted
+                } else {
+                    DateFormat::Iso
+                };
+                None
+            }
+            Message::SettingsUi(SettingsUIMessage::HighContrast(high_contrast)) => {
+                self.high_contrast = high_contrast;
+                None
+            }
+            Message::SettingsUi(SettingsUIMessage::TextSummary(text_summary)) => {
+                self.text_summary = text_summary;
+                None
+            }
+            Message::SettingsUi(SettingsUIMessage::ConfirmDeletes(confirm_deletes)) => {
+                self.confirm_deletes = confirm_deletes;
+                None
+            }
+            Message::SettingsUi(SettingsUIMessage::BreakNotification(notify)) => {
+                self.break_notification = notify;
+                None
+            }
+            Message::SettingsUi(SettingsUIMessage::BudgetNotification(notify)) => {
+                self.budget_notification = notify;
+                None
+            }
+            Message::SettingsUi(SettingsUIMessage::AutoExport(auto_export)) => {
+                self.auto_export = auto_export;
+                None
+            }
+            Message::SettingsUi(SettingsUIMessage::ExtendedExport(extended)) => {
+                self.extended_export = extended;
+                None
+            }
+            Message::SettingsUi(SettingsUIMessage::GitCommits(git_commits)) => {
+                self.git_commits = git_commits;
+                None
+            }
+            Message::SettingsUi(SettingsUIMessage::RankByFrequency(by_frequency)) => {
+                self.recent_ranking = if by_frequency {
+                    RecentRanking::Frequency
+                } else {
+                    RecentRanking::Recency
+                };
+                None
+            }
             Message::SettingsUi(SettingsUIMessage::ResetSettings) => {
                 let settings = self.settings.clone();
                 let guard = settings.load_full();
                 settings.store(Arc::new(guard.apply_ser(self.original.clone())));
-                *self = *SettingsUI::new(settings);
+                *self = *SettingsUI::new(settings, self.recent_issues.clone());
+                None
+            }
+            Message::SettingsUi(SettingsUIMessage::FileChanged(before)) => {
+                if self.validate().as_ref() == Some(&*before) {
+                    *self = *SettingsUI::new(self.settings.clone(), self.recent_issues.clone());
+                } else {
+                    // Reset restores the file instead of the settings before the edits
+                    self.original = SettingsSer::from_settings(&self.settings.load());
+                    self.settings_changed = true;
+                    self.file_changed = true;
+                }
+                None
+            }
+            Message::SettingsUi(SettingsUIMessage::ExportShared) => {
+                self.export_shared();
+                None
+            }
+            Message::SettingsUi(SettingsUIMessage::ImportShared) => self.import_shared(),
+            Message::SettingsUi(SettingsUIMessage::ConfirmImport(mode)) => self
+                .pending_import
+                .take()
+                .and_then(|shared| self.merge_shared(shared, mode)),
+            Message::SettingsUi(SettingsUIMessage::AddProfile) => self.add_profile(),
+            Message::SettingsUi(SettingsUIMessage::CancelImport) => {
+                self.pending_import = None;
+                self.shared_info.clear();
                 None
             }
             Message::Next => {
-                let _ = self.validate();
+                self.valid = self.validate().is_some();
                 self.focus_next()
             }
             Message::Previous => {
-                let _ = self.validate();
+                self.valid = self.validate().is_some();
                 self.focus_previous()
             }
             Message::SubmitCurrent(_) | Message::SettingsUi(SettingsUIMessage::SubmitSettings) => {
@@ -435,14 +1585,47 @@ impl MainView for SettingsUI {
                     self.settings_changed = true;
                     let updated = self.settings.load_full().apply_ser(x);
                     self.settings.store(Arc::new(updated));
+                    Some(Message::WriteSettings)
+                } else {
+                    None
                 }
-                None
             }
             _ => None,
         }
     }
 }
 
+fn profiles_view<'a>(
+    settings: &Settings,
+    buttons: &'a mut Vec<button::State>,
+    new_profile: &'a mut MyTextInput,
+    add_button: &'a mut button::State,
+) -> QElement<'a> {
+    let names = settings.profile_names();
+    buttons.resize_with(names.len(), button::State::new);
+
+    let mut row = Row::new().push(text("Profile:"));
+    for (name, state) in names.into_iter().zip(buttons.iter_mut()) {
+        let label = text(name.as_deref().unwrap_or("default"));
+        let button = if name == settings.profile {
+            Button::new(state, label).style(style::ActiveTab)
+        } else {
+            Button::new(state, label).style(style::Tab)
+        };
+        row = row
+            .push(h_space(style::SPACE))
+            .push(button.on_press(Message::SwitchProfile(name)));
+    }
+    row.push(h_space(style::DSPACE))
+        .push(new_profile.show_with_input_width("New profile:", Length::Units(150)))
+        .push(h_space(style::SPACE))
+        .push(
+            style::inline_button(add_button, "Add")
+                .on_press(Message::SettingsUi(SettingsUIMessage::AddProfile)),
+        )
+        .into()
+}
+
 fn no_check(_: &str) -> bool {
     true
 }
@@ -464,6 +1647,10 @@ fn valid_time(input: &str) -> bool {
     matches!(r, ParseResult::Valid(_)) && rest.is_empty()
 }
 
+fn scroll_break(field: BreakTime, steps: i32) -> Message {
+    Message::SettingsUi(SettingsUIMessage::ScrollBreak(field, steps))
+}
+
 fn accept_time(input: &str) -> bool {
     VALID_TIME.is_match(input)
 }
@@ -480,9 +1667,24 @@ fn accept_number(input: &str) -> bool {
     VALID_NUMBER.is_match(input)
 }
 
+fn accept_utc_offset(input: &str) -> bool {
+    VALID_UTC_OFFSET.is_match(input)
+}
+
+fn accept_weekday(input: &str) -> bool {
+    VALID_WEEKDAY.is_match(input)
+}
+
+fn accept_profile_name(input: &str) -> bool {
+    VALID_PROFILE_NAME.is_match(input)
+}
+
 lazy_static::lazy_static! {
     static ref VALID_NUMBER: Regex = Regex::new("^[0-9]{0,4}$").unwrap();
     static ref VALID_ISSUE: Regex = Regex::new("(^$)|(^[a-zA-Z]+(-[0-9]*)?$)").unwrap();
     static ref VALID_TIME: Regex = Regex::new("^([0-9]{1,2}:?([0-9]{0,2}))?$").unwrap();
+    static ref VALID_UTC_OFFSET: Regex = Regex::new("^([+-][0-9]{0,2}:?[0-9]{0,2})?$").unwrap();
+    static ref VALID_WEEKDAY: Regex = Regex::new("^[a-zA-Z]{0,9}$").unwrap();
+    static ref VALID_PROFILE_NAME: Regex = Regex::new("^[a-zA-Z0-9_-]{0,30}$").unwrap();
     static ref VALID_SHORTCUT: Regex = Regex::new("^[a-zA-Z]?$").unwrap();
 }
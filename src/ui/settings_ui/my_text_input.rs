@@ -1,7 +1,13 @@
+use std::num::NonZeroU32;
+
+use crate::parsing::locale::TimeFormat;
+use crate::parsing::parse_result::ParseResult;
+use crate::parsing::time::Time;
 use crate::ui::util::h_space;
+use crate::ui::wheel_steps::WheelSteps;
 use crate::ui::{style, text, Message, QElement, QRenderer};
 use iced_core::Length;
-use iced_native::widget::{text_input, Row, TextInput};
+use iced_native::widget::{text_input, Column, Row, Text, TextInput};
 
 pub struct MyTextInput {
     pub text: String,
@@ -41,7 +47,32 @@ impl MyTextInput {
         result = result.push(h_space(style::SPACE));
         result = result.push(self.show_text_input(width));
 
-        result.into()
+        with_error(result.into(), self.error.as_deref())
+    }
+
+    /// Input of a time, the mouse wheel over the input sends `on_steps`
+    pub fn show_time_with_input_width<F>(
+        &mut self,
+        label: &'static str,
+        width: Length,
+        on_steps: F,
+    ) -> QElement
+    where
+        F: Fn(i32) -> Message + 'static,
+    {
+        let row = Row::with_children(vec![
+            text(label),
+            h_space(style::SPACE),
+            self.show_time_input(width, on_steps),
+        ]);
+        with_error(row.into(), self.error.as_deref())
+    }
+
+    pub fn show_time_input<F>(&mut self, width: Length, on_steps: F) -> QElement
+    where
+        F: Fn(i32) -> Message + 'static,
+    {
+        WheelSteps::new(self.show_text_input(width), on_steps).into()
     }
 
     pub fn show_text_input(&mut self, width: Length) -> TextInput<Message, QRenderer> {
@@ -65,6 +96,14 @@ impl MyTextInput {
         }
     }
 
+    /// Moves the time of the input by resolution steps, other input is kept
+    pub fn step_time(&mut self, steps: i32, resolution: NonZeroU32) {
+        if let (ParseResult::Valid(time), "") = Time::parse_prefix(self.text.trim()) {
+            let time = time.step(steps, resolution);
+            self.accept_input(time.format(TimeFormat::H24));
+        }
+    }
+
     pub fn consume_err<T>(&mut self, result: Result<T, String>) -> Result<T, ()> {
         match result {
             Ok(t) => {
@@ -78,3 +117,19 @@ impl MyTextInput {
         }
     }
 }
+
+/// Shows the validation error as text below the input
+pub fn with_error<'a>(input: QElement<'a>, error: Option<&str>) -> QElement<'a> {
+    if let Some(error) = error {
+        Column::with_children(vec![
+            input,
+            Text::new(error)
+                .color(style::ERROR_COLOR)
+                .size(style::FONT_SIZE - 2)
+                .into(),
+        ])
+        .into()
+    } else {
+        input
+    }
+}
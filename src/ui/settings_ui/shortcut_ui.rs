@@ -1,5 +1,5 @@
 use crate::data::JiraIssue;
-use crate::ui::settings_ui::my_text_input::MyTextInput;
+use crate::ui::settings_ui::my_text_input::{with_error, MyTextInput};
 use crate::ui::util::h_space;
 use crate::ui::{settings_ui, style, QElement};
 use iced_core::Length;
@@ -37,7 +37,18 @@ impl ShortCutUi {
     }
 
     pub fn show(&mut self) -> QElement {
-        Row::with_children(vec![
+        let errors: Vec<&str> = [
+            &self.shortcut.error,
+            &self.id.error,
+            &self.description.error,
+            &self.default_action.error,
+        ]
+        .into_iter()
+        .filter_map(|e| e.as_deref())
+        .collect();
+        let errors = errors.join(", ");
+
+        let row = Row::with_children(vec![
             self.shortcut.show_text_input(Length::Units(30)).into(),
             h_space(style::SPACE),
             self.id.show_text_input(Length::Units(100)).into(),
@@ -47,7 +58,8 @@ impl ShortCutUi {
             self.default_action
                 .show_text_input(Length::Units(300))
                 .into(),
-        ])
-        .into()
+        ]);
+
+        with_error(row.into(), Some(errors.as_str()).filter(|e| !e.is_empty()))
     }
 }
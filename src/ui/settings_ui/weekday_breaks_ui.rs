@@ -0,0 +1,93 @@
+use crate::conf::WeekdayBreaks;
+use crate::ui::settings_ui::my_text_input::{with_error, MyTextInput};
+use crate::ui::settings_ui::{scroll_break, BreakTime};
+use crate::ui::util::h_space;
+use crate::ui::{settings_ui, style, text, QElement};
+use iced_core::Length;
+use iced_native::widget::Row;
+
+pub struct WeekdayBreaksUi {
+    pub weekday: MyTextInput,
+    pub min_breaks: MyTextInput,
+    pub min_work: MyTextInput,
+    pub break_start: MyTextInput,
+    pub break_end: MyTextInput,
+}
+
+impl WeekdayBreaksUi {
+    pub fn new(o: &WeekdayBreaks) -> Self {
+        Self::build(Some(o))
+    }
+
+    pub fn empty() -> Self {
+        Self::build(None)
+    }
+
+    pub fn build(o: Option<&WeekdayBreaks>) -> Self {
+        WeekdayBreaksUi {
+            weekday: MyTextInput::new_opt(o.map(|o| o.weekday), settings_ui::accept_weekday),
+            min_breaks: MyTextInput::new_opt(
+                o.map(|o| o.min_breaks_minutes),
+                settings_ui::accept_number,
+            ),
+            min_work: MyTextInput::new_opt(
+                o.map(|o| o.min_work_time_minutes),
+                settings_ui::accept_number,
+            ),
+            break_start: MyTextInput::new_opt(
+                o.map(|o| o.default_break.0),
+                settings_ui::accept_time,
+            ),
+            break_end: MyTextInput::new_opt(o.map(|o| o.default_break.1), settings_ui::accept_time),
+        }
+    }
+
+    /// All inputs of the row in focus order
+    pub fn inputs(&mut self) -> [&mut MyTextInput; 5] {
+        [
+            &mut self.weekday,
+            &mut self.min_breaks,
+            &mut self.min_work,
+            &mut self.break_start,
+            &mut self.break_end,
+        ]
+    }
+
+    /// The row of the rule at `index` of the form
+    pub fn show(&mut self, index: usize) -> QElement {
+        let errors: Vec<String> = self
+            .inputs()
+            .into_iter()
+            .filter_map(|i| i.error.clone())
+            .collect();
+        let errors = errors.join(", ");
+
+        let row = Row::with_children(vec![
+            self.weekday.show_text_input(Length::Units(60)).into(),
+            h_space(style::DSPACE),
+            text("Break:"),
+            h_space(style::SPACE),
+            self.min_breaks.show_text_input(Length::Units(60)).into(),
+            h_space(style::DSPACE),
+            text("After work time:"),
+            h_space(style::SPACE),
+            self.min_work.show_text_input(Length::Units(60)).into(),
+            h_space(style::DSPACE),
+            text("Default break:"),
+            h_space(style::SPACE),
+            self.break_start
+                .show_time_input(Length::Units(60), move |steps| {
+                    scroll_break(BreakTime::WeekdayStart(index), steps)
+                }),
+            h_space(style::SPACE),
+            text("-"),
+            h_space(style::SPACE),
+            self.break_end
+                .show_time_input(Length::Units(60), move |steps| {
+                    scroll_break(BreakTime::WeekdayEnd(index), steps)
+                }),
+        ]);
+
+        with_error(row.into(), Some(errors.as_str()).filter(|e| !e.is_empty()))
+    }
+}
@@ -0,0 +1,56 @@
+use std::hash::Hash;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use iced_futures::futures::channel::mpsc;
+use iced_futures::futures::StreamExt;
+use iced_futures::BoxStream;
+use iced_native::subscription::Recipe;
+use iced_native::{event, Event, Hasher};
+use iced_winit::Subscription;
+
+use crate::ui::Message;
+
+/// Time between two checks of the settings file
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Emits [Message::SettingsFileChanged] when the size or modification time of the settings
+/// file changes, including writes of quarble itself
+pub fn watch(location: PathBuf) -> Subscription<Message> {
+    Subscription::from_recipe(SettingsWatcher(location))
+}
+
+struct SettingsWatcher(PathBuf);
+
+impl Recipe<Hasher, (Event, event::Status)> for SettingsWatcher {
+    type Output = Message;
+
+    fn hash(&self, state: &mut Hasher) {
+        std::any::TypeId::of::<Self>().hash(state);
+        self.0.hash(state);
+    }
+
+    fn stream(self: Box<Self>, _input: BoxStream<(Event, event::Status)>) -> BoxStream<Message> {
+        let (sender, receiver) = mpsc::unbounded();
+        let location = self.0;
+        std::thread::spawn(move || {
+            let mut last = file_state(&location);
+            loop {
+                std::thread::sleep(POLL_INTERVAL);
+                let current = file_state(&location);
+                // a deleted file keeps the current settings
+                let changed = current != last && current.is_some();
+                if changed && sender.unbounded_send(Message::SettingsFileChanged).is_err() {
+                    return;
+                }
+                last = current;
+            }
+        });
+        receiver.boxed()
+    }
+}
+
+fn file_state(location: &Path) -> Option<(u64, SystemTime)> {
+    let metadata = std::fs::metadata(location).ok()?;
+    Some((metadata.len(), metadata.modified().ok()?))
+}
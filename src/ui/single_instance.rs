@@ -0,0 +1,198 @@
+use std::hash::Hash;
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use iced_futures::futures::channel::mpsc;
+use iced_futures::futures::StreamExt;
+use iced_futures::BoxStream;
+use iced_native::subscription::Recipe;
+use iced_native::{event, Event, Hasher};
+use iced_winit::Subscription;
+
+use crate::data::{Action, Day};
+use crate::ui::{Message, ViewId};
+
+/// Socket of the running instance in the database directory, so instances with
+/// different databases do not interfere
+const SOCKET_NAME: &str = "quarble.sock";
+
+/// A launch that connects without sending its view must not block later launches
+const READ_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Request of a later launch, sent as one JSON line per connection
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+enum Request {
+    View(String),
+    Store { day: Day, action: Action },
+}
+
+/// Sends the view to the instance that already uses the database, which switches to it.
+///
+/// Raising the window is left to the window manager, iced cannot request the focus.
+/// Returns false if there is no running instance
+pub fn forward(db_dir: &Path, view: ViewId) -> bool {
+    send(db_dir, &Request::View(view.name().to_string()))
+}
+
+/// Lets the instance that already uses the database store the action on the day, so
+/// its copy of the day does not overwrite the action with the next save.
+///
+/// Returns false if there is no running instance
+pub fn forward_store(db_dir: &Path, day: Day, action: &Action) -> bool {
+    let request = Request::Store {
+        day,
+        action: action.clone(),
+    };
+    send(db_dir, &request)
+}
+
+fn send(db_dir: &Path, request: &Request) -> bool {
+    let mut stream = match UnixStream::connect(db_dir.join(SOCKET_NAME)) {
+        Ok(stream) => stream,
+        Err(_) => return false,
+    };
+    let sent = serde_json::to_string(request)
+        .map_err(|e| e.to_string())
+        .and_then(|line| writeln!(stream, "{}", line).map_err(|e| e.to_string()));
+    match sent {
+        Ok(()) => true,
+        Err(e) => {
+            log::warn!("Cannot forward to the running instance: {}", e);
+            false
+        }
+    }
+}
+
+/// Accepts the views and actions forwarded by later launches
+#[derive(Clone, Debug)]
+pub struct InstanceListener(Arc<Socket>);
+
+/// Removes the socket file once the listener is gone
+#[derive(Debug)]
+struct Socket {
+    listener: UnixListener,
+    path: PathBuf,
+}
+
+impl Drop for Socket {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+impl InstanceListener {
+    /// Takes over the socket, call only after [forward] found no running instance
+    pub fn bind(db_dir: &Path) -> Option<InstanceListener> {
+        let path = db_dir.join(SOCKET_NAME);
+        // left behind by an instance that crashed
+        let _ = std::fs::remove_file(&path);
+        match UnixListener::bind(&path) {
+            Ok(listener) => Some(InstanceListener(Arc::new(Socket { listener, path }))),
+            Err(e) => {
+                log::warn!("Cannot listen on {}: {}", path.display(), e);
+                None
+            }
+        }
+    }
+
+    /// Removes the socket, so later launches open their own window
+    pub fn close(&self) {
+        if let Err(e) = std::fs::remove_file(&self.0.path) {
+            log::warn!("Cannot remove {}: {}", self.0.path.display(), e);
+        }
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        Subscription::from_recipe(self.clone())
+    }
+}
+
+impl Recipe<Hasher, (Event, event::Status)> for InstanceListener {
+    type Output = Message;
+
+    fn hash(&self, state: &mut Hasher) {
+        std::any::TypeId::of::<Self>().hash(state);
+    }
+
+    fn stream(self: Box<Self>, _input: BoxStream<(Event, event::Status)>) -> BoxStream<Message> {
+        let (sender, receiver) = mpsc::unbounded();
+        let socket = self.0;
+        std::thread::spawn(move || {
+            for connection in socket.listener.incoming() {
+                let message = connection.map_err(|e| e.to_string()).and_then(read_request);
+                match message {
+                    Ok(message) => {
+                        if sender.unbounded_send(message).is_err() {
+                            return;
+                        }
+                    }
+                    Err(e) => log::warn!("Ignoring forwarded launch: {}", e),
+                }
+            }
+        });
+        receiver.boxed()
+    }
+}
+
+fn read_request(connection: UnixStream) -> Result<Message, String> {
+    connection
+        .set_read_timeout(Some(READ_TIMEOUT))
+        .map_err(|e| e.to_string())?;
+    let mut line = String::new();
+    BufReader::new(connection)
+        .read_line(&mut line)
+        .map_err(|e| e.to_string())?;
+    match serde_json::from_str(&line).map_err(|e| e.to_string())? {
+        Request::View(name) => ViewId::from_name(&name)
+            .map(Message::ChangeView)
+            .ok_or_else(|| format!("unknown view '{}'", name)),
+        Request::Store { day, action } => Ok(Message::StoreForwarded(day, action)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::os::unix::net::UnixListener;
+
+    use iced_futures::futures::executor::block_on;
+    use iced_futures::futures::{stream, StreamExt};
+    use iced_native::subscription::Recipe;
+    use tempfile::TempDir;
+
+    use crate::ui::single_instance::{forward, InstanceListener, SOCKET_NAME};
+    use crate::ui::{Message, ViewId};
+
+    #[test]
+    fn takes_over_a_stale_socket() {
+        let db_dir = TempDir::new().unwrap();
+        // a crashed instance leaves the socket file without a listener
+        drop(UnixListener::bind(db_dir.path().join(SOCKET_NAME)).unwrap());
+        assert!(db_dir.path().join(SOCKET_NAME).exists());
+        assert!(!forward(db_dir.path(), ViewId::CurrentDayUi));
+
+        let listener = InstanceListener::bind(db_dir.path()).unwrap();
+        assert!(forward(db_dir.path(), ViewId::CurrentDayUi));
+
+        drop(listener);
+        assert!(!db_dir.path().join(SOCKET_NAME).exists());
+    }
+
+    #[test]
+    fn forwarded_view_activates_the_running_instance() {
+        let db_dir = TempDir::new().unwrap();
+        let listener = InstanceListener::bind(db_dir.path()).unwrap();
+        let mut messages = Box::new(listener.clone()).stream(stream::empty().boxed());
+
+        assert!(forward(db_dir.path(), ViewId::BookSingle));
+        match block_on(messages.next()) {
+            Some(Message::ChangeView(view)) => assert_eq!(view, ViewId::BookSingle),
+            m => panic!("unexpected {:?}", m),
+        }
+
+        listener.close();
+        assert!(!forward(db_dir.path(), ViewId::BookSingle));
+    }
+}
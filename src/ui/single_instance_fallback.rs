@@ -0,0 +1,34 @@
+use std::path::Path;
+
+use iced_winit::Subscription;
+
+use crate::data::{Action, Day};
+use crate::ui::{Message, ViewId};
+
+/// Without unix sockets there is no running instance to forward to,
+/// every launch opens its own window
+pub fn forward(_db_dir: &Path, _view: ViewId) -> bool {
+    false
+}
+
+pub fn forward_store(_db_dir: &Path, _day: Day, _action: &Action) -> bool {
+    false
+}
+
+/// Never created without unix sockets
+#[derive(Clone, Debug)]
+pub enum InstanceListener {}
+
+impl InstanceListener {
+    pub fn bind(_db_dir: &Path) -> Option<InstanceListener> {
+        None
+    }
+
+    pub fn close(&self) {
+        match *self {}
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        match *self {}
+    }
+}
@@ -0,0 +1,155 @@
+use iced_winit::widget::{text_input, Column, Row, Text};
+
+use crate::conf::SettingsRef;
+use crate::data::{ActiveDay, Standby};
+use crate::parsing::parse_result::ParseResult;
+use crate::parsing::time::Time;
+use crate::ui::single_edit_ui::SingleEditUi;
+use crate::ui::top_bar::TopBar;
+use crate::ui::util::{step_input_time, v_space};
+use crate::ui::wheel_steps::WheelSteps;
+use crate::ui::widget::TextInput;
+use crate::ui::{day_info_message, style, MainView, Message, QElement};
+
+#[derive(Clone, Debug)]
+pub enum StandbyMessage {
+    TextChanged(String),
+}
+
+pub struct StandbyEdit {
+    top_bar: TopBar,
+    text: String,
+    text_state: text_input::State,
+    value: ParseResult<Standby, ()>,
+    original_entry: Option<Standby>,
+}
+
+impl StandbyEdit {
+    pub fn for_work_day(settings: SettingsRef, work_day: Option<&ActiveDay>) -> Box<Self> {
+        Box::new(Self {
+            top_bar: TopBar {
+                title: "Standby:",
+                help_text: "<start> <end>, ends before start continue on the next day",
+                info: day_info_message(work_day),
+                settings,
+            },
+            text: String::new(),
+            text_state: text_input::State::focused(),
+            value: ParseResult::None,
+            original_entry: None,
+        })
+    }
+}
+
+impl SingleEditUi<Standby> for StandbyEdit {
+    fn update_input(&mut self, input: String) {
+        self.text = input;
+        self.value = parse_standby(&self.text);
+    }
+
+    fn as_text(&self, orig: &Standby) -> String {
+        format!("{} {}", orig.start, orig.end)
+    }
+
+    fn set_orig(&mut self, orig: Standby) {
+        self.original_entry = Some(orig);
+    }
+
+    fn try_build(&self) -> Option<Standby> {
+        self.value.get_ref().cloned()
+    }
+}
+
+/// Parses `<start> <end>` or `<start>-<end>`
+fn parse_standby(input: &str) -> ParseResult<Standby, ()> {
+    let (start, rest) = Time::parse_prefix(input.trim());
+    let rest = rest.trim_start();
+    let rest = rest.strip_prefix('-').unwrap_or(rest).trim_start();
+    let (end, rest) = Time::parse_prefix(rest);
+    if !rest.trim().is_empty() {
+        return ParseResult::Invalid(());
+    }
+
+    match (start, end) {
+        (ParseResult::Valid(start), ParseResult::Valid(end)) if start != end => {
+            ParseResult::Valid(Standby { start, end })
+        }
+        (ParseResult::Valid(_), ParseResult::Valid(_)) => ParseResult::Invalid(()),
+        (ParseResult::None, _) => ParseResult::None,
+        (ParseResult::Invalid(_), _) | (_, ParseResult::Invalid(_)) => ParseResult::Invalid(()),
+        _ => ParseResult::Incomplete,
+    }
+}
+
+impl MainView for StandbyEdit {
+    fn draft(&self) -> Option<&str> {
+        Some(self.text.as_str()).filter(|_| self.original_entry.is_none())
+    }
+
+    fn view(&mut self) -> QElement {
+        let value_str = match &self.value {
+            ParseResult::Valid(s) if s.crosses_midnight() => {
+                format!("{} - {} (next day)", s.start, s.end)
+            }
+            ParseResult::Valid(s) => format!("{} - {}", s.start, s.end),
+            ParseResult::Invalid(_) => "invalid".to_string(),
+            ParseResult::Incomplete => "missing end".to_string(),
+            ParseResult::None => String::new(),
+        };
+
+        Column::with_children(vec![
+            self.top_bar.view(),
+            v_space(style::SPACE),
+            WheelSteps::new(
+                TextInput::new(&mut self.text_state, "", &self.text, |input| {
+                    Message::Sb(StandbyMessage::TextChanged(input))
+                }),
+                Message::ScrollTime,
+            )
+            .into(),
+            v_space(style::SPACE),
+            Row::with_children(vec![Text::new(value_str).into()]).into(),
+        ])
+        .into()
+    }
+
+    fn update(&mut self, msg: Message) -> Option<Message> {
+        match msg {
+            Message::ScrollTime(steps) => {
+                step_input_time(&self.top_bar.settings.load(), &self.text, steps)
+                    .map(|text| Message::Sb(StandbyMessage::TextChanged(text)))
+            }
+            Message::Sb(StandbyMessage::TextChanged(new_value)) => {
+                self.update_input(new_value);
+                None
+            }
+            Message::SubmitCurrent(stay_active) => {
+                Self::on_submit_message(self.try_build(), &mut self.original_entry, stay_active)
+            }
+            Message::StoreSuccess(stay_active) => stay_active.on_main_view_store(),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::data::test_support::time;
+    use crate::data::Standby;
+    use crate::parsing::parse_result::ParseResult;
+    use crate::parsing::time::Time;
+    use crate::ui::standby_edit::parse_standby;
+
+    #[test]
+    fn test_parse_standby() {
+        let s = |start, end| ParseResult::Valid(Standby { start, end });
+
+        assert_eq!(parse_standby("18 24"), s(time("18"), Time::MAX));
+        assert_eq!(parse_standby("18:30-6"), s(time("18:30"), time("6")));
+        assert_eq!(parse_standby(" 20 - 0730 "), s(time("20"), time("7:30")));
+        assert_eq!(parse_standby("18"), ParseResult::Incomplete);
+        assert_eq!(parse_standby("18 18"), ParseResult::Invalid(()));
+        assert_eq!(parse_standby("18 6x"), ParseResult::Invalid(()));
+        assert_eq!(parse_standby(""), ParseResult::None);
+    }
+}
@@ -1,8 +1,13 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
 use iced_core::{Background, Color, Font, Vector};
 use iced_native::widget::container::Style;
-use iced_native::widget::{button, container, text_input, Button};
+use iced_native::widget::{button, container, text_input, Button, Container, Space};
 use iced_winit::Length;
 
+use crate::ui::QElement;
+use crate::Settings;
+
 pub const LABEL_WIDTH: Length = Length::Units(28);
 pub const TIME_WIDTH: Length = Length::Units(190);
 pub const DESCRIPTION_WIDTH: Length = Length::Units(500);
@@ -22,8 +27,12 @@ pub const TEXT_INPUT_PADDING: iced_core::Padding = iced_core::Padding {
 pub const FONT_SIZE: u16 = 16;
 
 pub const HIGHLIGHT_COLOR: Color = Color::from_rgb(0.95, 0.95, 1.0);
+pub const MATCH_COLOR: Color = Color::from_rgb(0.1, 0.3, 0.8);
 pub const ERROR_COLOR: Color = Color::from_rgb(0.5, 0.0, 0.0);
 pub const ERROR_COLOR_FOCUSSED: Color = Color::from_rgb(0.9, 0.0, 0.0);
+pub const SUCCESS_COLOR: Color = Color::from_rgb(0.0, 0.4, 0.0);
+/// Outline of the control with the keyboard focus
+pub const FOCUS_COLOR: Color = Color::from_rgb(0.0, 0.35, 0.9);
 const MAIN_COLOR: Color = Color {
     r: 0.8,
     g: 0.8,
@@ -41,6 +50,18 @@ const TEXT_MAIN_COLOR: Color = Color {
 pub const DEFAULT_BACKGROUND: Background = Background::Color(Color::from_rgb(1.0, 1.0, 1.0));
 pub const ODD_BACKGROUND: Background = Background::Color(HIGHLIGHT_COLOR);
 pub const SELECTED_BACKGROUND: Background = Background::Color(MAIN_COLOR);
+const HIGH_CONTRAST_SELECTED: Background = Background::Color(Color::from_rgb(1.0, 0.9, 0.0));
+
+static HIGH_CONTRAST: AtomicBool = AtomicBool::new(false);
+
+/// Switches the style sheets to black and white with strong borders
+pub fn set_high_contrast(enabled: bool) {
+    HIGH_CONTRAST.store(enabled, Ordering::Relaxed);
+}
+
+fn high_contrast() -> bool {
+    HIGH_CONTRAST.load(Ordering::Relaxed)
+}
 
 pub struct ContentStyle;
 
@@ -69,7 +90,9 @@ impl container::StyleSheet for ContentRow {
     fn style(&self) -> Style {
         let background = match self.state {
             RowState::Even => DEFAULT_BACKGROUND,
+            RowState::Odd if high_contrast() => DEFAULT_BACKGROUND,
             RowState::Odd => ODD_BACKGROUND,
+            RowState::Selected if high_contrast() => HIGH_CONTRAST_SELECTED,
             RowState::Selected => SELECTED_BACKGROUND,
         };
         let background = Some(background);
@@ -81,10 +104,25 @@ impl container::StyleSheet for ContentRow {
     }
 }
 
+/// Filled part of a gauge
+pub struct Gauge(pub Color);
+
+impl container::StyleSheet for Gauge {
+    fn style(&self) -> Style {
+        Style {
+            background: Some(Background::Color(self.0)),
+            ..Style::default()
+        }
+    }
+}
+
 pub struct EditButton;
 
 impl button::StyleSheet for EditButton {
     fn active(&self) -> button::Style {
+        if high_contrast() {
+            return black_on_white();
+        }
         button::Style {
             shadow_offset: Vector::new(0.0, 0.0),
             background: Some(Background::Color(MAIN_COLOR)),
@@ -96,6 +134,113 @@ impl button::StyleSheet for EditButton {
     }
 }
 
+/// Inline button with the keyboard focus
+pub struct FocusedButton;
+
+impl button::StyleSheet for FocusedButton {
+    fn active(&self) -> button::Style {
+        button::Style {
+            border_width: if high_contrast() { 3.0 } else { 2.0 },
+            border_color: FOCUS_COLOR,
+            ..EditButton.active()
+        }
+    }
+}
+
+fn black_on_white() -> button::Style {
+    button::Style {
+        shadow_offset: Vector::new(0.0, 0.0),
+        background: Some(Background::Color(Color::WHITE)),
+        border_radius: 0.0,
+        border_width: 1.0,
+        border_color: Color::BLACK,
+        text_color: Color::BLACK,
+    }
+}
+
+/// Day in the year heatmap
+pub struct CalendarBlock {
+    pub color: Color,
+}
+
+impl button::StyleSheet for CalendarBlock {
+    fn active(&self) -> button::Style {
+        button::Style {
+            shadow_offset: Vector::new(0.0, 0.0),
+            background: Some(Background::Color(self.color)),
+            border_radius: 2.0,
+            border_width: 1.0,
+            border_color: Color::WHITE,
+            text_color: TEXT_MAIN_COLOR,
+        }
+    }
+}
+
+pub const BOOKED_COLOR: Color = MAIN_COLOR;
+pub const BREAK_COLOR: Color = Color::from_rgb(0.75, 0.9, 0.75);
+pub const UNBOOKED_COLOR: Color = Color::from_rgb(1.0, 0.75, 0.75);
+pub const GAP_COLOR: Color = Color::from_rgb(0.97, 0.97, 0.97);
+pub const NO_ISSUE_COLOR: Color = Color::from_rgb(0.85, 0.85, 0.85);
+/// Nothing booked to at least the daily target
+pub const HEATMAP_COLORS: [Color; 5] = [
+    Color::from_rgb(0.92, 0.92, 0.92),
+    Color::from_rgb(0.78, 0.9, 0.78),
+    Color::from_rgb(0.55, 0.8, 0.55),
+    Color::from_rgb(0.3, 0.65, 0.3),
+    Color::from_rgb(0.1, 0.45, 0.1),
+];
+
+const ISSUE_COLORS: [Color; 10] = [
+    Color::from_rgb(0.8, 0.8, 0.95),
+    Color::from_rgb(0.75, 0.9, 0.75),
+    Color::from_rgb(0.95, 0.85, 0.7),
+    Color::from_rgb(0.9, 0.75, 0.85),
+    Color::from_rgb(0.7, 0.88, 0.92),
+    Color::from_rgb(0.92, 0.92, 0.7),
+    Color::from_rgb(0.85, 0.75, 0.95),
+    Color::from_rgb(0.7, 0.92, 0.85),
+    Color::from_rgb(0.95, 0.78, 0.75),
+    Color::from_rgb(0.8, 0.85, 0.7),
+];
+
+/// Color of an issue, configured in the settings or derived from the issue id
+pub fn issue_color(settings: &Settings, ident: &str) -> Color {
+    let configured = settings
+        .issue_colors
+        .iter()
+        .find(|(issue, _)| issue.eq_ignore_ascii_case(ident))
+        .and_then(|(_, color)| parse_color(color));
+    configured.unwrap_or_else(|| {
+        let hash = ident
+            .bytes()
+            .map(|b| b.to_ascii_uppercase())
+            .fold(0usize, |h, b| h.wrapping_mul(31).wrapping_add(b as usize));
+        ISSUE_COLORS[hash % ISSUE_COLORS.len()]
+    })
+}
+
+/// Parses `#rrggbb`
+pub fn parse_color(input: &str) -> Option<Color> {
+    let hex = input.trim().strip_prefix('#')?;
+    if hex.len() != 6 {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+    Some(Color::from_rgb8(channel(0)?, channel(2)?, channel(4)?))
+}
+
+/// Colored mark in front of the entries of an issue, an empty space without issue
+pub fn issue_mark<'a>(color: Option<Color>) -> QElement<'a> {
+    let space = Space::with_width(Length::Units(6));
+    match color {
+        Some(color) => Container::new(space)
+            .height(Length::Units(FONT_SIZE))
+            .style(Gauge(color))
+            .into(),
+        None => space.into(),
+    }
+}
+
 pub struct TextInput {
     pub error: bool,
 }
@@ -114,6 +259,11 @@ impl text_input::StyleSheet for TextInput {
                 border_color: ERROR_COLOR,
                 ..DEFAULT_TI_STYLE
             }
+        } else if high_contrast() {
+            text_input::Style {
+                border_color: Color::BLACK,
+                ..DEFAULT_TI_STYLE
+            }
         } else {
             text_input::Style {
                 border_color: Color::from_rgb(0.7, 0.7, 0.7),
@@ -123,21 +273,23 @@ impl text_input::StyleSheet for TextInput {
     }
 
     fn focused(&self) -> text_input::Style {
-        if self.error {
-            text_input::Style {
-                border_color: ERROR_COLOR_FOCUSSED,
-                ..DEFAULT_TI_STYLE
-            }
-        } else {
-            text_input::Style {
-                border_color: Color::from_rgb(0.5, 0.5, 0.5),
-                ..DEFAULT_TI_STYLE
-            }
+        text_input::Style {
+            border_width: if high_contrast() { 3.0 } else { 2.0 },
+            border_color: if self.error {
+                ERROR_COLOR_FOCUSSED
+            } else {
+                FOCUS_COLOR
+            },
+            ..DEFAULT_TI_STYLE
         }
     }
 
     fn placeholder_color(&self) -> Color {
-        Color::from_rgb(0.7, 0.7, 0.7)
+        if high_contrast() {
+            Color::from_rgb(0.4, 0.4, 0.4)
+        } else {
+            Color::from_rgb(0.7, 0.7, 0.7)
+        }
     }
 
     fn value_color(&self) -> Color {
@@ -153,6 +305,13 @@ pub struct ActiveTab;
 
 impl button::StyleSheet for ActiveTab {
     fn active(&self) -> button::Style {
+        if high_contrast() {
+            return button::Style {
+                background: Some(Background::Color(Color::BLACK)),
+                text_color: Color::WHITE,
+                ..black_on_white()
+            };
+        }
         button::Style {
             shadow_offset: Vector::new(0.0, 0.0),
             background: Some(Background::Color(HIGHLIGHT_COLOR)),
@@ -176,6 +335,9 @@ pub struct Tab;
 
 impl button::StyleSheet for Tab {
     fn active(&self) -> button::Style {
+        if high_contrast() {
+            return black_on_white();
+        }
         button::Style {
             shadow_offset: Vector::new(0.0, 0.0),
             background: Some(Background::Color(MAIN_COLOR)),
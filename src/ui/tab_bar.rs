@@ -1,3 +1,5 @@
+use crate::data::{format_minutes, TargetProgress};
+use crate::db::GitStatus;
 use crate::ui::style;
 use crate::ui::util::h_space;
 use crate::ui::{Message, QElement, ViewId};
@@ -9,13 +11,27 @@ pub struct TabBar {
     active_view: ViewId,
     current_day_ui: button::State,
     fast_day_start: button::State,
+    day_start_wizard: button::State,
     fast_day_end: button::State,
+    day_end_wizard: button::State,
+    gap_fill: button::State,
     book_single: button::State,
     book_issue_start: button::State,
     book_issue_end: button::State,
+    quick_switch: button::State,
+    work_event: button::State,
+    standby: button::State,
+    quick_day: button::State,
+    reassign: button::State,
     export: button::State,
+    issue_stats: button::State,
+    week_calendar: button::State,
+    year_overview: button::State,
+    to_fix: button::State,
     settings: button::State,
     exit: button::State,
+    progress: Option<TargetProgress>,
+    git_status: Option<GitStatus>,
 }
 
 impl TabBar {
@@ -25,13 +41,27 @@ impl TabBar {
 
             current_day_ui: Default::default(),
             fast_day_start: Default::default(),
+            day_start_wizard: Default::default(),
             fast_day_end: Default::default(),
+            day_end_wizard: Default::default(),
+            gap_fill: Default::default(),
             book_single: Default::default(),
             book_issue_start: Default::default(),
             book_issue_end: Default::default(),
+            quick_switch: Default::default(),
+            work_event: Default::default(),
+            standby: Default::default(),
+            quick_day: Default::default(),
+            reassign: Default::default(),
             export: Default::default(),
+            issue_stats: Default::default(),
+            week_calendar: Default::default(),
+            year_overview: Default::default(),
+            to_fix: Default::default(),
             settings: Default::default(),
             exit: Default::default(),
+            progress: None,
+            git_status: None,
         }
     }
 
@@ -54,6 +84,13 @@ impl TabBar {
                 ViewId::FastDayStart,
             ),
             h_space(style::TAB_SPACE),
+            tab_button(
+                active,
+                &mut self.day_start_wizard,
+                "Start day (u)",
+                ViewId::DayStartWizard,
+            ),
+            h_space(style::TAB_SPACE),
             tab_button(
                 active,
                 &mut self.fast_day_end,
@@ -61,6 +98,15 @@ impl TabBar {
                 ViewId::FastDayEnd,
             ),
             h_space(style::TAB_SPACE),
+            tab_button(
+                active,
+                &mut self.day_end_wizard,
+                "End day (g)",
+                ViewId::DayEndWizard,
+            ),
+            h_space(style::TAB_SPACE),
+            tab_button(active, &mut self.gap_fill, "Fill gaps (z)", ViewId::GapFill),
+            h_space(style::TAB_SPACE),
             tab_button(
                 active,
                 &mut self.book_single,
@@ -82,10 +128,62 @@ impl TabBar {
                 ViewId::BookIssueEnd,
             ),
             h_space(style::TAB_SPACE),
+            tab_button(
+                active,
+                &mut self.quick_switch,
+                "Switch (n)",
+                ViewId::QuickSwitch,
+            ),
+            h_space(style::TAB_SPACE),
+            tab_button(active, &mut self.work_event, "Event (v)", ViewId::WorkEvent),
+            h_space(style::TAB_SPACE),
+            tab_button(active, &mut self.standby, "Standby (b)", ViewId::Standby),
+            h_space(style::TAB_SPACE),
+            tab_button(
+                active,
+                &mut self.quick_day,
+                "Quick day (q)",
+                ViewId::QuickDay,
+            ),
+            h_space(style::TAB_SPACE),
+            tab_button(active, &mut self.reassign, "Reassign (r)", ViewId::Reassign),
+            h_space(style::TAB_SPACE),
             tab_button(active, &mut self.export, "Export (x)", ViewId::Export),
             h_space(style::TAB_SPACE),
+            tab_button(
+                active,
+                &mut self.issue_stats,
+                "Statistics (a)",
+                ViewId::IssueStats,
+            ),
+            h_space(style::TAB_SPACE),
+            tab_button(
+                active,
+                &mut self.week_calendar,
+                "Week (w)",
+                ViewId::WeekCalendar,
+            ),
+            h_space(style::TAB_SPACE),
+            tab_button(
+                active,
+                &mut self.year_overview,
+                "Year (y)",
+                ViewId::YearOverview,
+            ),
+            h_space(style::TAB_SPACE),
+            tab_button(active, &mut self.to_fix, "To fix (f)", ViewId::ToFix),
+            h_space(style::TAB_SPACE),
             tab_button(active, &mut self.settings, "Settings (t)", ViewId::Settings),
             h_space(Length::Fill),
+            Text::new(
+                self.git_status
+                    .and_then(GitStatus::label)
+                    .unwrap_or_default(),
+            )
+            .into(),
+            h_space(style::TAB_SPACE),
+            progress_text(self.progress),
+            h_space(style::TAB_SPACE),
             tab_button(active, &mut self.exit, "x", ViewId::Exit),
             h_space(style::TAB_SPACE),
         ];
@@ -127,6 +225,29 @@ impl TabBar {
     pub fn set_active_view(&mut self, view: ViewId) {
         self.active_view = view;
     }
+
+    pub fn set_progress(&mut self, progress: Option<TargetProgress>) {
+        self.progress = progress;
+    }
+
+    pub fn set_git_status(&mut self, git_status: Option<GitStatus>) {
+        self.git_status = git_status;
+    }
+}
+
+/// Booked work of the active day and of the week to date against the targets
+fn progress_text<'a>(progress: Option<TargetProgress>) -> QElement<'a> {
+    let text = match progress {
+        Some(p) => format!(
+            "Day {} / {}   Week {} / {}",
+            format_minutes(p.day_minutes),
+            format_minutes(p.day_target),
+            format_minutes(p.week_minutes),
+            format_minutes(p.week_target),
+        ),
+        None => String::new(),
+    };
+    Text::new(text).into()
 }
 
 fn tab_button<'a>(
@@ -0,0 +1,123 @@
+use std::sync::Arc;
+
+use iced_core::Length;
+use iced_native::widget::{button, scrollable, Column, Row, Scrollable};
+
+use crate::data::DayToFix;
+use crate::ui::button_focus::ButtonFocus;
+use crate::ui::util::{h_space, v_space};
+use crate::ui::widget::Checkbox;
+use crate::ui::{style, text, MainView, Message, QElement, ViewId};
+
+/// Past workdays that are checked for missing bookings
+pub const CHECKED_WORKDAYS: usize = 20;
+
+#[derive(Clone, Debug)]
+pub enum ToFixMessage {
+    Loaded(Arc<Vec<DayToFix>>),
+    /// Ticks a day off the list until the days are checked again
+    Ticked(usize, bool),
+}
+
+/// Checklist of past workdays without bookings or with bookings that cannot be exported
+pub struct ToFixView {
+    days: Option<Arc<Vec<DayToFix>>>,
+    ticked: Vec<bool>,
+    open_buttons: Vec<button::State>,
+    scroll_state: scrollable::State,
+    focus: ButtonFocus,
+}
+
+impl ToFixView {
+    pub fn new() -> Box<Self> {
+        Box::new(Self {
+            days: None,
+            ticked: Vec::new(),
+            open_buttons: Vec::new(),
+            scroll_state: scrollable::State::new(),
+            focus: ButtonFocus::default(),
+        })
+    }
+
+    pub fn load_message(&self) -> Option<Message> {
+        Some(Message::LoadDaysToFix)
+    }
+}
+
+impl MainView for ToFixView {
+    fn view(&mut self) -> QElement {
+        self.focus.clear_targets();
+        let days = match &self.days {
+            Some(days) => days,
+            None => return text("Checking days..."),
+        };
+        if days.is_empty() {
+            return text(format!(
+                "All of the last {} workdays are booked",
+                CHECKED_WORKDAYS
+            ));
+        }
+
+        let mut scroll = Scrollable::new(&mut self.scroll_state).width(Length::Fill);
+        let rows = days.iter().zip(&self.ticked).zip(&mut self.open_buttons);
+        for (index, ((to_fix, ticked), open_button)) in rows.enumerate() {
+            let open = Message::ChangeView(ViewId::CurrentDayUi);
+            scroll = scroll.push(Row::with_children(vec![
+                Checkbox::new(
+                    *ticked,
+                    format!("{} {}", to_fix.day.day_of_week(), to_fix.day),
+                    move |b| Message::Fix(ToFixMessage::Ticked(index, b)),
+                )
+                .into(),
+                h_space(style::SPACE),
+                self.focus
+                    .inline_button(
+                        open_button,
+                        "Open",
+                        Message::OpenOnDay(to_fix.day, Box::new(open)),
+                    )
+                    .into(),
+                h_space(style::SPACE),
+                text(to_fix.reason.label()),
+            ]));
+        }
+
+        Column::with_children(vec![
+            text(format!(
+                "{} of the last {} workdays need fixing",
+                days.len(),
+                CHECKED_WORKDAYS
+            )),
+            v_space(style::SPACE),
+            scroll.into(),
+        ])
+        .into()
+    }
+
+    fn update(&mut self, msg: Message) -> Option<Message> {
+        match msg {
+            Message::Fix(ToFixMessage::Loaded(days)) => {
+                self.ticked = vec![false; days.len()];
+                self.open_buttons = days.iter().map(|_| button::State::new()).collect();
+                self.days = Some(days);
+                None
+            }
+            Message::Fix(ToFixMessage::Ticked(index, ticked)) => {
+                if let Some(t) = self.ticked.get_mut(index) {
+                    *t = ticked;
+                }
+                None
+            }
+            Message::Next => {
+                self.focus.cycle_next();
+                None
+            }
+            Message::Previous => {
+                self.focus.cycle_previous();
+                None
+            }
+            Message::SubmitCurrent(_) => self.focus.press(),
+            _ => None,
+        }
+    }
+}
@@ -1,8 +1,8 @@
 use crate::conf::SettingsRef;
 use crate::ui::util::h_space;
+use crate::ui::widget::Checkbox;
 use crate::ui::{style, text, Message, QElement};
 use iced_core::Length;
-use iced_wgpu::Checkbox;
 use iced_winit::widget::Row;
 
 #[derive(Debug)]
@@ -0,0 +1,308 @@
+//! Drives [Quarble] without a window, to test complete flows across views.
+//!
+//! Commands returned by `update` are not executed, flows that wait for the clipboard
+//! or for background loads need to send the resulting message themselves.
+//! Run with `cargo test --features ui-tests`
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use iced_winit::{Application, Program};
+use tempfile::TempDir;
+
+use crate::conf::{into_settings_ref, Settings};
+use crate::data::test_support::{day_end, day_start, issue_start, work};
+use crate::db::DB;
+use crate::ui::book_single::BookSingleMessage;
+use crate::ui::fast_day_end::FastDayEndMessage;
+use crate::ui::fast_day_start::FastDayStartMessage;
+use crate::ui::gap_fill::GapFillMessage;
+use crate::ui::issue_end_edit::IssueEndMessage;
+use crate::ui::issue_start_edit::IssueStartMessage;
+use crate::ui::main_action::MainAction;
+use crate::ui::message::Confirmation;
+use crate::ui::quick_switch::QuickSwitchMessage;
+use crate::ui::stay_active::StayActive;
+use crate::ui::work_event_edit::WorkEventMessage;
+use crate::ui::{MainView, Message, Quarble, ViewId};
+use crate::util::StaticTimeline;
+
+/// Serializable state of the UI after a sequence of messages
+#[derive(Debug, Eq, PartialEq, serde::Serialize)]
+pub struct UiSnapshot {
+    pub view: &'static str,
+    /// Input of the current view, `None` for views without text input
+    pub input: Option<String>,
+    pub error: Option<String>,
+    /// A view or day change waits for confirmation
+    pub pending_transition: bool,
+    pub day: Option<String>,
+    /// Actions of the active day as stored in the database
+    pub stored: Vec<String>,
+}
+
+pub struct UiHarness {
+    pub quarble: Quarble,
+    pub timeline: Arc<StaticTimeline>,
+    _db_dir: TempDir,
+}
+
+impl UiHarness {
+    /// Opens `view` on an empty database, `now` is like `2022-03-07 12:00`
+    pub fn start(view: ViewId, now: &str) -> UiHarness {
+        Self::with_settings(view, now, Settings::default())
+    }
+
+    pub fn with_settings(view: ViewId, now: &str, settings: Settings) -> UiHarness {
+        let db_dir = TempDir::new().unwrap();
+        let timeline = Arc::new(StaticTimeline::parse(now));
+        let settings = Settings {
+            timeline: timeline.clone(),
+            db_dir: db_dir.path().to_path_buf(),
+            migrate_settings: false,
+            ..settings
+        };
+        let settings = Settings {
+            active_date: settings.today(),
+            ..settings
+        };
+        let db = DB::init(&settings.db_dir).unwrap();
+        let work_day = db.get_day(settings.active_date).unwrap();
+        let (quarble, _) = Quarble::new(MainAction {
+            settings: into_settings_ref(settings),
+            initial_view: view,
+            db,
+            work_day: Rc::new(RefCell::new(work_day)),
+            instance: None,
+        });
+        UiHarness {
+            quarble,
+            timeline,
+            _db_dir: db_dir,
+        }
+    }
+
+    pub fn send(&mut self, message: Message) -> &mut Self {
+        let _ = self.quarble.update(message);
+        self
+    }
+
+    /// Replaces the input of the current view
+    pub fn input(&mut self, text: &str) -> &mut Self {
+        let text = text.to_string();
+        let message = match self.quarble.current_view.view_id() {
+            ViewId::BookSingle => Message::Bs(BookSingleMessage::TextChanged(text)),
+            ViewId::BookIssueStart => Message::Is(IssueStartMessage::TextChanged(text)),
+            ViewId::BookIssueEnd => Message::Ie(IssueEndMessage::InputChanged(text)),
+            ViewId::QuickSwitch => Message::Qs(QuickSwitchMessage::TextChanged(text)),
+            ViewId::GapFill => Message::Gf(GapFillMessage::TextChanged(text)),
+            ViewId::WorkEvent => Message::Ev(WorkEventMessage::TextChanged(text)),
+            ViewId::FastDayStart | ViewId::DayStartWizard => {
+                Message::Fds(FastDayStartMessage::TextChanged(text))
+            }
+            ViewId::FastDayEnd => Message::Fde(FastDayEndMessage::TextChanged(text)),
+            other => panic!("No text input in {:?}", other),
+        };
+        self.send(message)
+    }
+
+    /// Submits the input and keeps the window open
+    pub fn submit(&mut self) -> &mut Self {
+        self.send(Message::SubmitCurrent(StayActive::Yes))
+    }
+
+    pub fn snapshot(&self) -> UiSnapshot {
+        let q = &self.quarble;
+        let day = q.active_day.as_ref().map(|d| d.get_day());
+        let stored = day
+            .and_then(|day| q.db.load_day(day).unwrap())
+            .map(|d| d.actions().iter().map(ToString::to_string).collect())
+            .unwrap_or_default();
+        UiSnapshot {
+            view: q.current_view.view_id().name(),
+            input: q.current_view.draft().map(str::to_string),
+            error: q.current_error.as_ref().map(|e| e.to_string()),
+            pending_transition: q.pending_transition.is_some(),
+            day: day.map(|d| d.to_string()),
+            stored,
+        }
+    }
+
+    pub fn snapshot_json(&self) -> String {
+        serde_json::to_string_pretty(&self.snapshot()).unwrap()
+    }
+}
+
+#[test]
+fn books_a_day_across_views() {
+    let mut ui = UiHarness::start(ViewId::CurrentDayUi, "2022-03-07 12:00");
+    ui.send(Message::ChangeView(ViewId::FastDayStart))
+        .input("8")
+        .submit();
+    ui.send(Message::ChangeView(ViewId::BookSingle))
+        .input("8 10:30 QUA-1 review")
+        .submit();
+
+    // storing returns to the initial view
+    let snapshot = ui.snapshot();
+    assert_eq!(snapshot.view, "current_day");
+    assert_eq!(snapshot.error, None);
+    assert_eq!(snapshot.day.as_deref(), Some("2022-03-07"));
+    assert_eq!(
+        snapshot.stored,
+        vec!["08:00 -   | Office start", "08:00 - 10:30 | QUA-1: review"]
+    );
+}
+
+#[test]
+fn starts_new_days_with_the_wizard() {
+    let mut ui = UiHarness::start(ViewId::CurrentDayUi, "2022-03-07 12:00");
+    assert_eq!(ui.snapshot().view, "day_start_wizard");
+
+    ui.input("h8").submit();
+    let snapshot = ui.snapshot();
+    assert_eq!(snapshot.view, "current_day");
+    assert_eq!(snapshot.stored, vec!["08:00 -   | Home Office start"]);
+}
+
+#[test]
+fn asks_before_dropping_input() {
+    let mut ui = UiHarness::start(ViewId::BookSingle, "2022-03-07 12:00");
+    ui.input("9 10 QUA-1 draft")
+        .send(Message::ChangeView(ViewId::CurrentDayUi));
+    let snapshot = ui.snapshot();
+    assert_eq!(snapshot.view, "book");
+    assert!(snapshot.pending_transition);
+    assert_eq!(snapshot.input.as_deref(), Some("9 10 QUA-1 draft"));
+
+    ui.send(Message::ConfirmTransition(Confirmation::Discard));
+    let snapshot = ui.snapshot();
+    assert_eq!(snapshot.view, "current_day");
+    assert!(!snapshot.pending_transition);
+    assert!(snapshot.stored.is_empty());
+}
+
+#[test]
+fn follows_relative_input_on_tick() {
+    let mut ui = UiHarness::start(ViewId::FastDayEnd, "2022-03-07 17:00");
+    ui.input("-15");
+    ui.timeline.advance();
+    ui.send(Message::Tick).submit();
+
+    assert_eq!(ui.snapshot().stored, vec!["   - 16:46 | work end"]);
+}
+
+#[test]
+fn stores_recent_issues_in_the_background() {
+    let mut ui = UiHarness::start(ViewId::FastDayStart, "2022-03-07 12:00");
+    ui.input("8").submit();
+    ui.send(Message::ChangeView(ViewId::BookSingle))
+        .input("8 10:30 QUA-1 review")
+        .submit();
+    ui.quarble.autosave.flush();
+
+    let recent = ui.quarble.db.load_recent().unwrap();
+    let idents: Vec<_> = recent
+        .issues
+        .iter()
+        .map(|r| r.issue.ident.as_str())
+        .collect();
+    assert_eq!(idents, vec!["QUA-1"]);
+}
+
+#[test]
+fn switches_and_stops_running_work() {
+    let mut ui = UiHarness::start(ViewId::CurrentDayUi, "2022-03-07 12:00");
+    ui.send(Message::StoreAction(
+        StayActive::Yes,
+        issue_start("9", "QUA-1", "review", "dev"),
+    ))
+    .send(Message::StoreAction(
+        StayActive::Yes,
+        issue_start("10", "QUA-2", "meeting", "dev"),
+    ));
+    let running = |ui: &UiHarness| ui.quarble.running_work().map(|(r, _)| r.task.ident);
+    assert_eq!(running(&ui).as_deref(), Some("QUA-2"));
+
+    ui.send(Message::SwitchRunningWork(0));
+    assert_eq!(running(&ui).as_deref(), Some("QUA-1"));
+    assert_eq!(ui.snapshot().stored.len(), 4);
+
+    ui.send(Message::StopRunningWork);
+    assert_eq!(running(&ui), None);
+    assert_eq!(ui.snapshot().stored.len(), 5);
+}
+
+#[test]
+fn asks_before_booking_work_twice() {
+    let mut ui = UiHarness::start(ViewId::CurrentDayUi, "2022-03-07 12:00");
+    let store =
+        |start, end| Message::StoreAction(StayActive::Yes, work(start, end, "QUA-1", "review"));
+    ui.send(store("9", "11")).send(store("9:30", "10"));
+    assert!(ui.quarble.pending_store.is_some());
+    assert_eq!(ui.snapshot().stored.len(), 1);
+
+    ui.send(Message::Exit);
+    assert!(ui.quarble.pending_store.is_none());
+    assert_eq!(ui.snapshot().stored.len(), 1);
+
+    ui.send(store("9:30", "10")).submit();
+    assert_eq!(ui.snapshot().stored.len(), 2);
+}
+
+#[test]
+fn holds_only_stores_that_add_work_beyond_the_cap() {
+    let settings = Settings {
+        max_daily_minutes: Some(60),
+        ..Settings::default()
+    };
+    let mut ui = UiHarness::with_settings(ViewId::CurrentDayUi, "2022-03-07 12:00", settings);
+    let store = |action| Message::StoreAction(StayActive::Yes, action);
+    ui.send(store(day_start("8")))
+        .send(store(work("9", "11", "QUA-1", "review")));
+    assert!(ui.quarble.pending_store.is_some());
+    ui.submit();
+    assert_eq!(ui.snapshot().stored.len(), 2);
+
+    // ending the day adds no work
+    ui.send(store(day_end("17")));
+    assert!(ui.quarble.pending_store.is_none());
+    assert_eq!(ui.snapshot().stored.len(), 3);
+}
+
+#[test]
+fn ends_the_running_issue_on_a_new_start() {
+    let settings = Settings {
+        auto_close_work: true,
+        ..Settings::default()
+    };
+    let mut ui = UiHarness::with_settings(ViewId::CurrentDayUi, "2022-03-07 12:00", settings);
+    let start =
+        |ts, issue| Message::StoreAction(StayActive::Yes, issue_start(ts, issue, "", "dev"));
+    ui.send(start("9", "QUA-1")).send(start("10", "QUA-2"));
+    let stored = ui.snapshot().stored;
+    assert_eq!(stored.len(), 3);
+    assert!(stored.contains(&"   - 10:00 | QUA-1".to_string()));
+}
+
+#[test]
+fn recalls_stored_inputs() {
+    let mut ui = UiHarness::start(ViewId::CurrentDayUi, "2022-03-07 12:00");
+    ui.input("8").submit();
+    ui.send(Message::ChangeView(ViewId::BookSingle))
+        .input("9 10 QUA-1 review")
+        .submit();
+    ui.send(Message::ChangeView(ViewId::BookSingle))
+        .input("10 11 QUA-2 daily")
+        .submit();
+    assert_eq!(ui.snapshot().stored.len(), 3);
+
+    ui.send(Message::ChangeView(ViewId::BookSingle)).input("11");
+    ui.send(Message::Up).send(Message::Up);
+    assert_eq!(ui.snapshot().input.as_deref(), Some("9 10 QUA-1 review"));
+    ui.send(Message::Up).send(Message::Down);
+    assert_eq!(ui.snapshot().input.as_deref(), Some("10 11 QUA-2 daily"));
+    ui.send(Message::Down);
+    assert_eq!(ui.snapshot().input.as_deref(), Some("11"));
+}
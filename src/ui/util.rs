@@ -1,7 +1,16 @@
 use iced_core::Length;
+use iced_futures::futures::channel::oneshot;
+use iced_winit::widget::{scrollable, Container, Scrollable};
+use std::future::Future;
+use std::num::NonZeroU32;
+use std::ops::Range;
 use std::str::FromStr;
 
+use crate::parsing::locale::TimeFormat;
+use crate::parsing::parse_result::ParseResult;
+use crate::parsing::time::Time;
 use crate::ui::{Message, QElement};
+use crate::Settings;
 
 pub(super) fn valid_start_time(id: usize, min_val: u32, input: String) -> Message {
     match valid_base_time(&input) {
@@ -65,6 +74,20 @@ pub(super) fn valid_base_time(input: &str) -> (bool, Option<u32>) {
     (false, None)
 }
 
+/// Runs the blocking `f` on its own thread, neither the UI nor the executor of the commands
+/// wait for it
+pub(in crate::ui) fn background<T, F>(f: F) -> impl Future<Output = T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    let (sender, receiver) = oneshot::channel();
+    std::thread::spawn(move || {
+        let _ = sender.send(f());
+    });
+    async move { receiver.await.expect("background job panicked") }
+}
+
 pub(in crate::ui) fn v_space<'a>(l: Length) -> QElement<'a> {
     iced_winit::widget::Space::with_height(l).into()
 }
@@ -72,3 +95,159 @@ pub(in crate::ui) fn v_space<'a>(l: Length) -> QElement<'a> {
 pub(in crate::ui) fn h_space<'a>(l: Length) -> QElement<'a> {
     iced_winit::widget::Space::with_width(l).into()
 }
+
+/// Height of the rows of a [VirtualList] that is longer than [VIRTUAL_THRESHOLD]
+const ROW_HEIGHT: u16 = 30;
+
+/// Shorter lists are rendered completely, with rows of their natural height
+const VIRTUAL_THRESHOLD: usize = 200;
+
+/// Rows of the highest expected viewport
+const VISIBLE_ROWS: usize = 60;
+
+/// Rows rendered above and below the viewport
+const OVERSCAN: usize = 20;
+
+/// A scrollable list that renders only the rows around the viewport.
+///
+/// The rows outside are replaced by space of the same height, so the scrollbar
+/// still covers the whole list. Build only the rows of [VirtualList::window]
+#[derive(Clone, Debug, Default)]
+pub(in crate::ui) struct VirtualList {
+    scroll: scrollable::State,
+    /// Relative scroll position, 0 at the top and 1 at the bottom
+    offset: f32,
+}
+
+impl VirtualList {
+    pub fn scrolled(&mut self, offset: f32) {
+        self.offset = offset.clamp(0.0, 1.0);
+    }
+
+    /// The rows to build of a list with `len` rows
+    pub fn window(&self, len: usize) -> Range<usize> {
+        visible_window(len, self.offset)
+    }
+
+    /// `rows` are the rows of [VirtualList::window]
+    pub fn view<'a>(
+        &'a mut self,
+        len: usize,
+        rows: Vec<QElement<'a>>,
+        on_scroll: fn(f32) -> Message,
+    ) -> QElement<'a> {
+        let window = self.window(len);
+        let mut scroll = Scrollable::new(&mut self.scroll)
+            .width(Length::Fill)
+            .on_scroll(on_scroll);
+        if window.len() == len {
+            for row in rows {
+                scroll = scroll.push(row);
+            }
+            return scroll.into();
+        }
+
+        for space in spaces(window.start) {
+            scroll = scroll.push(space);
+        }
+        for row in rows {
+            scroll = scroll.push(Container::new(row).height(Length::Units(ROW_HEIGHT)));
+        }
+        for space in spaces(len - window.end) {
+            scroll = scroll.push(space);
+        }
+        scroll.into()
+    }
+}
+
+fn visible_window(len: usize, offset: f32) -> Range<usize> {
+    if len <= VIRTUAL_THRESHOLD {
+        return 0..len;
+    }
+    // the first visible row depends on the unknown viewport height, cover all up to VISIBLE_ROWS
+    let first = ((len - VISIBLE_ROWS) as f32 * offset) as usize;
+    let last = (len as f32 * offset) as usize + VISIBLE_ROWS;
+    first.saturating_sub(OVERSCAN)..(last + OVERSCAN).min(len)
+}
+
+/// Space of `rows` rows, split as the height of a single space is limited to u16
+fn spaces<'a>(rows: usize) -> impl Iterator<Item = QElement<'a>> {
+    let per_space = (u16::MAX / ROW_HEIGHT) as usize;
+    (0..rows).step_by(per_space).map(move |start| {
+        v_space(Length::Units(
+            (per_space.min(rows - start)) as u16 * ROW_HEIGHT,
+        ))
+    })
+}
+
+/// Moves the last of the leading times of a booking input by `steps` of the booking
+/// resolution, `None` if the input has no time.
+///
+/// Words before the times like a location are kept, a time in the comment is never changed
+pub(in crate::ui) fn step_input_time(
+    settings: &Settings,
+    input: &str,
+    steps: i32,
+) -> Option<String> {
+    let resolution = NonZeroU32::new(settings.resolution.num_minutes() as u32)?;
+    let mut last = None;
+    let mut offset = 0;
+    for token in input.split(' ') {
+        match Time::parse_prefix(token) {
+            // relative times like +15 are not moved
+            (ParseResult::Valid(time), "") if token.starts_with(|c: char| c.is_ascii_digit()) => {
+                last = Some((offset, token.len(), time))
+            }
+            _ if last.is_some() && !token.is_empty() => break,
+            _ => (),
+        }
+        offset += token.len() + 1;
+    }
+    let (at, len, time) = last?;
+    let stepped = time.step(steps, resolution).format(TimeFormat::H24);
+    Some(format!("{}{}{}", &input[..at], stepped, &input[at + len..]))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ui::util::{step_input_time, visible_window, VIRTUAL_THRESHOLD, VISIBLE_ROWS};
+    use crate::Settings;
+
+    #[test]
+    fn steps_the_last_leading_time() {
+        let settings = Settings {
+            resolution: chrono::Duration::minutes(15),
+            ..Settings::default()
+        };
+        let step = |input: &str, steps| step_input_time(&settings, input, steps);
+        assert_eq!(
+            step("9 10 B-1 at 14", 1).as_deref(),
+            Some("9 10:15 B-1 at 14")
+        );
+        assert_eq!(step("9:10 B-1", -1).as_deref(), Some("09:00 B-1"));
+        assert_eq!(step("o 8", 2).as_deref(), Some("o 08:30"));
+        assert_eq!(step("B-1 review", 1), None);
+        assert_eq!(step("+15", 1), None);
+    }
+
+    #[test]
+    fn short_lists_are_complete() {
+        assert_eq!(visible_window(0, 0.0), 0..0);
+        assert_eq!(visible_window(VIRTUAL_THRESHOLD, 0.7), 0..VIRTUAL_THRESHOLD);
+    }
+
+    #[test]
+    fn window_covers_the_viewport() {
+        let len = 1000;
+        for viewport in [1, 10, VISIBLE_ROWS] {
+            for step in 0..=100 {
+                let offset = step as f32 / 100.0;
+                let first = ((len - viewport) as f32 * offset) as usize;
+                let window = visible_window(len, offset);
+                assert!(window.start <= first, "{} {:?}", offset, window);
+                assert!(window.end >= first + viewport, "{} {:?}", offset, window);
+                assert!(window.len() < 200, "{} {:?}", offset, window);
+            }
+        }
+    }
+}
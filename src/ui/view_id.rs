@@ -4,9 +4,21 @@ pub enum ViewId {
     BookSingle,
     BookIssueStart,
     BookIssueEnd,
+    QuickSwitch,
+    WorkEvent,
     FastDayStart,
+    DayStartWizard,
     FastDayEnd,
+    DayEndWizard,
+    GapFill,
+    Standby,
+    QuickDay,
+    Reassign,
     Export,
+    IssueStats,
+    WeekCalendar,
+    YearOverview,
+    ToFix,
     Settings,
     Exit,
 }
@@ -15,18 +27,117 @@ impl ViewId {
     pub const TAB_ORDER: &'static [ViewId] = &[
         Self::CurrentDayUi,
         Self::FastDayStart,
+        Self::DayStartWizard,
         Self::FastDayEnd,
+        Self::DayEndWizard,
+        Self::GapFill,
         Self::BookSingle,
         Self::BookIssueStart,
         Self::BookIssueEnd,
+        Self::QuickSwitch,
+        Self::WorkEvent,
+        Self::Standby,
+        Self::QuickDay,
+        Self::Reassign,
         Self::Export,
+        Self::IssueStats,
+        Self::WeekCalendar,
+        Self::YearOverview,
+        Self::ToFix,
         Self::Settings,
     ];
 
+    /// Stable name, used to persist drafts
+    pub fn name(self) -> &'static str {
+        match self {
+            ViewId::CurrentDayUi => "current_day",
+            ViewId::BookSingle => "book",
+            ViewId::BookIssueStart => "issue_start",
+            ViewId::BookIssueEnd => "issue_end",
+            ViewId::QuickSwitch => "switch",
+            ViewId::WorkEvent => "event",
+            ViewId::FastDayStart => "day_start",
+            ViewId::DayStartWizard => "day_start_wizard",
+            ViewId::FastDayEnd => "day_end",
+            ViewId::DayEndWizard => "day_end_wizard",
+            ViewId::GapFill => "gap_fill",
+            ViewId::Standby => "standby",
+            ViewId::QuickDay => "quick_day",
+            ViewId::Reassign => "reassign",
+            ViewId::Export => "export",
+            ViewId::IssueStats => "stats",
+            ViewId::WeekCalendar => "week",
+            ViewId::YearOverview => "year",
+            ViewId::ToFix => "to_fix",
+            ViewId::Settings => "settings",
+            ViewId::Exit => "exit",
+        }
+    }
+
+    /// Name shown to the user
+    pub fn title(self) -> &'static str {
+        match self {
+            ViewId::CurrentDayUi => "Current day",
+            ViewId::BookSingle => "Book issue",
+            ViewId::BookIssueStart => "Start issue",
+            ViewId::BookIssueEnd => "End issue",
+            ViewId::QuickSwitch => "Switch issue",
+            ViewId::WorkEvent => "Event",
+            ViewId::FastDayStart => "Day start",
+            ViewId::DayStartWizard => "Start of day",
+            ViewId::FastDayEnd => "Day end",
+            ViewId::DayEndWizard => "End of day",
+            ViewId::GapFill => "Fill gaps",
+            ViewId::Standby => "Standby",
+            ViewId::QuickDay => "Quick day",
+            ViewId::Reassign => "Reassign",
+            ViewId::Export => "Export",
+            ViewId::IssueStats => "Statistics",
+            ViewId::WeekCalendar => "Week",
+            ViewId::YearOverview => "Year",
+            ViewId::ToFix => "To fix",
+            ViewId::Settings => "Settings",
+            ViewId::Exit => "Exit",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<ViewId> {
+        Self::TAB_ORDER.iter().copied().find(|v| v.name() == name)
+    }
+
+    /// The view to open instead on a day without entries
+    pub fn for_new_day(self) -> ViewId {
+        match self {
+            ViewId::CurrentDayUi | ViewId::FastDayStart => ViewId::DayStartWizard,
+            other => other,
+        }
+    }
+
+    /// Views that keep their stored inputs for recalling them with ctrl+Up and ctrl+Down
+    pub fn has_input_history(self) -> bool {
+        matches!(
+            self,
+            ViewId::BookSingle | ViewId::BookIssueStart | ViewId::FastDayStart
+        )
+    }
+
+    /// Views without another use of Up and Down, they recall inputs without ctrl
+    pub fn recalls_with_arrows(self) -> bool {
+        matches!(self, ViewId::BookSingle | ViewId::BookIssueStart)
+    }
+
     pub fn show_recent(self) -> bool {
         matches!(
             self,
-            ViewId::BookSingle | ViewId::BookIssueStart | ViewId::BookIssueEnd
+            ViewId::BookSingle
+                | ViewId::BookIssueStart
+                | ViewId::BookIssueEnd
+                | ViewId::QuickSwitch
+                | ViewId::DayEndWizard
+                | ViewId::GapFill
+                | ViewId::WorkEvent
+                | ViewId::QuickDay
+                | ViewId::IssueStats
         )
     }
 }
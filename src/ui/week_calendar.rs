@@ -0,0 +1,379 @@
+use std::sync::Arc;
+
+use iced_core::{Color, Length, Point, Rectangle, Size};
+use iced_native::mouse;
+use iced_wgpu::canvas::{self, event, Canvas, Cursor, Frame, Geometry, Path, Stroke, Text};
+
+use crate::conf::SettingsRef;
+use crate::data::{Action, ActiveDay, CalendarBlock, Day, WeekCalendar};
+use crate::parsing::time::Time;
+use crate::ui::message::EditAction;
+use crate::ui::{style, text, MainView, Message, QElement};
+use crate::Settings;
+
+/// Width of the column with the hour labels
+const HOURS_WIDTH: f32 = 45.0;
+/// Height of the row with the day labels
+const HEADER_HEIGHT: f32 = style::FONT_SIZE as f32 + 4.0;
+const LABEL_SIZE: f32 = 12.0;
+/// Blocks lower than this show no label
+const MIN_LABEL_HEIGHT: f32 = 14.0;
+
+#[derive(Clone, Debug)]
+pub enum WeekCalendarMessage {
+    Loaded(Arc<WeekCalendar>),
+}
+
+/// Week grid with a column per day and a block per booked entry.
+///
+/// Clicking an entry edits it, clicking a gap books the gap
+pub struct WeekCalendarView {
+    settings: SettingsRef,
+    active_day: Option<Day>,
+    calendar: Option<Arc<WeekCalendar>>,
+}
+
+impl WeekCalendarView {
+    pub fn for_active_day(settings: SettingsRef, active_day: Option<&ActiveDay>) -> Box<Self> {
+        Box::new(Self {
+            settings,
+            active_day: active_day.map(ActiveDay::get_day),
+            calendar: None,
+        })
+    }
+
+    pub fn load_message(&self) -> Option<Message> {
+        self.active_day.map(Message::LoadWeekCalendar)
+    }
+}
+
+/// Positions of the hours and days of a calendar, scaled to the size of the canvas
+struct Grid {
+    start: Time,
+    end: Time,
+    days: usize,
+    size: Size,
+}
+
+impl Grid {
+    fn day_width(&self) -> f32 {
+        (self.size.width - HOURS_WIDTH) / self.days.max(1) as f32
+    }
+
+    fn x(&self, day: usize) -> f32 {
+        HOURS_WIDTH + day as f32 * self.day_width()
+    }
+
+    fn y(&self, t: Time) -> f32 {
+        let minutes = self.start.minutes_until(self.end).max(1) as f32;
+        let minute_height = (self.size.height - HEADER_HEIGHT) / minutes;
+        HEADER_HEIGHT + self.start.minutes_until(t) as f32 * minute_height
+    }
+}
+
+enum Cell<'a> {
+    Block(&'a CalendarBlock),
+    Gap(Time, Time),
+}
+
+struct PlacedCell<'a> {
+    day: Day,
+    bounds: Rectangle,
+    cell: Cell<'a>,
+}
+
+impl PlacedCell<'_> {
+    fn message(&self) -> Message {
+        let action = match self.cell {
+            Cell::Block(block) => Message::EditAction(EditAction(Box::new(block.action.clone()))),
+            Cell::Gap(start, end) => Message::BookRange(start, end),
+        };
+        Message::OpenOnDay(self.day, Box::new(action))
+    }
+}
+
+/// Gaps and blocks of all days, later cells are drawn on top of earlier ones
+fn cells<'a>(calendar: &'a WeekCalendar, grid: &Grid) -> Vec<PlacedCell<'a>> {
+    let mut result = Vec::new();
+    for (index, day) in calendar.days.iter().enumerate() {
+        let x = grid.x(index);
+        let rectangle = |x: f32, width: f32, start: Time, end: Time| Rectangle {
+            x,
+            y: grid.y(start),
+            width,
+            height: grid.y(end) - grid.y(start),
+        };
+        // standby may reach into gaps, so gaps span the whole day
+        for (start, end) in &day.gaps {
+            result.push(PlacedCell {
+                day: day.date,
+                bounds: rectangle(x, grid.day_width(), *start, *end),
+                cell: Cell::Gap(*start, *end),
+            });
+        }
+        let lane_width = grid.day_width() / day.lanes as f32;
+        for block in &day.blocks {
+            let lane_x = x + block.lane as f32 * lane_width;
+            result.push(PlacedCell {
+                day: day.date,
+                bounds: rectangle(lane_x, lane_width, block.start, block.end),
+                cell: Cell::Block(block),
+            });
+        }
+    }
+    result
+}
+
+/// Topmost cell at `position`
+fn cell_at<'a>(cells: &'a [PlacedCell<'a>], position: Point) -> Option<&'a PlacedCell<'a>> {
+    cells.iter().rev().find(|c| c.bounds.contains(position))
+}
+
+struct CalendarGrid {
+    settings: Arc<Settings>,
+    calendar: Arc<WeekCalendar>,
+    start: Time,
+    end: Time,
+}
+
+impl CalendarGrid {
+    fn grid(&self, size: Size) -> Grid {
+        Grid {
+            start: self.start,
+            end: self.end,
+            days: self.calendar.days.len(),
+            size,
+        }
+    }
+
+    fn draw_cell(&self, frame: &mut Frame, placed: &PlacedCell) {
+        let (color, overlaps, label) = match placed.cell {
+            Cell::Block(block) => match block.action.issue() {
+                Some(issue) => (
+                    style::issue_color(&self.settings, &issue.ident),
+                    block.overlaps,
+                    issue.ident.as_str(),
+                ),
+                None => (
+                    style::NO_ISSUE_COLOR,
+                    block.overlaps,
+                    kind_label(&block.action),
+                ),
+            },
+            Cell::Gap(_, _) => (style::GAP_COLOR, false, ""),
+        };
+        let Rectangle {
+            x,
+            y,
+            width,
+            height,
+        } = placed.bounds;
+        frame.fill_rectangle(Point::new(x, y), Size::new(width, height), color);
+        let (border, border_width) = if overlaps {
+            (style::ERROR_COLOR_FOCUSSED, 2.0)
+        } else {
+            (Color::WHITE, 1.0)
+        };
+        frame.stroke(
+            &Path::rectangle(Point::new(x, y), Size::new(width, height)),
+            Stroke {
+                color: border,
+                width: border_width,
+                ..Stroke::default()
+            },
+        );
+        if height >= MIN_LABEL_HEIGHT && !label.is_empty() {
+            frame.fill_text(Text {
+                content: label.to_string(),
+                position: Point::new(x + 2.0, y + 1.0),
+                size: LABEL_SIZE,
+                ..Text::default()
+            });
+        }
+    }
+}
+
+impl canvas::Program<Message> for CalendarGrid {
+    fn update(
+        &mut self,
+        event: canvas::Event,
+        bounds: Rectangle,
+        cursor: Cursor,
+    ) -> (event::Status, Option<Message>) {
+        let position = match cursor.position_in(&bounds) {
+            Some(position) => position,
+            None => return (event::Status::Ignored, None),
+        };
+        match event {
+            canvas::Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+                let cells = cells(&self.calendar, &self.grid(bounds.size()));
+                match cell_at(&cells, position) {
+                    Some(cell) => (event::Status::Captured, Some(cell.message())),
+                    None => (event::Status::Ignored, None),
+                }
+            }
+            _ => (event::Status::Ignored, None),
+        }
+    }
+
+    fn draw(&self, bounds: Rectangle, _cursor: Cursor) -> Vec<Geometry> {
+        let grid = self.grid(bounds.size());
+        let mut frame = Frame::new(bounds.size());
+
+        for h in self.start.h()..self.end.h() {
+            let y = grid.y(Time::hm(h, 0));
+            frame.stroke(
+                &Path::line(Point::new(0.0, y), Point::new(bounds.width, y)),
+                Stroke {
+                    color: style::HIGHLIGHT_COLOR,
+                    width: 1.0,
+                    ..Stroke::default()
+                },
+            );
+            frame.fill_text(Text {
+                content: format!("{:02}:00", h),
+                position: Point::new(2.0, y + 1.0),
+                size: LABEL_SIZE,
+                ..Text::default()
+            });
+        }
+        for (index, day) in self.calendar.days.iter().enumerate() {
+            frame.fill_text(Text {
+                content: format!("{} {}", day.date.day_of_week(), day.date),
+                position: Point::new(grid.x(index) + 2.0, 2.0),
+                size: LABEL_SIZE,
+                ..Text::default()
+            });
+        }
+        for placed in cells(&self.calendar, &grid) {
+            self.draw_cell(&mut frame, &placed);
+        }
+
+        vec![frame.into_geometry()]
+    }
+
+    fn mouse_interaction(&self, bounds: Rectangle, cursor: Cursor) -> mouse::Interaction {
+        let position = match cursor.position_in(&bounds) {
+            Some(position) => position,
+            None => return mouse::Interaction::default(),
+        };
+        let cells = cells(&self.calendar, &self.grid(bounds.size()));
+        if cell_at(&cells, position).is_some() {
+            mouse::Interaction::Pointer
+        } else {
+            mouse::Interaction::default()
+        }
+    }
+}
+
+fn kind_label(action: &Action) -> &'static str {
+    match action {
+        Action::ZA(_) => "ZA",
+        Action::Absence(_) => "Absence",
+        Action::Standby(_) => "Standby",
+        _ => "",
+    }
+}
+
+impl MainView for WeekCalendarView {
+    fn view(&mut self) -> QElement {
+        let calendar = match &self.calendar {
+            Some(calendar) => calendar,
+            None => return text("Loading week..."),
+        };
+        let (start, end) = match calendar.time_range() {
+            Some(range) => range,
+            None => return text(format!("Nothing booked in week of {}", calendar.first_day)),
+        };
+
+        Canvas::new(CalendarGrid {
+            settings: self.settings.load_full(),
+            calendar: calendar.clone(),
+            start,
+            end,
+        })
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .into()
+    }
+
+    fn update(&mut self, msg: Message) -> Option<Message> {
+        match msg {
+            Message::Cal(WeekCalendarMessage::Loaded(calendar)) => {
+                self.calendar = Some(calendar);
+                None
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use iced_core::{Point, Size};
+
+    use crate::data::test_support::{time, work};
+    use crate::data::{ActiveDay, Day, Location, WeekCalendar};
+    use crate::ui::message::EditAction;
+    use crate::ui::week_calendar::{cell_at, cells, Grid, HEADER_HEIGHT, HOURS_WIDTH};
+    use crate::ui::Message;
+
+    fn calendar() -> WeekCalendar {
+        let mut day = ActiveDay::new(Day::ymd(2022, 1, 3), Location::Office, None);
+        day.add_action(work("8", "10", "A-1", "a"));
+        day.add_action(work("9", "10", "A-2", "b"));
+        day.add_action(work("11", "12", "A-1", "c"));
+        WeekCalendar::collect(Day::ymd(2022, 1, 3), [&day])
+    }
+
+    /// One pixel per minute and 100 pixels per day
+    fn grid(calendar: &WeekCalendar) -> Grid {
+        let (start, end) = calendar.time_range().unwrap();
+        Grid {
+            start,
+            end,
+            days: 7,
+            size: Size::new(HOURS_WIDTH + 700.0, HEADER_HEIGHT + 240.0),
+        }
+    }
+
+    fn click(calendar: &WeekCalendar, x: f32, minute: f32) -> Option<Message> {
+        let cells = cells(calendar, &grid(calendar));
+        let position = Point::new(HOURS_WIDTH + x, HEADER_HEIGHT + minute);
+        cell_at(&cells, position).map(|c| c.message())
+    }
+
+    #[test]
+    fn clicking_a_block_edits_the_entry() {
+        let calendar = calendar();
+        // the overlapping entry is in the right lane
+        match click(&calendar, 75.0, 90.0) {
+            Some(Message::OpenOnDay(day, edit)) => {
+                assert_eq!(day, Day::ymd(2022, 1, 3));
+                match *edit {
+                    Message::EditAction(EditAction(action)) => {
+                        assert_eq!(*action, work("9", "10", "A-2", "b"))
+                    }
+                    m => panic!("unexpected {:?}", m),
+                }
+            }
+            m => panic!("unexpected {:?}", m),
+        }
+    }
+
+    #[test]
+    fn clicking_a_gap_books_the_range() {
+        let calendar = calendar();
+        match click(&calendar, 90.0, 150.0) {
+            Some(Message::OpenOnDay(day, book)) => {
+                assert_eq!(day, Day::ymd(2022, 1, 3));
+                assert!(
+                    matches!(*book, Message::BookRange(s, e) if s == time("10") && e == time("11"))
+                );
+            }
+            m => panic!("unexpected {:?}", m),
+        }
+        // nothing booked on tuesday
+        assert!(click(&calendar, 150.0, 30.0).is_none());
+    }
+}
@@ -0,0 +1,135 @@
+use iced_native::event;
+use iced_native::layout;
+use iced_native::mouse::{self, ScrollDelta};
+use iced_native::overlay;
+use iced_native::renderer;
+use iced_native::{Clipboard, Element, Event, Layout, Length, Point, Rectangle, Shell, Widget};
+
+use crate::ui::{Message, QElement, QRenderer};
+
+/// Turns the mouse wheel over `content` into a message, other events go to `content`.
+///
+/// The wheel is captured only while the cursor is over the content, so scrollables
+/// around it keep scrolling everywhere else
+pub struct WheelSteps<'a> {
+    content: QElement<'a>,
+    on_steps: Box<dyn Fn(i32) -> Message + 'a>,
+}
+
+impl<'a> WheelSteps<'a> {
+    /// `on_steps` gets the wheel notches, positive when scrolling up
+    pub fn new(content: impl Into<QElement<'a>>, on_steps: impl Fn(i32) -> Message + 'a) -> Self {
+        WheelSteps {
+            content: content.into(),
+            on_steps: Box::new(on_steps),
+        }
+    }
+}
+
+/// Wheel notches, positive when scrolling up
+fn scroll_steps(delta: ScrollDelta) -> Option<i32> {
+    let steps = match delta {
+        ScrollDelta::Lines { y, .. } => y.round() as i32,
+        ScrollDelta::Pixels { y, .. } if y > 0.0 => 1,
+        ScrollDelta::Pixels { y, .. } if y < 0.0 => -1,
+        ScrollDelta::Pixels { .. } => 0,
+    };
+    if steps == 0 {
+        None
+    } else {
+        Some(steps)
+    }
+}
+
+impl<'a> Widget<Message, QRenderer> for WheelSteps<'a> {
+    fn width(&self) -> Length {
+        self.content.width()
+    }
+
+    fn height(&self) -> Length {
+        self.content.height()
+    }
+
+    fn layout(&self, renderer: &QRenderer, limits: &layout::Limits) -> layout::Node {
+        self.content.layout(renderer, limits)
+    }
+
+    fn draw(
+        &self,
+        renderer: &mut QRenderer,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        viewport: &Rectangle,
+    ) {
+        self.content
+            .draw(renderer, style, layout, cursor_position, viewport)
+    }
+
+    fn on_event(
+        &mut self,
+        event: Event,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        renderer: &QRenderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+    ) -> event::Status {
+        if let Event::Mouse(mouse::Event::WheelScrolled { delta }) = event {
+            if layout.bounds().contains(cursor_position) {
+                if let Some(steps) = scroll_steps(delta) {
+                    shell.publish((self.on_steps)(steps));
+                }
+                return event::Status::Captured;
+            }
+        }
+        self.content
+            .on_event(event, layout, cursor_position, renderer, clipboard, shell)
+    }
+
+    fn mouse_interaction(
+        &self,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        viewport: &Rectangle,
+        renderer: &QRenderer,
+    ) -> mouse::Interaction {
+        self.content
+            .mouse_interaction(layout, cursor_position, viewport, renderer)
+    }
+
+    fn overlay(
+        &mut self,
+        layout: Layout<'_>,
+        renderer: &QRenderer,
+    ) -> Option<overlay::Element<'_, Message, QRenderer>> {
+        self.content.overlay(layout, renderer)
+    }
+}
+
+impl<'a> From<WheelSteps<'a>> for QElement<'a> {
+    fn from(wheel_steps: WheelSteps<'a>) -> QElement<'a> {
+        Element::new(wheel_steps)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use iced_native::mouse::ScrollDelta;
+
+    use crate::ui::wheel_steps::scroll_steps;
+
+    #[test]
+    fn counts_wheel_notches() {
+        assert_eq!(scroll_steps(ScrollDelta::Lines { x: 0.0, y: 2.0 }), Some(2));
+        assert_eq!(
+            scroll_steps(ScrollDelta::Lines { x: 0.0, y: -1.0 }),
+            Some(-1)
+        );
+        assert_eq!(
+            scroll_steps(ScrollDelta::Pixels { x: 0.0, y: 7.5 }),
+            Some(1)
+        );
+        assert_eq!(scroll_steps(ScrollDelta::Lines { x: 3.0, y: 0.0 }), None);
+    }
+}
@@ -0,0 +1,7 @@
+//! Widgets of the application renderer, views do not name the renderer directly
+
+use crate::ui::QRenderer;
+
+pub type Text = iced_native::widget::Text<QRenderer>;
+pub type TextInput<'a, Message> = iced_native::widget::TextInput<'a, Message, QRenderer>;
+pub type Checkbox<'a, Message> = iced_native::widget::Checkbox<'a, Message, QRenderer>;
@@ -0,0 +1,78 @@
+use iced_core::Length;
+use iced_native::widget::button;
+use iced_winit::widget::{Container, Row};
+
+use crate::data::{format_minutes, Action, JiraIssue, WorkEnd, WorkStart};
+use crate::parsing::time::Time;
+use crate::ui::util::h_space;
+use crate::ui::{style, text, Message, QElement};
+
+/// Recent issues offered to switch to, with ctrl+shift+1 to ctrl+shift+3
+pub const SWITCH_ISSUES: usize = 3;
+
+/// Ends the running work when `start` begins, stored together so that no gap or overlap remains
+pub fn switch_actions(running: Option<WorkStart>, start: WorkStart) -> Vec<Action> {
+    let mut actions = Vec::new();
+    if let Some(running) = running {
+        actions.push(Action::WorkEnd(WorkEnd {
+            ts: start.ts,
+            task: running.task,
+        }));
+    }
+    actions.push(Action::WorkStart(start));
+    actions
+}
+
+/// The running work, shown above all views
+#[derive(Debug, Default)]
+pub struct WorkBanner {
+    stop_button: button::State,
+    switch_buttons: [button::State; SWITCH_ISSUES],
+}
+
+impl WorkBanner {
+    /// `switch_to` are the issues of [Message::SwitchRunningWork]
+    pub fn view<'a>(
+        &'a mut self,
+        running: &WorkStart,
+        now: Time,
+        switch_to: &[JiraIssue],
+    ) -> QElement<'a> {
+        let elapsed = running.ts.minutes_until(now);
+        let mut row = vec![
+            text(format!(
+                "Working on {} {} since {} ({})",
+                running.task.ident,
+                running.description,
+                running.ts,
+                format_minutes(elapsed)
+            )),
+            h_space(Length::Fill),
+            style::inline_button(&mut self.stop_button, "Stop (ctrl+P)")
+                .on_press(Message::StopRunningWork)
+                .into(),
+        ];
+        if !switch_to.is_empty() {
+            row.push(h_space(style::DSPACE));
+            row.push(text("Switch (ctrl+shift+1..):"));
+        }
+        for (index, (issue, state)) in switch_to
+            .iter()
+            .zip(self.switch_buttons.iter_mut())
+            .enumerate()
+        {
+            row.push(h_space(style::SPACE));
+            row.push(
+                style::inline_button(state, &issue.ident)
+                    .on_press(Message::SwitchRunningWork(index))
+                    .into(),
+            );
+        }
+
+        Container::new(Row::with_children(row))
+            .style(style::ContentStyle)
+            .width(Length::Fill)
+            .padding(style::WINDOW_PADDING)
+            .into()
+    }
+}
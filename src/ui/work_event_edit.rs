@@ -0,0 +1,179 @@
+use iced_native::widget::{text_input, Column};
+
+use crate::conf::SettingsRef;
+use crate::data::{ActiveDay, RecentIssuesRef, WorkEvent};
+use crate::parsing::time::Time;
+use crate::ui::issue_start_edit::IssueStartBuilder;
+use crate::ui::single_edit_ui::SingleEditUi;
+use crate::ui::stay_active::StayActive;
+use crate::ui::top_bar::TopBar;
+use crate::ui::util::{step_input_time, v_space};
+use crate::ui::wheel_steps::WheelSteps;
+use crate::ui::widget::TextInput;
+use crate::ui::{
+    cycle_shortcut, day_info_message, shortcut_preview, style, text, MainView, Message, QElement,
+};
+
+#[derive(Clone, Debug)]
+pub enum WorkEventMessage {
+    TextChanged(String),
+}
+
+/// Books a momentary event on an issue, without a duration
+#[derive(Debug)]
+pub struct WorkEventEdit {
+    top_bar: TopBar,
+    input_state: text_input::State,
+    input: String,
+    builder: IssueStartBuilder,
+    settings: SettingsRef,
+    orig: Option<WorkEvent>,
+    last_end: Option<Time>,
+    recent_issues: RecentIssuesRef,
+    recent_filter: String,
+}
+
+impl WorkEventEdit {
+    pub fn for_active_day(
+        settings: SettingsRef,
+        recent_issues: RecentIssuesRef,
+        active_day: Option<&ActiveDay>,
+    ) -> Box<WorkEventEdit> {
+        let now = settings.load().timeline.time_now();
+        let last_end = active_day.and_then(|d| d.last_action_end(now));
+        Box::new(Self {
+            top_bar: TopBar {
+                title: "Event:",
+                help_text: "[time] [issue] <comment>",
+                info: day_info_message(active_day),
+                settings: settings.clone(),
+            },
+            input_state: text_input::State::focused(),
+            input: String::new(),
+            builder: IssueStartBuilder::default(),
+            settings,
+            orig: None,
+            last_end,
+            recent_issues,
+            recent_filter: String::new(),
+        })
+    }
+
+    fn on_submit(&mut self, stay_active: StayActive) -> Option<Message> {
+        let value = self.builder.try_build_event();
+
+        Self::on_submit_message(value, &mut self.orig, stay_active)
+    }
+}
+
+impl SingleEditUi<WorkEvent> for WorkEventEdit {
+    fn update_input(&mut self, input: String) {
+        self.input = input;
+        let x = self.settings.load();
+        self.builder.parse_input(
+            &**x,
+            self.last_end,
+            &**self.recent_issues.borrow(),
+            &self.input,
+        );
+    }
+
+    fn as_text(&self, e: &WorkEvent) -> String {
+        format!("{} {} {}", e.ts, e.task.ident, e.description)
+    }
+
+    fn set_orig(&mut self, orig: WorkEvent) {
+        self.orig = Some(orig);
+    }
+
+    fn try_build(&self) -> Option<WorkEvent> {
+        self.builder.try_build_event()
+    }
+}
+
+impl MainView for WorkEventEdit {
+    fn draft(&self) -> Option<&str> {
+        Some(self.input.as_str()).filter(|_| self.orig.is_none())
+    }
+
+    fn view(&mut self) -> QElement {
+        let input = TextInput::new(&mut self.input_state, "", &self.input, |i| {
+            Message::Ev(WorkEventMessage::TextChanged(i))
+        });
+        let input = WheelSteps::new(input, Message::ScrollTime);
+        let settings = self.settings.load();
+        let now = settings.timeline.time_now();
+
+        let mut content = vec![self.top_bar.view(), v_space(style::SPACE), input.into()];
+        if let Some(preview) = shortcut_preview(&settings, &self.input) {
+            content.push(text(preview));
+        }
+        content.push(v_space(style::SPACE));
+        content.push(self.builder.info_row(now));
+
+        Column::with_children(content).into()
+    }
+
+    fn update(&mut self, msg: Message) -> Option<Message> {
+        match msg {
+            Message::ScrollTime(steps) => {
+                step_input_time(&self.settings.load(), &self.input, steps)
+                    .map(|input| Message::Ev(WorkEventMessage::TextChanged(input)))
+            }
+            Message::Ev(WorkEventMessage::TextChanged(input)) => {
+                self.update_input(input);
+                self.builder.follow_up(&mut self.recent_filter)
+            }
+            Message::ClipboardValue(value) => {
+                self.builder.apply_clipboard(value);
+                None
+            }
+            // relative times follow the clock
+            Message::Tick => {
+                self.update_input(self.input.clone());
+                None
+            }
+            Message::CycleShortcut => cycle_shortcut(&self.settings.load(), &self.input, |input| {
+                Message::Ev(WorkEventMessage::TextChanged(input))
+            }),
+            Message::SubmitCurrent(stay_active) => self.on_submit(stay_active),
+            Message::StoreSuccess(stay_active) => stay_active.on_main_view_store(),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::data::test_support::time;
+    use crate::data::{ActiveDay, JiraIssue, Location, RecentIssuesRef, WorkEvent};
+    use crate::ui::single_edit_ui::SingleEditUi;
+    use crate::ui::work_event_edit::WorkEventEdit;
+    use crate::util::{StaticTimeline, Timeline};
+    use crate::Settings;
+
+    #[test]
+    fn parse_event() {
+        let timeline: Timeline = StaticTimeline::parse("2022-01-20 10:15").into();
+        let settings = Settings {
+            timeline: timeline.clone(),
+            ..Settings::default()
+        }
+        .into_settings_ref();
+        let mut ui = WorkEventEdit::for_active_day(
+            settings.clone(),
+            RecentIssuesRef::empty(settings),
+            Some(&ActiveDay::new(timeline.today(), Location::Office, None)),
+        );
+
+        assert_eq!(
+            ui.convert_input("11 A-1 deployed"),
+            Some(WorkEvent {
+                ts: time("11"),
+                task: JiraIssue::create("A-1").unwrap(),
+                description: "deployed".to_string()
+            })
+        );
+        assert_eq!(ui.convert_input("11 A-1"), None);
+    }
+}
@@ -0,0 +1,172 @@
+use std::sync::Arc;
+
+use chrono::Datelike;
+use iced_core::Length;
+use iced_native::widget::{button, Button, Column, Row};
+
+use crate::conf::SettingsRef;
+use crate::data::{format_minutes, ActiveDay, Day, YearOverview};
+use crate::ui::button_focus::ButtonFocus;
+use crate::ui::util::{h_space, v_space};
+use crate::ui::widget::Text;
+use crate::ui::{style, text, MainView, Message, QElement, ViewId};
+
+const CELL_SIZE: u16 = 14;
+const WEEKDAYS: [&str; 7] = ["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"];
+
+#[derive(Clone, Debug)]
+pub enum YearOverviewMessage {
+    Loaded(Arc<YearOverview>),
+}
+
+/// Heatmap of the booked hours of every day of a year.
+///
+/// Clicking a day opens it in the day view
+pub struct YearOverviewView {
+    settings: SettingsRef,
+    year: i32,
+    overview: Option<Arc<YearOverview>>,
+    /// One state per day of the year
+    buttons: Vec<button::State>,
+    previous_button: button::State,
+    next_button: button::State,
+    focus: ButtonFocus,
+}
+
+impl YearOverviewView {
+    pub fn for_active_day(settings: SettingsRef, active_day: Option<&ActiveDay>) -> Box<Self> {
+        let day = active_day
+            .map(ActiveDay::get_day)
+            .unwrap_or_else(|| settings.load().today());
+        let date: chrono::NaiveDate = day.into();
+        Box::new(Self {
+            settings,
+            year: date.year(),
+            overview: None,
+            buttons: Vec::new(),
+            previous_button: button::State::new(),
+            next_button: button::State::new(),
+            focus: ButtonFocus::default(),
+        })
+    }
+
+    pub fn load_message(&self) -> Option<Message> {
+        Some(Message::LoadYearOverview(self.year))
+    }
+}
+
+fn day_cell<'a>(state: &'a mut button::State, day: Day, minutes: u32, target: u32) -> QElement<'a> {
+    let open = Message::ChangeView(ViewId::CurrentDayUi);
+    Button::new(state, v_space(Length::Units(0)))
+        .width(Length::Units(CELL_SIZE))
+        .height(Length::Units(CELL_SIZE))
+        .padding(0)
+        .style(style::CalendarBlock {
+            color: style::HEATMAP_COLORS[YearOverview::level(minutes, target)],
+        })
+        .on_press(Message::OpenOnDay(day, Box::new(open)))
+        .into()
+}
+
+fn empty_cell<'a>() -> QElement<'a> {
+    v_space(Length::Units(CELL_SIZE))
+}
+
+impl MainView for YearOverviewView {
+    fn view(&mut self) -> QElement {
+        self.focus.clear_targets();
+        let overview = match &self.overview {
+            Some(overview) => overview,
+            None => return text(format!("Loading {}...", self.year)),
+        };
+
+        let target = self.settings.load().daily_target_minutes;
+        let booked_days = overview.work_minutes.iter().filter(|m| **m > 0).count();
+        let total: u32 = overview.work_minutes.iter().sum();
+        let top_row = Row::with_children(vec![
+            self.focus
+                .inline_button(
+                    &mut self.previous_button,
+                    "<",
+                    Message::LoadYearOverview(self.year - 1),
+                )
+                .into(),
+            h_space(style::SPACE),
+            text(format!("{}", self.year)),
+            h_space(style::SPACE),
+            self.focus
+                .inline_button(
+                    &mut self.next_button,
+                    ">",
+                    Message::LoadYearOverview(self.year + 1),
+                )
+                .into(),
+            h_space(style::DSPACE),
+            text(format!(
+                "{} booked on {} days",
+                format_minutes(total),
+                booked_days
+            )),
+        ]);
+
+        let labels = WEEKDAYS
+            .iter()
+            .map(|d| {
+                Text::new(*d)
+                    .size(11)
+                    .height(Length::Units(CELL_SIZE + 2))
+                    .into()
+            })
+            .collect();
+        let mut columns = vec![Column::with_children(labels)
+            .width(Length::Units(24))
+            .into()];
+        let mut buttons = self.buttons.iter_mut();
+        for week in overview.weeks() {
+            let mut cells = Vec::new();
+            for day in week {
+                // days of other years have no button
+                let cell = day.and_then(|day| buttons.next().map(|state| (day, state)));
+                cells.push(match cell {
+                    Some(((day, minutes), state)) => day_cell(state, day, minutes, target),
+                    None => empty_cell(),
+                });
+                cells.push(v_space(Length::Units(2)));
+            }
+            columns.push(Column::with_children(cells).into());
+            columns.push(h_space(Length::Units(2)));
+        }
+
+        Column::with_children(vec![
+            top_row.into(),
+            v_space(style::SPACE),
+            Row::with_children(columns).into(),
+        ])
+        .into()
+    }
+
+    fn update(&mut self, msg: Message) -> Option<Message> {
+        match msg {
+            Message::Year(YearOverviewMessage::Loaded(overview)) => {
+                self.year = overview.year;
+                self.buttons = overview
+                    .work_minutes
+                    .iter()
+                    .map(|_| button::State::new())
+                    .collect();
+                self.overview = Some(overview);
+                None
+            }
+            Message::Next => {
+                self.focus.cycle_next();
+                None
+            }
+            Message::Previous => {
+                self.focus.cycle_previous();
+                None
+            }
+            Message::SubmitCurrent(_) => self.focus.press(),
+            _ => None,
+        }
+    }
+}
@@ -1,6 +1,6 @@
 #![allow(dead_code)]
 
-use crate::data::Day;
+use crate::data::{Day, UtcOffset};
 use crate::parsing::time::Time;
 use arc_swap::ArcSwap;
 use std::fmt::Debug;
@@ -25,6 +25,10 @@ impl TimelineProvider for DefaultTimeline {
     fn now(&self) -> chrono::NaiveDateTime {
         chrono::DateTime::<chrono::Local>::from(SystemTime::now()).naive_local()
     }
+
+    fn utc_offset(&self) -> UtcOffset {
+        UtcOffset::local()
+    }
 }
 
 #[derive(Debug)]
@@ -64,6 +68,19 @@ pub trait TimelineProvider: Debug + Send + Sync {
     fn today(&self) -> Day {
         self.now().date().into()
     }
+
+    /// Offset of [TimelineProvider::now] to UTC
+    fn utc_offset(&self) -> UtcOffset {
+        UtcOffset::UTC
+    }
+
+    /// The current day in the given time zone
+    fn today_in(&self, zone: UtcOffset) -> Day {
+        let offset = zone.minutes() - self.utc_offset().minutes();
+        (self.now() + chrono::Duration::minutes(offset as i64))
+            .date()
+            .into()
+    }
 }
 
 pub type Timeline = Arc<dyn TimelineProvider>;
@@ -0,0 +1,50 @@
+use std::time::Duration;
+
+use reqwest::blocking::Client;
+
+use crate::conf::WebhookConfig;
+
+/// Wait before the first retry, doubled for every further retry
+const FIRST_BACKOFF: Duration = Duration::from_secs(2);
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Posts the document to the webhook and retries failed attempts.
+///
+/// Blocks until the post succeeded or all retries failed, returns the number of attempts
+pub fn post_json(config: &WebhookConfig, body: &serde_json::Value) -> (u32, Result<(), String>) {
+    let client = match Client::builder().timeout(TIMEOUT).build() {
+        Ok(client) => client,
+        Err(e) => return (0, Err(format!("Cannot create the HTTP client: {}", e))),
+    };
+    let mut backoff = FIRST_BACKOFF;
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        match post_once(&client, config, body) {
+            Ok(()) => return (attempts, Ok(())),
+            Err(e) if attempts > config.retries => return (attempts, Err(e)),
+            Err(e) => {
+                log::warn!("Webhook attempt {} failed, retrying: {}", attempts, e);
+                std::thread::sleep(backoff);
+                backoff *= 2;
+            }
+        }
+    }
+}
+
+fn post_once(
+    client: &Client,
+    config: &WebhookConfig,
+    body: &serde_json::Value,
+) -> Result<(), String> {
+    let mut request = client.post(&config.url).json(body);
+    if let Some(token) = &config.token {
+        request = request.bearer_auth(token);
+    }
+    let response = request.send().map_err(|e| e.to_string())?;
+    if response.status().is_success() {
+        Ok(())
+    } else {
+        Err(format!("HTTP {}", response.status()))
+    }
+}